@@ -19,6 +19,7 @@ use sui_types::gas_coin::TOTAL_SUPPLY_MIST;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, VerifiedCheckpoint,
 };
+use sui_types::randomness_state::get_randomness_state_obj_initial_shared_version;
 use sui_types::sui_system_state::{
     get_sui_system_state, get_sui_system_state_wrapper, SuiSystemState, SuiSystemStateTrait,
     SuiSystemStateWrapper, SuiValidatorGenesis,
@@ -159,6 +160,11 @@ impl Genesis {
             .expect("Read from genesis cannot fail")
     }
 
+    pub fn randomness_state_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
+        get_randomness_state_obj_initial_shared_version(&self.objects())
+            .expect("Read from genesis cannot fail")
+    }
+
     pub fn clock(&self) -> Clock {
         let clock = self
             .objects()
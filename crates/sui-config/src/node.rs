@@ -12,6 +12,7 @@ use once_cell::sync::OnceCell;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
@@ -65,6 +66,10 @@ pub struct NodeConfig {
     pub metrics_address: SocketAddr,
     #[serde(default = "default_admin_interface_port")]
     pub admin_interface_port: u16,
+    /// Bearer token that requests to the admin interface routes exposing consensus internals must
+    /// present. Those routes are disabled when no token is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_interface_token: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consensus_config: Option<ConsensusConfig>,
@@ -144,6 +149,16 @@ pub struct NodeConfig {
     #[serde(default)]
     pub state_snapshot_write_config: StateSnapshotConfig,
 
+    /// If set, historical object versions pruned from the perpetual tables are served from
+    /// formal snapshot files instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cold_object_store_config: Option<ColdObjectStoreConfig>,
+
+    /// If set, fullnodes reuse the results of dry runs and dev-inspects of identical transactions
+    /// within an epoch, instead of executing them again.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run_cache_config: Option<DryRunCacheConfig>,
+
     #[serde(default)]
     pub indexer_max_subscriptions: Option<usize>,
 
@@ -155,6 +170,11 @@ pub struct NodeConfig {
 
     #[serde(default = "default_jwk_fetch_interval_seconds")]
     pub jwk_fetch_interval_seconds: u64,
+
+    /// If set, validators admit gRPC requests against a separate budget for each class of client,
+    /// as configured in the file this points to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_qos_config: Option<ValidatorQosConfig>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -269,6 +289,10 @@ impl NodeConfig {
         self.db_path.join("snapshot")
     }
 
+    pub fn cold_object_store_path(&self) -> PathBuf {
+        self.db_path.join("cold_object_store")
+    }
+
     pub fn network_address(&self) -> &Multiaddr {
         &self.network_address
     }
@@ -328,6 +352,16 @@ pub struct ConsensusConfig {
     /// on consensus latency estimates.
     pub submit_delay_step_override_millis: Option<u64>,
 
+    /// When set, this validator never flags itself as low scoring when deciding which validators
+    /// should submit transactions to consensus.
+    #[serde(default)]
+    pub exclude_self_from_low_scoring: bool,
+
+    /// Authorities that this validator never flags as low scoring when deciding which validators
+    /// should submit transactions to consensus, e.g. its own co-located backup.
+    #[serde(default)]
+    pub low_scoring_exclusions: Vec<AuthorityPublicKeyBytes>,
+
     pub narwhal_config: ConsensusParameters,
 }
 
@@ -349,6 +383,18 @@ impl ConsensusConfig {
             .map(Duration::from_millis)
     }
 
+    /// The set of authorities that the validator `name` should never flag as low scoring.
+    pub fn low_scoring_exclusions(
+        &self,
+        name: AuthorityPublicKeyBytes,
+    ) -> HashSet<AuthorityPublicKeyBytes> {
+        let mut exclusions: HashSet<_> = self.low_scoring_exclusions.iter().copied().collect();
+        if self.exclude_self_from_low_scoring {
+            exclusions.insert(name);
+        }
+        exclusions
+    }
+
     pub fn narwhal_config(&self) -> &ConsensusParameters {
         &self.narwhal_config
     }
@@ -633,6 +679,83 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// Also write every snapshot partitioned by object type, for analytics jobs that only need
+    /// some types and would rather not restore the whole snapshot.
+    #[serde(default)]
+    pub partition_by_type: bool,
+    /// Secondary stores, e.g. buckets in other regions, that every completed snapshot is copied to
+    /// and verified against, so that restores do not depend on a single bucket being available.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replica_object_store_configs: Vec<ObjectStoreConfig>,
+}
+
+/// Configuration for the cold tier serving object versions older than
+/// `AuthorityStorePruningConfig::num_epochs_to_retain` epochs, which the pruner has removed from
+/// the perpetual tables.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ColdObjectStoreConfig {
+    /// Store holding the formal snapshots uploaded by `state-snapshot-write-config`.
+    pub object_store_config: ObjectStoreConfig,
+    /// Number of objects to keep in the in-memory LRU cache.
+    #[serde(default = "default_cold_object_store_cache_capacity")]
+    pub cache_capacity: usize,
+    /// Number of snapshots whose file listing is kept in memory, along with the first object ID of
+    /// each of their reference files read so far. Each holds a few entries per file, not per object.
+    #[serde(default = "default_cold_object_store_max_indexed_snapshots")]
+    pub max_indexed_snapshots: usize,
+}
+
+fn default_cold_object_store_cache_capacity() -> usize {
+    100_000
+}
+
+fn default_cold_object_store_max_indexed_snapshots() -> usize {
+    32
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DryRunCacheConfig {
+    /// Number of results to keep, for dry runs and dev-inspects each.
+    #[serde(default = "default_dry_run_cache_capacity")]
+    pub capacity: usize,
+    /// How long a result is reused for. Results also depend on the current versions of the shared
+    /// objects a transaction reads, which are not part of its digest, so this bounds how stale
+    /// they can get.
+    #[serde(default = "default_dry_run_cache_ttl_ms")]
+    pub ttl_ms: u64,
+}
+
+fn default_dry_run_cache_capacity() -> usize {
+    10_000
+}
+
+fn default_dry_run_cache_ttl_ms() -> u64 {
+    2_000
+}
+
+impl Default for DryRunCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_dry_run_cache_capacity(),
+            ttl_ms: default_dry_run_cache_ttl_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValidatorQosConfig {
+    /// YAML file holding a `QosClassesConfig`.
+    pub classes_path: PathBuf,
+    /// How often the file is read again, to pick up changes to it.
+    #[serde(default = "default_validator_qos_reload_interval_secs")]
+    pub reload_interval_secs: u64,
+}
+
+fn default_validator_qos_reload_interval_secs() -> u64 {
+    30
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -0,0 +1,68 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Config;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::num::NonZeroU32;
+
+/// How a validator shares its gRPC front-end between the clients that call it. Every client is
+/// assigned a class by its IP address, and every class is admitted against its own budget, so that
+/// a flood of requests from public clients does not crowd out the validator operator's own
+/// fullnodes, or its partners.
+///
+/// Lives in its own file, which validators read again periodically, so that clients can be added
+/// or removed, and budgets changed, without restarting the validator.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct QosClassesConfig {
+    /// Fullnodes run by the validator operator.
+    #[serde(default)]
+    pub own_fullnodes: QosClassConfig,
+    /// Clients the validator operator has an agreement with, e.g. RPC providers.
+    #[serde(default)]
+    pub partners: QosClassConfig,
+    /// Every other client. `clients` is ignored for this class.
+    #[serde(default)]
+    pub public: QosClassConfig,
+}
+
+impl Config for QosClassesConfig {}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct QosClassConfig {
+    /// IP addresses of the clients in this class.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clients: Vec<IpAddr>,
+    /// Most requests from clients in this class that are handled at once.
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Most requests from clients in this class that wait for one of the requests being handled
+    /// to finish. Requests beyond this are rejected straight away.
+    #[serde(default = "default_max_queued_requests")]
+    pub max_queued_requests: usize,
+    /// Requests admitted per second across all clients in this class, with bursts of up to as many
+    /// requests. Unlimited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<NonZeroU32>,
+}
+
+fn default_max_concurrent_requests() -> usize {
+    10_000
+}
+
+fn default_max_queued_requests() -> usize {
+    10_000
+}
+
+impl Default for QosClassConfig {
+    fn default() -> Self {
+        Self {
+            clients: vec![],
+            max_concurrent_requests: default_max_concurrent_requests(),
+            max_queued_requests: default_max_queued_requests(),
+            requests_per_second: None,
+        }
+    }
+}
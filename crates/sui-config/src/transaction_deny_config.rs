@@ -70,6 +70,16 @@ pub struct TransactionDenyConfig {
     /// A list of disabled OAuth providers for zkLogin
     #[serde(default)]
     zklogin_disabled_providers: HashSet<String>,
+
+    /// The furthest number of epochs past the current one that a transaction may be set to
+    /// expire at. Transactions that expire later are rejected. If unset, expiration is unbounded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_expiration_epochs: Option<u64>,
+
+    /// Number of epochs past `max_expiration_epochs` that are still accepted, to tolerate clients
+    /// that have already observed an epoch change that this node has not finished yet.
+    #[serde(default)]
+    expiration_epoch_skew: u64,
     // TODO: We could consider add a deny list for types that we want to disable public transfer.
     // TODO: We could also consider disable more types of commands, such as transfer, split and etc.
 }
@@ -117,6 +127,15 @@ impl TransactionDenyConfig {
     pub fn zklogin_disabled_providers(&self) -> &HashSet<String> {
         &self.zklogin_disabled_providers
     }
+
+    /// The latest epoch that a transaction signed in `epoch` may be set to expire at, if bounded.
+    pub fn max_expiration_epoch(&self, epoch: u64) -> Option<u64> {
+        self.max_expiration_epochs.map(|max| {
+            epoch
+                .saturating_add(max)
+                .saturating_add(self.expiration_epoch_skew)
+        })
+    }
 }
 
 #[derive(Default)]
@@ -182,4 +201,14 @@ impl TransactionDenyConfigBuilder {
         self.config.zklogin_disabled_providers.insert(provider);
         self
     }
+
+    pub fn max_expiration_epochs(mut self, epochs: u64) -> Self {
+        self.config.max_expiration_epochs = Some(epochs);
+        self
+    }
+
+    pub fn expiration_epoch_skew(mut self, epochs: u64) -> Self {
+        self.config.expiration_epoch_skew = epochs;
+        self
+    }
 }
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::authority::authority_store_types::{StoreObject, StoreObjectWrapper};
+use crate::authority::pinned_versions::PinnedVersions;
 use crate::verify_indexes::verify_indexes;
 use anyhow::anyhow;
 use arc_swap::{ArcSwap, Guard};
@@ -43,6 +44,7 @@ use sui_config::node::StateDebugDumpConfig;
 use sui_config::NodeConfig;
 use sui_types::execution::DynamicallyLoadedObjectMetadata;
 use tap::{TapFallible, TapOptional};
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::oneshot;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
@@ -60,7 +62,7 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_config::certificate_deny_config::CertificateDenyConfig;
 use sui_config::genesis::Genesis;
 use sui_config::node::{
-    AuthorityStorePruningConfig, DBCheckpointConfig, ExpensiveSafetyCheckConfig,
+    AuthorityStorePruningConfig, DBCheckpointConfig, DryRunCacheConfig, ExpensiveSafetyCheckConfig,
 };
 use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_framework::{BuiltInFramework, SystemPackage};
@@ -106,7 +108,7 @@ use sui_types::messages_grpc::{
 };
 use sui_types::metrics::{BytecodeVerifierMetrics, LimitsMetrics};
 use sui_types::object::{MoveObject, Owner, PastObjectRead, OBJECT_START_VERSION};
-use sui_types::storage::{ObjectKey, ObjectStore, WriteKind};
+use sui_types::storage::{BackingStore, ObjectKey, ObjectStore, WriteKind};
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::SuiSystemStateTrait;
 use sui_types::sui_system_state::{get_sui_system_state, SuiSystemState};
@@ -132,6 +134,8 @@ use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::CheckpointStore;
 use crate::consensus_adapter::ConsensusAdapter;
+use crate::dry_run_cache::{DryRunCache, DryRunResult};
+use crate::effects_notifier::{EffectsNotifier, ExecutedTransactionBatch};
 use crate::epoch::committee_store::CommitteeStore;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
@@ -166,6 +170,7 @@ pub mod authority_test_utils;
 pub mod authority_per_epoch_store;
 pub mod authority_per_epoch_store_pruner;
 
+pub mod authority_store_cold_tier;
 pub mod authority_store_pruner;
 pub mod authority_store_tables;
 pub mod authority_store_types;
@@ -174,6 +179,7 @@ pub mod test_authority_builder;
 
 pub(crate) mod authority_notify_read;
 pub(crate) mod authority_store;
+pub(crate) mod pinned_versions;
 
 pub static CHAIN_IDENTIFIER: OnceCell<ChainIdentifier> = OnceCell::new();
 
@@ -238,6 +244,9 @@ pub struct AuthorityMetrics {
     pub consensus_committed_subdags: IntCounterVec,
     pub consensus_committed_certificates: IntCounterVec,
 
+    /// Dry runs and dev-inspects served by the dry run cache, or executed because they missed it
+    pub dry_run_cache_requests: IntCounterVec,
+
     pub limits_metrics: Arc<LimitsMetrics>,
 
     /// bytecode verifier metrics for tracking timeouts
@@ -554,6 +563,13 @@ impl AuthorityMetrics {
                 registry,
             )
                 .unwrap(),
+            dry_run_cache_requests: register_int_counter_vec_with_registry!(
+                "dry_run_cache_requests",
+                "Number of dry runs and dev-inspects that hit or missed the dry run cache",
+                &["kind", "result"],
+                registry,
+            )
+            .unwrap(),
             limits_metrics: Arc::new(LimitsMetrics::new(registry)),
             bytecode_verifier_metrics: Arc::new(BytecodeVerifierMetrics::new(registry)),
             authenticator_state_update_failed: register_int_counter_with_registry!(
@@ -589,6 +605,10 @@ pub struct AuthorityState {
     pub indexes: Option<Arc<IndexStore>>,
 
     pub subscription_handler: Arc<SubscriptionHandler>,
+
+    /// Publishes committed transactions to in-process subscribers.
+    effects_notifier: EffectsNotifier,
+
     pub(crate) checkpoint_store: Arc<CheckpointStore>,
 
     committee_store: Arc<CommitteeStore>,
@@ -616,6 +636,9 @@ pub struct AuthorityState {
 
     /// Config for state dumping on forks
     debug_dump_config: StateDebugDumpConfig,
+
+    /// Results of recent dry runs and dev-inspects, if enabled.
+    dry_run_cache: Option<DryRunCache>,
 }
 
 /// The authority state encapsulates all state, drives execution, and ensures safety.
@@ -735,12 +758,27 @@ impl AuthorityState {
             return Err(SuiError::ValidatorHaltedAtEpochEnd);
         }
 
-        // Checks to see if the transaction has expired
-        if match &transaction.inner().data().transaction_data().expiration() {
-            TransactionExpiration::None => false,
-            TransactionExpiration::Epoch(epoch) => *epoch < epoch_store.epoch(),
-        } {
-            return Err(SuiError::TransactionExpired);
+        // Checks to see if the transaction has expired, or expires further out than this node
+        // is configured to accept.
+        if let TransactionExpiration::Epoch(expiration) =
+            transaction.inner().data().transaction_data().expiration()
+        {
+            if *expiration < epoch_store.epoch() {
+                return Err(SuiError::TransactionExpired);
+            }
+
+            if let Some(max_expiration) = self
+                .transaction_deny_config
+                .max_expiration_epoch(epoch_store.epoch())
+            {
+                fp_ensure!(
+                    *expiration <= max_expiration,
+                    SuiError::TransactionExpirationTooFar {
+                        expiration: *expiration,
+                        max_expiration,
+                    }
+                );
+            }
         }
 
         let signed = self.handle_transaction_impl(transaction, epoch_store).await;
@@ -1142,12 +1180,26 @@ impl AuthorityState {
             })
             .collect();
 
+        // Events are consumed by commit_certificate, so hold on to a copy for subscribers.
+        let events = self
+            .effects_notifier
+            .has_subscribers()
+            .then(|| inner_temporary_store.events.clone());
+
         self.commit_certificate(inner_temporary_store, certificate, effects, epoch_store)
             .await?;
 
         // commit_certificate finished, the tx is fully committed to the store.
         tx_guard.commit_tx();
 
+        // Queued before the transaction manager learns of the commit, so that subscribers see
+        // this transaction ahead of any that use its outputs.
+        if let Some(events) = events {
+            self.effects_notifier
+                .notify(digest, effects.clone(), events)
+                .await;
+        }
+
         // Notifies transaction manager about transaction and output objects committed.
         // This provides necessary information to transaction manager to start executing
         // additional ready transactions.
@@ -1224,9 +1276,30 @@ impl AuthorityState {
         let protocol_config = epoch_store.protocol_config();
         let transaction_data = &certificate.data().intent_message().value;
         let (kind, signer, gas) = transaction_data.execution_parts();
+        // In debug builds, check that the inputs were loaded, and that execution reads objects,
+        // at the versions the transaction is pinned to.
+        let pinned_versions = if cfg!(debug_assertions) {
+            let pinned_versions = PinnedVersions::new(
+                tx_digest,
+                &transaction_data.input_objects()?,
+                epoch_store.get_shared_locks(&tx_digest)?,
+            );
+            pinned_versions.check_inputs(&input_objects);
+            Some(pinned_versions)
+        } else {
+            None
+        };
+        let checked_store;
+        let store: &dyn BackingStore = match &pinned_versions {
+            Some(pinned_versions) => {
+                checked_store = pinned_versions.check_reads(&self.database);
+                &checked_store
+            }
+            None => &self.database,
+        };
         let (inner_temp_store, effects, execution_error_opt) =
             epoch_store.executor().execute_transaction_to_effects(
-                &self.database,
+                store,
                 protocol_config,
                 self.metrics.limits_metrics.clone(),
                 // TODO: would be nice to pass the whole NodeConfig here, but it creates a
@@ -1254,12 +1327,7 @@ impl AuthorityState {
         &self,
         transaction: TransactionData,
         transaction_digest: TransactionDigest,
-    ) -> SuiResult<(
-        DryRunTransactionBlockResponse,
-        BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
-        TransactionEffects,
-        Option<ObjectID>,
-    )> {
+    ) -> SuiResult<DryRunResult> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         if !self.is_fullnode(&epoch_store) {
             return Err(SuiError::UnsupportedFeatureError {
@@ -1273,6 +1341,15 @@ impl AuthorityState {
             });
         }
 
+        let epoch = epoch_store.epoch();
+        if let Some(cache) = &self.dry_run_cache {
+            let cached = cache.dry_runs.get(&transaction_digest, epoch);
+            self.record_dry_run_cache_request("dry_run", cached.is_some());
+            if let Some(result) = cached {
+                return Ok(result);
+            }
+        }
+
         // make a gas object if one was not provided
         let mut gas_object_refs = transaction.gas().to_vec();
         let ((gas_status, input_objects), mock_gas) = if transaction.gas().is_empty() {
@@ -1381,7 +1458,7 @@ impl AuthorityState {
             })
             .collect();
 
-        Ok((
+        let result = (
             DryRunTransactionBlockResponse {
                 input: SuiTransactionBlockData::try_from(transaction, &module_cache).map_err(
                     |e| SuiError::TransactionSerializationError {
@@ -1400,11 +1477,19 @@ impl AuthorityState {
                 )?,
                 object_changes,
                 balance_changes,
+                command_gas_used: inner_temp_store.command_gas_used.clone(),
             },
             written_with_kind,
             effects,
             mock_gas,
-        ))
+        );
+
+        if let Some(cache) = &self.dry_run_cache {
+            cache
+                .dry_runs
+                .insert(transaction_digest, epoch, result.clone());
+        }
+        Ok(result)
     }
 
     /// The object ID for gas can be any object ID, even for an uncreated object
@@ -1439,6 +1524,26 @@ impl AuthorityState {
         let gas_status =
             SuiGasStatus::new(max_tx_gas, gas_price, reference_gas_price, protocol_config)?;
 
+        // The gas object is made up for each dev-inspect, so it is left out of the cache key.
+        let cache_key = self.dry_run_cache.as_ref().map(|_| {
+            let data = TransactionData::new(
+                transaction_kind.clone(),
+                sender,
+                (ObjectID::ZERO, SequenceNumber::new(), ObjectDigest::MIN),
+                gas_price,
+                max_tx_gas,
+            );
+            TransactionDigest::new(default_hash(&data))
+        });
+        let epoch = epoch_store.epoch();
+        if let (Some(cache), Some(key)) = (&self.dry_run_cache, &cache_key) {
+            let cached = cache.dev_inspects.get(key, epoch);
+            self.record_dry_run_cache_request("dev_inspect", cached.is_some());
+            if let Some(results) = cached {
+                return Ok(results);
+            }
+        }
+
         let gas_object_id = ObjectID::random();
         // give the gas object 2x the max gas to have coin balance to play with during execution
         let gas_object = Object::new_move(
@@ -1494,12 +1599,24 @@ impl AuthorityState {
         let module_cache =
             TemporaryModuleResolver::new(&inner_temp_store, epoch_store.module_cache().clone());
 
-        DevInspectResults::new(
+        let results = DevInspectResults::new(
             effects,
             inner_temp_store.events.clone(),
             execution_result,
             &module_cache,
-        )
+        )?;
+
+        if let (Some(cache), Some(key)) = (&self.dry_run_cache, cache_key) {
+            cache.dev_inspects.insert(key, epoch, results.clone());
+        }
+        Ok(results)
+    }
+
+    fn record_dry_run_cache_request(&self, kind: &str, hit: bool) {
+        self.metrics
+            .dry_run_cache_requests
+            .with_label_values(&[kind, if hit { "hit" } else { "miss" }])
+            .inc();
     }
 
     // Only used for testing because of how epoch store is loaded.
@@ -1971,6 +2088,7 @@ impl AuthorityState {
         indirect_objects_threshold: usize,
         debug_dump_config: StateDebugDumpConfig,
         archive_readers: ArchiveReaderBalancer,
+        dry_run_cache_config: Option<DryRunCacheConfig>,
     ) -> Arc<Self> {
         Self::check_protocol_version(supported_protocol_versions, epoch_store.protocol_version());
 
@@ -2003,6 +2121,7 @@ impl AuthorityState {
             database: store,
             indexes,
             subscription_handler: Arc::new(SubscriptionHandler::new(prometheus_registry)),
+            effects_notifier: EffectsNotifier::default(),
             checkpoint_store,
             committee_store,
             transaction_manager,
@@ -2015,6 +2134,7 @@ impl AuthorityState {
             transaction_deny_config,
             certificate_deny_config,
             debug_dump_config,
+            dry_run_cache: dry_run_cache_config.as_ref().and_then(DryRunCache::new),
         });
 
         // Start a task to execute ready certificates.
@@ -2056,6 +2176,12 @@ impl AuthorityState {
         &self.transaction_manager
     }
 
+    /// Subscribe to batches of transactions committed by this authority, along with their effects
+    /// and events, in the order they were committed.
+    pub fn subscribe_executed_transactions(&self) -> broadcast::Receiver<ExecutedTransactionBatch> {
+        self.effects_notifier.subscribe()
+    }
+
     /// Adds certificates to transaction manager for ordered execution.
     /// It is unnecessary to persist the certificates into the pending_execution table,
     /// because only Narwhal output needs to be persisted.
@@ -2191,6 +2317,9 @@ impl AuthorityState {
             .await?;
         assert_eq!(new_epoch_store.epoch(), new_epoch);
         self.transaction_manager.reconfigure(new_epoch);
+        if let Some(cache) = &self.dry_run_cache {
+            cache.clear();
+        }
         *execution_lock = new_epoch;
         // drop execution_lock after epoch store was updated
         // see also assert in AuthorityState::process_certificate
@@ -2637,7 +2766,10 @@ impl AuthorityState {
         object_id: &ObjectID,
         version: SequenceNumber,
     ) -> SuiResult<Option<(Object, Option<MoveStructLayout>)>> {
-        let Some(object) = self.database.get_object_by_key(object_id, version)? else {
+        let Some(object) = self
+            .database
+            .get_historical_object_by_key(object_id, version)?
+        else {
             return Ok(None);
         };
 
@@ -3840,6 +3972,24 @@ impl AuthorityState {
         Some(tx)
     }
 
+    fn create_randomness_state_tx(
+        &self,
+        epoch_store: &Arc<AuthorityPerEpochStore>,
+    ) -> Option<EndOfEpochTransactionKind> {
+        if !epoch_store.protocol_config().random_beacon() {
+            info!("randomness state transactions not enabled");
+            return None;
+        }
+
+        if epoch_store.randomness_state_exists() {
+            return None;
+        }
+
+        let tx = EndOfEpochTransactionKind::new_randomness_state_create();
+        info!("Creating RandomnessStateCreate tx");
+        Some(tx)
+    }
+
     /// Creates and execute the advance epoch transaction to effects without committing it to the database.
     /// The effects of the change epoch tx are only written to the database after a certified checkpoint has been
     /// formed and executed by CheckpointExecutor.
@@ -3862,6 +4012,9 @@ impl AuthorityState {
         if let Some(tx) = self.create_authenticator_state_tx(epoch_store) {
             txns.push(tx);
         }
+        if let Some(tx) = self.create_randomness_state_tx(epoch_store) {
+            txns.push(tx);
+        }
 
         let next_epoch = epoch_store.epoch() + 1;
 
@@ -46,7 +46,7 @@ use crate::consensus_handler::{
     SequencedConsensusTransaction, SequencedConsensusTransactionKey,
     SequencedConsensusTransactionKind, VerifiedSequencedConsensusTransaction,
 };
-use crate::epoch::epoch_metrics::EpochMetrics;
+use crate::epoch::epoch_metrics::{EpochMetrics, ReconfigStage, ReconfigTimestamps};
 use crate::epoch::reconfiguration::ReconfigState;
 use crate::module_cache_metrics::ResolverMetrics;
 use crate::signature_verifier::*;
@@ -570,6 +570,18 @@ impl AuthorityPerEpochStore {
             .is_some()
     }
 
+    // Returns true if the random beacon is enabled in the protocol config *and* the
+    // randomness state object already exists
+    pub fn randomness_state_enabled(&self) -> bool {
+        self.protocol_config().random_beacon() && self.randomness_state_exists()
+    }
+
+    pub fn randomness_state_exists(&self) -> bool {
+        self.epoch_start_configuration
+            .randomness_obj_initial_shared_version()
+            .is_some()
+    }
+
     pub fn get_parent_path(&self) -> PathBuf {
         self.parent_path.clone()
     }
@@ -600,7 +612,7 @@ impl AuthorityPerEpochStore {
         assert_eq!(self.epoch() + 1, new_committee.epoch);
         self.record_reconfig_halt_duration_metric();
         self.record_epoch_total_duration_metric();
-        Self::new(
+        let new_epoch_store = Self::new(
             name,
             Arc::new(new_committee),
             &self.parent_path,
@@ -612,7 +624,9 @@ impl AuthorityPerEpochStore {
             self.signature_verifier.metrics.clone(),
             expensive_safety_check_config,
             chain_identifier,
-        )
+        );
+        new_epoch_store.record_reconfig_stage(ReconfigStage::NewCommitteeFormed);
+        new_epoch_store
     }
 
     pub fn committee(&self) -> &Arc<Committee> {
@@ -2383,6 +2397,21 @@ impl AuthorityPerEpochStore {
             .set(self.epoch_open_time.elapsed().as_millis() as i64);
     }
 
+    /// Records that reconfiguration reached `stage`, the first time it does. The stages up to
+    /// `EpochEnded` are reached in the epoch that is ending, and the rest in the new one.
+    pub fn record_reconfig_stage(&self, stage: ReconfigStage) {
+        let epoch = match stage {
+            ReconfigStage::LastCheckpointCertified | ReconfigStage::EpochEnded => self.epoch() + 1,
+            ReconfigStage::NewCommitteeFormed | ReconfigStage::FirstCommit => self.epoch(),
+        };
+        self.metrics.record_reconfig_stage(epoch, stage);
+    }
+
+    /// When the most recent reconfigurations reached each of their stages, oldest first.
+    pub fn reconfig_timeline(&self) -> Vec<ReconfigTimestamps> {
+        self.metrics.reconfig_timeline.reconfigs()
+    }
+
     pub fn record_is_safe_mode_metric(&self, safe_mode: bool) {
         self.metrics.is_safe_mode.set(safe_mode as i64);
     }
@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::fmt::Debug;
 use std::hash::Hash;
 use std::ops::Not;
 use std::sync::Arc;
@@ -13,6 +15,9 @@ use futures::stream::FuturesUnordered;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::resolver::ModuleResolver;
 use once_cell::sync::OnceCell;
+use prometheus::HistogramVec;
+use rocksdb::perf::set_perf_stats;
+use rocksdb::{PerfContext, PerfMetric, PerfStatsLevel};
 use serde::{Deserialize, Serialize};
 use sui_protocol_config::ProtocolConfig;
 use sui_storage::mutex_table::{MutexGuard, MutexTable, RwLockGuard, RwLockTable};
@@ -30,13 +35,15 @@ use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::{base_types::SequenceNumber, fp_bail, fp_ensure, storage::ParentSync};
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tokio::time::Instant;
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 use typed_store::rocks::{DBBatch, DBMap, TypedStoreError};
 use typed_store::traits::Map;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::authority::authority_store_cold_tier::ColdObjectStore;
 use crate::authority::authority_store_types::{
-    get_store_object_pair, ObjectContentDigest, StoreObject, StoreObjectPair, StoreObjectWrapper,
+    get_store_object_pair, ObjectContentDigest, StoreData, StoreObject, StoreObjectPair,
+    StoreObjectValue, StoreObjectWrapper,
 };
 use crate::authority::epoch_start_configuration::{EpochFlag, EpochStartConfiguration};
 
@@ -50,6 +57,22 @@ use typed_store::rocks::util::is_ref_count_value;
 
 const NUM_SHARDS: usize = 4096;
 
+const OBJECTS_CF: &str = "objects";
+const INDIRECT_MOVE_OBJECTS_CF: &str = "indirect_move_objects";
+
+/// Object reads that take longer than this many milliseconds are logged.
+const ENV_VAR_SLOW_OBJECT_READ_THRESHOLD_MS: &str = "SLOW_OBJECT_READ_THRESHOLD_MS";
+const DEFAULT_SLOW_OBJECT_READ_THRESHOLD_MS: u64 = 100;
+
+const OBJECT_READ_LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5.,
+    10.,
+];
+
+thread_local! {
+    static OBJECT_READ_PERF_CONTEXT: RefCell<PerfContext> = RefCell::new(PerfContext::default());
+}
+
 struct AuthorityStoreMetrics {
     sui_conservation_check_latency: IntGauge,
     sui_conservation_live_object_count: IntGauge,
@@ -58,6 +81,12 @@ struct AuthorityStoreMetrics {
     sui_conservation_storage_fund: IntGauge,
     sui_conservation_storage_fund_imbalance: IntGauge,
     epoch_flags: IntGaugeVec,
+
+    object_read_latency: HistogramVec,
+    object_read_block_cache_hits: IntCounterVec,
+    object_read_block_reads: IntCounterVec,
+    slow_object_reads: IntCounterVec,
+    slow_object_read_threshold: Duration,
 }
 
 impl AuthorityStoreMetrics {
@@ -99,8 +128,75 @@ impl AuthorityStoreMetrics {
                 &["flag"],
                 registry,
             ).unwrap(),
+            object_read_latency: register_histogram_vec_with_registry!(
+                "authority_store_object_read_latency",
+                "Latency of reading an object from each of the object column families",
+                &["cf"],
+                OBJECT_READ_LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            ).unwrap(),
+            object_read_block_cache_hits: register_int_counter_vec_with_registry!(
+                "authority_store_object_read_block_cache_hits",
+                "Number of blocks object reads found in the block cache, per object column family",
+                &["cf"],
+                registry,
+            ).unwrap(),
+            object_read_block_reads: register_int_counter_vec_with_registry!(
+                "authority_store_object_read_block_reads",
+                "Number of blocks object reads had to read from disk, per object column family",
+                &["cf"],
+                registry,
+            ).unwrap(),
+            slow_object_reads: register_int_counter_vec_with_registry!(
+                "authority_store_slow_object_reads",
+                "Number of object reads slower than the slow read threshold, per object column family",
+                &["cf"],
+                registry,
+            ).unwrap(),
+            slow_object_read_threshold: Duration::from_millis(
+                std::env::var(ENV_VAR_SLOW_OBJECT_READ_THRESHOLD_MS)
+                    .ok()
+                    .and_then(|threshold| threshold.parse().ok())
+                    .unwrap_or(DEFAULT_SLOW_OBJECT_READ_THRESHOLD_MS),
+            ),
         }
     }
+
+    /// Runs `read` against the `cf` column family, recording how long it took and how many of the
+    /// blocks it needed were found in the block cache, and logging `key` if it was slow.
+    fn observe_object_read<T>(
+        &self,
+        cf: &'static str,
+        key: &dyn Debug,
+        read: impl FnOnce() -> T,
+    ) -> T {
+        // Sampled reads in typed_store share this thread's perf context, and may reset or disable
+        // it in the middle of a read, in which case that read's block counts are undercounted.
+        OBJECT_READ_PERF_CONTEXT.with(|perf_context| perf_context.borrow_mut().reset());
+        set_perf_stats(PerfStatsLevel::EnableCount);
+        let start = Instant::now();
+        let result = read();
+        let elapsed = start.elapsed();
+        set_perf_stats(PerfStatsLevel::Disable);
+
+        self.object_read_latency
+            .with_label_values(&[cf])
+            .observe(elapsed.as_secs_f64());
+        OBJECT_READ_PERF_CONTEXT.with(|perf_context| {
+            let perf_context = perf_context.borrow();
+            self.object_read_block_cache_hits
+                .with_label_values(&[cf])
+                .inc_by(perf_context.metric(PerfMetric::BlockCacheHitCount));
+            self.object_read_block_reads
+                .with_label_values(&[cf])
+                .inc_by(perf_context.metric(PerfMetric::BlockReadCount));
+        });
+        if elapsed >= self.slow_object_read_threshold {
+            self.slow_object_reads.with_label_values(&[cf]).inc();
+            warn!(cf, ?key, ?elapsed, "Slow object read");
+        }
+        result
+    }
 }
 
 /// ALL_OBJ_VER determines whether we want to store all past
@@ -135,6 +231,9 @@ pub struct AuthorityStore {
     /// Whether to enable expensive SUI conservation check at epoch boundaries.
     enable_epoch_sui_conservation_check: bool,
 
+    /// Optional tier serving object versions that were pruned from the perpetual tables.
+    cold_object_store: OnceCell<Arc<dyn ColdObjectStore>>,
+
     metrics: AuthorityStoreMetrics,
 }
 
@@ -159,6 +258,7 @@ impl AuthorityStore {
                 genesis.sui_system_object().into_epoch_start_state(),
                 *genesis.checkpoint().digest(),
                 genesis.authenticator_state_obj_initial_shared_version(),
+                genesis.randomness_state_obj_initial_shared_version(),
             );
             perpetual_tables
                 .set_epoch_start_configuration(&epoch_start_configuration)
@@ -246,6 +346,7 @@ impl AuthorityStore {
             objects_lock_table: Arc::new(RwLockTable::new(NUM_SHARDS)),
             indirect_objects_threshold,
             enable_epoch_sui_conservation_check,
+            cold_object_store: OnceCell::new(),
             metrics: AuthorityStoreMetrics::new(registry),
         });
         // Only initialize an empty database.
@@ -288,6 +389,43 @@ impl AuthorityStore {
         Ok(store)
     }
 
+    /// Installs a cold tier to fall back to for historical object versions that have been pruned.
+    /// Can only be set once.
+    pub fn set_cold_object_store(&self, cold_object_store: Arc<dyn ColdObjectStore>) -> SuiResult {
+        self.cold_object_store.set(cold_object_store).map_err(|_| {
+            SuiError::GenericStorageError("Cold object store can only be set once".to_string())
+        })
+    }
+
+    /// Like `get_object_by_key`, but falls back to the cold tier, if one is configured, for
+    /// versions that have been pruned from the perpetual tables. Meant for historical reads
+    /// served to clients; execution must keep using `get_object_by_key`.
+    pub fn get_historical_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        if let Some(object) = self
+            .perpetual_tables
+            .get_object_by_key(object_id, version)?
+        {
+            return Ok(Some(object));
+        }
+        let Some(cold_object_store) = self.cold_object_store.get() else {
+            return Ok(None);
+        };
+        // A version can only have been pruned if a newer version of the object exists.
+        match self
+            .perpetual_tables
+            .get_latest_object_ref_or_tombstone(*object_id)?
+        {
+            Some((_, latest_version, _)) if latest_version > version => {
+                cold_object_store.get_object_by_key(object_id, version)
+            }
+            _ => Ok(None),
+        }
+    }
+
     pub fn get_root_state_hash(&self, epoch: EpochId) -> SuiResult<ECMHLiveObjectSetDigest> {
         let acc = self
             .perpetual_tables
@@ -519,14 +657,17 @@ impl AuthorityStore {
         object_keys: &[ObjectKey],
     ) -> Result<Vec<Option<Object>>, SuiError> {
         let wrappers = self
-            .perpetual_tables
-            .objects
-            .multi_get(object_keys.to_vec())?;
+            .metrics
+            .observe_object_read(OBJECTS_CF, &object_keys, || {
+                self.perpetual_tables
+                    .objects
+                    .multi_get(object_keys.to_vec())
+            })?;
         let mut ret = vec![];
 
         for (idx, w) in wrappers.into_iter().enumerate() {
             ret.push(
-                w.map(|object| self.perpetual_tables.object(&object_keys[idx], object))
+                w.map(|object| self.construct_object(&object_keys[idx], object))
                     .transpose()?
                     .flatten(),
             );
@@ -534,6 +675,31 @@ impl AuthorityStore {
         Ok(ret)
     }
 
+    /// Constructs the object stored at `object_key` out of its value in the objects table, reading
+    /// its contents from the indirect objects table if they are stored there. Returns `None` if
+    /// the object was deleted or wrapped.
+    fn construct_object(
+        &self,
+        object_key: &ObjectKey,
+        store_object: StoreObjectWrapper,
+    ) -> Result<Option<Object>, SuiError> {
+        let is_indirect = matches!(
+            store_object.inner(),
+            StoreObject::Value(StoreObjectValue {
+                data: StoreData::IndirectObject(_),
+                ..
+            })
+        );
+        if is_indirect {
+            self.metrics
+                .observe_object_read(INDIRECT_MOVE_OBJECTS_CF, object_key, || {
+                    self.perpetual_tables.object(object_key, store_object)
+                })
+        } else {
+            self.perpetual_tables.object(object_key, store_object)
+        }
+    }
+
     /// Get many objects
     pub fn get_objects(&self, objects: &[ObjectID]) -> Result<Vec<Option<Object>>, SuiError> {
         let mut result = Vec::new();
@@ -768,17 +934,29 @@ impl AuthorityStore {
                         digest, id
                     )
                     });
-                    self.get_object_by_key(id, *version)?.unwrap_or_else(|| {
+                    let obj = self.get_object_by_key(id, *version)?.unwrap_or_else(|| {
                         panic!("All dependencies of tx {:?} should have been executed now, but Shared Object id: {}, version: {} is absent", digest, *id, *version);
-                    })
+                    });
+                    debug_assert_eq!(
+                        (obj.id(), obj.version()),
+                        (*id, *version),
+                        "Read of shared object for tx {digest:?} does not match its assigned version",
+                    );
+                    obj
                 }
                 InputObjectKind::MovePackage(id) => self.get_object(id)?.unwrap_or_else(|| {
                     panic!("All dependencies of tx {:?} should have been executed now, but Move Package id: {} is absent", digest, id);
                 }),
                 InputObjectKind::ImmOrOwnedMoveObject(objref) => {
-                    self.get_object_by_key(&objref.0, objref.1)?.unwrap_or_else(|| {
+                    let obj = self.get_object_by_key(&objref.0, objref.1)?.unwrap_or_else(|| {
                         panic!("All dependencies of tx {:?} should have been executed now, but Immutable or Owned Object id: {}, version: {} is absent", digest, objref.0, objref.1);
-                    })
+                    });
+                    debug_assert_eq!(
+                        obj.compute_object_reference(),
+                        *objref,
+                        "Read of owned object for tx {digest:?} does not match its reference in the tx",
+                    );
+                    obj
                 }
             };
             result.push(obj);
@@ -1088,6 +1266,7 @@ impl AuthorityStore {
             loaded_runtime_objects: _,
             no_extraneous_module_bytes: _,
             runtime_packages_loaded_from_db: _,
+            command_gas_used: _,
         } = inner_temporary_store;
         trace!(written =? written.iter().map(|(obj_id, obj)| (obj_id, obj.version())).collect::<Vec<_>>(),
                "batch_update_objects: temp store written");
@@ -2002,7 +2181,16 @@ impl BackingPackageStore for AuthorityStore {
 impl ObjectStore for AuthorityStore {
     /// Read an object and return it, or Ok(None) if the object was not found.
     fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
-        self.perpetual_tables.as_ref().get_object(object_id)
+        let Some((object_key, store_object)) =
+            self.metrics
+                .observe_object_read(OBJECTS_CF, object_id, || {
+                    self.perpetual_tables
+                        .get_latest_object_or_tombstone(*object_id)
+                })?
+        else {
+            return Ok(None);
+        };
+        self.construct_object(&object_key, store_object)
     }
 
     fn get_object_by_key(
@@ -2010,7 +2198,14 @@ impl ObjectStore for AuthorityStore {
         object_id: &ObjectID,
         version: VersionNumber,
     ) -> Result<Option<Object>, SuiError> {
-        self.perpetual_tables.get_object_by_key(object_id, version)
+        let object_key = ObjectKey(*object_id, version);
+        self.metrics
+            .observe_object_read(OBJECTS_CF, &object_key, || {
+                self.perpetual_tables.objects.get(&object_key)
+            })?
+            .map(|store_object| self.construct_object(&object_key, store_object))
+            .transpose()
+            .map(Option::flatten)
     }
 }
 
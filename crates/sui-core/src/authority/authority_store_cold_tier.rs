@@ -0,0 +1,18 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::base_types::{ObjectID, VersionNumber};
+use sui_types::error::SuiResult;
+use sui_types::object::Object;
+
+/// A read-only storage tier for historical object versions that have been pruned from the
+/// perpetual tables. The authority store only consults it for historical reads of a version older
+/// than the latest one it still holds; execution never reads from it.
+pub trait ColdObjectStore: Send + Sync {
+    /// Returns the object at exactly `version`, or Ok(None) if the cold tier does not have it.
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> SuiResult<Option<Object>>;
+}
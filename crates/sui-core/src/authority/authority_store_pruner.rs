@@ -175,7 +175,7 @@ impl AuthorityStorePruner {
         perpetual_db: &Arc<AuthorityPerpetualTables>,
         checkpoint_db: &Arc<CheckpointStore>,
         checkpoint_number: CheckpointSequenceNumber,
-        checkpoints_to_prune: Vec<CheckpointDigest>,
+        checkpoints_to_prune: Vec<(CheckpointSequenceNumber, CheckpointDigest)>,
         checkpoint_content_to_prune: Vec<CheckpointContents>,
         effects_to_prune: &Vec<TransactionEffects>,
         metrics: Arc<AuthorityStorePruningMetrics>,
@@ -226,8 +226,13 @@ impl AuthorityStorePruner {
             checkpoint_content_digests,
         )?;
 
-        checkpoints_batch
-            .delete_batch(&checkpoint_db.checkpoint_by_digest, checkpoints_to_prune)?;
+        let (checkpoint_sequence_numbers, checkpoint_digests): (Vec<_>, Vec<_>) =
+            checkpoints_to_prune.into_iter().unzip();
+        checkpoints_batch.delete_batch(&checkpoint_db.checkpoint_by_digest, checkpoint_digests)?;
+        checkpoints_batch.delete_batch(
+            &checkpoint_db.checkpoint_execution_records,
+            checkpoint_sequence_numbers,
+        )?;
 
         checkpoints_batch.insert_batch(
             &checkpoint_db.watermarks,
@@ -363,7 +368,7 @@ impl AuthorityStorePruner {
                 .effects
                 .multi_get(content.iter().map(|tx| tx.effects))?;
 
-            checkpoints_to_prune.push(*checkpoint.digest());
+            checkpoints_to_prune.push((checkpoint_number, *checkpoint.digest()));
             checkpoint_content_to_prune.push(content);
             effects_to_prune.extend(effects.into_iter().flatten());
 
@@ -584,11 +589,13 @@ mod tests {
         get_store_object_pair, ObjectContentDigest, StoreData, StoreObject, StoreObjectPair,
         StoreObjectWrapper,
     };
+    use crate::checkpoints::{CheckpointExecutionRecord, CheckpointStore};
     use prometheus::Registry;
     use sui_storage::mutex_table::RwLockTable;
     use sui_types::base_types::ObjectDigest;
     use sui_types::effects::TransactionEffects;
     use sui_types::effects::TransactionEffectsAPI;
+    use sui_types::messages_checkpoint::CheckpointDigest;
     use sui_types::{
         base_types::{ObjectID, SequenceNumber},
         object::Object,
@@ -754,6 +761,42 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_checkpoint_execution_records_pruning() {
+        let path = tempfile::tempdir().unwrap().into_path();
+        let perpetual_db = Arc::new(AuthorityPerpetualTables::open(
+            &path.join("perpetual"),
+            None,
+        ));
+        let checkpoint_store = CheckpointStore::new(&path.join("checkpoints"));
+        for seq in 0..5 {
+            checkpoint_store
+                .insert_checkpoint_execution_record(seq, &CheckpointExecutionRecord::default())
+                .unwrap();
+        }
+
+        let checkpoints_to_prune = (0..3)
+            .map(|seq| (seq, CheckpointDigest::random()))
+            .collect();
+        AuthorityStorePruner::prune_checkpoints(
+            &perpetual_db,
+            &checkpoint_store,
+            2,
+            checkpoints_to_prune,
+            vec![],
+            &vec![],
+            AuthorityStorePruningMetrics::new(&Registry::default()),
+        )
+        .unwrap();
+
+        let remaining: Vec<_> = checkpoint_store
+            .get_checkpoint_execution_records(0, 4)
+            .into_iter()
+            .map(|(seq, _)| seq)
+            .collect();
+        assert_eq!(remaining, vec![3, 4]);
+    }
+
     #[cfg(not(target_env = "msvc"))]
     #[tokio::test]
     async fn test_db_size_after_compaction() -> Result<(), anyhow::Error> {
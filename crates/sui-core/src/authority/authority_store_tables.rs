@@ -14,7 +14,8 @@ use sui_types::storage::MarkerValue;
 use typed_store::metrics::SamplingInterval;
 use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
 use typed_store::rocks::{
-    default_db_options, read_size_from_env, DBBatch, DBMap, DBOptions, MetricConf, ReadWriteOptions,
+    default_db_options, read_size_from_env, ColumnFamilyStats, DBBatch, DBMap, DBOptions,
+    MetricConf, ReadWriteOptions,
 };
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
@@ -437,6 +438,22 @@ impl AuthorityPerpetualTables {
             .map_err(SuiError::StorageError)
     }
 
+    /// Compacts the tables that a state snapshot restore writes the live object set to, so that
+    /// they are not left with the large number of L0 files bulk insertion produces.
+    pub fn compact_live_object_tables(&self) {
+        self.objects.compact_to_bottom();
+        self.indirect_move_objects.compact_to_bottom();
+        self.owned_object_transaction_locks.compact_to_bottom();
+    }
+
+    pub fn live_object_table_stats(&self) -> SuiResult<Vec<ColumnFamilyStats>> {
+        Ok(vec![
+            self.objects.column_family_stats()?,
+            self.indirect_move_objects.column_family_stats()?,
+            self.owned_object_transaction_locks.column_family_stats()?,
+        ])
+    }
+
     pub fn reset_db_for_execution_since_genesis(&self) -> SuiResult {
         // TODO: Add new tables that get added to the db automatically
         self.objects.unsafe_clear()?;
@@ -18,6 +18,7 @@ pub trait EpochStartConfigTrait {
     fn epoch_start_state(&self) -> &EpochStartSystemState;
     fn flags(&self) -> &[EpochFlag];
     fn authenticator_obj_initial_shared_version(&self) -> Option<SequenceNumber>;
+    fn randomness_obj_initial_shared_version(&self) -> Option<SequenceNumber>;
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
@@ -33,6 +34,7 @@ pub enum EpochStartConfiguration {
     V1(EpochStartConfigurationV1),
     V2(EpochStartConfigurationV2),
     V3(EpochStartConfigurationV3),
+    V4(EpochStartConfigurationV4),
 }
 
 impl EpochStartConfiguration {
@@ -40,12 +42,14 @@ impl EpochStartConfiguration {
         system_state: EpochStartSystemState,
         epoch_digest: CheckpointDigest,
         authenticator_obj_initial_shared_version: Option<SequenceNumber>,
+        randomness_obj_initial_shared_version: Option<SequenceNumber>,
     ) -> Self {
-        Self::new_v3(
+        Self::new_v4(
             system_state,
             epoch_digest,
             EpochFlag::default_flags_for_new_epoch(),
             authenticator_obj_initial_shared_version,
+            randomness_obj_initial_shared_version,
         )
     }
 
@@ -79,6 +83,22 @@ impl EpochStartConfiguration {
         ))
     }
 
+    pub fn new_v4(
+        system_state: EpochStartSystemState,
+        epoch_digest: CheckpointDigest,
+        flags: Vec<EpochFlag>,
+        authenticator_obj_initial_shared_version: Option<SequenceNumber>,
+        randomness_obj_initial_shared_version: Option<SequenceNumber>,
+    ) -> Self {
+        Self::V4(EpochStartConfigurationV4::new(
+            system_state,
+            epoch_digest,
+            flags,
+            authenticator_obj_initial_shared_version,
+            randomness_obj_initial_shared_version,
+        ))
+    }
+
     pub fn epoch_data(&self) -> EpochData {
         EpochData::new(
             self.epoch_start_state().epoch(),
@@ -118,6 +138,17 @@ pub struct EpochStartConfigurationV3 {
     authenticator_obj_initial_shared_version: Option<SequenceNumber>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct EpochStartConfigurationV4 {
+    system_state: EpochStartSystemState,
+    epoch_digest: CheckpointDigest,
+    flags: Vec<EpochFlag>,
+    /// Does the authenticator state object exist at the beginning of the epoch?
+    authenticator_obj_initial_shared_version: Option<SequenceNumber>,
+    /// Does the randomness state object exist at the beginning of the epoch?
+    randomness_obj_initial_shared_version: Option<SequenceNumber>,
+}
+
 impl EpochStartConfigurationV1 {
     pub fn new(system_state: EpochStartSystemState, epoch_digest: CheckpointDigest) -> Self {
         Self {
@@ -157,6 +188,24 @@ impl EpochStartConfigurationV3 {
     }
 }
 
+impl EpochStartConfigurationV4 {
+    pub fn new(
+        system_state: EpochStartSystemState,
+        epoch_digest: CheckpointDigest,
+        flags: Vec<EpochFlag>,
+        authenticator_obj_initial_shared_version: Option<SequenceNumber>,
+        randomness_obj_initial_shared_version: Option<SequenceNumber>,
+    ) -> Self {
+        Self {
+            system_state,
+            epoch_digest,
+            flags,
+            authenticator_obj_initial_shared_version,
+            randomness_obj_initial_shared_version,
+        }
+    }
+}
+
 impl EpochStartConfigTrait for EpochStartConfigurationV1 {
     fn epoch_digest(&self) -> CheckpointDigest {
         self.epoch_digest
@@ -173,6 +222,10 @@ impl EpochStartConfigTrait for EpochStartConfigurationV1 {
     fn authenticator_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
         None
     }
+
+    fn randomness_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
+        None
+    }
 }
 
 impl EpochStartConfigTrait for EpochStartConfigurationV2 {
@@ -191,6 +244,10 @@ impl EpochStartConfigTrait for EpochStartConfigurationV2 {
     fn authenticator_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
         None
     }
+
+    fn randomness_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
+        None
+    }
 }
 
 impl EpochStartConfigTrait for EpochStartConfigurationV3 {
@@ -209,6 +266,32 @@ impl EpochStartConfigTrait for EpochStartConfigurationV3 {
     fn authenticator_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
         self.authenticator_obj_initial_shared_version
     }
+
+    fn randomness_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
+        None
+    }
+}
+
+impl EpochStartConfigTrait for EpochStartConfigurationV4 {
+    fn epoch_digest(&self) -> CheckpointDigest {
+        self.epoch_digest
+    }
+
+    fn epoch_start_state(&self) -> &EpochStartSystemState {
+        &self.system_state
+    }
+
+    fn flags(&self) -> &[EpochFlag] {
+        &self.flags
+    }
+
+    fn authenticator_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
+        self.authenticator_obj_initial_shared_version
+    }
+
+    fn randomness_obj_initial_shared_version(&self) -> Option<SequenceNumber> {
+        self.randomness_obj_initial_shared_version
+    }
 }
 
 impl EpochFlag {
@@ -0,0 +1,272 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks, in debug builds, that the reads made on behalf of a transaction are served the versions
+//! of its inputs that it is pinned to: the versions in the transaction for owned and immutable
+//! objects, and the versions assigned by consensus for shared objects. A read served any other
+//! version, e.g. by a cache that fell out of sync with the store, would make execution diverge
+//! between validators.
+
+use std::collections::BTreeMap;
+
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::ModuleId;
+use sui_types::base_types::{EpochId, ObjectID, ObjectRef, SequenceNumber, TransactionDigest};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::object::Object;
+use sui_types::storage::{
+    BackingPackageStore, BackingStore, ChildObjectResolver, ObjectStore, ParentSync,
+};
+use sui_types::transaction::{InputObjectKind, InputObjects};
+
+/// The versions that the inputs of a transaction are pinned to.
+pub(crate) struct PinnedVersions {
+    tx_digest: TransactionDigest,
+    versions: BTreeMap<ObjectID, SequenceNumber>,
+}
+
+impl PinnedVersions {
+    /// Owned and immutable inputs are pinned to the versions in `input_kinds`, and shared inputs
+    /// to the versions in `shared_locks`, as assigned to the transaction by consensus.
+    pub(crate) fn new(
+        tx_digest: TransactionDigest,
+        input_kinds: &[InputObjectKind],
+        shared_locks: Vec<(ObjectID, SequenceNumber)>,
+    ) -> Self {
+        let owned = input_kinds.iter().filter_map(|kind| match kind {
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => Some((*id, *version)),
+            InputObjectKind::MovePackage(_) | InputObjectKind::SharedMoveObject { .. } => None,
+        });
+
+        Self {
+            tx_digest,
+            versions: owned.chain(shared_locks).collect(),
+        }
+    }
+
+    /// Panics if any of the inputs in `input_objects` were loaded at a version other than the one
+    /// they are pinned to.
+    pub(crate) fn check_inputs(&self, input_objects: &InputObjects) {
+        for object in input_objects.objects() {
+            self.check(object);
+        }
+    }
+
+    /// Wraps `store` so that every object it returns is checked against these versions.
+    pub(crate) fn check_reads<'a>(
+        &'a self,
+        store: &'a dyn BackingStore,
+    ) -> PinnedVersionsStore<'a> {
+        PinnedVersionsStore {
+            store,
+            pinned: self,
+        }
+    }
+
+    /// Panics if `object` is an input, but not at the version it is pinned to.
+    fn check(&self, object: &Object) {
+        let id = object.id();
+        let Some(pinned) = self.versions.get(&id) else {
+            return;
+        };
+        assert_eq!(
+            object.version(),
+            *pinned,
+            "Tx {:?} read object {id} at version {}, but its input is pinned at version {pinned}",
+            self.tx_digest,
+            object.version(),
+        );
+    }
+}
+
+/// Serves reads from a store on behalf of a transaction, panicking if the store returns an object
+/// at a version other than the one the transaction is pinned to or asked for.
+pub(crate) struct PinnedVersionsStore<'a> {
+    store: &'a dyn BackingStore,
+    pinned: &'a PinnedVersions,
+}
+
+impl PinnedVersionsStore<'_> {
+    fn check_version(&self, object: &Object, expected: SequenceNumber) {
+        assert_eq!(
+            object.version(),
+            expected,
+            "Tx {:?} read object {} at version {}, but asked for version {expected}",
+            self.pinned.tx_digest,
+            object.id(),
+            object.version(),
+        );
+    }
+}
+
+impl BackingPackageStore for PinnedVersionsStore<'_> {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.store.get_package_object(package_id)
+    }
+}
+
+impl ChildObjectResolver for PinnedVersionsStore<'_> {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        let object = self
+            .store
+            .read_child_object(parent, child, child_version_upper_bound)?;
+        if let Some(object) = &object {
+            assert!(
+                object.version() <= child_version_upper_bound,
+                "Tx {:?} read child object {child} at version {}, after its upper bound {}",
+                self.pinned.tx_digest,
+                object.version(),
+                child_version_upper_bound,
+            );
+            self.pinned.check(object);
+        }
+        Ok(object)
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<Object>> {
+        let object = self.store.get_object_received_at_version(
+            owner,
+            receiving_object_id,
+            receive_object_at_version,
+            epoch_id,
+        )?;
+        if let Some(object) = &object {
+            self.check_version(object, receive_object_at_version);
+        }
+        Ok(object)
+    }
+}
+
+impl GetModule for PinnedVersionsStore<'_> {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<CompiledModule>, SuiError> {
+        self.store.get_module_by_id(id)
+    }
+}
+
+impl ObjectStore for PinnedVersionsStore<'_> {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        let object = self.store.get_object(object_id)?;
+        if let Some(object) = &object {
+            self.pinned.check(object);
+        }
+        Ok(object)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        let object = self.store.get_object_by_key(object_id, version)?;
+        if let Some(object) = &object {
+            self.check_version(object, version);
+        }
+        Ok(object)
+    }
+}
+
+impl ParentSync for PinnedVersionsStore<'_> {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<ObjectRef>> {
+        self.store.get_latest_parent_entry_ref_deprecated(object_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::SuiAddress;
+    use sui_types::in_memory_storage::InMemoryStorage;
+
+    use super::*;
+
+    fn object(id: ObjectID, version: u64) -> Object {
+        Object::with_id_owner_version_for_testing(
+            id,
+            SequenceNumber::from_u64(version),
+            SuiAddress::ZERO,
+        )
+    }
+
+    fn pinned(owned: &[Object], shared: &[(ObjectID, u64)]) -> PinnedVersions {
+        let kinds: Vec<_> = owned
+            .iter()
+            .map(|o| InputObjectKind::ImmOrOwnedMoveObject(o.compute_object_reference()))
+            .collect();
+        let shared = shared
+            .iter()
+            .map(|(id, version)| (*id, SequenceNumber::from_u64(*version)))
+            .collect();
+        PinnedVersions::new(TransactionDigest::random(), &kinds, shared)
+    }
+
+    #[test]
+    fn test_reads_at_pinned_versions() {
+        let owned = object(ObjectID::random(), 3);
+        let shared = ObjectID::random();
+        let other = object(ObjectID::random(), 7);
+        let store = InMemoryStorage::new(vec![owned.clone(), object(shared, 5), other.clone()]);
+        let pinned = pinned(&[owned.clone()], &[(shared, 5)]);
+        let reads = pinned.check_reads(&store);
+
+        assert_eq!(reads.get_object(&owned.id()).unwrap(), Some(owned.clone()));
+        assert!(reads.get_object(&shared).unwrap().is_some());
+        // Objects that are not inputs are not pinned.
+        assert_eq!(reads.get_object(&other.id()).unwrap(), Some(other));
+        assert_eq!(
+            reads
+                .get_object_by_key(&owned.id(), owned.version())
+                .unwrap(),
+            Some(owned),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "but its input is pinned at version 3")]
+    fn test_read_owned_input_at_wrong_version() {
+        let id = ObjectID::random();
+        let pinned = pinned(&[object(id, 3)], &[]);
+        // The store has moved on from the version the transaction is pinned to.
+        let store = InMemoryStorage::new(vec![object(id, 5)]);
+        pinned.check_reads(&store).get_object(&id).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "but its input is pinned at version 4")]
+    fn test_read_shared_input_at_wrong_version() {
+        let id = ObjectID::random();
+        let pinned = pinned(&[], &[(id, 4)]);
+        let store = InMemoryStorage::new(vec![object(id, 5)]);
+        pinned.check_reads(&store).get_object(&id).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "but its input is pinned at version 4")]
+    fn test_input_loaded_at_wrong_version() {
+        let id = ObjectID::random();
+        let pinned = pinned(&[], &[(id, 4)]);
+        let loaded = object(id, 5);
+        let kind = InputObjectKind::SharedMoveObject {
+            id,
+            initial_shared_version: SequenceNumber::from_u64(1),
+            mutable: true,
+        };
+        pinned.check_inputs(&InputObjects::new(vec![(kind, loaded)]));
+    }
+}
@@ -176,6 +176,7 @@ impl<'a> TestAuthorityBuilder<'a> {
             genesis.sui_system_object().into_epoch_start_state(),
             *genesis.checkpoint().digest(),
             genesis.authenticator_state_obj_initial_shared_version(),
+            genesis.randomness_state_obj_initial_shared_version(),
         );
         let expensive_safety_checks = match self.expensive_safety_checks {
             None => ExpensiveSafetyCheckConfig::default(),
@@ -235,6 +236,7 @@ impl<'a> TestAuthorityBuilder<'a> {
                 dump_file_directory: Some(tempdir().unwrap().into_path()),
             },
             ArchiveReaderBalancer::default(),
+            None,
         )
         .await;
         // For any type of local testing that does not actually spawn a node, the checkpoint executor
@@ -32,6 +32,7 @@ use tokio::task::JoinHandle;
 use tracing::{error_span, info, Instrument};
 
 use crate::consensus_adapter::{ConnectionMonitorStatusForTests, LazyNarwhalClient};
+use crate::validator_qos::{QosPermit, ValidatorQos};
 use crate::{
     authority::AuthorityState,
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
@@ -120,6 +121,7 @@ impl AuthorityServer {
                 state: self.state,
                 consensus_adapter: self.consensus_adapter,
                 metrics: self.metrics.clone(),
+                qos: None,
             }))
             .bind(&address)
             .await
@@ -219,6 +221,7 @@ pub struct ValidatorService {
     state: Arc<AuthorityState>,
     consensus_adapter: Arc<ConsensusAdapter>,
     metrics: Arc<ValidatorServiceMetrics>,
+    qos: Option<Arc<ValidatorQos>>,
 }
 
 impl ValidatorService {
@@ -231,9 +234,16 @@ impl ValidatorService {
             state,
             consensus_adapter,
             metrics,
+            qos: None,
         }
     }
 
+    /// Admit requests against a separate budget for each class of client.
+    pub fn with_qos(mut self, qos: Arc<ValidatorQos>) -> Self {
+        self.qos = Some(qos);
+        self
+    }
+
     pub fn validator_state(&self) -> &Arc<AuthorityState> {
         &self.state
     }
@@ -256,6 +266,7 @@ impl ValidatorService {
             state,
             consensus_adapter,
             metrics,
+            ..
         } = self;
 
         let transaction = request.into_inner();
@@ -333,6 +344,7 @@ impl ValidatorService {
             state,
             consensus_adapter,
             metrics,
+            ..
         } = self;
 
         let epoch_store = state.load_epoch_store_one_call_per_task();
@@ -450,6 +462,18 @@ impl ValidatorService {
             fastpath_input_objects: vec![], // fastpath is unused for now
         }))
     }
+
+    /// Waits for `request` to be admitted within the budget of its client's class, if QoS classes
+    /// are configured. The returned permit must be held until the request has been handled.
+    async fn admit<T>(
+        &self,
+        request: &tonic::Request<T>,
+    ) -> Result<Option<QosPermit>, tonic::Status> {
+        match &self.qos {
+            Some(qos) => qos.admit(request.remote_addr()).await.map(Some),
+            None => Ok(None),
+        }
+    }
 }
 
 #[async_trait]
@@ -458,6 +482,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<Transaction>,
     ) -> Result<tonic::Response<HandleTransactionResponse>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let validator_service = self.clone();
 
         // Spawns a task which handles the transaction. The task will unconditionally continue
@@ -471,6 +496,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<CertifiedTransaction>,
     ) -> Result<tonic::Response<SubmitCertificateResponse>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let validator_service = self.clone();
         // Spawns a task which handles the certificate. The task will unconditionally continue
         // processing in the event that the client connection is dropped.
@@ -493,6 +519,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<CertifiedTransaction>,
     ) -> Result<tonic::Response<HandleCertificateResponseV2>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let validator_service = self.clone();
         // Spawns a task which handles the certificate. The task will unconditionally continue
         // processing in the event that the client connection is dropped.
@@ -524,6 +551,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<ObjectInfoRequest>,
     ) -> Result<tonic::Response<ObjectInfoResponse>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let request = request.into_inner();
 
         let response = self.state.handle_object_info_request(request).await?;
@@ -535,6 +563,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<TransactionInfoRequest>,
     ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let request = request.into_inner();
 
         let response = self.state.handle_transaction_info_request(request).await?;
@@ -546,6 +575,7 @@ impl Validator for ValidatorService {
         &self,
         request: tonic::Request<CheckpointRequest>,
     ) -> Result<tonic::Response<CheckpointResponse>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let request = request.into_inner();
 
         let response = self.state.handle_checkpoint_request(&request)?;
@@ -555,8 +585,9 @@ impl Validator for ValidatorService {
 
     async fn get_system_state_object(
         &self,
-        _request: tonic::Request<SystemStateRequest>,
+        request: tonic::Request<SystemStateRequest>,
     ) -> Result<tonic::Response<SuiSystemState>, tonic::Status> {
+        let _permit = self.admit(&request).await?;
         let response = self.state.database.get_sui_system_state_object()?;
 
         return Ok(tonic::Response::new(response));
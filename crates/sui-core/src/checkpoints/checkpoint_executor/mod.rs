@@ -24,12 +24,14 @@ use std::{
     time::{Duration, Instant},
 };
 
+use fastcrypto::hash::MultisetHash;
 use futures::stream::FuturesOrdered;
 use itertools::izip;
 use mysten_metrics::{spawn_monitored_task, MonitoredFutureExt};
 use prometheus::Registry;
 use sui_config::node::CheckpointExecutorConfig;
 use sui_macros::{fail_point, fail_point_async};
+use sui_types::accumulator::Accumulator;
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::executable_transaction::VerifiedExecutableTransaction;
 use sui_types::message_envelope::Message;
@@ -51,9 +53,13 @@ use typed_store::Map;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::authority::AuthorityStore;
+use crate::epoch::epoch_metrics::ReconfigStage;
 use crate::state_accumulator::StateAccumulator;
 use crate::transaction_manager::TransactionManager;
-use crate::{authority::EffectsNotifyRead, checkpoints::CheckpointStore};
+use crate::{
+    authority::EffectsNotifyRead,
+    checkpoints::{CheckpointExecutionRecord, CheckpointStore},
+};
 
 use self::metrics::CheckpointExecutorMetrics;
 
@@ -339,6 +345,9 @@ impl CheckpointExecutor {
             checkpoint_epoch,
             epoch_store.epoch(),
         );
+        if checkpoint.end_of_epoch_data.is_some() {
+            epoch_store.record_reconfig_stage(ReconfigStage::LastCheckpointCertified);
+        }
 
         let epoch_store = epoch_store.clone();
         // NOTE: We can't re-enqueue out of order. Therefore we cannot allow
@@ -469,6 +478,9 @@ impl CheckpointExecutor {
             );
         }
 
+        let transaction_count = all_tx_digests.len() as u64;
+        let executed_transaction_count = executable_txns.len() as u64;
+        let cache_stats_start = self.tx_manager.cache_stats();
         self.tx_manager
             .enqueue_with_expected_effects_digest(executable_txns.clone(), &epoch_store)?;
 
@@ -479,14 +491,14 @@ impl CheckpointExecutor {
         let accumulator = self.accumulator.clone();
         let metrics = self.metrics.clone();
         pending.push_back(spawn_monitored_task!(async move {
-            handle_execution_effects(
+            let checkpoint_accumulator = handle_execution_effects(
                 execution_digests,
                 all_tx_digests,
                 checkpoint.clone(),
-                checkpoint_store,
+                checkpoint_store.clone(),
                 authority_store,
                 epoch_store,
-                tx_manager,
+                tx_manager.clone(),
                 accumulator,
                 local_execution_timeout_sec,
             )
@@ -498,6 +510,16 @@ impl CheckpointExecutor {
             if checkpoint.sequence_number % CHECKPOINT_PROGRESS_LOG_COUNT_INTERVAL == 0 {
                 info!(seq = ?checkpoint.sequence_number(), "Checkpoint execution took {:?}", exec_elapsed);
             }
+            let record = CheckpointExecutionRecord {
+                transaction_count,
+                executed_transaction_count,
+                effects_accumulator: checkpoint_accumulator.map(|acc| acc.digest().into()),
+                execution_duration_us: exec_elapsed.as_micros() as u64,
+                cache_stats: tx_manager.cache_stats().since(&cache_stats_start),
+            };
+            checkpoint_store
+                .insert_checkpoint_execution_record(*checkpoint.sequence_number(), &record)
+                .expect("Failed to insert checkpoint execution record");
             checkpoint
         }));
 
@@ -608,7 +630,7 @@ impl CheckpointExecutor {
                         .await
                         .expect("Failed to get executed effects for finalizing checkpoint");
 
-                    finalize_checkpoint(
+                    let checkpoint_accumulator = finalize_checkpoint(
                         self.authority_store.clone(),
                         &all_tx_digests,
                         epoch_store.clone(),
@@ -618,6 +640,18 @@ impl CheckpointExecutor {
                     )
                     .expect("Finalizing checkpoint cannot fail");
 
+                    // The execution record of the checkpoint was written before it could be
+                    // finalized.
+                    let mut record = self
+                        .checkpoint_store
+                        .get_checkpoint_execution_record(*checkpoint.sequence_number())
+                        .expect("read cannot fail")
+                        .unwrap_or_default();
+                    record.effects_accumulator = Some(checkpoint_accumulator.digest().into());
+                    self.checkpoint_store
+                        .insert_checkpoint_execution_record(*checkpoint.sequence_number(), &record)
+                        .expect("Failed to insert checkpoint execution record");
+
                     self.accumulator
                         .accumulate_epoch(
                             &cur_epoch,
@@ -628,6 +662,7 @@ impl CheckpointExecutor {
                         .await
                         .expect("Accumulating epoch cannot fail");
 
+                    epoch_store.record_reconfig_stage(ReconfigStage::EpochEnded);
                     return true;
                 }
             }
@@ -647,7 +682,7 @@ async fn handle_execution_effects(
     transaction_manager: Arc<TransactionManager>,
     accumulator: Arc<StateAccumulator>,
     local_execution_timeout_sec: u64,
-) {
+) -> Option<Accumulator> {
     // Once synced_txns have been awaited, all txns should have effects committed.
     let mut periods = 1;
     let log_timeout_sec = Duration::from_secs(local_execution_timeout_sec);
@@ -745,7 +780,10 @@ async fn handle_execution_effects(
 
                 // if end of epoch checkpoint, we must finalize the checkpoint after executing
                 // the change epoch tx, which is done after all other checkpoint execution
-                if checkpoint.end_of_epoch_data.is_none() {
+                if checkpoint.end_of_epoch_data.is_some() {
+                    return None;
+                }
+                return Some(
                     finalize_checkpoint(
                         authority_store.clone(),
                         &all_tx_digests,
@@ -754,9 +792,8 @@ async fn handle_execution_effects(
                         accumulator.clone(),
                         effects,
                     )
-                    .expect("Finalizing checkpoint cannot fail");
-                }
-                return;
+                    .expect("Finalizing checkpoint cannot fail"),
+                );
             }
         }
     }
@@ -987,7 +1024,7 @@ fn finalize_checkpoint(
     checkpoint_sequence: u64,
     accumulator: Arc<StateAccumulator>,
     effects: Vec<TransactionEffects>,
-) -> SuiResult {
+) -> SuiResult<Accumulator> {
     if epoch_store.per_epoch_finalized_txns_enabled() {
         epoch_store.insert_finalized_transactions(tx_digests, checkpoint_sequence)?;
     }
@@ -998,6 +1035,5 @@ fn finalize_checkpoint(
         checkpoint_sequence,
     )?;
 
-    accumulator.accumulate_checkpoint(effects, checkpoint_sequence, epoch_store)?;
-    Ok(())
+    accumulator.accumulate_checkpoint(effects, checkpoint_sequence, epoch_store)
 }
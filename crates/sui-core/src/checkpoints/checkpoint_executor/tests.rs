@@ -64,6 +64,15 @@ pub async fn test_checkpoint_executor_crash_recovery() {
         .expect("Expected highest executed to not be None");
     assert_eq!(highest_executed, 2 * (buffer_size as u64) - 1,);
 
+    // and recorded how each of them was executed
+    let records = checkpoint_store.get_checkpoint_execution_records(0, highest_executed);
+    assert_eq!(records.len() as u64, highest_executed + 1);
+    for (seq, (record_seq, record)) in records.iter().enumerate() {
+        assert_eq!(*record_seq, seq as u64);
+        assert_eq!(record.transaction_count, 0);
+        assert!(record.effects_accumulator.is_some());
+    }
+
     // Simulate node restart
     executor_handle.abort();
 
@@ -224,6 +233,14 @@ pub async fn test_checkpoint_executor_cross_epoch() {
         .contains_key(&first_epoch)
         .unwrap());
 
+    // The end of epoch checkpoint is only finalized once its change epoch transaction has been
+    // executed, which fills in the effects accumulator of the execution record written before.
+    let record = checkpoint_store
+        .get_checkpoint_execution_record(*end_of_epoch_0_checkpoint.sequence_number())
+        .unwrap()
+        .expect("Expected end of epoch checkpoint to have an execution record");
+    assert!(record.effects_accumulator.is_some());
+
     let system_state = EpochStartSystemState::new_for_testing_with_epoch(1);
 
     let new_epoch_store = authority_state
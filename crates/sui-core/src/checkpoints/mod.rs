@@ -15,6 +15,7 @@ pub use crate::checkpoints::checkpoint_output::{
 pub use crate::checkpoints::metrics::CheckpointMetrics;
 use crate::stake_aggregator::{InsertResult, StakeAggregator};
 use crate::state_accumulator::StateAccumulator;
+pub use crate::transaction_manager::TransactionManagerCacheStats;
 use futures::future::{select, Either};
 use futures::FutureExt;
 use mysten_metrics::{monitored_scope, spawn_monitored_task, MonitoredFutureExt};
@@ -38,8 +39,9 @@ use sui_types::message_envelope::Message;
 use sui_types::messages_checkpoint::SignedCheckpointSummary;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
-    CheckpointSignatureMessage, CheckpointSummary, CheckpointTimestamp, EndOfEpochData,
-    FullCheckpointContents, TrustedCheckpoint, VerifiedCheckpoint, VerifiedCheckpointContents,
+    CheckpointSignatureMessage, CheckpointSummary, CheckpointTimestamp, ECMHLiveObjectSetDigest,
+    EndOfEpochData, FullCheckpointContents, TrustedCheckpoint, VerifiedCheckpoint,
+    VerifiedCheckpointContents,
 };
 use sui_types::messages_consensus::ConsensusTransactionKey;
 use sui_types::signature::GenericSignature;
@@ -84,6 +86,27 @@ pub struct BuilderCheckpointSummary {
     pub position_in_commit: usize,
 }
 
+/// How this node executed a checkpoint, kept so that the execution of two nodes that diverged
+/// can be compared checkpoint by checkpoint.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CheckpointExecutionRecord {
+    /// Number of transactions in the checkpoint.
+    pub transaction_count: u64,
+    /// Number of those transactions that were executed for the checkpoint, rather than before it
+    /// was synced.
+    pub executed_transaction_count: u64,
+    /// Digest of the accumulated effects of the transactions in the checkpoint. It is missing
+    /// until the checkpoint is finalized, which for the last checkpoint of an epoch is only after
+    /// its change epoch transaction is executed.
+    pub effects_accumulator: Option<ECMHLiveObjectSetDigest>,
+    /// Time from the transactions being enqueued for execution until all their effects were
+    /// available, not including the change epoch transaction.
+    pub execution_duration_us: u64,
+    /// Cache hits and misses while the checkpoint was executing. Other checkpoints executing at
+    /// the same time contribute to them too.
+    pub cache_stats: TransactionManagerCacheStats,
+}
+
 #[derive(DBMapUtils)]
 pub struct CheckpointStore {
     /// Maps checkpoint contents digest to checkpoint contents
@@ -114,6 +137,9 @@ pub struct CheckpointStore {
     /// Watermarks used to determine the highest verified, fully synced, and
     /// fully executed checkpoints
     pub(crate) watermarks: DBMap<CheckpointWatermark, (CheckpointSequenceNumber, CheckpointDigest)>,
+
+    /// How each checkpoint was executed by this node
+    pub(crate) checkpoint_execution_records: DBMap<CheckpointSequenceNumber, CheckpointExecutionRecord>,
 }
 
 impl CheckpointStore {
@@ -306,6 +332,34 @@ impl CheckpointStore {
         self.full_checkpoint_content.get(&seq)
     }
 
+    pub fn insert_checkpoint_execution_record(
+        &self,
+        seq: CheckpointSequenceNumber,
+        record: &CheckpointExecutionRecord,
+    ) -> Result<(), TypedStoreError> {
+        self.checkpoint_execution_records.insert(&seq, record)
+    }
+
+    pub fn get_checkpoint_execution_record(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointExecutionRecord>, TypedStoreError> {
+        self.checkpoint_execution_records.get(&seq)
+    }
+
+    /// Execution records of the checkpoints from `start` to `end` inclusive, in order. Checkpoints
+    /// that were not executed by this node, e.g. because it was restored from a snapshot, are
+    /// skipped.
+    pub fn get_checkpoint_execution_records(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> Vec<(CheckpointSequenceNumber, CheckpointExecutionRecord)> {
+        self.checkpoint_execution_records
+            .range_iter(start..=end)
+            .collect()
+    }
+
     fn prune_local_summaries(&self) -> SuiResult {
         if let Some((last_local_summary, _)) = self
             .locally_computed_checkpoints
@@ -900,12 +954,13 @@ impl CheckpointBuilder {
             {
                 let (transaction, size) = transaction_and_size
                     .unwrap_or_else(|| panic!("Could not find executed transaction {:?}", effects));
-                // ConsensusCommitPrologue and AuthenticatorStateUpdate are guaranteed to be
-                // processed before we reach here
+                // ConsensusCommitPrologue, AuthenticatorStateUpdate and RandomnessStateUpdate are
+                // guaranteed to be processed before we reach here
                 if !matches!(
                     transaction.inner().transaction_data().kind(),
                     TransactionKind::ConsensusCommitPrologue(_)
                         | TransactionKind::AuthenticatorStateUpdate(_)
+                        | TransactionKind::RandomnessStateUpdate(_)
                 ) {
                     transaction_keys.push(SequencedConsensusTransactionKey::External(
                         ConsensusTransactionKey::Certificate(*effects.transaction_digest()),
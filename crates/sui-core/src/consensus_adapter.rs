@@ -43,6 +43,7 @@ use tokio::task::JoinHandle;
 use tokio::time::{self, sleep, timeout};
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::consensus_commit_log::{ConsensusCommitLog, CONSENSUS_COMMIT_LOG_CAPACITY};
 use crate::consensus_handler::{classify, SequencedConsensusTransactionKey};
 use crate::epoch::reconfiguration::{ReconfigState, ReconfigurationInitiator};
 use mysten_metrics::{spawn_monitored_task, GaugeGuard, GaugeGuardFutureExt};
@@ -309,6 +310,8 @@ pub struct ConsensusAdapter {
     /// Semaphore limiting parallel submissions to narwhal
     submit_semaphore: Semaphore,
     latency_observer: LatencyObserver,
+    /// Recent commits of consensus, written by the consensus handler of every epoch
+    commit_log: Arc<ConsensusCommitLog>,
 }
 
 pub trait CheckConnection: Send + Sync {
@@ -356,9 +359,17 @@ impl ConsensusAdapter {
             metrics,
             submit_semaphore: Semaphore::new(max_pending_local_submissions),
             latency_observer: LatencyObserver::new(),
+            commit_log: Arc::new(ConsensusCommitLog::new(
+                authority,
+                CONSENSUS_COMMIT_LOG_CAPACITY,
+            )),
         }
     }
 
+    pub fn commit_log(&self) -> Arc<ConsensusCommitLog> {
+        self.commit_log.clone()
+    }
+
     pub fn swap_low_scoring_authorities(
         &self,
         new_low_scoring: Arc<ArcSwap<HashMap<AuthorityName, u64>>>,
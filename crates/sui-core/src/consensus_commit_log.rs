@@ -0,0 +1,106 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use sui_types::base_types::{AuthorityName, EpochId};
+
+/// Number of commits kept by the log.
+pub const CONSENSUS_COMMIT_LOG_CAPACITY: usize = 1000;
+
+/// Summary of a single commit of the consensus output, as seen by the consensus handler.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct ConsensusCommitInfo {
+    pub epoch: EpochId,
+    pub round: u64,
+    pub sub_dag_index: u64,
+    pub timestamp_ms: u64,
+    pub leader: AuthorityName,
+    pub num_certificates: usize,
+    pub num_transactions: usize,
+    /// Number of certificates in the commit authored by this validator.
+    pub own_certificates: usize,
+    /// Number of transactions in the commit that were submitted through this validator.
+    pub own_transactions: usize,
+    /// Reputation scores of the leader schedule at the time of the commit.
+    pub reputation_scores: BTreeMap<AuthorityName, u64>,
+    /// Whether these are the final scores of the schedule, which are used to pick low scoring
+    /// authorities.
+    pub final_of_schedule: bool,
+}
+
+/// Keeps the most recent consensus commits in memory so that they can be inspected through the
+/// admin interface, instead of being reconstructed from logs.
+pub struct ConsensusCommitLog {
+    /// The validator this node runs as, used to tell whether its submissions were included.
+    authority: AuthorityName,
+    capacity: usize,
+    commits: Mutex<VecDeque<ConsensusCommitInfo>>,
+}
+
+impl ConsensusCommitLog {
+    pub fn new(authority: AuthorityName, capacity: usize) -> Self {
+        Self {
+            authority,
+            capacity,
+            commits: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn authority(&self) -> AuthorityName {
+        self.authority
+    }
+
+    pub fn record(&self, commit: ConsensusCommitInfo) {
+        let mut commits = self.commits.lock();
+        if commits.len() == self.capacity {
+            commits.pop_front();
+        }
+        commits.push_back(commit);
+    }
+
+    /// Returns up to `limit` of the most recent commits, newest first.
+    pub fn last(&self, limit: usize) -> Vec<ConsensusCommitInfo> {
+        self.commits
+            .lock()
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(round: u64) -> ConsensusCommitInfo {
+        ConsensusCommitInfo {
+            epoch: 0,
+            round,
+            sub_dag_index: round,
+            timestamp_ms: 0,
+            leader: AuthorityName::ZERO,
+            num_certificates: 1,
+            num_transactions: 0,
+            own_certificates: 0,
+            own_transactions: 0,
+            reputation_scores: BTreeMap::new(),
+            final_of_schedule: false,
+        }
+    }
+
+    #[test]
+    fn test_consensus_commit_log_keeps_most_recent() {
+        let log = ConsensusCommitLog::new(AuthorityName::ZERO, 3);
+        for round in 1..=5 {
+            log.record(commit(round));
+        }
+        let rounds: Vec<_> = log.last(10).iter().map(|c| c.round).collect();
+        assert_eq!(rounds, vec![5, 4, 3]);
+        let rounds: Vec<_> = log.last(2).iter().map(|c| c.round).collect();
+        assert_eq!(rounds, vec![5, 4]);
+    }
+}
@@ -7,6 +7,8 @@ use crate::authority::authority_per_epoch_store::{
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::AuthorityMetrics;
 use crate::checkpoints::{CheckpointServiceNotify, PendingCheckpoint, PendingCheckpointInfo};
+use crate::consensus_commit_log::{ConsensusCommitInfo, ConsensusCommitLog};
+use crate::epoch::epoch_metrics::ReconfigStage;
 use std::cmp::Ordering;
 
 use crate::scoring_decision::update_low_scoring_authorities;
@@ -17,7 +19,7 @@ use fastcrypto::hash::Hash as _Hash;
 use fastcrypto::traits::ToFromBytes;
 use lru::LruCache;
 use mysten_metrics::{monitored_scope, spawn_monitored_task};
-use narwhal_config::Committee;
+use narwhal_config::{AuthorityIdentifier, Committee};
 use narwhal_executor::{ExecutionIndices, ExecutionState};
 use narwhal_types::{BatchAPI, CertificateAPI, ConsensusOutput, HeaderAPI};
 use serde::{Deserialize, Serialize};
@@ -51,8 +53,12 @@ pub struct ConsensusHandler<T, C> {
     object_store: T,
     /// Reputation scores used by consensus adapter that we update, forwarded from consensus
     low_scoring_authorities: Arc<ArcSwap<HashMap<AuthorityName, u64>>>,
+    /// Authorities that are never flagged as low scoring, as configured by this validator
+    low_scoring_exclusions: HashSet<AuthorityName>,
     /// The narwhal committee used to do stake computations for deciding set of low scoring authorities
     committee: Committee,
+    /// Recent commits, kept for inspection through the admin interface
+    commit_log: Arc<ConsensusCommitLog>,
     // TODO: ConsensusHandler doesn't really share metrics with AuthorityState. We could define
     // a new metrics type here if we want to.
     metrics: Arc<AuthorityMetrics>,
@@ -70,7 +76,9 @@ impl<T, C> ConsensusHandler<T, C> {
         transaction_manager: Arc<TransactionManager>,
         object_store: T,
         low_scoring_authorities: Arc<ArcSwap<HashMap<AuthorityName, u64>>>,
+        low_scoring_exclusions: HashSet<AuthorityName>,
         committee: Committee,
+        commit_log: Arc<ConsensusCommitLog>,
         metrics: Arc<AuthorityMetrics>,
     ) -> Self {
         // last_seen is zero at the beginning of epoch, including for hash.
@@ -86,7 +94,9 @@ impl<T, C> ConsensusHandler<T, C> {
             checkpoint_service,
             object_store,
             low_scoring_authorities,
+            low_scoring_exclusions,
             committee,
+            commit_log,
             metrics,
             processed_cache: LruCache::new(NonZeroUsize::new(PROCESSED_CACHE_CAP).unwrap()),
             transaction_scheduler,
@@ -107,6 +117,53 @@ impl<T, C> ConsensusHandler<T, C> {
         }
         None
     }
+
+    fn authority_name(&self, authority: AuthorityIdentifier) -> AuthorityName {
+        self.committee
+            .authority_safe(&authority)
+            .protocol_key()
+            .into()
+    }
+
+    /// Records a summary of the commit in the commit log, including how much of it was submitted
+    /// through this validator.
+    fn record_commit(&self, consensus_output: &ConsensusOutput, round: u64, timestamp: u64) {
+        let own_authority = self.commit_log.authority();
+        let mut num_transactions = 0;
+        let mut own_certificates = 0;
+        let mut own_transactions = 0;
+        for (cert, batches) in consensus_output
+            .sub_dag
+            .certificates
+            .iter()
+            .zip(consensus_output.batches.iter())
+        {
+            let transactions: usize = batches.iter().map(|b| b.transactions().len()).sum();
+            num_transactions += transactions;
+            if self.authority_name(cert.header().author()) == own_authority {
+                own_certificates += 1;
+                own_transactions += transactions;
+            }
+        }
+        let reputation_score = &consensus_output.sub_dag.reputation_score;
+        self.commit_log.record(ConsensusCommitInfo {
+            epoch: self.epoch_store.epoch(),
+            round,
+            sub_dag_index: consensus_output.sub_dag.sub_dag_index,
+            timestamp_ms: timestamp,
+            leader: self.authority_name(consensus_output.sub_dag.leader.header().author()),
+            num_certificates: consensus_output.sub_dag.certificates.len(),
+            num_transactions,
+            own_certificates,
+            own_transactions,
+            reputation_scores: reputation_score
+                .scores_per_authority
+                .iter()
+                .map(|(authority, score)| (self.authority_name(*authority), *score))
+                .collect(),
+            final_of_schedule: reputation_score.final_of_schedule,
+        });
+    }
 }
 
 fn update_hash(
@@ -168,6 +225,8 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync> Exe
             );
             return;
         }
+        self.epoch_store
+            .record_reconfig_stage(ReconfigStage::FirstCommit);
 
         let mut sequenced_transactions = Vec::new();
         let mut end_of_publish_transactions = Vec::new();
@@ -232,6 +291,8 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync> Exe
             ));
         }
 
+        self.record_commit(&consensus_output, round, timestamp);
+
         update_low_scoring_authorities(
             self.low_scoring_authorities.clone(),
             &self.committee,
@@ -240,6 +301,7 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync> Exe
             self.epoch_store
                 .protocol_config()
                 .consensus_bad_nodes_stake_threshold(),
+            &self.low_scoring_exclusions,
         );
 
         self.metrics
@@ -451,18 +513,86 @@ fn order_by_gas_price(sequenced_transactions: &mut [VerifiedSequencedConsensusTr
         // Reverse order, so that transactions with higher gas price are put to the beginning.
         std::cmp::Reverse({
             match &txn.0.transaction {
-                SequencedConsensusTransactionKind::External(ConsensusTransaction {
-                    tracking_id: _,
-                    kind: ConsensusTransactionKind::UserTransaction(cert),
-                }) => cert.gas_price(),
+                SequencedConsensusTransactionKind::External(transaction) => {
+                    ordering_gas_price(transaction)
+                }
                 // Non-user transactions are considered to have gas price of MAX u64 and are put to the beginning.
                 // This way consensus commit prologue transactions will stay at the beginning.
-                _ => u64::MAX,
+                SequencedConsensusTransactionKind::System(_) => u64::MAX,
             }
         })
     });
 }
 
+/// The gas price that `transaction` is ordered by. Non-user transactions are placed as if they had
+/// the highest possible gas price.
+fn ordering_gas_price(transaction: &ConsensusTransaction) -> u64 {
+    match &transaction.kind {
+        ConsensusTransactionKind::UserTransaction(cert) => cert.gas_price(),
+        _ => u64::MAX,
+    }
+}
+
+/// Where a transaction is placed among the transactions sequenced from the same consensus output,
+/// as reported by `audit_consensus_ordering`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConsensusOrderingEntry {
+    /// Position of the transaction in the input, i.e. in the consensus output.
+    pub input_index: usize,
+    pub key: String,
+    /// Gas price of user transactions, `None` for other transactions, which are placed as if they
+    /// had the highest possible gas price.
+    pub gas_price: Option<u64>,
+    /// Whether the transaction is placed after the previous one only because it comes after it in
+    /// the consensus output, rather than because it has a lower gas price. The relative order of
+    /// tied transactions is decided by consensus, not by the validators that submitted them.
+    pub tied_with_previous: bool,
+}
+
+/// Reports the order that the consensus handler sequences `transactions` in, if they appear in
+/// that order in the same consensus output, under the given `ordering`. This is the order they are
+/// assigned shared object versions and scheduled for execution in, so it can be used to explain
+/// why one transaction was executed before another.
+///
+/// With `ByGasPrice` ordering, transactions are sorted by gas price, highest first. Ties are not
+/// broken by digest, or anything else about the transactions themselves: tied transactions keep
+/// their relative order from the consensus output, and are flagged as `tied_with_previous`.
+pub fn audit_consensus_ordering(
+    ordering: ConsensusTransactionOrdering,
+    transactions: &[ConsensusTransaction],
+) -> Vec<ConsensusOrderingEntry> {
+    let mut order: Vec<usize> = (0..transactions.len()).collect();
+    let by_gas_price = matches!(ordering, ConsensusTransactionOrdering::ByGasPrice);
+    if by_gas_price {
+        // Must match `order_by_gas_price`, including the stability of the sort.
+        order.sort_by_key(|i| std::cmp::Reverse(ordering_gas_price(&transactions[*i])));
+    }
+
+    let mut previous_gas_price = None;
+    order
+        .into_iter()
+        .map(|input_index| {
+            let transaction = &transactions[input_index];
+            let price = ordering_gas_price(transaction);
+            let tied_with_previous = match previous_gas_price {
+                None => false,
+                Some(previous) => !by_gas_price || previous == price,
+            };
+            previous_gas_price = Some(price);
+
+            ConsensusOrderingEntry {
+                input_index,
+                key: format!("{:?}", transaction.key()),
+                gas_price: match &transaction.kind {
+                    ConsensusTransactionKind::UserTransaction(cert) => Some(cert.gas_price()),
+                    _ => None,
+                },
+                tied_with_previous,
+            }
+        })
+        .collect()
+}
+
 struct AsyncTransactionScheduler {
     sender: tokio::sync::mpsc::Sender<Vec<VerifiedExecutableTransaction>>,
 }
@@ -675,7 +805,7 @@ mod tests {
     use crate::authority::test_authority_builder::TestAuthorityBuilder;
     use crate::checkpoints::CheckpointServiceNoop;
     use crate::consensus_adapter::consensus_tests::{test_certificates, test_gas_objects};
-    use narwhal_config::AuthorityIdentifier;
+    use crate::consensus_commit_log::CONSENSUS_COMMIT_LOG_CAPACITY;
     use narwhal_test_utils::latest_protocol_version;
     use narwhal_types::{
         Batch, Certificate, CommittedSubDag, Header, HeaderV1Builder, ReputationScores,
@@ -715,6 +845,10 @@ mod tests {
         let epoch_store = state.epoch_store_for_testing().clone();
         let new_epoch_start_state = epoch_store.epoch_start_state();
         let committee = new_epoch_start_state.get_narwhal_committee();
+        let commit_log = Arc::new(ConsensusCommitLog::new(
+            state.name,
+            CONSENSUS_COMMIT_LOG_CAPACITY,
+        ));
 
         let mut consensus_handler = ConsensusHandler::new(
             epoch_store,
@@ -722,7 +856,9 @@ mod tests {
             state.transaction_manager().clone(),
             state.db(),
             Arc::new(ArcSwap::default()),
+            HashSet::new(),
             committee.clone(),
+            commit_log.clone(),
             Arc::new(AuthorityMetrics::new(&Registry::new())),
         );
 
@@ -793,6 +929,14 @@ mod tests {
             let last_seen_2 = consensus_handler.last_seen.clone();
             assert_eq!(last_seen_1, last_seen_2);
         }
+
+        // AND the commit is only recorded once
+        let commits = commit_log.last(10);
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].round, 5);
+        assert_eq!(commits[0].sub_dag_index, 10);
+        assert_eq!(commits[0].num_certificates, certificates.len());
+        assert_eq!(commits[0].num_transactions, transactions.len());
     }
 
     #[test]
@@ -884,6 +1028,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_audit_consensus_ordering() {
+        let v = || {
+            vec![
+                user_txn(42),
+                cap_txn(10),
+                user_txn(100),
+                user_txn(42),
+                cap_txn(1),
+            ]
+        };
+        let transactions: Vec<_> = v()
+            .into_iter()
+            .map(|t| match t.0.transaction {
+                SequencedConsensusTransactionKind::External(ext) => ext,
+                SequencedConsensusTransactionKind::System(_) => unreachable!(),
+            })
+            .collect();
+
+        let report =
+            audit_consensus_ordering(ConsensusTransactionOrdering::ByGasPrice, &transactions);
+        assert_eq!(
+            report.iter().map(|e| e.input_index).collect::<Vec<_>>(),
+            vec![1, 4, 2, 0, 3]
+        );
+        assert_eq!(
+            report.iter().map(|e| e.gas_price).collect::<Vec<_>>(),
+            vec![None, None, Some(100), Some(42), Some(42)]
+        );
+        assert_eq!(
+            report
+                .iter()
+                .map(|e| e.tied_with_previous)
+                .collect::<Vec<_>>(),
+            vec![false, true, false, false, true]
+        );
+
+        // The report must match the order the handler actually sequences transactions in.
+        let mut sequenced = v();
+        order_by_gas_price(&mut sequenced);
+        let mut audited = v().into_iter().map(Some).collect::<Vec<_>>();
+        let audited = report
+            .iter()
+            .map(|e| audited[e.input_index].take().unwrap())
+            .collect();
+        assert_eq!(extract(sequenced), extract(audited));
+
+        // Without ordering, transactions stay in consensus order, each tied with the previous.
+        let report = audit_consensus_ordering(ConsensusTransactionOrdering::None, &transactions);
+        assert_eq!(
+            report.iter().map(|e| e.input_index).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            report
+                .iter()
+                .map(|e| e.tied_with_previous)
+                .collect::<Vec<_>>(),
+            vec![false, true, true, true, true]
+        );
+    }
+
     fn extract(v: Vec<VerifiedSequencedConsensusTransaction>) -> Vec<String> {
         v.into_iter().map(extract_one).collect()
     }
@@ -0,0 +1,135 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+use sui_config::node::DryRunCacheConfig;
+use sui_json_rpc_types::{DevInspectResults, DryRunTransactionBlockResponse};
+use sui_types::base_types::{EpochId, ObjectID, ObjectRef, TransactionDigest};
+use sui_types::effects::TransactionEffects;
+use sui_types::object::Object;
+use sui_types::storage::WriteKind;
+
+/// What `AuthorityState::dry_exec_transaction` returns.
+pub type DryRunResult = (
+    DryRunTransactionBlockResponse,
+    BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+    TransactionEffects,
+    Option<ObjectID>,
+);
+
+/// Results of dry runs and dev-inspects on a fullnode, so that simulating the same transaction
+/// again (as gas estimation tends to do) does not execute it again. Results are only reused in the
+/// epoch they were produced in, and for at most the configured time to live.
+pub struct DryRunCache {
+    pub(crate) dry_runs: ResultCache<DryRunResult>,
+    pub(crate) dev_inspects: ResultCache<DevInspectResults>,
+}
+
+pub(crate) struct ResultCache<T> {
+    ttl: Duration,
+    entries: Mutex<LruCache<TransactionDigest, Entry<T>>>,
+}
+
+struct Entry<T> {
+    epoch: EpochId,
+    inserted_at: Instant,
+    result: T,
+}
+
+impl DryRunCache {
+    /// Returns `None` if the configured capacity is zero.
+    pub fn new(config: &DryRunCacheConfig) -> Option<Self> {
+        let capacity = NonZeroUsize::new(config.capacity)?;
+        let ttl = Duration::from_millis(config.ttl_ms);
+        Some(Self {
+            dry_runs: ResultCache::new(capacity, ttl),
+            dev_inspects: ResultCache::new(capacity, ttl),
+        })
+    }
+
+    /// Drops all results, when the epoch they were produced in ends.
+    pub fn clear(&self) {
+        self.dry_runs.clear();
+        self.dev_inspects.clear();
+    }
+}
+
+impl<T: Clone> ResultCache<T> {
+    fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub(crate) fn get(&self, digest: &TransactionDigest, epoch: EpochId) -> Option<T> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get(digest)?;
+        if entry.epoch == epoch && entry.inserted_at.elapsed() < self.ttl {
+            return Some(entry.result.clone());
+        }
+        entries.pop(digest);
+        None
+    }
+
+    pub(crate) fn insert(&self, digest: TransactionDigest, epoch: EpochId, result: T) {
+        self.entries.lock().put(
+            digest,
+            Entry {
+                epoch,
+                inserted_at: Instant::now(),
+                result,
+            },
+        );
+    }
+
+    fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_expire_and_are_scoped_to_epoch() {
+        let ttl = Duration::from_millis(50);
+        let cache = ResultCache::new(NonZeroUsize::new(2).unwrap(), ttl);
+        let digest = TransactionDigest::random();
+        cache.insert(digest, 1, "result");
+
+        assert_eq!(cache.get(&digest, 1), Some("result"));
+        assert_eq!(cache.get(&TransactionDigest::random(), 1), None);
+
+        // A result from another epoch is dropped.
+        assert_eq!(cache.get(&digest, 2), None);
+        assert_eq!(cache.get(&digest, 1), None);
+
+        cache.insert(digest, 1, "result");
+        std::thread::sleep(ttl);
+        assert_eq!(cache.get(&digest, 1), None);
+    }
+
+    #[test]
+    fn test_least_recently_used_result_is_evicted() {
+        let cache = ResultCache::new(NonZeroUsize::new(2).unwrap(), Duration::from_secs(60));
+        let (a, b, c) = (
+            TransactionDigest::random(),
+            TransactionDigest::random(),
+            TransactionDigest::random(),
+        );
+        cache.insert(a, 1, 'a');
+        cache.insert(b, 1, 'b');
+        assert_eq!(cache.get(&a, 1), Some('a'));
+        cache.insert(c, 1, 'c');
+
+        assert_eq!(cache.get(&a, 1), Some('a'));
+        assert_eq!(cache.get(&b, 1), None);
+        assert_eq!(cache.get(&c, 1), Some('c'));
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use mysten_metrics::spawn_monitored_task;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEvents};
+use tokio::sync::{broadcast, mpsc};
+
+#[cfg(test)]
+#[path = "unit_tests/effects_notifier_tests.rs"]
+mod effects_notifier_tests;
+
+/// Most transactions published to subscribers in a single batch.
+pub const EFFECTS_NOTIFIER_MAX_BATCH_SIZE: usize = 256;
+
+/// Number of batches a subscriber can fall behind by before it starts missing batches.
+pub const EFFECTS_NOTIFIER_CAPACITY: usize = 1024;
+
+/// A transaction that was committed to the store, along with its outputs.
+#[derive(Clone, Debug)]
+pub struct ExecutedTransaction {
+    pub digest: TransactionDigest,
+    pub effects: TransactionEffects,
+    pub events: TransactionEvents,
+}
+
+pub type ExecutedTransactionBatch = Arc<Vec<ExecutedTransaction>>;
+
+/// Publishes every transaction committed by the authority to in-process subscribers, so that
+/// services running alongside it can follow execution without polling the store.
+///
+/// Transactions are published in batches: whatever has been queued since the last batch was sent
+/// goes into the next one, up to a maximum batch size. Batches keep the order transactions were
+/// queued in. A transaction is queued before any transaction that uses its outputs can run, so it
+/// is always published ahead of them, but transactions that execute concurrently can be published
+/// in a different order from the one they were committed in.
+///
+/// At most a batch's worth of transactions is queued at a time. Queueing waits for the batching
+/// task to catch up, which never waits on subscribers: a subscriber that falls too far behind
+/// misses batches instead, and is told how many it missed the next time it receives (see
+/// [`broadcast::error::RecvError::Lagged`]).
+pub struct EffectsNotifier {
+    pending: mpsc::Sender<ExecutedTransaction>,
+    batches: broadcast::Sender<ExecutedTransactionBatch>,
+}
+
+impl EffectsNotifier {
+    pub fn new(max_batch_size: usize, capacity: usize) -> Self {
+        let (pending, rx) = mpsc::channel(max_batch_size);
+        let (batches, _) = broadcast::channel(capacity);
+        spawn_monitored_task!(batch_and_publish(rx, batches.clone(), max_batch_size));
+        Self { pending, batches }
+    }
+
+    /// Whether anyone is listening. Callers can use this to avoid building notifications that
+    /// nobody would receive.
+    pub fn has_subscribers(&self) -> bool {
+        self.batches.receiver_count() > 0
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ExecutedTransactionBatch> {
+        self.batches.subscribe()
+    }
+
+    /// Queue a committed transaction to be published in the next batch. Must be called before
+    /// transactions that use the transaction's outputs can be scheduled, for them to be published
+    /// after it.
+    pub async fn notify(
+        &self,
+        digest: TransactionDigest,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+    ) {
+        // Only fails if the batching task has stopped, in which case there is no one to notify.
+        let _ = self
+            .pending
+            .send(ExecutedTransaction {
+                digest,
+                effects,
+                events,
+            })
+            .await;
+    }
+}
+
+impl Default for EffectsNotifier {
+    fn default() -> Self {
+        Self::new(EFFECTS_NOTIFIER_MAX_BATCH_SIZE, EFFECTS_NOTIFIER_CAPACITY)
+    }
+}
+
+async fn batch_and_publish(
+    mut rx: mpsc::Receiver<ExecutedTransaction>,
+    batches: broadcast::Sender<ExecutedTransactionBatch>,
+    max_batch_size: usize,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        while batch.len() < max_batch_size {
+            match rx.try_recv() {
+                Ok(tx) => batch.push(tx),
+                Err(_) => break,
+            }
+        }
+
+        // Fails only if there are no subscribers, in which case the batch is dropped.
+        let _ = batches.send(Arc::new(batch));
+    }
+}
@@ -54,6 +54,25 @@ impl CommitteeStore {
         store
     }
 
+    /// Opens a committee store that has already been initialized, e.g. one in a db checkpoint.
+    pub fn open_existing(path: PathBuf, db_options: Option<Options>) -> Self {
+        let tables = CommitteeStoreTables::open_tables_read_write(
+            path,
+            MetricConf::default(),
+            db_options,
+            None,
+        );
+        let store = Self {
+            tables,
+            cache: RwLock::new(HashMap::new()),
+        };
+        assert!(
+            !store.database_is_empty(),
+            "Committee store must be initialized"
+        );
+        store
+    }
+
     pub fn new_for_testing(genesis_committee: &Committee) -> Self {
         let dir = std::env::temp_dir();
         let path = dir.join(format!("DB_{:?}", nondeterministic!(ObjectID::random())));
@@ -1,8 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
+use parking_lot::Mutex;
+use prometheus::{
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, IntGauge, IntGaugeVec,
+    Registry,
+};
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sui_types::committee::EpochId;
+
+/// Number of reconfigurations that `ReconfigTimeline` remembers.
+const RECONFIG_TIMELINE_LENGTH: usize = 10;
 
 pub struct EpochMetrics {
     /// The current epoch ID. This is updated only when the AuthorityState finishes reconfiguration.
@@ -76,6 +87,101 @@ pub struct EpochMetrics {
 
     /// Buffer stake current in effect for this epoch
     pub effective_buffer_stake: IntGauge,
+
+    /// Unix timestamp in milliseconds at which the latest reconfiguration reached each stage,
+    /// labelled by stage (see `ReconfigStage`).
+    pub epoch_reconfig_stage_timestamp_ms: IntGaugeVec,
+
+    /// The stages that recent reconfigurations reached and when, for the admin API.
+    pub reconfig_timeline: ReconfigTimeline,
+}
+
+/// The stages that a node goes through to reconfigure from one epoch to the next, in order. Their
+/// timestamps attribute slow reconfigurations to a stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReconfigStage {
+    /// The certified last checkpoint of the ending epoch was scheduled for execution.
+    LastCheckpointCertified,
+    /// The last checkpoint of the ending epoch, including its change epoch transaction, was
+    /// executed and finalized.
+    EpochEnded,
+    /// The epoch store for the new epoch, with its committee, was created.
+    NewCommitteeFormed,
+    /// Consensus output was first handled in the new epoch.
+    FirstCommit,
+}
+
+impl ReconfigStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReconfigStage::LastCheckpointCertified => "last_checkpoint_certified",
+            ReconfigStage::EpochEnded => "epoch_ended",
+            ReconfigStage::NewCommitteeFormed => "new_committee_formed",
+            ReconfigStage::FirstCommit => "first_commit",
+        }
+    }
+}
+
+/// When the reconfiguration to `epoch` reached each of its stages, as Unix timestamps in
+/// milliseconds.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ReconfigTimestamps {
+    pub epoch: EpochId,
+    pub last_checkpoint_certified_ms: Option<u64>,
+    pub epoch_ended_ms: Option<u64>,
+    pub new_committee_formed_ms: Option<u64>,
+    pub first_commit_ms: Option<u64>,
+}
+
+impl ReconfigTimestamps {
+    fn stage_mut(&mut self, stage: ReconfigStage) -> &mut Option<u64> {
+        match stage {
+            ReconfigStage::LastCheckpointCertified => &mut self.last_checkpoint_certified_ms,
+            ReconfigStage::EpochEnded => &mut self.epoch_ended_ms,
+            ReconfigStage::NewCommitteeFormed => &mut self.new_committee_formed_ms,
+            ReconfigStage::FirstCommit => &mut self.first_commit_ms,
+        }
+    }
+}
+
+/// The timestamps of the most recent reconfigurations, oldest first. Each stage is only recorded
+/// the first time it is reached for an epoch, so stages can be reported repeatedly.
+#[derive(Default)]
+pub struct ReconfigTimeline {
+    reconfigs: Mutex<VecDeque<ReconfigTimestamps>>,
+}
+
+impl ReconfigTimeline {
+    /// Records that the reconfiguration to `epoch` reached `stage` now, returning the timestamp,
+    /// or `None` if the stage was already recorded, or the reconfiguration is too old to remember.
+    pub fn record(&self, epoch: EpochId, stage: ReconfigStage) -> Option<u64> {
+        let mut reconfigs = self.reconfigs.lock();
+        if reconfigs.back().map_or(true, |last| last.epoch < epoch) {
+            if reconfigs.len() == RECONFIG_TIMELINE_LENGTH {
+                reconfigs.pop_front();
+            }
+            reconfigs.push_back(ReconfigTimestamps {
+                epoch,
+                ..Default::default()
+            });
+        }
+
+        let timestamps = reconfigs.iter_mut().find(|r| r.epoch == epoch)?;
+        let timestamp = timestamps.stage_mut(stage);
+        if timestamp.is_some() {
+            return None;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64;
+        *timestamp = Some(now);
+        Some(now)
+    }
+
+    pub fn reconfigs(&self) -> Vec<ReconfigTimestamps> {
+        self.reconfigs.lock().iter().cloned().collect()
+    }
 }
 
 impl EpochMetrics {
@@ -158,7 +264,57 @@ impl EpochMetrics {
                 "Buffer stake current in effect for this epoch",
                 registry,
             ).unwrap(),
+            epoch_reconfig_stage_timestamp_ms: register_int_gauge_vec_with_registry!(
+                "epoch_reconfig_stage_timestamp_ms",
+                "Unix timestamp in milliseconds at which the latest reconfiguration reached each stage",
+                &["stage"],
+                registry,
+            ).unwrap(),
+            reconfig_timeline: ReconfigTimeline::default(),
         };
         Arc::new(this)
     }
+
+    /// Records that the reconfiguration to `epoch` reached `stage`, the first time it does.
+    pub fn record_reconfig_stage(&self, epoch: EpochId, stage: ReconfigStage) {
+        if let Some(timestamp_ms) = self.reconfig_timeline.record(epoch, stage) {
+            self.epoch_reconfig_stage_timestamp_ms
+                .with_label_values(&[stage.as_str()])
+                .set(timestamp_ms as i64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconfig_timeline() {
+        let timeline = ReconfigTimeline::default();
+        assert!(timeline
+            .record(1, ReconfigStage::LastCheckpointCertified)
+            .is_some());
+        // Stages are only recorded the first time they are reached.
+        assert!(timeline
+            .record(1, ReconfigStage::LastCheckpointCertified)
+            .is_none());
+        assert!(timeline.record(1, ReconfigStage::FirstCommit).is_some());
+
+        let reconfigs = timeline.reconfigs();
+        assert_eq!(reconfigs.len(), 1);
+        assert_eq!(reconfigs[0].epoch, 1);
+        assert!(reconfigs[0].last_checkpoint_certified_ms.is_some());
+        assert!(reconfigs[0].epoch_ended_ms.is_none());
+        assert!(reconfigs[0].first_commit_ms.is_some());
+
+        // Only the most recent reconfigurations are remembered.
+        for epoch in 2..=(RECONFIG_TIMELINE_LENGTH as u64 + 1) {
+            timeline.record(epoch, ReconfigStage::EpochEnded);
+        }
+        let reconfigs = timeline.reconfigs();
+        assert_eq!(reconfigs.len(), RECONFIG_TIMELINE_LENGTH);
+        assert_eq!(reconfigs[0].epoch, 2);
+        assert!(timeline.record(1, ReconfigStage::EpochEnded).is_none());
+    }
 }
@@ -10,9 +10,12 @@ pub mod authority_client;
 pub mod authority_server;
 pub mod checkpoints;
 pub mod consensus_adapter;
+pub mod consensus_commit_log;
 pub mod consensus_handler;
 pub mod consensus_validator;
 pub mod db_checkpoint_handler;
+pub mod dry_run_cache;
+pub mod effects_notifier;
 pub mod epoch;
 mod execution_driver;
 pub mod metrics;
@@ -31,6 +34,7 @@ pub mod test_utils;
 pub mod transaction_input_checker;
 mod transaction_manager;
 pub mod transaction_orchestrator;
+pub mod validator_qos;
 pub mod verify_indexes;
 
 #[cfg(test)]
@@ -4,10 +4,10 @@ use crate::authority::AuthorityMetrics;
 use arc_swap::ArcSwap;
 use narwhal_config::{Authority, Committee, Stake};
 use narwhal_types::ReputationScores;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use sui_types::base_types::AuthorityName;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// Updates list of authorities that are deemed to have low reputation scores by consensus
 /// these may be lagging behind the network, byzantine, or not reliably participating for any reason.
@@ -16,12 +16,15 @@ use tracing::debug;
 /// submission side with the Narwhal leader election schedule. Practically we don't want to submit
 /// transactions for sequencing to validators that have low scores and are not part of the leader
 /// schedule since the chances of getting them sequenced are lower.
+/// Authorities in `excluded_authorities` are never flagged, but their stake still counts towards
+/// the threshold, so excluding an authority never causes another one to be flagged in its place.
 pub fn update_low_scoring_authorities(
     low_scoring_authorities: Arc<ArcSwap<HashMap<AuthorityName, u64>>>,
     committee: &Committee,
     reputation_scores: ReputationScores,
     metrics: &Arc<AuthorityMetrics>,
     consensus_bad_nodes_stake_threshold: u64,
+    excluded_authorities: &HashSet<AuthorityName>,
 ) {
     assert!((0..=33).contains(&consensus_bad_nodes_stake_threshold), "The bad_nodes_stake_threshold should be in range [0 - 33], out of bounds parameter detected {}", consensus_bad_nodes_stake_threshold);
 
@@ -51,8 +54,17 @@ pub fn update_low_scoring_authorities(
         let included = if total_stake
             <= (consensus_bad_nodes_stake_threshold * committee.total_stake()) / 100 as Stake
         {
-            final_low_scoring_map.insert(authority_name, score);
-            true
+            if excluded_authorities.contains(&authority_name) {
+                info!(
+                    "authority {} has low score {} but is excluded from the low scoring authorities",
+                    authority_name.concise(),
+                    score
+                );
+                false
+            } else {
+                final_low_scoring_map.insert(authority_name, score);
+                true
+            }
         } else {
             false
         };
@@ -94,8 +106,9 @@ mod tests {
     use prometheus::Registry;
     use rand::rngs::{OsRng, StdRng};
     use rand::SeedableRng;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::sync::Arc;
+    use sui_types::base_types::AuthorityName;
 
     #[test]
     pub fn test_update_low_scoring_authorities() {
@@ -140,6 +153,7 @@ mod tests {
             reputation_scores.clone(),
             &metrics,
             consensus_bad_nodes_stake_threshold,
+            &HashSet::new(),
         );
 
         // THEN
@@ -162,6 +176,7 @@ mod tests {
             reputation_scores,
             &metrics,
             consensus_bad_nodes_stake_threshold,
+            &HashSet::new(),
         );
 
         // THEN
@@ -172,6 +187,45 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_update_low_scoring_authorities_with_exclusions() {
+        // GIVEN
+        // Total stake is 8 for this committee and every authority has equal stake = 1
+        let committee = generate_committee(8);
+
+        let authorities: Vec<_> = committee.authorities().collect();
+        let low_scoring = Arc::new(ArcSwap::from_pointee(HashMap::new()));
+        let metrics = Arc::new(AuthorityMetrics::new(&Registry::new()));
+
+        let mut scores = HashMap::new();
+        for (i, authority) in authorities.iter().enumerate() {
+            scores.insert(authority.id(), 100 * i as u64);
+        }
+        let reputation_scores = ReputationScores {
+            scores_per_authority: scores,
+            final_of_schedule: true,
+        };
+
+        // WHEN excluding the lowest scoring authority
+        let a1: AuthorityName = authorities[0].protocol_key().into();
+        let a2: AuthorityName = authorities[1].protocol_key().into();
+        let consensus_bad_nodes_stake_threshold = 33; // 33 * 8 / 100 = 2 maximum stake that will considered low scoring
+
+        update_low_scoring_authorities(
+            low_scoring.clone(),
+            &committee,
+            reputation_scores,
+            &metrics,
+            consensus_bad_nodes_stake_threshold,
+            &HashSet::from([a1]),
+        );
+
+        // THEN it is not flagged, and its stake still counts towards the threshold
+        assert_eq!(low_scoring.load().len(), 1);
+        assert!(low_scoring.load().get(&a1).is_none());
+        assert_eq!(*low_scoring.load().get(&a2).unwrap(), 100);
+    }
+
     /// Generate a random committee for the given size. It's important to create the Authorities
     /// via the committee to ensure than an AuthorityIdentifier will be assigned, as this is dynamically
     /// calculated during committee creation.
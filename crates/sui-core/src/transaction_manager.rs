@@ -10,6 +10,7 @@ use std::{
 use lru::LruCache;
 use mysten_metrics::monitored_scope;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use sui_types::executable_transaction::VerifiedExecutableTransaction;
 use sui_types::{base_types::TransactionDigest, error::SuiResult, fp_ensure};
 use sui_types::{
@@ -45,6 +46,28 @@ pub(crate) const MAX_TM_QUEUE_LENGTH: usize = 100_000;
 // is above the threshold.
 pub(crate) const MAX_PER_OBJECT_QUEUE_LENGTH: usize = 200;
 
+/// Hits and misses of the caches TransactionManager checks the availability of input objects and
+/// packages against, counted since the node started.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransactionManagerCacheStats {
+    pub object_hits: u64,
+    pub object_misses: u64,
+    pub package_hits: u64,
+    pub package_misses: u64,
+}
+
+impl TransactionManagerCacheStats {
+    /// The hits and misses counted after `earlier` was taken.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            object_hits: self.object_hits.saturating_sub(earlier.object_hits),
+            object_misses: self.object_misses.saturating_sub(earlier.object_misses),
+            package_hits: self.package_hits.saturating_sub(earlier.package_hits),
+            package_misses: self.package_misses.saturating_sub(earlier.package_misses),
+        }
+    }
+}
+
 /// TransactionManager is responsible for managing object dependencies of pending transactions,
 /// and publishing a stream of certified transactions (certificates) ready to execute.
 /// It receives certificates from Narwhal, validator RPC handlers, and checkpoint executor.
@@ -872,6 +895,15 @@ impl TransactionManager {
         inner.pending_certificates.len() + inner.executing_certificates.len()
     }
 
+    pub(crate) fn cache_stats(&self) -> TransactionManagerCacheStats {
+        TransactionManagerCacheStats {
+            object_hits: self.metrics.transaction_manager_object_cache_hits.get(),
+            object_misses: self.metrics.transaction_manager_object_cache_misses.get(),
+            package_hits: self.metrics.transaction_manager_package_cache_hits.get(),
+            package_misses: self.metrics.transaction_manager_package_cache_misses.get(),
+        }
+    }
+
     // Reconfigures the TransactionManager for a new epoch. Existing transactions will be dropped
     // because they are no longer relevant and may be incorrect in the new epoch.
     pub(crate) fn reconfigure(&self, new_epoch: EpochId) {
@@ -1193,9 +1193,15 @@ async fn test_dry_run_dev_inspect_max_gas_version() {
     );
     let transaction = to_sender_signed_transaction(data.clone(), &sender_key);
     let digest = *transaction.digest();
-    let DryRunTransactionBlockResponse { effects, .. } =
-        fullnode.dry_exec_transaction(data, digest).await.unwrap().0;
+    let DryRunTransactionBlockResponse {
+        effects,
+        command_gas_used,
+        ..
+    } = fullnode.dry_exec_transaction(data, digest).await.unwrap().0;
     assert_eq!(effects.status(), &SuiExecutionStatus::Success);
+    // The gas used by the only command, a Move call
+    assert_eq!(command_gas_used.len(), 1);
+    assert!(command_gas_used[0] > 0);
 }
 
 #[tokio::test]
@@ -0,0 +1,74 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEvents};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::timeout;
+
+use super::*;
+
+async fn notify_n(notifier: &EffectsNotifier, n: usize) -> Vec<TransactionDigest> {
+    let mut digests = vec![];
+    for _ in 0..n {
+        let digest = TransactionDigest::random();
+        notifier
+            .notify(
+                digest,
+                TransactionEffects::default(),
+                TransactionEvents::default(),
+            )
+            .await;
+        digests.push(digest);
+    }
+    digests
+}
+
+async fn recv(rx: &mut broadcast::Receiver<ExecutedTransactionBatch>) -> Vec<TransactionDigest> {
+    timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("Timed out waiting for a batch")
+        .unwrap()
+        .iter()
+        .map(|tx| tx.digest)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_batches_preserve_commit_order() {
+    let notifier = EffectsNotifier::new(4, 16);
+    let mut rx = notifier.subscribe();
+    assert!(notifier.has_subscribers());
+
+    let expected = notify_n(&notifier, 10).await;
+    let mut received = vec![];
+    while received.len() < expected.len() {
+        let batch = recv(&mut rx).await;
+        assert!(!batch.is_empty() && batch.len() <= 4, "{batch:?}");
+        received.extend(batch);
+    }
+
+    assert_eq!(expected, received);
+}
+
+#[tokio::test]
+async fn test_lagging_subscriber() {
+    let notifier = EffectsNotifier::new(1, 2);
+    let mut slow = notifier.subscribe();
+    let mut fast = notifier.subscribe();
+
+    // The fast subscriber keeps up with every batch as it is published.
+    let mut expected = vec![];
+    for _ in 0..5 {
+        let digest = notify_n(&notifier, 1).await[0];
+        assert_eq!(recv(&mut fast).await, vec![digest]);
+        expected.push(digest);
+    }
+
+    // Only the last two batches were kept for the slow subscriber.
+    assert!(matches!(slow.recv().await, Err(RecvError::Lagged(3))));
+    assert_eq!(recv(&mut slow).await, vec![expected[3]]);
+    assert_eq!(recv(&mut slow).await, vec![expected[4]]);
+}
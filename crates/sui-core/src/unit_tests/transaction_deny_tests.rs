@@ -23,8 +23,9 @@ use sui_types::error::{SuiError, SuiResult, UserInputError};
 use sui_types::execution_status::{ExecutionFailureStatus, ExecutionStatus};
 use sui_types::messages_grpc::HandleTransactionResponse;
 use sui_types::transaction::{
-    CallArg, CertifiedTransaction, Transaction, TransactionData, VerifiedCertificate,
-    VerifiedTransaction, TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+    CallArg, CertifiedTransaction, Transaction, TransactionData, TransactionDataAPI,
+    TransactionExpiration, VerifiedCertificate, VerifiedTransaction,
+    TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
 };
 use sui_types::utils::{
     make_zklogin_tx, to_sender_signed_transaction, to_sender_signed_transaction_with_multi_signers,
@@ -478,3 +479,50 @@ async fn test_certificate_deny() {
         }
     ));
 }
+
+#[tokio::test]
+async fn test_max_expiration_epochs() {
+    let (network_config, state) = setup_test(
+        TransactionDenyConfigBuilder::new()
+            .max_expiration_epochs(2)
+            .expiration_epoch_skew(1)
+            .build(),
+    )
+    .await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let (sender, key, gas_objects) = &accounts[0];
+    let rgp = state.reference_gas_price_for_testing().unwrap();
+
+    let transfer_expiring_at = |gas: ObjectRef, epoch| {
+        let mut data = TransactionData::new_transfer_sui(
+            *sender,
+            *sender,
+            None,
+            gas,
+            TEST_ONLY_GAS_UNIT_FOR_TRANSFER * rgp,
+            rgp,
+        );
+        *data.expiration_mut_for_testing() = TransactionExpiration::Epoch(epoch);
+        let tx = to_sender_signed_transaction(data, key);
+        state.verify_transaction(tx).unwrap()
+    };
+
+    // The latest accepted expiration is the current epoch (0), plus two epochs, plus one epoch of
+    // skew.
+    let epoch_store = state.epoch_store_for_testing();
+    let tx = transfer_expiring_at(gas_objects[0], 3);
+    assert!(state.handle_transaction(&epoch_store, tx).await.is_ok());
+
+    let tx = transfer_expiring_at(gas_objects[1], 4);
+    let result = state.handle_transaction(&epoch_store, tx).await;
+    assert!(
+        matches!(
+            result,
+            Err(SuiError::TransactionExpirationTooFar {
+                expiration: 4,
+                max_expiration: 3,
+            })
+        ),
+        "{result:?}"
+    );
+}
@@ -0,0 +1,98 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::num::NonZeroU32;
+
+use sui_config::qos_config::QosClassConfig;
+
+use super::*;
+
+const OWN_FULLNODE: &str = "10.0.0.1:8080";
+const PARTNER: &str = "10.0.0.2:8080";
+const PUBLIC: &str = "10.0.0.3:8080";
+
+fn addr(addr: &str) -> Option<SocketAddr> {
+    Some(addr.parse().unwrap())
+}
+
+fn ip(addr: &str) -> IpAddr {
+    addr.parse::<SocketAddr>().unwrap().ip()
+}
+
+fn config() -> QosClassesConfig {
+    QosClassesConfig {
+        own_fullnodes: QosClassConfig {
+            clients: vec![ip(OWN_FULLNODE)],
+            ..Default::default()
+        },
+        partners: QosClassConfig {
+            clients: vec![ip(PARTNER)],
+            ..Default::default()
+        },
+        public: QosClassConfig::default(),
+    }
+}
+
+#[test]
+fn test_classify() {
+    let qos = ValidatorQos::new(config(), ValidatorQosMetrics::new_for_tests());
+    assert_eq!(qos.classify(addr(OWN_FULLNODE)), QosClass::OwnFullnode);
+    assert_eq!(qos.classify(addr(PARTNER)), QosClass::Partner);
+    assert_eq!(qos.classify(addr(PUBLIC)), QosClass::Public);
+    assert_eq!(qos.classify(None), QosClass::Public);
+}
+
+#[tokio::test]
+async fn test_rate_budgets_are_per_class() {
+    let mut config = config();
+    config.public.requests_per_second = NonZeroU32::new(1);
+    let qos = ValidatorQos::new(config, ValidatorQosMetrics::new_for_tests());
+
+    qos.admit(addr(PUBLIC)).await.unwrap();
+    let err = qos.admit(addr(PUBLIC)).await.err().unwrap();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+    // Partners are unaffected by public clients exhausting their budget.
+    for _ in 0..10 {
+        qos.admit(addr(PARTNER)).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_queue_budget() {
+    let mut config = config();
+    config.partners.max_concurrent_requests = 1;
+    config.partners.max_queued_requests = 1;
+    let qos = Arc::new(ValidatorQos::new(
+        config,
+        ValidatorQosMetrics::new_for_tests(),
+    ));
+
+    let permit = qos.admit(addr(PARTNER)).await.unwrap();
+
+    // The second request waits for the first one to finish, and the third has nowhere to wait.
+    let queued = tokio::spawn({
+        let qos = qos.clone();
+        async move { qos.admit(addr(PARTNER)).await.map(|_| ()) }
+    });
+    tokio::task::yield_now().await;
+    let err = qos.admit(addr(PARTNER)).await.err().unwrap();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+    // Other classes are admitted in the meantime.
+    qos.admit(addr(OWN_FULLNODE)).await.unwrap();
+
+    drop(permit);
+    queued.await.unwrap().unwrap();
+}
+
+#[test]
+fn test_reload() {
+    let qos = ValidatorQos::new(config(), ValidatorQosMetrics::new_for_tests());
+    assert_eq!(qos.classify(addr(PUBLIC)), QosClass::Public);
+
+    let mut config = config();
+    config.partners.clients.push(ip(PUBLIC));
+    qos.reload(config);
+    assert_eq!(qos.classify(addr(PUBLIC)), QosClass::Partner);
+}
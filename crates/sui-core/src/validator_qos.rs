@@ -0,0 +1,269 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use mysten_metrics::spawn_monitored_task;
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntCounterVec,
+    IntGaugeVec, Registry,
+};
+use sui_config::qos_config::{QosClassConfig, QosClassesConfig};
+use sui_config::Config;
+use sui_network::tonic;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+#[cfg(test)]
+#[path = "unit_tests/validator_qos_tests.rs"]
+mod validator_qos_tests;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QosClass {
+    OwnFullnode,
+    Partner,
+    Public,
+}
+
+impl QosClass {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QosClass::OwnFullnode => "own_fullnode",
+            QosClass::Partner => "partner",
+            QosClass::Public => "public",
+        }
+    }
+}
+
+pub struct ValidatorQosMetrics {
+    admitted_requests: IntCounterVec,
+    rejected_requests: IntCounterVec,
+    queued_requests: IntGaugeVec,
+}
+
+impl ValidatorQosMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            admitted_requests: register_int_counter_vec_with_registry!(
+                "validator_qos_admitted_requests",
+                "Number of requests admitted, by class of client",
+                &["class"],
+                registry,
+            )
+            .unwrap(),
+            rejected_requests: register_int_counter_vec_with_registry!(
+                "validator_qos_rejected_requests",
+                "Number of requests rejected, by class of client and the budget they exceeded",
+                &["class", "reason"],
+                registry,
+            )
+            .unwrap(),
+            queued_requests: register_int_gauge_vec_with_registry!(
+                "validator_qos_queued_requests",
+                "Number of requests waiting to be admitted, by class of client",
+                &["class"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn new_for_tests() -> Self {
+        Self::new(&Registry::new())
+    }
+}
+
+/// The budget requests from one class of client are admitted against.
+struct ClassBudget {
+    concurrency: Arc<Semaphore>,
+    max_queued: usize,
+    queued: AtomicUsize,
+    rate: Option<DefaultDirectRateLimiter>,
+}
+
+impl ClassBudget {
+    fn new(config: &QosClassConfig) -> Self {
+        Self {
+            concurrency: Arc::new(Semaphore::new(config.max_concurrent_requests)),
+            max_queued: config.max_queued_requests,
+            queued: AtomicUsize::new(0),
+            rate: config
+                .requests_per_second
+                .map(|rps| RateLimiter::direct(Quota::per_second(rps))),
+        }
+    }
+}
+
+struct QosPolicy {
+    config: QosClassesConfig,
+    classes: HashMap<IpAddr, QosClass>,
+    own_fullnodes: ClassBudget,
+    partners: ClassBudget,
+    public: ClassBudget,
+}
+
+impl QosPolicy {
+    fn new(config: QosClassesConfig) -> Self {
+        // A client listed in both classes is treated as one of the operator's own fullnodes.
+        let classes = config
+            .partners
+            .clients
+            .iter()
+            .map(|ip| (*ip, QosClass::Partner))
+            .chain(
+                config
+                    .own_fullnodes
+                    .clients
+                    .iter()
+                    .map(|ip| (*ip, QosClass::OwnFullnode)),
+            )
+            .collect();
+        Self {
+            classes,
+            own_fullnodes: ClassBudget::new(&config.own_fullnodes),
+            partners: ClassBudget::new(&config.partners),
+            public: ClassBudget::new(&config.public),
+            config,
+        }
+    }
+
+    fn classify(&self, remote_addr: Option<SocketAddr>) -> QosClass {
+        remote_addr
+            .and_then(|addr| self.classes.get(&addr.ip()).copied())
+            .unwrap_or(QosClass::Public)
+    }
+
+    fn budget(&self, class: QosClass) -> &ClassBudget {
+        match class {
+            QosClass::OwnFullnode => &self.own_fullnodes,
+            QosClass::Partner => &self.partners,
+            QosClass::Public => &self.public,
+        }
+    }
+}
+
+/// Held while a request is being handled, to count it against its class's concurrency budget.
+pub struct QosPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Admits requests to the validator's gRPC front-end against a separate budget per class of
+/// client (see [`QosClassesConfig`]): a rate at which requests are admitted, a number of requests
+/// handled at once, and a number of requests waiting for their turn. Requests exceeding any of
+/// them are rejected with `RESOURCE_EXHAUSTED`, and only count against their own class.
+///
+/// Clients are classified by the IP address of their connection. Requests whose address is not
+/// known are public.
+pub struct ValidatorQos {
+    policy: ArcSwap<QosPolicy>,
+    metrics: ValidatorQosMetrics,
+}
+
+impl ValidatorQos {
+    pub fn new(config: QosClassesConfig, metrics: ValidatorQosMetrics) -> Self {
+        Self {
+            policy: ArcSwap::from_pointee(QosPolicy::new(config)),
+            metrics,
+        }
+    }
+
+    pub fn classify(&self, remote_addr: Option<SocketAddr>) -> QosClass {
+        self.policy.load().classify(remote_addr)
+    }
+
+    /// Replaces the classes and budgets that requests are admitted against. Budgets start afresh,
+    /// but requests that were admitted before the reload keep counting against the concurrency
+    /// budget they were admitted against until they finish.
+    pub fn reload(&self, config: QosClassesConfig) {
+        if self.policy.load().config == config {
+            return;
+        }
+        self.policy.store(Arc::new(QosPolicy::new(config)));
+        info!("Reloaded validator QoS classes");
+    }
+
+    /// Reads the classes from `path` every `interval`, and reloads them if they changed. A file
+    /// that can't be read leaves the current classes in place.
+    pub fn spawn_reloader(self: &Arc<Self>, path: PathBuf, interval: Duration) {
+        let qos = Arc::downgrade(self);
+        spawn_monitored_task!(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(qos) = qos.upgrade() else {
+                    return;
+                };
+                match QosClassesConfig::load(&path) {
+                    Ok(config) => qos.reload(config),
+                    Err(e) => warn!(
+                        "Failed to reload validator QoS classes from {}: {e:?}",
+                        path.display()
+                    ),
+                }
+            }
+        });
+    }
+
+    /// Waits until a request from `remote_addr` can be handled within its class's budget, or
+    /// rejects it if it exceeds that budget.
+    pub async fn admit(&self, remote_addr: Option<SocketAddr>) -> Result<QosPermit, tonic::Status> {
+        let policy = self.policy.load_full();
+        let class = policy.classify(remote_addr);
+        let label = class.as_str();
+        let budget = policy.budget(class);
+
+        if let Some(rate) = &budget.rate {
+            if rate.check().is_err() {
+                return Err(self.reject(class, "rate"));
+            }
+        }
+
+        let permit = match budget.concurrency.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                if budget.queued.fetch_add(1, Ordering::SeqCst) >= budget.max_queued {
+                    budget.queued.fetch_sub(1, Ordering::SeqCst);
+                    return Err(self.reject(class, "queue"));
+                }
+                let queued = self.metrics.queued_requests.with_label_values(&[label]);
+                queued.inc();
+                // Leave the queue even if the client gives up while waiting.
+                let _dequeue = scopeguard::guard(queued, |queued| {
+                    queued.dec();
+                    budget.queued.fetch_sub(1, Ordering::SeqCst);
+                });
+                budget
+                    .concurrency
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("Admission semaphores are never closed")
+            }
+        };
+
+        self.metrics
+            .admitted_requests
+            .with_label_values(&[label])
+            .inc();
+        Ok(QosPermit { _permit: permit })
+    }
+
+    fn reject(&self, class: QosClass, reason: &str) -> tonic::Status {
+        self.metrics
+            .rejected_requests
+            .with_label_values(&[class.as_str(), reason])
+            .inc();
+        tonic::Status::resource_exhausted(format!(
+            "Too many requests from {} clients",
+            class.as_str()
+        ))
+    }
+}
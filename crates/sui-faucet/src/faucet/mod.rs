@@ -112,6 +112,11 @@ pub struct FaucetConfig {
     #[clap(long, default_value_t = 300)]
     pub ttl_expiration: u64,
 
+    /// Number of gas coins to keep in the pool used for batched requests. When fewer are
+    /// available, a coin from the pool is split to top it back up.
+    #[clap(long, default_value_t = 10)]
+    pub batch_gas_pool_size: usize,
+
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
 }
@@ -131,6 +136,7 @@ impl Default for FaucetConfig {
             max_request_queue_length: 10000,
             batch_request_size: 500,
             ttl_expiration: 300,
+            batch_gas_pool_size: 10,
             batch_enabled: false,
         }
     }
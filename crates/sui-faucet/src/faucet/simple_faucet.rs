@@ -61,6 +61,7 @@ pub struct SimpleFaucet {
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
     ttl_expiration: u64,
     coin_amount: u64,
+    batch_gas_pool_size: usize,
     /// Shuts down the batch transfer task. Used only in testing.
     #[allow(unused)]
     batch_transfer_shutdown: parking_lot::Mutex<Option<oneshot::Sender<()>>>,
@@ -77,6 +78,7 @@ impl fmt::Debug for SimpleFaucet {
             .field("batch_request_size", &self.batch_request_size)
             .field("ttl_expiration", &self.ttl_expiration)
             .field("coin_amount", &self.coin_amount)
+            .field("batch_gas_pool_size", &self.batch_gas_pool_size)
             .finish()
     }
 }
@@ -122,7 +124,9 @@ impl SimpleFaucet {
         let mut pending = vec![];
 
         let (producer, consumer) = mpsc::channel(coins.len());
-        let (batch_producer, batch_consumer) = mpsc::channel(coins.len());
+        // The batch pool can grow up to its configured size by splitting coins.
+        let (batch_producer, batch_consumer) =
+            mpsc::channel(coins.len().max(config.batch_gas_pool_size));
 
         let (sender, mut receiver) =
             mpsc::channel::<(Uuid, SuiAddress, Vec<u64>)>(config.max_request_queue_length as usize);
@@ -188,6 +192,7 @@ impl SimpleFaucet {
             task_id_cache: TtlCache::new(config.max_request_per_second as usize * 60 * 10).into(),
             ttl_expiration: config.ttl_expiration,
             coin_amount: config.amount,
+            batch_gas_pool_size: config.batch_gas_pool_size,
             batch_transfer_shutdown: parking_lot::Mutex::new(Some(batch_transfer_shutdown)),
         };
 
@@ -570,6 +575,89 @@ impl SimpleFaucet {
         info!(?uuid, ?coin_id, "Recycled coin");
     }
 
+    /// Number of coins currently waiting in the batch gas pool.
+    async fn batch_gas_pool_len(&self) -> usize {
+        let batch_producer = self.batch_producer.lock().await;
+        batch_producer.max_capacity() - batch_producer.capacity()
+    }
+
+    /// Tops the batch gas pool back up to `batch_gas_pool_size` coins, by splitting the next coin
+    /// in the pool into coins of equal balance. Coins are only split as long as each part can still
+    /// pay for a full batch, so the pool may stay below its target size.
+    async fn replenish_batch_gas_pool(&self) -> Result<(), FaucetError> {
+        let available = self.batch_gas_pool_len().await;
+        if available >= self.batch_gas_pool_size {
+            return Ok(());
+        }
+
+        let uuid = Uuid::new_v4();
+        let gas_cost = self.get_gas_cost().await?;
+        let Some(coin_id) = self.pop_gas_coin_for_batch(uuid).await else {
+            return Err(FaucetError::NoGasCoinAvailable);
+        };
+        let balance = match self.get_gas_coin_and_check_faucet_owner(coin_id).await {
+            Ok(Some(gas_coin)) => gas_coin.value(),
+            Ok(None) => {
+                warn!(?uuid, ?coin_id, "Invalid, removing from pool");
+                self.metrics.total_discarded_coins.inc();
+                return Ok(());
+            }
+            Err(e) => {
+                self.recycle_gas_coin_for_batch(coin_id, uuid).await;
+                return Err(FaucetError::FullnodeReadingError(format!(
+                    "unknown gas coin {coin_id:?}: {e:?}"
+                )));
+            }
+        };
+
+        // Every part, including what is left in the original coin, has to cover a full batch.
+        let min_balance = self.coin_amount * self.batch_request_size + gas_cost;
+        let splits = (self.batch_gas_pool_size - available)
+            .min((balance.saturating_sub(gas_cost) / min_balance).saturating_sub(1) as usize);
+        if splits == 0 {
+            info!(?uuid, ?coin_id, balance, "Coin is too small to split");
+            self.recycle_gas_coin_for_batch(coin_id, uuid).await;
+            return Ok(());
+        }
+
+        let share = (balance - gas_cost) / (splits as u64 + 1);
+        info!(
+            ?uuid,
+            ?coin_id,
+            splits,
+            share,
+            "Splitting coin to replenish batch gas pool"
+        );
+        let tx_data = self
+            .build_split_coin_txn(coin_id, splits, share, gas_cost)
+            .await
+            .map_err(FaucetError::internal)?;
+        {
+            let mut wal = self.wal.lock().await;
+            wal.reserve(uuid, coin_id, self.active_address, tx_data.clone())
+                .map_err(FaucetError::internal)?;
+        }
+        let response = self
+            .sign_and_execute_txn(uuid, self.active_address, coin_id, tx_data, true)
+            .await?;
+
+        let created = response
+            .effects
+            .ok_or_else(|| {
+                FaucetError::ParseTransactionResponseError(format!(
+                    "effects field missing for txn {}",
+                    response.digest
+                ))
+            })?
+            .created()
+            .to_vec();
+        for coin in created {
+            self.recycle_gas_coin_for_batch(coin.object_id(), uuid)
+                .await;
+        }
+        Ok(())
+    }
+
     async fn execute_pay_sui_txn_with_retries(
         &self,
         tx: &Transaction,
@@ -716,13 +804,17 @@ impl SimpleFaucet {
     ) -> Result<TransactionData, anyhow::Error> {
         let gas_payment = self.wallet.get_object_ref(coin_id).await?;
         let gas_price = self.wallet.get_reference_gas_price().await?;
-        // TODO (Jian): change to make this more efficient by changing impl to one Splitcoin, and many TransferObjects
+        // All the coins of the batch are split off the gas coin in a single SplitCoins, followed by
+        // one TransferObjects per recipient.
+        let (recipients, amounts): (Vec<_>, Vec<_>) = batch_requests
+            .into_iter()
+            .flat_map(|(_uuid, recipient, amounts)| {
+                amounts.into_iter().map(move |amount| (recipient, amount))
+            })
+            .unzip();
         let pt = {
             let mut builder = ProgrammableTransactionBuilder::new();
-            for (_uuid, recipient, amounts) in batch_requests {
-                let recipients = vec![recipient; amounts.len()];
-                builder.pay_sui(recipients, amounts)?;
-            }
+            builder.pay_sui(recipients, amounts)?;
             builder.finish()
         };
 
@@ -735,6 +827,30 @@ impl SimpleFaucet {
         ))
     }
 
+    async fn build_split_coin_txn(
+        &self,
+        coin_id: ObjectID,
+        splits: usize,
+        amount: u64,
+        budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas_payment = self.wallet.get_object_ref(coin_id).await?;
+        let gas_price = self.wallet.get_reference_gas_price().await?;
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.pay_sui(vec![self.active_address; splits], vec![amount; splits])?;
+            builder.finish()
+        };
+
+        Ok(TransactionData::new_programmable(
+            self.active_address,
+            vec![gas_payment],
+            pt,
+            budget,
+            gas_price,
+        ))
+    }
+
     async fn check_and_map_batch_transfer_gas_result(
         &self,
         res: SuiTransactionBlockResponse,
@@ -1000,6 +1116,10 @@ pub async fn batch_transfer_gases(
         info!("Batch timeout elapsed while waiting.");
     };
 
+    if let Err(e) = faucet.replenish_batch_gas_pool().await {
+        warn!("Failed to replenish batch gas pool: {e:?}");
+    }
+
     let total_requests = requests.len();
     let gas_cost = faucet.get_gas_cost().await?;
     // The UUID here is for the batched request
@@ -1879,6 +1999,66 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_batch_gas_pool_is_replenished() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let config = FaucetConfig {
+            batch_request_size: 1,
+            batch_gas_pool_size: 12,
+            ..Default::default()
+        };
+        let address = test_cluster.get_address_0();
+        let mut context = test_cluster.wallet;
+        let gases = get_current_gases(address, &mut context).await;
+        // Split some extra gas coins so that the batch queue gets some coins
+        SuiClientCommands::SplitCoin {
+            coin_id: *gases[0].id(),
+            amounts: None,
+            gas_budget: 50000000,
+            gas: None,
+            count: Some(10),
+            serialize_unsigned_transaction: false,
+            serialize_signed_transaction: false,
+        }
+        .execute(&mut context)
+        .await
+        .expect("split failed");
+
+        let prom_registry = Registry::new();
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(faucet.batch_gas_pool_len().await < config.batch_gas_pool_size);
+
+        let uuid = Uuid::new_v4();
+        faucet
+            .batch_send(
+                uuid,
+                SuiAddress::random_for_testing_only(),
+                &[config.amount],
+            )
+            .await
+            .unwrap();
+        while faucet.get_batch_send_status(uuid).await.unwrap().status
+            != BatchSendStatusType::SUCCEEDED
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        // The pool was topped up before the batch was sent, and the coin paying for the batch
+        // has been recycled since.
+        assert_eq!(
+            faucet.batch_gas_pool_len().await,
+            config.batch_gas_pool_size
+        );
+    }
+
     async fn test_send_interface_has_success_status(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
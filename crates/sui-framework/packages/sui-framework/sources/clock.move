@@ -88,6 +88,13 @@ module sui::clock {
         clock.timestamp_ms = timestamp_ms;
     }
 
+    #[test_only]
+    /// Move `clock` forward to the timestamp of the test clock (see
+    /// `sui::test_utils::set_clock_timestamp_ms`).
+    public fun sync_for_testing(clock: &mut Clock) {
+        set_for_testing(clock, sui::test_utils::clock_timestamp_ms());
+    }
+
     #[test_only]
     public fun destroy_for_testing(clock: Clock) {
         let Clock { id, timestamp_ms: _ }  = clock;
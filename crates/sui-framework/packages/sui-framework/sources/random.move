@@ -0,0 +1,143 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// APIs for accessing the output of the validators' randomness beacon
+/// from move calls, via `Random`: a unique shared object that is created
+/// at 0x8 at the end of the epoch in which the beacon is enabled.
+module sui::random {
+    use sui::dynamic_field;
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    /// Sender is not @0x0 the system address.
+    const ENotSystemAddress: u64 = 0;
+    const EWrongInnerVersion: u64 = 1;
+    const EInvalidRandomnessUpdate: u64 = 2;
+
+    const CurrentVersion: u64 = 1;
+
+    /// Singleton shared object that exposes the latest output of the
+    /// randomness beacon to Move calls. This object is found at address
+    /// 0x8, and its contents are set by a system transaction every time
+    /// the validators produce a new round of randomness.
+    struct Random has key {
+        id: UID,
+        version: u64,
+    }
+
+    struct RandomInner has store {
+        version: u64,
+
+        /// Epoch in which `random_bytes` were produced.
+        epoch: u64,
+        /// Round of the beacon within `epoch`, starting from 0.
+        randomness_round: u64,
+        random_bytes: vector<u8>,
+    }
+
+    #[allow(unused_function)]
+    /// Create and share the singleton Random -- this function is
+    /// called exactly once, at the end of the first epoch in which
+    /// the randomness beacon is enabled.
+    fun create(ctx: &TxContext) {
+        assert!(tx_context::sender(ctx) == @0x0, ENotSystemAddress);
+
+        let version = CurrentVersion;
+
+        let inner = RandomInner {
+            version,
+            epoch: tx_context::epoch(ctx),
+            randomness_round: 0,
+            random_bytes: vector[],
+        };
+
+        let self = Random {
+            id: object::randomness_state(),
+            version,
+        };
+
+        dynamic_field::add(&mut self.id, version, inner);
+        transfer::share_object(self);
+    }
+
+    fun load_inner_mut(self: &mut Random): &mut RandomInner {
+        let version = self.version;
+
+        assert!(version == CurrentVersion, EWrongInnerVersion);
+
+        let inner: &mut RandomInner = dynamic_field::borrow_mut(&mut self.id, version);
+
+        assert!(inner.version == version, EWrongInnerVersion);
+        inner
+    }
+
+    fun load_inner(self: &Random): &RandomInner {
+        let version = self.version;
+
+        assert!(version == CurrentVersion, EWrongInnerVersion);
+
+        let inner: &RandomInner = dynamic_field::borrow(&self.id, version);
+
+        assert!(inner.version == version, EWrongInnerVersion);
+        inner
+    }
+
+    #[allow(unused_function)]
+    /// Record new randomness. Called by a system transaction, once for
+    /// every round of the beacon. Rounds restart from 0 every epoch, and
+    /// must otherwise be recorded one after the other.
+    fun update_randomness_state(
+        self: &mut Random,
+        new_round: u64,
+        new_bytes: vector<u8>,
+        ctx: &TxContext,
+    ) {
+        // Validator will make a special system call with sender set as 0x0.
+        assert!(tx_context::sender(ctx) == @0x0, ENotSystemAddress);
+
+        let epoch = tx_context::epoch(ctx);
+        let inner = load_inner_mut(self);
+        if (inner.epoch == epoch) {
+            assert!(new_round == inner.randomness_round + 1, EInvalidRandomnessUpdate);
+        } else {
+            assert!(epoch > inner.epoch && new_round == 0, EInvalidRandomnessUpdate);
+            inner.epoch = epoch;
+        };
+
+        inner.randomness_round = new_round;
+        inner.random_bytes = new_bytes;
+    }
+
+    /// Epoch in which the latest randomness was produced.
+    public fun epoch(self: &Random): u64 {
+        load_inner(self).epoch
+    }
+
+    /// Round of the beacon that produced the latest randomness, within
+    /// its epoch.
+    public fun randomness_round(self: &Random): u64 {
+        load_inner(self).randomness_round
+    }
+
+    /// The latest output of the randomness beacon. Empty until the
+    /// first round of randomness has been recorded.
+    public fun random_bytes(self: &Random): vector<u8> {
+        load_inner(self).random_bytes
+    }
+
+    #[test_only]
+    public fun create_for_testing(ctx: &TxContext) {
+        create(ctx);
+    }
+
+    #[test_only]
+    public fun update_randomness_state_for_testing(
+        self: &mut Random,
+        new_round: u64,
+        new_bytes: vector<u8>,
+        ctx: &TxContext,
+    ) {
+        update_randomness_state(self, new_round, new_bytes, ctx);
+    }
+}
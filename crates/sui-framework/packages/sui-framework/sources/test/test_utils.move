@@ -25,4 +25,21 @@ module sui::test_utils {
     public native fun destroy<T>(x: T);
 
     public native fun create_one_time_witness<T: drop>(): T;
+
+    /// Set the timestamp (in milliseconds) of the test clock. The test clock
+    /// starts at 0 for every test, and can be copied into a `Clock` with
+    /// `sui::clock::sync_for_testing`.
+    public native fun set_clock_timestamp_ms(timestamp_ms: u64);
+
+    /// The current timestamp (in milliseconds) of the test clock.
+    public native fun clock_timestamp_ms(): u64;
+
+    /// Reset the test random source, so that subsequent calls to
+    /// `random_bytes` produce the sequence derived from `seed`.
+    public native fun set_random_seed(seed: vector<u8>);
+
+    /// Return the next `n` bytes from the test random source. The sequence
+    /// only depends on the seed (empty by default), so the output is the same
+    /// on every run of the test. Not safe for cryptographic purposes.
+    public native fun random_bytes(n: u64): vector<u8>;
 }
@@ -4,6 +4,7 @@
 #[test_only]
 module sui::clock_tests {
     use sui::clock;
+    use sui::test_utils;
     use sui::tx_context;
 
     #[test]
@@ -19,4 +20,32 @@ module sui::clock_tests {
 
         clock::destroy_for_testing(clock);
     }
+
+    #[test]
+    fun syncing_a_clock_with_the_test_clock() {
+        let ctx = tx_context::dummy();
+        let clock = clock::create_for_testing(&mut ctx);
+        assert!(test_utils::clock_timestamp_ms() == 0, 0);
+
+        test_utils::set_clock_timestamp_ms(1000);
+        assert!(clock::timestamp_ms(&clock) == 0, 1);
+
+        clock::sync_for_testing(&mut clock);
+        assert!(clock::timestamp_ms(&clock) == 1000, 2);
+
+        clock::destroy_for_testing(clock);
+    }
+
+    #[test]
+    #[expected_failure]
+    fun syncing_a_clock_backwards() {
+        let ctx = tx_context::dummy();
+        let clock = clock::create_for_testing(&mut ctx);
+        clock::set_for_testing(&mut clock, 50);
+
+        test_utils::set_clock_timestamp_ms(10);
+        clock::sync_for_testing(&mut clock);
+
+        clock::destroy_for_testing(clock);
+    }
 }
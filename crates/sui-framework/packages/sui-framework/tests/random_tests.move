@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module sui::random_tests {
+    use sui::test_scenario;
+    use sui::random::{Self, Random, update_randomness_state_for_testing};
+
+    #[test]
+    fun random_tests_basic() {
+        let scenario_val = test_scenario::begin(@0x0);
+        let scenario = &mut scenario_val;
+
+        random::create_for_testing(test_scenario::ctx(scenario));
+        test_scenario::next_epoch(scenario, @0x0);
+
+        let random_state = test_scenario::take_shared<Random>(scenario);
+        assert!(random::random_bytes(&random_state) == vector[], 0);
+
+        update_randomness_state_for_testing(&mut random_state, 0, x"deadbeef", test_scenario::ctx(scenario));
+        assert!(random::epoch(&random_state) == 1, 0);
+        assert!(random::randomness_round(&random_state) == 0, 0);
+        assert!(random::random_bytes(&random_state) == x"deadbeef", 0);
+
+        update_randomness_state_for_testing(&mut random_state, 1, x"cafe", test_scenario::ctx(scenario));
+        assert!(random::randomness_round(&random_state) == 1, 0);
+        assert!(random::random_bytes(&random_state) == x"cafe", 0);
+
+        test_scenario::return_shared(random_state);
+        test_scenario::end(scenario_val);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = sui::random::EInvalidRandomnessUpdate)]
+    fun random_tests_skipped_round() {
+        let scenario_val = test_scenario::begin(@0x0);
+        let scenario = &mut scenario_val;
+
+        random::create_for_testing(test_scenario::ctx(scenario));
+        test_scenario::next_epoch(scenario, @0x0);
+
+        let random_state = test_scenario::take_shared<Random>(scenario);
+        update_randomness_state_for_testing(&mut random_state, 0, x"deadbeef", test_scenario::ctx(scenario));
+        update_randomness_state_for_testing(&mut random_state, 2, x"cafe", test_scenario::ctx(scenario));
+
+        test_scenario::return_shared(random_state);
+        test_scenario::end(scenario_val);
+    }
+}
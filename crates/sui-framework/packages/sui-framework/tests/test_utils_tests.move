@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module sui::test_utils_tests {
+    use std::vector;
+    use sui::test_utils;
+
+    #[test]
+    fun test_clock_timestamp() {
+        assert!(test_utils::clock_timestamp_ms() == 0, 0);
+        test_utils::set_clock_timestamp_ms(42);
+        assert!(test_utils::clock_timestamp_ms() == 42, 1);
+        test_utils::set_clock_timestamp_ms(7);
+        assert!(test_utils::clock_timestamp_ms() == 7, 2);
+    }
+
+    #[test]
+    fun test_random_bytes_length() {
+        assert!(vector::is_empty(&test_utils::random_bytes(0)), 0);
+        assert!(vector::length(&test_utils::random_bytes(1)) == 1, 1);
+        assert!(vector::length(&test_utils::random_bytes(32)) == 32, 2);
+        assert!(vector::length(&test_utils::random_bytes(65)) == 65, 3);
+    }
+
+    #[test]
+    fun test_random_bytes_deterministic() {
+        test_utils::set_random_seed(b"seed");
+        let first = test_utils::random_bytes(40);
+        let second = test_utils::random_bytes(40);
+        assert!(first != second, 0);
+
+        // Re-seeding replays the same sequence.
+        test_utils::set_random_seed(b"seed");
+        assert!(test_utils::random_bytes(40) == first, 1);
+        assert!(test_utils::random_bytes(40) == second, 2);
+
+        // A different seed produces a different sequence.
+        test_utils::set_random_seed(b"other seed");
+        assert!(test_utils::random_bytes(40) != first, 3);
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use async_graphql::*;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,11 @@ use crate::functional_group::FunctionalGroup;
 
 const MAX_QUERY_DEPTH: u32 = 10;
 const MAX_QUERY_NODES: u32 = 100;
+const MAX_BATCH_SIZE: u32 = 10;
+const MAX_SUBSCRIPTION_BUFFER: u32 = 50;
+const MAX_SUBSCRIPTIONS: u32 = 1_000;
+const REQUEST_TIMEOUT_MS: u64 = 10_000;
+const RESPONSE_CACHE_TTL_SECS: u64 = 60;
 
 /// Configuration on connections for the RPC, passed in as command-line arguments.
 pub struct ConnectionConfig {
@@ -30,6 +35,28 @@ pub struct ServiceConfig {
 
     #[serde(default)]
     pub(crate) experiments: Experiments,
+
+    #[serde(default)]
+    pub(crate) query_logging: QueryLoggingConfig,
+
+    #[serde(default)]
+    pub(crate) response_cache: ResponseCacheConfig,
+
+    #[serde(default)]
+    pub(crate) rate_limit: RateLimitConfig,
+
+    #[serde(default)]
+    pub(crate) cors: CorsConfig,
+
+    #[serde(default)]
+    pub(crate) access_log: AccessLogConfig,
+
+    /// Secret that cursors are signed with, so that only cursors issued by the service are
+    /// accepted. Instances serving the same clients must share it. If it is not set, a random one
+    /// is picked on startup, with a warning, and cursors are no longer accepted once the service
+    /// restarts.
+    #[serde(default)]
+    pub(crate) cursor_secret: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Copy)]
@@ -39,6 +66,20 @@ pub struct Limits {
     pub(crate) max_query_depth: u32,
     #[serde(default)]
     pub(crate) max_query_nodes: u32,
+    /// Most operations a client can send in a single batched request (a JSON array of operations).
+    #[serde(default = "default_max_batch_size")]
+    pub(crate) max_batch_size: u32,
+    /// Most items a subscription fetches ahead of what its client has received.
+    #[serde(default = "default_max_subscription_buffer")]
+    pub(crate) max_subscription_buffer: u32,
+    /// Most subscriptions the service serves at once, across all clients. Subscribing beyond it
+    /// fails until an existing subscription ends.
+    #[serde(default = "default_max_subscriptions")]
+    pub(crate) max_subscriptions: u32,
+    /// Longest a request can run for before it is aborted, along with the database queries it is
+    /// running.
+    #[serde(default = "default_request_timeout_ms")]
+    pub(crate) request_timeout_ms: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
@@ -50,6 +91,102 @@ pub struct Experiments {
     test_flag: bool,
 }
 
+/// Opt-in logging of a sample of the database queries made while serving requests, and of those
+/// that are slow, to diagnose slow resolvers.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct QueryLoggingConfig {
+    /// Log one in this many database queries, or none if 0. Statements are logged without the
+    /// values bound to them, which may come from user input.
+    #[serde(default)]
+    pub(crate) sample_one_in: u32,
+    /// Also log the plans of the statements of sampled queries, by running `EXPLAIN` on them, with
+    /// the string literals in them, which may be bound values, redacted.
+    #[serde(default)]
+    pub(crate) explain: bool,
+    /// Log every database query that takes at least this long, in milliseconds, whether it was
+    /// sampled or not. Its statements are logged without their plans.
+    #[serde(default)]
+    pub(crate) slow_query_threshold_ms: Option<u64>,
+}
+
+/// Opt-in caching of responses to repeated queries that read at the same checkpoint, or that only
+/// read historical data.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ResponseCacheConfig {
+    /// Most responses cached in memory, or none if 0.
+    #[serde(default)]
+    pub(crate) max_entries: usize,
+    /// Cache responses in Redis at this URL instead of in memory, to share them between instances
+    /// of the service.
+    #[serde(default)]
+    pub(crate) redis_url: Option<String>,
+    /// How long responses stay in Redis for, in seconds.
+    #[serde(default = "default_response_cache_ttl_secs")]
+    pub(crate) ttl_secs: u64,
+}
+
+/// Opt-in limits on how often each client can send requests. Clients are identified by the API key
+/// they send, if it is one of the known `api-keys`, and otherwise by their IP address.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimitConfig {
+    /// Requests each client can send per second, on average, or no limit if 0.
+    #[serde(default)]
+    pub(crate) requests_per_second: u32,
+    /// Requests each client can send at once, after being idle for long enough. Defaults to
+    /// `requests-per-second`.
+    #[serde(default)]
+    pub(crate) burst: Option<u32>,
+    /// Header that clients send their API key in. It is ignored until there are known keys.
+    #[serde(default)]
+    pub(crate) api_key_header: Option<String>,
+    /// Limits for the clients whose API keys belong to a tier, by tier name, instead of the limits
+    /// above.
+    #[serde(default)]
+    pub(crate) tiers: BTreeMap<String, RateTier>,
+    /// The tier each known API key belongs to. Once there are known keys, requests with an unknown
+    /// key are rejected.
+    #[serde(default)]
+    pub(crate) api_keys: BTreeMap<String, String>,
+    /// Reject requests that don't come with a known API key.
+    #[serde(default)]
+    pub(crate) require_api_key: bool,
+}
+
+/// Limits for the clients whose API keys belong to a tier.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateTier {
+    /// Requests each client in the tier can send per second, on average, or no limit if 0.
+    #[serde(default)]
+    pub(crate) requests_per_second: u32,
+    /// Requests each client in the tier can send at once. Defaults to `requests-per-second`.
+    #[serde(default)]
+    pub(crate) burst: Option<u32>,
+}
+
+/// Which web pages can call the service from a browser, through Cross-Origin Resource Sharing.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct CorsConfig {
+    /// Origins (e.g. `https://example.com`) of the pages that can call the service, or `*` for
+    /// any page. Browsers only let pages served from the same origin as the service call it if
+    /// this is empty.
+    #[serde(default)]
+    pub(crate) allowed_origins: Vec<String>,
+}
+
+/// Opt-in logging of a line per HTTP request served, with who sent it, the operations it contained,
+/// how it was answered, and how long that took.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccessLogConfig {
+    #[serde(default)]
+    pub(crate) enabled: bool,
+}
+
 impl ConnectionConfig {
     pub fn new(port: Option<u16>, host: Option<String>, rpc_url: Option<String>) -> Self {
         let default = Self::default();
@@ -67,6 +204,13 @@ impl ServiceConfig {
     }
 }
 
+impl QueryLoggingConfig {
+    /// Whether any database queries are logged.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.sample_one_in != 0 || self.slow_query_threshold_ms.is_some()
+    }
+}
+
 #[Object]
 impl ServiceConfig {
     /// Check whether `feature` is enabled on this GraphQL service.
@@ -92,6 +236,16 @@ impl ServiceConfig {
     async fn max_query_nodes(&self) -> Result<u32> {
         Ok(self.limits.max_query_nodes)
     }
+
+    /// The maximum number of operations the service will accept in a single batched request.
+    async fn max_batch_size(&self) -> Result<u32> {
+        Ok(self.limits.max_batch_size)
+    }
+
+    /// The maximum time, in milliseconds, that the service spends on a request before aborting it.
+    async fn request_timeout_ms(&self) -> Result<u64> {
+        Ok(self.limits.request_timeout_ms)
+    }
 }
 
 impl Default for ConnectionConfig {
@@ -109,10 +263,44 @@ impl Default for Limits {
         Self {
             max_query_depth: MAX_QUERY_DEPTH,
             max_query_nodes: MAX_QUERY_NODES,
+            max_batch_size: MAX_BATCH_SIZE,
+            max_subscription_buffer: MAX_SUBSCRIPTION_BUFFER,
+            max_subscriptions: MAX_SUBSCRIPTIONS,
+            request_timeout_ms: REQUEST_TIMEOUT_MS,
         }
     }
 }
 
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 0,
+            redis_url: None,
+            ttl_secs: RESPONSE_CACHE_TTL_SECS,
+        }
+    }
+}
+
+fn default_max_batch_size() -> u32 {
+    MAX_BATCH_SIZE
+}
+
+fn default_max_subscription_buffer() -> u32 {
+    MAX_SUBSCRIPTION_BUFFER
+}
+
+fn default_max_subscriptions() -> u32 {
+    MAX_SUBSCRIPTIONS
+}
+
+fn default_request_timeout_ms() -> u64 {
+    REQUEST_TIMEOUT_MS
+}
+
+fn default_response_cache_ttl_secs() -> u64 {
+    RESPONSE_CACHE_TTL_SECS
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,6 +318,8 @@ mod tests {
             r#" [limits]
                 max-query-depth = 100
                 max-query-nodes = 300
+                max-batch-size = 5
+                request-timeout-ms = 2000
             "#,
         )
         .unwrap();
@@ -138,6 +328,10 @@ mod tests {
             limits: Limits {
                 max_query_depth: 100,
                 max_query_nodes: 300,
+                max_batch_size: 5,
+                max_subscription_buffer: MAX_SUBSCRIPTION_BUFFER,
+                max_subscriptions: MAX_SUBSCRIPTIONS,
+                request_timeout_ms: 2000,
             },
             ..Default::default()
         };
@@ -161,6 +355,12 @@ mod tests {
             limits: Limits::default(),
             disabled_features: BTreeSet::from([G::Coins, G::NameService]),
             experiments: Experiments::default(),
+            query_logging: QueryLoggingConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            cors: CorsConfig::default(),
+            access_log: AccessLogConfig::default(),
+            cursor_secret: None,
         };
 
         assert_eq!(actual, expect)
@@ -183,6 +383,131 @@ mod tests {
         assert_eq!(actual, expect)
     }
 
+    #[test]
+    fn test_read_query_logging_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [query-logging]
+                sample-one-in = 100
+                explain = true
+                slow-query-threshold-ms = 500
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            query_logging: QueryLoggingConfig {
+                sample_one_in: 100,
+                explain: true,
+                slow_query_threshold_ms: Some(500),
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
+    #[test]
+    fn test_query_logging_enabled() {
+        assert!(!QueryLoggingConfig::default().is_enabled());
+
+        let sampled = QueryLoggingConfig {
+            sample_one_in: 100,
+            ..Default::default()
+        };
+        assert!(sampled.is_enabled());
+
+        let slow = QueryLoggingConfig {
+            slow_query_threshold_ms: Some(500),
+            ..Default::default()
+        };
+        assert!(slow.is_enabled());
+    }
+
+    #[test]
+    fn test_read_response_cache_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [response-cache]
+                redis-url = "redis://127.0.0.1:6379"
+                ttl-secs = 30
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            response_cache: ResponseCacheConfig {
+                max_entries: 0,
+                redis_url: Some("redis://127.0.0.1:6379".to_string()),
+                ttl_secs: 30,
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
+    #[test]
+    fn test_read_rate_limit_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [rate-limit]
+                requests-per-second = 10
+                burst = 50
+                api-key-header = "x-api-key"
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            rate_limit: RateLimitConfig {
+                requests_per_second: 10,
+                burst: Some(50),
+                api_key_header: Some("x-api-key".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
+    #[test]
+    fn test_read_rate_tiers_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [rate-limit]
+                requests-per-second = 10
+                api-key-header = "x-api-key"
+                require-api-key = true
+
+                [rate-limit.tiers.partner]
+                requests-per-second = 100
+                burst = 200
+
+                [rate-limit.api-keys]
+                secret = "partner"
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            rate_limit: RateLimitConfig {
+                requests_per_second: 10,
+                burst: None,
+                api_key_header: Some("x-api-key".to_string()),
+                tiers: BTreeMap::from([(
+                    "partner".to_string(),
+                    RateTier {
+                        requests_per_second: 100,
+                        burst: Some(200),
+                    },
+                )]),
+                api_keys: BTreeMap::from([("secret".to_string(), "partner".to_string())]),
+                require_api_key: true,
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
     #[test]
     fn test_read_everything_in_service_config() {
         let actual = ServiceConfig::read(
@@ -194,6 +519,21 @@ mod tests {
 
                 [experiments]
                 test-flag = true
+
+                [query-logging]
+                sample-one-in = 10
+
+                [response-cache]
+                max-entries = 1000
+
+                [rate-limit]
+                requests-per-second = 5
+
+                [cors]
+                allowed-origins = ["https://example.com"]
+
+                [access-log]
+                enabled = true
             "#,
         )
         .unwrap();
@@ -202,9 +542,31 @@ mod tests {
             limits: Limits {
                 max_query_depth: 42,
                 max_query_nodes: 320,
+                max_batch_size: MAX_BATCH_SIZE,
+                max_subscription_buffer: MAX_SUBSCRIPTION_BUFFER,
+                max_subscriptions: MAX_SUBSCRIPTIONS,
+                request_timeout_ms: REQUEST_TIMEOUT_MS,
             },
             disabled_features: BTreeSet::from([FunctionalGroup::Analytics]),
             experiments: Experiments { test_flag: true },
+            query_logging: QueryLoggingConfig {
+                sample_one_in: 10,
+                explain: false,
+                slow_query_threshold_ms: None,
+            },
+            response_cache: ResponseCacheConfig {
+                max_entries: 1000,
+                redis_url: None,
+                ttl_secs: RESPONSE_CACHE_TTL_SECS,
+            },
+            rate_limit: RateLimitConfig {
+                requests_per_second: 5,
+                ..Default::default()
+            },
+            cors: CorsConfig {
+                allowed_origins: vec!["https://example.com".to_string()],
+            },
+            access_log: AccessLogConfig { enabled: true },
         };
 
         assert_eq!(actual, expect);
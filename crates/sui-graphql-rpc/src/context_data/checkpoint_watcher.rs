@@ -0,0 +1,156 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{sync::Arc, time::Duration};
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use sui_indexer::{errors::IndexerError, indexer_reader::IndexerReader, schema_v2::checkpoints};
+use tokio::{
+    sync::{watch, OwnedSemaphorePermit, Semaphore},
+    task::JoinHandle,
+};
+use tracing::warn;
+
+use crate::extensions::consistent_read::ReadScope;
+
+/// How often the latest checkpoint in the database is checked for.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How much data the database has: everything up to and including a checkpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Watermark {
+    /// Sequence number of the latest checkpoint.
+    pub checkpoint: i64,
+    /// Number of transactions up to and including the latest checkpoint.
+    pub transactions: i64,
+}
+
+/// Checks the primary database for newly indexed checkpoints on behalf of every subscription, so
+/// that the database is polled once, no matter how many subscriptions are waiting for new data.
+/// Subscriptions read from the primary as of the latest checkpoint it has seen, so that they never
+/// skip over data that has not been committed yet.
+pub(crate) struct CheckpointWatcher {
+    reader: IndexerReader,
+    latest: watch::Receiver<Option<Watermark>>,
+    /// One for each subscription that can be served at once.
+    permits: Arc<Semaphore>,
+    task: JoinHandle<()>,
+}
+
+/// A subscription's view of the latest checkpoint found by the watcher. Counts towards the limit on
+/// subscriptions served at once until it is dropped.
+pub(crate) struct Subscriber {
+    /// Notified whenever a new checkpoint is found, holding the latest one, or `None` until the
+    /// database has one.
+    pub latest: watch::Receiver<Option<Watermark>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl CheckpointWatcher {
+    /// Starts checking the database that `reader` reads from for its latest checkpoint, for as
+    /// long as the watcher is alive, on behalf of up to `max_subscribers` subscriptions at once.
+    pub(crate) fn spawn(reader: IndexerReader, max_subscribers: usize) -> Self {
+        let (tx, latest) = watch::channel(None);
+        let poller = reader.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                match fetch_watermark(&poller).await {
+                    Ok(watermark) => {
+                        tx.send_if_modified(|latest| {
+                            let modified = *latest != watermark;
+                            *latest = watermark;
+                            modified
+                        });
+                    }
+                    Err(e) => warn!("Failed to check for new checkpoints: {e}"),
+                }
+            }
+        });
+
+        Self {
+            reader,
+            latest,
+            permits: Arc::new(Semaphore::new(max_subscribers)),
+            task,
+        }
+    }
+
+    /// A new subscriber, or `None` if there are already as many as the watcher serves at once.
+    pub(crate) fn subscribe(&self) -> Option<Subscriber> {
+        Subscriber::try_new(self.latest.clone(), &self.permits)
+    }
+
+    /// Reads from the database that was checked, as of `watermark`.
+    pub(crate) fn scope(&self, watermark: &Watermark) -> ReadScope {
+        ReadScope {
+            reader: self.reader.clone(),
+            checkpoint: watermark.checkpoint,
+        }
+    }
+}
+
+impl Subscriber {
+    /// A subscriber to `latest`, if one of `permits` is available, which it holds on to until it
+    /// is dropped.
+    pub(crate) fn try_new(
+        latest: watch::Receiver<Option<Watermark>>,
+        permits: &Arc<Semaphore>,
+    ) -> Option<Self> {
+        Some(Self {
+            latest,
+            _permit: permits.clone().try_acquire_owned().ok()?,
+        })
+    }
+}
+
+impl Drop for CheckpointWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn fetch_watermark(reader: &IndexerReader) -> Result<Option<Watermark>, IndexerError> {
+    reader
+        .run_query_async(|conn| {
+            checkpoints::table
+                .select((
+                    checkpoints::sequence_number,
+                    checkpoints::network_total_transactions,
+                ))
+                .order_by(checkpoints::sequence_number.desc())
+                .first::<(i64, i64)>(conn)
+                .optional()
+        })
+        .await
+        .map(|latest| {
+            latest.map(|(checkpoint, transactions)| Watermark {
+                checkpoint,
+                transactions,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribers_limited() {
+        let (_tx, latest) = watch::channel(None);
+        let permits = Arc::new(Semaphore::new(2));
+
+        let first = Subscriber::try_new(latest.clone(), &permits).unwrap();
+        let second = Subscriber::try_new(latest.clone(), &permits).unwrap();
+        assert!(Subscriber::try_new(latest.clone(), &permits).is_none());
+
+        // A subscriber that is dropped makes room for another.
+        drop(first);
+        let third = Subscriber::try_new(latest.clone(), &permits).unwrap();
+        assert!(Subscriber::try_new(latest.clone(), &permits).is_none());
+
+        drop((second, third));
+        assert_eq!(permits.available_permits(), 2);
+    }
+}
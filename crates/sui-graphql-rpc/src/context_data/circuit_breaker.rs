@@ -0,0 +1,150 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of consecutive failures after which the breaker opens.
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+/// How long the breaker stays open before letting a trial call through.
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Copy)]
+pub(crate) struct CircuitBreakerConfig {
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+}
+
+/// Stops calls to a dependency (the database) after it has failed repeatedly, so that requests
+/// fail fast instead of each waiting for it to time out. Each time the cooldown elapses a single
+/// trial call is let through: if it succeeds the breaker closes again, otherwise it stays open.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Calls go through. Counts the consecutive failures so far.
+    Closed { failures: u32 },
+    /// Calls are rejected until the cooldown ends.
+    Open { until: Instant },
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_COOLDOWN,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+
+    /// Whether a call to the dependency may be made. The outcome of calls that are allowed should
+    /// be reported through `record_success` or `record_failure`.
+    pub(crate) fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Closed { .. } => true,
+            // Let a trial call through, and start another cooldown for the calls behind it. If the
+            // trial never completes (e.g. the request is cancelled), the next cooldown allows
+            // another one.
+            State::Open { until } if Instant::now() >= until => {
+                *state = State::Open {
+                    until: Instant::now() + self.config.cooldown,
+                };
+                true
+            }
+            State::Open { .. } => false,
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        *self.state.lock().unwrap() = State::Closed { failures: 0 };
+    }
+
+    pub(crate) fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            // A failed trial call keeps the breaker open.
+            State::Open { .. } => self.config.failure_threshold,
+        };
+        *state = if failures >= self.config.failure_threshold {
+            State::Open {
+                until: Instant::now() + self.config.cooldown,
+            }
+        } else {
+            State::Closed { failures }
+        };
+    }
+
+    /// Whether calls are currently being rejected, apart from trial calls.
+    pub(crate) fn is_open(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), State::Open { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown,
+        })
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let cb = breaker(Duration::from_secs(60));
+        assert!(cb.allow());
+        cb.record_failure();
+        assert!(!cb.is_open());
+
+        // A success resets the count of consecutive failures.
+        cb.record_success();
+        cb.record_failure();
+        assert!(!cb.is_open());
+
+        cb.record_failure();
+        assert!(cb.is_open());
+        assert!(!cb.allow());
+    }
+
+    #[test]
+    fn test_lets_one_trial_call_through_per_cooldown() {
+        let cooldown = Duration::from_millis(50);
+        let cb = breaker(cooldown);
+        cb.record_failure();
+        cb.record_failure();
+        assert!(!cb.allow());
+
+        // The cooldown has elapsed, so one trial call is allowed, but no more.
+        std::thread::sleep(cooldown);
+        assert!(cb.allow());
+        assert!(!cb.allow());
+
+        // A failed trial keeps the breaker open.
+        cb.record_failure();
+        assert!(cb.is_open());
+        assert!(!cb.allow());
+
+        // A successful trial closes it.
+        std::thread::sleep(cooldown);
+        assert!(cb.allow());
+        cb.record_success();
+        assert!(!cb.is_open());
+        assert!(cb.allow());
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hmac::{hmac_sha3_256, HmacKey};
+use fastcrypto::traits::ToFromBytes;
+use rand::RngCore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Number of bytes of the payload's HMAC that are kept as its tag.
+const TAG_LENGTH: usize = 16;
+
+/// Length of the secrets picked at random, when none is configured.
+const RANDOM_SECRET_LENGTH: usize = 32;
+
+/// The connection a cursor was issued by. A cursor is only accepted by connections of the kind it
+/// was issued by.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum CursorKind {
+    Transaction,
+    Event,
+    Package,
+    Object,
+    Checkpoint,
+    ExchangeRate,
+    SearchName,
+    KioskItem,
+}
+
+/// Position of an item in a connection served from the database. Cursors are handed out to
+/// clients as the Base64 encoding of their BCS representation, along with an HMAC of it keyed by
+/// the service's `CursorSecret`, so that they are opaque to clients, and a cursor that was forged
+/// or modified, or issued by a different kind of connection, is rejected instead of being
+/// interpreted as some other position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Cursor {
+    pub kind: CursorKind,
+    /// Sequence number of the checkpoint the item belongs to.
+    pub checkpoint: i64,
+    /// BCS encoding of whatever else is needed to place the item relative to the other items in
+    /// its checkpoint.
+    key: Vec<u8>,
+}
+
+/// Versions of the cursor format, so that it can evolve without invalidating cursors that clients
+/// are already holding.
+#[derive(Serialize, Deserialize)]
+enum CursorPayload {
+    V1 {
+        kind: CursorKind,
+        checkpoint: i64,
+        key: Vec<u8>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncodedCursor {
+    payload: Vec<u8>,
+    tag: [u8; TAG_LENGTH],
+}
+
+/// Key that cursors are signed with. Cursors are only accepted by services signing them with the
+/// same secret.
+#[derive(Clone)]
+pub(crate) struct CursorSecret(Arc<[u8]>);
+
+impl CursorSecret {
+    /// Signs cursors with `secret`, or with a secret picked at random if there is none.
+    pub(crate) fn new(secret: Option<&str>) -> Self {
+        match secret {
+            Some(secret) => Self(secret.as_bytes().into()),
+            None => {
+                let mut secret = [0u8; RANDOM_SECRET_LENGTH];
+                rand::thread_rng().fill_bytes(&mut secret);
+                Self(secret[..].into())
+            }
+        }
+    }
+
+    fn tag(&self, payload: &[u8]) -> [u8; TAG_LENGTH] {
+        let key = HmacKey::from_bytes(&self.0).expect("HMAC keys can be of any length.");
+        let digest = hmac_sha3_256(&key, payload).digest;
+        let mut tag = [0u8; TAG_LENGTH];
+        tag.copy_from_slice(&digest[..TAG_LENGTH]);
+        tag
+    }
+
+    /// Whether `tag` is the tag of `payload`, in time independent of where they differ.
+    fn verify(&self, payload: &[u8], tag: &[u8; TAG_LENGTH]) -> bool {
+        let expected = self.tag(payload);
+        expected
+            .iter()
+            .zip(tag.iter())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+}
+
+impl Cursor {
+    pub(crate) fn new(kind: CursorKind, checkpoint: i64, key: &impl Serialize) -> Self {
+        Self {
+            kind,
+            checkpoint,
+            key: bcs::to_bytes(key).expect("Serializing a cursor key cannot fail."),
+        }
+    }
+
+    pub(crate) fn encode(&self, secret: &CursorSecret) -> String {
+        let payload = bcs::to_bytes(&CursorPayload::V1 {
+            kind: self.kind,
+            checkpoint: self.checkpoint,
+            key: self.key.clone(),
+        })
+        .expect("Serializing a cursor cannot fail.");
+
+        let tag = secret.tag(&payload);
+        let bytes = bcs::to_bytes(&EncodedCursor { payload, tag })
+            .expect("Serializing a cursor cannot fail.");
+        Base64::encode(bytes)
+    }
+
+    /// Decodes `cursor`, which must have been issued by a connection of kind `kind`, and signed
+    /// with `secret`.
+    pub(crate) fn decode(
+        kind: CursorKind,
+        cursor: &str,
+        secret: &CursorSecret,
+    ) -> Result<Self, Error> {
+        let invalid = || Error::InvalidCursor(format!("Not a valid {kind:?} cursor"));
+
+        let bytes = Base64::decode(cursor).map_err(|_| invalid())?;
+        let EncodedCursor { payload, tag } = bcs::from_bytes(&bytes).map_err(|_| invalid())?;
+        if !secret.verify(&payload, &tag) {
+            return Err(invalid());
+        }
+
+        let CursorPayload::V1 {
+            kind: actual,
+            checkpoint,
+            key,
+        } = bcs::from_bytes(&payload).map_err(|_| invalid())?;
+        if actual != kind {
+            return Err(Error::InvalidCursor(format!(
+                "Expected a {kind:?} cursor, but got a {actual:?} cursor"
+            )));
+        }
+
+        Ok(Self {
+            kind,
+            checkpoint,
+            key,
+        })
+    }
+
+    pub(crate) fn key<K: DeserializeOwned>(&self) -> Result<K, Error> {
+        bcs::from_bytes(&self.key)
+            .map_err(|_| Error::InvalidCursor(format!("Not a valid {:?} cursor", self.kind)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret() -> CursorSecret {
+        CursorSecret::new(Some("secret"))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cursor = Cursor::new(CursorKind::Event, 42, &(7i64, 3i64));
+        let decoded =
+            Cursor::decode(CursorKind::Event, &cursor.encode(&secret()), &secret()).unwrap();
+        assert_eq!(cursor, decoded);
+        assert_eq!(decoded.checkpoint, 42);
+        assert_eq!(decoded.key::<(i64, i64)>().unwrap(), (7, 3));
+    }
+
+    #[test]
+    fn test_wrong_kind() {
+        let cursor = Cursor::new(CursorKind::Transaction, 42, &7i64).encode(&secret());
+        let err = Cursor::decode(CursorKind::Checkpoint, &cursor, &secret()).unwrap_err();
+        assert!(matches!(err, Error::InvalidCursor(_)), "{err}");
+    }
+
+    #[test]
+    fn test_tampered() {
+        let cursor = Cursor::new(CursorKind::Transaction, 42, &7i64).encode(&secret());
+        let mut bytes = Base64::decode(&cursor).unwrap();
+
+        // Flip a bit in the payload, leaving the tag as is.
+        bytes[2] ^= 1;
+        let err =
+            Cursor::decode(CursorKind::Transaction, &Base64::encode(bytes), &secret()).unwrap_err();
+        assert!(matches!(err, Error::InvalidCursor(_)), "{err}");
+    }
+
+    #[test]
+    fn test_raw_sequence_number() {
+        let err = Cursor::decode(CursorKind::Checkpoint, "42", &secret()).unwrap_err();
+        assert!(matches!(err, Error::InvalidCursor(_)), "{err}");
+    }
+
+    #[test]
+    fn test_different_secret() {
+        let cursor = Cursor::new(CursorKind::Transaction, 42, &7i64).encode(&secret());
+        let err = Cursor::decode(
+            CursorKind::Transaction,
+            &cursor,
+            &CursorSecret::new(Some("other")),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidCursor(_)), "{err}");
+
+        // Random secrets are not shared between services.
+        let cursor =
+            Cursor::new(CursorKind::Transaction, 42, &7i64).encode(&CursorSecret::new(None));
+        let err =
+            Cursor::decode(CursorKind::Transaction, &cursor, &CursorSecret::new(None)).unwrap_err();
+        assert!(matches!(err, Error::InvalidCursor(_)), "{err}");
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::types::balance::Balance;
 use crate::types::checkpoint::Checkpoint;
-use crate::types::object::ObjectFilter;
+use crate::types::dry_run_result::DryRunResult;
 use crate::types::protocol_config::ProtocolConfigs;
 use crate::types::{object::Object, sui_address::SuiAddress};
 use async_graphql::connection::Connection;
@@ -12,21 +12,12 @@ use async_trait::async_trait;
 use sui_json_rpc_types::SuiObjectDataOptions;
 use sui_sdk::types::base_types::ObjectID;
 use sui_sdk::types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_sdk::types::transaction::TransactionData;
 
 #[async_trait]
 pub(crate) trait DataProvider: Send + Sync {
     async fn fetch_obj(&self, address: SuiAddress, version: Option<u64>) -> Result<Option<Object>>;
 
-    async fn fetch_owned_objs(
-        &self,
-        owner: &SuiAddress,
-        first: Option<u64>,
-        after: Option<String>,
-        last: Option<u64>,
-        before: Option<String>,
-        _filter: Option<ObjectFilter>,
-    ) -> Result<Connection<String, Object>>;
-
     async fn get_object_with_options(
         &self,
         object_id: ObjectID,
@@ -62,5 +53,10 @@ pub(crate) trait DataProvider: Send + Sync {
 
     async fn fetch_protocol_config(&self, version: Option<u64>) -> Result<ProtocolConfigs>;
 
+    /// The largest gas budget a transaction can have under the current protocol version.
+    async fn fetch_max_tx_gas(&self) -> Result<u64>;
+
     async fn get_latest_sui_system_state(&self) -> Result<SuiSystemStateSummary>;
+
+    async fn dry_run_tx_block(&self, tx_data: TransactionData) -> Result<DryRunResult>;
 }
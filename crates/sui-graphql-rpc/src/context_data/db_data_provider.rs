@@ -1,66 +1,846 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::Error, types::digest::Digest};
-use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
-use std::str::FromStr;
+use crate::{
+    config::QueryLoggingConfig,
+    context_data::{
+        checkpoint_watcher::CheckpointWatcher,
+        circuit_breaker::{CircuitBreaker, CircuitBreakerConfig},
+        cursor::{Cursor, CursorKind, CursorSecret},
+        query_log::{current_resolver, logged, QueryLogger},
+        read_router::{watermark, ReadRouter, ReadRouterConfig},
+    },
+    error::Error,
+    extensions::{
+        consistent_read::{request_read_scope, ReadScope},
+        timeout::request_deadline,
+    },
+    metrics::Metrics,
+    types::{
+        digest::Digest,
+        event::EventFilter,
+        move_package::PackageFilter,
+        object::ObjectFilter,
+        package_activity::{ActivityPeriod, PackageActivityMetric},
+        search::SearchResultKind,
+        sui_address::SuiAddress,
+        transaction_block::{TransactionBlockFilter, TransactionBlockKindInput},
+    },
+};
+use diesel::{
+    dsl::sql,
+    pg::Pg,
+    sql_function, sql_query,
+    sql_types::{Array, BigInt, Bool, Bytea, Float4, Text},
+    BoolExpressionMethods, ExpressionMethods, IntoSql, NullableExpressionMethods,
+    OptionalExtension, PgArrayExpressionMethods, PgConnection, PgTextExpressionMethods, QueryDsl,
+    QueryableByName, RunQueryDsl,
+};
+use move_binary_format::normalized::{Function as NormalizedFunction, Type as NormalizedType};
+use move_bytecode_utils::{layout::TypeLayoutBuilder, module_cache::GetModule};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    value::{MoveStructLayout, MoveTypeLayout},
+};
+use std::{
+    collections::HashMap, future::Future, ops::RangeInclusive, str::FromStr, sync::Arc,
+    time::Instant,
+};
 use sui_indexer::{
+    errors::IndexerError,
     indexer_reader::IndexerReader,
     models_v2::{
-        checkpoints::StoredCheckpoint, epoch::StoredEpochInfo, transactions::StoredTransaction,
+        address_object_stats::StoredAddressObjectStats,
+        checkpoints::StoredCheckpoint,
+        epoch::StoredEpochInfo,
+        events::StoredEvent,
+        objects::StoredObject,
+        package_leaderboards::PackageLeaderboard,
+        packages::StoredPackage,
+        search_names::{SearchNameKind, StoredSearchName},
+        transactions::StoredTransaction,
     },
-    schema_v2::{checkpoints, epochs, transactions},
+    schema_v2::{
+        checkpoints, epochs, events, objects, packages, search_names, transactions, tx_indices,
+    },
+    types_v2::{OwnerType, TransactionKind},
     PgConnectionPoolConfig,
 };
+use sui_sdk::types::{
+    base_types::{ObjectID, SuiAddress as NativeSuiAddress},
+    dynamic_field::{derive_dynamic_field_id, Field},
+    object::{MoveObject as NativeMoveObject, Object as NativeObject, ObjectFormatOptions},
+    parse_sui_struct_tag,
+    sui_system_state::PoolTokenExchangeRate,
+};
+
+#[derive(QueryableByName)]
+struct RowEstimate {
+    #[diesel(sql_type = BigInt)]
+    estimate: i64,
+}
+
+/// Number of items returned by a connection when `first` is not specified.
+const DEFAULT_PAGE_SIZE: u64 = 50;
+
+/// Most items returned by a connection or a list that is limited by `first`, however many are asked
+/// for.
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// Most items that can be fetched by key in a single multi-get.
+const MAX_MULTI_GET_SIZE: usize = 50;
+
+/// Latest checkpoint that the request the current task is executing reads data from, see
+/// `ConsistentRead`. Requests that are not subject to consistent reads see every checkpoint.
+fn checkpoint_watermark() -> i64 {
+    request_read_scope().map_or(i64::MAX, |scope| scope.checkpoint)
+}
+
+// Trigram similarity of two strings, from the pg_trgm extension.
+sql_function!(fn similarity(x: Text, y: Text) -> Float4);
+
+// Whether two strings are similar enough, by their trigram similarity, from the pg_trgm extension.
+diesel::infix_operator!(SimilarTo, " % ", backend: Pg);
+
+/// Escapes the characters that have a special meaning in `LIKE` patterns.
+fn escape_like_pattern(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
 
 pub(crate) struct PgManager {
-    pub inner: IndexerReader,
+    /// Picks the database each query is sent to.
+    router: ReadRouter,
+    /// Makes queries fail fast while the database is unreachable.
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Picks queries to log, for diagnosing slow resolvers.
+    query_logger: QueryLogger,
+    /// Where the latency of each query is recorded, if anywhere.
+    metrics: Option<Arc<Metrics>>,
+    /// Signs the cursors handed out to clients.
+    cursor_secret: CursorSecret,
 }
 
 impl PgManager {
+    /// Reads from the database at `db_url`, and spreads reads across the read replicas of that
+    /// database at `replica_urls`, if any, once `spawn_watermark_monitor` has been called.
     pub(crate) fn new<T: Into<String>>(
         db_url: T,
+        replica_urls: Vec<String>,
         config: Option<PgConnectionPoolConfig>,
+        query_logging: QueryLoggingConfig,
     ) -> Result<Self, Error> {
         // TODO (wlmyng): support config
         let mut config = config.unwrap_or(PgConnectionPoolConfig::default());
         config.set_pool_size(30);
-        let inner = IndexerReader::new_with_config(db_url, config)
+        let primary = IndexerReader::new_with_config(db_url, config)
+            .map_err(|e| Error::Internal(e.to_string()))?;
+        let replicas = replica_urls
+            .into_iter()
+            .map(|url| IndexerReader::new_with_config(url, config))
+            .collect::<Result<_, _>>()
             .map_err(|e| Error::Internal(e.to_string()))?;
 
-        Ok(Self { inner })
+        Ok(Self {
+            router: ReadRouter::new(ReadRouterConfig::default(), primary, replicas),
+            circuit_breaker: Arc::new(CircuitBreaker::new(CircuitBreakerConfig::default())),
+            query_logger: QueryLogger::new(query_logging),
+            metrics: None,
+            cursor_secret: CursorSecret::new(None),
+        })
+    }
+
+    /// Signs cursors with `cursor_secret`. By default, cursors are signed with a secret picked at
+    /// random, which other instances of the service don't share.
+    pub(crate) fn with_cursor_secret(mut self, cursor_secret: CursorSecret) -> Self {
+        self.cursor_secret = cursor_secret;
+        self
     }
 
+    /// Records the latency of each query in `metrics`, by the field that made it.
+    pub(crate) fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Starts checking the latest checkpoint in each database, for requests to read at without
+    /// querying for it themselves, and how far behind the primary each read replica is, to stop
+    /// reading from it while it lags too far behind. Replicas are not read from until this has
+    /// been called.
+    pub(crate) fn spawn_watermark_monitor(&self) -> tokio::task::JoinHandle<()> {
+        self.router.spawn_watermark_monitor()
+    }
+
+    /// Starts checking the primary database for new checkpoints, on behalf of up to
+    /// `max_subscriptions` subscriptions at once.
+    pub(crate) fn spawn_checkpoint_watcher(&self, max_subscriptions: usize) -> CheckpointWatcher {
+        CheckpointWatcher::spawn(self.router.primary().clone(), max_subscriptions)
+    }
+
+    /// Every database that is read from, named for metrics, see `ReadRouter::readers`.
+    pub(crate) fn readers(&self) -> Vec<(String, IndexerReader)> {
+        self.router.readers()
+    }
+
+    /// Picks the database that a request reads from, and the latest checkpoint in it, for all of
+    /// its fields to read at, see `ConsistentRead`. The checkpoint is the one the watermark monitor
+    /// last saw, and is only read from the database if the monitor has not seen one yet.
+    pub(crate) async fn read_scope(&self) -> Result<ReadScope, Error> {
+        let (reader, checkpoint) = self.router.reader_with_watermark();
+        let reader = reader.clone();
+        let checkpoint = match checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => self.guarded(watermark(&reader)).await?,
+        };
+        Ok(ReadScope { reader, checkpoint })
+    }
+
+    /// The database to send the next query to: the one the current request is pinned to, if any,
+    /// otherwise whichever the router picks.
+    fn reader(&self) -> IndexerReader {
+        request_read_scope().map_or_else(|| self.router.reader().clone(), |scope| scope.reader)
+    }
+
+    /// Whether the service is running in degraded mode, because the database is unreachable.
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.circuit_breaker.is_open()
+    }
+
+    /// The circuit breaker guarding queries to the database, which is open while the service runs
+    /// in degraded mode.
+    pub(crate) fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        self.circuit_breaker.clone()
+    }
+
+    /// Runs `query` on a blocking thread. If it is made on behalf of a request that is subject to
+    /// a timeout, it fails with `Error::RequestTimeout` once the request times out, and Postgres
+    /// cancels whichever of its statements is still running at that point, so that it does not
+    /// hold on to a connection after the request has been aborted.
     pub async fn run_query_async<T, E, F>(&self, query: F) -> Result<T, Error>
     where
         F: FnOnce(&mut PgConnection) -> Result<T, E> + Send + 'static,
         E: From<diesel::result::Error> + std::error::Error + Send + 'static,
         T: Send + 'static,
     {
-        self.inner
-            .run_query_async(query)
-            .await
-            .map_err(|e| Error::Internal(e.to_string()))
+        let Some(deadline) = request_deadline() else {
+            return self.run_query_sampled(query).await;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(Error::RequestTimeout);
+        }
+
+        // Scoped to the read-only transaction that the query runs in.
+        let statement_timeout_ms = remaining.as_millis().max(1);
+        let query = move |conn: &mut PgConnection| {
+            sql_query(format!(
+                "SET LOCAL statement_timeout = {statement_timeout_ms}"
+            ))
+            .execute(conn)?;
+            query(conn)
+        };
+        match tokio::time::timeout(remaining, self.run_query_sampled(query)).await {
+            // The query failed because Postgres cancelled it.
+            Ok(Err(_)) if Instant::now() >= deadline => Err(Error::RequestTimeout),
+            Ok(result) => result,
+            Err(_) => Err(Error::RequestTimeout),
+        }
+    }
+
+    async fn run_query_sampled<T, E, F>(&self, query: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut PgConnection) -> Result<T, E> + Send + 'static,
+        E: From<diesel::result::Error> + std::error::Error + Send + 'static,
+        T: Send + 'static,
+    {
+        let start = Instant::now();
+        let result = match self.query_logger.sample() {
+            Some(sample) => {
+                self.guarded(
+                    self.reader()
+                        .run_query_async(move |conn| sample.run(conn, query)),
+                )
+                .await
+            }
+            None => self.guarded(self.reader().run_query_async(query)).await,
+        };
+
+        if let Some(metrics) = &self.metrics {
+            let resolver = current_resolver();
+            metrics
+                .db_query_latency
+                .with_label_values(&[resolver.as_deref().unwrap_or("<none>")])
+                .observe(start.elapsed().as_secs_f64());
+        }
+
+        result
+    }
+
+    /// Runs `call` against the database unless the circuit breaker is open, in which case it
+    /// fails immediately. Failures to connect to the database count towards opening the breaker.
+    async fn guarded<T>(
+        &self,
+        call: impl Future<Output = Result<T, IndexerError>>,
+    ) -> Result<T, Error> {
+        if !self.circuit_breaker.allow() {
+            return Err(Error::DatabaseUnavailable);
+        }
+        let result = call.await;
+        match &result {
+            Err(IndexerError::PgPoolConnectionError(_)) => self.circuit_breaker.record_failure(),
+            _ => self.circuit_breaker.record_success(),
+        }
+        result.map_err(|e| Error::Internal(e.to_string()))
     }
 
     pub(crate) async fn fetch_tx(&self, digest: &str) -> Result<Option<StoredTransaction>, Error> {
         let digest = Digest::from_str(digest)?.into_vec();
+        let watermark = checkpoint_watermark();
 
-        self.run_query_async(|conn| {
-            transactions::dsl::transactions
-                .filter(transactions::dsl::transaction_digest.eq(digest))
-                .get_result::<StoredTransaction>(conn) // Expect exactly 0 to 1 result
-                .optional()
+        self.run_query_async(move |conn| {
+            logged(
+                conn,
+                transactions::dsl::transactions
+                    .filter(transactions::dsl::transaction_digest.eq(digest))
+                    .filter(transactions::dsl::checkpoint_sequence_number.le(watermark)),
+            )
+            .get_result::<StoredTransaction>(conn) // Expect exactly 0 to 1 result
+            .optional()
         })
         .await
     }
 
+    /// Fetch the transactions with each of `digests`, in a single query. Transactions are returned
+    /// in the order of their digests, with `None` for digests of transactions that were not found.
+    pub(crate) async fn fetch_txs_by_digests(
+        &self,
+        digests: &[String],
+    ) -> Result<Vec<Option<StoredTransaction>>, Error> {
+        if digests.len() > MAX_MULTI_GET_SIZE {
+            return Err(Error::MultiGet(format!(
+                "Requested {} transactions, but at most {MAX_MULTI_GET_SIZE} can be fetched at once",
+                digests.len()
+            )));
+        }
+
+        let digests = digests
+            .iter()
+            .map(|digest| Ok(Digest::from_str(digest)?.into_vec()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        if digests.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let keys = digests.clone();
+        let watermark = checkpoint_watermark();
+        let txs = self
+            .run_query_async(move |conn| {
+                logged(
+                    conn,
+                    transactions::dsl::transactions
+                        .filter(transactions::dsl::transaction_digest.eq_any(keys))
+                        .filter(transactions::dsl::checkpoint_sequence_number.le(watermark)),
+                )
+                .load::<StoredTransaction>(conn)
+            })
+            .await?;
+
+        // The same digest may be requested more than once.
+        let by_digest: HashMap<_, _> = txs
+            .into_iter()
+            .map(|tx| (tx.transaction_digest.clone(), tx))
+            .collect();
+        Ok(digests
+            .iter()
+            .map(|digest| by_digest.get(digest).cloned())
+            .collect())
+    }
+
+    /// Fetch a page of transactions matching `filter`, in transaction sequence order, along with
+    /// whether there are more transactions after the page. The cursor of a transaction is its
+    /// sequence number, see `tx_cursor`.
+    pub(crate) async fn fetch_txs(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<(Vec<StoredTransaction>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let after = after.map(|c| self.parse_tx_cursor(&c)).transpose()?;
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as i64;
+        let filter = filter.unwrap_or_default();
+
+        if filter.function.is_some() && filter.module.is_none() {
+            return Err(Error::UnsupportedFilter(
+                "function can only be filtered on along with module".to_string(),
+            ));
+        }
+        if filter.module.is_some() && filter.package.is_none() {
+            return Err(Error::UnsupportedFilter(
+                "module can only be filtered on along with package".to_string(),
+            ));
+        }
+
+        // Bounds on the checkpoints that transactions can be from, both inclusive.
+        let mut lo = [
+            filter.checkpoint.map(|c| c as i64),
+            filter.after_checkpoint.map(|c| c as i64 + 1),
+            after.map(|(checkpoint, _)| checkpoint),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        let mut hi = [
+            filter.checkpoint.map(|c| c as i64),
+            filter.before_checkpoint.map(|c| c as i64 - 1),
+            request_read_scope().map(|scope| scope.checkpoint),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        // A time range corresponds to a range of checkpoints, which is cheaper to filter
+        // transactions by than their timestamps. Both bounds of the filter are exclusive.
+        if filter.after_checkpoint_timestamp.is_some()
+            || filter.before_checkpoint_timestamp.is_some()
+        {
+            let start_ms = filter
+                .after_checkpoint_timestamp
+                .map(|t| t.timestamp_ms() + 1);
+            let end_ms = filter
+                .before_checkpoint_timestamp
+                .map(|t| t.timestamp_ms() - 1);
+            let Some((start, end)) = self
+                .guarded(
+                    self.reader()
+                        .get_checkpoint_range_for_timestamps_async(start_ms, end_ms),
+                )
+                .await?
+            else {
+                return Ok((vec![], false));
+            };
+            lo = lo.max(Some(start as i64));
+            hi = Some(hi.map_or(end as i64, |hi| hi.min(end as i64)));
+        }
+
+        let query = tx_query(&filter, after.map(|(_, tx)| tx), lo, hi, limit);
+        let mut txs = self
+            .run_query_async(move |conn| logged(conn, query).load::<StoredTransaction>(conn))
+            .await?;
+
+        let has_next_page = txs.len() as i64 > limit;
+        txs.truncate(limit as usize);
+        Ok((txs, has_next_page))
+    }
+
+    pub(crate) fn tx_cursor(&self, tx: &StoredTransaction) -> String {
+        Cursor::new(
+            CursorKind::Transaction,
+            tx.checkpoint_sequence_number,
+            &tx.tx_sequence_number,
+        )
+        .encode(&self.cursor_secret)
+    }
+
+    pub(crate) fn parse_tx_cursor(&self, cursor: &str) -> Result<(i64, i64), Error> {
+        let cursor = Cursor::decode(CursorKind::Transaction, cursor, &self.cursor_secret)?;
+        Ok((cursor.checkpoint, cursor.key()?))
+    }
+
+    /// Fetch a page of events matching `filter`, in the order they were emitted in, along with
+    /// whether there are more events after the page. The cursor of an event is the sequence
+    /// number of the transaction that emitted it and its sequence number within that
+    /// transaction, see `event_cursor`.
+    pub(crate) async fn fetch_events(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<EventFilter>,
+    ) -> Result<(Vec<StoredEvent>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let after = after.map(|c| self.parse_event_cursor(&c)).transpose()?;
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as i64;
+        let filter = filter.unwrap_or_default();
+
+        if filter.emitting_module.is_some() && filter.emitting_package.is_none() {
+            return Err(Error::UnsupportedFilter(
+                "emittingModule without emittingPackage".to_string(),
+            ));
+        }
+
+        let mut query = events::dsl::events
+            .filter(events::dsl::checkpoint_sequence_number.le(checkpoint_watermark()))
+            .into_boxed();
+        if let Some((checkpoint, tx, event)) = after {
+            query = query.filter(events::dsl::checkpoint_sequence_number.ge(checkpoint));
+            query = query.filter(
+                events::dsl::tx_sequence_number
+                    .gt(tx)
+                    .or(events::dsl::tx_sequence_number
+                        .eq(tx)
+                        .and(events::dsl::event_sequence_number.gt(event))),
+            );
+        }
+
+        if let Some(sender) = filter.sender {
+            query =
+                query.filter(events::dsl::senders.contains(vec![Some(sender.as_slice().to_vec())]));
+        }
+
+        if let Some(digest) = filter.transaction_digest {
+            let digest = Digest::from_str(&digest)?.into_vec();
+            query = query.filter(events::dsl::transaction_digest.eq(digest));
+        }
+
+        if let Some(package) = filter.emitting_package {
+            query = query.filter(events::dsl::package.eq(package.as_slice().to_vec()));
+        }
+
+        if let Some(module) = filter.emitting_module {
+            query = query.filter(events::dsl::module.eq(module));
+        }
+
+        if let Some(event_type) = filter.event_type {
+            // Normalize the type to the form it is stored in.
+            let tag = parse_sui_struct_tag(&event_type)
+                .map_err(|e| Error::InvalidType(format!("{event_type}: {e}")))?;
+            let event_type = tag.to_string();
+            query = if tag.type_params.is_empty() {
+                let instantiations = format!("{}<%", escape_like_pattern(&event_type));
+                query.filter(
+                    events::dsl::event_type
+                        .eq(event_type)
+                        .or(events::dsl::event_type.like(instantiations)),
+                )
+            } else {
+                query.filter(events::dsl::event_type.eq(event_type))
+            };
+        }
+
+        let mut events = self
+            .run_query_async(move |conn| {
+                logged(
+                    conn,
+                    query
+                        .order_by((
+                            events::dsl::tx_sequence_number.asc(),
+                            events::dsl::event_sequence_number.asc(),
+                        ))
+                        .limit(limit + 1),
+                )
+                .load::<StoredEvent>(conn)
+            })
+            .await?;
+
+        let has_next_page = events.len() as i64 > limit;
+        events.truncate(limit as usize);
+        Ok((events, has_next_page))
+    }
+
+    pub(crate) fn event_cursor(&self, event: &StoredEvent) -> String {
+        Cursor::new(
+            CursorKind::Event,
+            event.checkpoint_sequence_number,
+            &(event.tx_sequence_number, event.event_sequence_number),
+        )
+        .encode(&self.cursor_secret)
+    }
+
+    /// Cursor positioned after the last event emitted in checkpoint `checkpoint`, which brought
+    /// the total number of transactions to `transactions`.
+    pub(crate) fn event_cursor_after_checkpoint(
+        &self,
+        checkpoint: i64,
+        transactions: i64,
+    ) -> String {
+        // The last transaction in the checkpoint, and its last possible event.
+        Cursor::new(CursorKind::Event, checkpoint, &(transactions - 1, i64::MAX))
+            .encode(&self.cursor_secret)
+    }
+
+    pub(crate) fn parse_event_cursor(&self, cursor: &str) -> Result<(i64, i64, i64), Error> {
+        let cursor = Cursor::decode(CursorKind::Event, cursor, &self.cursor_secret)?;
+        let (tx, event) = cursor.key()?;
+        Ok((cursor.checkpoint, tx, event))
+    }
+
+    /// Fetch a page of published and upgraded packages matching `filter`, in the order they were
+    /// published in, along with whether there are more packages after the page. The cursor of a
+    /// package is its checkpoint sequence number and ID, see `package_cursor`.
+    pub(crate) async fn fetch_packages(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<PackageFilter>,
+    ) -> Result<(Vec<StoredPackage>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let after = after.map(|c| self.parse_package_cursor(&c)).transpose()?;
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as i64;
+        let filter = filter.unwrap_or_default();
+
+        let mut query = packages::dsl::packages
+            .filter(packages::dsl::checkpoint_sequence_number.le(checkpoint_watermark()))
+            .into_boxed();
+        if let Some((checkpoint, package_id)) = after {
+            query = query.filter(
+                packages::dsl::checkpoint_sequence_number.gt(checkpoint).or(
+                    packages::dsl::checkpoint_sequence_number
+                        .eq(checkpoint)
+                        .and(packages::dsl::package_id.gt(package_id)),
+                ),
+            );
+        }
+
+        if let Some(publisher) = filter.publisher {
+            query = query.filter(packages::dsl::publisher.eq(publisher.as_slice().to_vec()));
+        }
+
+        if let Some(checkpoint) = filter.after_checkpoint {
+            query = query.filter(packages::dsl::checkpoint_sequence_number.gt(checkpoint as i64));
+        }
+
+        let mut packages = self
+            .run_query_async(move |conn| {
+                logged(
+                    conn,
+                    query
+                        .order_by((
+                            packages::dsl::checkpoint_sequence_number.asc(),
+                            packages::dsl::package_id.asc(),
+                        ))
+                        .limit(limit + 1),
+                )
+                .load::<StoredPackage>(conn)
+            })
+            .await?;
+
+        let has_next_page = packages.len() as i64 > limit;
+        packages.truncate(limit as usize);
+        Ok((packages, has_next_page))
+    }
+
+    pub(crate) async fn fetch_package(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Option<StoredPackage>, Error> {
+        let address = address.as_slice().to_vec();
+        let watermark = checkpoint_watermark();
+        self.run_query_async(move |conn| {
+            logged(
+                conn,
+                packages::dsl::packages
+                    .filter(packages::dsl::package_id.eq(address))
+                    .filter(packages::dsl::checkpoint_sequence_number.le(watermark)),
+            )
+            .get_result::<StoredPackage>(conn) // Expect exactly 0 to 1 result
+            .optional()
+        })
+        .await
+    }
+
+    /// Layout of values of the struct type `tag`, with the modules that define it and the types
+    /// of its fields read from the packages table.
+    pub(crate) async fn fetch_move_struct_layout(
+        &self,
+        tag: StructTag,
+    ) -> Result<MoveStructLayout, Error> {
+        self.guarded(self.reader().spawn_blocking(move |this| {
+            Ok(NativeMoveObject::get_layout_from_struct_tag(
+                tag,
+                ObjectFormatOptions::default(),
+                &this,
+            )?)
+        }))
+        .await
+    }
+
+    /// Layouts of the values passed to each parameter of the Move function `module::function` in
+    /// `package`, instantiated with `type_arguments`, with the modules involved read from the
+    /// packages table. Parameters that take a reference are laid out as the type they refer to.
+    /// The layout of a parameter is `None` if it can't be built, and the result is `None` if the
+    /// function doesn't exist.
+    pub(crate) async fn fetch_move_function_parameter_layouts(
+        &self,
+        package: ObjectID,
+        module: Identifier,
+        function: Identifier,
+        type_arguments: Vec<TypeTag>,
+    ) -> Result<Option<Vec<Option<MoveTypeLayout>>>, Error> {
+        self.guarded(self.reader().spawn_blocking(move |this| {
+            let module_id = ModuleId::new(package.into(), module);
+            let Some(module) = this.get_module_by_id(&module_id)? else {
+                return Ok(None);
+            };
+            let Some(function) = NormalizedFunction::new_from_name(&module, &function) else {
+                return Ok(None);
+            };
+
+            let type_arguments: Vec<_> = type_arguments
+                .into_iter()
+                .map(NormalizedType::from)
+                .collect();
+            let layouts = function
+                .parameters
+                .iter()
+                .map(|param| {
+                    let param = match param.subst(&type_arguments) {
+                        NormalizedType::Reference(t) | NormalizedType::MutableReference(t) => *t,
+                        t => t,
+                    };
+                    TypeLayoutBuilder::build_with_types(&param.into_type_tag()?, &this).ok()
+                })
+                .collect();
+            Ok(Some(layouts))
+        }))
+        .await
+    }
+
+    pub(crate) fn package_cursor(&self, package: &StoredPackage) -> String {
+        Cursor::new(
+            CursorKind::Package,
+            package.checkpoint_sequence_number,
+            &package.package_id,
+        )
+        .encode(&self.cursor_secret)
+    }
+
+    pub(crate) fn parse_package_cursor(&self, cursor: &str) -> Result<(i64, Vec<u8>), Error> {
+        let cursor = Cursor::decode(CursorKind::Package, cursor, &self.cursor_secret)?;
+        Ok((cursor.checkpoint, cursor.key()?))
+    }
+
+    /// Fetch a page of the names that contain `query` or are similar to it, most similar first,
+    /// along with whether there are more names after the page. Names are ordered by how similar
+    /// they are to the query, which is not stored, so the cursor of a name is its offset in the
+    /// results, see `search_name_cursor`.
+    pub(crate) async fn fetch_search_names(
+        &self,
+        query: String,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        kinds: Option<Vec<SearchResultKind>>,
+    ) -> Result<(Vec<(u64, StoredSearchName)>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let offset = after
+            .map(|c| self.parse_search_name_cursor(&c))
+            .transpose()?
+            .map_or(0, |after| after + 1);
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let pattern = format!("%{}%", escape_like_pattern(&query));
+
+        let mut search = search_names::dsl::search_names
+            .filter(search_names::dsl::name.ilike(pattern).or(SimilarTo::new(
+                search_names::dsl::name,
+                query.clone().into_sql::<Text>(),
+            )))
+            .into_boxed();
+        if let Some(kinds) = kinds {
+            let kinds = kinds
+                .into_iter()
+                .map(|k| SearchNameKind::from(k) as i16)
+                .collect::<Vec<_>>();
+            search = search.filter(search_names::dsl::kind.eq_any(kinds));
+        }
+
+        let names = self
+            .run_query_async(move |conn| {
+                logged(
+                    conn,
+                    search
+                        .order_by((
+                            similarity(search_names::dsl::name, query).desc(),
+                            search_names::dsl::kind.asc(),
+                            search_names::dsl::full_name.asc(),
+                        ))
+                        .offset(offset as i64)
+                        .limit(limit as i64 + 1),
+                )
+                .load::<StoredSearchName>(conn)
+            })
+            .await?;
+
+        let has_next_page = names.len() as u64 > limit;
+        let names = names
+            .into_iter()
+            .take(limit as usize)
+            .zip(offset..)
+            .map(|(name, offset)| (offset, name))
+            .collect();
+        Ok((names, has_next_page))
+    }
+
+    pub(crate) fn search_name_cursor(&self, offset: u64) -> String {
+        // Search results are not indexed per checkpoint, so they are placed by offset alone.
+        Cursor::new(CursorKind::SearchName, 0, &offset).encode(&self.cursor_secret)
+    }
+
+    pub(crate) fn parse_search_name_cursor(&self, cursor: &str) -> Result<u64, Error> {
+        Cursor::decode(CursorKind::SearchName, cursor, &self.cursor_secret)?.key()
+    }
+
     pub(crate) async fn fetch_latest_epoch(&self) -> Result<StoredEpochInfo, Error> {
-        self.run_query_async(|conn| {
-            epochs::dsl::epochs
-                .order_by(epochs::dsl::epoch.desc())
-                .limit(1)
-                .first::<StoredEpochInfo>(conn)
+        let watermark = checkpoint_watermark();
+        self.run_query_async(move |conn| {
+            logged(
+                conn,
+                epochs::dsl::epochs
+                    .filter(epochs::dsl::first_checkpoint_id.le(watermark))
+                    .order_by(epochs::dsl::epoch.desc())
+                    .limit(1),
+            )
+            .first::<StoredEpochInfo>(conn)
         })
         .await
     }
@@ -71,11 +851,16 @@ impl PgManager {
     ) -> Result<Option<StoredEpochInfo>, Error> {
         let epoch_id = i64::try_from(epoch_id)
             .map_err(|_| Error::Internal("Failed to convert epoch id to i64".to_string()))?;
+        let watermark = checkpoint_watermark();
         self.run_query_async(move |conn| {
-            epochs::dsl::epochs
-                .filter(epochs::dsl::epoch.eq(epoch_id))
-                .get_result::<StoredEpochInfo>(conn) // Expect exactly 0 to 1 result
-                .optional()
+            logged(
+                conn,
+                epochs::dsl::epochs
+                    .filter(epochs::dsl::epoch.eq(epoch_id))
+                    .filter(epochs::dsl::first_checkpoint_id.le(watermark)),
+            )
+            .get_result::<StoredEpochInfo>(conn) // Expect exactly 0 to 1 result
+            .optional()
         })
         .await
     }
@@ -89,11 +874,16 @@ impl PgManager {
     }
 
     pub(crate) async fn fetch_latest_checkpoint(&self) -> Result<StoredCheckpoint, Error> {
-        self.run_query_async(|conn| {
-            checkpoints::dsl::checkpoints
-                .order_by(checkpoints::dsl::sequence_number.desc())
-                .limit(1)
-                .first::<StoredCheckpoint>(conn)
+        let watermark = checkpoint_watermark();
+        self.run_query_async(move |conn| {
+            logged(
+                conn,
+                checkpoints::dsl::checkpoints
+                    .filter(checkpoints::dsl::sequence_number.le(watermark))
+                    .order_by(checkpoints::dsl::sequence_number.desc())
+                    .limit(1),
+            )
+            .first::<StoredCheckpoint>(conn)
         })
         .await
     }
@@ -103,7 +893,9 @@ impl PgManager {
         digest: Option<&str>,
         sequence_number: Option<u64>,
     ) -> Result<Option<StoredCheckpoint>, Error> {
-        let mut query = checkpoints::dsl::checkpoints.into_boxed();
+        let mut query = checkpoints::dsl::checkpoints
+            .filter(checkpoints::dsl::sequence_number.le(checkpoint_watermark()))
+            .into_boxed();
 
         match (digest, sequence_number) {
             (Some(digest), None) => {
@@ -116,7 +908,977 @@ impl PgManager {
             _ => (), // No-op if invalid input
         }
 
-        self.run_query_async(|conn| query.get_result::<StoredCheckpoint>(conn).optional())
+        self.run_query_async(|conn| {
+            logged(conn, query)
+                .get_result::<StoredCheckpoint>(conn)
+                .optional()
+        })
+        .await
+    }
+
+    /// Fetch a page of the checkpoints in epoch `epoch_id`, in sequence number order, along with
+    /// whether there are more checkpoints in the epoch after the page. The cursor of a checkpoint
+    /// is its sequence number, see `checkpoint_cursor`.
+    pub(crate) async fn fetch_checkpoints(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        epoch_id: u64,
+    ) -> Result<(Vec<StoredCheckpoint>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let after = after
+            .map(|c| self.parse_checkpoint_cursor(&c))
+            .transpose()?;
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as i64;
+
+        let mut query = checkpoints::dsl::checkpoints
+            .filter(checkpoints::dsl::epoch.eq(epoch_id as i64))
+            .filter(checkpoints::dsl::sequence_number.le(checkpoint_watermark()))
+            .into_boxed();
+        if let Some(after) = after {
+            query = query.filter(checkpoints::dsl::sequence_number.gt(after));
+        }
+
+        let mut checkpoints = self
+            .run_query_async(move |conn| {
+                logged(
+                    conn,
+                    query
+                        .order_by(checkpoints::dsl::sequence_number.asc())
+                        .limit(limit + 1),
+                )
+                .load::<StoredCheckpoint>(conn)
+            })
+            .await?;
+
+        let has_next_page = checkpoints.len() as i64 > limit;
+        checkpoints.truncate(limit as usize);
+        Ok((checkpoints, has_next_page))
+    }
+
+    /// Fetch up to `limit` checkpoints with sequence numbers from `from` onwards, in sequence
+    /// number order, or only the latest checkpoint if `from` is not given. Used to follow
+    /// checkpoints as they are indexed.
+    pub(crate) async fn fetch_checkpoints_from(
+        &self,
+        from: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<StoredCheckpoint>, Error> {
+        let query = match from {
+            Some(from) => checkpoints::dsl::checkpoints
+                .filter(checkpoints::dsl::sequence_number.ge(from))
+                .order_by(checkpoints::dsl::sequence_number.asc())
+                .limit(limit)
+                .into_boxed(),
+            None => checkpoints::dsl::checkpoints
+                .order_by(checkpoints::dsl::sequence_number.desc())
+                .limit(1)
+                .into_boxed(),
+        };
+
+        self.run_query_async(move |conn| logged(conn, query).load::<StoredCheckpoint>(conn))
+            .await
+    }
+
+    pub(crate) fn checkpoint_cursor(&self, checkpoint: &StoredCheckpoint) -> String {
+        Cursor::new(CursorKind::Checkpoint, checkpoint.sequence_number, &())
+            .encode(&self.cursor_secret)
+    }
+
+    pub(crate) fn parse_checkpoint_cursor(&self, cursor: &str) -> Result<i64, Error> {
+        Ok(Cursor::decode(CursorKind::Checkpoint, cursor, &self.cursor_secret)?.checkpoint)
+    }
+
+    /// Fetch the last checkpoint of epoch `epoch_id`, if the epoch has ended.
+    pub(crate) async fn fetch_end_of_epoch_checkpoint(
+        &self,
+        epoch_id: u64,
+    ) -> Result<Option<StoredCheckpoint>, Error> {
+        let watermark = checkpoint_watermark();
+        self.run_query_async(move |conn| {
+            logged(
+                conn,
+                checkpoints::dsl::checkpoints
+                    .filter(checkpoints::dsl::epoch.eq(epoch_id as i64))
+                    .filter(checkpoints::dsl::end_of_epoch.eq(true))
+                    .filter(checkpoints::dsl::sequence_number.le(watermark)),
+            )
+            .get_result::<StoredCheckpoint>(conn)
+            .optional()
+        })
+        .await
+    }
+
+    /// Fetch the latest checkpoint whose timestamp is at or before `timestamp_ms`.
+    pub(crate) async fn fetch_checkpoint_at_or_before(
+        &self,
+        timestamp_ms: i64,
+    ) -> Result<Option<StoredCheckpoint>, Error> {
+        let checkpoint = self
+            .guarded(
+                self.reader()
+                    .get_checkpoint_at_or_before_timestamp_async(timestamp_ms),
+            )
+            .await?;
+        match checkpoint {
+            Some(checkpoint) if checkpoint.sequence_number > checkpoint_watermark() => {
+                self.fetch_latest_checkpoint().await.map(Some)
+            }
+            checkpoint => Ok(checkpoint),
+        }
+    }
+
+    /// The gas prices of up to `limit` user (programmable) transactions, from the latest
+    /// checkpoints, from checkpoint `start_checkpoint` onwards. Only system transactions have no
+    /// gas price, so filtering on it also limits the query to user transactions.
+    pub(crate) async fn fetch_recent_gas_prices(
+        &self,
+        start_checkpoint: u64,
+        limit: i64,
+    ) -> Result<Vec<i64>, Error> {
+        let watermark = checkpoint_watermark();
+        self.run_query_async(move |conn| {
+            logged(
+                conn,
+                transactions::dsl::transactions
+                    .select(transactions::dsl::gas_price.assume_not_null())
+                    .filter(
+                        transactions::dsl::checkpoint_sequence_number.ge(start_checkpoint as i64),
+                    )
+                    .filter(transactions::dsl::checkpoint_sequence_number.le(watermark))
+                    .filter(transactions::dsl::gas_price.is_not_null())
+                    .order_by(transactions::dsl::checkpoint_sequence_number.desc())
+                    .limit(limit),
+            )
+            .load::<i64>(conn)
+        })
+        .await
+    }
+
+    /// Estimate of the number of live objects, from the statistics Postgres keeps on the objects
+    /// table rather than counting them. Negative if the table has never been analyzed.
+    pub(crate) async fn fetch_live_object_count_estimate(&self) -> Result<i64, Error> {
+        self.run_query_async(|conn| {
+            logged(
+                conn,
+                sql_query(
+                    "SELECT reltuples::bigint AS estimate FROM pg_class WHERE relname = 'objects'",
+                ),
+            )
+            .get_result::<RowEstimate>(conn)
+        })
+        .await
+        .map(|r| r.estimate)
+    }
+
+    pub(crate) async fn fetch_obj(&self, address: Vec<u8>) -> Result<Option<StoredObject>, Error> {
+        self.run_query_async(|conn| {
+            logged(
+                conn,
+                objects::dsl::objects.filter(objects::dsl::object_id.eq(address)),
+            )
+            .get_result::<StoredObject>(conn) // Expect exactly 0 to 1 result
+            .optional()
+        })
+        .await
+    }
+
+    /// Fetch a page of objects matching `filter`, ordered by ID, along with whether there are more
+    /// objects before and after the page. Pages are read backwards from the end when `last` or
+    /// `before` is set. The cursor of an object is its ID, see `object_cursor`.
+    pub(crate) async fn fetch_objs(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<ObjectFilter>,
+    ) -> Result<(Vec<StoredObject>, bool, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+
+        let backward = last.is_some() || before.is_some();
+        let after = after.map(|c| self.parse_object_cursor(&c)).transpose()?;
+        let before = before.map(|c| self.parse_object_cursor(&c)).transpose()?;
+        let limit = first
+            .or(last)
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .min(MAX_PAGE_SIZE) as i64;
+        let filter = filter.unwrap_or_default();
+
+        let mut query = objects::dsl::objects.into_boxed();
+        if let Some(object_id) = after.clone() {
+            query = query.filter(objects::dsl::object_id.gt(object_id));
+        }
+
+        if let Some(object_id) = before.clone() {
+            query = query.filter(objects::dsl::object_id.lt(object_id));
+        }
+
+        if let Some(owner) = filter.owner {
+            query = query.filter(objects::dsl::owner_id.eq(owner.as_slice().to_vec()));
+            // Only objects owned by addresses or other objects have an owner ID. Saying so lets
+            // the query use the index on owners, which is limited to them.
+            if filter.owner_kind.is_none() {
+                query = query.filter(
+                    objects::dsl::owner_type
+                        .between(OwnerType::Address as i16, OwnerType::Object as i16),
+                );
+            }
+        }
+
+        if let Some(kind) = filter.owner_kind {
+            query = query.filter(objects::dsl::owner_type.eq(kind.owner_type() as i16));
+        }
+
+        if let Some(package) = filter.package {
+            let address = AccountAddress::new(package.into_array()).short_str_lossless();
+            let prefix = match &filter.module {
+                Some(module) => format!("0x{address}::{module}::"),
+                None => format!("0x{address}::"),
+            };
+            query = query.filter(
+                objects::dsl::object_type.like(format!("{}%", escape_like_pattern(&prefix))),
+            );
+        } else if filter.module.is_some() {
+            return Err(Error::UnsupportedFilter(
+                "module can only be filtered on along with package".to_string(),
+            ));
+        }
+
+        if let Some(ty) = filter.ty {
+            // Normalize the type to the form it is stored in.
+            let tag =
+                parse_sui_struct_tag(&ty).map_err(|e| Error::InvalidType(format!("{ty}: {e}")))?;
+            let ty = tag.to_string();
+            query = if tag.type_params.is_empty() {
+                let instantiations = format!("{}<%", escape_like_pattern(&ty));
+                query.filter(
+                    objects::dsl::object_type
+                        .eq(ty)
+                        .or(objects::dsl::object_type.like(instantiations)),
+                )
+            } else {
+                query.filter(objects::dsl::object_type.eq(ty))
+            };
+        }
+
+        if let Some(object_ids) = filter.object_ids {
+            query = query.filter(
+                objects::dsl::object_id.eq_any(
+                    object_ids
+                        .into_iter()
+                        .map(|id| id.as_slice().to_vec())
+                        .collect::<Vec<_>>(),
+                ),
+            );
+        }
+
+        if let Some(keys) = filter.object_keys {
+            if keys.len() > MAX_MULTI_GET_SIZE {
+                return Err(Error::MultiGet(format!(
+                    "Requested {} object keys, but at most {MAX_MULTI_GET_SIZE} can be fetched at once",
+                    keys.len()
+                )));
+            }
+
+            let keys = keys
+                .into_iter()
+                .map(|key| {
+                    let version = i64::try_from(key.version).map_err(|_| {
+                        Error::MultiGet(format!(
+                            "Object {} can't be at version {}",
+                            key.object_id, key.version
+                        ))
+                    })?;
+                    Ok((key.object_id.as_slice().to_vec(), version))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            // Only the live version of each object is indexed, so keys for any other version are
+            // rejected, rather than silently matching nothing.
+            let ids: Vec<_> = keys.iter().map(|(id, _)| id.clone()).collect();
+            let live = self
+                .run_query_async(move |conn| {
+                    logged(
+                        conn,
+                        objects::dsl::objects
+                            .select((objects::dsl::object_id, objects::dsl::object_version))
+                            .filter(objects::dsl::object_id.eq_any(ids)),
+                    )
+                    .load::<(Vec<u8>, i64)>(conn)
+                })
+                .await?;
+            check_live_versions(&keys, &live)?;
+
+            let (ids, versions): (Vec<_>, Vec<_>) = keys.into_iter().unzip();
+
+            // The keys are bound as two arrays and joined against as one relation, so that the
+            // query has the same shape, and the same plan, however many keys there are.
+            query = query.filter(
+                sql::<Bool>("(object_id, object_version) IN (SELECT * FROM UNNEST(")
+                    .bind::<Array<Bytea>, _>(ids)
+                    .sql(", ")
+                    .bind::<Array<BigInt>, _>(versions)
+                    .sql("))"),
+            );
+        }
+
+        query = if backward {
+            query.order_by(objects::dsl::object_id.desc())
+        } else {
+            query.order_by(objects::dsl::object_id.asc())
+        };
+
+        let mut objects = self
+            .run_query_async(move |conn| {
+                logged(conn, query.limit(limit + 1)).load::<StoredObject>(conn)
+            })
+            .await?;
+
+        let has_more = objects.len() as i64 > limit;
+        objects.truncate(limit as usize);
+        if backward {
+            objects.reverse();
+            Ok((objects, has_more, before.is_some()))
+        } else {
+            Ok((objects, after.is_some(), has_more))
+        }
+    }
+
+    pub(crate) fn object_cursor(&self, object: &StoredObject) -> String {
+        Cursor::new(
+            CursorKind::Object,
+            object.checkpoint_sequence_number,
+            &object.object_id,
+        )
+        .encode(&self.cursor_secret)
+    }
+
+    /// Objects are placed by their IDs alone, the checkpoint in their cursors is informational.
+    pub(crate) fn parse_object_cursor(&self, cursor: &str) -> Result<Vec<u8>, Error> {
+        Cursor::decode(CursorKind::Object, cursor, &self.cursor_secret)?.key()
+    }
+
+    pub(crate) async fn fetch_address_object_stats(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Option<StoredAddressObjectStats>, Error> {
+        let address = NativeSuiAddress::from_bytes(address.into_array()).map_err(|e| {
+            Error::Internal(format!("Can't convert address into SuiAddress. Error: {e}"))
+        })?;
+        self.guarded(self.reader().get_address_object_stats_async(address))
             .await
     }
+
+    /// Fetch the `first` packages (up to `MAX_PAGE_SIZE`) with the most activity by `order_by`
+    /// over `period`.
+    pub(crate) async fn fetch_package_leaderboard(
+        &self,
+        period: ActivityPeriod,
+        order_by: PackageActivityMetric,
+        first: Option<u64>,
+    ) -> Result<PackageLeaderboard, Error> {
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+        self.guarded(self.reader().get_package_leaderboard_async(
+            period.into(),
+            order_by.into(),
+            limit,
+        ))
+        .await
+    }
+
+    pub(crate) async fn multi_get_objs(
+        &self,
+        addresses: Vec<Vec<u8>>,
+    ) -> Result<Vec<StoredObject>, Error> {
+        self.run_query_async(|conn| {
+            logged(
+                conn,
+                objects::dsl::objects.filter(objects::dsl::object_id.eq_any(addresses)),
+            )
+            .load::<StoredObject>(conn)
+        })
+        .await
+    }
+
+    /// Fetch a live object whose type is exactly `object_type` (in the form it is stored in, see
+    /// `fetch_objs`), for types that only ever have one instance, such as a coin's `CoinMetadata`
+    /// or `TreasuryCap`. If there is more than one, the one with the lowest ID is returned.
+    pub(crate) async fn fetch_obj_by_type(
+        &self,
+        object_type: String,
+    ) -> Result<Option<StoredObject>, Error> {
+        self.run_query_async(|conn| {
+            logged(
+                conn,
+                objects::dsl::objects
+                    .filter(objects::dsl::object_type.eq(object_type))
+                    .order_by(objects::dsl::object_id.asc())
+                    .limit(1),
+            )
+            .get_result::<StoredObject>(conn)
+            .optional()
+        })
+        .await
+    }
+
+    /// Fetch a page of the fields holding the items placed in the kiosk with UID `kiosk_id`, in
+    /// the order of their IDs, along with whether there are more items after the page. Items are
+    /// the only dynamic object fields of a kiosk. The cursor of an item is the ID of its field,
+    /// see `kiosk_item_cursor`.
+    pub(crate) async fn fetch_kiosk_items(
+        &self,
+        kiosk_id: Vec<u8>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<(Vec<StoredObject>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let after = after
+            .map(|c| self.parse_kiosk_item_cursor(&c))
+            .transpose()?;
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as i64;
+
+        let mut query = objects::dsl::objects
+            .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16))
+            .filter(objects::dsl::owner_id.eq(kiosk_id))
+            // Dynamic object fields, as opposed to dynamic fields (0).
+            .filter(objects::dsl::df_kind.eq(1))
+            .into_boxed();
+        if let Some(after) = after {
+            query = query.filter(objects::dsl::object_id.gt(after));
+        }
+
+        let mut fields = self
+            .run_query_async(move |conn| {
+                logged(
+                    conn,
+                    query
+                        .order_by(objects::dsl::object_id.asc())
+                        .limit(limit + 1),
+                )
+                .load::<StoredObject>(conn)
+            })
+            .await?;
+
+        let has_next_page = fields.len() as i64 > limit;
+        fields.truncate(limit as usize);
+        Ok((fields, has_next_page))
+    }
+
+    pub(crate) fn kiosk_item_cursor(&self, field: &StoredObject) -> String {
+        Cursor::new(
+            CursorKind::KioskItem,
+            field.checkpoint_sequence_number,
+            &field.object_id,
+        )
+        .encode(&self.cursor_secret)
+    }
+
+    /// Items are placed by the IDs of their fields alone, the checkpoint in their cursors is
+    /// informational.
+    pub(crate) fn parse_kiosk_item_cursor(&self, cursor: &str) -> Result<Vec<u8>, Error> {
+        Cursor::decode(CursorKind::KioskItem, cursor, &self.cursor_secret)?.key()
+    }
+
+    /// Fetch a page of the exchange rates of a staking pool, most recent epoch first, along with
+    /// whether there are more exchange rates after the page. The cursor of an exchange rate is its
+    /// epoch, see `exchange_rate_cursor`.
+    pub(crate) async fn fetch_exchange_rates(
+        &self,
+        exchange_rates_id: SuiAddress,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<(Vec<(u64, PoolTokenExchangeRate)>, bool), Error> {
+        if before.is_some() && after.is_some() {
+            return Err(Error::CursorNoBeforeAfter);
+        }
+        if first.is_some() && last.is_some() {
+            return Err(Error::CursorNoFirstLast);
+        }
+        if before.is_some() || last.is_some() {
+            return Err(Error::CursorNoReversePagination);
+        }
+
+        let after = after
+            .map(|c| self.parse_exchange_rate_cursor(&c))
+            .transpose()?;
+        let limit = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+
+        let mut rates = self.fetch_all_exchange_rates(exchange_rates_id).await?;
+        if let Some(after) = after {
+            rates.retain(|(epoch, _)| *epoch < after);
+        }
+        let has_next_page = rates.len() > limit;
+        rates.truncate(limit);
+        Ok((rates, has_next_page))
+    }
+
+    pub(crate) fn exchange_rate_cursor(&self, epoch: u64) -> String {
+        // Exchange rates are not indexed per checkpoint, so they are placed by epoch alone.
+        Cursor::new(CursorKind::ExchangeRate, 0, &epoch).encode(&self.cursor_secret)
+    }
+
+    pub(crate) fn parse_exchange_rate_cursor(&self, cursor: &str) -> Result<u64, Error> {
+        Cursor::decode(CursorKind::ExchangeRate, cursor, &self.cursor_secret)?.key()
+    }
+
+    /// Fetch all the exchange rates of a staking pool, most recent epoch first. They are the
+    /// dynamic fields of the pool's `exchange_rates` table, which has ID `exchange_rates_id`.
+    pub(crate) async fn fetch_all_exchange_rates(
+        &self,
+        exchange_rates_id: SuiAddress,
+    ) -> Result<Vec<(u64, PoolTokenExchangeRate)>, Error> {
+        let exchange_rates_id = exchange_rates_id.as_slice().to_vec();
+        let fields = self
+            .run_query_async(|conn| {
+                logged(
+                    conn,
+                    objects::dsl::objects
+                        .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16))
+                        .filter(objects::dsl::owner_id.eq(exchange_rates_id))
+                        .filter(objects::dsl::df_kind.is_not_null()),
+                )
+                .load::<StoredObject>(conn)
+            })
+            .await?;
+
+        exchange_rates_from_fields(fields)
+    }
+
+    /// Fetch the exchange rates of a staking pool for the epochs in `epochs`, most recent epoch
+    /// first. Epochs without an exchange rate are skipped. Only the dynamic fields for those
+    /// epochs are read, looked up by their IDs, which are derived from the epochs.
+    pub(crate) async fn fetch_exchange_rates_for_epochs(
+        &self,
+        exchange_rates_id: SuiAddress,
+        epochs: RangeInclusive<u64>,
+    ) -> Result<Vec<(u64, PoolTokenExchangeRate)>, Error> {
+        let parent = NativeSuiAddress::from_bytes(exchange_rates_id.into_array()).map_err(|e| {
+            Error::Internal(format!("Can't convert address into SuiAddress. Error: {e}"))
+        })?;
+        let field_ids = epochs
+            .map(|epoch| {
+                let key = bcs::to_bytes(&epoch).expect("Serializing a u64 can't fail");
+                derive_dynamic_field_id(parent, &TypeTag::U64, &key)
+                    .map(|id| id.to_vec())
+                    .map_err(|e| Error::Internal(format!("Can't derive exchange rate ID: {e}")))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let fields = self
+            .run_query_async(|conn| {
+                logged(
+                    conn,
+                    objects::dsl::objects.filter(objects::dsl::object_id.eq_any(field_ids)),
+                )
+                .load::<StoredObject>(conn)
+            })
+            .await?;
+
+        exchange_rates_from_fields(fields)
+    }
+
+    /// Fetch the live transfer policies whose creation was announced by an event of type
+    /// `created_event_type` (a `0x2::transfer_policy::TransferPolicyCreated<T>`). The event's
+    /// only field is the policy's ID, so its BCS bytes double as the policy's object ID.
+    pub(crate) async fn fetch_transfer_policies(
+        &self,
+        created_event_type: String,
+    ) -> Result<Vec<StoredObject>, Error> {
+        self.run_query_async(|conn| {
+            logged(
+                conn,
+                objects::dsl::objects
+                    .filter(
+                        objects::dsl::object_id.eq_any(
+                            events::dsl::events
+                                .select(events::dsl::bcs)
+                                .filter(events::dsl::event_type.eq(created_event_type)),
+                        ),
+                    )
+                    .order_by(objects::dsl::object_id.asc()),
+            )
+            .load::<StoredObject>(conn)
+        })
+        .await
+    }
+}
+
+/// Decodes the `Field<u64, PoolTokenExchangeRate>` dynamic fields of a staking pool's exchange
+/// rates table, most recent epoch first.
+fn exchange_rates_from_fields(
+    fields: Vec<StoredObject>,
+) -> Result<Vec<(u64, PoolTokenExchangeRate)>, Error> {
+    let mut rates = fields
+        .into_iter()
+        .map(|stored| {
+            let object = NativeObject::try_from(stored)
+                .map_err(|e| Error::Internal(format!("Can't deserialize object: {e}")))?;
+            let contents = object
+                .data
+                .try_as_move()
+                .ok_or_else(|| Error::Internal("Exchange rate is not a Move object".into()))?
+                .contents();
+            let field: Field<u64, PoolTokenExchangeRate> = bcs::from_bytes(contents)
+                .map_err(|e| Error::Internal(format!("Can't deserialize exchange rate: {e}")))?;
+            Ok((field.name, field.value))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    rates.sort_by(|(a, _), (b, _)| b.cmp(a));
+    Ok(rates)
+}
+
+/// Query for the page of transactions matching `filter`, with sequence numbers greater than
+/// `after_tx`, from checkpoints between `lo` and `hi` inclusive, fetching one more than `limit`
+/// to detect whether there is a next page.
+///
+/// All the conditions on `tx_indices` are combined in a single sub-query, along with the bounds
+/// on checkpoints and sequence numbers, so that Postgres can combine the indices on its array
+/// columns with each other and with the index on its checkpoints (e.g. a sender along with a
+/// function, which has a composite index), rather than scan for the matches of each condition
+/// separately. When there are no other conditions on `transactions`, the sub-query is limited to
+/// the page too, so that it stops at the first matches, in order.
+fn tx_query(
+    filter: &TransactionBlockFilter,
+    after_tx: Option<i64>,
+    lo: Option<i64>,
+    hi: Option<i64>,
+    limit: i64,
+) -> transactions::BoxedQuery<'static, Pg> {
+    let mut query = transactions::dsl::transactions.into_boxed();
+    let mut indices = tx_indices::dsl::tx_indices
+        .select(tx_indices::dsl::tx_sequence_number)
+        .into_boxed();
+    let mut filters_indices = false;
+
+    if let Some(tx) = after_tx {
+        query = query.filter(transactions::dsl::tx_sequence_number.gt(tx));
+        indices = indices.filter(tx_indices::dsl::tx_sequence_number.gt(tx));
+    }
+
+    if let Some(lo) = lo {
+        query = query.filter(transactions::dsl::checkpoint_sequence_number.ge(lo));
+        indices = indices.filter(tx_indices::dsl::checkpoint_sequence_number.ge(lo));
+    }
+
+    if let Some(hi) = hi {
+        query = query.filter(transactions::dsl::checkpoint_sequence_number.le(hi));
+        indices = indices.filter(tx_indices::dsl::checkpoint_sequence_number.le(hi));
+    }
+
+    if let Some(kind) = filter.kind {
+        let kind = match kind {
+            TransactionBlockKindInput::SystemTx => TransactionKind::SystemTransaction,
+            TransactionBlockKindInput::ProgrammableTx => TransactionKind::ProgrammableTransaction,
+        };
+        query = query.filter(transactions::dsl::transaction_kind.eq(kind as i16));
+    }
+
+    // Transactions signed by an address are also the ones that send objects from it.
+    for address in [filter.sign_address, filter.sent_address]
+        .into_iter()
+        .flatten()
+    {
+        filters_indices = true;
+        indices = indices
+            .filter(tx_indices::dsl::senders.contains(vec![Some(address.as_slice().to_vec())]));
+    }
+
+    if let Some(address) = filter.recv_address {
+        filters_indices = true;
+        indices = indices
+            .filter(tx_indices::dsl::recipients.contains(vec![Some(address.as_slice().to_vec())]));
+    }
+
+    if let Some(address) = filter.paid_address {
+        filters_indices = true;
+        indices = indices
+            .filter(tx_indices::dsl::payers.contains(vec![Some(address.as_slice().to_vec())]));
+    }
+
+    if let Some(address) = filter.affected_address {
+        filters_indices = true;
+        let address = vec![Some(address.as_slice().to_vec())];
+        indices = indices.filter(
+            tx_indices::dsl::senders
+                .contains(address.clone())
+                .or(tx_indices::dsl::recipients.contains(address.clone()))
+                .or(tx_indices::dsl::payers.contains(address)),
+        );
+    }
+
+    // Move calls are indexed by package, by module (`package::module`) and by function
+    // (`package::module::function`), with packages formatted as full length hex addresses.
+    if let Some(package) = filter.package {
+        filters_indices = true;
+        let package = ObjectID::new(package.into_array());
+        indices = match (&filter.module, &filter.function) {
+            (Some(module), Some(function)) => indices.filter(
+                tx_indices::dsl::package_module_functions
+                    .contains(vec![Some(format!("{package}::{module}::{function}"))]),
+            ),
+            (Some(module), None) => indices.filter(
+                tx_indices::dsl::package_modules
+                    .contains(vec![Some(format!("{package}::{module}"))]),
+            ),
+            (None, _) => {
+                indices.filter(tx_indices::dsl::packages.contains(vec![Some(package.to_vec())]))
+            }
+        };
+    }
+
+    if let Some(object) = filter.input_object {
+        filters_indices = true;
+        indices = indices.filter(
+            tx_indices::dsl::input_objects.contains(vec![Some(object.as_slice().to_vec())]),
+        );
+    }
+
+    if let Some(object) = filter.changed_object {
+        filters_indices = true;
+        indices = indices.filter(
+            tx_indices::dsl::changed_objects.contains(vec![Some(object.as_slice().to_vec())]),
+        );
+    }
+
+    if filters_indices {
+        if filter.kind.is_none() {
+            indices = indices
+                .order_by(tx_indices::dsl::tx_sequence_number.asc())
+                .limit(limit + 1);
+        }
+        query = query.filter(transactions::dsl::tx_sequence_number.eq_any(indices));
+    }
+
+    query
+        .order_by(transactions::dsl::tx_sequence_number.asc())
+        .limit(limit + 1)
+}
+
+/// Fails if any of the `(object ID, version)` `keys` is for an object that is `live` at another
+/// version. Keys for objects that are not live at all (deleted, wrapped, or never created) are
+/// allowed, and match nothing.
+fn check_live_versions(keys: &[(Vec<u8>, i64)], live: &[(Vec<u8>, i64)]) -> Result<(), Error> {
+    let live: HashMap<_, _> = live.iter().map(|(id, v)| (id.as_slice(), *v)).collect();
+    for (id, version) in keys {
+        match live.get(id.as_slice()) {
+            Some(live_version) if live_version != version => {
+                return Err(Error::MultiGet(format!(
+                    "Object 0x{} is at version {live_version}, not {version}, and only the live \
+                     version of an object can be fetched",
+                    hex::encode(id)
+                )));
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::debug_query;
+
+    fn sql(filter: &TransactionBlockFilter, lo: Option<i64>, hi: Option<i64>) -> String {
+        debug_query::<Pg, _>(&tx_query(filter, Some(7), lo, hi, 10)).to_string()
+    }
+
+    /// A filter on the sender, along with the function called, from a range of checkpoints.
+    fn sender_and_function() -> TransactionBlockFilter {
+        TransactionBlockFilter {
+            sent_address: Some(SuiAddress::from_array([1; 32])),
+            package: Some(SuiAddress::from_array([2; 32])),
+            module: Some("m".to_string()),
+            function: Some("f".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tx_indices_in_one_sub_query() {
+        let sql = sql(&sender_and_function(), Some(3), Some(5));
+
+        // Both conditions are answered by the same scan of `tx_indices`, which is bounded by
+        // checkpoint and cursor, and stops at the end of the page.
+        assert_eq!(sql.matches("FROM \"tx_indices\"").count(), 1, "{sql}");
+        let (outer, inner) = sql.split_once("FROM \"tx_indices\"").unwrap();
+        for condition in [
+            "\"tx_indices\".\"senders\" @>",
+            "\"tx_indices\".\"package_module_functions\" @>",
+            "\"tx_indices\".\"checkpoint_sequence_number\" >=",
+            "\"tx_indices\".\"checkpoint_sequence_number\" <=",
+            "\"tx_indices\".\"tx_sequence_number\" >",
+        ] {
+            assert!(inner.contains(condition), "{condition} missing from {sql}");
+        }
+        assert_eq!(sql.matches("LIMIT").count(), 2, "{sql}");
+
+        // The bounds are also applied to `transactions` directly, to use its own indices.
+        assert!(
+            outer.contains("\"transactions\".\"checkpoint_sequence_number\" >="),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_kind_not_limited_in_sub_query() {
+        let filter = TransactionBlockFilter {
+            kind: Some(TransactionBlockKindInput::ProgrammableTx),
+            ..sender_and_function()
+        };
+
+        // The page can only be cut once the kind of transaction is known.
+        let sql = sql(&filter, None, None);
+        assert_eq!(sql.matches("FROM \"tx_indices\"").count(), 1, "{sql}");
+        assert_eq!(sql.matches("LIMIT").count(), 1, "{sql}");
+    }
+
+    #[test]
+    fn test_affected_address() {
+        let filter = TransactionBlockFilter {
+            affected_address: Some(SuiAddress::from_array([1; 32])),
+            ..Default::default()
+        };
+
+        // Transactions the address sent, received objects in, or paid for are all matched by the
+        // same scan of `tx_indices`.
+        let sql = sql(&filter, None, None);
+        assert_eq!(sql.matches("FROM \"tx_indices\"").count(), 1, "{sql}");
+        let (_, inner) = sql.split_once("FROM \"tx_indices\"").unwrap();
+        for condition in [
+            "((\"tx_indices\".\"senders\" @>",
+            " OR \"tx_indices\".\"recipients\" @>",
+            ") OR \"tx_indices\".\"payers\" @>",
+        ] {
+            assert!(inner.contains(condition), "{condition} missing from {sql}");
+        }
+    }
+
+    #[test]
+    fn test_no_sub_query_without_tx_indices() {
+        let filter = TransactionBlockFilter {
+            kind: Some(TransactionBlockKindInput::SystemTx),
+            ..Default::default()
+        };
+
+        let sql = sql(&filter, Some(3), None);
+        assert!(!sql.contains("tx_indices"), "{sql}");
+    }
+
+    #[test]
+    fn test_check_live_versions() {
+        let live = vec![(vec![1; 32], 5), (vec![2; 32], 7)];
+
+        // Live versions, and objects that aren't live at all, are accepted.
+        check_live_versions(&[(vec![1; 32], 5), (vec![3; 32], 1)], &live).unwrap();
+        check_live_versions(&[], &live).unwrap();
+
+        // Older and newer versions of live objects are rejected.
+        for version in [4, 8] {
+            let err = check_live_versions(&[(vec![1; 32], 5), (vec![2; 32], version)], &live)
+                .unwrap_err();
+            assert!(
+                matches!(&err, Error::MultiGet(msg) if msg.contains("is at version 7")),
+                "{err}"
+            );
+        }
+    }
+
+    #[cfg(feature = "pg_integration")]
+    mod explain {
+        use diesel::{
+            query_builder::{AstPass, Query, QueryFragment, QueryId},
+            QueryResult,
+        };
+        use sui_indexer::{get_pg_pool_connection, new_pg_connection_pool, utils::reset_database};
+
+        use super::*;
+
+        /// The plan Postgres chooses for the query it wraps, one line per row.
+        #[derive(QueryId)]
+        struct Explain<Q>(Q);
+
+        impl<Q: QueryFragment<Pg>> QueryFragment<Pg> for Explain<Q> {
+            fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+                out.push_sql("EXPLAIN ");
+                self.0.walk_ast(out.reborrow())
+            }
+        }
+
+        impl<Q> Query for Explain<Q> {
+            type SqlType = Text;
+        }
+
+        impl<Q> RunQueryDsl<PgConnection> for Explain<Q> {}
+
+        fn plan(filter: &TransactionBlockFilter) -> String {
+            let pg_host = std::env::var("POSTGRES_HOST").unwrap_or_else(|_| "localhost".into());
+            let pg_port = std::env::var("POSTGRES_PORT").unwrap_or_else(|_| "32770".into());
+            let pw = std::env::var("POSTGRES_PASSWORD").unwrap_or_else(|_| "postgrespw".into());
+            let db_url = format!("postgres://postgres:{pw}@{pg_host}:{pg_port}");
+            let pool = new_pg_connection_pool(&db_url).unwrap();
+            let mut conn = get_pg_pool_connection(&pool).unwrap();
+            reset_database(&mut conn, true, true).unwrap();
+
+            // The tables are empty, so scanning them in full would otherwise always be cheapest.
+            sql_query("SET enable_seqscan = off")
+                .execute(&mut conn)
+                .unwrap();
+            Explain(tx_query(filter, Some(7), Some(3), Some(5), 10))
+                .load::<String>(&mut *conn)
+                .unwrap()
+                .join("\n")
+        }
+
+        #[test]
+        fn test_tx_indices_filters_use_indices() {
+            // With sequential scans disabled, a table is only scanned in full if none of its
+            // indices answer the query's conditions.
+            let plan = plan(&sender_and_function());
+            assert!(!plan.contains("Seq Scan"), "{plan}");
+            assert!(plan.contains("tx_indices_"), "{plan}");
+        }
+
+        #[test]
+        fn test_kind_filter_uses_indices() {
+            let filter = TransactionBlockFilter {
+                kind: Some(TransactionBlockKindInput::ProgrammableTx),
+                ..sender_and_function()
+            };
+
+            let plan = plan(&filter);
+            assert!(!plan.contains("Seq Scan"), "{plan}");
+        }
+    }
 }
@@ -1,7 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod checkpoint_watcher;
+pub(crate) mod circuit_breaker;
 pub(crate) mod context_ext;
+pub(crate) mod cursor;
 pub(crate) mod data_provider;
 pub(crate) mod db_data_provider;
+pub(crate) mod query_log;
+pub(crate) mod read_router;
 pub(crate) mod sui_sdk_data_provider;
@@ -0,0 +1,258 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    cell::RefCell,
+    fmt::Write,
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use diesel::{
+    pg::{Pg, PgQueryBuilder},
+    query_builder::{AstPass, Query, QueryBuilder, QueryFragment, QueryId},
+    sql_types::Text,
+    Connection, PgConnection, QueryResult, RunQueryDsl,
+};
+use tracing::{info, warn};
+
+use crate::config::QueryLoggingConfig;
+
+tokio::task_local! {
+    /// Name of the GraphQL operation that the current task is executing.
+    static OPERATION_NAME: Option<String>;
+
+    /// The field (as `Type.field`) that the current task is resolving, which the database queries
+    /// it makes are attributed to.
+    static RESOLVER: String;
+}
+
+thread_local! {
+    /// The sampled query running on this thread, if any.
+    static SAMPLE: RefCell<Option<QuerySample>> = RefCell::new(None);
+}
+
+/// Decides which database queries to log, according to `QueryLoggingConfig`. A query is a call to
+/// `PgManager::run_query_async`, which may run several statements. Statements are only logged if
+/// they go through `logged`. Queries are recorded if they are sampled, or if slow queries are
+/// logged, as it is only known whether a query is slow once it has run.
+///
+/// Statements are logged without the values bound to them, which may come from user input.
+pub(crate) struct QueryLogger {
+    config: QueryLoggingConfig,
+    queries: AtomicU64,
+}
+
+pub(crate) struct QuerySample {
+    operation: Option<String>,
+    resolver: Option<String>,
+    /// Whether the query is logged however long it takes.
+    sampled: bool,
+    explain: bool,
+    /// The query is logged, without the plans of its statements, if it takes at least this long.
+    slow_after: Option<Duration>,
+    /// The SQL of each statement, and its plan, if it was explained.
+    statements: Vec<(String, String)>,
+}
+
+/// `EXPLAIN` of a statement, which returns its plan as lines of text.
+struct Explain<Q>(Q);
+
+/// Runs `fut` with `operation` as the name of the GraphQL operation that queries it makes are
+/// logged under.
+pub(crate) async fn with_operation_name<F: Future>(operation: Option<String>, fut: F) -> F::Output {
+    OPERATION_NAME.scope(operation, fut).await
+}
+
+/// Runs `fut` with `resolver` as the field that the database queries it makes are attributed to, in
+/// logs and metrics.
+pub(crate) async fn with_resolver<F: Future>(resolver: String, fut: F) -> F::Output {
+    RESOLVER.scope(resolver, fut).await
+}
+
+/// The field that database queries made by the current task are attributed to, if any.
+pub(crate) fn current_resolver() -> Option<String> {
+    RESOLVER.try_with(Clone::clone).ok()
+}
+
+/// Records the SQL of `statement`, but not the values bound to it, as part of the query running on
+/// this thread if it was sampled, along with its plan if configured to. Returns `statement` to be
+/// executed.
+pub(crate) fn logged<Q: QueryFragment<Pg>>(conn: &mut PgConnection, statement: Q) -> Q {
+    SAMPLE.with(|sample| {
+        let mut sample = sample.borrow_mut();
+        let Some(sample) = sample.as_mut() else {
+            return;
+        };
+
+        let mut builder = PgQueryBuilder::default();
+        let sql = match statement.to_sql(&mut builder, &Pg) {
+            Ok(()) => builder.finish(),
+            Err(e) => format!("Failed to build statement: {e}"),
+        };
+
+        let mut explained = String::new();
+        if sample.explain {
+            // In a savepoint, so that a failure does not abort the query's transaction.
+            match conn.transaction(|conn| Explain(&statement).load::<String>(conn)) {
+                Ok(plan) => {
+                    for line in plan {
+                        write!(explained, "\n  {}", redacted(&line)).unwrap();
+                    }
+                }
+                Err(e) => write!(explained, "\n  Failed to explain statement: {e}").unwrap(),
+            }
+        }
+        sample.statements.push((sql, explained));
+    });
+    statement
+}
+
+impl QueryLogger {
+    pub(crate) fn new(config: QueryLoggingConfig) -> Self {
+        Self {
+            config,
+            queries: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether the next query should be recorded to be logged, and if so what to log it with.
+    pub(crate) fn sample(&self) -> Option<QuerySample> {
+        let one_in = self.config.sample_one_in as u64;
+        let sampled = one_in != 0 && self.queries.fetch_add(1, Ordering::Relaxed) % one_in == 0;
+        let slow_after = self
+            .config
+            .slow_query_threshold_ms
+            .map(Duration::from_millis);
+        if !sampled && slow_after.is_none() {
+            return None;
+        }
+
+        Some(QuerySample {
+            operation: OPERATION_NAME.try_with(Clone::clone).ok().flatten(),
+            resolver: current_resolver(),
+            sampled,
+            explain: sampled && self.config.explain,
+            slow_after,
+            statements: vec![],
+        })
+    }
+}
+
+impl QuerySample {
+    /// Runs `query`, and logs how long it took along with the statements it ran through `logged`,
+    /// if it was sampled or turned out to be slow.
+    pub(crate) fn run<T, E, F>(self, conn: &mut PgConnection, query: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut PgConnection) -> Result<T, E>,
+        E: std::fmt::Display,
+    {
+        SAMPLE.with(|sample| *sample.borrow_mut() = Some(self));
+        let start = Instant::now();
+        let result = query(conn);
+        let elapsed = start.elapsed();
+        let elapsed_ms = elapsed.as_millis() as u64;
+
+        let Some(sample) = SAMPLE.with(|sample| sample.borrow_mut().take()) else {
+            return result;
+        };
+        let operation = sample.operation.as_deref().unwrap_or("<anonymous>");
+        let resolver = sample.resolver.as_deref().unwrap_or("<none>");
+        let outcome = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        if sample.sampled {
+            let statements: Vec<_> = sample
+                .statements
+                .iter()
+                .map(|(sql, plan)| format!("{sql}{plan}"))
+                .collect();
+            info!(
+                operation,
+                resolver,
+                elapsed_ms,
+                outcome,
+                "[DB query] {}",
+                statements.join("\n")
+            );
+        }
+
+        if sample
+            .slow_after
+            .is_some_and(|slow_after| elapsed >= slow_after)
+        {
+            let statements: Vec<_> = sample.statements.iter().map(|(sql, _)| sql).collect();
+            warn!(
+                operation,
+                resolver,
+                elapsed_ms,
+                outcome,
+                "[Slow DB query] {}",
+                statements.join("\n")
+            );
+        }
+
+        result
+    }
+}
+
+/// `line` of a statement's plan, with its string literals replaced by `'?'`. Plans are made with
+/// the values bound to the statement, and include them as literals, e.g. in filter conditions.
+fn redacted(line: &str) -> String {
+    let mut redacted = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\'' {
+            redacted.push(c);
+            continue;
+        }
+
+        // Skip to the closing quote, where a quote inside the literal is escaped by doubling it.
+        redacted.push_str("'?'");
+        while let Some(c) = chars.next() {
+            if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                break;
+            }
+        }
+    }
+    redacted
+}
+
+impl<Q: QueryFragment<Pg>> QueryFragment<Pg> for Explain<Q> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("EXPLAIN ");
+        self.0.walk_ast(out.reborrow())
+    }
+}
+
+impl<Q> QueryId for Explain<Q> {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<Q> Query for Explain<Q> {
+    type SqlType = Text;
+}
+
+impl<Q> RunQueryDsl<PgConnection> for Explain<Q> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted() {
+        assert_eq!(
+            redacted("Index Cond: (object_id = '\\x0102'::bytea)"),
+            "Index Cond: (object_id = '?'::bytea)",
+        );
+        assert_eq!(
+            redacted("Filter: ((a = 'it''s'::text) OR (b = ''::text)) AND (c > 5)"),
+            "Filter: ((a = '?'::text) OR (b = '?'::text)) AND (c > 5)",
+        );
+        assert_eq!(redacted("Seq Scan on objects"), "Seq Scan on objects");
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use diesel::{dsl::max, QueryDsl, RunQueryDsl};
+use sui_indexer::{errors::IndexerError, indexer_reader::IndexerReader, schema_v2::checkpoints};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Replicas more than this many checkpoints behind the primary are not read from.
+const DEFAULT_MAX_LAG_CHECKPOINTS: i64 = 10;
+/// How often the lag of each replica is checked.
+const DEFAULT_LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lag of a replica whose watermark is not known, because it has not been checked yet, or could
+/// not be read.
+const UNKNOWN_LAG: i64 = i64::MAX;
+
+/// Watermark of a database that has not been checked yet, or could not be read.
+const UNKNOWN_WATERMARK: i64 = i64::MIN;
+
+#[derive(Clone, Debug, Copy)]
+pub(crate) struct ReadRouterConfig {
+    pub max_lag_checkpoints: i64,
+    pub lag_check_interval: Duration,
+}
+
+/// Spreads reads across read replicas of the indexer's database, round-robin, falling back to the
+/// primary while none of them are available. Each request picks a database once, and sends all of
+/// its queries to it (see `ConsistentRead`), so it is requests, rather than the queries within
+/// one, that are spread across replicas.
+///
+/// A replica's lag is measured against the primary using the latest checkpoint in its
+/// `checkpoints` table, which the indexer writes after everything else in the checkpoint, so it
+/// doubles as a watermark of how much data a replica has. Replicas that fall too far behind, or
+/// that can't be reached, stop being read from until they catch up again. Watermarks are only
+/// checked while `spawn_watermark_monitor` is running, and until they are first checked, replicas
+/// are not read from.
+pub(crate) struct ReadRouter {
+    config: ReadRouterConfig,
+    primary: IndexerReader,
+    /// Latest watermark read from the primary.
+    primary_watermark: Arc<AtomicI64>,
+    replicas: Arc<Vec<Replica>>,
+    next: AtomicUsize,
+}
+
+struct Replica {
+    /// Position of the replica in the list of replicas, to identify it in logs without exposing
+    /// its credentials.
+    index: usize,
+    reader: IndexerReader,
+    /// Latest watermark read from the replica.
+    watermark: AtomicI64,
+    /// Number of checkpoints that the replica is behind the primary by.
+    lag: AtomicI64,
+}
+
+impl Default for ReadRouterConfig {
+    fn default() -> Self {
+        Self {
+            max_lag_checkpoints: DEFAULT_MAX_LAG_CHECKPOINTS,
+            lag_check_interval: DEFAULT_LAG_CHECK_INTERVAL,
+        }
+    }
+}
+
+impl ReadRouter {
+    pub(crate) fn new(
+        config: ReadRouterConfig,
+        primary: IndexerReader,
+        replicas: Vec<IndexerReader>,
+    ) -> Self {
+        let replicas = replicas
+            .into_iter()
+            .enumerate()
+            .map(|(index, reader)| Replica {
+                index,
+                reader,
+                watermark: AtomicI64::new(UNKNOWN_WATERMARK),
+                lag: AtomicI64::new(UNKNOWN_LAG),
+            })
+            .collect();
+        Self {
+            config,
+            primary,
+            primary_watermark: Arc::new(AtomicI64::new(UNKNOWN_WATERMARK)),
+            replicas: Arc::new(replicas),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn primary(&self) -> &IndexerReader {
+        &self.primary
+    }
+
+    /// Every database that is read from, named for logs and metrics: the primary, then each
+    /// replica.
+    pub(crate) fn readers(&self) -> Vec<(String, IndexerReader)> {
+        let replicas = self
+            .replicas
+            .iter()
+            .map(|r| (format!("replica-{}", r.index), r.reader.clone()));
+        std::iter::once(("primary".to_string(), self.primary.clone()))
+            .chain(replicas)
+            .collect()
+    }
+
+    /// The reader to send the next query to: the next available replica, or the primary if none
+    /// are available.
+    pub(crate) fn reader(&self) -> &IndexerReader {
+        self.reader_with_watermark().0
+    }
+
+    /// The reader to send the next query to, as in `reader`, along with the latest watermark read
+    /// from it, if it has been read yet. The watermark is at most `lag_check_interval` old, so
+    /// the database is known to contain every checkpoint up to and including it.
+    pub(crate) fn reader_with_watermark(&self) -> (&IndexerReader, Option<i64>) {
+        let known = |watermark: &AtomicI64| {
+            Some(watermark.load(Ordering::Relaxed)).filter(|w| *w != UNKNOWN_WATERMARK)
+        };
+
+        let n = self.replicas.len();
+        let start = if n == 0 {
+            0
+        } else {
+            self.next.fetch_add(1, Ordering::Relaxed)
+        };
+        (0..n)
+            .map(|i| &self.replicas[(start + i) % n])
+            .find(|r| r.lag.load(Ordering::Relaxed) <= self.config.max_lag_checkpoints)
+            .map_or_else(
+                || (&self.primary, known(&self.primary_watermark)),
+                |r| (&r.reader, known(&r.watermark)),
+            )
+    }
+
+    /// Checks the watermark of the primary, and the watermark and lag of every replica, every
+    /// `lag_check_interval`, for as long as the returned task is running.
+    pub(crate) fn spawn_watermark_monitor(&self) -> JoinHandle<()> {
+        let config = self.config;
+        let primary = self.primary.clone();
+        let primary_watermark = self.primary_watermark.clone();
+        let replicas = self.replicas.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.lag_check_interval);
+            loop {
+                interval.tick().await;
+                let watermark = match watermark(&primary).await {
+                    Ok(watermark) => watermark,
+                    Err(e) => {
+                        warn!("Failed to read watermark of primary database: {e}");
+                        primary_watermark.store(UNKNOWN_WATERMARK, Ordering::Relaxed);
+                        continue;
+                    }
+                };
+                primary_watermark.store(watermark, Ordering::Relaxed);
+                for replica in replicas.iter() {
+                    replica.update_lag(watermark, &config).await;
+                }
+            }
+        })
+    }
+}
+
+impl Replica {
+    async fn update_lag(&self, primary_watermark: i64, config: &ReadRouterConfig) {
+        let lag = match watermark(&self.reader).await {
+            Ok(watermark) => {
+                self.watermark.store(watermark, Ordering::Relaxed);
+                primary_watermark.saturating_sub(watermark).max(0)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to read watermark of read replica {}: {e}",
+                    self.index
+                );
+                self.watermark.store(UNKNOWN_WATERMARK, Ordering::Relaxed);
+                UNKNOWN_LAG
+            }
+        };
+
+        let prev = self.lag.swap(lag, Ordering::Relaxed);
+        let max = config.max_lag_checkpoints;
+        // Replicas that can't be reached were already warned about.
+        if prev <= max && lag > max && lag != UNKNOWN_LAG {
+            warn!(
+                "Read replica {} is {lag} checkpoints behind, not reading from it",
+                self.index
+            );
+        } else if prev > max && lag <= max {
+            info!("Read replica {} caught up, reading from it", self.index);
+        }
+    }
+}
+
+/// URLs of read replicas from a comma-separated list, e.g. as set in `PG_READ_REPLICA_URLS`. Blank
+/// entries are ignored, so an empty list means there are no replicas.
+pub(crate) fn parse_replica_urls(urls: &str) -> Vec<String> {
+    urls.split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Sequence number of the latest checkpoint in the database that `reader` reads from, or -1 if
+/// there are none.
+pub(crate) async fn watermark(reader: &IndexerReader) -> Result<i64, IndexerError> {
+    reader
+        .run_query_async(|conn| {
+            checkpoints::table
+                .select(max(checkpoints::sequence_number))
+                .first::<Option<i64>>(conn)
+        })
+        .await
+        .map(|watermark| watermark.unwrap_or(-1))
+}
+
+#[cfg(all(test, feature = "pg_integration"))]
+mod tests {
+    use sui_indexer::{
+        pg_integration::{db_url, insert_checkpoint, test_pool},
+        PgConnectionPoolConfig,
+    };
+
+    use super::*;
+
+    fn reader() -> IndexerReader {
+        IndexerReader::new_with_config(db_url(), PgConnectionPoolConfig::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_watermark_read_by_monitor() {
+        let pool = test_pool();
+        insert_checkpoint(&pool, 0);
+        let config = ReadRouterConfig {
+            lag_check_interval: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let router = ReadRouter::new(config, reader(), vec![]);
+        assert_eq!(router.reader_with_watermark().1, None);
+
+        // The first check happens as soon as the monitor starts.
+        let monitor = router.spawn_watermark_monitor();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while router.reader_with_watermark().1.is_none() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(router.reader_with_watermark().1, Some(0));
+
+        // Until the next check, the watermark is not read again.
+        insert_checkpoint(&pool, 1);
+        assert_eq!(router.reader_with_watermark().1, Some(0));
+        monitor.abort();
+    }
+
+    #[tokio::test]
+    async fn test_reads_spread_across_available_replicas() {
+        let router = ReadRouter::new(
+            ReadRouterConfig::default(),
+            reader(),
+            vec![reader(), reader()],
+        );
+        let is = |reader: &IndexerReader, expected: &IndexerReader| std::ptr::eq(reader, expected);
+
+        // Replicas are not read from until their lag is known.
+        assert!(is(router.reader(), &router.primary));
+
+        for (replica, watermark) in router.replicas.iter().zip([5, 6]) {
+            replica.watermark.store(watermark, Ordering::Relaxed);
+            replica.lag.store(0, Ordering::Relaxed);
+        }
+        let (first, second) = (router.reader(), router.reader());
+        assert!(!is(first, second));
+        assert!(is(router.reader(), first));
+
+        // Replicas that fall behind are skipped, along with their watermarks.
+        router.replicas[0].lag.store(100, Ordering::Relaxed);
+        for _ in 0..3 {
+            let (reader, watermark) = router.reader_with_watermark();
+            assert!(is(reader, &router.replicas[1].reader));
+            assert_eq!(watermark, Some(6));
+        }
+
+        router.replicas[1].lag.store(UNKNOWN_LAG, Ordering::Relaxed);
+        assert!(is(router.reader(), &router.primary));
+    }
+
+    #[test]
+    fn test_parse_replica_urls() {
+        assert!(parse_replica_urls("").is_empty());
+        assert!(parse_replica_urls(" , ").is_empty());
+        assert_eq!(
+            parse_replica_urls("postgres://a, postgres://b,,"),
+            vec!["postgres://a", "postgres://b"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_replicas() {
+        let router = ReadRouter::new(ReadRouterConfig::default(), reader(), vec![]);
+        assert_eq!(router.readers().len(), 1);
+        assert!(std::ptr::eq(router.reader(), &router.primary));
+        assert!(std::ptr::eq(router.reader(), &router.primary));
+    }
+}
@@ -4,17 +4,19 @@
 // For testing, use existing RPC as data source
 
 use crate::error::Error;
+use crate::extensions::timeout::{request_deadline, spawn_with_request_deadline};
 use crate::types::address::Address;
 use crate::types::balance::Balance;
 use crate::types::base64::Base64;
 use crate::types::big_int::BigInt;
 use crate::types::checkpoint::Checkpoint;
-use crate::types::committee_member::CommitteeMember;
 use crate::types::date_time::DateTime;
 use crate::types::digest::Digest;
+use crate::types::dry_run_result::DryRunResult;
 use crate::types::end_of_epoch_data::EndOfEpochData;
 use crate::types::epoch::Epoch;
-use crate::types::object::{Object, ObjectFilter, ObjectKind};
+use crate::types::move_object::MoveObject;
+use crate::types::object::{Object, ObjectData, ObjectKind};
 use crate::types::protocol_config::{
     ProtocolConfigAttr, ProtocolConfigFeatureFlag, ProtocolConfigs,
 };
@@ -38,12 +40,13 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
 use sui_json_rpc_types::{
-    SuiObjectDataOptions, SuiObjectResponseQuery, SuiPastObjectResponse, SuiRawData,
+    SuiObjectDataOptions, SuiPastObjectResponse, SuiProtocolConfigValue, SuiRawData,
     SuiTransactionBlockResponseOptions,
 };
 use sui_sdk::types::digests::TransactionDigest;
 use sui_sdk::types::sui_serde::BigInt as SerdeBigInt;
 use sui_sdk::types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_sdk::types::transaction::TransactionData;
 use sui_sdk::{
     types::{
         base_types::{ObjectID as NativeObjectID, SuiAddress as NativeSuiAddress},
@@ -70,21 +73,25 @@ impl Loader<Digest> for SuiClientLoader {
     type Value = TransactionBlock;
     type Error = async_graphql::Error;
 
+    /// Batches are loaded with the deadline of the request that started them (see
+    /// `lru_cache_data_loader`), and fail with `Error::RequestTimeout` once it passes.
     async fn load(&self, keys: &[Digest]) -> Result<HashMap<Digest, Self::Value>, Self::Error> {
         let mut map = HashMap::new();
         let keys: Vec<_> = keys
             .iter()
             .map(|x| TransactionDigest::new(x.into_array()))
             .collect();
-        for tx in self
-            .client
-            .read_api()
-            .multi_get_transactions_with_options(
-                keys,
-                SuiTransactionBlockResponseOptions::full_content(),
-            )
-            .await?
-        {
+        let txs = self.client.read_api().multi_get_transactions_with_options(
+            keys,
+            SuiTransactionBlockResponseOptions::full_content(),
+        );
+        let txs = match request_deadline() {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), txs)
+                .await
+                .map_err(|_| Error::RequestTimeout.extend())?,
+            None => txs.await,
+        };
+        for tx in txs? {
             let digest = Digest::from_array(tx.digest.into_inner());
             let mtx = TransactionBlock::from(tx);
             map.insert(digest, mtx);
@@ -119,60 +126,6 @@ impl DataProvider for SuiClient {
         Ok(Some(convert_obj(&g)))
     }
 
-    async fn fetch_owned_objs(
-        &self,
-        owner: &SuiAddress,
-        first: Option<u64>,
-        after: Option<String>,
-        last: Option<u64>,
-        before: Option<String>,
-        _filter: Option<ObjectFilter>,
-    ) -> Result<Connection<String, Object>> {
-        ensure_forward_pagination(&first, &after, &last, &before)?;
-
-        let count = first.map(|q| q as usize);
-        let native_owner = NativeSuiAddress::from(owner);
-        let query = SuiObjectResponseQuery::new_with_options(SuiObjectDataOptions::full_content());
-
-        let cursor = match after {
-            Some(q) => Some(
-                NativeObjectID::from_hex_literal(&q)
-                    .map_err(|w| Error::InvalidCursor(w.to_string()).extend())?,
-            ),
-            None => None,
-        };
-
-        let pg = self
-            .read_api()
-            .get_owned_objects(native_owner, Some(query), cursor, count)
-            .await?;
-
-        // TODO: support partial success/ failure responses
-        pg.data.iter().try_for_each(|n| {
-            if n.error.is_some() {
-                return Err(Error::CursorConnectionFetchFailed(
-                    n.error.as_ref().unwrap().to_string(),
-                )
-                .extend());
-            } else if n.data.is_none() {
-                return Err(Error::Internal(
-                    "Expected either data or error fields, received neither".to_string(),
-                )
-                .extend());
-            }
-            Ok(())
-        })?;
-        let mut connection = Connection::new(false, pg.has_next_page);
-
-        connection.edges.extend(pg.data.into_iter().map(|n| {
-            let g = n.data.unwrap();
-            let o = convert_obj(&g);
-
-            Edge::new(g.object_id.to_string(), o)
-        }));
-        Ok(connection)
-    }
-
     async fn get_object_with_options(
         &self,
         object_id: NativeObjectID,
@@ -340,9 +293,22 @@ impl DataProvider for SuiClient {
         })
     }
 
+    async fn fetch_max_tx_gas(&self) -> Result<u64> {
+        let cfg = self.read_api().get_protocol_config(None).await?;
+        match cfg.attributes.get("max_tx_gas") {
+            Some(Some(SuiProtocolConfigValue::U64(max_tx_gas))) => Ok(*max_tx_gas),
+            _ => Err(Error::Internal("Failed to read max_tx_gas".to_string()).into()),
+        }
+    }
+
     async fn get_latest_sui_system_state(&self) -> Result<SuiSystemStateSummary> {
         Ok(self.governance_api().get_latest_sui_system_state().await?)
     }
+
+    async fn dry_run_tx_block(&self, tx_data: TransactionData) -> Result<DryRunResult> {
+        let response = self.read_api().dry_run_transaction_block(tx_data).await?;
+        Ok(DryRunResult::from(response))
+    }
 }
 
 pub(crate) async fn sui_sdk_client_v0(rpc_url: impl AsRef<str>) -> SuiClient {
@@ -361,7 +327,7 @@ pub(crate) async fn lru_cache_data_loader(
         SuiClientLoader {
             client: client.clone(),
         },
-        tokio::spawn,
+        spawn_with_request_deadline,
         async_graphql::dataloader::LruCache::new(DATA_LOADER_LRU_CACHE_SIZE),
     );
     data_loader.enable_all_cache(true);
@@ -381,28 +347,7 @@ pub(crate) fn convert_json_rpc_checkpoint(
     let network_total_transactions = Some(c.network_total_transactions);
     let rolling_gas_summary = GasCostSummary::from(&c.epoch_rolling_gas_cost_summary);
 
-    let end_of_epoch_data = &c.end_of_epoch_data;
-    let end_of_epoch = end_of_epoch_data.clone().map(|e| {
-        let committees = e.next_epoch_committee;
-        let new_committee = if committees.is_empty() {
-            None
-        } else {
-            Some(
-                committees
-                    .iter()
-                    .map(|c| CommitteeMember {
-                        authority_name: Some(c.0.into_concise().to_string()),
-                        stake_unit: Some(c.1),
-                    })
-                    .collect::<Vec<_>>(),
-            )
-        };
-
-        EndOfEpochData {
-            new_committee,
-            next_protocol_version: Some(e.next_epoch_protocol_version.as_u64()),
-        }
-    });
+    let end_of_epoch = c.end_of_epoch_data.clone().map(EndOfEpochData::from);
 
     Ok(Checkpoint {
         digest,
@@ -429,9 +374,13 @@ pub(crate) fn convert_obj(s: &sui_json_rpc_types::SuiObjectData) -> Object {
             .get_owner_address()
             .map(|x| SuiAddress::from_array(x.to_inner()))
             .ok(),
-        bcs: s.bcs.as_ref().map(|raw| match raw {
-            SuiRawData::Package(raw_package) => Base64::from(bcs::to_bytes(raw_package).unwrap()),
-            SuiRawData::MoveObject(raw_object) => Base64::from(&raw_object.bcs_bytes),
+        data: s.bcs.as_ref().map(|raw| match raw {
+            SuiRawData::Package(_) => ObjectData::Package,
+            SuiRawData::MoveObject(raw_object) => ObjectData::Move(MoveObject {
+                type_: raw_object.type_.clone(),
+                has_public_transfer: raw_object.has_public_transfer,
+                contents: raw_object.bcs_bytes.clone(),
+            }),
         }),
         previous_transaction: s
             .previous_transaction
@@ -460,7 +409,7 @@ pub(crate) fn convert_to_epoch(
     protocol_configs: &ProtocolConfigs,
 ) -> Result<Epoch> {
     let epoch_id = system_state.epoch;
-    let active_validators = convert_to_validators(system_state.active_validators.clone())?;
+    let active_validators = convert_to_validators(system_state.active_validators.clone());
 
     let start_timestamp = i64::try_from(system_state.epoch_start_timestamp_ms).map_err(|_| {
         Error::Internal(format!(
@@ -530,13 +479,13 @@ pub(crate) fn convert_to_epoch(
         }),
         protocol_configs: Some(protocol_configs.clone()),
         start_timestamp: Some(start_timestamp),
+        end_timestamp: None,
+        total_stake_rewards: None,
     })
 }
 
-pub(crate) fn convert_to_validators(
-    validators: Vec<SuiValidatorSummary>,
-) -> Result<Vec<Validator>> {
-    let result = validators
+pub(crate) fn convert_to_validators(validators: Vec<SuiValidatorSummary>) -> Vec<Validator> {
+    validators
         .iter()
         .map(|v| {
             let credentials = ValidatorCredentials {
@@ -559,6 +508,7 @@ pub(crate) fn convert_to_validators(
                 description: Some(v.description.clone()),
                 image_url: Some(v.image_url.clone()),
                 project_url: Some(v.project_url.clone()),
+                exchange_rates_id: Some(SuiAddress::from_array(v.exchange_rates_id.into_bytes())),
                 exchange_rates_size: Some(v.exchange_rates_size),
 
                 staking_pool_activation_epoch: Some(v.staking_pool_activation_epoch.unwrap()),
@@ -580,9 +530,7 @@ pub(crate) fn convert_to_validators(
                 // apy: todo!(),
             }
         })
-        .collect();
-
-    Ok(result)
+        .collect()
 }
 
 impl From<Address> for SuiAddress {
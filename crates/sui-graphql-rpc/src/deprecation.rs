@@ -0,0 +1,46 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use async_graphql::Context;
+use once_cell::sync::Lazy;
+
+use crate::server::version::SchemaVersion;
+
+/// Mapping from type and field name in the schema to the version of the schema that the field is
+/// removed in. Fields are marked deprecated in the schema when they are added here, and given
+/// `is_visible` as their `visible` function, and they keep being served to requests for versions
+/// before the one they were removed in, for as long as this instance of the RPC serves those
+/// versions (see `SchemaVersion::supported`).
+fn removals() -> &'static BTreeMap<(&'static str, &'static str), SchemaVersion> {
+    static REMOVALS: Lazy<BTreeMap<(&str, &str), SchemaVersion>> = Lazy::new(|| {
+        BTreeMap::from_iter([(
+            ("TransactionBlockEffects", "errors"),
+            SchemaVersion::new(0, 2),
+        )])
+    });
+
+    Lazy::force(&REMOVALS)
+}
+
+/// The version of the schema that a field was removed in, if it was removed.
+pub(crate) fn removed_in(type_: &str, field: &str) -> Option<SchemaVersion> {
+    removals().get(&(type_, field)).copied()
+}
+
+/// Whether `field` of `type_` is in the version of the schema that the request asked for, and so
+/// whether introspection lists it. Removed fields use this (through a function that names them)
+/// as their `visible` function, while requests for them are rejected by `VersionGate`.
+pub(crate) fn is_visible(ctx: &Context<'_>, type_: &str, field: &str) -> bool {
+    let Some(removed) = removed_in(type_, field) else {
+        return true;
+    };
+
+    let version = ctx
+        .data_opt::<SchemaVersion>()
+        .copied()
+        .unwrap_or_else(SchemaVersion::current);
+
+    version < removed
+}
@@ -11,7 +11,13 @@ use sui_indexer::errors::IndexerError;
 pub(crate) mod code {
     pub const BAD_REQUEST: &str = "BAD_REQUEST";
     pub const BAD_USER_INPUT: &str = "BAD_USER_INPUT";
+    pub const FEATURE_DISABLED: &str = "FEATURE_DISABLED";
+    pub const FIELD_REMOVED: &str = "FIELD_REMOVED";
     pub const INTERNAL_SERVER_ERROR: &str = "INTERNAL_SERVER_ERROR";
+    pub const REQUEST_TIMEOUT: &str = "REQUEST_TIMEOUT";
+    pub const SERVICE_UNAVAILABLE: &str = "SERVICE_UNAVAILABLE";
+    pub const TOO_MANY_REQUESTS: &str = "TOO_MANY_REQUESTS";
+    pub const UNAUTHENTICATED: &str = "UNAUTHENTICATED";
 }
 
 /// Create a GraphQL Response containing an Error.
@@ -61,6 +67,14 @@ pub enum Error {
     CursorConnectionFetchFailed(String),
     #[error("Error received in multi-get query: {0}")]
     MultiGet(String),
+    #[error("Invalid type: {0}")]
+    InvalidType(String),
+    #[error("Unsupported filter: {0}")]
+    UnsupportedFilter(String),
+    #[error("Database is unavailable, try again later")]
+    DatabaseUnavailable,
+    #[error("Request timed out")]
+    RequestTimeout,
     #[error("Internal error occurred while processing request")]
     Internal(String),
 }
@@ -75,10 +89,18 @@ impl ErrorExtensions for Error {
             | Error::InvalidCursor(_)
             | Error::CursorConnectionFetchFailed(_)
             | Error::MultiGet(_)
+            | Error::InvalidType(_)
+            | Error::UnsupportedFilter(_)
             | Error::InvalidBase58(_)
             | Error::InvalidDigestLength { .. } => {
                 e.set("code", code::BAD_USER_INPUT);
             }
+            Error::DatabaseUnavailable => {
+                e.set("code", code::SERVICE_UNAVAILABLE);
+            }
+            Error::RequestTimeout => {
+                e.set("code", code::REQUEST_TIMEOUT);
+            }
             Error::Internal(_) => {
                 e.set("code", code::INTERNAL_SERVER_ERROR);
             }
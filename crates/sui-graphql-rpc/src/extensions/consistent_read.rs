@@ -0,0 +1,125 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
+    Response,
+};
+use std::{future::Future, sync::Arc};
+use sui_indexer::indexer_reader::IndexerReader;
+use tracing::warn;
+
+use crate::context_data::db_data_provider::PgManager;
+
+tokio::task_local! {
+    /// What the request that the current task is executing reads from the database.
+    static READ_SCOPE: ReadScope;
+}
+
+/// The database that a request reads from, and the latest checkpoint it reads data from, both
+/// picked once at the start of the request, so that all of its fields see the same state.
+#[derive(Clone)]
+pub(crate) struct ReadScope {
+    pub reader: IndexerReader,
+    /// Sequence number of the latest checkpoint in `reader` when the request started, or -1 if it
+    /// had none.
+    pub checkpoint: i64,
+}
+
+/// What the request that the current task is executing reads from the database, if it is subject
+/// to consistent reads. Database queries made on behalf of the request use this to read from the
+/// same database, and to ignore data from checkpoints after the request started (see
+/// `PgManager::run_query_async`).
+pub(crate) fn request_read_scope() -> Option<ReadScope> {
+    READ_SCOPE.try_with(|scope| scope.clone()).ok()
+}
+
+/// Runs `f` as if it were made on behalf of a request that reads from `scope`, for work done outside
+/// of a request's extensions, such as polling for subscriptions.
+pub(crate) async fn with_read_scope<F: Future>(scope: ReadScope, f: F) -> F::Output {
+    READ_SCOPE.scope(scope, f).await
+}
+
+/// Pins each request to one database, and to the latest checkpoint in it when the request
+/// started, so that a single query never mixes data from different checkpoints, even as the
+/// indexer writes new ones, or when reads are spread across replicas that lag behind each other.
+///
+/// Only data that is indexed per checkpoint is read as of that checkpoint: transactions, events,
+/// packages, checkpoints and epochs. Everything read from the live objects (objects by ID, owner or
+/// type, dynamic fields, kiosks, and per-address object stats), and the data added to an epoch
+/// when it ends, is only kept as of the latest checkpoint in the database. That is read from the
+/// same database, but may be newer than the rest of a response.
+///
+/// Requests that fail to pick a checkpoint (e.g. because the database is unreachable) run without
+/// one, and their fields that need the database fail by themselves.
+pub(crate) struct ConsistentRead;
+
+struct ConsistentReadExtension;
+
+impl ExtensionFactory for ConsistentRead {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ConsistentReadExtension)
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ConsistentReadExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let Some(pg) = ctx.data_opt::<PgManager>() else {
+            return next.run(ctx).await;
+        };
+
+        match pg.read_scope().await {
+            Ok(scope) => READ_SCOPE.scope(scope, next.run(ctx)).await,
+            Err(e) => {
+                warn!("Failed to pick a checkpoint to read at: {e}");
+                next.run(ctx).await
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "pg_integration"))]
+mod tests {
+    use sui_indexer::pg_integration::{db_url, insert_checkpoint, test_pool};
+
+    use super::*;
+    use crate::config::QueryLoggingConfig;
+
+    #[tokio::test]
+    async fn test_reads_pinned_to_checkpoint() {
+        let pool = test_pool();
+        insert_checkpoint(&pool, 0);
+        insert_checkpoint(&pool, 1);
+        let pg = PgManager::new(db_url(), vec![], None, QueryLoggingConfig::default()).unwrap();
+
+        let scope = pg.read_scope().await.unwrap();
+        assert_eq!(scope.checkpoint, 1);
+
+        // Checkpoints indexed after the request started are not seen by its fields.
+        insert_checkpoint(&pool, 2);
+        let latest = with_read_scope(scope.clone(), pg.fetch_latest_checkpoint())
+            .await
+            .unwrap();
+        assert_eq!(latest.sequence_number, 1);
+        let missing = with_read_scope(scope, pg.fetch_checkpoint(None, Some(2)))
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+
+        // Requests that start later do.
+        let scope = pg.read_scope().await.unwrap();
+        assert_eq!(scope.checkpoint, 2);
+        let latest = with_read_scope(scope, pg.fetch_latest_checkpoint())
+            .await
+            .unwrap();
+        assert_eq!(latest.sequence_number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_scope_without_checkpoints() {
+        let _pool = test_pool();
+        let pg = PgManager::new(db_url(), vec![], None, QueryLoggingConfig::default()).unwrap();
+        assert_eq!(pg.read_scope().await.unwrap().checkpoint, -1);
+    }
+}
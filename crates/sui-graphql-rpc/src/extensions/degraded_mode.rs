@@ -0,0 +1,37 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
+    value, Response,
+};
+use std::sync::Arc;
+
+use crate::context_data::db_data_provider::PgManager;
+
+/// Flags responses served while the database is unreachable with a `degraded` extension, so that
+/// clients can tell that fields backed by the database may be missing from them.
+pub(crate) struct DegradedMode;
+
+struct DegradedModeExtension;
+
+impl ExtensionFactory for DegradedMode {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(DegradedModeExtension)
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for DegradedModeExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let resp = next.run(ctx).await;
+        let degraded = ctx
+            .data_opt::<PgManager>()
+            .is_some_and(|pg| pg.is_degraded());
+        if degraded {
+            resp.extension("degraded", value!(true))
+        } else {
+            resp
+        }
+    }
+}
@@ -4,17 +4,24 @@
 use std::sync::Arc;
 
 use async_graphql::{
-    extensions::{Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo},
-    ServerError, ServerResult, Value,
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextResolve, ResolveInfo,
+    },
+    parser::types::{ExecutableDocument, OperationType, Selection},
+    Pos, ServerError, ServerResult, Value, Variables,
 };
 use async_trait::async_trait;
 
 use crate::{
     config::ServiceConfig,
     error::{code, graphql_error},
-    functional_group::functional_group,
+    functional_group::{functional_group, FunctionalGroup},
 };
 
+/// Rejects requests for fields that belong to a functional group that the service has been
+/// configured to disable (see `ServiceConfig::disabled_features`), with a `FEATURE_DISABLED`
+/// error. Disabled fields stay in the schema, so that clients can tell a field that is disabled on
+/// this deployment apart from one that does not exist.
 pub(crate) struct FeatureGate;
 
 impl ExtensionFactory for FeatureGate {
@@ -25,6 +32,42 @@ impl ExtensionFactory for FeatureGate {
 
 #[async_trait]
 impl Extension for FeatureGate {
+    /// Subscriptions are checked up-front, because their root fields are not resolved like those
+    /// of queries, but turned into streams.
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let doc = next.run(ctx, query, variables).await?;
+        let ServiceConfig {
+            disabled_features, ..
+        } = service_config(ctx)?;
+
+        for (_name, oper) in doc.operations.iter() {
+            if oper.node.ty != OperationType::Subscription {
+                continue;
+            }
+
+            for sel in &oper.node.selection_set.node.items {
+                let Selection::Field(field) = &sel.node else {
+                    continue;
+                };
+
+                let name = field.node.name.node.as_str();
+                if let Some(group) = functional_group("Subscription", name) {
+                    if disabled_features.contains(&group) {
+                        return Err(feature_disabled("Subscription", name, group, Some(sel.pos)));
+                    }
+                }
+            }
+        }
+
+        Ok(doc)
+    }
+
     async fn resolve(
         &self,
         ctx: &ExtensionContext<'_>,
@@ -40,12 +83,7 @@ impl Extension for FeatureGate {
 
         let ServiceConfig {
             disabled_features, ..
-        } = ctx.data().map_err(|_| {
-            graphql_error(
-                code::INTERNAL_SERVER_ERROR,
-                "Unable to fetch service configuration",
-            )
-        })?;
+        } = service_config(ctx)?;
 
         // TODO: Is there a way to set `is_visible` on `MetaField` and `MetaType` in a generic way
         // after building the schema? (to a function which reads the `ServiceConfig` from the
@@ -57,17 +95,10 @@ impl Extension for FeatureGate {
                 return if *is_for_introspection {
                     Ok(None)
                 } else {
-                    Err(ServerError::new(
-                        format!(
-                            "Cannot query field \"{name}\" on type \"{parent_type}\". \
-                             Feature {} is disabled.",
-                            group.name(),
-                        ),
-                        // TODO: Fork `async-graphl` to add field position information to
-                        // `ResolveInfo`, so the error can take advantage of it.  Similarly for
-                        // utilising the `path_node` to set the error path.
-                        None,
-                    ))
+                    // TODO: Fork `async-graphl` to add field position information to
+                    // `ResolveInfo`, so the error can take advantage of it.  Similarly for
+                    // utilising the `path_node` to set the error path.
+                    Err(feature_disabled(parent_type, name, group, None))
                 };
             }
         }
@@ -76,6 +107,32 @@ impl Extension for FeatureGate {
     }
 }
 
+fn service_config<'a>(ctx: &'a ExtensionContext<'_>) -> ServerResult<&'a ServiceConfig> {
+    ctx.data().map_err(|_| {
+        graphql_error(
+            code::INTERNAL_SERVER_ERROR,
+            "Unable to fetch service configuration",
+        )
+    })
+}
+
+fn feature_disabled(
+    type_: &str,
+    field: &str,
+    group: FunctionalGroup,
+    pos: Option<Pos>,
+) -> ServerError {
+    let mut err = graphql_error(
+        code::FEATURE_DISABLED,
+        format!(
+            "Cannot query field \"{field}\" on type \"{type_}\". Feature {} is disabled.",
+            group.name(),
+        ),
+    );
+    err.locations.extend(pos);
+    err
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -83,7 +140,7 @@ mod tests {
     use async_graphql::{EmptyMutation, EmptySubscription, Schema};
     use expect_test::expect;
 
-    use crate::{functional_group::FunctionalGroup, types::query::Query};
+    use crate::types::{query::Query, subscription::Subscription};
 
     use super::*;
 
@@ -112,12 +169,48 @@ mod tests {
             .into_result()
             .unwrap_err()
             .into_iter()
+            .map(|e| {
+                (
+                    e.message,
+                    e.extensions.and_then(|ext| ext.get("code").cloned()),
+                )
+            })
+            .collect();
+
+        let expect = expect![[r#"
+            [
+                (
+                    "Cannot query field \"protocolConfig\" on type \"Query\". Feature \"system-state\" is disabled.",
+                    Some(
+                        String(
+                            "FEATURE_DISABLED",
+                        ),
+                    ),
+                ),
+            ]"#]];
+        expect.assert_eq(&format!("{errs:#?}"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribing_to_a_disabled_field() {
+        let errs: Vec<_> = Schema::build(Query, EmptyMutation, Subscription)
+            .data(ServiceConfig {
+                disabled_features: BTreeSet::from_iter([FunctionalGroup::Subscriptions]),
+                ..Default::default()
+            })
+            .extension(FeatureGate)
+            .finish()
+            .execute("subscription { checkpoints { sequenceNumber } }")
+            .await
+            .into_result()
+            .unwrap_err()
+            .into_iter()
             .map(|e| e.message)
             .collect();
 
         let expect = expect![[r#"
             [
-                "Cannot query field \"protocolConfig\" on type \"Query\". Feature \"system-state\" is disabled.",
+                "Cannot query field \"checkpoints\" on type \"Subscription\". Feature \"subscriptions\" is disabled.",
             ]"#]];
         expect.assert_eq(&format!("{errs:#?}"));
     }
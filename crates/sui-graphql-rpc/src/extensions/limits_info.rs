@@ -12,7 +12,7 @@ use axum::{
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-static LIMITS_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-show-usage");
+pub(crate) static LIMITS_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-show-usage");
 
 /// Only display usage information if this header was in the request.
 pub(crate) struct ShowUsage;
@@ -1,8 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod consistent_read;
+pub(crate) mod degraded_mode;
 pub(crate) mod feature_gate;
 pub(crate) mod limits_info;
 pub(crate) mod logger;
 pub(crate) mod query_limits_checker;
+pub(crate) mod query_logging;
+pub(crate) mod query_metrics;
 pub(crate) mod timeout;
+pub(crate) mod version_gate;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::ServiceConfig;
+use crate::error::code;
 use async_graphql::extensions::NextParseQuery;
 use async_graphql::parser::types::ExecutableDocument;
 use async_graphql::parser::types::Selection::Field;
@@ -10,7 +11,7 @@ use async_graphql::ServerResult;
 use async_graphql::Variables;
 use async_graphql::{
     extensions::{Extension, ExtensionContext, ExtensionFactory},
-    ServerError,
+    ErrorExtensionValues, ServerError,
 };
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -36,7 +37,9 @@ impl Extension for QueryLimitsChecker {
         // TODO: limit number of variables, fragments, etc
 
         // Use BFS to analyze the query and
-        // count the number of nodes and the depth of the query
+        // count the number of nodes and the depth of the query.
+        // The whole query is measured even once a limit has been exceeded, so that the error can
+        // report its full cost.
 
         let cfg = ctx
             .data::<ServiceConfig>()
@@ -51,12 +54,17 @@ impl Extension for QueryLimitsChecker {
         let mut depth: u32 = 0;
         // Number of nodes at each level
         let mut level_len;
+        // Positions at which the node and depth limits were first exceeded
+        let mut nodes_exceeded_at = None;
+        let mut depth_exceeded_at = None;
 
         for (_name, oper) in doc.operations.iter() {
             for sel in oper.node.selection_set.node.items.iter() {
                 que.push_back(sel);
                 num_nodes += 1;
-                self.check_limits(cfg, num_nodes, depth, Some(sel.pos))?;
+                if num_nodes > cfg.limits.max_query_nodes {
+                    nodes_exceeded_at.get_or_insert(sel.pos);
+                }
             }
         }
         // Track the number of nodes at first level if any
@@ -65,7 +73,9 @@ impl Extension for QueryLimitsChecker {
         while !que.is_empty() {
             // Signifies the start of a new level
             depth += 1;
-            self.check_limits(cfg, num_nodes, depth, None)?;
+            if depth > cfg.limits.max_query_depth {
+                depth_exceeded_at.get_or_insert(que[0].pos);
+            }
             while level_len > 0 {
                 let sel = que.pop_front().unwrap();
                 // TODO: check for fragments, variables, etc
@@ -74,7 +84,9 @@ impl Extension for QueryLimitsChecker {
                     for sel in f.node.selection_set.node.items.iter() {
                         que.push_back(sel);
                         num_nodes += 1;
-                        self.check_limits(cfg, num_nodes, depth, Some(sel.pos))?;
+                        if num_nodes > cfg.limits.max_query_nodes {
+                            nodes_exceeded_at.get_or_insert(sel.pos);
+                        }
                     }
                 }
                 level_len -= 1;
@@ -82,38 +94,112 @@ impl Extension for QueryLimitsChecker {
             level_len = que.len();
         }
 
-        Ok(doc)
-    }
-}
-
-impl QueryLimitsChecker {
-    fn check_limits(
-        &self,
-        cfg: &ServiceConfig,
-        nodes: u32,
-        depth: u32,
-        pos: Option<Pos>,
-    ) -> ServerResult<()> {
-        if nodes > cfg.limits.max_query_nodes {
-            return Err(ServerError::new(
+        if let Some(pos) = nodes_exceeded_at {
+            return Err(limit_exceeded(
                 format!(
                     "Query has too many nodes. The maximum allowed is {}",
                     cfg.limits.max_query_nodes
                 ),
                 pos,
+                "maxQueryNodes",
+                num_nodes,
+                cfg.limits.max_query_nodes,
             ));
         }
 
-        if depth > cfg.limits.max_query_depth {
-            return Err(ServerError::new(
+        if let Some(pos) = depth_exceeded_at {
+            return Err(limit_exceeded(
                 format!(
                     "Query has too many levels of nesting. The maximum allowed is {}",
                     cfg.limits.max_query_depth
                 ),
                 pos,
+                "maxQueryDepth",
+                depth,
+                cfg.limits.max_query_depth,
             ));
         }
 
-        Ok(())
+        Ok(doc)
+    }
+}
+
+/// An error rejecting a query for exceeding the limit named `limit`, which describes the limit
+/// and the query's cost against it in its extensions, so that clients can adjust their queries
+/// without parsing the message.
+fn limit_exceeded(message: String, pos: Pos, limit: &str, cost: u32, max: u32) -> ServerError {
+    let mut ext = ErrorExtensionValues::default();
+    ext.set("code", code::BAD_USER_INPUT);
+    ext.set("limit", limit);
+    ext.set("cost", cost);
+    ext.set("max", max);
+
+    let mut error = ServerError::new(message, Some(pos));
+    error.extensions = Some(ext);
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Limits;
+    use crate::types::query::Query;
+    use async_graphql::{EmptyMutation, EmptySubscription, Schema, Value};
+
+    async fn exec_with_limits(limits: Limits, query: &str) -> Vec<ServerError> {
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .data(ServiceConfig {
+                limits,
+                ..Default::default()
+            })
+            .extension(QueryLimitsChecker)
+            .finish();
+        schema.execute(query).await.errors
+    }
+
+    fn extension(err: &ServerError, key: &str) -> Option<Value> {
+        err.extensions.as_ref()?.get(key).cloned()
+    }
+
+    #[tokio::test]
+    async fn test_node_limit_reports_cost() {
+        let limits = Limits {
+            max_query_nodes: 2,
+            ..Default::default()
+        };
+        let errs = exec_with_limits(
+            limits,
+            "{ chainIdentifier protocolConfig { configs { value key }} }",
+        )
+        .await;
+
+        assert_eq!(errs.len(), 1);
+        let err = &errs[0];
+        assert_eq!(
+            extension(err, "code"),
+            Some(Value::from(code::BAD_USER_INPUT))
+        );
+        assert_eq!(extension(err, "limit"), Some(Value::from("maxQueryNodes")));
+        assert_eq!(extension(err, "cost"), Some(Value::from(5)));
+        assert_eq!(extension(err, "max"), Some(Value::from(2)));
+    }
+
+    #[tokio::test]
+    async fn test_depth_limit_reports_cost() {
+        let limits = Limits {
+            max_query_depth: 1,
+            ..Default::default()
+        };
+        let errs = exec_with_limits(
+            limits,
+            "{ chainIdentifier protocolConfig { configs { value key }} }",
+        )
+        .await;
+
+        assert_eq!(errs.len(), 1);
+        let err = &errs[0];
+        assert_eq!(extension(err, "limit"), Some(Value::from("maxQueryDepth")));
+        assert_eq!(extension(err, "cost"), Some(Value::from(3)));
+        assert_eq!(extension(err, "max"), Some(Value::from(1)));
     }
 }
@@ -0,0 +1,53 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextResolve},
+    ResolveInfo, Response, ServerResult, Value,
+};
+use std::sync::Arc;
+
+use crate::context_data::query_log::{with_operation_name, with_resolver};
+
+/// Tags the database queries that an operation makes with its name, and with the field that made
+/// them, for when they are logged (see `QueryLoggingConfig`), and for their metrics. Only installed
+/// if queries are logged, as it allocates for every field that is resolved.
+pub(crate) struct QueryLogging;
+
+struct QueryLoggingExtension;
+
+impl ExtensionFactory for QueryLogging {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryLoggingExtension)
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for QueryLoggingExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        with_operation_name(
+            operation_name.map(str::to_string),
+            next.run(ctx, operation_name),
+        )
+        .await
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+
+        let resolver = format!("{}.{}", info.parent_type, info.name);
+        with_resolver(resolver, next.run(ctx, info)).await
+    }
+}
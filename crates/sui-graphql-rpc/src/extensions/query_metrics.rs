@@ -0,0 +1,73 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest, NextResolve},
+    ResolveInfo, Response, ServerResult, Value,
+};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::metrics::Metrics;
+
+/// Error code recorded for errors that don't have a `code` extension.
+const UNKNOWN_CODE: &str = "UNKNOWN";
+
+/// Records how long each top-level field takes to resolve, and the codes of errors in responses,
+/// in `Metrics`.
+pub(crate) struct QueryMetrics {
+    metrics: Arc<Metrics>,
+}
+
+impl QueryMetrics {
+    pub(crate) fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl ExtensionFactory for QueryMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryMetricsExtension {
+            metrics: self.metrics.clone(),
+        })
+    }
+}
+
+struct QueryMetricsExtension {
+    metrics: Arc<Metrics>,
+}
+
+#[async_trait::async_trait]
+impl Extension for QueryMetricsExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let resp = next.run(ctx).await;
+        for err in &resp.errors {
+            let code = match err.extensions.as_ref().and_then(|ext| ext.get("code")) {
+                Some(Value::String(code)) => code.as_str(),
+                _ => UNKNOWN_CODE,
+            };
+            self.metrics.errors.with_label_values(&[code]).inc();
+        }
+        resp
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if info.path_node.parent.is_some() || info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+
+        let field = info.name;
+        let start = Instant::now();
+        let result = next.run(ctx, info).await;
+        self.metrics
+            .query_latency
+            .with_label_values(&[field])
+            .observe(start.elapsed().as_secs_f64());
+        result
+    }
+}
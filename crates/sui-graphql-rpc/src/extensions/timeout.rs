@@ -3,15 +3,44 @@
 
 use async_graphql::{
     extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
-    Response, ServerError,
+    Response,
 };
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::timeout;
+use std::time::{Duration, Instant};
+use tokio::{task::JoinHandle, time::timeout};
+
+use crate::error::{code, graphql_error};
 
 // 10s
 const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_millis(10_000);
 
+tokio::task_local! {
+    /// When the request that the current task is executing times out.
+    static DEADLINE: Instant;
+}
+
+/// When the request that the current task is executing times out, if it is subject to a timeout.
+/// Database queries made on behalf of the request use this to give up once the request would be
+/// aborted anyway (see `PgManager::run_query_async`).
+pub(crate) fn request_deadline() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Spawns `task` with the deadline of the request that the current task is executing, if any, so
+/// that work the request hands off to another task, such as the batches that a `DataLoader` loads,
+/// also gives up once the request times out.
+pub(crate) fn spawn_with_request_deadline<F>(task: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match request_deadline() {
+        Some(deadline) => tokio::spawn(DEADLINE.scope(deadline, task)),
+        None => tokio::spawn(task),
+    }
+}
+
 #[derive(Clone, Debug, Copy)]
 pub(crate) struct TimeoutConfig {
     pub request_timeout: Duration,
@@ -25,11 +54,21 @@ impl Default for TimeoutConfig {
     }
 }
 
+/// Aborts requests that take longer than `TimeoutConfig::request_timeout`, responding with a
+/// `REQUEST_TIMEOUT` error instead.
 #[derive(Clone, Debug, Default)]
 pub(crate) struct Timeout {
     pub config: TimeoutConfig,
 }
 
+impl Timeout {
+    pub(crate) fn new(request_timeout: Duration) -> Self {
+        Self {
+            config: TimeoutConfig { request_timeout },
+        }
+    }
+}
+
 impl ExtensionFactory for Timeout {
     fn create(&self) -> Arc<dyn Extension> {
         Arc::new(TimeoutExtension {
@@ -45,16 +84,44 @@ struct TimeoutExtension {
 #[async_trait::async_trait]
 impl Extension for TimeoutExtension {
     async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
-        timeout(self.config.request_timeout, next.run(ctx))
+        let deadline = Instant::now() + self.config.request_timeout;
+        DEADLINE
+            .scope(
+                deadline,
+                timeout(self.config.request_timeout, next.run(ctx)),
+            )
             .await
             .unwrap_or_else(|_| {
-                Response::from_errors(vec![ServerError::new(
+                Response::from_errors(vec![graphql_error(
+                    code::REQUEST_TIMEOUT,
                     format!(
                         "Request timed out. Limit: {}s",
                         self.config.request_timeout.as_secs_f32()
                     ),
-                    None,
                 )])
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spawned_task_inherits_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        let inherited = DEADLINE
+            .scope(deadline, async {
+                spawn_with_request_deadline(async { request_deadline() }).await
+            })
+            .await
+            .unwrap();
+        assert_eq!(inherited, Some(deadline));
+
+        // Tasks spawned outside of a request have no deadline.
+        let outside = spawn_with_request_deadline(async { request_deadline() })
+            .await
+            .unwrap();
+        assert_eq!(outside, None);
+    }
+}
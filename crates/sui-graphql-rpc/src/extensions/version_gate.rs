@@ -0,0 +1,173 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo},
+    ServerError, ServerResult, Value,
+};
+use async_trait::async_trait;
+
+use crate::{
+    deprecation::removed_in,
+    error::{code, graphql_error},
+    server::version::SchemaVersion,
+};
+
+/// Rejects requests for fields that were removed from the schema in the version that the request
+/// asked for, or in an earlier one (see `deprecation::removed_in`), with a `FIELD_REMOVED` error.
+/// Requests that don't say which version they want are served the current version.
+///
+/// Removed fields are hidden from introspection by their `visible` function instead (see
+/// `deprecation::is_visible`), because introspection resolves the fields of `__Type`, not the
+/// removed fields themselves.
+pub(crate) struct VersionGate;
+
+impl ExtensionFactory for VersionGate {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(VersionGate)
+    }
+}
+
+#[async_trait]
+impl Extension for VersionGate {
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        let ResolveInfo {
+            parent_type, name, ..
+        } = &info;
+
+        if let Some(removed) = removed_in(parent_type, name) {
+            let version = ctx
+                .data_opt::<SchemaVersion>()
+                .copied()
+                .unwrap_or_else(SchemaVersion::current);
+
+            if version >= removed {
+                return Err(field_removed(parent_type, name, removed));
+            }
+        }
+
+        next.run(ctx, info).await
+    }
+}
+
+fn field_removed(type_: &str, field: &str, removed: SchemaVersion) -> ServerError {
+    graphql_error(
+        code::FIELD_REMOVED,
+        format!(
+            "Cannot query field \"{field}\" on type \"{type_}\". It was removed in version \
+             {removed}.",
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Response, Schema};
+    use expect_test::expect;
+    use serde_json::json;
+    use sui_sdk::types::effects::TransactionEffects;
+
+    use crate::types::transaction_block::TransactionBlockEffects;
+
+    use super::*;
+
+    /// The version that `TransactionBlockEffects.errors` was removed in.
+    const REMOVED: SchemaVersion = SchemaVersion::new(0, 2);
+    const BEFORE: SchemaVersion = SchemaVersion::new(0, 1);
+    const AFTER: SchemaVersion = SchemaVersion::new(0, 3);
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn effects(&self) -> TransactionBlockEffects {
+            TransactionBlockEffects::from(&TransactionEffects::default())
+        }
+    }
+
+    async fn execute(query: &str, version: SchemaVersion) -> Response {
+        Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(VersionGate)
+            .finish()
+            .execute(Request::new(query).data(version))
+            .await
+    }
+
+    /// Names of the fields of `TransactionBlockEffects` that introspection lists at `version`.
+    async fn introspected_fields(version: SchemaVersion) -> Vec<String> {
+        let query = r#"{
+            __type(name: "TransactionBlockEffects") { fields(includeDeprecated: true) { name } }
+        }"#;
+
+        let response = execute(query, version).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        data["__type"]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_field_served_before_removal() {
+        let response = execute("{ effects { status errors } }", BEFORE).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({ "effects": { "status": "SUCCESS", "errors": null } }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_field_removed() {
+        for version in [REMOVED, AFTER] {
+            let errs: Vec<_> = execute("{ effects { status errors } }", version)
+                .await
+                .into_result()
+                .unwrap_err()
+                .into_iter()
+                .map(|e| {
+                    (
+                        e.message,
+                        e.extensions.and_then(|ext| ext.get("code").cloned()),
+                    )
+                })
+                .collect();
+
+            let expect = expect![[r#"
+                [
+                    (
+                        "Cannot query field \"errors\" on type \"TransactionBlockEffects\". It was removed in version 0.2.",
+                        Some(
+                            String(
+                                "FIELD_REMOVED",
+                            ),
+                        ),
+                    ),
+                ]"#]];
+            expect.assert_eq(&format!("{errs:#?}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_field_hidden_from_introspection_after_removal() {
+        assert!(introspected_fields(BEFORE)
+            .await
+            .contains(&"errors".to_string()));
+
+        for version in [REMOVED, AFTER] {
+            let fields = introspected_fields(version).await;
+            assert!(!fields.contains(&"errors".to_string()), "{version}");
+            assert!(fields.contains(&"status".to_string()), "{version}");
+        }
+    }
+}
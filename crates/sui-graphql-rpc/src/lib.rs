@@ -4,21 +4,26 @@
 pub mod commands;
 pub mod config;
 pub mod server;
+#[cfg(feature = "pg_integration")]
+pub mod test_infra;
 
+pub(crate) mod deprecation;
 pub(crate) mod functional_group;
 
 mod context_data;
 mod error;
 mod extensions;
+mod metrics;
 mod types;
 
 use async_graphql::*;
 use types::owner::ObjectOwner;
 
 use crate::types::query::Query;
+use crate::types::subscription::Subscription;
 
 pub fn schema_sdl_export() -> String {
-    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+    let schema = Schema::build(Query, EmptyMutation, Subscription)
         .register_output_type::<ObjectOwner>()
         .finish();
     schema.sdl()
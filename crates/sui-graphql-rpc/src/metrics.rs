@@ -0,0 +1,148 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, Encoder, HistogramVec, IntCounterVec, IntGaugeVec,
+    Registry, TextEncoder,
+};
+use sui_indexer::indexer_reader::IndexerReader;
+use tokio::task::JoinHandle;
+
+/// How often the utilization of the database connection pools is sampled.
+const DB_POOL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+const LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Metrics about the service, served in the Prometheus text format at `/metrics`.
+pub(crate) struct Metrics {
+    registry: Registry,
+    /// Time taken to resolve each top-level field of an operation, including everything nested
+    /// under it.
+    pub query_latency: HistogramVec,
+    /// Time taken by each database query, including waiting for a connection, by the field
+    /// (`Type.field`) that made it.
+    pub db_query_latency: HistogramVec,
+    /// Errors in responses, by their `code` extension.
+    pub errors: IntCounterVec,
+    /// Connections open to each database, by whether they are in use or idle.
+    pub db_pool_connections: IntGaugeVec,
+    /// Most connections that can be open to each database.
+    pub db_pool_max_connections: IntGaugeVec,
+}
+
+impl Metrics {
+    pub(crate) fn new(registry: Registry) -> Self {
+        Self {
+            query_latency: register_histogram_vec_with_registry!(
+                "graphql_query_latency",
+                "Time taken to resolve each top-level field of an operation, in seconds",
+                &["field"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            db_query_latency: register_histogram_vec_with_registry!(
+                "graphql_db_query_latency",
+                "Time taken by each database query, in seconds, by the field that made it",
+                &["resolver"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            errors: register_int_counter_vec_with_registry!(
+                "graphql_errors",
+                "Number of errors in responses, by error code",
+                &["code"],
+                registry,
+            )
+            .unwrap(),
+            db_pool_connections: register_int_gauge_vec_with_registry!(
+                "graphql_db_pool_connections",
+                "Number of connections open to each database, by whether they are in use or idle",
+                &["db", "state"],
+                registry,
+            )
+            .unwrap(),
+            db_pool_max_connections: register_int_gauge_vec_with_registry!(
+                "graphql_db_pool_max_connections",
+                "Most connections that can be open to each database",
+                &["db"],
+                registry,
+            )
+            .unwrap(),
+            registry,
+        }
+    }
+
+    /// All metrics in the Prometheus text format.
+    pub(crate) fn encode(&self) -> String {
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("Encoding metrics cannot fail");
+        String::from_utf8(buffer).expect("Metrics are encoded as UTF8")
+    }
+
+    /// Samples the utilization of the connection pool of each of `readers` every
+    /// `DB_POOL_POLL_INTERVAL`, for as long as the returned task is running.
+    pub(crate) fn spawn_db_pool_monitor(
+        self: &Arc<Self>,
+        readers: Vec<(String, IndexerReader)>,
+    ) -> JoinHandle<()> {
+        let metrics = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DB_POOL_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                for (db, reader) in &readers {
+                    let state = reader.pool_state();
+                    let idle = state.idle_connections as i64;
+                    let active = state.connections as i64 - idle;
+                    metrics
+                        .db_pool_connections
+                        .with_label_values(&[db, "active"])
+                        .set(active);
+                    metrics
+                        .db_pool_connections
+                        .with_label_values(&[db, "idle"])
+                        .set(idle);
+                    metrics
+                        .db_pool_max_connections
+                        .with_label_values(&[db])
+                        .set(reader.pool_max_size() as i64);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let metrics = Metrics::new(Registry::new());
+        metrics.errors.with_label_values(&["BAD_USER_INPUT"]).inc();
+        metrics
+            .query_latency
+            .with_label_values(&["checkpoint"])
+            .observe(0.02);
+
+        let encoded = metrics.encode();
+        assert!(
+            encoded.contains("graphql_errors{code=\"BAD_USER_INPUT\"} 1"),
+            "{encoded}"
+        );
+        assert!(
+            encoded.contains("graphql_query_latency_count{field=\"checkpoint\"} 1"),
+            "{encoded}"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{net::SocketAddr, time::Instant};
+
+use async_graphql::BatchRequest;
+use axum::{extract::ConnectInfo, http::Request, middleware::Next, response::Response};
+use tracing::info;
+
+use super::{rate_limit::ApiKeyTier, version::VERSION_HEADER};
+
+/// Name logged for operations that are sent without one.
+const ANONYMOUS_OPERATION: &str = "<anonymous>";
+
+/// Names of the operations in the GraphQL request that a response answers, added to the extensions
+/// of the response for access logs.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OperationNames(pub Vec<String>);
+
+impl OperationNames {
+    /// The names that the operations in `batch` were sent with, in order.
+    pub(crate) fn of(batch: &BatchRequest) -> Self {
+        let name = |req: &async_graphql::Request| {
+            req.operation_name
+                .clone()
+                .unwrap_or_else(|| ANONYMOUS_OPERATION.to_string())
+        };
+
+        Self(match batch {
+            BatchRequest::Single(req) => vec![name(req)],
+            BatchRequest::Batch(reqs) => reqs.iter().map(name).collect(),
+        })
+    }
+}
+
+/// Middleware that logs a line per request, under the `access_log` target, once it has been
+/// responded to: who sent it (IP address and API key tier), what it asked for (path, schema
+/// version and operation names), and how it was answered (status, and time taken until the
+/// response started).
+pub(crate) async fn access_log_middleware<B>(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let version = request
+        .headers()
+        .get(&VERSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+    let extensions = response.extensions();
+    let tier = extensions.get::<ApiKeyTier>().map(|t| t.0.as_str());
+    let operations = extensions
+        .get::<OperationNames>()
+        .map(|names| names.0.join(","));
+
+    info!(
+        target: "access_log",
+        client = %addr.ip(),
+        %method,
+        path,
+        version,
+        operations,
+        tier,
+        status,
+        elapsed_ms,
+        "Served request",
+    );
+
+    response
+}
@@ -2,13 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    extensions::limits_info::ShowUsage,
-    server::version::{check_version_middleware, set_version_middleware},
-    types::query::{Query, SuiGraphQLSchema},
+    config::Limits,
+    context_data::circuit_breaker::CircuitBreaker,
+    error::{code, graphql_error},
+    extensions::{limits_info::ShowUsage, query_metrics::QueryMetrics},
+    metrics::Metrics,
+    server::{
+        access_log::{access_log_middleware, OperationNames},
+        rate_limit::{rate_limit_middleware, RateLimiter},
+        response_cache::{CachedResponses, RequestKey, ResponseCache},
+        version::{check_version_middleware, set_version_middleware, SchemaVersion},
+    },
+    types::{
+        query::{Query, SuiGraphQLSchema},
+        subscription::Subscription,
+    },
 };
 use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql::{EmptyMutation, EmptySubscription};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{BatchRequest, BatchResponse, EmptyMutation, Response};
+use async_graphql_axum::{GraphQLBatchRequest, GraphQLBatchResponse, GraphQLSubscription};
 use axum::Router;
 use axum::{
     extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
@@ -16,7 +28,8 @@ use axum::{
 };
 use hyper::server::conn::AddrIncoming as HyperAddrIncoming;
 use hyper::Server as HyperServer;
-use std::{any::Any, net::SocketAddr};
+use std::{any::Any, net::SocketAddr, sync::Arc};
+use tower_http::cors::CorsLayer;
 
 pub(crate) struct Server {
     pub server: HyperServer<HyperAddrIncoming, IntoMakeServiceWithConnectInfo<Router, SocketAddr>>,
@@ -31,16 +44,38 @@ impl Server {
 pub(crate) struct ServerBuilder {
     port: u16,
     host: String,
-
-    schema: SchemaBuilder<Query, EmptyMutation, EmptySubscription>,
+    max_batch_size: usize,
+    response_cache: Option<Arc<ResponseCache>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    cors: Option<CorsLayer>,
+    access_log: bool,
+    metrics: Option<Arc<Metrics>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+
+    schema: SchemaBuilder<Query, EmptyMutation, Subscription>,
 }
 
+/// Most operations accepted in a single batched request.
+#[derive(Clone, Copy)]
+struct MaxBatchSize(usize);
+
+/// Whether responses are cached.
+#[derive(Clone, Copy)]
+struct CacheResponses(bool);
+
 impl ServerBuilder {
     pub fn new(port: u16, host: String) -> Self {
         Self {
             port,
             host,
-            schema: async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription),
+            max_batch_size: Limits::default().max_batch_size as usize,
+            response_cache: None,
+            rate_limiter: None,
+            cors: None,
+            access_log: false,
+            metrics: None,
+            circuit_breaker: None,
+            schema: async_graphql::Schema::build(Query, EmptyMutation, Subscription),
         }
     }
 
@@ -58,6 +93,48 @@ impl ServerBuilder {
         self
     }
 
+    pub fn max_batch_size(mut self, max_batch_size: u32) -> Self {
+        self.max_batch_size = max_batch_size as usize;
+        self
+    }
+
+    pub fn response_cache(mut self, response_cache: Option<ResponseCache>) -> Self {
+        self.response_cache = response_cache.map(Arc::new);
+        self
+    }
+
+    /// Limits how often each client can send requests to the GraphQL endpoints.
+    pub fn rate_limiter(mut self, rate_limiter: Option<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter.map(Arc::new);
+        self
+    }
+
+    /// Lets pages from other origins call the service from a browser, following `cors`.
+    pub fn cors(mut self, cors: Option<CorsLayer>) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    /// Logs a line per request served, if `enabled`.
+    pub fn access_log(mut self, enabled: bool) -> Self {
+        self.access_log = enabled;
+        self
+    }
+
+    /// Records metrics about the queries served in `metrics`, and serves them at `/metrics`.
+    pub fn metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.schema = self.schema.extension(QueryMetrics::new(metrics.clone()));
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Reports the service as degraded at `/health` while `circuit_breaker` is open, i.e. while the
+    /// database is unreachable.
+    pub(crate) fn circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
     pub fn context_data(mut self, context_data: impl Any + Send + Sync) -> Self {
         self.schema = self.schema.data(context_data);
         self
@@ -68,19 +145,66 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, EmptyMutation, EmptySubscription> {
-        self.schema.finish()
+    pub(crate) fn build_schema(self) -> Schema<Query, EmptyMutation, Subscription> {
+        let mut schema = self.schema;
+        // Added after every other extension, so that it runs once the request has picked the
+        // checkpoint it reads at.
+        if let Some(response_cache) = self.response_cache {
+            schema = schema.extension(CachedResponses(response_cache));
+        }
+        schema.finish()
     }
 
     pub fn build(self) -> Server {
         let address = self.address();
+        let max_batch_size = MaxBatchSize(self.max_batch_size);
+        let cache_responses = CacheResponses(self.response_cache.is_some());
+        let rate_limiter = self.rate_limiter.clone();
+        let cors = self.cors.clone();
+        let access_log = self.access_log;
+        let metrics = self.metrics.clone();
+        let circuit_breaker = self.circuit_breaker.clone();
         let schema = self.build_schema();
 
-        let app = axum::Router::new()
+        let mut app = axum::Router::new()
             .route("/", axum::routing::get(graphiql).post(graphql_handler))
+            .route_service("/subscriptions", GraphQLSubscription::new(schema.clone()));
+        // Only the routes added so far are rate limited, so that health checks and metrics scrapes
+        // are never turned away.
+        if let Some(rate_limiter) = rate_limiter {
+            app = app.route_layer(middleware::from_fn_with_state(
+                rate_limiter,
+                rate_limit_middleware,
+            ));
+        }
+
+        app = app.route(
+            "/health",
+            axum::routing::get(move || health(circuit_breaker.clone())),
+        );
+        if let Some(metrics) = metrics {
+            app = app.route(
+                "/metrics",
+                axum::routing::get(move || async move { metrics.encode() }),
+            );
+        }
+
+        app = app
             .layer(axum::extract::Extension(schema))
+            .layer(axum::extract::Extension(max_batch_size))
+            .layer(axum::extract::Extension(cache_responses))
             .layer(middleware::from_fn(check_version_middleware))
             .layer(middleware::from_fn(set_version_middleware));
+
+        // Layers added later wrap those added earlier, so requests that are turned away by the
+        // layers above are still logged, and their responses still carry CORS headers.
+        if access_log {
+            app = app.layer(middleware::from_fn(access_log_middleware));
+        }
+        if let Some(cors) = cors {
+            app = app.layer(cors);
+        }
+
         Server {
             server: axum::Server::bind(&address.parse().unwrap())
                 .serve(app.into_make_service_with_connect_info::<SocketAddr>()),
@@ -91,23 +215,87 @@ impl ServerBuilder {
 async fn graphql_handler(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     schema: axum::Extension<SuiGraphQLSchema>,
+    axum::Extension(MaxBatchSize(max_batch_size)): axum::Extension<MaxBatchSize>,
+    axum::Extension(CacheResponses(cache_responses)): axum::Extension<CacheResponses>,
+    version: Option<axum::Extension<SchemaVersion>>,
     usage: Option<TypedHeader<ShowUsage>>,
-    req: GraphQLRequest,
-) -> GraphQLResponse {
-    let mut req = req.into_inner();
-    if let Some(TypedHeader(usage)) = usage {
-        req.data.insert(usage)
+    req: GraphQLBatchRequest,
+) -> (axum::Extension<OperationNames>, GraphQLBatchResponse) {
+    let batch = req.into_inner();
+    let operations = OperationNames::of(&batch);
+    let version = version.map_or_else(SchemaVersion::current, |v| v.0);
+    let show_usage = usage.is_some();
+    // Usage is reported for the request being served, so it can't be served from the cache.
+    let cache_responses = cache_responses && !show_usage;
+    let response = execute_batch(&schema, batch, max_batch_size, |req| {
+        if show_usage {
+            req.data.insert(ShowUsage)
+        }
+        req.data.insert(version);
+        // Capture the IP address of the client
+        // Note: if a load balancer is used it must be configured to forward the client IP
+        // address
+        req.data.insert(addr);
+        // Tags the request to be served through the response cache, keyed on everything added
+        // to it so far.
+        if cache_responses {
+            let key = RequestKey::of(req);
+            req.data.insert(key);
+        }
+    })
+    .await;
+    (axum::Extension(operations), response.into())
+}
+
+/// Executes a single operation, or a batch of up to `max_batch_size` operations sent as a JSON
+/// array. The operations in a batch run concurrently, sharing the schema's data, including its
+/// connection pool and data loaders, and their responses are returned in the order they were sent.
+async fn execute_batch(
+    schema: &SuiGraphQLSchema,
+    batch: BatchRequest,
+    max_batch_size: usize,
+    add_data: impl Fn(&mut async_graphql::Request),
+) -> BatchResponse {
+    match batch {
+        BatchRequest::Single(mut req) => {
+            add_data(&mut req);
+            BatchResponse::Single(schema.execute(req).await)
+        }
+        BatchRequest::Batch(reqs) if reqs.len() > max_batch_size => {
+            BatchResponse::Single(Response::from_errors(vec![graphql_error(
+                code::BAD_USER_INPUT,
+                format!(
+                    "Batch of {} operations exceeds the maximum batch size of {max_batch_size}",
+                    reqs.len()
+                ),
+            )]))
+        }
+        BatchRequest::Batch(reqs) => {
+            let responses = reqs.into_iter().map(|mut req| {
+                add_data(&mut req);
+                schema.execute(req)
+            });
+            BatchResponse::Batch(futures::future::join_all(responses).await)
+        }
+    }
+}
+
+/// Responds as long as the service is up, for load balancers and orchestrators to check. While the
+/// database is unreachable, the service still serves the fields that do not need it, so it
+/// responds successfully, but reports that it is degraded.
+async fn health(circuit_breaker: Option<Arc<CircuitBreaker>>) -> &'static str {
+    if circuit_breaker.is_some_and(|cb| cb.is_open()) {
+        "DEGRADED"
+    } else {
+        "OK"
     }
-    // Capture the IP address of the client
-    // Note: if a load balancer is used it must be configured to forward the client IP address
-    req.data.insert(addr);
-    schema.execute(req).await.into()
 }
 
 async fn graphiql() -> impl axum::response::IntoResponse {
     axum::response::Html(
         async_graphql::http::GraphiQLSource::build()
             .endpoint("/")
+            .subscription_endpoint("/subscriptions")
             .finish(),
     )
 }
@@ -116,12 +304,16 @@ async fn graphiql() -> impl axum::response::IntoResponse {
 mod tests {
     use super::*;
     use crate::{
-        context_data::{data_provider::DataProvider, sui_sdk_data_provider::sui_sdk_client_v0},
+        config::ServiceConfig,
+        context_data::{
+            circuit_breaker::CircuitBreakerConfig, data_provider::DataProvider,
+            sui_sdk_data_provider::sui_sdk_client_v0,
+        },
         extensions::timeout::{Timeout, TimeoutConfig},
     };
     use async_graphql::{
         extensions::{Extension, ExtensionContext, NextExecute},
-        Response,
+        Request, Response,
     };
     use std::sync::Arc;
     use std::time::Duration;
@@ -187,6 +379,17 @@ mod tests {
             .collect();
         let exp = format!("Request timed out. Limit: {}s", timeout.as_secs_f32());
         assert_eq!(errs, vec![exp]);
+
+        let err = test_timeout(timeout, timeout)
+            .await
+            .into_result()
+            .unwrap_err()
+            .remove(0);
+        let code = err.extensions.unwrap().get("code").cloned();
+        assert_eq!(
+            code,
+            Some(async_graphql::Value::from(code::REQUEST_TIMEOUT))
+        );
     }
 
     #[tokio::test]
@@ -278,4 +481,68 @@ mod tests {
         .collect();
         assert_eq!(err, vec!["Query is too complex.".to_string()]);
     }
+
+    #[tokio::test]
+    async fn test_batch_execution() {
+        let schema = ServerBuilder::new(8000, "127.0.0.1".to_string())
+            .context_data(ServiceConfig::default())
+            .build_schema();
+
+        let batch = BatchRequest::Batch(vec![
+            Request::new("{ serviceConfig { maxQueryDepth } }"),
+            Request::new("{ serviceConfig { maxQueryNodes } }"),
+        ]);
+        let resp = execute_batch(&schema, batch, 2, |_| {}).await;
+        let BatchResponse::Batch(responses) = resp else {
+            panic!("Expected a response per operation");
+        };
+        let data: Vec<_> = responses
+            .into_iter()
+            .map(|resp| resp.data.into_json().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            data,
+            vec![
+                r#"{"serviceConfig":{"maxQueryDepth":10}}"#,
+                r#"{"serviceConfig":{"maxQueryNodes":100}}"#,
+            ]
+        );
+
+        // Should fail, without executing any of the operations
+        let reqs = (0..3)
+            .map(|_| Request::new("{ chainIdentifier }"))
+            .collect();
+        let batch = BatchRequest::Batch(reqs);
+        let resp = execute_batch(&schema, batch, 2, |_| {}).await;
+        let BatchResponse::Single(resp) = resp else {
+            panic!("Expected a single error response");
+        };
+        let errs: Vec<_> = resp
+            .into_result()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            errs,
+            vec!["Batch of 3 operations exceeds the maximum batch size of 2".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_degraded() {
+        let circuit_breaker = Arc::new(CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown: Duration::from_secs(60),
+        }));
+        assert_eq!(health(None).await, "OK");
+        assert_eq!(health(Some(circuit_breaker.clone())).await, "OK");
+
+        // The database became unreachable.
+        circuit_breaker.record_failure();
+        assert_eq!(health(Some(circuit_breaker.clone())).await, "DEGRADED");
+
+        circuit_breaker.record_success();
+        assert_eq!(health(Some(circuit_breaker)).await, "OK");
+    }
 }
@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::{
+    config::CorsConfig,
+    extensions::limits_info::LIMITS_HEADER,
+    server::version::{SUPPORTED_VERSIONS_HEADER, VERSION_HEADER},
+};
+
+/// A layer that lets pages from the origins in `config` call the service from a browser, or `None`
+/// if no other origins are allowed. Pages can send the headers the service reads, including the API
+/// key header, if there is one, and read the headers it responds with. Fails if an origin or the
+/// API key header are not valid header values.
+pub(crate) fn cors_layer(
+    config: &CorsConfig,
+    api_key_header: Option<&str>,
+) -> Result<Option<CorsLayer>, String> {
+    if config.allowed_origins.is_empty() {
+        return Ok(None);
+    }
+
+    let allow_origin = if config.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = config
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin).map_err(|e| format!("Bad origin {origin}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        AllowOrigin::list(origins)
+    };
+
+    let mut allow_headers = vec![
+        header::CONTENT_TYPE,
+        VERSION_HEADER.clone(),
+        LIMITS_HEADER.clone(),
+    ];
+    if let Some(name) = api_key_header {
+        let name =
+            HeaderName::try_from(name).map_err(|e| format!("Bad API key header {name}: {e}"))?;
+        allow_headers.push(name);
+    }
+
+    Ok(Some(
+        CorsLayer::new()
+            .allow_methods([Method::GET, Method::POST])
+            .allow_origin(allow_origin)
+            .allow_headers(allow_headers)
+            .expose_headers([
+                VERSION_HEADER.clone(),
+                SUPPORTED_VERSIONS_HEADER.clone(),
+                header::RETRY_AFTER,
+            ]),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_origins() {
+        let config = CorsConfig::default();
+        assert!(cors_layer(&config, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bad_origin() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com\n".to_string()],
+        };
+        assert!(cors_layer(&config, None).is_err());
+    }
+
+    #[test]
+    fn test_origins() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".to_string()],
+        };
+        assert!(cors_layer(&config, Some("x-api-key")).unwrap().is_some());
+
+        let config = CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+        };
+        assert!(cors_layer(&config, None).unwrap().is_some());
+    }
+}
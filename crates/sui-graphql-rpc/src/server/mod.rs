@@ -3,5 +3,9 @@
 
 pub mod simple_server;
 
-mod builder;
-mod version;
+mod access_log;
+pub(crate) mod builder;
+mod cors;
+mod rate_limit;
+mod response_cache;
+pub(crate) mod version;
@@ -0,0 +1,433 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, HeaderName, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use lru::LruCache;
+
+use crate::{
+    config::RateLimitConfig,
+    error::{code, graphql_error, graphql_error_response},
+};
+
+/// Most clients whose buckets are tracked at once. Once there are more, the buckets of the clients
+/// that were seen least recently are dropped, which starts those clients afresh.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// Limits how often each client can send requests, with a token bucket per client: each request
+/// takes a token from its client's bucket, which holds up to `burst` tokens, and is refilled at
+/// `requests_per_second`. Clients are identified by their API key if it is a known one, and
+/// otherwise by their IP address, so that clients can't get a fresh bucket by sending a made up
+/// key.
+///
+/// Clients whose API keys belong to a tier are limited at that tier's rate instead. Once there are
+/// known API keys, requests with unknown keys are rejected, and the limiter can also be told to
+/// reject requests without a key. Until then, API keys are ignored.
+pub(crate) struct RateLimiter {
+    /// Limits for clients without a tier, or `None` if they are not limited.
+    default: Option<Rate>,
+    api_key_header: Option<HeaderName>,
+    /// Limits for the clients in each tier, by tier name.
+    tiers: BTreeMap<String, Option<Rate>>,
+    /// The tier of each known API key.
+    api_keys: BTreeMap<String, String>,
+    require_api_key: bool,
+    buckets: Mutex<LruCache<String, Bucket>>,
+}
+
+/// How quickly a client's bucket is refilled, and how many tokens it holds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rate {
+    requests_per_second: f64,
+    burst: f64,
+}
+
+/// Who sent a request, as far as its limits are concerned.
+#[derive(Debug, PartialEq)]
+struct Client<'l> {
+    /// Identifies the client's bucket.
+    id: String,
+    /// The tier of the client's API key, if it has one.
+    tier: Option<&'l str>,
+    /// The client's limits, or `None` if it is not limited.
+    rate: Option<Rate>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// The tier of the API key that a request was sent with, added to the extensions of its response
+/// for access logs.
+#[derive(Clone, Debug)]
+pub(crate) struct ApiKeyTier(pub String);
+
+impl Rate {
+    /// Limits of `requests_per_second` and `burst`, or `None` if requests are not limited.
+    fn new(requests_per_second: u32, burst: Option<u32>) -> Option<Self> {
+        if requests_per_second == 0 {
+            return None;
+        }
+
+        let requests_per_second = requests_per_second as f64;
+        let burst = burst.map_or(requests_per_second, |b| b.max(1) as f64);
+        Some(Self {
+            requests_per_second,
+            burst,
+        })
+    }
+}
+
+impl RateLimiter {
+    /// A limiter following `config`, or `None` if it doesn't limit or authenticate requests. Fails
+    /// if the API key header is not a valid header name, if API keys are configured without a
+    /// header to read them from, or if an API key belongs to a tier that doesn't exist.
+    pub(crate) fn new(config: &RateLimitConfig) -> Result<Option<Self>, String> {
+        let authenticates = config.require_api_key || !config.api_keys.is_empty();
+        if config.requests_per_second == 0 && !authenticates {
+            return Ok(None);
+        }
+
+        let api_key_header = config
+            .api_key_header
+            .as_deref()
+            .map(|name| {
+                HeaderName::try_from(name).map_err(|e| format!("Bad API key header {name}: {e}"))
+            })
+            .transpose()?;
+
+        if authenticates && api_key_header.is_none() {
+            return Err("API keys are configured without an API key header".to_string());
+        }
+
+        if config.require_api_key && config.api_keys.is_empty() {
+            return Err("API keys are required, but there are no known API keys".to_string());
+        }
+
+        // API keys are secrets, so they are left out of errors.
+        if let Some(tier) = config
+            .api_keys
+            .values()
+            .find(|tier| !config.tiers.contains_key(*tier))
+        {
+            return Err(format!("An API key belongs to unknown tier {tier}"));
+        }
+
+        let tiers = config
+            .tiers
+            .iter()
+            .map(|(name, tier)| {
+                let rate = Rate::new(tier.requests_per_second, tier.burst);
+                (name.clone(), rate)
+            })
+            .collect();
+
+        Ok(Some(Self {
+            default: Rate::new(config.requests_per_second, config.burst),
+            api_key_header,
+            tiers,
+            api_keys: config.api_keys.clone(),
+            require_api_key: config.require_api_key,
+            buckets: Mutex::new(LruCache::new(
+                NonZeroUsize::new(MAX_TRACKED_CLIENTS).unwrap(),
+            )),
+        }))
+    }
+
+    /// Takes a token from `client`'s bucket at `now`, or returns how long the client needs to wait
+    /// for one to become available if its bucket is empty. The bucket is refilled at `rate`.
+    fn take(&self, client: &str, rate: Rate, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.get_or_insert_mut(client.to_string(), || Bucket {
+            tokens: rate.burst,
+            updated: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate.requests_per_second).min(rate.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / rate.requests_per_second))
+        }
+    }
+
+    /// The API key that `request` was sent with, if the service reads one.
+    fn api_key<'r, B>(&self, request: &'r Request<B>) -> Option<&'r str> {
+        self.api_key_header
+            .as_ref()
+            .and_then(|header| request.headers().get(header))
+            .and_then(|value| value.to_str().ok())
+    }
+
+    /// Identifies the client sending a request from `addr`, with API key `key`, or explains why
+    /// the request is rejected. API keys and IP addresses are kept apart, so that a key can't be
+    /// chosen to share (or exhaust) an address's bucket. Keys are ignored if there are no known
+    /// ones.
+    fn client(&self, key: Option<&str>, addr: &SocketAddr) -> Result<Client<'_>, &'static str> {
+        let key = key.filter(|_| !self.api_keys.is_empty());
+        match key {
+            Some(key) => {
+                let Some(tier) = self.api_keys.get(key) else {
+                    return Err("Unknown API key");
+                };
+                Ok(Client {
+                    id: format!("key:{key}"),
+                    tier: Some(tier),
+                    rate: self.tiers.get(tier).copied().flatten(),
+                })
+            }
+            None if self.require_api_key => Err("Missing API key"),
+            None => Ok(Client {
+                id: format!("ip:{}", addr.ip()),
+                tier: None,
+                rate: self.default,
+            }),
+        }
+    }
+}
+
+/// Middleware that rejects requests from clients that have run out of tokens, with a
+/// `429 Too Many Requests` status, and a GraphQL error saying how long to wait before retrying, in
+/// its `retryAfterMs` extension, as well as in the `Retry-After` header (rounded up to seconds).
+/// Requests with an unknown API key, or without one when one is required, are rejected with a
+/// `401 Unauthorized` status.
+///
+/// A batch of operations sent in one request counts as one request.
+pub(crate) async fn rate_limit_middleware<B>(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let client = match limiter.client(limiter.api_key(&request), &addr) {
+        Ok(client) => client,
+        Err(reason) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                graphql_error_response(code::UNAUTHENTICATED, reason),
+            )
+                .into_response();
+        }
+    };
+
+    let taken = match client.rate {
+        Some(rate) => limiter.take(&client.id, rate, Instant::now()),
+        None => Ok(()),
+    };
+
+    let mut response = match taken {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => too_many_requests(retry_after),
+    };
+
+    if let Some(tier) = client.tier {
+        response
+            .extensions_mut()
+            .insert(ApiKeyTier(tier.to_string()));
+    }
+    response
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let retry_after_ms = retry_after.as_millis() as u64 + 1;
+    let mut error = graphql_error(
+        code::TOO_MANY_REQUESTS,
+        format!("Rate limit exceeded, retry in {retry_after_ms}ms"),
+    );
+    if let Some(extensions) = &mut error.extensions {
+        extensions.set("retryAfterMs", retry_after_ms);
+    }
+
+    let retry_after_secs = (retry_after_ms + 999) / 1000;
+    let response = async_graphql::Response::from_errors(vec![error]);
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        async_graphql_axum::GraphQLResponse::from(response),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::RateTier;
+
+    use super::*;
+
+    fn limiter(requests_per_second: u32, burst: Option<u32>) -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig {
+            requests_per_second,
+            burst,
+            api_key_header: Some("x-api-key".to_string()),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    fn tiered_limiter(require_api_key: bool) -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig {
+            requests_per_second: 1,
+            api_key_header: Some("x-api-key".to_string()),
+            tiers: BTreeMap::from([
+                (
+                    "partner".to_string(),
+                    RateTier {
+                        requests_per_second: 10,
+                        burst: Some(20),
+                    },
+                ),
+                ("internal".to_string(), RateTier::default()),
+            ]),
+            api_keys: BTreeMap::from([
+                ("p".to_string(), "partner".to_string()),
+                ("i".to_string(), "internal".to_string()),
+            ]),
+            require_api_key,
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_unlimited_by_default() {
+        assert!(RateLimiter::new(&RateLimitConfig::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = limiter(2, Some(3));
+        let rate = limiter.default.unwrap();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.take("a", rate, start), Ok(()));
+        }
+
+        // The bucket is empty, and is refilled at two tokens a second.
+        assert_eq!(
+            limiter.take("a", rate, start),
+            Err(Duration::from_millis(500))
+        );
+
+        // Other clients have their own buckets.
+        assert_eq!(limiter.take("b", rate, start), Ok(()));
+
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.take("a", rate, later), Ok(()));
+        assert!(limiter.take("a", rate, later).is_err());
+
+        // Buckets never hold more than the burst.
+        let much_later = later + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.take("a", rate, much_later), Ok(()));
+        }
+        assert!(limiter.take("a", rate, much_later).is_err());
+    }
+
+    #[test]
+    fn test_clients_by_api_key_or_ip() {
+        let limiter = tiered_limiter(false);
+        let addr: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+
+        let request = Request::builder().body(()).unwrap();
+        let key = limiter.api_key(&request);
+        assert_eq!(limiter.client(key, &addr).unwrap().id, "ip:10.0.0.1");
+
+        let request = Request::builder()
+            .header("x-api-key", "p")
+            .body(())
+            .unwrap();
+        let key = limiter.api_key(&request);
+        assert_eq!(limiter.client(key, &addr).unwrap().id, "key:p");
+    }
+
+    #[test]
+    fn test_api_keys_ignored_without_known_keys() {
+        let limiter = limiter(1, None);
+        let addr: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+
+        // Made up keys share the bucket of the address they are sent from, so sending a new one
+        // with each request doesn't get around the limit.
+        for key in ["a", "b"] {
+            let request = Request::builder()
+                .header("x-api-key", key)
+                .body(())
+                .unwrap();
+            let key = limiter.api_key(&request);
+            let client = limiter.client(key, &addr).unwrap();
+            assert_eq!(client.id, "ip:10.0.0.1");
+            assert_eq!(client.tier, None);
+        }
+    }
+
+    #[test]
+    fn test_clients_by_tier() {
+        let limiter = tiered_limiter(false);
+        let addr: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+
+        let partner = limiter.client(Some("p"), &addr).unwrap();
+        assert_eq!(partner.tier, Some("partner"));
+        assert_eq!(partner.rate, Rate::new(10, Some(20)));
+
+        // A tier without a rate is not limited.
+        let internal = limiter.client(Some("i"), &addr).unwrap();
+        assert_eq!(internal.tier, Some("internal"));
+        assert_eq!(internal.rate, None);
+
+        // Clients without a key get the default limits.
+        let anonymous = limiter.client(None, &addr).unwrap();
+        assert_eq!(anonymous.tier, None);
+        assert_eq!(anonymous.rate, Rate::new(1, None));
+
+        assert_eq!(limiter.client(Some("x"), &addr), Err("Unknown API key"));
+    }
+
+    #[test]
+    fn test_require_api_key() {
+        let limiter = tiered_limiter(true);
+        let addr: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+
+        assert!(limiter.client(Some("p"), &addr).is_ok());
+        assert_eq!(limiter.client(None, &addr), Err("Missing API key"));
+    }
+
+    #[test]
+    fn test_bad_api_key_config() {
+        let config = RateLimitConfig {
+            api_keys: BTreeMap::from([("k".to_string(), "missing".to_string())]),
+            ..Default::default()
+        };
+        assert!(RateLimiter::new(&config).is_err());
+
+        let config = RateLimitConfig {
+            api_key_header: Some("x-api-key".to_string()),
+            ..config
+        };
+        assert!(RateLimiter::new(&config).is_err());
+    }
+}
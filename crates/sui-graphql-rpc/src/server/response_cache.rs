@@ -0,0 +1,465 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::TypeId;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
+    parser::{
+        parse_query,
+        types::{Field, OperationType, Selection, SelectionSet},
+    },
+    Name, Request, Response, Value, Variables,
+};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use lru::LruCache;
+use redis::AsyncCommands;
+use tracing::warn;
+
+use crate::config::ResponseCacheConfig;
+use crate::error::Error;
+use crate::extensions::consistent_read::request_read_scope;
+use crate::server::version::SchemaVersion;
+
+/// Root fields whose response does not change once it has been found, as long as the argument
+/// named alongside them is set, because it identifies a point in the history of the chain. Only
+/// the fields of theirs named alongside them can be selected, as the rest may read live data
+/// (e.g. the current balance of an object's owner).
+const HISTORICAL_FIELDS: &[(&str, Option<&str>, &[&str])] = &[
+    ("chainIdentifier", None, &[]),
+    (
+        "checkpoint",
+        Some("id"),
+        &[
+            "digest",
+            "sequenceNumber",
+            "validatorSignature",
+            "previousCheckpointDigest",
+            "liveObjectSetDigest",
+            "networkTotalTransactions",
+        ],
+    ),
+    ("transactionBlock", Some("digest"), &["digest", "bcs"]),
+    ("transactionBlocks", Some("digests"), &["digest", "bcs"]),
+    (
+        "object",
+        Some("version"),
+        &["version", "digest", "storageRebate", "location"],
+    ),
+];
+
+/// Caches the responses to queries, so that repeated queries are not served from the database
+/// again until another checkpoint has been indexed. Responses are keyed on a hash of the query,
+/// and the checkpoint that the request read at (see `ConsistentRead`), so a response is only
+/// served to requests that read at the same checkpoint, on whichever database they are sent to.
+///
+/// Queries that only read historical data (see `HISTORICAL_FIELDS`) are keyed on their hash alone,
+/// as their responses stay the same as new checkpoints are indexed, once everything they ask for
+/// has been found.
+///
+/// Only responses without errors are cached, and only their data, not their extensions.
+pub(crate) struct ResponseCache {
+    backend: Backend,
+}
+
+/// Serves requests tagged with a `RequestKey` through the response cache. Registered after every
+/// other extension, so that it runs once the request has picked the checkpoint it reads at.
+pub(crate) struct CachedResponses(pub Arc<ResponseCache>);
+
+struct CachedResponsesExtension(Arc<ResponseCache>);
+
+/// Identifies the response to a request, independent of the checkpoint it is read at. Requests
+/// that differ in their query, operation name, variables or the version of the schema they ask for
+/// get different keys.
+pub(crate) struct RequestKey {
+    digest: String,
+    /// Whether the request only reads historical data.
+    historical: bool,
+}
+
+enum Backend {
+    InMemory(Mutex<LruCache<String, Vec<u8>>>),
+    /// Shared between instances of the service, with responses expiring after `ttl_secs`,
+    /// because Redis does not evict them on its own.
+    Redis {
+        conn: redis::aio::ConnectionManager,
+        ttl_secs: usize,
+    },
+}
+
+impl ResponseCache {
+    /// The cache described by `config`, or `None` if responses should not be cached.
+    pub(crate) async fn new(config: &ResponseCacheConfig) -> Result<Option<Self>, Error> {
+        let backend = if let Some(url) = &config.redis_url {
+            let client = redis::Client::open(url.as_str())
+                .map_err(|e| Error::Internal(format!("Invalid Redis URL: {e}")))?;
+            let conn = redis::aio::ConnectionManager::new(client)
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to connect to Redis: {e}")))?;
+            Backend::Redis {
+                conn,
+                ttl_secs: config.ttl_secs as usize,
+            }
+        } else if let Some(max_entries) = NonZeroUsize::new(config.max_entries) {
+            Backend::InMemory(Mutex::new(LruCache::new(max_entries)))
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self { backend }))
+    }
+
+    /// Serves the request identified by `key` from the cache if its response is cached, or by
+    /// running `execute` and caching its response otherwise. `checkpoint` is the checkpoint that
+    /// the request reads at, if it picked one. Requests that read live data are not cached without
+    /// one.
+    async fn execute(
+        &self,
+        key: &RequestKey,
+        checkpoint: Option<i64>,
+        execute: impl Future<Output = Response>,
+    ) -> Response {
+        let cache_key = match (key.historical, checkpoint) {
+            (true, _) => format!("graphql:historical:{}", key.digest),
+            (false, Some(checkpoint)) => format!("graphql:{checkpoint}:{}", key.digest),
+            (false, None) => return execute.await,
+        };
+
+        if let Some(data) = self.get(&cache_key).await {
+            match serde_json::from_slice::<Value>(&data) {
+                Ok(data) => return Response::new(data),
+                Err(e) => warn!("Failed to decode cached response: {e}"),
+            }
+        }
+
+        let response = execute.await;
+        // Something a historical query asks for that has not been found may still be indexed
+        // later, so its response can only be kept once everything has been found.
+        if response.errors.is_empty() && (!key.historical || all_found(&response.data)) {
+            match serde_json::to_vec(&response.data) {
+                Ok(data) => self.put(cache_key, data).await,
+                Err(e) => warn!("Failed to encode response to cache: {e}"),
+            }
+        }
+        response
+    }
+
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        match &self.backend {
+            Backend::InMemory(cache) => cache.lock().unwrap().get(key).cloned(),
+            Backend::Redis { conn, .. } => conn
+                .clone()
+                .get::<_, Option<Vec<u8>>>(key)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to read cached response from Redis: {e}");
+                    None
+                }),
+        }
+    }
+
+    async fn put(&self, key: String, data: Vec<u8>) {
+        match &self.backend {
+            Backend::InMemory(cache) => {
+                cache.lock().unwrap().put(key, data);
+            }
+            Backend::Redis { conn, ttl_secs } => {
+                if let Err(e) = conn.clone().set_ex::<_, _, ()>(key, data, *ttl_secs).await {
+                    warn!("Failed to cache response in Redis: {e}");
+                }
+            }
+        }
+    }
+}
+
+impl ExtensionFactory for CachedResponses {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(CachedResponsesExtension(self.0.clone()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for CachedResponsesExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let Some(key) = ctx.data_opt::<RequestKey>() else {
+            return next.run(ctx).await;
+        };
+
+        let checkpoint = request_read_scope().map(|scope| scope.checkpoint);
+        self.0.execute(key, checkpoint, next.run(ctx)).await
+    }
+}
+
+impl RequestKey {
+    pub(crate) fn of(request: &Request) -> Self {
+        let version = request
+            .data
+            .get(&TypeId::of::<SchemaVersion>())
+            .and_then(|v| v.downcast_ref::<SchemaVersion>())
+            .map(|v| v.to_string());
+        let payload = serde_json::to_vec(&(
+            &request.query,
+            &request.operation_name,
+            &request.variables,
+            version,
+        ))
+        .expect("Serializing a request cannot fail");
+        let digest = Blake2b256::digest(payload).digest;
+
+        Self {
+            digest: hex::encode(digest),
+            historical: is_historical(request),
+        }
+    }
+}
+
+/// Whether `request` is a query that only selects `HISTORICAL_FIELDS`, without fragments, so that
+/// it only reads historical data. Requests that fail to parse are not, and fail when they run.
+fn is_historical(request: &Request) -> bool {
+    let Ok(doc) = parse_query(&request.query) else {
+        return false;
+    };
+    let operation = match &request.operation_name {
+        Some(name) => doc
+            .operations
+            .iter()
+            .find(|(n, _)| n.map(Name::as_str) == Some(name.as_str())),
+        None => doc.operations.iter().next(),
+    };
+    let Some((_, operation)) = operation else {
+        return false;
+    };
+    if operation.node.ty != OperationType::Query {
+        return false;
+    }
+
+    fields(&operation.node.selection_set.node).is_some_and(|fields| {
+        fields.into_iter().all(|field| {
+            HISTORICAL_FIELDS.iter().any(|(name, arg, selectable)| {
+                field.name.node.as_str() == *name
+                    && arg.map_or(true, |arg| is_set(field, arg, &request.variables))
+                    && fields(&field.selection_set.node).is_some_and(|selected| {
+                        selected.into_iter().all(|f| {
+                            f.selection_set.node.items.is_empty()
+                                && (f.name.node.as_str() == "__typename"
+                                    || selectable.contains(&f.name.node.as_str()))
+                        })
+                    })
+            })
+        })
+    })
+}
+
+/// The fields selected directly in `selection_set`, or `None` if it spreads fragments.
+fn fields(selection_set: &SelectionSet) -> Option<Vec<&Field>> {
+    selection_set
+        .items
+        .iter()
+        .map(|selection| match &selection.node {
+            Selection::Field(field) => Some(&field.node),
+            Selection::FragmentSpread(_) | Selection::InlineFragment(_) => None,
+        })
+        .collect()
+}
+
+/// Whether `field`'s argument called `arg` is set to something other than null, once any
+/// variables in it are resolved against `variables`. Input objects are only set if one of their
+/// fields is.
+fn is_set(field: &Field, arg: &str, variables: &Variables) -> bool {
+    let Some(value) = field.get_argument(arg) else {
+        return false;
+    };
+    let Ok(value) = value
+        .node
+        .clone()
+        .into_const_with(|name| variables.get(&name).cloned().ok_or(()))
+    else {
+        return false;
+    };
+    match value {
+        Value::Null => false,
+        Value::Object(fields) => fields.values().any(|v| *v != Value::Null),
+        _ => true,
+    }
+}
+
+/// Whether every root field in `data` was found, including every element of root fields that are
+/// lists.
+fn all_found(data: &Value) -> bool {
+    let Value::Object(fields) = data else {
+        return false;
+    };
+    fields.values().all(|value| match value {
+        Value::Null => false,
+        Value::List(items) => items.iter().all(|item| *item != Value::Null),
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::ServerError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cache(max_entries: usize) -> ResponseCache {
+        ResponseCache {
+            backend: Backend::InMemory(Mutex::new(LruCache::new(
+                NonZeroUsize::new(max_entries).unwrap(),
+            ))),
+        }
+    }
+
+    /// Executes `request` through `cache` as if it read at `checkpoint`, counting how many times
+    /// it actually ran in `runs`, and responding with `data`, or the number of times it ran if
+    /// there is none.
+    async fn execute(
+        cache: &ResponseCache,
+        request: Request,
+        checkpoint: Option<i64>,
+        data: Option<Value>,
+        runs: &Arc<AtomicUsize>,
+    ) -> Response {
+        let runs = runs.clone();
+        let key = RequestKey::of(&request);
+        cache
+            .execute(&key, checkpoint, async move {
+                let n = runs.fetch_add(1, Ordering::SeqCst);
+                Response::new(data.unwrap_or_else(|| Value::from(n as i32)))
+            })
+            .await
+    }
+
+    fn historical(query: &str) -> bool {
+        RequestKey::of(&Request::new(query)).historical
+    }
+
+    #[tokio::test]
+    async fn test_cached_per_checkpoint() {
+        let cache = cache(10);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let query = "{ checkpoint { sequenceNumber } }";
+
+        // Nothing is cached for requests that did not pick a checkpoint to read at.
+        execute(&cache, Request::new(query), None, None, &runs).await;
+        execute(&cache, Request::new(query), None, None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        let first = execute(&cache, Request::new(query), Some(5), None, &runs).await;
+        let second = execute(&cache, Request::new(query), Some(5), None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(first.data, second.data);
+
+        // Different variables make for a different query.
+        let variables = Variables::from_json(serde_json::json!({ "id": 1 }));
+        let request = Request::new(query).variables(variables);
+        execute(&cache, request, Some(5), None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+
+        // So does asking for a different version of the schema.
+        let request = Request::new(query).data(SchemaVersion::new(0, 0));
+        execute(&cache, request, Some(5), None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 5);
+
+        // Reading at another checkpoint, later or earlier (e.g. on a replica that lags behind),
+        // does not see the response cached at checkpoint 5.
+        execute(&cache, Request::new(query), Some(6), None, &runs).await;
+        execute(&cache, Request::new(query), Some(4), None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 7);
+        execute(&cache, Request::new(query), Some(5), None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 7);
+    }
+
+    #[tokio::test]
+    async fn test_historical_cached_across_checkpoints() {
+        let cache = cache(10);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let query = r#"{ transactionBlock(digest: "abc") { digest } }"#;
+        let found = Value::from_json(serde_json::json!({
+            "transactionBlock": { "digest": "abc" },
+        }))
+        .unwrap();
+        let missing = Value::from_json(serde_json::json!({ "transactionBlock": null })).unwrap();
+
+        // Not found yet, so it may still be indexed later.
+        execute(&cache, Request::new(query), Some(5), Some(missing), &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Found, and served from the cache at later checkpoints, or without one.
+        let found = execute(&cache, Request::new(query), Some(5), Some(found), &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        let cached = execute(&cache, Request::new(query), Some(6), None, &runs).await;
+        assert_eq!(found.data, cached.data);
+        execute(&cache, Request::new(query), None, None, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_historical_queries() {
+        assert!(historical("{ chainIdentifier }"));
+        assert!(historical(
+            r#"{ transactionBlock(digest: "abc") { __typename digest bcs } }"#
+        ));
+        assert!(historical(
+            "{ checkpoint(id: { sequenceNumber: 3 }) { digest } chainIdentifier }"
+        ));
+        assert!(historical(
+            r#"{ object(address: "0x1", version: 2) { version storageRebate } }"#
+        ));
+
+        // Variables are resolved to check whether arguments are set.
+        let query = "query ($v: Int) { object(address: \"0x1\", version: $v) { digest } }";
+        let set = Variables::from_json(serde_json::json!({ "v": 2 }));
+        let unset = Variables::from_json(serde_json::json!({ "v": null }));
+        assert!(RequestKey::of(&Request::new(query).variables(set)).historical);
+        assert!(!RequestKey::of(&Request::new(query).variables(unset)).historical);
+        assert!(!historical(query));
+
+        // Latest versions of objects and checkpoints.
+        assert!(!historical(r#"{ object(address: "0x1") { digest } }"#));
+        assert!(!historical("{ checkpoint { digest } }"));
+        assert!(!historical("{ checkpoint(id: {}) { digest } }"));
+
+        // Fields that may read live data.
+        assert!(!historical(
+            r#"{ transactionBlock(digest: "abc") { sender { location } } }"#
+        ));
+        assert!(!historical(
+            r#"{ object(address: "0x1", version: 2) { defaultNameServiceName } }"#
+        ));
+        assert!(!historical(
+            r#"{ chainIdentifier transactionBlockConnection { nodes { digest } } }"#
+        ));
+
+        // Fragments are not looked into.
+        assert!(!historical(
+            r#"{ transactionBlock(digest: "abc") { ...F } } fragment F on TransactionBlock { digest }"#
+        ));
+
+        // Only the operation that is run counts.
+        let query = "query A { chainIdentifier } query B { checkpoint { digest } }";
+        assert!(RequestKey::of(&Request::new(query).operation_name("A")).historical);
+        assert!(!RequestKey::of(&Request::new(query).operation_name("B")).historical);
+    }
+
+    #[tokio::test]
+    async fn test_errors_not_cached() {
+        let cache = cache(10);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let key = RequestKey::of(&Request::new("{ chainIdentifier }"));
+
+        for _ in 0..2 {
+            let runs = runs.clone();
+            cache
+                .execute(&key, Some(5), async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    Response::from_errors(vec![ServerError::new("Boom", None)])
+                })
+                .await;
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+}
@@ -2,53 +2,138 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::{ConnectionConfig, ServiceConfig};
+use crate::context_data::cursor::CursorSecret;
 use crate::context_data::data_provider::DataProvider;
 use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::read_router::parse_replica_urls;
 use crate::context_data::sui_sdk_data_provider::{lru_cache_data_loader, sui_sdk_client_v0};
+use crate::extensions::consistent_read::ConsistentRead;
+use crate::extensions::degraded_mode::DegradedMode;
 use crate::extensions::feature_gate::FeatureGate;
 use crate::extensions::limits_info::LimitsInfo;
 use crate::extensions::logger::Logger;
 use crate::extensions::query_limits_checker::QueryLimitsChecker;
+use crate::extensions::query_logging::QueryLogging;
 use crate::extensions::timeout::Timeout;
+use crate::extensions::version_gate::VersionGate;
+use crate::metrics::Metrics;
 use crate::server::builder::ServerBuilder;
+use crate::server::cors::cors_layer;
+use crate::server::rate_limit::RateLimiter;
+use crate::server::response_cache::ResponseCache;
+use crate::types::query::ChainIdentifierCache;
 
 use std::default::Default;
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
 
 pub async fn start_example_server(conn: ConnectionConfig, service_config: ServiceConfig) {
     let _guard = telemetry_subscribers::TelemetryConfig::new()
         .with_env()
         .init();
 
+    if service_config.cursor_secret.is_none() {
+        warn!(
+            "No cursor secret is configured, so cursors are signed with a random one: they will \
+             not be accepted after a restart, or by other instances of the service"
+        );
+    }
+
     let sui_sdk_client_v0 = sui_sdk_client_v0(&conn.rpc_url).await;
     let data_provider: Box<dyn DataProvider> = Box::new(sui_sdk_client_v0.clone());
     let data_loader = lru_cache_data_loader(&sui_sdk_client_v0).await;
 
     // TODO (wlmyng): Allow users to choose which data sources to back graphql
     let db_url = env::var("PG_DB_URL").expect("PG_DB_URL must be set");
-    let pg_conn_pool = PgManager::new(db_url, None)
+    // Comma-separated URLs of read replicas of the database at PG_DB_URL, to spread reads across.
+    let replica_urls = env::var("PG_READ_REPLICA_URLS")
+        .map(|urls| parse_replica_urls(&urls))
+        .unwrap_or_default();
+    let metrics = Arc::new(Metrics::new(prometheus::Registry::new()));
+    let pg_conn_pool = PgManager::new(
+        db_url,
+        replica_urls,
+        None,
+        service_config.query_logging.clone(),
+    )
+    .map_err(|e| {
+        println!("Failed to create pg connection pool: {}", e);
+        e
+    })
+    .unwrap()
+    .with_metrics(metrics.clone())
+    .with_cursor_secret(CursorSecret::new(service_config.cursor_secret.as_deref()));
+    pg_conn_pool.spawn_watermark_monitor();
+    let checkpoint_watcher =
+        pg_conn_pool.spawn_checkpoint_watcher(service_config.limits.max_subscriptions as usize);
+
+    let response_cache = ResponseCache::new(&service_config.response_cache)
+        .await
         .map_err(|e| {
-            println!("Failed to create pg connection pool: {}", e);
+            println!("Failed to create response cache: {}", e);
             e
         })
         .unwrap();
 
+    metrics.spawn_db_pool_monitor(pg_conn_pool.readers());
+
+    let rate_limiter = RateLimiter::new(&service_config.rate_limit)
+        .map_err(|e| {
+            println!("Failed to create rate limiter: {}", e);
+            e
+        })
+        .unwrap();
+
+    let cors = cors_layer(
+        &service_config.cors,
+        service_config.rate_limit.api_key_header.as_deref(),
+    )
+    .map_err(|e| {
+        println!("Failed to configure CORS: {}", e);
+        e
+    })
+    .unwrap();
+
+    let request_timeout = Duration::from_millis(service_config.limits.request_timeout_ms);
+    let query_logging = service_config.query_logging.is_enabled();
+    let circuit_breaker = pg_conn_pool.circuit_breaker();
     let builder = ServerBuilder::new(conn.port, conn.host);
     println!("Launch GraphiQL IDE at: http://{}", builder.address());
 
-    builder
+    let builder = builder
         .max_query_depth(service_config.limits.max_query_depth)
         .max_query_nodes(service_config.limits.max_query_nodes)
+        .max_batch_size(service_config.limits.max_batch_size)
+        .response_cache(response_cache)
+        .rate_limiter(rate_limiter)
+        .cors(cors)
+        .access_log(service_config.access_log.enabled)
+        .metrics(metrics)
+        .circuit_breaker(circuit_breaker)
         .context_data(data_provider)
         .context_data(data_loader)
         .context_data(service_config)
         .context_data(pg_conn_pool)
+        .context_data(checkpoint_watcher)
+        .context_data(ChainIdentifierCache::default())
         .extension(QueryLimitsChecker)
         .extension(FeatureGate)
+        .extension(VersionGate)
         .extension(LimitsInfo)
         .extension(Logger::default())
-        .extension(Timeout::default())
-        .build()
-        .run()
-        .await;
+        .extension(Timeout::new(request_timeout))
+        .extension(ConsistentRead)
+        .extension(DegradedMode);
+
+    // Attributing queries to the fields that make them costs every field that is resolved, so it
+    // is only done if queries are logged.
+    let builder = if query_logging {
+        builder.extension(QueryLogging)
+    } else {
+        builder
+    };
+
+    builder.build().run().await;
 }
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt;
+
 use axum::{
     headers,
     http::{HeaderName, HeaderValue, Request, StatusCode},
@@ -15,7 +17,23 @@ const RPC_VERSION_FULL: &str = env!("CARGO_PKG_VERSION");
 const RPC_VERSION_YEAR: &str = env!("CARGO_PKG_VERSION_MAJOR");
 const RPC_VERSION_MONTH: &str = env!("CARGO_PKG_VERSION_MINOR");
 
-static VERSION_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-version");
+/// Versions of the schema, older than the current one, that this instance of the RPC still
+/// serves, so that clients can upgrade at their own pace once fields they rely on are removed
+/// (see `deprecation::removed_in`). Oldest first.
+const PREVIOUS_VERSIONS: &[SchemaVersion] = &[];
+
+pub(crate) static VERSION_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-version");
+pub(crate) static SUPPORTED_VERSIONS_HEADER: HeaderName =
+    HeaderName::from_static("x-sui-rpc-supported-versions");
+
+/// A version of the schema, named after the `<YEAR>.<MONTH>` release of the RPC that introduced
+/// it. Requests are served the schema as of the version they ask for in their version header, or
+/// the current version if they don't ask for one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) struct SchemaVersion {
+    year: u32,
+    month: u32,
+}
 
 pub(crate) struct SuiRpcVersion(Vec<u8>, Vec<Vec<u8>>);
 
@@ -45,15 +63,50 @@ impl headers::Header for SuiRpcVersion {
     }
 }
 
+impl SchemaVersion {
+    pub(crate) const fn new(year: u32, month: u32) -> Self {
+        Self { year, month }
+    }
+
+    /// The version of the schema of this release of the RPC.
+    pub(crate) fn current() -> Self {
+        Self::from_parts(RPC_VERSION_YEAR, RPC_VERSION_MONTH)
+            .expect("RPC version is a valid schema version")
+    }
+
+    /// Versions of the schema that this instance of the RPC serves, oldest first.
+    pub(crate) fn supported() -> impl Iterator<Item = SchemaVersion> {
+        PREVIOUS_VERSIONS
+            .iter()
+            .copied()
+            .chain(std::iter::once(Self::current()))
+    }
+
+    /// The version with the components that `parse_version` split a version into, or `None` if
+    /// they are too large to be a version.
+    fn from_parts(year: &str, month: &str) -> Option<Self> {
+        Some(Self::new(year.parse().ok()?, month.parse().ok()?))
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.year, self.month)
+    }
+}
+
 /// Middleware to check for the existence of a version constraint in the request header, and confirm
-/// that this instance of the RPC matches that version constraint.  Each RPC instance only supports
-/// one version of the RPC software, and it is the responsibility of the load balancer to make sure
-/// version constraints are met.
+/// that this instance of the RPC serves that version of the schema (see `SchemaVersion::supported`).
+/// It is the responsibility of the load balancer to route requests for other versions to instances
+/// that serve them.
+///
+/// The version is added to the request's extensions, for its fields to be gated on.
 pub(crate) async fn check_version_middleware<B>(
     version: Option<TypedHeader<SuiRpcVersion>>,
-    request: Request<B>,
+    mut request: Request<B>,
     next: Next<B>,
 ) -> Response {
+    let mut schema_version = SchemaVersion::current();
     if let Some(TypedHeader(SuiRpcVersion(req_version, rest))) = version {
         if !rest.is_empty() {
             return (
@@ -89,23 +142,32 @@ pub(crate) async fn check_version_middleware<B>(
             ).into_response();
         };
 
-        if year != RPC_VERSION_YEAR || month != RPC_VERSION_MONTH {
+        let supported = SchemaVersion::from_parts(year, month)
+            .filter(|version| SchemaVersion::supported().any(|v| v == *version));
+
+        let Some(version) = supported else {
             return (
                 StatusCode::MISDIRECTED_REQUEST,
                 graphql_error_response(
                     code::INTERNAL_SERVER_ERROR,
-                    format!("Version '{req_version}' not supported."),
+                    format!(
+                        "Version '{req_version}' not supported. Supported versions: {}.",
+                        supported_versions(),
+                    ),
                 ),
             )
                 .into_response();
-        }
+        };
+
+        schema_version = version;
     };
 
+    request.extensions_mut().insert(schema_version);
     next.run(request).await
 }
 
 /// Mark every outgoing response with a header indicating the precise version of the RPC that was
-/// used (including the patch version).
+/// used (including the patch version), and a header listing the versions of the schema it serves.
 pub(crate) async fn set_version_middleware<B>(request: Request<B>, next: Next<B>) -> Response {
     let mut response = next.run(request).await;
     let headers = response.headers_mut();
@@ -113,9 +175,21 @@ pub(crate) async fn set_version_middleware<B>(request: Request<B>, next: Next<B>
         VERSION_HEADER.clone(),
         HeaderValue::from_static(RPC_VERSION_FULL),
     );
+    headers.insert(
+        SUPPORTED_VERSIONS_HEADER.clone(),
+        HeaderValue::from_str(&supported_versions()).expect("Versions are valid header values"),
+    );
     response
 }
 
+/// The versions of the schema this instance serves, as a comma-separated list.
+fn supported_versions() -> String {
+    SchemaVersion::supported()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Split a `version` string into two parts (year and month) separated by a ".".
 ///
 /// Confirms that the version specifier contains exactly two components, and that both
@@ -200,6 +274,24 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn supported_versions_advertised() {
+        let service = service();
+        let response = service.oneshot(plain_request()).await.unwrap();
+
+        let supported = SchemaVersion::supported()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            response.headers().get(&SUPPORTED_VERSIONS_HEADER),
+            Some(&HeaderValue::from_str(&supported.join(", ")).unwrap()),
+        );
+        assert_eq!(
+            supported.last(),
+            Some(&format!("{RPC_VERSION_YEAR}.{RPC_VERSION_MONTH}"))
+        );
+    }
+
     #[tokio::test]
     async fn default_version() {
         let service = service();
@@ -231,7 +323,7 @@ mod tests {
               "data": null,
               "errors": [
                 {
-                  "message": "Version '0.0' not supported.",
+                  "message": "Version '0.0' not supported. Supported versions: 0.1.",
                   "extensions": {
                     "code": "INTERNAL_SERVER_ERROR"
                   }
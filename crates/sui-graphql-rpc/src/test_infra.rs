@@ -0,0 +1,58 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the full schema against an indexer database loaded with recorded rows, so that resolvers
+//! backed by the database can be regression tested against golden responses without a live
+//! indexer (see `tests/fixture_tests.rs`).
+
+use async_graphql::{EmptyMutation, Schema};
+use diesel::connection::SimpleConnection;
+use sui_indexer::{get_pg_pool_connection, new_pg_connection_pool, utils::reset_database};
+
+use crate::{
+    config::{QueryLoggingConfig, ServiceConfig},
+    context_data::db_data_provider::PgManager,
+    extensions::consistent_read::ConsistentRead,
+    server::builder::ServerBuilder,
+    types::{
+        query::{ChainIdentifierCache, Query},
+        subscription::Subscription,
+    },
+};
+
+/// The schema, reading from a database that only contains the rows of a fixture. Fields that are
+/// not backed by the database (e.g. those that read from a fullnode) can't be resolved by it.
+pub struct FixtureSchema {
+    schema: Schema<Query, EmptyMutation, Subscription>,
+}
+
+impl FixtureSchema {
+    /// Resets the database at `db_url` to the latest indexer schema, dropping all of its data, and
+    /// then runs `fixture`, SQL statements that insert the rows the fixture's queries read.
+    pub fn new(db_url: &str, fixture: &str) -> Self {
+        let pool = new_pg_connection_pool(db_url).expect("Failed to connect to fixture database");
+        let mut conn =
+            get_pg_pool_connection(&pool).expect("Failed to connect to fixture database");
+        reset_database(&mut conn, /* drop_all */ true, /* use_v2 */ true)
+            .expect("Failed to reset fixture database");
+        conn.batch_execute(fixture)
+            .expect("Failed to load fixture into database");
+
+        let pg_manager = PgManager::new(db_url, vec![], None, QueryLoggingConfig::default())
+            .expect("Failed to connect to fixture database");
+        let schema = ServerBuilder::new(0, "127.0.0.1".to_string())
+            .context_data(pg_manager)
+            .context_data(ServiceConfig::default())
+            .context_data(ChainIdentifierCache::default())
+            .extension(ConsistentRead)
+            .build_schema();
+        Self { schema }
+    }
+
+    /// Executes `query`, returning its response as pretty-printed JSON, to compare against a
+    /// golden response.
+    pub async fn execute(&self, query: &str) -> String {
+        let response = self.schema.execute(query).await;
+        serde_json::to_string_pretty(&response).expect("Responses can be serialized to JSON")
+    }
+}
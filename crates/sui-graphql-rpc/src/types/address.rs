@@ -1,15 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use async_graphql::{connection::Connection, *};
+use async_graphql::{
+    connection::{Connection, Edge},
+    *,
+};
 
-use crate::context_data::context_ext::DataProviderContextExt;
+use crate::context_data::{context_ext::DataProviderContextExt, db_data_provider::PgManager};
 
 use super::name_service::NameService;
 use super::{
     balance::Balance,
+    big_int::BigInt,
     coin::Coin,
-    object::{Object, ObjectFilter},
+    object::{Object, ObjectFilter, ObjectKind},
     stake::Stake,
     sui_address::SuiAddress,
     transaction_block::{TransactionBlock, TransactionBlockFilter},
@@ -22,26 +26,86 @@ pub(crate) struct Address {
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 pub(crate) enum AddressTransactionBlockRelationship {
-    Sign, // Transactions this address has signed
-    Sent, // Transactions that transferred objects from this address
-    Recv, // Transactions that received objects into this address
-    Paid, // Transactions that were paid for by this address
+    Sign,     // Transactions this address has signed
+    Sent,     // Transactions that transferred objects from this address
+    Received, // Transactions that transferred objects into this address
+    Paid,     // Transactions that were paid for by this address
+    Affected, // Transactions that were sent by, sent objects to, or were paid for by this address
+}
+
+impl AddressTransactionBlockRelationship {
+    /// Limits `filter` to the transactions that `address` has this relation to.
+    fn restrict(self, filter: &mut TransactionBlockFilter, address: SuiAddress) {
+        use AddressTransactionBlockRelationship as R;
+        match self {
+            R::Sign => filter.sign_address = Some(address),
+            R::Sent => filter.sent_address = Some(address),
+            R::Received => filter.recv_address = Some(address),
+            R::Paid => filter.paid_address = Some(address),
+            R::Affected => filter.affected_address = Some(address),
+        }
+    }
+}
+
+/// Number and total size of the objects owned by an address.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AddressObjectStats {
+    /// Number of objects owned by the address.
+    pub object_count: u64,
+    /// Total storage rebate of the objects owned by the address, in MIST. Storage rebates are
+    /// proportional to the size of the objects, so this measures how much storage the address uses.
+    pub storage_footprint: BigInt,
 }
 
 #[allow(unreachable_code)]
 #[allow(unused_variables)]
 #[Object]
 impl Address {
+    /// Transactions related to this address, signed by it unless `relation` says otherwise.
     async fn transaction_block_connection(
         &self,
+        ctx: &Context<'_>,
         first: Option<u64>,
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
         relation: Option<AddressTransactionBlockRelationship>,
         filter: Option<TransactionBlockFilter>,
-    ) -> Option<Connection<String, TransactionBlock>> {
-        unimplemented!()
+    ) -> Result<Option<Connection<String, TransactionBlock>>> {
+        let mut filter = filter.unwrap_or_default();
+        relation
+            .unwrap_or(AddressTransactionBlockRelationship::Sign)
+            .restrict(&mut filter, self.address);
+
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (txs, has_next_page) = pg_manager
+            .fetch_txs(first, after, last, before, Some(filter))
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for tx in txs {
+            let cursor = pg_manager.tx_cursor(&tx);
+            let tx = TransactionBlock::try_from(tx).extend()?;
+            connection.edges.push(Edge::new(cursor, tx));
+        }
+
+        Ok(Some(connection))
+    }
+
+    /// Number and total size of the objects owned by this address.
+    async fn object_stats(&self, ctx: &Context<'_>) -> Result<Option<AddressObjectStats>> {
+        let stats = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_address_object_stats(self.address)
+            .await
+            .extend()?;
+        let (object_count, storage_rebate) =
+            stats.map_or((0, 0), |s| (s.object_count as u64, s.storage_rebate as u64));
+        Ok(Some(AddressObjectStats {
+            object_count,
+            storage_footprint: BigInt::from(storage_rebate),
+        }))
     }
 
     // =========== Owner interface methods =============
@@ -50,6 +114,7 @@ impl Address {
         self.address
     }
 
+    /// Objects owned by this address, optionally filtered by `filter`, in the order of their IDs.
     pub async fn object_connection(
         &self,
         ctx: &Context<'_>,
@@ -59,9 +124,23 @@ impl Address {
         before: Option<String>,
         filter: Option<ObjectFilter>,
     ) -> Result<Connection<String, Object>> {
-        ctx.data_provider()
-            .fetch_owned_objs(&self.address, first, after, last, before, filter)
+        let mut filter = filter.unwrap_or_default();
+        filter.owner = Some(self.address);
+        filter.owner_kind.get_or_insert(ObjectKind::Owned);
+
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (objects, has_previous_page, has_next_page) = pg_manager
+            .fetch_objs(first, after, last, before, Some(filter))
             .await
+            .extend()?;
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        for object in objects {
+            let cursor = pg_manager.object_cursor(&object);
+            let object = Object::try_from(object).extend()?;
+            connection.edges.push(Edge::new(cursor, object));
+        }
+        Ok(connection)
     }
 
     pub async fn balance(&self, ctx: &Context<'_>, type_: Option<String>) -> Result<Balance> {
@@ -118,3 +197,32 @@ impl Address {
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relationship_restricts_filter() {
+        use AddressTransactionBlockRelationship as R;
+        let address = SuiAddress::from_array([1; 32]);
+        let restricted = |relation: R| {
+            let mut filter = TransactionBlockFilter::default();
+            relation.restrict(&mut filter, address);
+            [
+                filter.sign_address,
+                filter.sent_address,
+                filter.recv_address,
+                filter.paid_address,
+                filter.affected_address,
+            ]
+        };
+
+        let some = Some(address);
+        assert_eq!(restricted(R::Sign), [some, None, None, None, None]);
+        assert_eq!(restricted(R::Sent), [None, some, None, None, None]);
+        assert_eq!(restricted(R::Received), [None, None, some, None, None]);
+        assert_eq!(restricted(R::Paid), [None, None, None, some, None]);
+        assert_eq!(restricted(R::Affected), [None, None, None, None, some]);
+    }
+}
@@ -14,7 +14,9 @@ pub(crate) struct Balance {
 
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
 pub(crate) struct BalanceChange {
+    /// The address or object whose balance changed.
     pub(crate) owner: Owner,
+    /// Negative if the balance decreased.
     pub(crate) amount: BigInt,
-    // pub(crate) coin_type: MoveType,
+    pub(crate) coin_type: String,
 }
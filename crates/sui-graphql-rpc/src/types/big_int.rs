@@ -62,7 +62,7 @@ macro_rules! impl_From {
     }
 }
 
-impl_From!(u8, u16, u32, u64, u128, U256);
+impl_From!(u8, u16, u32, u64, u128, i128, U256);
 
 #[cfg(test)]
 mod tests {
@@ -4,11 +4,19 @@
 use crate::context_data::db_data_provider::PgManager;
 
 use super::{
-    base64::Base64, digest::Digest, end_of_epoch_data::EndOfEpochData, epoch::Epoch,
+    base64::Base64,
+    digest::Digest,
+    end_of_epoch_data::EndOfEpochData,
+    epoch::Epoch,
     gas::GasCostSummary,
+    transaction_block::{TransactionBlock, TransactionBlockFilter},
+};
+use async_graphql::{
+    connection::{Connection, Edge},
+    *,
 };
-use async_graphql::*;
 use sui_indexer::models_v2::checkpoints::StoredCheckpoint;
+use sui_sdk::types::messages_checkpoint::EndOfEpochData as NativeEndOfEpochData;
 
 use crate::error::Error;
 
@@ -33,13 +41,24 @@ pub(crate) struct Checkpoint {
     #[graphql(skip)]
     pub epoch_id: u64,
     pub end_of_epoch: Option<EndOfEpochData>,
-    // transactionConnection(first: Int, after: String, last: Int, before: String): TransactionBlockConnection
     // address_metrics: AddressMetrics,
 }
 
 impl TryFrom<StoredCheckpoint> for Checkpoint {
     type Error = Error;
     fn try_from(c: StoredCheckpoint) -> Result<Self, Self::Error> {
+        let end_of_epoch = c
+            .end_of_epoch_data
+            .map(|data| {
+                bcs::from_bytes::<NativeEndOfEpochData>(&data).map_err(|e| {
+                    Error::Internal(format!(
+                        "Can't convert end_of_epoch_data into EndOfEpochData. Error: {e}"
+                    ))
+                })
+            })
+            .transpose()?
+            .map(EndOfEpochData::from);
+
         Ok(Self {
             digest: Digest::try_from(c.checkpoint_digest)?.to_string(),
             sequence_number: c.sequence_number as u64,
@@ -57,7 +76,7 @@ impl TryFrom<StoredCheckpoint> for Checkpoint {
                 non_refundable_storage_fee: c.non_refundable_storage_fee as u64,
             }),
             epoch_id: c.epoch as u64,
-            end_of_epoch: None,
+            end_of_epoch,
         })
     }
 }
@@ -69,6 +88,34 @@ impl Checkpoint {
             .data_unchecked::<PgManager>()
             .fetch_epoch_strict(self.epoch_id)
             .await?;
-        Ok(Some(Epoch::from(result)))
+        Epoch::try_from(result).map(Some).extend()
+    }
+
+    /// The transactions in this checkpoint, in the order they were executed.
+    async fn transaction_block_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<Connection<String, TransactionBlock>> {
+        let mut filter = filter.unwrap_or_default();
+        filter.checkpoint = Some(self.sequence_number);
+
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (txs, has_next_page) = pg_manager
+            .fetch_txs(first, after, last, before, Some(filter))
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for tx in txs {
+            let cursor = pg_manager.tx_cursor(&tx);
+            let tx = TransactionBlock::try_from(tx).extend()?;
+            connection.edges.push(Edge::new(cursor, tx));
+        }
+        Ok(connection)
     }
 }
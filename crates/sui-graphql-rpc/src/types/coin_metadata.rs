@@ -0,0 +1,118 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use move_core_types::language_storage::StructTag;
+use sui_indexer::models_v2::objects::StoredObject;
+use sui_sdk::types::{
+    coin::{CoinMetadata as NativeCoinMetadata, TreasuryCap},
+    gas_coin::{GAS, TOTAL_SUPPLY_MIST},
+    object::Object as NativeObject,
+    parse_sui_struct_tag,
+};
+
+use super::{big_int::BigInt, object::Object, sui_address::SuiAddress};
+use crate::{
+    context_data::{context_ext::DataProviderContextExt, db_data_provider::PgManager},
+    error::Error,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct CoinMetadata {
+    /// The metadata's object ID.
+    pub address: SuiAddress,
+    /// Number of decimal places the coin uses, to format its balances for display.
+    pub decimals: u8,
+    /// Name of the coin.
+    pub name: String,
+    /// Symbol of the coin.
+    pub symbol: String,
+    /// Description of the coin.
+    pub description: String,
+    /// URL of the coin's logo, if it has one.
+    pub icon_url: Option<String>,
+    /// Number of coins in circulation, in the coin's smallest unit, as recorded by its
+    /// `TreasuryCap`. `null` if the `TreasuryCap` could not be found (e.g. because it has been
+    /// wrapped in another object).
+    pub total_supply: Option<BigInt>,
+}
+
+#[ComplexObject]
+impl CoinMetadata {
+    /// The `CoinMetadata` object itself.
+    async fn object(&self, ctx: &Context<'_>) -> Result<Option<Object>> {
+        ctx.data_provider().fetch_obj(self.address, None).await
+    }
+}
+
+impl CoinMetadata {
+    /// Fetch the metadata of coins of type `coin_type` (e.g. `0x2::sui::SUI`), along with their
+    /// total supply, returning `None` if the coin has no `CoinMetadata`.
+    pub(crate) async fn query(pg: &PgManager, coin_type: &str) -> Result<Option<Self>, Error> {
+        let coin_struct = parse_sui_struct_tag(coin_type)
+            .map_err(|e| Error::InvalidType(format!("{coin_type}: {e}")))?;
+
+        let metadata_type = NativeCoinMetadata::type_(coin_struct.clone());
+        let Some(stored) = pg.fetch_obj_by_type(metadata_type.to_string()).await? else {
+            return Ok(None);
+        };
+
+        let Some(contents) = move_contents(stored, &metadata_type)? else {
+            return Ok(None);
+        };
+
+        let NativeCoinMetadata {
+            id,
+            decimals,
+            name,
+            symbol,
+            description,
+            icon_url,
+        } = NativeCoinMetadata::from_bcs_bytes(&contents)
+            .map_err(|e| Error::Internal(e.to_string()))?;
+
+        let total_supply = if GAS::is_gas(&coin_struct) {
+            // SUI's `TreasuryCap` is wrapped in the system state, and its supply is fixed.
+            Some(TOTAL_SUPPLY_MIST)
+        } else {
+            let treasury_cap_type = TreasuryCap::type_(coin_struct);
+            match pg.fetch_obj_by_type(treasury_cap_type.to_string()).await? {
+                None => None,
+                Some(stored) => match move_contents(stored, &treasury_cap_type)? {
+                    None => None,
+                    Some(contents) => Some(
+                        TreasuryCap::from_bcs_bytes(&contents)
+                            .map_err(|e| Error::Internal(e.to_string()))?
+                            .total_supply
+                            .value,
+                    ),
+                },
+            }
+        };
+
+        Ok(Some(Self {
+            address: SuiAddress::from_array(id.id.bytes.into_bytes()),
+            decimals,
+            name,
+            symbol,
+            description,
+            icon_url,
+            total_supply: total_supply.map(BigInt::from),
+        }))
+    }
+}
+
+/// The contents of `stored`, if it is a Move object of type `expected`.
+fn move_contents(stored: StoredObject, expected: &StructTag) -> Result<Option<Vec<u8>>, Error> {
+    let object = NativeObject::try_from(stored)?;
+    let Some(move_object) = object.data.try_as_move() else {
+        return Ok(None);
+    };
+
+    if &StructTag::from(move_object.type_().clone()) != expected {
+        return Ok(None);
+    }
+
+    Ok(Some(move_object.contents().to_vec()))
+}
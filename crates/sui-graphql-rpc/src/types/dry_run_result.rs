@@ -0,0 +1,310 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use async_graphql::*;
+use sui_json_rpc_types::{
+    BalanceChange as NativeBalanceChange, DryRunTransactionBlockResponse,
+    ObjectChange as NativeObjectChange, SuiEvent,
+};
+use sui_sdk::types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress as NativeSuiAddress},
+    digests::ObjectDigest,
+    object::Owner as NativeOwner,
+    transaction::{GasData, TransactionData, TransactionKind},
+};
+
+use super::{
+    address::Address, balance::BalanceChange, base64::Base64, big_int::BigInt, date_time::DateTime,
+    digest::Digest, event::Event, owner::Owner, sui_address::SuiAddress,
+    transaction_block::TransactionBlockEffects,
+};
+use crate::{
+    context_data::data_provider::DataProvider,
+    error::{code, graphql_error},
+};
+
+/// Gas and sender details to run transaction kind bytes with, in place of the ones that
+/// transaction data bytes would carry.
+#[derive(InputObject, Debug, Default)]
+pub(crate) struct TransactionMetadata {
+    /// Defaults to `0x0`.
+    pub sender: Option<SuiAddress>,
+    /// Defaults to the current reference gas price.
+    pub gas_price: Option<u64>,
+    /// Coins to pay for gas with. If there are none, the transaction is paid for with a mock coin
+    /// that holds enough SUI to cover the budget.
+    pub gas_objects: Option<Vec<ObjectRef>>,
+    /// Defaults to the maximum budget a transaction can have.
+    pub gas_budget: Option<u64>,
+    /// Address paying for gas. Defaults to the sender.
+    pub gas_sponsor: Option<SuiAddress>,
+}
+
+#[derive(InputObject, Debug)]
+pub(crate) struct ObjectRef {
+    pub address: SuiAddress,
+    pub version: u64,
+    pub digest: String,
+}
+
+impl TransactionMetadata {
+    /// Transaction data that runs `kind` with these details, filling in the ones that were not
+    /// given from the current state of the network.
+    pub(crate) async fn into_tx_data(
+        self,
+        kind: TransactionKind,
+        data_provider: &dyn DataProvider,
+    ) -> Result<TransactionData> {
+        let sender = self
+            .sender
+            .map(NativeSuiAddress::from)
+            .unwrap_or(NativeSuiAddress::ZERO);
+
+        let price = match self.gas_price {
+            Some(price) => price,
+            None => {
+                data_provider
+                    .get_latest_sui_system_state()
+                    .await?
+                    .reference_gas_price
+            }
+        };
+
+        let budget = match self.gas_budget {
+            Some(budget) => budget,
+            None => data_provider.fetch_max_tx_gas().await?,
+        };
+
+        let payment = self
+            .gas_objects
+            .unwrap_or_default()
+            .into_iter()
+            .map(|object| {
+                let digest = ObjectDigest::from_str(&object.digest).map_err(|e| {
+                    graphql_error(
+                        code::BAD_USER_INPUT,
+                        format!("Invalid gas object digest {}: {e}", object.digest),
+                    )
+                })?;
+                Ok((
+                    ObjectID::new(object.address.into_array()),
+                    SequenceNumber::from_u64(object.version),
+                    digest,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let owner = self
+            .gas_sponsor
+            .map(NativeSuiAddress::from)
+            .unwrap_or(sender);
+        Ok(TransactionData::new_with_gas_data(
+            kind,
+            sender,
+            GasData {
+                payment,
+                owner,
+                price,
+                budget,
+            },
+        ))
+    }
+}
+
+/// What a transaction would do if it were executed, without executing it.
+#[derive(Clone, PartialEq, Eq, SimpleObject)]
+pub(crate) struct DryRunResult {
+    pub effects: TransactionBlockEffects,
+    pub events: Vec<Event>,
+    pub object_changes: Vec<ObjectChange>,
+    /// Changes to the balances of addresses and objects. Changes to the balances of shared or
+    /// immutable objects are not listed.
+    pub balance_changes: Vec<BalanceChange>,
+    /// Computation gas units used by each command of a programmable transaction, by command
+    /// index. Commands after one that failed are not listed.
+    pub command_gas_used: Vec<BigInt>,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ObjectChangeKind {
+    Published,
+    Created,
+    Mutated,
+    Transferred,
+    Wrapped,
+    Deleted,
+}
+
+/// An object or package created, modified, or removed by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct ObjectChange {
+    pub kind: ObjectChangeKind,
+    /// The object's ID, or the package's, for published packages.
+    pub address: SuiAddress,
+    /// Version of the object after the transaction.
+    pub version: u64,
+    /// Digest of the object after the transaction, null if it was deleted or wrapped.
+    pub digest: Option<String>,
+    /// Null for published packages.
+    pub object_type: Option<String>,
+    /// Owner of the object after the transaction, null if it is not owned by an address or
+    /// object.
+    pub owner: Option<Owner>,
+}
+
+impl From<DryRunTransactionBlockResponse> for DryRunResult {
+    fn from(response: DryRunTransactionBlockResponse) -> Self {
+        Self {
+            effects: TransactionBlockEffects::from(&response.effects),
+            events: response.events.data.into_iter().map(Event::from).collect(),
+            object_changes: response
+                .object_changes
+                .into_iter()
+                .map(ObjectChange::from)
+                .collect(),
+            balance_changes: response
+                .balance_changes
+                .into_iter()
+                .filter_map(balance_change)
+                .collect(),
+            command_gas_used: response
+                .command_gas_used
+                .into_iter()
+                .map(BigInt::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<NativeObjectChange> for ObjectChange {
+    fn from(change: NativeObjectChange) -> Self {
+        let id = |id: ObjectID| SuiAddress::from_array(id.into_bytes());
+        match change {
+            NativeObjectChange::Published {
+                package_id,
+                version,
+                digest,
+                ..
+            } => Self {
+                kind: ObjectChangeKind::Published,
+                address: id(package_id),
+                version: version.value(),
+                digest: Some(digest.to_string()),
+                object_type: None,
+                owner: None,
+            },
+            NativeObjectChange::Created {
+                owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => Self {
+                kind: ObjectChangeKind::Created,
+                address: id(object_id),
+                version: version.value(),
+                digest: Some(digest.to_string()),
+                object_type: Some(object_type.to_canonical_string()),
+                owner: owner_address(owner),
+            },
+            NativeObjectChange::Mutated {
+                owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => Self {
+                kind: ObjectChangeKind::Mutated,
+                address: id(object_id),
+                version: version.value(),
+                digest: Some(digest.to_string()),
+                object_type: Some(object_type.to_canonical_string()),
+                owner: owner_address(owner),
+            },
+            NativeObjectChange::Transferred {
+                recipient,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => Self {
+                kind: ObjectChangeKind::Transferred,
+                address: id(object_id),
+                version: version.value(),
+                digest: Some(digest.to_string()),
+                object_type: Some(object_type.to_canonical_string()),
+                owner: owner_address(recipient),
+            },
+            NativeObjectChange::Wrapped {
+                object_type,
+                object_id,
+                version,
+                ..
+            } => Self {
+                kind: ObjectChangeKind::Wrapped,
+                address: id(object_id),
+                version: version.value(),
+                digest: None,
+                object_type: Some(object_type.to_canonical_string()),
+                owner: None,
+            },
+            NativeObjectChange::Deleted {
+                object_type,
+                object_id,
+                version,
+                ..
+            } => Self {
+                kind: ObjectChangeKind::Deleted,
+                address: id(object_id),
+                version: version.value(),
+                digest: None,
+                object_type: Some(object_type.to_canonical_string()),
+                owner: None,
+            },
+        }
+    }
+}
+
+/// The change to an address's or object's balance, or `None` if the balance belongs to neither.
+fn balance_change(change: NativeBalanceChange) -> Option<BalanceChange> {
+    Some(BalanceChange {
+        owner: owner_address(change.owner)?,
+        amount: BigInt::from(change.amount),
+        coin_type: change.coin_type.to_canonical_string(),
+    })
+}
+
+impl From<SuiEvent> for Event {
+    fn from(event: SuiEvent) -> Self {
+        Self {
+            sending_package: SuiAddress::from_array(event.package_id.into_bytes()),
+            sending_module: event.transaction_module.to_string(),
+            event_type: event.type_.to_canonical_string(),
+            senders: vec![Address {
+                address: SuiAddress::from_array(event.sender.to_inner()),
+            }],
+            timestamp: event
+                .timestamp_ms
+                .and_then(|ms| DateTime::from_ms(ms as i64)),
+            bcs: Base64::from(event.bcs),
+            transaction_digest: Digest::from_array(event.id.tx_digest.into_inner()),
+        }
+    }
+}
+
+fn owner_address(owner: NativeOwner) -> Option<Owner> {
+    match owner {
+        NativeOwner::AddressOwner(address) => Some(Owner {
+            address: SuiAddress::from_array(address.to_inner()),
+        }),
+        NativeOwner::ObjectOwner(address) => Some(Owner {
+            address: SuiAddress::from_array(address.to_inner()),
+        }),
+        NativeOwner::Shared { .. } | NativeOwner::Immutable => None,
+    }
+}
@@ -1,11 +1,131 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use super::committee_member::CommitteeMember;
+use crate::error::Error;
 use async_graphql::*;
+use sui_indexer::models_v2::{checkpoints::StoredCheckpoint, epoch::StoredEpochInfo};
+use sui_sdk::types::{
+    crypto::{AuthorityPublicKeyBytes, ToFromBytes},
+    messages_checkpoint::EndOfEpochData as NativeEndOfEpochData,
+    sui_system_state::sui_system_state_summary::SuiValidatorSummary,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
 pub(crate) struct EndOfEpochData {
     pub new_committee: Option<Vec<CommitteeMember>>,
     pub next_protocol_version: Option<u64>,
 }
+
+/// How an epoch ended, as recorded by its last checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct EndOfEpochInfo {
+    /// Sequence number of the last checkpoint of the epoch.
+    pub last_checkpoint: u64,
+    /// The committee for the next epoch.
+    pub new_committee: Vec<CommitteeMember>,
+    pub protocol_version: u64,
+    pub next_protocol_version: u64,
+    /// Committee members whose stake differs between this epoch and the next, including members
+    /// that joined (previous stake of zero) or left (new stake of zero) the committee.
+    pub stake_changes: Vec<CommitteeStakeChange>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct CommitteeStakeChange {
+    pub authority_name: String,
+    pub previous_stake_unit: u64,
+    pub stake_unit: u64,
+}
+
+impl From<NativeEndOfEpochData> for EndOfEpochData {
+    fn from(e: NativeEndOfEpochData) -> Self {
+        let new_committee = if e.next_epoch_committee.is_empty() {
+            None
+        } else {
+            Some(committee_members(&e.next_epoch_committee))
+        };
+
+        Self {
+            new_committee,
+            next_protocol_version: Some(e.next_epoch_protocol_version.as_u64()),
+        }
+    }
+}
+
+impl EndOfEpochInfo {
+    /// Decodes the end of epoch data of `checkpoint`, the last checkpoint of `epoch`, comparing
+    /// the next epoch's committee against the validators `epoch` started with.
+    pub(crate) fn try_from_stored(
+        epoch: &StoredEpochInfo,
+        checkpoint: &StoredCheckpoint,
+    ) -> Result<Self, Error> {
+        let data = checkpoint.end_of_epoch_data.as_ref().ok_or_else(|| {
+            Error::Internal(format!(
+                "Checkpoint {} has no end of epoch data",
+                checkpoint.sequence_number
+            ))
+        })?;
+
+        let data: NativeEndOfEpochData = bcs::from_bytes(data).map_err(|e| {
+            Error::Internal(format!(
+                "Can't convert end_of_epoch_data into EndOfEpochData. Error: {e}"
+            ))
+        })?;
+
+        let mut previous_stakes = BTreeMap::new();
+        for validator in epoch.validators.iter().flatten() {
+            let validator: SuiValidatorSummary = bcs::from_bytes(validator).map_err(|e| {
+                Error::Internal(format!(
+                    "Can't convert validator into SuiValidatorSummary. Error: {e}"
+                ))
+            })?;
+            let name = AuthorityPublicKeyBytes::from_bytes(&validator.protocol_pubkey_bytes)
+                .map_err(|e| {
+                    Error::Internal(format!("Invalid validator protocol public key. Error: {e}"))
+                })?;
+            previous_stakes.insert(name, validator.voting_power);
+        }
+
+        let mut stake_changes = vec![];
+        for (name, stake) in &data.next_epoch_committee {
+            let previous_stake = previous_stakes.remove(name).unwrap_or(0);
+            if previous_stake != *stake {
+                stake_changes.push(CommitteeStakeChange {
+                    authority_name: name.into_concise().to_string(),
+                    previous_stake_unit: previous_stake,
+                    stake_unit: *stake,
+                });
+            }
+        }
+
+        // Whoever is left did not make it into the next committee.
+        for (name, previous_stake) in previous_stakes {
+            stake_changes.push(CommitteeStakeChange {
+                authority_name: name.into_concise().to_string(),
+                previous_stake_unit: previous_stake,
+                stake_unit: 0,
+            });
+        }
+
+        Ok(Self {
+            last_checkpoint: checkpoint.sequence_number as u64,
+            new_committee: committee_members(&data.next_epoch_committee),
+            protocol_version: epoch.protocol_version as u64,
+            next_protocol_version: data.next_epoch_protocol_version.as_u64(),
+            stake_changes,
+        })
+    }
+}
+
+fn committee_members(committee: &[(AuthorityPublicKeyBytes, u64)]) -> Vec<CommitteeMember> {
+    committee
+        .iter()
+        .map(|(name, stake)| CommitteeMember {
+            authority_name: Some(name.into_concise().to_string()),
+            stake_unit: Some(*stake),
+        })
+        .collect()
+}
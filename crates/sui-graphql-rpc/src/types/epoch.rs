@@ -2,17 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::big_int::BigInt;
+use super::checkpoint::Checkpoint;
 use super::date_time::DateTime;
+use super::end_of_epoch_data::EndOfEpochInfo;
 use super::protocol_config::ProtocolConfigs;
 use super::safe_mode::SafeMode;
 use super::stake_subsidy::StakeSubsidy;
 use super::storage_fund::StorageFund;
 use super::system_parameters::SystemParameters;
 use super::validator_set::ValidatorSet;
+use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::sui_sdk_data_provider::convert_to_validators;
+use crate::error::Error;
+use async_graphql::connection::{Connection, Edge};
 use async_graphql::*;
 use sui_indexer::models_v2::epoch::StoredEpochInfo;
+use sui_sdk::types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
 
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct Epoch {
     pub epoch_id: u64,
     pub system_state_version: Option<BigInt>,
@@ -24,22 +32,107 @@ pub(crate) struct Epoch {
     pub storage_fund: Option<StorageFund>,
     pub safe_mode: Option<SafeMode>,
     pub start_timestamp: Option<DateTime>,
-    // pub end_timestamp: Option<DateTime>, //TODO decide if we want this data exposed or not
+    /// Null if the epoch has not ended yet.
+    pub end_timestamp: Option<DateTime>,
+    /// Stake rewards distributed to validators and their stakers at the end of the epoch, null if
+    /// the epoch has not ended yet.
+    pub total_stake_rewards: Option<BigInt>,
 }
 
-impl From<StoredEpochInfo> for Epoch {
-    fn from(e: StoredEpochInfo) -> Self {
-        Self {
+#[ComplexObject]
+impl Epoch {
+    /// The epoch's checkpoints, in sequence number order.
+    async fn checkpoint_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Connection<String, Checkpoint>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (checkpoints, has_next_page) = pg_manager
+            .fetch_checkpoints(first, after, last, before, self.epoch_id)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for checkpoint in checkpoints {
+            let cursor = pg_manager.checkpoint_cursor(&checkpoint);
+            let checkpoint = Checkpoint::try_from(checkpoint).extend()?;
+            connection.edges.push(Edge::new(cursor, checkpoint));
+        }
+        Ok(connection)
+    }
+
+    /// The next committee, protocol version and changes in stake that the epoch ended with, null
+    /// if the epoch has not ended yet.
+    async fn end_of_epoch_info(&self, ctx: &Context<'_>) -> Result<Option<EndOfEpochInfo>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let Some(checkpoint) = pg_manager
+            .fetch_end_of_epoch_checkpoint(self.epoch_id)
+            .await
+            .extend()?
+        else {
+            return Ok(None);
+        };
+
+        let epoch = pg_manager
+            .fetch_epoch_strict(self.epoch_id)
+            .await
+            .extend()?;
+        EndOfEpochInfo::try_from_stored(&epoch, &checkpoint)
+            .map(Some)
+            .extend()
+    }
+}
+
+impl TryFrom<StoredEpochInfo> for Epoch {
+    type Error = Error;
+
+    fn try_from(e: StoredEpochInfo) -> Result<Self, Self::Error> {
+        // The validators are recorded as of the start of the epoch.
+        let validators = e
+            .validators
+            .iter()
+            .flatten()
+            .map(|v| {
+                bcs::from_bytes::<SuiValidatorSummary>(v).map_err(|e| {
+                    Error::Internal(format!(
+                        "Can't convert validator into SuiValidatorSummary. Error: {e}"
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total_stake: u128 = validators
+            .iter()
+            .map(|v| v.staking_pool_sui_balance as u128)
+            .sum();
+
+        Ok(Self {
             epoch_id: e.epoch as u64,
             system_state_version: None,
             protocol_configs: None,
             reference_gas_price: Some(BigInt::from(e.reference_gas_price as u64)),
             system_parameters: None,
             stake_subsidy: None,
-            validator_set: None,
+            validator_set: Some(ValidatorSet {
+                total_stake: Some(BigInt::from(total_stake)),
+                active_validators: Some(convert_to_validators(validators)),
+                pending_removals: None,
+                pending_active_validators_size: None,
+                stake_pool_mappings_size: None,
+                inactive_pools_size: None,
+                validator_candidates_size: None,
+            }),
             storage_fund: None,
             safe_mode: None,
-            start_timestamp: None,
-        }
+            start_timestamp: DateTime::from_ms(e.epoch_start_timestamp),
+            end_timestamp: e.epoch_end_timestamp.and_then(DateTime::from_ms),
+            total_stake_rewards: e
+                .total_stake_rewards_distributed
+                .map(|r| BigInt::from(r as u64)),
+        })
     }
 }
@@ -0,0 +1,98 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_indexer::models_v2::events::StoredEvent;
+use sui_sdk::types::base_types::SuiAddress as NativeSuiAddress;
+
+use super::{
+    address::Address, base64::Base64, date_time::DateTime, digest::Digest, sui_address::SuiAddress,
+    transaction_block::TransactionBlock,
+};
+use crate::{context_data::db_data_provider::PgManager, error::Error};
+
+#[derive(InputObject, Clone, Debug, Default)]
+pub(crate) struct EventFilter {
+    /// Limit to events emitted by transactions signed by this address.
+    pub sender: Option<SuiAddress>,
+    /// Limit to events emitted by the transaction with this digest.
+    pub transaction_digest: Option<String>,
+    /// Limit to events emitted while running a Move call to a function in this package. This is
+    /// the package of the function the transaction called, which may not be the package whose
+    /// code emitted the event, if that function called into another package.
+    pub emitting_package: Option<SuiAddress>,
+    /// Limit to events emitted while running a Move call to a function in this module of
+    /// `emittingPackage`, which it requires.
+    pub emitting_module: Option<String>,
+    /// Limit to events of this type, e.g. `0x2::coin::CurrencyCreated`. A type without type
+    /// parameters also matches all of its instantiations.
+    pub event_type: Option<String>,
+}
+
+/// An event emitted by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct Event {
+    /// The package of the function that the transaction called, in the Move call that emitted the
+    /// event. This may not be the package whose code emitted the event.
+    pub sending_package: SuiAddress,
+    /// The module of the function that the transaction called, in the Move call that emitted the
+    /// event.
+    pub sending_module: String,
+    /// The type of the event's contents.
+    pub event_type: String,
+    /// Addresses that signed the transaction that emitted the event.
+    pub senders: Vec<Address>,
+    pub timestamp: Option<DateTime>,
+    /// The event's contents, serialized as BCS.
+    pub bcs: Base64,
+    #[graphql(skip)]
+    pub transaction_digest: Digest,
+}
+
+#[ComplexObject]
+impl Event {
+    /// The transaction that emitted the event.
+    async fn transaction_block(&self, ctx: &Context<'_>) -> Result<Option<TransactionBlock>> {
+        let result = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_tx(&self.transaction_digest.to_string())
+            .await?;
+        result.map(TransactionBlock::try_from).transpose().extend()
+    }
+}
+
+impl TryFrom<StoredEvent> for Event {
+    type Error = Error;
+
+    fn try_from(e: StoredEvent) -> Result<Self, Self::Error> {
+        let to_address = |bytes: &[u8]| {
+            NativeSuiAddress::from_bytes(bytes)
+                .map(|address| SuiAddress::from_array(address.to_inner()))
+                .map_err(|e| {
+                    Error::Internal(format!("Can't convert bytes into SuiAddress. Error: {e}"))
+                })
+        };
+
+        let senders = e
+            .senders
+            .iter()
+            .flatten()
+            .map(|sender| {
+                Ok(Address {
+                    address: to_address(sender)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            sending_package: to_address(&e.package)?,
+            sending_module: e.module,
+            event_type: e.event_type,
+            senders,
+            timestamp: DateTime::from_ms(e.timestamp_ms),
+            bcs: Base64::from(e.bcs),
+            transaction_digest: Digest::try_from(e.transaction_digest.as_slice())?,
+        })
+    }
+}
@@ -7,6 +7,7 @@ use async_graphql::*;
 use sui_json_rpc_types::{OwnedObjectRef, SuiGasData, SuiObjectDataOptions};
 use sui_sdk::types::{
     base_types::{ObjectID, SuiAddress as NativeSuiAddress},
+    effects::{TransactionEffects as NativeTransactionEffects, TransactionEffectsAPI},
     gas::GasCostSummary as NativeGasCostSummary,
     transaction::GasData,
 };
@@ -90,18 +91,24 @@ impl From<&NativeGasCostSummary> for GasCostSummary {
 
 #[Object]
 impl GasCostSummary {
+    /// Cost of executing the transaction, in MIST.
     async fn computation_cost(&self) -> Option<BigInt> {
         Some(BigInt::from(self.computation_cost))
     }
 
+    /// Cost of storing the objects written by the transaction, in MIST.
     async fn storage_cost(&self) -> Option<BigInt> {
         Some(BigInt::from(self.storage_cost))
     }
 
+    /// Part of the storage cost of the objects mutated or deleted by the transaction that was
+    /// refunded, in MIST.
     async fn storage_rebate(&self) -> Option<BigInt> {
         Some(BigInt::from(self.storage_rebate))
     }
 
+    /// Part of the storage cost of the objects mutated or deleted by the transaction that was kept
+    /// by the storage fund instead of being refunded, in MIST.
     async fn non_refundable_storage_fee(&self) -> Option<BigInt> {
         Some(BigInt::from(self.non_refundable_storage_fee))
     }
@@ -112,6 +119,7 @@ impl GasCostSummary {
 pub(crate) struct GasEffects {
     pub gcs: GasCostSummary,
     pub object_id: ObjectID,
+    pub object_version: u64,
 }
 
 // From trait to convert data into GasEffects
@@ -120,6 +128,18 @@ impl From<(&NativeGasCostSummary, &OwnedObjectRef)> for GasEffects {
         Self {
             gcs: gcs.into(),
             object_id: gas_obj_ref.object_id(),
+            object_version: gas_obj_ref.version().value(),
+        }
+    }
+}
+
+impl From<&NativeTransactionEffects> for GasEffects {
+    fn from(effects: &NativeTransactionEffects) -> Self {
+        let ((object_id, object_version, _), _) = effects.gas_object();
+        Self {
+            gcs: effects.gas_cost_summary().into(),
+            object_id,
+            object_version: object_version.value(),
         }
     }
 }
@@ -127,12 +147,14 @@ impl From<(&NativeGasCostSummary, &OwnedObjectRef)> for GasEffects {
 // impl #[Object] macro handles conversions and data fetches
 #[Object]
 impl GasEffects {
+    /// The gas coin that paid for the transaction, as of right after the transaction.
     async fn gas_object(&self, ctx: &Context<'_>) -> Result<Option<Object>> {
-        let gas_obj = ctx
-            .data_provider()
-            .get_object_with_options(self.object_id, SuiObjectDataOptions::full_content())
-            .await?;
-        Ok(gas_obj)
+        ctx.data_provider()
+            .fetch_obj(
+                SuiAddress::from_array(self.object_id.into_bytes()),
+                Some(self.object_version),
+            )
+            .await
     }
 
     async fn gas_summary(&self) -> Option<GasCostSummary> {
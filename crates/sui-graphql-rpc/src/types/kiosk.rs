@@ -0,0 +1,460 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use async_graphql::{
+    connection::{Connection, Edge},
+    *,
+};
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sui_indexer::models_v2::objects::StoredObject;
+use sui_sdk::types::{
+    base_types::{ObjectID, SuiAddress as NativeSuiAddress},
+    dynamic_field::{derive_dynamic_field_id, DynamicFieldInfo, DynamicFieldType, Field},
+    id::{ID, UID},
+    object::Object as NativeObject,
+    parse_sui_type_tag, SUI_FRAMEWORK_ADDRESS,
+};
+
+use super::{big_int::BigInt, object::Object, sui_address::SuiAddress};
+use crate::{
+    context_data::{context_ext::DataProviderContextExt, db_data_provider::PgManager},
+    error::Error,
+};
+
+const KIOSK_MODULE: &IdentStr = ident_str!("kiosk");
+const KIOSK_STRUCT: &IdentStr = ident_str!("Kiosk");
+const ITEM_KEY_STRUCT: &IdentStr = ident_str!("Item");
+const LISTING_KEY_STRUCT: &IdentStr = ident_str!("Listing");
+const LOCK_KEY_STRUCT: &IdentStr = ident_str!("Lock");
+
+const TRANSFER_POLICY_MODULE: &IdentStr = ident_str!("transfer_policy");
+const TRANSFER_POLICY_STRUCT: &IdentStr = ident_str!("TransferPolicy");
+const TRANSFER_POLICY_CREATED_STRUCT: &IdentStr = ident_str!("TransferPolicyCreated");
+
+/// Rust version of the Move sui::kiosk::Kiosk type.
+#[derive(Deserialize)]
+struct NativeKiosk {
+    id: UID,
+    profits: u64,
+    owner: NativeSuiAddress,
+    item_count: u32,
+    allow_extensions: bool,
+}
+
+/// Rust version of the Move sui::kiosk::Item dynamic field key.
+#[derive(Deserialize)]
+struct ItemKey {
+    id: ID,
+}
+
+/// Rust version of the Move sui::kiosk::Listing dynamic field key.
+#[derive(Serialize, Deserialize)]
+struct ListingKey {
+    id: ID,
+    is_exclusive: bool,
+}
+
+/// Rust version of the Move sui::kiosk::Lock dynamic field key.
+#[derive(Serialize, Deserialize)]
+struct LockKey {
+    id: ID,
+}
+
+/// Rust version of the Move sui::transfer_policy::TransferPolicy type. `rules` is a
+/// `VecSet<TypeName>`, which has the same BCS representation as a vector of strings.
+#[derive(Deserialize)]
+struct NativeTransferPolicy {
+    id: UID,
+    balance: u64,
+    rules: Vec<String>,
+}
+
+/// The objects that describe one of a kiosk's items: the item itself, and the dynamic fields that
+/// hold its listing and its lock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ItemDetail {
+    Object,
+    Listing,
+    Lock,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct Kiosk {
+    /// The kiosk's object ID.
+    pub address: SuiAddress,
+    /// The address that is allowed to place, list and take items from this kiosk.
+    pub owner: SuiAddress,
+    /// Number of items stored in this kiosk.
+    pub item_count: u64,
+    /// Proceeds from sales that have not been withdrawn yet.
+    pub profits: BigInt,
+    /// Whether the deprecated `allow_extensions` flag is set.
+    pub allow_extensions: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct KioskItem {
+    /// ID of the object placed in the kiosk.
+    pub object_id: SuiAddress,
+    /// Move type of the item, if it could be found.
+    pub type_: Option<String>,
+    /// Locked items can only be taken out of the kiosk by purchasing them.
+    pub is_locked: bool,
+    /// Present if the item is currently listed for sale.
+    pub listing: Option<KioskListing>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct KioskListing {
+    /// Asking price for an open listing, or the minimum price for an exclusive one.
+    pub price: BigInt,
+    /// Exclusive listings can only be purchased by the holder of the matching `PurchaseCap`.
+    pub is_exclusive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct TransferPolicy {
+    /// The transfer policy's object ID.
+    pub address: SuiAddress,
+    /// Fees collected by the policy's rules that have not been withdrawn yet.
+    pub balance: BigInt,
+    /// Types of the rules that must be satisfied to confirm a transfer.
+    pub rules: Vec<String>,
+}
+
+#[ComplexObject]
+impl Kiosk {
+    /// Items placed in this kiosk, along with their listing and lock status, in the order of the
+    /// IDs of the fields that hold them.
+    async fn item_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Connection<String, KioskItem>> {
+        let pg = ctx.data_unchecked::<PgManager>();
+        let (fields, has_next_page) = pg
+            .fetch_kiosk_items(self.address.as_slice().to_vec(), first, after, last, before)
+            .await
+            .extend()?;
+
+        let mut items = vec![];
+        for field in fields {
+            let cursor = pg.kiosk_item_cursor(&field);
+            let (Some(name), contents) = field_name_and_contents(field).extend()? else {
+                continue;
+            };
+
+            if !is_framework_struct(&name, KIOSK_MODULE, Some(ITEM_KEY_STRUCT)) {
+                continue;
+            }
+
+            let Field { name, .. } = deserialize::<Field<ItemKey, ID>>(&contents).extend()?;
+            items.push((
+                cursor,
+                KioskItem {
+                    object_id: SuiAddress::from_array(name.id.bytes.into_bytes()),
+                    type_: None,
+                    is_locked: false,
+                    listing: None,
+                },
+            ));
+        }
+
+        // The listings and locks of the items on this page are fetched by the IDs of the fields
+        // that would hold them, along with the items themselves, for their types.
+        let kiosk_id = ObjectID::new(self.address.into_array());
+        let mut details = HashMap::new();
+        for (i, (_, item)) in items.iter().enumerate() {
+            let item_id = ObjectID::new(item.object_id.into_array());
+            for (id, detail) in item_detail_ids(kiosk_id, item_id).extend()? {
+                details.insert(id, (i, detail));
+            }
+        }
+
+        let ids = details.keys().map(|id| id.to_vec()).collect();
+        for stored in pg.multi_get_objs(ids).await.extend()? {
+            let object = native_object(stored).extend()?;
+            let Some((i, detail)) = details.get(&object.id()) else {
+                continue;
+            };
+
+            let item = &mut items[*i].1;
+            match detail {
+                ItemDetail::Object => {
+                    item.type_ = object.struct_tag().map(|tag| tag.to_string());
+                }
+                ItemDetail::Listing => {
+                    if let Some(move_object) = object.data.try_as_move() {
+                        item.listing = Some(listing(move_object.contents()).extend()?);
+                    }
+                }
+                ItemDetail::Lock => item.is_locked = true,
+            }
+        }
+
+        let mut connection = Connection::new(false, has_next_page);
+        for (cursor, item) in items {
+            connection.edges.push(Edge::new(cursor, item));
+        }
+        Ok(connection)
+    }
+}
+
+#[ComplexObject]
+impl KioskItem {
+    /// The object placed in the kiosk.
+    async fn object(&self, ctx: &Context<'_>) -> Result<Option<Object>> {
+        ctx.data_provider().fetch_obj(self.object_id, None).await
+    }
+
+    /// Transfer policies that can be used to confirm the purchase of this item.
+    async fn policies(&self, ctx: &Context<'_>) -> Result<Vec<TransferPolicy>> {
+        let Some(type_) = &self.type_ else {
+            return Ok(vec![]);
+        };
+
+        TransferPolicy::query(ctx.data_unchecked::<PgManager>(), type_)
+            .await
+            .extend()
+    }
+}
+
+impl Kiosk {
+    /// Fetch the kiosk at `address`, returning `None` if there is no object at that address or
+    /// it is not a `0x2::kiosk::Kiosk`.
+    pub(crate) async fn query(pg: &PgManager, address: SuiAddress) -> Result<Option<Self>, Error> {
+        let Some(stored) = pg.fetch_obj(address.as_slice().to_vec()).await? else {
+            return Ok(None);
+        };
+
+        let object = native_object(stored)?;
+        let Some(move_object) = object.data.try_as_move() else {
+            return Ok(None);
+        };
+
+        let tag: StructTag = move_object.type_().clone().into();
+        if !is_framework_struct(&tag, KIOSK_MODULE, Some(KIOSK_STRUCT)) {
+            return Ok(None);
+        }
+
+        let NativeKiosk {
+            id,
+            profits,
+            owner,
+            item_count,
+            allow_extensions,
+        } = deserialize(move_object.contents())?;
+
+        Ok(Some(Self {
+            address: SuiAddress::from_array(id.id.bytes.into_bytes()),
+            owner: SuiAddress::from_array(owner.to_inner()),
+            item_count: item_count as u64,
+            profits: BigInt::from(profits),
+            allow_extensions,
+        }))
+    }
+}
+
+impl TransferPolicy {
+    /// Fetch the transfer policies that exist for items of type `type_`.
+    pub(crate) async fn query(pg: &PgManager, type_: &str) -> Result<Vec<Self>, Error> {
+        let type_ = parse_sui_type_tag(type_)
+            .map_err(|e| Error::InvalidType(format!("{type_}: {e}")))?;
+
+        let created_event_type = StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: TRANSFER_POLICY_MODULE.to_owned(),
+            name: TRANSFER_POLICY_CREATED_STRUCT.to_owned(),
+            type_params: vec![type_.clone()],
+        };
+
+        let expected_tag = StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: TRANSFER_POLICY_MODULE.to_owned(),
+            name: TRANSFER_POLICY_STRUCT.to_owned(),
+            type_params: vec![type_],
+        };
+
+        let mut policies = vec![];
+        for stored in pg
+            .fetch_transfer_policies(created_event_type.to_string())
+            .await?
+        {
+            let object = native_object(stored)?;
+            let Some(move_object) = object.data.try_as_move() else {
+                continue;
+            };
+
+            // Only `transfer_policy` can emit this event, so this should always hold, but check
+            // before trusting the layout of the contents.
+            if StructTag::from(move_object.type_().clone()) != expected_tag {
+                continue;
+            }
+
+            let NativeTransferPolicy { id, balance, rules } = deserialize(move_object.contents())?;
+            policies.push(TransferPolicy {
+                address: SuiAddress::from_array(id.id.bytes.into_bytes()),
+                balance: BigInt::from(balance),
+                rules,
+            });
+        }
+
+        Ok(policies)
+    }
+}
+
+fn native_object(stored: StoredObject) -> Result<NativeObject, Error> {
+    Ok(NativeObject::try_from(stored)?)
+}
+
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    bcs::from_bytes(bytes).map_err(|e| Error::Internal(format!("Failed to deserialize: {e}")))
+}
+
+/// Extract the key type and raw contents of a dynamic field object. Keys of dynamic object fields
+/// are unwrapped, so that both kinds of field can be treated uniformly. Kiosks only use struct keys,
+/// so the key type is `None` for fields with any other kind of key.
+fn field_name_and_contents(field: StoredObject) -> Result<(Option<StructTag>, Vec<u8>), Error> {
+    let kind = match field.df_kind {
+        Some(0) => DynamicFieldType::DynamicField,
+        Some(1) => DynamicFieldType::DynamicObject,
+        _ => return Err(Error::Internal("Expected a dynamic field".to_string())),
+    };
+
+    let object = native_object(field)?;
+    let Some(move_object) = object.data.try_as_move() else {
+        return Err(Error::Internal("Dynamic field is not a Move object".to_string()));
+    };
+
+    let tag: StructTag = move_object.type_().clone().into();
+    let name = match DynamicFieldInfo::try_extract_field_name(&tag, &kind) {
+        Ok(TypeTag::Struct(name)) => Some(*name),
+        Ok(_) => None,
+        Err(e) => return Err(Error::Internal(e.to_string())),
+    };
+
+    Ok((name, move_object.contents().to_vec()))
+}
+
+/// The IDs of the objects that describe the item with ID `item_id` in the kiosk with ID
+/// `kiosk_id`: the item itself, and the dynamic fields that hold its listing, if it is listed
+/// (exclusively or not), and its lock, if it is locked.
+fn item_detail_ids(
+    kiosk_id: ObjectID,
+    item_id: ObjectID,
+) -> Result<Vec<(ObjectID, ItemDetail)>, Error> {
+    let key_type = |name: &IdentStr| {
+        TypeTag::Struct(Box::new(StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: KIOSK_MODULE.to_owned(),
+            name: name.to_owned(),
+            type_params: vec![],
+        }))
+    };
+
+    let field_id = |name: &IdentStr, key: Vec<u8>| {
+        derive_dynamic_field_id(kiosk_id, &key_type(name), &key)
+            .map_err(|e| Error::Internal(format!("Failed to derive kiosk field ID: {e}")))
+    };
+
+    let mut ids = vec![(item_id, ItemDetail::Object)];
+    for is_exclusive in [false, true] {
+        let key = bcs::to_bytes(&ListingKey {
+            id: ID::new(item_id),
+            is_exclusive,
+        })
+        .map_err(|e| Error::Internal(format!("Failed to serialize listing key: {e}")))?;
+        ids.push((field_id(LISTING_KEY_STRUCT, key)?, ItemDetail::Listing));
+    }
+
+    let key = bcs::to_bytes(&LockKey {
+        id: ID::new(item_id),
+    })
+    .map_err(|e| Error::Internal(format!("Failed to serialize lock key: {e}")))?;
+    ids.push((field_id(LOCK_KEY_STRUCT, key)?, ItemDetail::Lock));
+    Ok(ids)
+}
+
+/// The listing held by a `Listing` dynamic field, with `contents`.
+fn listing(contents: &[u8]) -> Result<KioskListing, Error> {
+    let Field { name, value, .. } = deserialize::<Field<ListingKey, u64>>(contents)?;
+    Ok(KioskListing {
+        price: BigInt::from(value),
+        is_exclusive: name.is_exclusive,
+    })
+}
+
+fn is_framework_struct(tag: &StructTag, module: &IdentStr, name: Option<&IdentStr>) -> bool {
+    tag.address == SUI_FRAMEWORK_ADDRESS
+        && tag.module.as_ident_str() == module
+        && name.map_or(true, |name| tag.name.as_ident_str() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_detail_ids() {
+        let kiosk_id = ObjectID::random();
+        let item_id = ObjectID::random();
+        let field_id = |type_: &str, key: Vec<u8>| {
+            derive_dynamic_field_id(kiosk_id, &parse_sui_type_tag(type_).unwrap(), &key).unwrap()
+        };
+
+        // Listing keys are the item's ID followed by whether the listing is exclusive, and lock
+        // keys are the item's ID alone.
+        let id = item_id.to_vec();
+        assert_eq!(
+            item_detail_ids(kiosk_id, item_id).unwrap(),
+            vec![
+                (item_id, ItemDetail::Object),
+                (
+                    field_id("0x2::kiosk::Listing", [&id[..], &[0]].concat()),
+                    ItemDetail::Listing
+                ),
+                (
+                    field_id("0x2::kiosk::Listing", [&id[..], &[1]].concat()),
+                    ItemDetail::Listing
+                ),
+                (field_id("0x2::kiosk::Lock", id.clone()), ItemDetail::Lock),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_listing() {
+        let item_id = ObjectID::random();
+        for is_exclusive in [false, true] {
+            let contents = bcs::to_bytes(&Field {
+                id: UID::new(ObjectID::random()),
+                name: ListingKey {
+                    id: ID::new(item_id),
+                    is_exclusive,
+                },
+                value: 42u64,
+            })
+            .unwrap();
+
+            assert_eq!(
+                listing(&contents).unwrap(),
+                KioskListing {
+                    price: BigInt::from(42u64),
+                    is_exclusive,
+                },
+            );
+        }
+    }
+}
@@ -7,26 +7,38 @@ pub(crate) mod base64;
 pub(crate) mod big_int;
 pub(crate) mod checkpoint;
 pub(crate) mod coin;
+pub(crate) mod coin_metadata;
 pub(crate) mod committee_member;
 pub(crate) mod date_time;
 pub(crate) mod digest;
 pub(crate) mod display;
+pub(crate) mod dry_run_result;
 pub(crate) mod end_of_epoch_data;
 pub(crate) mod epoch;
+pub(crate) mod event;
 pub(crate) mod gas;
+pub(crate) mod kiosk;
+pub(crate) mod move_object;
+pub(crate) mod move_package;
 pub(crate) mod move_value;
 pub(crate) mod name_service;
+pub(crate) mod network_metrics;
 pub(crate) mod object;
 pub(crate) mod owner;
+pub(crate) mod package_activity;
 pub(crate) mod protocol_config;
 pub(crate) mod query;
 pub(crate) mod safe_mode;
+pub(crate) mod search;
 pub(crate) mod stake;
 pub(crate) mod stake_subsidy;
 pub(crate) mod storage_fund;
+pub(crate) mod subscription;
 pub(crate) mod sui_address;
 pub(crate) mod system_parameters;
 pub(crate) mod transaction_block;
+pub(crate) mod transaction_block_kind;
+pub(crate) mod transaction_signature;
 pub(crate) mod validator;
 pub(crate) mod validator_credentials;
 pub(crate) mod validator_set;
@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use move_core_types::{language_storage::StructTag, value::MoveTypeLayout};
+
+use super::{base64::Base64, move_value::MoveValue};
+use crate::context_data::db_data_provider::PgManager;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MoveObject {
+    pub type_: StructTag,
+    pub has_public_transfer: bool,
+    /// The object's contents, serialized as BCS.
+    pub contents: Vec<u8>,
+}
+
+/// The contents of an object that is not a package.
+#[Object]
+impl MoveObject {
+    /// The object's type, e.g. `0x2::coin::Coin<0x2::sui::SUI>`.
+    #[graphql(name = "type")]
+    async fn type_(&self) -> String {
+        self.type_.to_canonical_string()
+    }
+
+    /// Whether the object can be transferred by its owner with `sui::transfer::public_transfer`,
+    /// rather than only by the module that defines its type.
+    async fn has_public_transfer(&self) -> bool {
+        self.has_public_transfer
+    }
+
+    /// The object's contents, decoded according to the layout of its type.
+    async fn contents(&self, ctx: &Context<'_>) -> Result<MoveValue> {
+        let layout = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_move_struct_layout(self.type_.clone())
+            .await
+            .extend()?;
+        Ok(MoveValue::new(
+            MoveTypeLayout::Struct(layout),
+            Base64::from(self.contents.clone()),
+        ))
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use move_binary_format::CompiledModule;
+use sui_indexer::models_v2::packages::StoredPackage;
+use sui_sdk::types::{
+    base_types::SuiAddress as NativeSuiAddress, move_package::MovePackage as NativeMovePackage,
+};
+
+use super::{address::Address, checkpoint::Checkpoint, sui_address::SuiAddress};
+use crate::{context_data::db_data_provider::PgManager, error::Error};
+
+#[derive(InputObject, Debug, Default)]
+pub(crate) struct PackageFilter {
+    /// Limit to packages published or upgraded by this address.
+    pub publisher: Option<SuiAddress>,
+    /// Limit to packages published or upgraded after this checkpoint.
+    pub after_checkpoint: Option<u64>,
+}
+
+/// A Move package, as it was published or upgraded.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct MovePackage {
+    /// Address of the package object.
+    pub address: SuiAddress,
+    /// 1 for newly published packages, and incremented by every upgrade.
+    pub version: u64,
+    /// Address of the first version of the package, which the types it defines are identified by.
+    pub original_address: SuiAddress,
+    /// The address that published or upgraded the package.
+    pub publisher: Address,
+    /// The package's modules, ordered by name.
+    pub modules: Vec<MoveModuleSummary>,
+    /// The versions of the packages this package depends on that it was linked against, ordered
+    /// by the address of their first version.
+    pub linkage: Vec<Linkage>,
+    #[graphql(skip)]
+    pub checkpoint_sequence_number: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct MoveModuleSummary {
+    pub name: String,
+    /// Number of structs the module defines.
+    pub struct_count: u64,
+    /// Number of functions the module defines.
+    pub function_count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct Linkage {
+    /// Address of the first version of the dependency.
+    pub original_address: SuiAddress,
+    /// Address of the version of the dependency that the package was linked against.
+    pub upgraded_address: SuiAddress,
+    pub version: u64,
+}
+
+impl TryFrom<StoredPackage> for MovePackage {
+    type Error = Error;
+
+    fn try_from(p: StoredPackage) -> Result<Self, Self::Error> {
+        let package: NativeMovePackage = bcs::from_bytes(&p.move_package).map_err(|e| {
+            Error::Internal(format!(
+                "Can't convert move_package into MovePackage. Error: {e}"
+            ))
+        })?;
+
+        let publisher = NativeSuiAddress::from_bytes(&p.publisher).map_err(|e| {
+            Error::Internal(format!(
+                "Can't convert publisher into SuiAddress. Error: {e}"
+            ))
+        })?;
+
+        let modules = package
+            .serialized_module_map()
+            .iter()
+            .map(|(name, bytes)| {
+                let module = CompiledModule::deserialize_with_defaults(bytes).map_err(|e| {
+                    Error::Internal(format!("Can't deserialize module {name}. Error: {e}"))
+                })?;
+                Ok(MoveModuleSummary {
+                    name: name.clone(),
+                    struct_count: module.struct_defs.len() as u64,
+                    function_count: module.function_defs.len() as u64,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let linkage = package
+            .linkage_table()
+            .iter()
+            .map(|(original_id, info)| Linkage {
+                original_address: SuiAddress::from_array(original_id.into_bytes()),
+                upgraded_address: SuiAddress::from_array(info.upgraded_id.into_bytes()),
+                version: info.upgraded_version.value(),
+            })
+            .collect();
+
+        Ok(Self {
+            address: SuiAddress::from_array(package.id().into_bytes()),
+            version: package.version().value(),
+            original_address: SuiAddress::from_array(package.original_package_id().into_bytes()),
+            publisher: Address {
+                address: SuiAddress::from_array(publisher.to_inner()),
+            },
+            modules,
+            linkage,
+            checkpoint_sequence_number: p.checkpoint_sequence_number as u64,
+        })
+    }
+}
+
+#[ComplexObject]
+impl MovePackage {
+    /// The checkpoint the package was published or upgraded in.
+    async fn checkpoint(&self, ctx: &Context<'_>) -> Result<Option<Checkpoint>> {
+        let result = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_checkpoint(None, Some(self.checkpoint_sequence_number))
+            .await?;
+        result.map(Checkpoint::try_from).transpose().extend()
+    }
+}
@@ -38,8 +38,8 @@ pub(crate) struct MoveValue {
 
 scalar!(
     MoveData,
-    r#"\
-The contents of a Move Value, corresponding to the following recursive type:
+    "MoveData",
+    r#"The contents of a Move Value, corresponding to the following recursive type:
 
 type MoveData =
     { Address: SuiAddress }
@@ -49,8 +49,7 @@ type MoveData =
   | { String:  string }
   | { Vector:  [MoveData] }
   | { Option:   MoveData? }
-  | { Struct:  [{ name: string, value: MoveData }] }
-"#
+  | { Struct:  [{ name: string, value: MoveData }] }"#
 );
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -81,6 +80,10 @@ impl MoveValue {
 }
 
 impl MoveValue {
+    pub(crate) fn new(layout: MoveTypeLayout, bcs: Base64) -> Self {
+        Self { layout, bcs }
+    }
+
     fn data_impl(&self) -> Result<MoveData> {
         // TODO: If this becomes a performance bottleneck, it can be made more efficient by not
         // deserializing via `value::MoveValue` (but this is significantly more code).
@@ -0,0 +1,207 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_indexer::models_v2::checkpoints::StoredCheckpoint;
+
+use super::{big_int::BigInt, date_time::DateTime};
+use crate::{
+    context_data::db_data_provider::PgManager,
+    error::{code, graphql_error, Error},
+};
+
+/// TPS is measured over this many seconds before the latest checkpoint by default.
+const DEFAULT_TPS_WINDOW_SECS: u64 = 30;
+
+/// TPS can be measured over at most a day.
+const MAX_TPS_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// Gas prices are sampled from this many checkpoints up to the latest checkpoint by default.
+const DEFAULT_GAS_PRICE_WINDOW_CHECKPOINTS: u64 = 100;
+
+/// Gas prices can be sampled from at most this many checkpoints.
+const MAX_GAS_PRICE_WINDOW_CHECKPOINTS: u64 = 1000;
+
+/// Gas prices are sampled from at most this many of the latest transactions in the window.
+const MAX_GAS_PRICE_SAMPLE_SIZE: i64 = 10_000;
+
+/// Statistics about the network as of the latest checkpoint that has been indexed.
+#[derive(Clone, Debug, PartialEq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct NetworkMetrics {
+    /// The latest checkpoint that has been indexed, which the metrics are as of.
+    pub checkpoint_sequence_number: u64,
+    /// The number of transactions executed by the network since genesis.
+    pub total_transactions: BigInt,
+    /// Estimate of the number of live objects, which is only refreshed periodically and so may lag
+    /// behind the latest checkpoint.
+    pub live_object_count_estimate: BigInt,
+    pub epoch_progress: EpochProgress,
+
+    #[graphql(skip)]
+    pub timestamp_ms: i64,
+    #[graphql(skip)]
+    pub network_total_transactions: i64,
+    #[graphql(skip)]
+    pub reference_gas_price: u64,
+}
+
+/// How far along the current epoch is.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct EpochProgress {
+    pub epoch_id: u64,
+    pub start_timestamp: Option<DateTime>,
+    /// Time between the start of the epoch and the latest checkpoint.
+    pub elapsed_ms: BigInt,
+    /// Checkpoints in the epoch so far.
+    pub checkpoint_count: u64,
+    /// Transactions in the epoch so far.
+    pub transaction_count: BigInt,
+}
+
+/// The gas prices that user transactions paid for computation in recent checkpoints.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct GasPriceStats {
+    /// The first checkpoint that transactions were sampled from, up to the latest checkpoint.
+    pub start_checkpoint: u64,
+    /// The number of transactions sampled. Percentiles are `null` if this is 0.
+    pub transaction_count: u64,
+    pub p25: Option<BigInt>,
+    pub p50: Option<BigInt>,
+    pub p95: Option<BigInt>,
+    /// The reference gas price of the current epoch, which every transaction must pay at least.
+    pub reference_gas_price: BigInt,
+    /// A gas price to offer for a transaction to be treated like recent transactions: the median
+    /// price paid, but no less than the reference gas price.
+    pub suggested_gas_price: BigInt,
+}
+
+#[ComplexObject]
+impl NetworkMetrics {
+    /// The average number of transactions per second over the `windowSeconds` (30 by default, up
+    /// to a day) before the latest checkpoint. `null` if no checkpoint that old has been indexed.
+    async fn tps(&self, ctx: &Context<'_>, window_seconds: Option<u64>) -> Result<Option<f64>> {
+        let window_secs = window_seconds.unwrap_or(DEFAULT_TPS_WINDOW_SECS);
+        if window_secs == 0 || window_secs > MAX_TPS_WINDOW_SECS {
+            return Err(graphql_error(
+                code::BAD_USER_INPUT,
+                format!("windowSeconds must be between 1 and {MAX_TPS_WINDOW_SECS}"),
+            )
+            .into());
+        }
+
+        let since_ms = self.timestamp_ms - (window_secs * 1000) as i64;
+        let Some(start) = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_checkpoint_at_or_before(since_ms)
+            .await
+            .extend()?
+        else {
+            return Ok(None);
+        };
+
+        Ok(tps_between(
+            &start,
+            self.timestamp_ms,
+            self.network_total_transactions,
+        ))
+    }
+
+    /// Percentiles of the gas price paid by user transactions over the last `checkpoints` (100 by
+    /// default, up to 1000) checkpoints, sampling at most the latest 10000 transactions.
+    async fn gas_prices(
+        &self,
+        ctx: &Context<'_>,
+        checkpoints: Option<u64>,
+    ) -> Result<GasPriceStats> {
+        let checkpoints = checkpoints.unwrap_or(DEFAULT_GAS_PRICE_WINDOW_CHECKPOINTS);
+        if checkpoints == 0 || checkpoints > MAX_GAS_PRICE_WINDOW_CHECKPOINTS {
+            return Err(graphql_error(
+                code::BAD_USER_INPUT,
+                format!("checkpoints must be between 1 and {MAX_GAS_PRICE_WINDOW_CHECKPOINTS}"),
+            )
+            .into());
+        }
+
+        let start_checkpoint = (self.checkpoint_sequence_number + 1).saturating_sub(checkpoints);
+        let mut prices: Vec<_> = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_recent_gas_prices(start_checkpoint, MAX_GAS_PRICE_SAMPLE_SIZE)
+            .await
+            .extend()?
+            .into_iter()
+            .map(|price| price as u64)
+            .collect();
+        prices.sort_unstable();
+
+        let p50 = percentile(&prices, 50);
+        Ok(GasPriceStats {
+            start_checkpoint,
+            transaction_count: prices.len() as u64,
+            p25: percentile(&prices, 25).map(BigInt::from),
+            p50: p50.map(BigInt::from),
+            p95: percentile(&prices, 95).map(BigInt::from),
+            reference_gas_price: BigInt::from(self.reference_gas_price),
+            suggested_gas_price: BigInt::from(
+                p50.unwrap_or_default().max(self.reference_gas_price),
+            ),
+        })
+    }
+}
+
+impl NetworkMetrics {
+    pub(crate) async fn query(pg: &PgManager) -> Result<Self, Error> {
+        let latest = pg.fetch_latest_checkpoint().await?;
+        let epoch = pg.fetch_latest_epoch().await?;
+        let live_object_count_estimate = pg.fetch_live_object_count_estimate().await?;
+
+        // Transactions before the epoch are those up to and including its predecessor's last
+        // checkpoint.
+        let transactions_before_epoch = match epoch.first_checkpoint_id.checked_sub(1) {
+            Some(seq) if seq >= 0 => pg
+                .fetch_checkpoint(None, Some(seq as u64))
+                .await?
+                .map_or(0, |c| c.network_total_transactions),
+            _ => 0,
+        };
+
+        Ok(Self {
+            checkpoint_sequence_number: latest.sequence_number as u64,
+            total_transactions: BigInt::from(latest.network_total_transactions as u64),
+            live_object_count_estimate: BigInt::from(live_object_count_estimate.max(0) as u64),
+            epoch_progress: EpochProgress {
+                epoch_id: epoch.epoch as u64,
+                start_timestamp: DateTime::from_ms(epoch.epoch_start_timestamp),
+                elapsed_ms: BigInt::from(
+                    (latest.timestamp_ms - epoch.epoch_start_timestamp).max(0) as u64,
+                ),
+                checkpoint_count: (latest.sequence_number - epoch.first_checkpoint_id + 1).max(0)
+                    as u64,
+                transaction_count: BigInt::from(
+                    (latest.network_total_transactions - transactions_before_epoch).max(0) as u64,
+                ),
+            },
+            timestamp_ms: latest.timestamp_ms,
+            network_total_transactions: latest.network_total_transactions,
+            reference_gas_price: epoch.reference_gas_price as u64,
+        })
+    }
+}
+
+/// Transactions per second between checkpoint `start` and a later checkpoint at `end_ms` with
+/// `end_total` transactions since genesis.
+fn tps_between(start: &StoredCheckpoint, end_ms: i64, end_total: i64) -> Option<f64> {
+    let elapsed_ms = end_ms - start.timestamp_ms;
+    if elapsed_ms <= 0 {
+        return None;
+    }
+
+    let transactions = end_total - start.network_total_transactions;
+    Some(transactions as f64 * 1000.0 / elapsed_ms as f64)
+}
+
+/// The `p`th percentile of `sorted` by the nearest-rank method, or `None` if it is empty.
+fn percentile(sorted: &[u64], p: usize) -> Option<u64> {
+    let rank = (sorted.len() * p + 99) / 100;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
@@ -2,18 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_graphql::dataloader::{DataLoader, LruCache};
-use async_graphql::{connection::Connection, *};
+use async_graphql::{
+    connection::{Connection, Edge},
+    *,
+};
 
 use super::big_int::BigInt;
 use super::digest::Digest;
 use super::name_service::NameService;
 use super::{
-    balance::Balance, coin::Coin, owner::Owner, stake::Stake, sui_address::SuiAddress,
-    transaction_block::TransactionBlock,
+    balance::Balance, coin::Coin, move_object::MoveObject, move_package::MovePackage, owner::Owner,
+    stake::Stake, sui_address::SuiAddress, transaction_block::TransactionBlock,
 };
 use crate::context_data::context_ext::DataProviderContextExt;
+use crate::context_data::db_data_provider::PgManager;
 use crate::context_data::sui_sdk_data_provider::SuiClientLoader;
-use crate::types::base64::Base64;
+use crate::error::Error;
+use move_core_types::language_storage::StructTag;
+use sui_indexer::models_v2::objects::StoredObject;
+use sui_indexer::types_v2::OwnerType;
+use sui_sdk::types::object::{Data, Object as NativeObject, Owner as NativeOwner};
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) struct Object {
@@ -22,11 +30,18 @@ pub(crate) struct Object {
     pub digest: String,
     pub storage_rebate: Option<BigInt>,
     pub owner: Option<SuiAddress>,
-    pub bcs: Option<Base64>,
+    pub data: Option<ObjectData>,
     pub previous_transaction: Option<Digest>,
     pub kind: Option<ObjectKind>,
 }
 
+/// What an object holds: either a Move value, or the modules of a package.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) enum ObjectData {
+    Move(MoveObject),
+    Package,
+}
+
 #[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
 pub(crate) enum ObjectKind {
     Owned,
@@ -35,21 +50,30 @@ pub(crate) enum ObjectKind {
     Immutable,
 }
 
-#[derive(InputObject)]
+#[derive(InputObject, Default)]
 pub(crate) struct ObjectFilter {
-    package: Option<SuiAddress>,
-    module: Option<String>,
-    ty: Option<String>,
+    /// Limit to objects whose type is defined in this package.
+    pub package: Option<SuiAddress>,
+    /// Limit to objects whose type is defined in this module of `package`.
+    pub module: Option<String>,
+    /// Limit to objects of this struct type. A type without type parameters, e.g.
+    /// `0x2::coin::Coin`, matches all its instantiations, while one with type parameters, e.g.
+    /// `0x2::coin::Coin<0x2::sui::SUI>`, only matches that instantiation.
+    pub ty: Option<String>,
 
-    owner: Option<SuiAddress>,
-    object_ids: Option<Vec<SuiAddress>>,
-    object_keys: Option<Vec<ObjectKey>>,
+    pub owner: Option<SuiAddress>,
+    /// Limit to objects with this kind of owner, e.g. only shared objects.
+    pub owner_kind: Option<ObjectKind>,
+    pub object_ids: Option<Vec<SuiAddress>>,
+    /// Limit to objects with one of these IDs, at exactly the given version. Only the live version
+    /// of each object is indexed, so keys for any other version of a live object are rejected.
+    pub object_keys: Option<Vec<ObjectKey>>,
 }
 
 #[derive(InputObject)]
 pub(crate) struct ObjectKey {
-    object_id: SuiAddress,
-    version: u64,
+    pub object_id: SuiAddress,
+    pub version: u64,
 }
 
 #[allow(unreachable_code)]
@@ -68,8 +92,25 @@ impl Object {
         self.storage_rebate.clone()
     }
 
-    async fn bcs(&self) -> Option<Base64> {
-        self.bcs.clone()
+    /// The object's type and contents, null if the object is a package.
+    async fn as_move_object(&self) -> Option<MoveObject> {
+        match &self.data {
+            Some(ObjectData::Move(object)) => Some(object.clone()),
+            Some(ObjectData::Package) | None => None,
+        }
+    }
+
+    /// The object as a package, null if it is not one.
+    async fn as_move_package(&self, ctx: &Context<'_>) -> Result<Option<MovePackage>> {
+        let Some(ObjectData::Package) = &self.data else {
+            return Ok(None);
+        };
+        let result = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_package(self.address)
+            .await
+            .extend()?;
+        result.map(MovePackage::try_from).transpose().extend()
     }
 
     async fn previous_transaction_block(
@@ -107,9 +148,22 @@ impl Object {
         before: Option<String>,
         filter: Option<ObjectFilter>,
     ) -> Result<Connection<String, Object>> {
-        ctx.data_provider()
-            .fetch_owned_objs(&self.address, first, after, last, before, filter)
+        let mut filter = filter.unwrap_or_default();
+        filter.owner = Some(self.address);
+
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (objects, has_previous_page, has_next_page) = pg_manager
+            .fetch_objs(first, after, last, before, Some(filter))
             .await
+            .extend()?;
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        for object in objects {
+            let cursor = pg_manager.object_cursor(&object);
+            let object = Object::try_from(object).extend()?;
+            connection.edges.push(Edge::new(cursor, object));
+        }
+        Ok(connection)
     }
 
     pub async fn balance(&self, ctx: &Context<'_>, type_: Option<String>) -> Result<Balance> {
@@ -166,3 +220,51 @@ impl Object {
         unimplemented!()
     }
 }
+
+impl ObjectKind {
+    /// The owner type that objects of this kind are stored with by the indexer.
+    pub(crate) fn owner_type(&self) -> OwnerType {
+        match self {
+            ObjectKind::Owned => OwnerType::Address,
+            ObjectKind::Child => OwnerType::Object,
+            ObjectKind::Shared => OwnerType::Shared,
+            ObjectKind::Immutable => OwnerType::Immutable,
+        }
+    }
+}
+
+impl TryFrom<StoredObject> for Object {
+    type Error = Error;
+
+    fn try_from(o: StoredObject) -> Result<Self, Self::Error> {
+        let object = NativeObject::try_from(o)?;
+        let data = match &object.data {
+            Data::Move(o) => ObjectData::Move(MoveObject {
+                type_: StructTag::from(o.type_().clone()),
+                has_public_transfer: o.has_public_transfer(),
+                contents: o.contents().to_vec(),
+            }),
+            Data::Package(_) => ObjectData::Package,
+        };
+
+        let (owner, kind) = match object.owner {
+            NativeOwner::AddressOwner(a) => (Some(a), ObjectKind::Owned),
+            NativeOwner::ObjectOwner(a) => (Some(a), ObjectKind::Child),
+            NativeOwner::Shared { .. } => (None, ObjectKind::Shared),
+            NativeOwner::Immutable => (None, ObjectKind::Immutable),
+        };
+
+        Ok(Self {
+            address: SuiAddress::from_array(object.id().into_bytes()),
+            version: object.version().value(),
+            digest: object.digest().base58_encode(),
+            storage_rebate: Some(BigInt::from(object.storage_rebate)),
+            owner: owner.map(|a| SuiAddress::from_array(a.to_inner())),
+            data: Some(data),
+            previous_transaction: Some(Digest::from_array(
+                object.previous_transaction.into_inner(),
+            )),
+            kind: Some(kind),
+        })
+    }
+}
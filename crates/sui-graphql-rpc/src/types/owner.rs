@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::context_data::context_ext::DataProviderContextExt;
+use crate::context_data::db_data_provider::PgManager;
 use crate::types::balance::*;
 use crate::types::coin::*;
 use crate::types::object::*;
 use crate::types::stake::*;
 use crate::types::sui_address::SuiAddress;
-use async_graphql::connection::Connection;
+use async_graphql::connection::{Connection, Edge};
 use async_graphql::*;
 
 use super::address::Address;
@@ -109,9 +110,22 @@ impl Owner {
         before: Option<String>,
         filter: Option<ObjectFilter>,
     ) -> Result<Connection<String, Object>> {
-        ctx.data_provider()
-            .fetch_owned_objs(&self.address, first, after, last, before, filter)
+        let mut filter = filter.unwrap_or_default();
+        filter.owner = Some(self.address);
+
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (objects, has_previous_page, has_next_page) = pg_manager
+            .fetch_objs(first, after, last, before, Some(filter))
             .await
+            .extend()?;
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        for object in objects {
+            let cursor = pg_manager.object_cursor(&object);
+            let object = Object::try_from(object).extend()?;
+            connection.edges.push(Edge::new(cursor, object));
+        }
+        Ok(connection)
     }
 
     pub async fn balance(&self, ctx: &Context<'_>, type_: Option<String>) -> Result<Balance> {
@@ -0,0 +1,113 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_indexer::models_v2::package_leaderboards::{
+    LeaderboardMetric, LeaderboardPeriod, StoredPackageLeaderboardEntry,
+    StoredPackageLeaderboardWindow,
+};
+use sui_sdk::types::base_types::SuiAddress as NativeSuiAddress;
+
+use super::{
+    big_int::BigInt, date_time::DateTime, move_package::MovePackage, sui_address::SuiAddress,
+};
+use crate::{context_data::db_data_provider::PgManager, error::Error};
+
+/// A period that package activity is measured over, up to the latest checkpoint.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum ActivityPeriod {
+    /// The last 24 hours.
+    Day,
+    /// The last 7 days.
+    Week,
+}
+
+/// What packages are ranked by.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum PackageActivityMetric {
+    /// Number of Move calls to the package.
+    Calls,
+    /// Number of events emitted by the package.
+    Events,
+    /// Number of distinct senders that called the package.
+    Senders,
+    /// Gas used by the transactions that called the package.
+    GasUsed,
+}
+
+/// How much a package was used over a period.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub(crate) struct PackageActivity {
+    pub address: SuiAddress,
+    /// Number of Move calls to the package.
+    pub call_count: BigInt,
+    /// Number of events emitted by the package.
+    pub event_count: BigInt,
+    /// Number of distinct senders that called the package.
+    pub sender_count: BigInt,
+    /// Computation and storage cost of the transactions that called the package, in MIST. A
+    /// transaction that calls several packages counts towards each of them in full.
+    pub gas_used: BigInt,
+    /// The period covers activity after this time, and up to `periodEnd`.
+    pub period_start: Option<DateTime>,
+    /// Time of the latest checkpoint when the activity was last measured. Activity is measured
+    /// periodically, so it can lag behind the latest checkpoint by a few minutes.
+    pub period_end: Option<DateTime>,
+}
+
+#[ComplexObject]
+impl PackageActivity {
+    async fn package(&self, ctx: &Context<'_>) -> Result<Option<MovePackage>> {
+        let result = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_package(self.address)
+            .await
+            .extend()?;
+        result.map(MovePackage::try_from).transpose().extend()
+    }
+}
+
+impl From<ActivityPeriod> for LeaderboardPeriod {
+    fn from(period: ActivityPeriod) -> Self {
+        match period {
+            ActivityPeriod::Day => LeaderboardPeriod::Day,
+            ActivityPeriod::Week => LeaderboardPeriod::Week,
+        }
+    }
+}
+
+impl From<PackageActivityMetric> for LeaderboardMetric {
+    fn from(metric: PackageActivityMetric) -> Self {
+        match metric {
+            PackageActivityMetric::Calls => LeaderboardMetric::Calls,
+            PackageActivityMetric::Events => LeaderboardMetric::Events,
+            PackageActivityMetric::Senders => LeaderboardMetric::Senders,
+            PackageActivityMetric::GasUsed => LeaderboardMetric::GasUsed,
+        }
+    }
+}
+
+impl PackageActivity {
+    /// The activity of a package in a leaderboard, which covers the activity in `window`.
+    pub(crate) fn try_from_stored(
+        entry: StoredPackageLeaderboardEntry,
+        window: Option<StoredPackageLeaderboardWindow>,
+    ) -> Result<Self, Error> {
+        let address = NativeSuiAddress::from_bytes(entry.package).map_err(|e| {
+            Error::Internal(format!(
+                "Can't convert package ID into SuiAddress. Error: {e}"
+            ))
+        })?;
+
+        Ok(Self {
+            address: SuiAddress::from_array(address.to_inner()),
+            call_count: BigInt::from(entry.calls as u64),
+            event_count: BigInt::from(entry.events as u64),
+            sender_count: BigInt::from(entry.senders as u64),
+            gas_used: BigInt::from(entry.gas_used as u64),
+            period_start: window.and_then(|w| DateTime::from_ms(w.window_start_ms)),
+            period_end: window.and_then(|w| DateTime::from_ms(w.window_end_ms)),
+        })
+    }
+}
@@ -1,17 +1,31 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use async_graphql::{connection::Connection, *};
+use async_graphql::{
+    connection::{Connection, Edge},
+    *,
+};
+use tokio::sync::OnceCell;
 
 use super::{
     address::Address,
+    base64::Base64,
     checkpoint::{Checkpoint, CheckpointId},
+    coin_metadata::CoinMetadata,
+    dry_run_result::{DryRunResult, TransactionMetadata},
     epoch::Epoch,
-    object::Object,
+    event::{Event, EventFilter},
+    kiosk::{Kiosk, TransferPolicy},
+    move_package::{MovePackage, PackageFilter},
+    network_metrics::NetworkMetrics,
+    object::{Object, ObjectFilter},
     owner::ObjectOwner,
+    package_activity::{ActivityPeriod, PackageActivity, PackageActivityMetric},
     protocol_config::ProtocolConfigs,
+    search::{SearchResult, SearchResultKind},
+    subscription::Subscription,
     sui_address::SuiAddress,
-    transaction_block::TransactionBlock,
+    transaction_block::{TransactionBlock, TransactionBlockFilter},
 };
 use crate::{
     config::ServiceConfig,
@@ -20,7 +34,12 @@ use crate::{
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, EmptyMutation, Subscription>;
+
+/// The chain identifier never changes, so it is fetched once and then served from memory, even
+/// while the service's dependencies are unavailable.
+#[derive(Default)]
+pub(crate) struct ChainIdentifierCache(OnceCell<String>);
 
 #[allow(unreachable_code)]
 #[allow(unused_variables)]
@@ -29,7 +48,13 @@ impl Query {
     /// First four bytes of the network's genesis checkpoint digest (uniquely identifies the
     /// network).
     async fn chain_identifier(&self, ctx: &Context<'_>) -> Result<String> {
-        ctx.data_provider().fetch_chain_id().await
+        let Some(ChainIdentifierCache(cache)) = ctx.data_opt::<ChainIdentifierCache>() else {
+            return ctx.data_provider().fetch_chain_id().await;
+        };
+        cache
+            .get_or_try_init(|| ctx.data_provider().fetch_chain_id())
+            .await
+            .cloned()
     }
 
     /// Configuration for this RPC service
@@ -74,6 +99,25 @@ impl Query {
         result.map(TransactionBlock::try_from).transpose().extend()
     }
 
+    /// The transaction blocks with each of `digests`, in the same order, with null for those that
+    /// were not found. At most 50 can be fetched at once.
+    async fn transaction_blocks(
+        &self,
+        ctx: &Context<'_>,
+        digests: Vec<String>,
+    ) -> Result<Vec<Option<TransactionBlock>>> {
+        let result = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_txs_by_digests(&digests)
+            .await
+            .extend()?;
+        result
+            .into_iter()
+            .map(|tx| tx.map(TransactionBlock::try_from).transpose())
+            .collect::<Result<_, _>>()
+            .extend()
+    }
+
     async fn epoch(&self, ctx: &Context<'_>, id: Option<u64>) -> Result<Option<Epoch>> {
         let result = if let Some(epoch_id) = id {
             ctx.data_unchecked::<PgManager>()
@@ -86,7 +130,7 @@ impl Query {
                     .await?,
             )
         };
-        Ok(result.map(Epoch::from))
+        result.map(Epoch::try_from).transpose().extend()
     }
 
     async fn checkpoint(
@@ -127,6 +171,197 @@ impl Query {
             .await
     }
 
+    /// Transactions matching `filter`, in the order they were executed.
+    async fn transaction_block_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<Connection<String, TransactionBlock>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (txs, has_next_page) = pg_manager
+            .fetch_txs(first, after, last, before, filter)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for tx in txs {
+            let cursor = pg_manager.tx_cursor(&tx);
+            let tx = TransactionBlock::try_from(tx).extend()?;
+            connection.edges.push(Edge::new(cursor, tx));
+        }
+
+        Ok(connection)
+    }
+
+    /// Events matching `filter`, in the order they were emitted in.
+    async fn event_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<EventFilter>,
+    ) -> Result<Connection<String, Event>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (events, has_next_page) = pg_manager
+            .fetch_events(first, after, last, before, filter)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for event in events {
+            let cursor = pg_manager.event_cursor(&event);
+            let event = Event::try_from(event).extend()?;
+            connection.edges.push(Edge::new(cursor, event));
+        }
+
+        Ok(connection)
+    }
+
+    /// Live objects matching `filter`, in the order of their IDs.
+    async fn object_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<ObjectFilter>,
+    ) -> Result<Connection<String, Object>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (objects, has_previous_page, has_next_page) = pg_manager
+            .fetch_objs(first, after, last, before, filter)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        for object in objects {
+            let cursor = pg_manager.object_cursor(&object);
+            let object = Object::try_from(object).extend()?;
+            connection.edges.push(Edge::new(cursor, object));
+        }
+
+        Ok(connection)
+    }
+
+    /// Packages that have been published or upgraded, in the order they were published in.
+    async fn package_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<PackageFilter>,
+    ) -> Result<Connection<String, MovePackage>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (packages, has_next_page) = pg_manager
+            .fetch_packages(first, after, last, before, filter)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for package in packages {
+            let cursor = pg_manager.package_cursor(&package);
+            let package = MovePackage::try_from(package).extend()?;
+            connection.edges.push(Edge::new(cursor, package));
+        }
+
+        Ok(connection)
+    }
+
+    /// Packages, modules, structs and SuiNS names whose names contain `query` or are similar to
+    /// it, most similar first. Results can be limited to some `kinds` of names.
+    async fn search_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        kinds: Option<Vec<SearchResultKind>>,
+    ) -> Result<Connection<String, SearchResult>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (names, has_next_page) = pg_manager
+            .fetch_search_names(query, first, after, last, before, kinds)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for (offset, name) in names {
+            let cursor = pg_manager.search_name_cursor(offset);
+            let result = SearchResult::try_from(name).extend()?;
+            connection.edges.push(Edge::new(cursor, result));
+        }
+        Ok(connection)
+    }
+
+    /// Statistics about the network as of the latest checkpoint that has been indexed.
+    async fn network_metrics(&self, ctx: &Context<'_>) -> Result<NetworkMetrics> {
+        NetworkMetrics::query(ctx.data_unchecked::<PgManager>())
+            .await
+            .extend()
+    }
+
+    /// The packages that were used the most over `period`, most used first, by the number of calls
+    /// made to them unless `orderBy` says otherwise. At most 100 packages are returned, however
+    /// many are asked for with `first`.
+    async fn top_packages(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default_with = "ActivityPeriod::Day")] period: ActivityPeriod,
+        #[graphql(default_with = "PackageActivityMetric::Calls")] order_by: PackageActivityMetric,
+        first: Option<u64>,
+    ) -> Result<Vec<PackageActivity>> {
+        let leaderboard = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_package_leaderboard(period, order_by, first)
+            .await
+            .extend()?;
+        leaderboard
+            .entries
+            .into_iter()
+            .map(|entry| PackageActivity::try_from_stored(entry, leaderboard.window))
+            .collect::<Result<_, _>>()
+            .extend()
+    }
+
+    /// The kiosk at `address`, if there is one.
+    async fn kiosk(&self, ctx: &Context<'_>, address: SuiAddress) -> Result<Option<Kiosk>> {
+        Kiosk::query(ctx.data_unchecked::<PgManager>(), address)
+            .await
+            .extend()
+    }
+
+    /// The metadata of coins of type `coinType` (e.g. `0x2::sui::SUI`), along with their total
+    /// supply, if the coin has metadata.
+    async fn coin_metadata(
+        &self,
+        ctx: &Context<'_>,
+        coin_type: String,
+    ) -> Result<Option<CoinMetadata>> {
+        CoinMetadata::query(ctx.data_unchecked::<PgManager>(), &coin_type)
+            .await
+            .extend()
+    }
+
+    /// Transfer policies that have been created for items of the given type.
+    async fn transfer_policies(
+        &self,
+        ctx: &Context<'_>,
+        type_: String,
+    ) -> Result<Vec<TransferPolicy>> {
+        TransferPolicy::query(ctx.data_unchecked::<PgManager>(), &type_)
+            .await
+            .extend()
+    }
+
     async fn protocol_config(
         &self,
         ctx: &Context<'_>,
@@ -136,4 +371,35 @@ impl Query {
             .fetch_protocol_config(protocol_version)
             .await
     }
+
+    /// Simulates running a transaction, without executing it. `txBytes` are the BCS bytes of
+    /// `TransactionData`, unless `txMeta` is given, in which case they are the BCS bytes of a
+    /// `TransactionKind`, which is run with the sender and gas details from `txMeta`.
+    async fn dry_run_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        tx_meta: Option<TransactionMetadata>,
+    ) -> Result<DryRunResult> {
+        let data_provider = ctx.data_provider();
+        let tx_data = match tx_meta {
+            Some(tx_meta) => {
+                let kind = bcs::from_bytes(&tx_bytes.0).map_err(|e| {
+                    graphql_error(
+                        code::BAD_USER_INPUT,
+                        format!("Failed to deserialize TransactionKind: {e}"),
+                    )
+                })?;
+                tx_meta.into_tx_data(kind, data_provider).await?
+            }
+            None => bcs::from_bytes(&tx_bytes.0).map_err(|e| {
+                graphql_error(
+                    code::BAD_USER_INPUT,
+                    format!("Failed to deserialize TransactionData: {e}"),
+                )
+            })?,
+        };
+
+        data_provider.dry_run_tx_block(tx_data).await
+    }
 }
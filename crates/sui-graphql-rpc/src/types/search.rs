@@ -0,0 +1,116 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_indexer::models_v2::search_names::{SearchNameKind, StoredSearchName};
+use sui_sdk::types::base_types::SuiAddress as NativeSuiAddress;
+
+use super::{address::Address, sui_address::SuiAddress};
+use crate::error::Error;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum SearchResultKind {
+    Package,
+    Module,
+    Struct,
+    NameService,
+}
+
+/// A package, module, struct or SuiNS name whose name matched a search query.
+#[derive(Union, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SearchResult {
+    Package(PackageSearchResult),
+    Module(ModuleSearchResult),
+    Struct(StructSearchResult),
+    NameService(NameServiceSearchResult),
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PackageSearchResult {
+    pub address: SuiAddress,
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ModuleSearchResult {
+    /// Address of the package defining the module.
+    pub package: SuiAddress,
+    pub name: String,
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct StructSearchResult {
+    /// Address of the package defining the struct.
+    pub package: SuiAddress,
+    /// Name of the module defining the struct.
+    pub module: String,
+    pub name: String,
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct NameServiceSearchResult {
+    pub name: String,
+    /// The address the name points to, if it has been set.
+    pub address: Option<Address>,
+}
+
+impl From<SearchResultKind> for SearchNameKind {
+    fn from(kind: SearchResultKind) -> Self {
+        match kind {
+            SearchResultKind::Package => SearchNameKind::Package,
+            SearchResultKind::Module => SearchNameKind::Module,
+            SearchResultKind::Struct => SearchNameKind::Struct,
+            SearchResultKind::NameService => SearchNameKind::NameService,
+        }
+    }
+}
+
+impl TryFrom<StoredSearchName> for SearchResult {
+    type Error = Error;
+
+    fn try_from(n: StoredSearchName) -> Result<Self, Self::Error> {
+        let address = n
+            .address
+            .map(|a| {
+                NativeSuiAddress::from_bytes(a)
+                    .map(|a| SuiAddress::from_array(a.to_inner()))
+                    .map_err(|e| {
+                        Error::Internal(format!(
+                            "Can't convert search name address into SuiAddress. Error: {e}"
+                        ))
+                    })
+            })
+            .transpose()?;
+        let kind = SearchNameKind::try_from(n.kind)
+            .map_err(|k| Error::Internal(format!("Unknown search name kind: {k}")))?;
+        let package = || {
+            address.ok_or_else(|| {
+                Error::Internal(format!("Search name {} has no package", n.full_name))
+            })
+        };
+
+        Ok(match kind {
+            SearchNameKind::Package => SearchResult::Package(PackageSearchResult {
+                address: package()?,
+            }),
+            SearchNameKind::Module => SearchResult::Module(ModuleSearchResult {
+                package: package()?,
+                name: n.name,
+            }),
+            SearchNameKind::Struct => {
+                // The full name of a struct is `package::module::Struct`
+                let module = n.full_name.split("::").nth(1).ok_or_else(|| {
+                    Error::Internal(format!("Malformed struct name {}", n.full_name))
+                })?;
+                SearchResult::Struct(StructSearchResult {
+                    package: package()?,
+                    module: module.to_string(),
+                    name: n.name,
+                })
+            }
+            SearchNameKind::NameService => SearchResult::NameService(NameServiceSearchResult {
+                name: n.name,
+                address: address.map(|address| Address { address }),
+            }),
+        })
+    }
+}
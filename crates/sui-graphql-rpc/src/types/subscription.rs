@@ -0,0 +1,329 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future::Future;
+
+use async_graphql::*;
+use futures::{stream, Stream, StreamExt};
+
+use super::{
+    checkpoint::Checkpoint,
+    event::{Event, EventFilter},
+};
+use crate::{
+    config::ServiceConfig,
+    context_data::{
+        checkpoint_watcher::{CheckpointWatcher, Subscriber, Watermark},
+        db_data_provider::PgManager,
+    },
+    error::{code, graphql_error, Error},
+    extensions::consistent_read::with_read_scope,
+};
+
+/// Subscriptions are served by fetching the items after the last one they sent, up to
+/// `maxSubscriptionBuffer` items at a time, as of the latest checkpoint that the
+/// `CheckpointWatcher` has seen. The next page is only fetched once the client has received the
+/// previous one, so a client that falls behind slows down its own subscription, instead of causing
+/// items to pile up in the service. Subscriptions that have fetched everything up to the latest
+/// checkpoint wait for the watcher to see a new one, instead of polling the database themselves.
+/// At most `maxSubscriptions` subscriptions are served at once.
+pub(crate) struct Subscription;
+
+/// A page of items fetched for a subscription, and the state to fetch the next page with.
+struct Page<S, T> {
+    items: Vec<Result<T, Error>>,
+    next: S,
+    /// Whether the page reached the end of the data as of the watermark it was fetched at.
+    caught_up: bool,
+}
+
+#[Subscription]
+impl Subscription {
+    /// The latest checkpoint, followed by every checkpoint after it, in sequence number order, as
+    /// the indexer commits them. The subscription ends if checkpoints can't be fetched.
+    async fn checkpoints<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+    ) -> Result<impl Stream<Item = Result<Checkpoint>> + 'ctx> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let watcher = ctx.data_unchecked::<CheckpointWatcher>();
+        let subscriber = subscribe(watcher)?;
+        let buffer = ctx
+            .data_unchecked::<ServiceConfig>()
+            .limits
+            .max_subscription_buffer;
+
+        // The sequence number of the next checkpoint to send is unknown until the latest
+        // checkpoint has been fetched.
+        Ok(poll(
+            None,
+            subscriber,
+            move |next: Option<i64>, watermark: Watermark| async move {
+                let checkpoints = with_read_scope(
+                    watcher.scope(&watermark),
+                    pg_manager.fetch_checkpoints_from(next, buffer as i64),
+                )
+                .await?;
+                let next = checkpoints.last().map(|c| c.sequence_number + 1).or(next);
+                Ok(Page {
+                    items: checkpoints.into_iter().map(Checkpoint::try_from).collect(),
+                    next,
+                    caught_up: next.is_some_and(|next| next > watermark.checkpoint),
+                })
+            },
+        ))
+    }
+
+    /// Events matching `filter`, emitted after the latest checkpoint at the time of subscribing,
+    /// in the order they were emitted, as the indexer commits them. The subscription ends if
+    /// events can't be fetched, including if `filter` is invalid.
+    async fn events<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = Result<Event>> + 'ctx> {
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let watcher = ctx.data_unchecked::<CheckpointWatcher>();
+        let subscriber = subscribe(watcher)?;
+        let buffer = ctx
+            .data_unchecked::<ServiceConfig>()
+            .limits
+            .max_subscription_buffer;
+        let filter = filter.unwrap_or_default();
+
+        let latest = pg_manager.fetch_checkpoints_from(None, 1).await.extend()?;
+        let after = latest.first().map(|c| {
+            pg_manager
+                .event_cursor_after_checkpoint(c.sequence_number, c.network_total_transactions)
+        });
+
+        Ok(poll(
+            after,
+            subscriber,
+            move |after: Option<String>, watermark: Watermark| {
+                let filter = filter.clone();
+                async move {
+                    let (events, has_next) = with_read_scope(
+                        watcher.scope(&watermark),
+                        pg_manager.fetch_events(
+                            Some(buffer as u64),
+                            after.clone(),
+                            None,
+                            None,
+                            Some(filter),
+                        ),
+                    )
+                    .await?;
+
+                    // Once every event up to the watermark has been fetched, the next page starts
+                    // after it, so that the events that did not match the filter are not scanned
+                    // again.
+                    let next = if has_next {
+                        events.last().map(|e| pg_manager.event_cursor(e)).or(after)
+                    } else {
+                        Some(pg_manager.event_cursor_after_checkpoint(
+                            watermark.checkpoint,
+                            watermark.transactions,
+                        ))
+                    };
+                    Ok(Page {
+                        items: events.into_iter().map(Event::try_from).collect(),
+                        next,
+                        caught_up: !has_next,
+                    })
+                }
+            },
+        ))
+    }
+}
+
+/// Subscribes to `watcher`, failing if the service is already serving as many subscriptions as it
+/// can.
+fn subscribe(watcher: &CheckpointWatcher) -> Result<Subscriber> {
+    watcher.subscribe().ok_or_else(|| {
+        graphql_error(
+            code::TOO_MANY_REQUESTS,
+            "Too many subscriptions, try again later.",
+        )
+        .into()
+    })
+}
+
+/// Repeatedly calls `fetch` with the latest `state` and the latest watermark seen by `subscriber`
+/// to get the next page of items and the state after them. Once a page has caught up with its
+/// watermark, waits for the watermark to move past it before fetching again. The stream ends after
+/// the first error returned by `fetch`, or once the watermark stops being updated. The subscriber
+/// is held on to for as long as the stream is alive.
+fn poll<'a, S, T, F, Fut>(
+    state: S,
+    subscriber: Subscriber,
+    fetch: F,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    S: Send + 'a,
+    T: Send + 'a,
+    F: Fn(S, Watermark) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Page<S, T>, Error>> + Send + 'a,
+{
+    // The checkpoint that everything has been fetched up to, if any.
+    let caught_up: Option<i64> = None;
+    let init = Some((state, subscriber, fetch, caught_up));
+    stream::unfold(init, |next| async move {
+        let (mut state, mut subscriber, fetch, mut caught_up) = next?;
+        loop {
+            let watermark = loop {
+                if let Some(watermark) = *subscriber.latest.borrow_and_update() {
+                    if Some(watermark.checkpoint) > caught_up {
+                        break watermark;
+                    }
+                }
+                subscriber.latest.changed().await.ok()?;
+            };
+
+            match fetch(state, watermark).await {
+                Err(e) => return Some((vec![Err(e).extend()], None)),
+                Ok(page) => {
+                    state = page.next;
+                    if page.caught_up {
+                        caught_up = Some(watermark.checkpoint);
+                    }
+                    if !page.items.is_empty() {
+                        let items = page.items.into_iter().map(|item| item.extend()).collect();
+                        return Some((items, Some((state, subscriber, fetch, caught_up))));
+                    }
+                }
+            }
+        }
+    })
+    .flat_map(stream::iter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::{ready, Ready};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::{watch, Semaphore};
+
+    /// Where `fetch` was called from, and at which checkpoint.
+    type Calls = Arc<Mutex<Vec<(usize, i64)>>>;
+
+    fn watermark(checkpoint: i64) -> Option<Watermark> {
+        Some(Watermark {
+            checkpoint,
+            transactions: checkpoint + 1,
+        })
+    }
+
+    fn subscriber(latest: watch::Receiver<Option<Watermark>>) -> Subscriber {
+        Subscriber::try_new(latest, &Arc::new(Semaphore::new(1))).unwrap()
+    }
+
+    /// Fetches pages of up to `page_size` of the values in `items` that match, by their position,
+    /// only looking at items up to the watermark's checkpoint, like fetching events matching a
+    /// filter.
+    fn fetch(
+        items: Vec<(i64, u32, bool)>,
+        page_size: usize,
+        calls: Calls,
+    ) -> impl Fn(usize, Watermark) -> Ready<Result<Page<usize, u32>, Error>> {
+        move |from, watermark| {
+            calls.lock().unwrap().push((from, watermark.checkpoint));
+            let mut values = vec![];
+            let mut next = from;
+            for (checkpoint, value, matches) in &items[from..] {
+                if *checkpoint > watermark.checkpoint || values.len() == page_size {
+                    break;
+                }
+                next += 1;
+                if *matches {
+                    values.push(Ok(*value));
+                }
+            }
+            let caught_up = next == items.len() || items[next].0 > watermark.checkpoint;
+            ready(Ok(Page {
+                items: values,
+                next,
+                caught_up,
+            }))
+        }
+    }
+
+    async fn next<S: Stream<Item = Result<u32>> + Unpin>(stream: &mut S) -> Option<u32> {
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .ok()
+            .flatten()
+            .map(|value| value.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_poll_waits_for_new_checkpoints() {
+        let items = vec![
+            (1, 10, false),
+            (1, 11, true),
+            (2, 20, false),
+            (3, 30, true),
+            (3, 31, true),
+            (3, 32, true),
+        ];
+        let calls = Calls::default();
+        let (tx, rx) = watch::channel(watermark(1));
+        let mut stream = Box::pin(poll(0, subscriber(rx), fetch(items, 2, calls.clone())));
+
+        assert_eq!(next(&mut stream).await, Some(11));
+        assert_eq!(*calls.lock().unwrap(), vec![(0, 1)]);
+
+        // Everything up to the latest checkpoint has been fetched, so nothing is fetched again
+        // until there is a new one.
+        assert_eq!(next(&mut stream).await, None);
+        assert_eq!(*calls.lock().unwrap(), vec![(0, 1)]);
+
+        // The next checkpoint has nothing to send, but it is not scanned again.
+        tx.send(watermark(2)).unwrap();
+        assert_eq!(next(&mut stream).await, None);
+        assert_eq!(*calls.lock().unwrap(), vec![(0, 1), (2, 2)]);
+
+        // Pages that are full are followed by the next page without waiting.
+        tx.send(watermark(3)).unwrap();
+        assert_eq!(next(&mut stream).await, Some(30));
+        assert_eq!(next(&mut stream).await, Some(31));
+        assert_eq!(next(&mut stream).await, Some(32));
+        assert_eq!(*calls.lock().unwrap(), vec![(0, 1), (2, 2), (3, 3), (5, 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_poll_waits_for_first_checkpoint() {
+        let calls = Calls::default();
+        let (tx, rx) = watch::channel(None);
+        let mut stream = Box::pin(poll(
+            0,
+            subscriber(rx),
+            fetch(vec![(0, 1, true)], 2, calls.clone()),
+        ));
+
+        assert_eq!(next(&mut stream).await, None);
+        assert!(calls.lock().unwrap().is_empty());
+
+        tx.send(watermark(0)).unwrap();
+        assert_eq!(next(&mut stream).await, Some(1));
+
+        // The stream ends once there will be no new checkpoints.
+        drop(tx);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_poll_ends_after_error() {
+        let (_tx, rx) = watch::channel(watermark(0));
+        let mut stream = Box::pin(poll(0, subscriber(rx), |_, _| {
+            ready(Err::<Page<usize, u32>, _>(Error::Internal(
+                "Boom".to_string(),
+            )))
+        }));
+
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+}
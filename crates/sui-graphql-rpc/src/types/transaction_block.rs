@@ -8,48 +8,59 @@ use crate::context_data::{
 use super::{
     address::Address,
     base64::Base64,
+    big_int::BigInt,
+    date_time::DateTime,
     digest::Digest,
     epoch::Epoch,
     gas::{GasEffects, GasInput},
     sui_address::SuiAddress,
+    transaction_block_kind::TransactionBlockKind,
+    transaction_signature::TransactionSignature,
 };
-use crate::error::Error;
+use crate::{deprecation::is_visible, error::Error};
 use async_graphql::*;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
 use sui_indexer::models_v2::transactions::StoredTransaction;
 use sui_json_rpc_types::{
-    SuiExecutionStatus, SuiTransactionBlockDataAPI, SuiTransactionBlockEffects,
-    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiExecutionStatus, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse,
 };
 use sui_sdk::types::{
-    effects::TransactionEffects,
+    effects::{TransactionEffects, TransactionEffectsAPI},
+    execution_status::{
+        ExecutionFailureStatus, ExecutionStatus as NativeExecutionStatus, MoveLocation,
+    },
     transaction::{SenderSignedData, TransactionDataAPI},
 };
 
-#[derive(SimpleObject, Clone, Eq, PartialEq)]
-#[graphql(complex)]
+/// A transaction block, which is only deserialized as far as the fields that a query selects
+/// need it to be.
+#[derive(Clone, Eq, PartialEq)]
 pub(crate) struct TransactionBlock {
-    #[graphql(skip)]
     pub digest: Digest,
-    pub effects: Option<TransactionBlockEffects>,
-    pub sender: Option<Address>,
-    pub bcs: Option<Base64>,
-    pub gas_input: Option<GasInput>,
+    /// BCS-encoded `SenderSignedData`, empty if it was not fetched.
+    raw_transaction: Vec<u8>,
+    effects: EffectsSource,
+}
+
+#[derive(Clone, Eq, PartialEq)]
+enum EffectsSource {
+    /// Effects that have already been converted, e.g. from a fullnode response.
+    Converted(Option<TransactionBlockEffects>),
+    /// BCS-encoded `TransactionEffects`, as stored by the indexer.
+    Raw(Vec<u8>),
 }
 
 impl From<SuiTransactionBlockResponse> for TransactionBlock {
     fn from(tx_block: SuiTransactionBlockResponse) -> Self {
-        let transaction = tx_block.transaction.as_ref();
-        let sender = transaction.map(|tx| Address {
-            address: SuiAddress::from_array(tx.data.sender().to_inner()),
-        });
-        let gas_input = transaction.map(|tx| GasInput::from(tx.data.gas_data()));
-
         Self {
             digest: Digest::from_array(tx_block.digest.into_inner()),
-            effects: tx_block.effects.as_ref().map(TransactionBlockEffects::from),
-            sender,
-            bcs: Some(Base64::from(&tx_block.raw_transaction)),
-            gas_input,
+            effects: EffectsSource::Converted(
+                tx_block.effects.as_ref().map(TransactionBlockEffects::from),
+            ),
+            raw_transaction: tx_block.raw_transaction,
         }
     }
 }
@@ -58,66 +69,112 @@ impl TryFrom<StoredTransaction> for TransactionBlock {
     type Error = Error;
 
     fn try_from(tx: StoredTransaction) -> Result<Self, Self::Error> {
-        // TODO (wlmyng): Split the below into resolver methods
-        let digest = Digest::try_from(tx.transaction_digest.as_slice())?;
+        Ok(Self {
+            digest: Digest::try_from(tx.transaction_digest.as_slice())?,
+            raw_transaction: tx.raw_transaction,
+            effects: EffectsSource::Raw(tx.raw_effects),
+        })
+    }
+}
+
+impl TransactionBlock {
+    fn sender_signed_data(&self) -> Result<Option<SenderSignedData>, Error> {
+        if self.raw_transaction.is_empty() {
+            return Ok(None);
+        }
 
-        let sender_signed_data: SenderSignedData =
-            bcs::from_bytes(&tx.raw_transaction).map_err(|e| {
+        bcs::from_bytes(&self.raw_transaction)
+            .map(Some)
+            .map_err(|e| {
                 Error::Internal(format!(
                     "Can't convert raw_transaction into SenderSignedData. Error: {e}",
                 ))
-            })?;
-
-        let sender = Address {
-            address: SuiAddress::from_array(
-                sender_signed_data
-                    .intent_message()
-                    .value
-                    .sender()
-                    .to_inner(),
-            ),
-        };
-
-        let gas_input = GasInput::from(sender_signed_data.intent_message().value.gas_data());
-        let effects: TransactionEffects = bcs::from_bytes(&tx.raw_effects).map_err(|e| {
-            Error::Internal(format!(
-                "Can't convert raw_effects into TransactionEffects. Error: {e}",
-            ))
-        })?;
-        let effects = match SuiTransactionBlockEffects::try_from(effects) {
-            Ok(effects) => Ok(Some(TransactionBlockEffects::from(&effects))),
-            Err(e) => Err(Error::Internal(format!(
-                "Can't convert TransactionEffects into SuiTransactionBlockEffects. Error: {e}",
-            ))),
-        }?;
+            })
+    }
 
-        Ok(Self {
-            digest,
-            effects,
-            sender: Some(sender),
-            bcs: Some(Base64::from(&tx.raw_transaction)),
-            gas_input: Some(gas_input),
-        })
+    fn resolve_effects(&self) -> Result<Option<TransactionBlockEffects>, Error> {
+        match &self.effects {
+            EffectsSource::Converted(effects) => Ok(effects.clone()),
+            EffectsSource::Raw(bytes) => {
+                let effects: TransactionEffects = bcs::from_bytes(bytes).map_err(|e| {
+                    Error::Internal(format!(
+                        "Can't convert raw_effects into TransactionEffects. Error: {e}",
+                    ))
+                })?;
+                Ok(Some(TransactionBlockEffects::from(&effects)))
+            }
+        }
     }
 }
 
-#[ComplexObject]
+#[Object]
 impl TransactionBlock {
+    async fn effects(&self) -> Result<Option<TransactionBlockEffects>> {
+        self.resolve_effects().extend()
+    }
+
+    async fn sender(&self) -> Result<Option<Address>> {
+        let Some(data) = self.sender_signed_data().extend()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Address {
+            address: SuiAddress::from_array(data.intent_message().value.sender().to_inner()),
+        }))
+    }
+
+    async fn bcs(&self) -> Option<Base64> {
+        (!self.raw_transaction.is_empty()).then(|| Base64::from(&self.raw_transaction))
+    }
+
+    async fn gas_input(&self) -> Result<Option<GasInput>> {
+        let Some(data) = self.sender_signed_data().extend()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(GasInput::from(data.intent_message().value.gas_data())))
+    }
+
+    /// What the transaction does: the commands of a programmable transaction, along with their
+    /// inputs, or the details of a system transaction.
+    async fn kind(&self) -> Result<Option<TransactionBlockKind>> {
+        let Some(data) = self.sender_signed_data().extend()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(TransactionBlockKind::from(
+            data.intent_message().value.kind(),
+        )))
+    }
+
     async fn digest(&self) -> String {
         self.digest.to_string()
     }
 
     async fn expiration(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
-        if self.effects.is_none() {
+        let Some(effects) = self.resolve_effects().extend()? else {
             return Ok(None);
-        }
-        let gcs = self.effects.as_ref().unwrap().gas_effects.gcs;
+        };
+        let gcs = effects.gas_effects.gcs;
         let data_provider = ctx.data_provider();
         let system_state = data_provider.get_latest_sui_system_state().await?;
         let protocol_configs = data_provider.fetch_protocol_config(None).await?;
         let epoch = convert_to_epoch(gcs, &system_state, &protocol_configs)?;
         Ok(Some(epoch))
     }
+
+    /// The signatures over the transaction: the sender's, followed by the sponsor's if it has one.
+    async fn signatures(&self) -> Result<Option<Vec<TransactionSignature>>> {
+        let Some(sender_signed_data) = self.sender_signed_data().extend()? else {
+            return Ok(None);
+        };
+        let signatures = sender_signed_data
+            .tx_signatures()
+            .iter()
+            .map(TransactionSignature::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Some(signatures))
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, SimpleObject)]
@@ -128,7 +185,14 @@ pub(crate) struct TransactionBlockEffects {
     #[graphql(skip)]
     pub gas_effects: GasEffects,
     pub status: ExecutionStatus,
+    #[graphql(
+        deprecation = "Use `executionError`, which breaks the error down.",
+        visible = "errors_visible"
+    )]
     pub errors: Option<String>,
+    /// Why the transaction failed, broken down so that clients don't need to parse `errors`, or
+    /// `null` if it succeeded.
+    pub execution_error: Option<ExecutionError>,
     // pub transaction_block: TransactionBlock,
     // pub dependencies: Vec<TransactionBlock>,
     // pub lamport_version: Option<u64>,
@@ -148,16 +212,51 @@ impl From<&SuiTransactionBlockEffects> for TransactionBlockEffects {
             }
         };
 
+        let execution_error = errors.clone().map(ExecutionError::from_sui_error);
+
         Self {
             // TODO (wlmyng): To remove as this is the wrong digest, effects digest is not a field on SuiTransactionBlockEffects
             digest: Digest::from_array(tx_effects.transaction_digest().into_inner()),
             gas_effects: GasEffects::from((tx_effects.gas_cost_summary(), tx_effects.gas_object())),
             status,
             errors,
+            execution_error,
+        }
+    }
+}
+
+/// Reads only what `TransactionBlockEffects` exposes from the raw effects, instead of converting
+/// all of them into `SuiTransactionBlockEffects` first.
+impl From<&TransactionEffects> for TransactionBlockEffects {
+    fn from(tx_effects: &TransactionEffects) -> Self {
+        let (status, errors) = match SuiExecutionStatus::from(tx_effects.status().clone()) {
+            SuiExecutionStatus::Success => (ExecutionStatus::Success, None),
+            SuiExecutionStatus::Failure { error } => (ExecutionStatus::Failure, Some(error)),
+        };
+
+        let execution_error = match tx_effects.status() {
+            NativeExecutionStatus::Success => None,
+            NativeExecutionStatus::Failure { error, command } => Some(ExecutionError {
+                message: errors.clone().unwrap_or_default(),
+                command: command.map(|c| c as u64),
+                move_abort: MoveAbort::from_failure(error),
+            }),
+        };
+
+        Self {
+            digest: Digest::from_array(tx_effects.transaction_digest().into_inner()),
+            gas_effects: GasEffects::from(tx_effects),
+            status,
+            errors,
+            execution_error,
         }
     }
 }
 
+fn errors_visible(ctx: &Context<'_>) -> bool {
+    is_visible(ctx, "TransactionBlockEffects", "errors")
+}
+
 #[ComplexObject]
 impl TransactionBlockEffects {
     async fn digest(&self) -> String {
@@ -177,6 +276,119 @@ impl TransactionBlockEffects {
     }
 }
 
+/// Why a transaction failed to execute.
+#[derive(Clone, Debug, Eq, PartialEq, SimpleObject)]
+pub(crate) struct ExecutionError {
+    /// Description of the error, the same as `TransactionBlockEffects.errors`.
+    pub message: String,
+    /// Index of the command in the transaction that failed, if the failure can be attributed to
+    /// one.
+    pub command: Option<u64>,
+    /// Where the transaction aborted, if it failed because a Move function aborted.
+    pub move_abort: Option<MoveAbort>,
+}
+
+/// A Move function aborting execution, along with where it aborted.
+///
+/// The names of the constants that abort codes are defined as are not part of the published
+/// bytecode, so only the code itself is available.
+#[derive(Clone, Debug, Eq, PartialEq, SimpleObject)]
+pub(crate) struct MoveAbort {
+    /// The package containing the module that aborted.
+    pub package: SuiAddress,
+    /// The name of the module that aborted.
+    pub module: String,
+    /// The name of the function that aborted, if it is known.
+    pub function: Option<String>,
+    /// Index of the function that aborted among the module's function definitions.
+    pub function_index: u64,
+    /// Offset of the instruction that aborted in the function's bytecode.
+    pub instruction: u64,
+    /// The abort code.
+    pub code: BigInt,
+}
+
+impl ExecutionError {
+    /// Breaks down the error in a failed `SuiExecutionStatus`, which is the `Debug` output of the
+    /// native failure, followed by the index of the command that failed, if there is one.
+    fn from_sui_error(message: String) -> Self {
+        let (failure, command) = message
+            .rsplit_once(" in command ")
+            .and_then(|(failure, idx)| Some((failure, Some(idx.parse().ok()?))))
+            .unwrap_or((message.as_str(), None));
+
+        let move_abort = parse_move_abort(failure)
+            .as_ref()
+            .and_then(MoveAbort::from_failure);
+
+        Self {
+            message,
+            command,
+            move_abort,
+        }
+    }
+}
+
+impl MoveAbort {
+    fn from_failure(error: &ExecutionFailureStatus) -> Option<Self> {
+        let ExecutionFailureStatus::MoveAbort(location, code) = error else {
+            return None;
+        };
+
+        let MoveLocation {
+            module,
+            function,
+            instruction,
+            function_name,
+        } = location;
+
+        Some(Self {
+            package: SuiAddress::from(*module.address()),
+            module: module.name().to_string(),
+            function: function_name.clone(),
+            function_index: *function as u64,
+            instruction: *instruction as u64,
+            code: BigInt::from(*code),
+        })
+    }
+}
+
+/// Parses the `Debug` output of an `ExecutionFailureStatus::MoveAbort`, e.g.
+/// `MoveAbort(MoveLocation { module: ModuleId { address: 00..02, name: Identifier("coin") },
+/// function: 3, instruction: 10, function_name: Some("split") }, 5)`, or returns `None` if
+/// `failure` is any other failure.
+fn parse_move_abort(failure: &str) -> Option<ExecutionFailureStatus> {
+    let rest = failure.strip_prefix("MoveAbort(MoveLocation { module: ModuleId { address: ")?;
+    let (address, rest) = rest.split_once(", name: Identifier(\"")?;
+    let (module, rest) = rest.split_once("\") }, function: ")?;
+    let (function, rest) = rest.split_once(", instruction: ")?;
+    let (instruction, rest) = rest.split_once(", function_name: ")?;
+    let (function_name, code) = rest.split_once(" }, ")?;
+
+    let function_name = match function_name {
+        "None" => None,
+        name => {
+            let name = name.strip_prefix("Some(\"")?.strip_suffix("\")")?;
+            Some(name.to_string())
+        }
+    };
+
+    let module = ModuleId::new(
+        AccountAddress::from_hex(address).ok()?,
+        Identifier::new(module).ok()?,
+    );
+
+    let location = MoveLocation {
+        module,
+        function: function.parse().ok()?,
+        instruction: instruction.parse().ok()?,
+        function_name,
+    };
+
+    let code = code.strip_suffix(')')?.parse().ok()?;
+    Some(ExecutionFailureStatus::MoveAbort(location, code))
+}
+
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 pub(crate) enum TransactionBlockKindInput {
     ProgrammableTx,
@@ -189,20 +401,121 @@ pub enum ExecutionStatus {
     Failure,
 }
 
-#[derive(InputObject)]
+#[derive(InputObject, Default)]
 pub(crate) struct TransactionBlockFilter {
-    package: Option<SuiAddress>,
-    module: Option<String>,
-    function: Option<String>,
+    /// Limit to transactions that call into this package.
+    pub package: Option<SuiAddress>,
+    /// Limit to transactions that call into this module of `package`.
+    pub module: Option<String>,
+    /// Limit to transactions that call this function of `module`.
+    pub function: Option<String>,
 
-    kind: Option<TransactionBlockKindInput>,
-    checkpoint: Option<u64>,
+    pub kind: Option<TransactionBlockKindInput>,
+    pub checkpoint: Option<u64>,
+    /// Limit to transactions in checkpoints with a sequence number strictly greater than this.
+    pub after_checkpoint: Option<u64>,
+    /// Limit to transactions in checkpoints with a sequence number strictly less than this.
+    pub before_checkpoint: Option<u64>,
+    /// Limit to transactions in checkpoints created strictly after this time.
+    pub after_checkpoint_timestamp: Option<DateTime>,
+    /// Limit to transactions in checkpoints created strictly before this time.
+    pub before_checkpoint_timestamp: Option<DateTime>,
 
-    sign_address: Option<SuiAddress>,
-    sent_address: Option<SuiAddress>,
-    recv_address: Option<SuiAddress>,
-    paid_address: Option<SuiAddress>,
+    pub sign_address: Option<SuiAddress>,
+    pub sent_address: Option<SuiAddress>,
+    pub recv_address: Option<SuiAddress>,
+    pub paid_address: Option<SuiAddress>,
+    /// Limit to transactions that were sent by, sent objects to, or were paid for by this address.
+    pub affected_address: Option<SuiAddress>,
 
-    input_object: Option<SuiAddress>,
-    changed_object: Option<SuiAddress>,
+    /// Limit to transactions that take this object as an input.
+    pub input_object: Option<SuiAddress>,
+    /// Limit to transactions that created or modified this object.
+    pub changed_object: Option<SuiAddress>,
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::ident_str;
+    use sui_sdk::types::execution_status::CommandArgumentError;
+
+    use super::*;
+
+    fn move_abort(function_name: Option<&str>) -> ExecutionFailureStatus {
+        ExecutionFailureStatus::MoveAbort(
+            MoveLocation {
+                module: ModuleId::new(AccountAddress::TWO, ident_str!("coin").to_owned()),
+                function: 3,
+                instruction: 10,
+                function_name: function_name.map(str::to_string),
+            },
+            42,
+        )
+    }
+
+    /// The error as reported by `SuiExecutionStatus`, broken back down.
+    fn from_sui_status(error: ExecutionFailureStatus, command: Option<usize>) -> ExecutionError {
+        let SuiExecutionStatus::Failure { error } =
+            SuiExecutionStatus::from(NativeExecutionStatus::Failure { error, command })
+        else {
+            panic!("Expected a failure");
+        };
+
+        ExecutionError::from_sui_error(error)
+    }
+
+    #[test]
+    fn test_move_abort_from_failure() {
+        let abort = MoveAbort::from_failure(&move_abort(Some("split"))).unwrap();
+        assert_eq!(
+            abort,
+            MoveAbort {
+                package: SuiAddress::from(AccountAddress::TWO),
+                module: "coin".to_string(),
+                function: Some("split".to_string()),
+                function_index: 3,
+                instruction: 10,
+                code: BigInt::from(42u64),
+            },
+        );
+
+        let abort = MoveAbort::from_failure(&move_abort(None)).unwrap();
+        assert_eq!(abort.function, None);
+    }
+
+    #[test]
+    fn test_move_abort_from_other_failures() {
+        assert_eq!(
+            MoveAbort::from_failure(&ExecutionFailureStatus::InsufficientGas),
+            None,
+        );
+        assert_eq!(
+            MoveAbort::from_failure(&ExecutionFailureStatus::CommandArgumentError {
+                arg_idx: 0,
+                kind: CommandArgumentError::TypeMismatch,
+            }),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_execution_error_from_sui_status() {
+        for function_name in [Some("split"), None] {
+            for command in [Some(1), None] {
+                let failure = move_abort(function_name);
+                let error = from_sui_status(failure.clone(), command);
+                assert_eq!(error.command, command.map(|c| c as u64));
+                assert_eq!(error.move_abort, MoveAbort::from_failure(&failure));
+            }
+        }
+
+        let error = from_sui_status(ExecutionFailureStatus::InsufficientGas, Some(2));
+        assert_eq!(error.message, "InsufficientGas in command 2");
+        assert_eq!(error.command, Some(2));
+        assert_eq!(error.move_abort, None);
+
+        let error = from_sui_status(ExecutionFailureStatus::InsufficientGas, None);
+        assert_eq!(error.message, "InsufficientGas");
+        assert_eq!(error.command, None);
+    }
 }
@@ -0,0 +1,713 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_sdk::types::{
+    base_types::ObjectID,
+    messages_consensus::ConsensusCommitPrologue,
+    transaction::{
+        Argument, AuthenticatorStateUpdate, CallArg, ChangeEpoch, Command,
+        EndOfEpochTransactionKind, GenesisTransaction as NativeGenesisTransaction, ObjectArg,
+        ProgrammableMoveCall, ProgrammableTransaction as NativeProgrammableTransaction,
+        RandomnessStateUpdate, TransactionKind,
+    },
+};
+
+use super::{
+    base64::Base64, big_int::BigInt, date_time::DateTime, epoch::Epoch, move_value::MoveValue,
+    sui_address::SuiAddress,
+};
+use crate::context_data::db_data_provider::PgManager;
+
+/// What a transaction does, decoded from its BCS representation.
+#[derive(Union, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TransactionBlockKind {
+    ConsensusCommitPrologue(ConsensusCommitPrologueTransaction),
+    Genesis(GenesisTransaction),
+    ChangeEpoch(ChangeEpochTransaction),
+    Programmable(ProgrammableTransactionBlock),
+    AuthenticatorState(AuthenticatorStateUpdateTransaction),
+    RandomnessState(RandomnessStateUpdateTransaction),
+    EndOfEpoch(EndOfEpochTransaction),
+}
+
+/// System transaction that starts each commit of transactions from consensus, and updates the
+/// on-chain clock.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub(crate) struct ConsensusCommitPrologueTransaction {
+    #[graphql(skip)]
+    pub epoch_id: u64,
+    /// Consensus round of the commit.
+    pub round: u64,
+    /// Time of the commit, according to consensus.
+    pub timestamp: Option<DateTime>,
+}
+
+/// System transaction that creates the objects the chain starts with.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct GenesisTransaction {
+    /// IDs of the objects created at genesis.
+    pub objects: Vec<SuiAddress>,
+}
+
+/// System transaction that ends an epoch and starts the next one.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub(crate) struct ChangeEpochTransaction {
+    /// ID of the epoch being started.
+    #[graphql(skip)]
+    pub epoch_id: u64,
+    /// Protocol version the next epoch runs.
+    pub protocol_version: u64,
+    /// Time the next epoch starts at.
+    pub timestamp: Option<DateTime>,
+    pub storage_charge: BigInt,
+    pub computation_charge: BigInt,
+    pub storage_rebate: BigInt,
+    pub non_refundable_storage_fee: BigInt,
+}
+
+/// System transaction that updates the JWKs that zkLogin signatures are checked against.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub(crate) struct AuthenticatorStateUpdateTransaction {
+    #[graphql(skip)]
+    pub epoch_id: u64,
+    /// Consensus round of the update.
+    pub round: u64,
+    /// Number of JWKs that became active.
+    pub new_active_jwk_count: u64,
+}
+
+/// System transaction that updates the source of on-chain randomness.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub(crate) struct RandomnessStateUpdateTransaction {
+    #[graphql(skip)]
+    pub epoch_id: u64,
+    pub randomness_round: u64,
+    pub random_bytes: Base64,
+}
+
+/// System transaction run at the end of an epoch, made up of several smaller transactions.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EndOfEpochTransaction {
+    /// Names of the transactions that make up this one, in the order they run, e.g.
+    /// `ChangeEpoch`.
+    pub kinds: Vec<String>,
+    /// The transaction that starts the next epoch, if this transaction includes one.
+    pub change_epoch: Option<ChangeEpochTransaction>,
+}
+
+/// A transaction made up of commands that run in sequence, and can use the results of the commands
+/// before them.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ProgrammableTransactionBlock {
+    /// Inputs to the commands, referred to by their index by `Input` arguments.
+    pub inputs: Vec<TransactionInput>,
+    /// The commands, in the order they run.
+    pub transactions: Vec<ProgrammableTransaction>,
+}
+
+#[derive(Union, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TransactionInput {
+    OwnedOrImmutable(OwnedOrImmutable),
+    Shared(SharedInput),
+    Receiving(Receiving),
+    Pure(Pure),
+}
+
+/// An owned or immutable object, at the version the transaction uses it at.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct OwnedOrImmutable {
+    pub address: SuiAddress,
+    pub version: u64,
+    pub digest: String,
+}
+
+/// A shared object. The version it is used at is only decided once the transaction is sequenced.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SharedInput {
+    pub address: SuiAddress,
+    /// The version the object was shared at.
+    pub initial_shared_version: u64,
+    /// Whether the transaction can modify the object.
+    pub mutable: bool,
+}
+
+/// An object sent to another object, that the transaction receives.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Receiving {
+    pub address: SuiAddress,
+    pub version: u64,
+    pub digest: String,
+}
+
+/// A value that is not an object. Transactions don't say what type their pure values are, so they
+/// are only available as BCS bytes here. Values passed to Move calls are decoded by the types of
+/// the parameters they are passed to in `MoveCallTransaction.argumentValues`.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Pure {
+    pub bytes: Base64,
+}
+
+/// A value passed to a command.
+#[derive(Union, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TransactionArgument {
+    GasCoin(GasCoin),
+    Input(Input),
+    Result(TxResult),
+}
+
+/// The coin the transaction pays for gas with.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct GasCoin {
+    /// Placeholder, as GraphQL types need at least one field. Always null.
+    #[graphql(name = "_")]
+    pub dummy: Option<bool>,
+}
+
+/// One of the transaction's inputs.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Input {
+    /// Index of the input in `ProgrammableTransactionBlock.inputs`.
+    pub ix: u64,
+}
+
+/// The result of an earlier command.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(name = "Result")]
+pub(crate) struct TxResult {
+    /// Index of the command in `ProgrammableTransactionBlock.transactions`.
+    pub cmd: u64,
+    /// Index of the value among the command's results, if the command returned more than one.
+    pub ix: Option<u64>,
+}
+
+#[derive(Union, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ProgrammableTransaction {
+    MoveCall(MoveCallTransaction),
+    TransferObjects(TransferObjectsTransaction),
+    SplitCoins(SplitCoinsTransaction),
+    MergeCoins(MergeCoinsTransaction),
+    Publish(PublishTransaction),
+    Upgrade(UpgradeTransaction),
+    MakeMoveVec(MakeMoveVecTransaction),
+}
+
+/// A call to a Move function.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+#[graphql(complex)]
+pub(crate) struct MoveCallTransaction {
+    pub package: SuiAddress,
+    pub module: String,
+    pub function_name: String,
+    /// The function's type arguments, in canonical form.
+    pub type_arguments: Vec<String>,
+    pub arguments: Vec<TransactionArgument>,
+    #[graphql(skip)]
+    pub call: ProgrammableMoveCall,
+    /// BCS bytes of each of `arguments` that is a pure input.
+    #[graphql(skip)]
+    pub pure_arguments: Vec<Option<Base64>>,
+}
+
+/// Sends objects to an address.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TransferObjectsTransaction {
+    pub objects: Vec<TransactionArgument>,
+    pub address: TransactionArgument,
+}
+
+/// Splits new coins off a coin, with the given amounts.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SplitCoinsTransaction {
+    pub coin: TransactionArgument,
+    pub amounts: Vec<TransactionArgument>,
+}
+
+/// Merges coins into a coin.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MergeCoinsTransaction {
+    pub coin: TransactionArgument,
+    pub coins: Vec<TransactionArgument>,
+}
+
+/// Publishes a package.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PublishTransaction {
+    /// The package's modules, as bytecode.
+    pub modules: Vec<Base64>,
+    /// IDs of the packages the package depends on, directly or indirectly.
+    pub dependencies: Vec<SuiAddress>,
+}
+
+/// Upgrades a package.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct UpgradeTransaction {
+    /// The upgraded package's modules, as bytecode.
+    pub modules: Vec<Base64>,
+    /// IDs of the packages the upgraded package depends on, directly or indirectly.
+    pub dependencies: Vec<SuiAddress>,
+    /// ID of the package being upgraded.
+    pub current_package: SuiAddress,
+    pub upgrade_ticket: TransactionArgument,
+}
+
+/// Makes a vector out of values.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MakeMoveVecTransaction {
+    /// Type of the vector's elements, in canonical form. Only given when it can't be inferred from
+    /// the elements, because they are not objects, or there are none.
+    #[graphql(name = "type")]
+    pub type_: Option<String>,
+    pub elements: Vec<TransactionArgument>,
+}
+
+#[ComplexObject]
+impl ConsensusCommitPrologueTransaction {
+    async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
+        fetch_epoch(ctx, self.epoch_id).await
+    }
+}
+
+#[ComplexObject]
+impl ChangeEpochTransaction {
+    /// The epoch being started.
+    async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
+        fetch_epoch(ctx, self.epoch_id).await
+    }
+}
+
+#[ComplexObject]
+impl AuthenticatorStateUpdateTransaction {
+    async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
+        fetch_epoch(ctx, self.epoch_id).await
+    }
+}
+
+#[ComplexObject]
+impl RandomnessStateUpdateTransaction {
+    async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
+        fetch_epoch(ctx, self.epoch_id).await
+    }
+}
+
+#[ComplexObject]
+impl MoveCallTransaction {
+    /// The value of each of `arguments` that is a pure input, decoded by the type of the parameter
+    /// it is passed to. Null for arguments that are objects or the results of other commands, and
+    /// for every argument if the function can't be found.
+    async fn argument_values(&self, ctx: &Context<'_>) -> Result<Vec<Option<MoveValue>>> {
+        if self.pure_arguments.iter().all(Option::is_none) {
+            return Ok(self.pure_arguments.iter().map(|_| None).collect());
+        }
+
+        let layouts = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_move_function_parameter_layouts(
+                self.call.package,
+                self.call.module.clone(),
+                self.call.function.clone(),
+                self.call.type_arguments.clone(),
+            )
+            .await
+            .extend()?
+            .unwrap_or_default();
+
+        Ok(self
+            .pure_arguments
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                let layout = layouts.get(i)?.clone()?;
+                Some(MoveValue::new(layout, bytes.clone()?))
+            })
+            .collect())
+    }
+}
+
+async fn fetch_epoch(ctx: &Context<'_>, epoch_id: u64) -> Result<Option<Epoch>> {
+    let result = ctx
+        .data_unchecked::<PgManager>()
+        .fetch_epoch_strict(epoch_id)
+        .await?;
+    Epoch::try_from(result).map(Some).extend()
+}
+
+impl From<&TransactionKind> for TransactionBlockKind {
+    fn from(kind: &TransactionKind) -> Self {
+        use TransactionBlockKind as K;
+        match kind {
+            TransactionKind::ProgrammableTransaction(pt) => K::Programmable(pt.into()),
+            TransactionKind::ChangeEpoch(ce) => K::ChangeEpoch(ce.into()),
+            TransactionKind::Genesis(g) => K::Genesis(g.into()),
+            TransactionKind::ConsensusCommitPrologue(ccp) => K::ConsensusCommitPrologue(ccp.into()),
+            TransactionKind::AuthenticatorStateUpdate(asu) => K::AuthenticatorState(asu.into()),
+            TransactionKind::RandomnessStateUpdate(rsu) => K::RandomnessState(rsu.into()),
+            TransactionKind::EndOfEpochTransaction(txs) => K::EndOfEpoch(EndOfEpochTransaction {
+                kinds: txs
+                    .iter()
+                    .map(|tx| <&'static str>::from(tx).to_string())
+                    .collect(),
+                change_epoch: txs.iter().find_map(|tx| match tx {
+                    EndOfEpochTransactionKind::ChangeEpoch(ce) => Some(ce.into()),
+                    _ => None,
+                }),
+            }),
+        }
+    }
+}
+
+impl From<&ConsensusCommitPrologue> for ConsensusCommitPrologueTransaction {
+    fn from(ccp: &ConsensusCommitPrologue) -> Self {
+        Self {
+            epoch_id: ccp.epoch,
+            round: ccp.round,
+            timestamp: DateTime::from_ms(ccp.commit_timestamp_ms as i64),
+        }
+    }
+}
+
+impl From<&NativeGenesisTransaction> for GenesisTransaction {
+    fn from(g: &NativeGenesisTransaction) -> Self {
+        Self {
+            objects: g.objects.iter().map(|o| address(o.id())).collect(),
+        }
+    }
+}
+
+impl From<&ChangeEpoch> for ChangeEpochTransaction {
+    fn from(ce: &ChangeEpoch) -> Self {
+        Self {
+            epoch_id: ce.epoch,
+            protocol_version: ce.protocol_version.as_u64(),
+            timestamp: DateTime::from_ms(ce.epoch_start_timestamp_ms as i64),
+            storage_charge: BigInt::from(ce.storage_charge),
+            computation_charge: BigInt::from(ce.computation_charge),
+            storage_rebate: BigInt::from(ce.storage_rebate),
+            non_refundable_storage_fee: BigInt::from(ce.non_refundable_storage_fee),
+        }
+    }
+}
+
+impl From<&AuthenticatorStateUpdate> for AuthenticatorStateUpdateTransaction {
+    fn from(asu: &AuthenticatorStateUpdate) -> Self {
+        Self {
+            epoch_id: asu.epoch,
+            round: asu.round,
+            new_active_jwk_count: asu.new_active_jwks.len() as u64,
+        }
+    }
+}
+
+impl From<&RandomnessStateUpdate> for RandomnessStateUpdateTransaction {
+    fn from(rsu: &RandomnessStateUpdate) -> Self {
+        Self {
+            epoch_id: rsu.epoch,
+            randomness_round: rsu.randomness_round,
+            random_bytes: Base64::from(&rsu.random_bytes),
+        }
+    }
+}
+
+impl From<&NativeProgrammableTransaction> for ProgrammableTransactionBlock {
+    fn from(pt: &NativeProgrammableTransaction) -> Self {
+        Self {
+            inputs: pt.inputs.iter().map(TransactionInput::from).collect(),
+            transactions: pt
+                .commands
+                .iter()
+                .map(|command| ProgrammableTransaction::new(command, &pt.inputs))
+                .collect(),
+        }
+    }
+}
+
+impl From<&CallArg> for TransactionInput {
+    fn from(arg: &CallArg) -> Self {
+        use TransactionInput as I;
+        match arg {
+            CallArg::Pure(bytes) => I::Pure(Pure {
+                bytes: Base64::from(bytes),
+            }),
+            CallArg::Object(ObjectArg::ImmOrOwnedObject((id, version, digest))) => {
+                I::OwnedOrImmutable(OwnedOrImmutable {
+                    address: address(*id),
+                    version: version.value(),
+                    digest: digest.to_string(),
+                })
+            }
+            CallArg::Object(ObjectArg::SharedObject {
+                id,
+                initial_shared_version,
+                mutable,
+            }) => I::Shared(SharedInput {
+                address: address(*id),
+                initial_shared_version: initial_shared_version.value(),
+                mutable: *mutable,
+            }),
+            CallArg::Object(ObjectArg::Receiving((id, version, digest))) => {
+                I::Receiving(Receiving {
+                    address: address(*id),
+                    version: version.value(),
+                    digest: digest.to_string(),
+                })
+            }
+        }
+    }
+}
+
+impl From<&Argument> for TransactionArgument {
+    fn from(arg: &Argument) -> Self {
+        use TransactionArgument as A;
+        match arg {
+            Argument::GasCoin => A::GasCoin(GasCoin { dummy: None }),
+            Argument::Input(ix) => A::Input(Input { ix: *ix as u64 }),
+            Argument::Result(cmd) => A::Result(TxResult {
+                cmd: *cmd as u64,
+                ix: None,
+            }),
+            Argument::NestedResult(cmd, ix) => A::Result(TxResult {
+                cmd: *cmd as u64,
+                ix: Some(*ix as u64),
+            }),
+        }
+    }
+}
+
+impl ProgrammableTransaction {
+    /// The command `command`, of a transaction with inputs `inputs`.
+    fn new(command: &Command, inputs: &[CallArg]) -> Self {
+        use ProgrammableTransaction as P;
+        match command {
+            Command::MoveCall(call) => P::MoveCall(MoveCallTransaction::new(call, inputs)),
+
+            Command::TransferObjects(objects, address) => {
+                P::TransferObjects(TransferObjectsTransaction {
+                    objects: arguments(objects),
+                    address: address.into(),
+                })
+            }
+
+            Command::SplitCoins(coin, amounts) => P::SplitCoins(SplitCoinsTransaction {
+                coin: coin.into(),
+                amounts: arguments(amounts),
+            }),
+
+            Command::MergeCoins(coin, coins) => P::MergeCoins(MergeCoinsTransaction {
+                coin: coin.into(),
+                coins: arguments(coins),
+            }),
+
+            Command::Publish(modules, dependencies) => P::Publish(PublishTransaction {
+                modules: modules.iter().map(Base64::from).collect(),
+                dependencies: dependencies.iter().map(|id| address(*id)).collect(),
+            }),
+
+            Command::Upgrade(modules, dependencies, current_package, upgrade_ticket) => {
+                P::Upgrade(UpgradeTransaction {
+                    modules: modules.iter().map(Base64::from).collect(),
+                    dependencies: dependencies.iter().map(|id| address(*id)).collect(),
+                    current_package: address(*current_package),
+                    upgrade_ticket: upgrade_ticket.into(),
+                })
+            }
+
+            Command::MakeMoveVec(type_, elements) => P::MakeMoveVec(MakeMoveVecTransaction {
+                type_: type_.as_ref().map(|t| t.to_canonical_string()),
+                elements: arguments(elements),
+            }),
+        }
+    }
+}
+
+impl MoveCallTransaction {
+    /// The call `call`, of a transaction with inputs `inputs`.
+    fn new(call: &ProgrammableMoveCall, inputs: &[CallArg]) -> Self {
+        let pure_arguments = call
+            .arguments
+            .iter()
+            .map(|arg| match arg {
+                Argument::Input(ix) => match inputs.get(*ix as usize) {
+                    Some(CallArg::Pure(bytes)) => Some(Base64::from(bytes)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            package: address(call.package),
+            module: call.module.to_string(),
+            function_name: call.function.to_string(),
+            type_arguments: call
+                .type_arguments
+                .iter()
+                .map(|t| t.to_canonical_string())
+                .collect(),
+            arguments: arguments(&call.arguments),
+            call: call.clone(),
+            pure_arguments,
+        }
+    }
+}
+
+fn address(id: ObjectID) -> SuiAddress {
+    SuiAddress::from_array(id.into_bytes())
+}
+
+fn arguments(args: &[Argument]) -> Vec<TransactionArgument> {
+    args.iter().map(TransactionArgument::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::{identifier::Identifier, language_storage::TypeTag};
+    use sui_sdk::types::{
+        base_types::SuiAddress as NativeSuiAddress, committee::ProtocolVersion,
+        programmable_transaction_builder::ProgrammableTransactionBuilder,
+    };
+
+    use super::*;
+
+    fn pure<T: serde::Serialize>(value: T) -> TransactionInput {
+        TransactionInput::Pure(Pure {
+            bytes: Base64::from(bcs::to_bytes(&value).unwrap()),
+        })
+    }
+
+    fn input(ix: u64) -> TransactionArgument {
+        TransactionArgument::Input(Input { ix })
+    }
+
+    fn change_epoch() -> ChangeEpoch {
+        ChangeEpoch {
+            epoch: 4,
+            protocol_version: ProtocolVersion::new(10),
+            storage_charge: 1,
+            computation_charge: 2,
+            storage_rebate: 3,
+            non_refundable_storage_fee: 4,
+            epoch_start_timestamp_ms: 5000,
+            system_packages: vec![],
+        }
+    }
+
+    #[test]
+    fn test_programmable_transaction() {
+        let recipient = NativeSuiAddress::random_for_testing_only();
+        let package = ObjectID::random();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let amount = builder.pure(42u64).unwrap();
+        let coin = builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+        builder.programmable_move_call(
+            package,
+            Identifier::new("m").unwrap(),
+            Identifier::new("f").unwrap(),
+            vec![TypeTag::U64],
+            vec![amount, Argument::NestedResult(0, 0), Argument::GasCoin],
+        );
+        builder.transfer_arg(recipient, coin);
+        let kind = TransactionKind::ProgrammableTransaction(builder.finish());
+
+        let TransactionBlockKind::Programmable(ptb) = TransactionBlockKind::from(&kind) else {
+            panic!("Expected a programmable transaction");
+        };
+        assert_eq!(ptb.inputs, vec![pure(42u64), pure(recipient)]);
+
+        let [split, call, transfer] = &ptb.transactions[..] else {
+            panic!("Expected three commands, got {:?}", ptb.transactions);
+        };
+        assert_eq!(
+            split,
+            &ProgrammableTransaction::SplitCoins(SplitCoinsTransaction {
+                coin: TransactionArgument::GasCoin(GasCoin { dummy: None }),
+                amounts: vec![input(0)],
+            }),
+        );
+        assert_eq!(
+            transfer,
+            &ProgrammableTransaction::TransferObjects(TransferObjectsTransaction {
+                objects: vec![TransactionArgument::Result(TxResult { cmd: 0, ix: None })],
+                address: input(1),
+            }),
+        );
+
+        let ProgrammableTransaction::MoveCall(call) = call else {
+            panic!("Expected a Move call, got {call:?}");
+        };
+        assert_eq!(call.package, address(package));
+        assert_eq!(call.module, "m");
+        assert_eq!(call.function_name, "f");
+        assert_eq!(call.type_arguments, vec!["u64".to_string()]);
+        assert_eq!(
+            call.arguments,
+            vec![
+                input(0),
+                TransactionArgument::Result(TxResult {
+                    cmd: 0,
+                    ix: Some(0),
+                }),
+                TransactionArgument::GasCoin(GasCoin { dummy: None }),
+            ],
+        );
+        // Only pure inputs are decoded by the types of the parameters they are passed to.
+        assert_eq!(
+            call.pure_arguments,
+            vec![
+                Some(Base64::from(bcs::to_bytes(&42u64).unwrap())),
+                None,
+                None,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_system_transactions() {
+        let kind = TransactionKind::ConsensusCommitPrologue(ConsensusCommitPrologue {
+            epoch: 3,
+            round: 7,
+            commit_timestamp_ms: 1000,
+        });
+        assert_eq!(
+            TransactionBlockKind::from(&kind),
+            TransactionBlockKind::ConsensusCommitPrologue(ConsensusCommitPrologueTransaction {
+                epoch_id: 3,
+                round: 7,
+                timestamp: DateTime::from_ms(1000),
+            }),
+        );
+
+        let expected = ChangeEpochTransaction {
+            epoch_id: 4,
+            protocol_version: 10,
+            timestamp: DateTime::from_ms(5000),
+            storage_charge: BigInt::from(1u64),
+            computation_charge: BigInt::from(2u64),
+            storage_rebate: BigInt::from(3u64),
+            non_refundable_storage_fee: BigInt::from(4u64),
+        };
+        let kind = TransactionKind::ChangeEpoch(change_epoch());
+        assert_eq!(
+            TransactionBlockKind::from(&kind),
+            TransactionBlockKind::ChangeEpoch(expected.clone()),
+        );
+
+        let kind = TransactionKind::EndOfEpochTransaction(vec![
+            EndOfEpochTransactionKind::AuthenticatorStateCreate,
+            EndOfEpochTransactionKind::ChangeEpoch(change_epoch()),
+        ]);
+        assert_eq!(
+            TransactionBlockKind::from(&kind),
+            TransactionBlockKind::EndOfEpoch(EndOfEpochTransaction {
+                kinds: vec![
+                    "AuthenticatorStateCreate".to_string(),
+                    "ChangeEpoch".to_string(),
+                ],
+                change_epoch: Some(expected),
+            }),
+        );
+    }
+}
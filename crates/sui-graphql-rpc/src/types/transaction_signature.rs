@@ -0,0 +1,266 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use async_graphql::*;
+use sui_sdk::types::{
+    base_types::SuiAddress as NativeSuiAddress,
+    crypto::{PublicKey, SignatureScheme as NativeSignatureScheme, SuiSignature},
+    signature::GenericSignature,
+};
+
+use super::{base64::Base64, sui_address::SuiAddress};
+use crate::error::Error;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum SignatureScheme {
+    Ed25519,
+    Secp256k1,
+    Secp256r1,
+    Bls12381,
+    Multisig,
+    ZkLogin,
+}
+
+/// A signature over a transaction block, by its sender or its sponsor.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TransactionSignature {
+    pub scheme: SignatureScheme,
+    /// The address that the signature authorizes the transaction for.
+    pub address: SuiAddress,
+    /// The signature as it was submitted, starting with the byte that identifies its scheme.
+    pub signature: Base64,
+    /// The public key that signed, for signatures by a single key.
+    pub public_key: Option<Base64>,
+    pub multisig: Option<MultisigSignature>,
+    pub zk_login: Option<ZkLoginSignature>,
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MultisigSignature {
+    /// The total weight of the signatures needed to authorize a transaction.
+    pub threshold: u16,
+    /// All of the keys of the multisig, whether or not they signed.
+    pub members: Vec<MultisigMember>,
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MultisigMember {
+    pub scheme: SignatureScheme,
+    pub public_key: Base64,
+    /// The address of the key on its own.
+    pub address: SuiAddress,
+    pub weight: u8,
+    /// Whether this key contributed a signature.
+    pub signed: bool,
+}
+
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ZkLoginSignature {
+    /// The OpenID provider that issued the credential.
+    pub issuer: String,
+    pub address_seed: String,
+    /// The last epoch in which the ephemeral key can sign.
+    pub max_epoch: u64,
+    /// The scheme of the ephemeral key that signed.
+    pub ephemeral_scheme: SignatureScheme,
+    pub ephemeral_public_key: Base64,
+}
+
+impl From<NativeSignatureScheme> for SignatureScheme {
+    fn from(scheme: NativeSignatureScheme) -> Self {
+        match scheme {
+            NativeSignatureScheme::ED25519 => SignatureScheme::Ed25519,
+            NativeSignatureScheme::Secp256k1 => SignatureScheme::Secp256k1,
+            NativeSignatureScheme::Secp256r1 => SignatureScheme::Secp256r1,
+            NativeSignatureScheme::BLS12381 => SignatureScheme::Bls12381,
+            NativeSignatureScheme::MultiSig => SignatureScheme::Multisig,
+            NativeSignatureScheme::ZkLoginAuthenticator => SignatureScheme::ZkLogin,
+        }
+    }
+}
+
+impl TryFrom<&GenericSignature> for TransactionSignature {
+    type Error = Error;
+
+    fn try_from(sig: &GenericSignature) -> Result<Self, Self::Error> {
+        let address = NativeSuiAddress::try_from(sig).map_err(|e| {
+            Error::Internal(format!(
+                "Can't derive the address of a transaction signature. Error: {e}"
+            ))
+        })?;
+
+        let mut signature = Self {
+            scheme: SignatureScheme::Multisig,
+            address: SuiAddress::from_array(address.to_inner()),
+            signature: Base64::from(sig.as_ref().to_vec()),
+            public_key: None,
+            multisig: None,
+            zk_login: None,
+        };
+
+        match sig {
+            GenericSignature::Signature(s) => {
+                signature.scheme = s.scheme().into();
+                signature.public_key = Some(Base64::from(s.public_key_bytes().to_vec()));
+            }
+            GenericSignature::MultiSig(ms) => {
+                let signed = ms
+                    .get_indices()
+                    .map_err(|e| {
+                        Error::Internal(format!("Can't read the signers of a multisig. Error: {e}"))
+                    })?
+                    .into_iter()
+                    .map(usize::from)
+                    .collect();
+                let pk = ms.get_pk();
+                signature.multisig = Some(MultisigSignature::new(
+                    *pk.threshold(),
+                    pk.pubkeys(),
+                    &signed,
+                ));
+            }
+            GenericSignature::MultiSigLegacy(ms) => {
+                let signed = ms.get_bitmap().iter().map(|i| i as usize).collect();
+                let pk = ms.get_pk();
+                signature.multisig = Some(MultisigSignature::new(
+                    *pk.threshold(),
+                    pk.pubkeys(),
+                    &signed,
+                ));
+            }
+            GenericSignature::ZkLoginAuthenticator(zk) => {
+                let ephemeral = zk.get_user_signature();
+                signature.scheme = SignatureScheme::ZkLogin;
+                signature.zk_login = Some(ZkLoginSignature {
+                    issuer: zk.get_iss().to_string(),
+                    address_seed: zk.get_address_seed().to_string(),
+                    max_epoch: zk.get_max_epoch(),
+                    ephemeral_scheme: ephemeral.scheme().into(),
+                    ephemeral_public_key: Base64::from(ephemeral.public_key_bytes().to_vec()),
+                });
+            }
+        }
+
+        Ok(signature)
+    }
+}
+
+impl MultisigSignature {
+    /// `signed` holds the positions in `pubkeys` of the keys that signed.
+    fn new(threshold: u16, pubkeys: &[(PublicKey, u8)], signed: &BTreeSet<usize>) -> Self {
+        let members = pubkeys
+            .iter()
+            .enumerate()
+            .map(|(i, (pk, weight))| MultisigMember {
+                scheme: pk.scheme().into(),
+                public_key: Base64::from(pk.as_ref().to_vec()),
+                address: SuiAddress::from_array(NativeSuiAddress::from(pk).to_inner()),
+                weight: *weight,
+                signed: signed.contains(&i),
+            })
+            .collect();
+
+        Self { threshold, members }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::{
+        transaction::{Transaction, TransactionDataAPI},
+        utils::{create_fake_transaction, keys, make_upgraded_multisig_tx, make_zklogin_tx},
+    };
+
+    fn sender(tx: &Transaction) -> SuiAddress {
+        SuiAddress::from_array(tx.transaction_data().sender().to_inner())
+    }
+
+    fn signature(tx: &Transaction) -> TransactionSignature {
+        let [sig] = tx.data().tx_signatures() else {
+            panic!("Expected one signature");
+        };
+        TransactionSignature::try_from(sig).unwrap()
+    }
+
+    #[test]
+    fn test_single_key_signature() {
+        let tx = create_fake_transaction();
+        let sig = signature(&tx);
+
+        assert_eq!(sig.scheme, SignatureScheme::Ed25519);
+        assert_eq!(sig.address, sender(&tx));
+        // Flag, signature, then public key.
+        let public_key = sig.public_key.unwrap().0;
+        assert_eq!(sig.signature.0.len(), 1 + 64 + 32);
+        assert_eq!(sig.signature.0[0], NativeSignatureScheme::ED25519.flag());
+        assert_eq!(&sig.signature.0[65..], public_key.as_slice());
+        assert_eq!(sig.multisig, None);
+        assert_eq!(sig.zk_login, None);
+    }
+
+    #[test]
+    fn test_multisig_signature() {
+        // Signed by the first two of three keys, with a threshold of two.
+        let tx = make_upgraded_multisig_tx();
+        let sig = signature(&tx);
+
+        assert_eq!(sig.scheme, SignatureScheme::Multisig);
+        assert_eq!(sig.address, sender(&tx));
+        assert_eq!(sig.public_key, None);
+        assert_eq!(sig.zk_login, None);
+
+        let multisig = sig.multisig.unwrap();
+        assert_eq!(multisig.threshold, 2);
+        let expected: Vec<_> = keys()
+            .iter()
+            .zip([true, true, false])
+            .map(|(key, signed)| {
+                let pk = key.public();
+                MultisigMember {
+                    scheme: pk.scheme().into(),
+                    public_key: Base64::from(pk.as_ref().to_vec()),
+                    address: SuiAddress::from_array(NativeSuiAddress::from(&pk).to_inner()),
+                    weight: 1,
+                    signed,
+                }
+            })
+            .collect();
+        assert_eq!(multisig.members, expected);
+        assert_eq!(
+            multisig
+                .members
+                .iter()
+                .map(|m| m.scheme)
+                .collect::<Vec<_>>(),
+            vec![
+                SignatureScheme::Ed25519,
+                SignatureScheme::Secp256k1,
+                SignatureScheme::Secp256r1,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_zklogin_signature() {
+        let (address, _, sig) = make_zklogin_tx();
+        let sig = TransactionSignature::try_from(&sig).unwrap();
+
+        assert_eq!(sig.scheme, SignatureScheme::ZkLogin);
+        assert_eq!(sig.address, SuiAddress::from_array(address.to_inner()));
+        assert_eq!(sig.public_key, None);
+        assert_eq!(sig.multisig, None);
+
+        let zk_login = sig.zk_login.unwrap();
+        assert_eq!(zk_login.issuer, "https://id.twitch.tv/oauth2");
+        assert_eq!(
+            zk_login.address_seed,
+            "16657007263003735230240998439420301694514420923267872433517882233836276100450",
+        );
+        assert_eq!(zk_login.max_epoch, 10);
+        assert_eq!(zk_login.ephemeral_scheme, SignatureScheme::Ed25519);
+        assert_eq!(zk_login.ephemeral_public_key.0.len(), 32);
+    }
+}
@@ -1,13 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::context_data::{context_ext::DataProviderContextExt, db_data_provider::PgManager};
+
 use super::address::Address;
 use super::big_int::BigInt;
-// use super::sui_address::SuiAddress;
+use super::sui_address::SuiAddress;
 use super::validator_credentials::ValidatorCredentials;
-use async_graphql::*;
+use async_graphql::{
+    connection::{Connection, Edge},
+    *,
+};
+use sui_sdk::types::sui_system_state::PoolTokenExchangeRate;
+
+/// Number of most recent epochs that a validator's APY is averaged over.
+const APY_EPOCHS: usize = 30;
 
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct Validator {
     pub address: Address,
     pub credentials: Option<ValidatorCredentials>,
@@ -18,7 +28,9 @@ pub(crate) struct Validator {
     pub project_url: Option<String>,
     // operationCap: Option<MoveObject>,
     // stakingPool: Option<MoveObject>,
-    // exchangeRates: Option<MoveObject>,
+    /// ID of the table holding the exchange rates of the validator's staking pool, one per epoch.
+    #[graphql(skip)]
+    pub exchange_rates_id: Option<SuiAddress>,
     pub exchange_rates_size: Option<u64>,
     pub staking_pool_activation_epoch: Option<u64>,
     pub staking_pool_sui_balance: Option<BigInt>,
@@ -36,5 +48,200 @@ pub(crate) struct Validator {
     pub next_epoch_commission_rate: Option<u64>,
     // pub at_risk: Option<u64>,
     // pub report_records: Option<Vec<SuiAddress>>,
-    // pub apy: Option<u64>,
+}
+
+/// The exchange rate between SUI and the pool tokens of a validator's staking pool, at the start of
+/// an epoch.
+#[derive(Clone, Debug, PartialEq, SimpleObject)]
+pub(crate) struct ExchangeRate {
+    pub epoch: u64,
+    /// Amount of SUI in the staking pool.
+    pub sui_amount: BigInt,
+    /// Amount of pool tokens issued for the SUI in the staking pool.
+    pub pool_token_amount: BigInt,
+    /// Pool tokens per SUI, or 1.0 if the staking pool is empty.
+    pub rate: f64,
+}
+
+#[ComplexObject]
+impl Validator {
+    /// The validator's annual percentage yield, averaged over the last 30 epochs since the stake
+    /// subsidy started, from the exchange rates of its staking pool. Yields outside of the range
+    /// (0%, 10%) are ignored, as they are when serving `suix_getValidatorsApy`.
+    async fn apy(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        let Some(exchange_rates_id) = self.exchange_rates_id else {
+            return Ok(None);
+        };
+
+        let system_state = ctx.data_provider().get_latest_sui_system_state().await?;
+        let stake_subsidy_start_epoch = system_state.stake_subsidy_start_epoch;
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+
+        // Fetch the exchange rates a window of epochs at a time, most recent first, until there
+        // are enough yields (outliers are skipped, so a window may not be enough).
+        let mut rates = vec![];
+        let mut hi = system_state.epoch;
+        while hi >= stake_subsidy_start_epoch {
+            let lo = hi
+                .saturating_sub(APY_EPOCHS as u64)
+                .max(stake_subsidy_start_epoch);
+            rates.extend(
+                pg_manager
+                    .fetch_exchange_rates_for_epochs(exchange_rates_id, lo..=hi)
+                    .await
+                    .extend()?,
+            );
+            if lo == stake_subsidy_start_epoch
+                || yields(stake_subsidy_start_epoch, &rates).len() >= APY_EPOCHS
+            {
+                break;
+            }
+            hi = lo - 1;
+        }
+
+        Ok(Some(apy(stake_subsidy_start_epoch, &rates)))
+    }
+
+    /// The exchange rates of the validator's staking pool, most recent epoch first.
+    async fn exchange_rate_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, ExchangeRate>>> {
+        let Some(exchange_rates_id) = self.exchange_rates_id else {
+            return Ok(None);
+        };
+
+        let pg_manager = ctx.data_unchecked::<PgManager>();
+        let (rates, has_next_page) = pg_manager
+            .fetch_exchange_rates(exchange_rates_id, first, after, last, before)
+            .await
+            .extend()?;
+
+        let mut connection = Connection::new(false, has_next_page);
+        for (epoch, rate) in rates {
+            let cursor = pg_manager.exchange_rate_cursor(epoch);
+            connection.edges.push(Edge::new(
+                cursor,
+                ExchangeRate {
+                    epoch,
+                    sui_amount: BigInt::from(rate.sui_amount()),
+                    pool_token_amount: BigInt::from(rate.pool_token_amount()),
+                    rate: rate.rate(),
+                },
+            ));
+        }
+        Ok(Some(connection))
+    }
+}
+
+/// APY implied by `rates`, which are sorted by epoch, most recent first: the average of the last
+/// `APY_EPOCHS` annualized yields between consecutive epochs, as `calculate_apys` computes it in
+/// `sui-json-rpc`. It is 0 if there are no such yields.
+fn apy(stake_subsidy_start_epoch: u64, rates: &[(u64, PoolTokenExchangeRate)]) -> f64 {
+    let apys = yields(stake_subsidy_start_epoch, rates);
+    if apys.is_empty() {
+        0.0
+    } else {
+        apys.iter().sum::<f64>() / apys.len() as f64
+    }
+}
+
+/// The annualized yields between consecutive epochs in `rates` (most recent first), from the epoch
+/// the stake subsidy started, leaving out outliers, up to `APY_EPOCHS` of them.
+fn yields(stake_subsidy_start_epoch: u64, rates: &[(u64, PoolTokenExchangeRate)]) -> Vec<f64> {
+    let rates: Vec<_> = rates
+        .iter()
+        .filter(|(epoch, _)| *epoch >= stake_subsidy_start_epoch)
+        .map(|(_, rate)| rate)
+        .collect();
+
+    // Pool tokens per SUI go down as rewards accrue, so the yield from epoch e to e + 1 is
+    // APY_e = (ER_e / ER_e+1) ^ 365 - 1, where ER_e is the older rate.
+    rates
+        .windows(2)
+        .map(|pair| {
+            let (rate_e_1, rate_e) = (pair[0], pair[1]);
+            (rate_e.rate() / rate_e_1.rate()).powf(365.0) - 1.0
+        })
+        .filter(|apy| *apy > 0.0 && *apy < 0.1)
+        .take(APY_EPOCHS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exchange rate of a pool holding `sui_amount` SUI, for a fixed number of pool tokens.
+    fn rate(sui_amount: u64) -> PoolTokenExchangeRate {
+        bcs::from_bytes(&bcs::to_bytes(&(sui_amount, 100_000_000u64)).unwrap()).unwrap()
+    }
+
+    fn annualized(older_sui: f64, newer_sui: f64) -> f64 {
+        (newer_sui / older_sui).powf(365.0) - 1.0
+    }
+
+    #[test]
+    fn test_apy_from_known_rates() {
+        let rates = vec![
+            (3, rate(100_025_000)),
+            (2, rate(100_015_000)),
+            (1, rate(100_005_000)),
+            (0, rate(100_000_000)),
+        ];
+
+        let expected = (annualized(100_005_000.0, 100_015_000.0)
+            + annualized(100_015_000.0, 100_025_000.0))
+            / 2.0;
+        assert!(expected > 0.0);
+        assert!((apy(1, &rates) - expected).abs() < 1e-9);
+
+        let expected = (annualized(100_000_000.0, 100_005_000.0)
+            + annualized(100_005_000.0, 100_015_000.0)
+            + annualized(100_015_000.0, 100_025_000.0))
+            / 3.0;
+        assert!((apy(0, &rates) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_apy_skips_outliers() {
+        // The pool doubles between epochs 1 and 2, and shrinks between epochs 2 and 3.
+        let rates = vec![
+            (3, rate(199_000_000)),
+            (2, rate(200_000_000)),
+            (1, rate(100_005_000)),
+            (0, rate(100_000_000)),
+        ];
+        let expected = annualized(100_000_000.0, 100_005_000.0);
+        assert!((apy(0, &rates) - expected).abs() < 1e-9);
+
+        // Not enough data points.
+        assert_eq!(apy(3, &rates), 0.0);
+        assert_eq!(apy(0, &[]), 0.0);
+    }
+
+    #[test]
+    fn test_apy_averages_most_recent_epochs() {
+        let epochs = APY_EPOCHS as u64 + 10;
+        // The pool grows faster in the most recent epochs.
+        let rates: Vec<_> = (0..=epochs)
+            .rev()
+            .map(|epoch| {
+                let growth = if epoch > 10 { 10_000 } else { 1_000 };
+                (epoch, rate(100_000_000 + epoch * growth))
+            })
+            .collect();
+
+        let yields = yields(0, &rates);
+        assert_eq!(yields.len(), APY_EPOCHS);
+        let recent = annualized(
+            (100_000_000 + (epochs - 1) * 10_000) as f64,
+            (100_000_000 + epochs * 10_000) as f64,
+        );
+        assert!((yields[0] - recent).abs() < 1e-9);
+    }
 }
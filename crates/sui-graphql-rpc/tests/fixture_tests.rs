@@ -0,0 +1,153 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Runs the schema against recorded rows in a standalone Postgres database. Each directory under
+// tests/fixtures holds the rows to load (data.sql), queries (*.graphql), and the golden response
+// to each query (*.json, next to it). Set UPDATE_EXPECT=1 to re-record the golden responses.
+// Tests that need to follow cursors, which are not stable across runs, load their rows themselves.
+// Tests share the database, so they must be run one at a time.
+#[cfg(feature = "pg_integration")]
+mod fixture_tests {
+    use expect_test::expect_file;
+    use move_binary_format::file_format::empty_module;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+    use serde_json::Value;
+    use std::{collections::BTreeMap, fs, path::PathBuf};
+    use sui_graphql_rpc::test_infra::FixtureSchema;
+    use sui_indexer::pg_integration::db_url;
+    use sui_types::{
+        base_types::{ObjectID, SequenceNumber},
+        move_package::MovePackage,
+    };
+
+    fn sorted_entries(dir: &PathBuf) -> Vec<PathBuf> {
+        let mut entries: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[tokio::test]
+    async fn test_fixtures() {
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let db_url = db_url();
+
+        // Fixtures share the database, so they are loaded one at a time.
+        for fixture in sorted_entries(&fixtures) {
+            let data = fs::read_to_string(fixture.join("data.sql")).unwrap();
+            let schema = FixtureSchema::new(&db_url, &data);
+
+            for query in sorted_entries(&fixture) {
+                if query.extension().is_some_and(|ext| ext == "graphql") {
+                    let response = schema.execute(&fs::read_to_string(&query).unwrap()).await;
+                    expect_file![query.with_extension("json")].assert_eq(&response);
+                }
+            }
+        }
+    }
+
+    /// `0x` followed by 32 `byte`s, in hex, as addresses are formatted in responses.
+    fn address(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 32]))
+    }
+
+    /// A row of the packages table, for a package at `address(id)` with a single empty module,
+    /// published by `address(publisher)` in `checkpoint`.
+    fn package_row(id: u8, publisher: u8, checkpoint: u64) -> String {
+        let mut module = empty_module();
+        module.identifiers[0] = Identifier::new("m").unwrap();
+        module.address_identifiers[0] = AccountAddress::new([id; 32]);
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+
+        let package = MovePackage::new(
+            ObjectID::new([id; 32]),
+            SequenceNumber::from_u64(1),
+            BTreeMap::from([("m".to_string(), bytes)]),
+            u64::MAX,
+            vec![],
+            BTreeMap::new(),
+        )
+        .unwrap();
+        format!(
+            "('\\x{}', '\\x{}', {checkpoint}, '\\x{}')",
+            hex::encode([id; 32]),
+            hex::encode(bcs::to_bytes(&package).unwrap()),
+            hex::encode([publisher; 32]),
+        )
+    }
+
+    /// Addresses of the packages in a page of `packageConnection`, and its end cursor if it has a
+    /// next page.
+    fn package_page(response: &str) -> (Vec<String>, Option<String>) {
+        let response: Value = serde_json::from_str(response).unwrap();
+        let connection = &response["data"]["packageConnection"];
+        let addresses = connection["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| edge["node"]["address"].as_str().unwrap().to_string())
+            .collect();
+        let page_info = &connection["pageInfo"];
+        let end_cursor = page_info["hasNextPage"]
+            .as_bool()
+            .unwrap()
+            .then(|| page_info["endCursor"].as_str().unwrap().to_string());
+        (addresses, end_cursor)
+    }
+
+    #[tokio::test]
+    async fn test_package_connection_by_publisher() {
+        let checkpoints: Vec<_> = (0..3)
+            .map(|seq| {
+                format!(
+                    "({seq}, decode(repeat('0{seq}', 32), 'hex'), 0, 0, NULL, false, \
+                     ARRAY[]::bytea[], 0, 0, 0, 0, 0, 0, '\\x00', '\\x00', NULL)"
+                )
+            })
+            .collect();
+        // Packages 1, 3 and 4 were published by 0xaa..., in that order.
+        let packages = [
+            package_row(1, 0xaa, 0),
+            package_row(2, 0xbb, 1),
+            package_row(3, 0xaa, 1),
+            package_row(4, 0xaa, 2),
+        ];
+        let data = format!(
+            "INSERT INTO checkpoints (
+                sequence_number, checkpoint_digest, epoch, network_total_transactions,
+                previous_checkpoint_digest, end_of_epoch, tx_digests, timestamp_ms,
+                total_gas_cost, computation_cost, storage_cost, storage_rebate,
+                non_refundable_storage_fee, checkpoint_commitments, validator_signature,
+                end_of_epoch_data
+            ) VALUES {};
+            INSERT INTO packages (
+                package_id, move_package, checkpoint_sequence_number, publisher
+            ) VALUES {};",
+            checkpoints.join(", "),
+            packages.join(", "),
+        );
+        let schema = FixtureSchema::new(&db_url(), &data);
+
+        let query = |after: Option<&str>| {
+            let after = after.map_or(String::new(), |c| format!(", after: \"{c}\""));
+            format!(
+                "{{ packageConnection(first: 2{after}, filter: {{ publisher: \"{}\" }}) {{
+                    edges {{ node {{ address }} }}
+                    pageInfo {{ hasNextPage endCursor }}
+                }} }}",
+                address(0xaa),
+            )
+        };
+
+        let (page, cursor) = package_page(&schema.execute(&query(None)).await);
+        assert_eq!(page, vec![address(1), address(3)]);
+        let cursor = cursor.expect("Expected a second page");
+
+        let (page, cursor) = package_page(&schema.execute(&query(Some(&cursor))).await);
+        assert_eq!(page, vec![address(4)]);
+        assert_eq!(cursor, None);
+    }
+}
@@ -0,0 +1,20 @@
+-- Genesis, and the checkpoint after it, in epoch 0.
+INSERT INTO checkpoints (
+    sequence_number, checkpoint_digest, epoch, network_total_transactions,
+    previous_checkpoint_digest, end_of_epoch, tx_digests, timestamp_ms, total_gas_cost,
+    computation_cost, storage_cost, storage_rebate, non_refundable_storage_fee,
+    checkpoint_commitments, validator_signature, end_of_epoch_data
+) VALUES
+(
+    0, decode(repeat('00', 32), 'hex'), 0, 1,
+    NULL, false, ARRAY[decode(repeat('aa', 32), 'hex')], 1697000000000, 0,
+    0, 0, 0, 0,
+    '\x00', '\x00', NULL
+),
+(
+    1, decode(repeat('01', 32), 'hex'), 0, 3,
+    decode(repeat('00', 32), 'hex'), false,
+    ARRAY[decode(repeat('bb', 32), 'hex'), decode(repeat('cc', 32), 'hex')], 1697000001000, 300,
+    100, 500, 200, 2,
+    '\x00', '\x00', NULL
+);
@@ -0,0 +1,7 @@
+-- How far each pruner has got, so that it picks up where it left off after a restart.
+CREATE TABLE pruner_watermarks
+(
+    pruner          TEXT   PRIMARY KEY,
+    -- The next checkpoint whose versions supersede older ones.
+    next_checkpoint BIGINT NOT NULL
+);
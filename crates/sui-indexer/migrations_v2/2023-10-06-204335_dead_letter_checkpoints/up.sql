@@ -0,0 +1,9 @@
+CREATE TABLE dead_letter_checkpoints
+(
+    sequence_number             BIGINT       PRIMARY KEY,
+    -- bcs serialized CheckpointData
+    raw_checkpoint              bytea        NOT NULL,
+    -- error that caused the checkpoint to be dead-lettered
+    error                       TEXT         NOT NULL,
+    failed_at_ms                BIGINT       NOT NULL
+);
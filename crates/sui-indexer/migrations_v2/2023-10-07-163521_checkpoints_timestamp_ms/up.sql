@@ -0,0 +1 @@
+CREATE INDEX checkpoints_timestamp_ms ON checkpoints (timestamp_ms);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS packages_publisher;
+DROP INDEX IF EXISTS packages_checkpoint_sequence_number;
+ALTER TABLE packages
+    DROP COLUMN IF EXISTS publisher,
+    DROP COLUMN IF EXISTS checkpoint_sequence_number;
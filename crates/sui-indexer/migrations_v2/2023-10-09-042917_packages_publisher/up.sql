@@ -0,0 +1,18 @@
+ALTER TABLE packages
+    ADD COLUMN checkpoint_sequence_number   BIGINT  NOT NULL DEFAULT 0,
+    ADD COLUMN publisher                    bytea   NOT NULL DEFAULT '\x0000000000000000000000000000000000000000000000000000000000000000';
+ALTER TABLE packages
+    ALTER COLUMN checkpoint_sequence_number DROP DEFAULT,
+    ALTER COLUMN publisher DROP DEFAULT;
+
+-- Packages indexed before this migration are backfilled from the transactions that published
+-- them, which created the package object, and were sent by the publisher. Packages whose
+-- transactions are not indexed are recorded as published by 0x0 in checkpoint 0.
+UPDATE packages p
+SET publisher = t.senders[1],
+    checkpoint_sequence_number = t.checkpoint_sequence_number
+FROM tx_indices t
+WHERE t.changed_objects @> ARRAY[p.package_id];
+
+CREATE INDEX packages_checkpoint_sequence_number ON packages (checkpoint_sequence_number, package_id);
+CREATE INDEX packages_publisher ON packages (publisher, checkpoint_sequence_number, package_id);
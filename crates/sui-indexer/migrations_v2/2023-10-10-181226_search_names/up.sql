@@ -0,0 +1,21 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Names of packages, modules, structs and SuiNS domains, for search by substring or similarity.
+CREATE TABLE search_names (
+    -- 0: package, 1: module, 2: struct, 3: SuiNS name
+    kind                        SMALLINT    NOT NULL,
+    -- The name search queries are matched against
+    name                        TEXT        NOT NULL,
+    -- Unique per kind: a package ID, `package::module`, `package::module::Struct` or a SuiNS domain
+    full_name                   TEXT        NOT NULL,
+    -- The package defining the name, or for SuiNS names, the address the name points to
+    address                     bytea,
+    -- For SuiNS names, the object holding the name's record, which removes the name when deleted
+    object_id                   bytea,
+    -- For SuiNS names, when the name expires and is removed
+    expiration_timestamp_ms     BIGINT,
+    PRIMARY KEY (kind, full_name)
+);
+CREATE INDEX search_names_name ON search_names USING GIN (name gin_trgm_ops);
+CREATE INDEX search_names_object_id ON search_names (object_id) WHERE object_id IS NOT NULL;
+CREATE INDEX search_names_expiration_timestamp_ms ON search_names (expiration_timestamp_ms) WHERE expiration_timestamp_ms IS NOT NULL;
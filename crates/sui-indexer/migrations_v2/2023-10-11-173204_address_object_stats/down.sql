@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS objects_address_object_stats_insert ON objects;
+DROP TRIGGER IF EXISTS objects_address_object_stats_update ON objects;
+DROP TRIGGER IF EXISTS objects_address_object_stats_delete ON objects;
+DROP FUNCTION IF EXISTS log_address_object_stats_deltas();
+DROP TABLE IF EXISTS address_object_stats_deltas;
+DROP TABLE IF EXISTS address_object_stats;
+ALTER TABLE objects DROP COLUMN IF EXISTS storage_rebate;
@@ -0,0 +1,71 @@
+ALTER TABLE objects ADD COLUMN storage_rebate BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE objects ALTER COLUMN storage_rebate DROP DEFAULT;
+
+-- Objects indexed before this migration are backfilled from their BCS serialized contents, which
+-- end with the storage rebate, as a little-endian u64.
+UPDATE objects SET storage_rebate = (
+    SELECT SUM(get_byte(serialized_object, length(serialized_object) - 8 + i)::BIGINT << (8 * i))
+    FROM generate_series(0, 7) AS i
+);
+
+-- Number and total storage rebate of the objects owned by each address, so that they can be read
+-- without scanning the objects table.
+CREATE TABLE address_object_stats (
+    owner_id                    bytea       PRIMARY KEY,
+    object_count                BIGINT      NOT NULL,
+    -- Sum of the storage rebates of the owned objects, which is proportional to their size
+    storage_rebate              BIGINT      NOT NULL
+);
+
+INSERT INTO address_object_stats (owner_id, object_count, storage_rebate)
+SELECT owner_id, COUNT(*), SUM(storage_rebate)
+FROM objects
+WHERE owner_type = 1 AND owner_id IS NOT NULL
+GROUP BY owner_id;
+
+-- Changes to the stats, appended by the triggers below as objects are written, and folded into
+-- `address_object_stats` once per commit of checkpoints. Writes to objects are made in parallel,
+-- and appending to this table, rather than updating the stats directly, keeps them from
+-- contending over the rows of the addresses that own the most objects.
+CREATE TABLE address_object_stats_deltas (
+    owner_id                    bytea       NOT NULL,
+    object_count                BIGINT      NOT NULL,
+    storage_rebate              BIGINT      NOT NULL
+);
+
+-- OwnerType: 1: Address, see types_v2.rs
+CREATE OR REPLACE FUNCTION log_address_object_stats_deltas() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        INSERT INTO address_object_stats_deltas (owner_id, object_count, storage_rebate)
+        SELECT owner_id, -COUNT(*), -SUM(storage_rebate)
+        FROM old_objects
+        WHERE owner_type = 1 AND owner_id IS NOT NULL
+        GROUP BY owner_id;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        INSERT INTO address_object_stats_deltas (owner_id, object_count, storage_rebate)
+        SELECT owner_id, COUNT(*), SUM(storage_rebate)
+        FROM new_objects
+        WHERE owner_type = 1 AND owner_id IS NOT NULL
+        GROUP BY owner_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Statement level triggers with transition tables can only have one event each.
+CREATE TRIGGER objects_address_object_stats_insert
+AFTER INSERT ON objects
+REFERENCING NEW TABLE AS new_objects
+FOR EACH STATEMENT EXECUTE FUNCTION log_address_object_stats_deltas();
+
+CREATE TRIGGER objects_address_object_stats_update
+AFTER UPDATE ON objects
+REFERENCING OLD TABLE AS old_objects NEW TABLE AS new_objects
+FOR EACH STATEMENT EXECUTE FUNCTION log_address_object_stats_deltas();
+
+CREATE TRIGGER objects_address_object_stats_delete
+AFTER DELETE ON objects
+REFERENCING OLD TABLE AS old_objects
+FOR EACH STATEMENT EXECUTE FUNCTION log_address_object_stats_deltas();
@@ -0,0 +1,20 @@
+-- Versions of the shared objects that each transaction was sequenced against, for analyzing
+-- contention on shared objects. Only populated when the indexer runs with
+-- --index-shared-object-versions.
+CREATE TABLE tx_shared_objects (
+    tx_sequence_number          BIGINT       NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    -- bytes of the transaction digest
+    transaction_digest          bytea        NOT NULL,
+    -- ObjectID in bytes
+    object_id                   bytea        NOT NULL,
+    -- the version assigned to the object for this transaction
+    object_version              BIGINT       NOT NULL,
+    -- whether the transaction took the object by mutable reference, and so had to be sequenced
+    -- after every other transaction that used it at this version.
+    mutable                     BOOLEAN      NOT NULL,
+    PRIMARY KEY (tx_sequence_number, object_id)
+);
+
+CREATE INDEX tx_shared_objects_object_id_version ON tx_shared_objects (object_id, object_version);
+CREATE INDEX tx_shared_objects_checkpoint_sequence_number ON tx_shared_objects (checkpoint_sequence_number);
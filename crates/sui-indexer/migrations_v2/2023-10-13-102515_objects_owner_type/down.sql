@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS objects_owner_id_object_id;
+DROP INDEX IF EXISTS objects_owner_type;
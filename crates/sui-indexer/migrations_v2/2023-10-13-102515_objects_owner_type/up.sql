@@ -0,0 +1,6 @@
+-- For listing objects by the kind of their owner (e.g. all shared objects) in object ID order.
+-- OwnerType: 0: Immutable, 1: Address, 2: Object, 3: Shared, see types_v2.rs
+CREATE INDEX objects_owner_type ON objects (owner_type, object_id);
+-- For listing the objects owned by an address or object, optionally limited to one kind of owner,
+-- in object ID order. Only objects owned by addresses and objects have an owner ID.
+CREATE INDEX objects_owner_id_object_id ON objects (owner_id, object_id) WHERE owner_type BETWEEN 1 AND 2 AND owner_id IS NOT NULL;
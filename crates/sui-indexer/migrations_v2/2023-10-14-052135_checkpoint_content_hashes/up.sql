@@ -0,0 +1,6 @@
+CREATE TABLE checkpoint_content_hashes
+(
+    sequence_number             BIGINT       PRIMARY KEY,
+    -- blake2b256 digest of the rows derived from the checkpoint
+    content_hash                bytea        NOT NULL
+);
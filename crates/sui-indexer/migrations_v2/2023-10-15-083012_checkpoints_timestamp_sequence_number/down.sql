@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS checkpoints_timestamp_ms_sequence_number;
+CREATE INDEX checkpoints_timestamp_ms ON checkpoints (timestamp_ms);
@@ -0,0 +1,4 @@
+-- Several checkpoints can share a timestamp, so include the sequence number in the index to find
+-- the latest checkpoint at or before a given time with a single index lookup.
+DROP INDEX IF EXISTS checkpoints_timestamp_ms;
+CREATE INDEX checkpoints_timestamp_ms_sequence_number ON checkpoints (timestamp_ms, sequence_number);
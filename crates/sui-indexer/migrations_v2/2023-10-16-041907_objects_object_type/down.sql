@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS objects_owner_object_type;
+DROP INDEX IF EXISTS objects_object_type;
+ALTER TABLE objects DROP COLUMN IF EXISTS object_type;
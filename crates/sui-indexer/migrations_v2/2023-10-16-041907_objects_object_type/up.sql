@@ -0,0 +1,128 @@
+-- Type of the object, as a struct tag, e.g. `0x2::coin::Coin<0x2::sui::SUI>`. Null for packages.
+ALTER TABLE objects ADD COLUMN IF NOT EXISTS object_type TEXT;
+
+-- The types of objects indexed before this migration are backfilled from their BCS serialized
+-- contents, formatted as `StructTag`'s `Display` implementation does, using the functions below,
+-- which each decode a value from `bytes` starting at (zero-based) offset `pos`, and return the
+-- offset after it.
+CREATE OR REPLACE FUNCTION bcs_uleb128(bytes bytea, INOUT pos int, OUT value int) AS $$
+DECLARE
+    b int;
+    shift int := 0;
+BEGIN
+    value := 0;
+    LOOP
+        b := get_byte(bytes, pos);
+        pos := pos + 1;
+        value := value | ((b & 127) << shift);
+        EXIT WHEN b < 128;
+        shift := shift + 7;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION bcs_identifier(bytes bytea, INOUT pos int, OUT ident text) AS $$
+DECLARE
+    len int;
+BEGIN
+    SELECT * INTO pos, len FROM bcs_uleb128(bytes, pos);
+    ident := convert_from(substring(bytes FROM pos + 1 FOR len), 'UTF8');
+    pos := pos + len;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+-- Variants are numbered in the order they are declared in `TypeTag`.
+CREATE OR REPLACE FUNCTION bcs_type_tag(bytes bytea, INOUT pos int, OUT tag text) AS $$
+DECLARE
+    variant int;
+BEGIN
+    SELECT * INTO pos, variant FROM bcs_uleb128(bytes, pos);
+    CASE variant
+        WHEN 0 THEN tag := 'bool';
+        WHEN 1 THEN tag := 'u8';
+        WHEN 2 THEN tag := 'u64';
+        WHEN 3 THEN tag := 'u128';
+        WHEN 4 THEN tag := 'address';
+        WHEN 5 THEN tag := 'signer';
+        WHEN 6 THEN
+            SELECT * INTO pos, tag FROM bcs_type_tag(bytes, pos);
+            tag := 'vector<' || tag || '>';
+        WHEN 7 THEN
+            SELECT * INTO pos, tag FROM bcs_struct_tag(bytes, pos);
+        WHEN 8 THEN tag := 'u16';
+        WHEN 9 THEN tag := 'u32';
+        WHEN 10 THEN tag := 'u256';
+    END CASE;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION bcs_struct_tag(bytes bytea, INOUT pos int, OUT tag text) AS $$
+DECLARE
+    address text;
+    module_name text;
+    struct_name text;
+    num_params int;
+    param text;
+    params text[] := '{}';
+BEGIN
+    -- Addresses are formatted without leading zeroes.
+    address := ltrim(encode(substring(bytes FROM pos + 1 FOR 32), 'hex'), '0');
+    pos := pos + 32;
+    SELECT * INTO pos, module_name FROM bcs_identifier(bytes, pos);
+    SELECT * INTO pos, struct_name FROM bcs_identifier(bytes, pos);
+    SELECT * INTO pos, num_params FROM bcs_uleb128(bytes, pos);
+    FOR i IN 1..num_params LOOP
+        SELECT * INTO pos, param FROM bcs_type_tag(bytes, pos);
+        params := params || param;
+    END LOOP;
+
+    tag := '0x' || CASE WHEN address = '' THEN '0' ELSE address END
+        || '::' || module_name || '::' || struct_name;
+    IF num_params > 0 THEN
+        tag := tag || '<' || array_to_string(params, ', ') || '>';
+    END IF;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+-- Objects are serialized starting with their `Data`, and Move objects with their
+-- `MoveObjectType`, which has compact representations for the most common types.
+CREATE OR REPLACE FUNCTION bcs_object_type(bytes bytea) RETURNS text AS $$
+DECLARE
+    pos int := 0;
+    variant int;
+    tag text;
+BEGIN
+    SELECT * INTO pos, variant FROM bcs_uleb128(bytes, pos);
+    -- Packages have no type.
+    IF variant <> 0 THEN
+        RETURN NULL;
+    END IF;
+
+    SELECT * INTO pos, variant FROM bcs_uleb128(bytes, pos);
+    CASE variant
+        WHEN 0 THEN
+            SELECT * INTO pos, tag FROM bcs_struct_tag(bytes, pos);
+            RETURN tag;
+        WHEN 1 THEN
+            RETURN '0x2::coin::Coin<0x2::sui::SUI>';
+        WHEN 2 THEN
+            RETURN '0x3::staking_pool::StakedSui';
+        WHEN 3 THEN
+            SELECT * INTO pos, tag FROM bcs_type_tag(bytes, pos);
+            RETURN '0x2::coin::Coin<' || tag || '>';
+    END CASE;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+UPDATE objects SET object_type = bcs_object_type(serialized_object) WHERE object_type IS NULL;
+
+DROP FUNCTION bcs_object_type(bytea);
+DROP FUNCTION bcs_struct_tag(bytea, int);
+DROP FUNCTION bcs_type_tag(bytea, int);
+DROP FUNCTION bcs_identifier(bytea, int);
+DROP FUNCTION bcs_uleb128(bytea, int);
+
+-- For filtering objects by their type, or by the package or module that defines it.
+CREATE INDEX IF NOT EXISTS objects_object_type ON objects (object_type text_pattern_ops, object_id) WHERE object_type IS NOT NULL;
+-- For filtering the objects owned by an address by type.
+CREATE INDEX IF NOT EXISTS objects_owner_object_type ON objects (owner_id, object_type text_pattern_ops) WHERE object_type IS NOT NULL AND owner_type = 1;
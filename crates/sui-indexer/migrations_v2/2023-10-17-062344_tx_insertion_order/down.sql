@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS export_watermarks;
+DROP TABLE IF EXISTS tx_insertion_order;
@@ -0,0 +1,20 @@
+-- The order in which transactions were committed to this database, for exporting them to
+-- downstream warehouses incrementally. Rows are written in the same database transaction as the
+-- checkpoint the transactions belong to, which is committed after all of its other data, so an
+-- exporter reading past its watermark never sees a transaction whose data is incomplete, and never
+-- sees a smaller insertion_order after a larger one. Only populated when the indexer runs with
+-- --tx-insertion-order.
+CREATE TABLE tx_insertion_order (
+    insertion_order             BIGSERIAL    PRIMARY KEY,
+    tx_sequence_number          BIGINT       NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL
+);
+
+-- Re-ingesting a checkpoint must not export its transactions again.
+CREATE UNIQUE INDEX tx_insertion_order_tx_sequence_number ON tx_insertion_order (tx_sequence_number);
+
+-- The last insertion_order that each exporter has durably loaded downstream.
+CREATE TABLE export_watermarks (
+    exporter                    TEXT         PRIMARY KEY,
+    insertion_order             BIGINT       NOT NULL
+);
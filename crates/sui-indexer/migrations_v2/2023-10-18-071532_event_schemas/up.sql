@@ -0,0 +1,17 @@
+-- The layouts of the event types that the indexer has seen, so that consumers can decode the
+-- contents of historical events without resolving their types against the packages as they are
+-- now. A layout is identified by its schema_version, the Blake2b256 digest of its BCS bytes.
+CREATE TABLE event_schemas
+(
+    -- StructTag in Display format
+    event_type                  text         NOT NULL,
+    schema_version              bytea        NOT NULL,
+    -- bcs of the MoveStructLayout of the event type
+    layout                      bytea        NOT NULL,
+    -- The checkpoint that the layout was first seen in.
+    checkpoint_sequence_number  bigint       NOT NULL,
+    PRIMARY KEY(event_type, schema_version)
+);
+
+-- NULL for events indexed before the registry existed, or whose layout could not be resolved.
+ALTER TABLE events ADD COLUMN schema_version bytea;
@@ -0,0 +1,3 @@
+-- Transactions are commonly filtered by sender along with the function they call, so index both
+-- together, to find their intersection with a single index scan.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS tx_indices_senders_package_module_functions ON tx_indices USING GIN(senders, package_module_functions);
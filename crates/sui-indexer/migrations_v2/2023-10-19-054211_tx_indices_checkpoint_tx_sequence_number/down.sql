@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX CONCURRENTLY IF EXISTS tx_indices_checkpoint_sequence_number_tx_sequence_number;
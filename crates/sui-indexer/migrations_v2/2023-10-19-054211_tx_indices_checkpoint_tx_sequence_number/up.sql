@@ -0,0 +1,3 @@
+-- Queries for transactions page through them in order of sequence number, within a range of
+-- checkpoints, which this index answers without sorting.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS tx_indices_checkpoint_sequence_number_tx_sequence_number ON tx_indices (checkpoint_sequence_number, tx_sequence_number);
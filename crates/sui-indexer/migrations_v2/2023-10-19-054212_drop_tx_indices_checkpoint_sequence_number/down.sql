@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+CREATE INDEX CONCURRENTLY IF NOT EXISTS tx_indices_checkpoint_sequence_number ON tx_indices (checkpoint_sequence_number);
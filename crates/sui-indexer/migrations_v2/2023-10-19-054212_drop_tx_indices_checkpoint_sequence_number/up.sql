@@ -0,0 +1,3 @@
+-- The index on checkpoints alone is a prefix of the index on checkpoints and sequence numbers, so
+-- it is no longer needed once that one has been built.
+DROP INDEX CONCURRENTLY IF EXISTS tx_indices_checkpoint_sequence_number;
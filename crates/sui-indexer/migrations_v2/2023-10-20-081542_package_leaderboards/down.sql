@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS package_leaderboard_windows;
+DROP TABLE IF EXISTS package_leaderboard_senders;
+DROP TABLE IF EXISTS package_leaderboards;
+DROP TABLE IF EXISTS package_activity;
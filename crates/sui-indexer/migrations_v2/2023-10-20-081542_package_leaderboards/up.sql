@@ -0,0 +1,65 @@
+-- The calls that each sender made to each package in each checkpoint, the events the package
+-- emitted in those transactions, and the gas used by the transactions that made them. Only
+-- populated when the indexer runs with --index-package-activity, and only kept for as long as the
+-- longest leaderboard period (see leaderboards.rs).
+CREATE TABLE package_activity (
+    -- ObjectID of the package in bytes
+    package                     bytea        NOT NULL,
+    -- SuiAddress of the sender in bytes
+    sender                      bytea        NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    timestamp_ms                BIGINT       NOT NULL,
+    -- number of Move calls to the package, over all of the sender's transactions in the checkpoint
+    calls                       BIGINT       NOT NULL,
+    -- number of events emitted by the package, over all of the sender's transactions in the
+    -- checkpoint
+    events                      BIGINT       NOT NULL,
+    -- computation and storage cost of those transactions, counted in full towards every package
+    -- that they call
+    gas_used                    BIGINT       NOT NULL,
+    PRIMARY KEY (package, sender, checkpoint_sequence_number)
+);
+
+CREATE INDEX package_activity_timestamp_ms ON package_activity (timestamp_ms);
+
+-- Packages ranked by their activity over the last day and week, kept up to date incrementally
+-- from package_activity so that reading them doesn't aggregate over the activity itself.
+CREATE TABLE package_leaderboards (
+    -- LeaderboardPeriod: 0: Day, 1: Week, see models_v2/package_leaderboards.rs
+    period                      SMALLINT     NOT NULL,
+    package                     bytea        NOT NULL,
+    calls                       BIGINT       NOT NULL,
+    events                      BIGINT       NOT NULL,
+    -- number of distinct senders that called the package, i.e. of its rows in
+    -- package_leaderboard_senders
+    senders                     BIGINT       NOT NULL,
+    gas_used                    BIGINT       NOT NULL,
+    PRIMARY KEY (period, package)
+);
+
+CREATE INDEX package_leaderboards_calls ON package_leaderboards (period, calls DESC, package);
+CREATE INDEX package_leaderboards_events ON package_leaderboards (period, events DESC, package);
+CREATE INDEX package_leaderboards_senders ON package_leaderboards (period, senders DESC, package);
+CREATE INDEX package_leaderboards_gas_used ON package_leaderboards (period, gas_used DESC, package);
+
+-- The senders that called each package over each period, and when they last did, so that senders
+-- can be counted as they enter and leave the period, without counting them all again.
+CREATE TABLE package_leaderboard_senders (
+    period                      SMALLINT     NOT NULL,
+    package                     bytea        NOT NULL,
+    sender                      bytea        NOT NULL,
+    last_active_ms              BIGINT       NOT NULL,
+    PRIMARY KEY (period, package, sender)
+);
+
+CREATE INDEX package_leaderboard_senders_last_active_ms
+    ON package_leaderboard_senders (period, last_active_ms);
+
+-- The activity each leaderboard covers: activity with timestamps in (window_start_ms,
+-- window_end_ms], where window_end_ms is the timestamp of the latest checkpoint when the
+-- leaderboard was last refreshed.
+CREATE TABLE package_leaderboard_windows (
+    period                      SMALLINT     PRIMARY KEY,
+    window_start_ms             BIGINT       NOT NULL,
+    window_end_ms               BIGINT       NOT NULL
+);
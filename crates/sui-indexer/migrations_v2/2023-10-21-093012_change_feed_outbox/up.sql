@@ -0,0 +1,11 @@
+-- Change feed messages (see cdc.rs) waiting to be published. They are written along with the rest
+-- of their checkpoint's data, published once the checkpoint is committed, and deleted once the
+-- message bus has accepted them.
+CREATE TABLE change_feed_outbox (
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    -- position of the message among its checkpoint's messages, which are published in order
+    message_index               SMALLINT     NOT NULL,
+    subject                     TEXT         NOT NULL,
+    payload                     bytea        NOT NULL,
+    PRIMARY KEY (checkpoint_sequence_number, message_index)
+);
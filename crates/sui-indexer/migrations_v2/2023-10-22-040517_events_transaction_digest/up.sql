@@ -0,0 +1,2 @@
+-- For listing the events emitted by a transaction, by its digest.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS events_transaction_digest ON events (transaction_digest);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS transactions_checkpoint_gas_price;
+ALTER TABLE transactions DROP COLUMN IF EXISTS gas_price;
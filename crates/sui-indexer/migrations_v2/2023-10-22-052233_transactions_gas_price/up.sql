@@ -0,0 +1,5 @@
+-- Gas price of programmable transactions, so that recent gas prices can be read without decoding
+-- the transactions. Transactions indexed before this migration are not backfilled.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS gas_price BIGINT;
+-- For reading the gas prices of the transactions in the latest checkpoints from the index alone.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS transactions_checkpoint_gas_price ON transactions (checkpoint_sequence_number, gas_price) WHERE gas_price IS NOT NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS checkpoints_epoch_sequence_number;
+CREATE INDEX checkpoints_epoch ON checkpoints (epoch);
@@ -0,0 +1,4 @@
+-- For paging through the checkpoints of an epoch in order, which an index on `epoch` alone can only
+-- do by sorting all of the epoch's checkpoints first.
+DROP INDEX IF EXISTS checkpoints_epoch;
+CREATE INDEX checkpoints_epoch_sequence_number ON checkpoints (epoch, sequence_number);
@@ -0,0 +1,600 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Change data capture: publishes what every committed checkpoint changed to a message bus, so
+//! that downstream systems can follow the chain without polling Postgres.
+//!
+//! Each checkpoint produces one message on each of these subjects (topics, for Kafka), in this
+//! order:
+//! - `<prefix>.transactions`: the checkpoint's transactions.
+//! - `<prefix>.objects`: the objects the checkpoint created, mutated, wrapped or deleted.
+//! - `<prefix>.events`: the events the checkpoint's transactions emitted.
+//!
+//! Messages are JSON, and carry the checkpoint they describe in their `Sui-Checkpoint` header.
+//! They are encoded when the checkpoint is indexed, and written to the `change_feed_outbox` table
+//! along with the rest of its data. A separate task publishes the messages of committed
+//! checkpoints from the outbox, and deletes each one once the bus has accepted it, so committing
+//! never waits on the bus. If the indexer stops between publishing a message and deleting it, the
+//! message is published again when it restarts, so delivery is at-least-once: consumers may see a
+//! message more than once, but never miss one. NATS messages also carry a `Nats-Msg-Id` header
+//! that is unique to the subject and checkpoint, so JetStream can drop those duplicates itself.
+//!
+//! Messages are published in checkpoint order, except for checkpoints that are committed late,
+//! when they are replayed from the dead letter queue.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::Serialize;
+use sui_types::base_types::{ObjectDigest, ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::transaction::TransactionDataAPI;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::errors::IndexerError;
+use crate::handlers::CheckpointDataToCommit;
+use crate::metrics::IndexerMetrics;
+use crate::models_v2::change_feed_outbox::StoredChangeFeedMessage;
+use crate::store::IndexerStoreV2;
+use crate::types_v2::{OwnerType, TransactionKind};
+use crate::IndexerConfig;
+
+/// Header carrying the sequence number of the checkpoint a message describes.
+pub const CHECKPOINT_HEADER: &str = "Sui-Checkpoint";
+
+/// Header JetStream uses to drop messages it has already stored.
+const MSG_ID_HEADER: &str = "Nats-Msg-Id";
+
+/// Failed publishes are retried after this long at first, backing off up to `MAX_RETRY_DELAY`.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(100);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How long publishes wait for the bus to accept a message.
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of messages read from the outbox at a time.
+const OUTBOX_BATCH_SIZE: usize = 300;
+
+/// The outbox is checked for messages after every commit, and at least this often.
+const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A message bus that change feed messages are published to.
+#[async_trait]
+pub trait ChangeFeedPublisher: Send {
+    /// Publishes `payload` on `subject`, returning once the bus has durably accepted it.
+    async fn publish(
+        &mut self,
+        subject: &str,
+        headers: &[(&str, String)],
+        payload: &[u8],
+    ) -> anyhow::Result<()>;
+}
+
+/// Publishes the messages in the outbox, see the module documentation.
+pub struct ChangeFeed {
+    publisher: Box<dyn ChangeFeedPublisher>,
+    metrics: IndexerMetrics,
+}
+
+#[derive(Serialize)]
+struct CheckpointTransactions {
+    checkpoint: u64,
+    timestamp_ms: u64,
+    transactions: Vec<TransactionChange>,
+}
+
+#[derive(Serialize)]
+struct TransactionChange {
+    digest: TransactionDigest,
+    tx_sequence_number: u64,
+    sender: SuiAddress,
+    kind: &'static str,
+    success: bool,
+}
+
+#[derive(Serialize)]
+struct CheckpointObjectChanges {
+    checkpoint: u64,
+    changed: Vec<ObjectChange>,
+    deleted: Vec<DeletedObject>,
+}
+
+#[derive(Serialize)]
+struct ObjectChange {
+    object_id: ObjectID,
+    version: u64,
+    digest: ObjectDigest,
+    owner_kind: &'static str,
+    owner: Option<SuiAddress>,
+    object_type: Option<String>,
+    coin_balance: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct DeletedObject {
+    object_id: ObjectID,
+    version: u64,
+    digest: ObjectDigest,
+}
+
+#[derive(Serialize)]
+struct CheckpointEvents {
+    checkpoint: u64,
+    events: Vec<EventChange>,
+}
+
+#[derive(Serialize)]
+struct EventChange {
+    transaction_digest: TransactionDigest,
+    event_sequence_number: u64,
+    package: ObjectID,
+    module: String,
+    event_type: String,
+    senders: Vec<SuiAddress>,
+    /// The event's contents, as Base64-encoded BCS.
+    bcs: String,
+    /// Hex-encoded schema version of the layout of `bcs`, in the `event_schemas` table.
+    schema_version: Option<String>,
+}
+
+/// The messages describing `checkpoint`, in the order they should be published in, on subjects
+/// starting with `subject_prefix`.
+pub fn encode(
+    subject_prefix: &str,
+    checkpoint: &CheckpointDataToCommit,
+) -> Result<Vec<StoredChangeFeedMessage>, IndexerError> {
+    let sequence_number = checkpoint.checkpoint.sequence_number;
+
+    let transactions = CheckpointTransactions {
+        checkpoint: sequence_number,
+        timestamp_ms: checkpoint.checkpoint.timestamp_ms,
+        transactions: checkpoint
+            .transactions
+            .iter()
+            .map(|tx| TransactionChange {
+                digest: tx.tx_digest,
+                tx_sequence_number: tx.tx_sequence_number,
+                sender: tx.sender_signed_data.transaction_data().sender(),
+                kind: match tx.transaction_kind {
+                    TransactionKind::SystemTransaction => "system",
+                    TransactionKind::ProgrammableTransaction => "programmable",
+                },
+                success: tx.effects.status().is_ok(),
+            })
+            .collect(),
+    };
+
+    let objects = CheckpointObjectChanges {
+        checkpoint: sequence_number,
+        changed: checkpoint
+            .object_changes
+            .changed_objects
+            .iter()
+            .map(|o| ObjectChange {
+                object_id: o.object_id,
+                version: o.object_version,
+                digest: o.object_digest,
+                owner_kind: match o.owner_type {
+                    OwnerType::Immutable => "immutable",
+                    OwnerType::Address => "address",
+                    OwnerType::Object => "object",
+                    OwnerType::Shared => "shared",
+                },
+                owner: o.owner_id,
+                object_type: o.object.type_().map(|t| t.to_string()),
+                coin_balance: o.coin_balance,
+            })
+            .collect(),
+        deleted: checkpoint
+            .object_changes
+            .deleted_objects
+            .iter()
+            .map(|(object_id, version, digest)| DeletedObject {
+                object_id: *object_id,
+                version: version.value(),
+                digest: *digest,
+            })
+            .collect(),
+    };
+
+    let events = CheckpointEvents {
+        checkpoint: sequence_number,
+        events: checkpoint
+            .events
+            .iter()
+            .map(|e| EventChange {
+                transaction_digest: e.transaction_digest,
+                event_sequence_number: e.event_sequence_number,
+                package: e.package,
+                module: e.module.clone(),
+                event_type: e.event_type.clone(),
+                senders: e.senders.clone(),
+                bcs: Base64::encode(&e.bcs),
+                schema_version: e.schema_version.map(Hex::encode),
+            })
+            .collect(),
+    };
+
+    Ok(vec![
+        message(
+            subject_prefix,
+            sequence_number,
+            0,
+            "transactions",
+            &transactions,
+        )?,
+        message(subject_prefix, sequence_number, 1, "objects", &objects)?,
+        message(subject_prefix, sequence_number, 2, "events", &events)?,
+    ])
+}
+
+fn message(
+    subject_prefix: &str,
+    checkpoint: u64,
+    index: i16,
+    kind: &str,
+    contents: &impl Serialize,
+) -> Result<StoredChangeFeedMessage, IndexerError> {
+    let payload = serde_json::to_vec(contents).map_err(|e| {
+        IndexerError::SerdeError(format!(
+            "Failed to encode {kind} change feed message of checkpoint {checkpoint}: {e}"
+        ))
+    })?;
+    Ok(StoredChangeFeedMessage {
+        checkpoint_sequence_number: checkpoint as i64,
+        message_index: index,
+        subject: format!("{subject_prefix}.{kind}"),
+        payload,
+    })
+}
+
+impl ChangeFeed {
+    pub fn new(publisher: Box<dyn ChangeFeedPublisher>, metrics: IndexerMetrics) -> Self {
+        Self { publisher, metrics }
+    }
+
+    /// The change feed configured in `config`, if any, connected to its message bus.
+    pub async fn from_config(
+        config: &IndexerConfig,
+        metrics: IndexerMetrics,
+    ) -> Result<Option<Self>, IndexerError> {
+        let publisher: Box<dyn ChangeFeedPublisher> = if let Some(url) = &config.cdc_nats_url {
+            info!("Publishing change feed to NATS at {url}");
+            Box::new(
+                NatsPublisher::connect(
+                    url,
+                    config.cdc_nats_credentials.clone(),
+                    config.cdc_nats_tls_ca.clone(),
+                )
+                .await?,
+            )
+        } else if let Some(brokers) = &config.cdc_kafka_brokers {
+            info!("Publishing change feed to Kafka at {brokers}");
+            Box::new(KafkaPublisher::new(brokers, &config.cdc_kafka_properties)?)
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(Self::new(publisher, metrics)))
+    }
+
+    /// Publishes the messages of committed checkpoints from the outbox, for as long as
+    /// checkpoints are being committed. `commit_watcher` is notified of every commit.
+    pub async fn run<S>(
+        mut self,
+        state: S,
+        mut commit_watcher: watch::Receiver<Option<CheckpointSequenceNumber>>,
+    ) where
+        S: IndexerStoreV2 + Sync + Send + 'static,
+    {
+        loop {
+            let messages = match state
+                .get_committed_change_feed_messages(OUTBOX_BATCH_SIZE)
+                .await
+            {
+                Ok(messages) => messages,
+                Err(e) => {
+                    warn!("Failed to read the change feed outbox, retrying: {e}");
+                    tokio::time::sleep(OUTBOX_POLL_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            if messages.is_empty() {
+                match tokio::time::timeout(OUTBOX_POLL_INTERVAL, commit_watcher.changed()).await {
+                    // The commit task has stopped, so nothing more will be committed.
+                    Ok(Err(_)) => return,
+                    Ok(Ok(())) | Err(_) => continue,
+                }
+            }
+
+            for message in messages {
+                self.publish(&message).await;
+                while let Err(e) = state
+                    .delete_change_feed_message(
+                        message.checkpoint_sequence_number,
+                        message.message_index,
+                    )
+                    .await
+                {
+                    warn!("Failed to delete published change feed message, retrying: {e}");
+                    tokio::time::sleep(INITIAL_RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+
+    /// Publishes `message`, retrying until the bus accepts it.
+    pub async fn publish(&mut self, message: &StoredChangeFeedMessage) {
+        let checkpoint = message.checkpoint_sequence_number;
+        let headers = [
+            (CHECKPOINT_HEADER, checkpoint.to_string()),
+            (MSG_ID_HEADER, format!("{}.{checkpoint}", message.subject)),
+        ];
+
+        let mut delay = INITIAL_RETRY_DELAY;
+        while let Err(e) = self
+            .publisher
+            .publish(&message.subject, &headers, &message.payload)
+            .await
+        {
+            self.metrics.total_cdc_publish_failures.inc();
+            warn!(
+                "Failed to publish checkpoint {checkpoint} to {}, retrying in {delay:?}: {e:#}",
+                message.subject
+            );
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RETRY_DELAY);
+        }
+
+        self.metrics.total_cdc_messages_published.inc();
+        self.metrics
+            .latest_cdc_checkpoint_sequence_number
+            .set(checkpoint);
+    }
+}
+
+/// Publishes to NATS JetStream. Publishes wait for the stream that captures their subject to
+/// acknowledge them, so streams must be set up to capture the change feed's subjects before the
+/// indexer starts.
+pub struct NatsPublisher {
+    jetstream: async_nats::jetstream::Context,
+}
+
+impl NatsPublisher {
+    /// Connects to the NATS server at `url`, which may carry a user and password or a token. TLS
+    /// is required if `url` starts with `tls://`, or if `tls_ca`, a PEM file of root certificates
+    /// to verify the server against, is set. `credentials` is a NATS credentials file, with a user
+    /// JWT and NKey seed.
+    pub async fn connect(
+        url: &str,
+        credentials: Option<PathBuf>,
+        tls_ca: Option<PathBuf>,
+    ) -> Result<Self, IndexerError> {
+        let mut options = match credentials {
+            Some(path) => async_nats::ConnectOptions::with_credentials_file(path)
+                .await
+                .map_err(|e| {
+                    IndexerError::GenericError(format!("Failed to read NATS credentials: {e}"))
+                })?,
+            None => async_nats::ConnectOptions::new(),
+        };
+        if let Some(tls_ca) = tls_ca {
+            options = options.require_tls(true).add_root_certificates(tls_ca);
+        }
+
+        let client = options
+            .name("sui-indexer")
+            .connect(url)
+            .await
+            .map_err(|e| {
+                IndexerError::GenericError(format!("Failed to connect to NATS at {url}: {e}"))
+            })?;
+        Ok(Self {
+            jetstream: async_nats::jetstream::new(client),
+        })
+    }
+}
+
+#[async_trait]
+impl ChangeFeedPublisher for NatsPublisher {
+    async fn publish(
+        &mut self,
+        subject: &str,
+        headers: &[(&str, String)],
+        payload: &[u8],
+    ) -> anyhow::Result<()> {
+        let mut header_map = async_nats::HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(*name, value.as_str());
+        }
+
+        let ack = tokio::time::timeout(PUBLISH_TIMEOUT, async {
+            self.jetstream
+                .publish_with_headers(subject.to_string(), header_map, payload.to_vec().into())
+                .await?
+                .await
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for JetStream to acknowledge"))?;
+        ack.context("JetStream did not accept the message")?;
+        Ok(())
+    }
+}
+
+/// Publishes to Kafka, with each subject as a topic. Messages are keyed by their topic, so that
+/// all of a topic's messages go to the same partition, in order.
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    /// `brokers` is a comma separated list of `host:port`s. `properties` are extra librdkafka
+    /// producer properties as `key=value`, e.g. `security.protocol=SASL_SSL` and the `sasl.*` and
+    /// `ssl.*` properties to authenticate with.
+    pub fn new(brokers: &str, properties: &[String]) -> Result<Self, IndexerError> {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers)
+            // Retries within the producer must not duplicate or reorder messages.
+            .set("enable.idempotence", "true")
+            .set(
+                "message.timeout.ms",
+                PUBLISH_TIMEOUT.as_millis().to_string(),
+            );
+        for property in properties {
+            let (key, value) = property.split_once('=').ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Kafka property {property} is not of the form key=value"
+                ))
+            })?;
+            config.set(key, value);
+        }
+
+        let producer = config.create().map_err(|e| {
+            IndexerError::GenericError(format!("Failed to create Kafka producer: {e}"))
+        })?;
+        Ok(Self { producer })
+    }
+}
+
+#[async_trait]
+impl ChangeFeedPublisher for KafkaPublisher {
+    async fn publish(
+        &mut self,
+        subject: &str,
+        headers: &[(&str, String)],
+        payload: &[u8],
+    ) -> anyhow::Result<()> {
+        let mut kafka_headers = OwnedHeaders::new();
+        for (name, value) in headers {
+            kafka_headers = kafka_headers.insert(Header {
+                key: name,
+                value: Some(value.as_str()),
+            });
+        }
+
+        let record = FutureRecord::to(subject)
+            .key(subject)
+            .payload(payload)
+            .headers(kafka_headers);
+        self.producer
+            .send(record, PUBLISH_TIMEOUT)
+            .await
+            .map_err(|(e, _)| anyhow!("Kafka did not accept the message: {e}"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use prometheus::Registry;
+
+    use super::*;
+
+    type Published = Arc<Mutex<Vec<(String, Vec<(String, String)>, Vec<u8>)>>>;
+
+    /// Records what it publishes, after failing the first `failures` publishes.
+    struct TestPublisher {
+        published: Published,
+        failures: usize,
+    }
+
+    #[async_trait]
+    impl ChangeFeedPublisher for TestPublisher {
+        async fn publish(
+            &mut self,
+            subject: &str,
+            headers: &[(&str, String)],
+            payload: &[u8],
+        ) -> anyhow::Result<()> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(anyhow!("bus unavailable"));
+            }
+            let headers = headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect();
+            self.published
+                .lock()
+                .unwrap()
+                .push((subject.to_string(), headers, payload.to_vec()));
+            Ok(())
+        }
+    }
+
+    fn change_feed(failures: usize) -> (ChangeFeed, Published, IndexerMetrics) {
+        let published = Published::default();
+        let metrics = IndexerMetrics::new(&Registry::default());
+        let publisher = TestPublisher {
+            published: published.clone(),
+            failures,
+        };
+        (
+            ChangeFeed::new(Box::new(publisher), metrics.clone()),
+            published,
+            metrics,
+        )
+    }
+
+    fn stored_message(checkpoint: i64, subject: &str) -> StoredChangeFeedMessage {
+        StoredChangeFeedMessage {
+            checkpoint_sequence_number: checkpoint,
+            message_index: 0,
+            subject: subject.to_string(),
+            payload: b"{}".to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_sets_headers() {
+        let (mut change_feed, published, metrics) = change_feed(0);
+        change_feed
+            .publish(&stored_message(42, "sui.transactions"))
+            .await;
+
+        let published = published.lock().unwrap();
+        assert_eq!(
+            *published,
+            vec![(
+                "sui.transactions".to_string(),
+                vec![
+                    (CHECKPOINT_HEADER.to_string(), "42".to_string()),
+                    (MSG_ID_HEADER.to_string(), "sui.transactions.42".to_string()),
+                ],
+                b"{}".to_vec(),
+            )]
+        );
+        assert_eq!(metrics.total_cdc_messages_published.get(), 1);
+        assert_eq!(metrics.latest_cdc_checkpoint_sequence_number.get(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_publish_retries_until_accepted() {
+        let (mut change_feed, published, metrics) = change_feed(2);
+        change_feed.publish(&stored_message(7, "sui.events")).await;
+
+        assert_eq!(published.lock().unwrap().len(), 1);
+        assert_eq!(metrics.total_cdc_publish_failures.get(), 2);
+        assert_eq!(metrics.total_cdc_messages_published.get(), 1);
+    }
+
+    #[test]
+    fn test_kafka_properties_must_be_key_value() {
+        assert!(matches!(
+            KafkaPublisher::new("localhost:9092", &["security.protocol".to_string()]),
+            Err(IndexerError::InvalidArgumentError(_))
+        ));
+    }
+}
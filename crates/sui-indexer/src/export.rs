@@ -0,0 +1,118 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Incremental exports of transactions to downstream warehouses. When the indexer runs with
+//! `--tx-insertion-order`, every transaction it commits is given a strictly increasing
+//! `insertion_order`, and an exporter keeps a watermark of the last `insertion_order` it has
+//! loaded downstream, in the same database.
+//!
+//! An exporter repeatedly reads the next batch after its watermark, loads it, and commits the
+//! batch to advance its watermark. If the exporter crashes between loading a batch and committing
+//! it, the same batch is read again after it restarts, so loads must be idempotent on
+//! `insertion_order` (e.g. a `MERGE` on it) for the export to be exactly-once.
+
+use diesel::{ExpressionMethods, JoinOnDsl, QueryDsl, RunQueryDsl};
+
+use crate::errors::{Context, IndexerError};
+use crate::models_v2::transactions::StoredTransaction;
+use crate::models_v2::tx_insertion_order::StoredExportWatermark;
+use crate::schema_v2::{export_watermarks, transactions, tx_insertion_order};
+use crate::store::diesel_macro::{read_only_blocking, transactional_blocking};
+use crate::PgConnectionPool;
+
+/// Transactions committed after an exporter's watermark, in the order they were committed.
+#[derive(Debug, Clone)]
+pub struct ExportBatch {
+    /// The exporter's watermark when the batch was read.
+    pub after: i64,
+    pub transactions: Vec<(i64, StoredTransaction)>,
+}
+
+impl ExportBatch {
+    /// The watermark after this batch has been loaded.
+    pub fn watermark(&self) -> i64 {
+        self.transactions
+            .last()
+            .map_or(self.after, |(insertion_order, _)| *insertion_order)
+    }
+}
+
+/// Follows `tx_insertion_order` on behalf of one named exporter. Exporters with different names
+/// keep independent watermarks.
+#[derive(Clone)]
+pub struct TxExporter {
+    pool: PgConnectionPool,
+    name: String,
+}
+
+impl TxExporter {
+    pub fn new(pool: PgConnectionPool, name: impl Into<String>) -> Self {
+        Self {
+            pool,
+            name: name.into(),
+        }
+    }
+
+    /// The last `insertion_order` this exporter committed, or zero if it has not committed any.
+    pub fn watermark(&self) -> Result<i64, IndexerError> {
+        transactional_blocking!(&self.pool, |conn| {
+            diesel::insert_into(export_watermarks::table)
+                .values(StoredExportWatermark {
+                    exporter: self.name.clone(),
+                    insertion_order: 0,
+                })
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            export_watermarks::table
+                .select(export_watermarks::insertion_order)
+                .filter(export_watermarks::exporter.eq(&self.name))
+                .first::<i64>(conn)
+        })
+        .context("Failed reading export watermark from PostgresDB")
+    }
+
+    /// Up to `limit` transactions after this exporter's watermark.
+    pub fn next_batch(&self, limit: i64) -> Result<ExportBatch, IndexerError> {
+        let after = self.watermark()?;
+        let transactions = read_only_blocking!(&self.pool, |conn| {
+            tx_insertion_order::table
+                .inner_join(transactions::table.on(
+                    transactions::tx_sequence_number.eq(tx_insertion_order::tx_sequence_number),
+                ))
+                .select((
+                    tx_insertion_order::insertion_order,
+                    transactions::all_columns,
+                ))
+                .filter(tx_insertion_order::insertion_order.gt(after))
+                .order(tx_insertion_order::insertion_order.asc())
+                .limit(limit)
+                .load::<(i64, StoredTransaction)>(conn)
+        })
+        .context("Failed reading transactions to export from PostgresDB")?;
+        Ok(ExportBatch {
+            after,
+            transactions,
+        })
+    }
+
+    /// Advance this exporter's watermark past `batch`, once it has been loaded downstream. Fails
+    /// without changing the watermark if it has moved since `batch` was read, e.g. because another
+    /// instance of the same exporter committed in the meantime.
+    pub fn commit(&self, batch: &ExportBatch) -> Result<(), IndexerError> {
+        let updated = transactional_blocking!(&self.pool, |conn| {
+            diesel::update(export_watermarks::table)
+                .filter(export_watermarks::exporter.eq(&self.name))
+                .filter(export_watermarks::insertion_order.eq(batch.after))
+                .set(export_watermarks::insertion_order.eq(batch.watermark()))
+                .execute(conn)
+        })
+        .context("Failed writing export watermark to PostgresDB")?;
+        if updated == 0 {
+            return Err(IndexerError::PostgresWriteError(format!(
+                "Export watermark of {} is no longer {}",
+                self.name, batch.after
+            )));
+        }
+        Ok(())
+    }
+}
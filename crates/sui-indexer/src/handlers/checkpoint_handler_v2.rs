@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use itertools::Itertools;
 use move_bytecode_utils::module_cache::GetModule;
 use mysten_metrics::{get_metrics, spawn_monitored_task};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 use sui_rest_api::CheckpointData;
 use sui_rest_api::CheckpointTransaction;
@@ -16,6 +16,7 @@ use sui_types::dynamic_field::DynamicFieldInfo;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents};
+use sui_types::object::MoveObject;
 use sui_types::object::Object;
 use sui_types::object::ObjectFormatOptions;
 use tokio::sync::watch;
@@ -24,17 +25,19 @@ use std::collections::hash_map::Entry;
 use std::collections::HashSet;
 use sui_json_rpc_types::SuiMoveValue;
 use sui_types::base_types::SequenceNumber;
-use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::effects::{InputSharedObjectKind, TransactionEffects, TransactionEffectsAPI};
 use sui_types::event::SystemEpochInfoEvent;
 use sui_types::object::Owner;
+use sui_types::parse_sui_struct_tag;
 use sui_types::transaction::TransactionDataAPI;
 use tap::tap::TapFallible;
 use tracing::{error, info, warn};
 
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
 
+use crate::cdc::{self, ChangeFeed};
 use crate::errors::IndexerError;
 use crate::framework::interface::Handler;
 use crate::metrics::IndexerMetrics;
@@ -43,7 +46,8 @@ use crate::store::module_resolver_v2::InterimModuleResolver;
 use crate::store::IndexerStoreV2;
 use crate::types_v2::IndexedEpochInfo;
 use crate::types_v2::{
-    IndexedCheckpoint, IndexedEvent, IndexedTransaction, IndexerResult, TransactionKind, TxIndex,
+    IndexedCheckpoint, IndexedEvent, IndexedEventSchema, IndexedTransaction, IndexerResult,
+    PackageActivity, TransactionKind, TxIndex, TxSharedObject,
 };
 use crate::types_v2::{IndexedObject, IndexedPackage};
 use crate::IndexerConfig;
@@ -89,11 +93,23 @@ where
         tx,
     ));
 
+    let change_feed = ChangeFeed::from_config(config, metrics.clone()).await?;
+    let change_feed_subject_prefix = change_feed
+        .as_ref()
+        .map(|_| config.cdc_subject_prefix.clone());
+    if let Some(change_feed) = change_feed {
+        spawn_monitored_task!(change_feed.run(state.clone(), rx.clone()));
+    }
+
     let checkpoint_handler = CheckpointHandler {
         state,
         metrics,
         indexed_checkpoint_sender,
         package_cache: IndexingPackageCache::start(rx),
+        event_schemas: Arc::new(Mutex::new(HashMap::new())),
+        index_shared_object_versions: config.index_shared_object_versions,
+        index_package_activity: config.index_package_activity,
+        change_feed_subject_prefix,
     };
 
     Ok(checkpoint_handler)
@@ -106,6 +122,13 @@ pub struct CheckpointHandler<S> {
     // Map from checkpoint sequence number and its starting transaction sequence number
     // This thing is small enough to be kept in memory
     package_cache: Arc<Mutex<IndexingPackageCache>>,
+    /// Schemas of the event types seen so far, so that the layout of each type is only resolved
+    /// the first time it is seen.
+    event_schemas: Arc<Mutex<HashMap<String, IndexedEventSchema>>>,
+    index_shared_object_versions: bool,
+    index_package_activity: bool,
+    /// Set if the change feed is enabled, see `cdc.rs`.
+    change_feed_subject_prefix: Option<String>,
 }
 
 #[async_trait]
@@ -117,9 +140,45 @@ where
         "checkpoint-handler"
     }
     async fn process_checkpoints(&mut self, checkpoints: &[CheckpointData]) -> anyhow::Result<()> {
+        self.index_and_send_checkpoints(checkpoints).await?;
+        Ok(())
+    }
+}
+
+impl<S> CheckpointHandler<S>
+where
+    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
+{
+    /// Re-index the checkpoints in the dead letter queue. The ones that now succeed are removed
+    /// from the queue by the commit handler once they are committed; checkpoints that fail again
+    /// stay in the queue with their latest error.
+    pub async fn replay_dead_letter_checkpoints(&mut self) -> anyhow::Result<()> {
+        let checkpoints = self.state.get_dead_letter_checkpoints().await?;
         if checkpoints.is_empty() {
+            info!("No dead letter checkpoints to replay");
             return Ok(());
         }
+        info!("Replaying {} dead letter checkpoints", checkpoints.len());
+
+        let failed = self.index_and_send_checkpoints(&checkpoints).await?;
+        info!(
+            replayed = checkpoints.len() - failed.len(),
+            failed = failed.len(),
+            "Finished replaying dead letter checkpoints"
+        );
+        Ok(())
+    }
+
+    /// Index `checkpoints` and send the results to the commit handler. Checkpoints that fail to
+    /// index are persisted to the dead letter queue instead of failing the whole batch, and
+    /// their sequence numbers are returned.
+    async fn index_and_send_checkpoints(
+        &mut self,
+        checkpoints: &[CheckpointData],
+    ) -> anyhow::Result<HashSet<u64>> {
+        if checkpoints.is_empty() {
+            return Ok(HashSet::new());
+        }
         // Safe to unwrap, checked emptiness above
         let first_checkpoint_seq = checkpoints
             .first()
@@ -166,23 +225,46 @@ where
                 metrics_clone.clone(),
                 packages,
                 module_resolver.clone(),
+                self.event_schemas.clone(),
+                self.index_shared_object_versions,
+                self.index_package_activity,
             )));
         }
-        let checkpoint_data_to_commit = futures::future::join_all(tasks)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
-            .tap_err(|e| {
-                error!(
-                    "Failed to join all checkpoint indexing tasks with error: {}",
-                    e.to_string()
-                );
-            })?
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
-            .tap_err(|e| {
-                error!("Failed to index checkpoints with error: {}", e.to_string());
-            })?;
+        let results = futures::future::join_all(tasks).await;
+        let mut checkpoint_data_to_commit = vec![];
+        let mut failed = HashSet::new();
+        for (checkpoint, result) in checkpoints.iter().zip(results) {
+            let error = match result {
+                Ok(Ok(mut checkpoint_data)) => {
+                    match self.encode_change_feed(&mut checkpoint_data) {
+                        Ok(()) => {
+                            checkpoint_data_to_commit.push(checkpoint_data);
+                            continue;
+                        }
+                        Err(e) => e.to_string(),
+                    }
+                }
+                Ok(Err(e)) => e.to_string(),
+                Err(e) => format!("Checkpoint indexing task failed: {}", e),
+            };
+            let checkpoint_seq = *checkpoint.checkpoint_summary.sequence_number();
+            error!(
+                checkpoint_seq,
+                "Failed to index checkpoint, moving it to the dead letter queue: {}", error
+            );
+            self.state
+                .persist_dead_letter_checkpoint(checkpoint, error)
+                .await
+                .tap_err(|e| {
+                    error!(
+                        checkpoint_seq,
+                        "Failed to persist dead letter checkpoint with error: {}",
+                        e.to_string()
+                    );
+                })?;
+            self.metrics.total_dead_letter_checkpoints.inc();
+            failed.insert(checkpoint_seq);
+        }
         let elapsed = indexing_timer.stop_and_record();
 
         info!(
@@ -207,14 +289,19 @@ where
                     )
                 });
         }
+        Ok(failed)
+    }
+
+    fn encode_change_feed(
+        &self,
+        checkpoint_data: &mut CheckpointDataToCommit,
+    ) -> Result<(), IndexerError> {
+        if let Some(subject_prefix) = &self.change_feed_subject_prefix {
+            checkpoint_data.change_feed = cdc::encode(subject_prefix, checkpoint_data)?;
+        }
         Ok(())
     }
-}
 
-impl<S> CheckpointHandler<S>
-where
-    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
-{
     async fn index_epoch(
         state: Arc<S>,
         data: &CheckpointData,
@@ -295,6 +382,9 @@ where
         metrics: Arc<IndexerMetrics>,
         packages: Vec<IndexedPackage>,
         module_resolver: Arc<impl GetModule>,
+        event_schemas: Arc<Mutex<HashMap<String, IndexedEventSchema>>>,
+        index_shared_object_versions: bool,
+        index_package_activity: bool,
     ) -> Result<CheckpointDataToCommit, IndexerError> {
         let checkpoint_seq = data.checkpoint_summary.sequence_number;
         info!(checkpoint_seq, "Indexing checkpoint data blob");
@@ -306,20 +396,32 @@ where
         let object_changes: TransactionObjectChangesToCommit =
             Self::index_objects(data.clone(), &metrics, &module_resolver);
 
-        let (checkpoint, db_transactions, db_events, db_indices) = {
+        let shared_objects = if index_shared_object_versions {
+            Self::index_shared_objects(&data)
+        } else {
+            vec![]
+        };
+
+        let (checkpoint, db_transactions, db_events, event_schemas, db_indices) = {
             let CheckpointData {
                 transactions,
                 checkpoint_summary,
                 checkpoint_contents,
             } = data;
 
-            let (db_transactions, db_events, db_indices) = Self::index_transactions(
+            let (db_transactions, mut db_events, db_indices) = Self::index_transactions(
                 transactions,
                 &checkpoint_summary,
                 &checkpoint_contents,
                 &metrics,
             )
             .await?;
+            let event_schemas = Self::index_event_schemas(
+                &mut db_events,
+                checkpoint_seq,
+                &module_resolver,
+                &event_schemas,
+            );
 
             let successful_tx_num: u64 = db_transactions.iter().map(|t| t.successful_tx_num).sum();
             (
@@ -330,18 +432,29 @@ where
                 ),
                 db_transactions,
                 db_events,
+                event_schemas,
                 db_indices,
             )
         };
 
+        let package_activity = if index_package_activity {
+            Self::index_package_activity(&db_transactions, &db_events)
+        } else {
+            vec![]
+        };
+
         Ok(CheckpointDataToCommit {
             checkpoint,
             transactions: db_transactions,
             events: db_events,
+            event_schemas,
             tx_indices: db_indices,
+            shared_objects,
+            package_activity,
             object_changes,
             packages,
             epoch,
+            change_feed: vec![],
         })
     }
 
@@ -493,6 +606,149 @@ where
         Ok((db_transactions, db_events, db_indices))
     }
 
+    /// Tags the events in the checkpoint with the schema version of the layout of their type.
+    /// Layouts are only resolved for types that are not in `cache` yet, and are then added to it.
+    /// The schemas of all types in the checkpoint are returned to be registered, and
+    /// re-registering one that has already been seen is a no-op, so that a schema is still
+    /// registered if the checkpoint it was first seen in fails to commit. Events whose layout can't
+    /// be resolved are indexed without a schema version rather than failing the checkpoint.
+    fn index_event_schemas(
+        events: &mut [IndexedEvent],
+        checkpoint_seq: u64,
+        module_resolver: &impl GetModule,
+        cache: &Mutex<HashMap<String, IndexedEventSchema>>,
+    ) -> Vec<IndexedEventSchema> {
+        let mut schemas: HashMap<String, Option<IndexedEventSchema>> = HashMap::new();
+        for event in events.iter_mut() {
+            let schema = schemas
+                .entry(event.event_type.clone())
+                .or_insert_with_key(|event_type| {
+                    if let Some(schema) = cache.lock().unwrap().get(event_type) {
+                        return Some(IndexedEventSchema {
+                            checkpoint_sequence_number: checkpoint_seq,
+                            ..schema.clone()
+                        });
+                    }
+
+                    let layout = parse_sui_struct_tag(event_type)
+                        .map_err(|e| e.to_string())
+                        .and_then(|tag| {
+                            MoveObject::get_layout_from_struct_tag(
+                                tag,
+                                ObjectFormatOptions::default(),
+                                module_resolver,
+                            )
+                            .map_err(|e| e.to_string())
+                        });
+                    match layout {
+                        Ok(layout) => {
+                            let schema =
+                                IndexedEventSchema::new(event_type.clone(), layout, checkpoint_seq);
+                            cache
+                                .lock()
+                                .unwrap()
+                                .insert(event_type.clone(), schema.clone());
+                            Some(schema)
+                        }
+                        Err(e) => {
+                            warn!(
+                                checkpoint_seq,
+                                "Failed to resolve layout of event type {event_type}: {e}"
+                            );
+                            None
+                        }
+                    }
+                });
+            event.schema_version = schema.as_ref().map(|schema| schema.schema_version);
+        }
+        schemas.into_values().flatten().collect()
+    }
+
+    /// The versions of the shared objects each transaction in the checkpoint was sequenced
+    /// against. Consensus does not record the transactions it defers because of congestion in
+    /// checkpoints, so only the versions they were eventually executed at are indexed.
+    fn index_shared_objects(data: &CheckpointData) -> Vec<TxSharedObject> {
+        let checkpoint_seq = data.checkpoint_summary.sequence_number;
+        data.checkpoint_contents
+            .enumerate_transactions(&data.checkpoint_summary)
+            .zip(&data.transactions)
+            .flat_map(|((tx_sequence_number, execution_digest), tx)| {
+                tx.effects.input_shared_objects().into_iter().map(
+                    move |((object_id, version, _), kind)| TxSharedObject {
+                        tx_sequence_number,
+                        checkpoint_sequence_number: checkpoint_seq,
+                        transaction_digest: execution_digest.transaction,
+                        object_id,
+                        version,
+                        mutable: matches!(kind, InputSharedObjectKind::Mutate),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// The calls that each sender made to each package in the checkpoint, and the events that the
+    /// package emitted in the sender's transactions. A transaction that calls several packages
+    /// counts all of the gas it used towards each of them.
+    fn index_package_activity(
+        transactions: &[IndexedTransaction],
+        events: &[IndexedEvent],
+    ) -> Vec<PackageActivity> {
+        let mut activity: BTreeMap<(ObjectID, SuiAddress), PackageActivity> = BTreeMap::new();
+        let new_activity =
+            |package, sender, checkpoint_sequence_number, timestamp_ms| PackageActivity {
+                package,
+                sender,
+                checkpoint_sequence_number,
+                timestamp_ms,
+                calls: 0,
+                events: 0,
+                gas_used: 0,
+            };
+
+        for tx in transactions {
+            let data = tx.sender_signed_data.transaction_data();
+            let sender = data.sender();
+            let gas_used = tx.effects.gas_cost_summary().gas_used();
+
+            let mut calls: BTreeMap<ObjectID, u64> = BTreeMap::new();
+            for (package, _module, _function) in data.move_calls() {
+                *calls.entry(*package).or_default() += 1;
+            }
+
+            for (package, calls) in calls {
+                let entry = activity.entry((package, sender)).or_insert_with(|| {
+                    new_activity(
+                        package,
+                        sender,
+                        tx.checkpoint_sequence_number,
+                        tx.timestamp_ms,
+                    )
+                });
+                entry.calls += calls;
+                entry.gas_used += gas_used;
+            }
+        }
+
+        for event in events {
+            // Every event in a transaction has the transaction's sender.
+            let Some(sender) = event.senders.first().copied() else {
+                continue;
+            };
+            let entry = activity.entry((event.package, sender)).or_insert_with(|| {
+                new_activity(
+                    event.package,
+                    sender,
+                    event.checkpoint_sequence_number,
+                    event.timestamp_ms,
+                )
+            });
+            entry.events += 1;
+        }
+
+        activity.into_values().collect()
+    }
+
     fn index_objects(
         data: CheckpointData,
         metrics: &IndexerMetrics,
@@ -573,18 +829,22 @@ where
             .iter()
             .flat_map(|data| {
                 let checkpoint_sequence_number = data.checkpoint_summary.sequence_number;
-                data.output_objects()
+                data.transactions
                     .iter()
-                    .filter_map(|o| {
-                        if let sui_types::object::Data::Package(p) = &o.data {
-                            Some(IndexedPackage {
-                                package_id: o.id(),
-                                move_package: p.clone(),
-                                checkpoint_sequence_number,
-                            })
-                        } else {
-                            None
-                        }
+                    .flat_map(|tx| {
+                        let publisher = tx.transaction.transaction_data().sender();
+                        tx.output_objects.iter().filter_map(move |o| {
+                            if let sui_types::object::Data::Package(p) = &o.data {
+                                Some(IndexedPackage {
+                                    package_id: o.id(),
+                                    move_package: p.clone(),
+                                    checkpoint_sequence_number,
+                                    publisher,
+                                })
+                            } else {
+                                None
+                            }
+                        })
                     })
                     .collect::<Vec<_>>()
             })
@@ -699,3 +959,197 @@ fn try_create_dynamic_field_info(
         },
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use move_binary_format::CompiledModule;
+    use move_core_types::language_storage::ModuleId;
+    use shared_crypto::intent::Intent;
+    use std::cell::Cell;
+    use std::path::PathBuf;
+    use sui_move_build::BuildConfig;
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::digests::TransactionDigest;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::transaction::SenderSignedData;
+
+    use super::*;
+    use crate::store::PgIndexerStoreV2;
+
+    const CHECKPOINT: u64 = 7;
+    const TIMESTAMP_MS: u64 = 1_000;
+
+    fn call(sender: SuiAddress, package: ObjectID, gas_used: u64) -> IndexedTransaction {
+        let data = TestTransactionBuilder::new(sender, random_object_ref(), 1)
+            .move_call(package, "module", "function", vec![])
+            .build();
+        let mut effects = TransactionEffects::default();
+        *effects.gas_cost_summary_mut_for_testing() = GasCostSummary::new(gas_used, 0, 0, 0);
+        IndexedTransaction {
+            tx_sequence_number: 0,
+            tx_digest: TransactionDigest::random(),
+            sender_signed_data: SenderSignedData::new(data, Intent::sui_transaction(), vec![]),
+            effects,
+            checkpoint_sequence_number: CHECKPOINT,
+            timestamp_ms: TIMESTAMP_MS,
+            object_changes: vec![],
+            balance_change: vec![],
+            events: vec![],
+            transaction_kind: TransactionKind::ProgrammableTransaction,
+            successful_tx_num: 1,
+        }
+    }
+
+    /// Resolves modules of the Sui framework, counting how many are looked up.
+    struct FrameworkResolver {
+        modules: HashMap<ModuleId, CompiledModule>,
+        lookups: Cell<usize>,
+    }
+
+    impl FrameworkResolver {
+        fn new() -> Self {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../sui-framework/packages/sui-framework");
+            let package = BuildConfig::new_for_testing().build(path).unwrap();
+            Self {
+                modules: package
+                    .get_modules()
+                    .map(|module| (module.self_id(), module.clone()))
+                    .collect(),
+                lookups: Cell::new(0),
+            }
+        }
+    }
+
+    impl GetModule for FrameworkResolver {
+        type Error = anyhow::Error;
+        type Item = CompiledModule;
+
+        fn get_module_by_id(&self, id: &ModuleId) -> anyhow::Result<Option<CompiledModule>> {
+            self.lookups.set(self.lookups.get() + 1);
+            Ok(self.modules.get(id).cloned())
+        }
+    }
+
+    fn event_of_type(event_type: &str) -> IndexedEvent {
+        IndexedEvent {
+            event_type: event_type.to_string(),
+            ..event(SuiAddress::ZERO, ObjectID::ZERO)
+        }
+    }
+
+    fn event(sender: SuiAddress, package: ObjectID) -> IndexedEvent {
+        IndexedEvent {
+            tx_sequence_number: 0,
+            event_sequence_number: 0,
+            checkpoint_sequence_number: CHECKPOINT,
+            transaction_digest: TransactionDigest::random(),
+            senders: vec![sender],
+            package,
+            module: "module".to_string(),
+            event_type: "0x2::module::Event".to_string(),
+            bcs: vec![],
+            timestamp_ms: TIMESTAMP_MS,
+            schema_version: None,
+        }
+    }
+
+    #[test]
+    fn test_index_package_activity() {
+        let (alice, bob) = (
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        );
+        let (p, q) = (ObjectID::random(), ObjectID::random());
+
+        let transactions = vec![call(alice, p, 10), call(alice, p, 20), call(bob, p, 5)];
+        let events = vec![event(alice, p), event(bob, p), event(bob, p), event(bob, q)];
+        let activity =
+            CheckpointHandler::<PgIndexerStoreV2>::index_package_activity(&transactions, &events);
+
+        let summary: BTreeMap<_, _> = activity
+            .iter()
+            .map(|a| {
+                assert_eq!(a.checkpoint_sequence_number, CHECKPOINT);
+                assert_eq!(a.timestamp_ms, TIMESTAMP_MS);
+                ((a.package, a.sender), (a.calls, a.events, a.gas_used))
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            BTreeMap::from([
+                ((p, alice), (2, 1, 30)),
+                ((p, bob), (1, 2, 5)),
+                // Events from packages that the sender didn't call are still counted.
+                ((q, bob), (0, 1, 0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_index_event_schemas() {
+        let resolver = FrameworkResolver::new();
+        let cache = Mutex::new(HashMap::new());
+        let id = "0x2::object::ID";
+        let uid = "0x2::object::UID";
+        let missing = "0x2::object::Missing";
+
+        let mut events = vec![event_of_type(id), event_of_type(uid), event_of_type(id)];
+        let schemas = CheckpointHandler::<PgIndexerStoreV2>::index_event_schemas(
+            &mut events,
+            CHECKPOINT,
+            &resolver,
+            &cache,
+        );
+        let lookups = resolver.lookups.get();
+        assert!(lookups > 0);
+
+        // Each type is registered once, and its events are tagged with its schema.
+        let versions: HashMap<_, _> = schemas
+            .iter()
+            .map(|s| (s.event_type.as_str(), s.schema_version))
+            .collect();
+        assert_eq!(versions.len(), 2);
+        assert_ne!(versions[id], versions[uid]);
+        for event in &events {
+            assert_eq!(
+                event.schema_version,
+                Some(versions[event.event_type.as_str()])
+            );
+        }
+
+        // Types that were seen before are not resolved again, but are registered again, with the
+        // checkpoint they are seen in now.
+        let mut events = vec![event_of_type(uid)];
+        let schemas = CheckpointHandler::<PgIndexerStoreV2>::index_event_schemas(
+            &mut events,
+            CHECKPOINT + 1,
+            &resolver,
+            &cache,
+        );
+        assert_eq!(resolver.lookups.get(), lookups);
+        assert_eq!(schemas.len(), 1);
+        assert_eq!(schemas[0].event_type, uid);
+        assert_eq!(schemas[0].schema_version, versions[uid]);
+        assert_eq!(schemas[0].checkpoint_sequence_number, CHECKPOINT + 1);
+        assert_eq!(events[0].schema_version, Some(versions[uid]));
+
+        // Types that fail to resolve are left untagged, and are tried again the next time.
+        for checkpoint in [CHECKPOINT + 2, CHECKPOINT + 3] {
+            let lookups = resolver.lookups.get();
+            let mut events = vec![event_of_type(missing)];
+            let schemas = CheckpointHandler::<PgIndexerStoreV2>::index_event_schemas(
+                &mut events,
+                checkpoint,
+                &resolver,
+                &cache,
+            );
+            assert!(resolver.lookups.get() > lookups);
+            assert!(schemas.is_empty());
+            assert_eq!(events[0].schema_version, None);
+        }
+        assert_eq!(cache.lock().unwrap().len(), 2);
+    }
+}
@@ -1,11 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::{Encoding, Hex};
 use tokio::sync::watch;
 use tracing::instrument;
 
 use tap::tap::TapFallible;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
@@ -15,7 +16,7 @@ use crate::store::IndexerStoreV2;
 use crate::types_v2::IndexerResult;
 use crate::IndexerConfig;
 
-use super::CheckpointDataToCommit;
+use super::{CheckpointDataToCommit, TransactionObjectChangesToCommit};
 
 pub async fn start_tx_checkpoint_commit_task<S>(
     state: S,
@@ -35,6 +36,12 @@ pub async fn start_tx_checkpoint_commit_task<S>(
         .unwrap();
     info!("Using checkpoint commit batch size {checkpoint_commit_batch_size}");
 
+    // Checkpoints up to this one were committed already, by this run or a previous one.
+    let mut last_committed_checkpoint = state
+        .get_latest_tx_checkpoint_sequence_number()
+        .await
+        .expect("Failed to get latest tx checkpoint sequence number from DB");
+
     let mut stream = mysten_metrics::metered_channel::ReceiverStream::new(tx_indexing_receiver)
         .ready_chunks(checkpoint_commit_batch_size);
 
@@ -52,8 +59,118 @@ pub async fn start_tx_checkpoint_commit_task<S>(
             );
             continue;
         }
-        commit_checkpoints(&state, indexed_checkpoint_batch, &metrics, &commit_notifier).await;
+
+        let last_checkpoint_seq = indexed_checkpoint_batch
+            .last()
+            .unwrap()
+            .checkpoint
+            .sequence_number;
+        // Replayed dead letter checkpoints are below the watermark, don't move it back.
+        let watermark = last_committed_checkpoint
+            .map_or(last_checkpoint_seq, |last| last.max(last_checkpoint_seq));
+        let (indexed_checkpoint_batch, content_hashes) = verify_reingested_checkpoints(
+            &state,
+            indexed_checkpoint_batch,
+            last_committed_checkpoint,
+            &metrics,
+        )
+        .await;
+        if indexed_checkpoint_batch.is_empty() {
+            commit_notifier
+                .send(Some(watermark))
+                .expect("Commit watcher should not be closed");
+            continue;
+        }
+
+        commit_checkpoints(
+            &state,
+            indexed_checkpoint_batch,
+            content_hashes,
+            watermark,
+            &metrics,
+            &commit_notifier,
+        )
+        .await;
+        last_committed_checkpoint = Some(watermark);
+    }
+}
+
+/// Checks the checkpoints in `indexed_checkpoint_batch` that were committed already, which only
+/// happens when re-ingesting, against the content hashes stored for them instead of committing
+/// them again, and reports the ones that diverge. Checkpoints below the watermark that were never
+/// committed because they are in the dead letter queue are committed like new ones, except for
+/// their object changes. Returns the checkpoints to commit along with their content hashes.
+async fn verify_reingested_checkpoints<S>(
+    state: &S,
+    indexed_checkpoint_batch: Vec<CheckpointDataToCommit>,
+    last_committed_checkpoint: Option<CheckpointSequenceNumber>,
+    metrics: &IndexerMetrics,
+) -> (Vec<CheckpointDataToCommit>, Vec<(u64, [u8; 32])>)
+where
+    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
+{
+    let mut checkpoints_to_commit = vec![];
+    let mut content_hashes = vec![];
+    let mut reingested = vec![];
+    for indexed_checkpoint in indexed_checkpoint_batch {
+        let checkpoint_seq = indexed_checkpoint.checkpoint.sequence_number;
+        let content_hash = indexed_checkpoint.content_hash();
+        if matches!(last_committed_checkpoint, Some(last) if checkpoint_seq <= last) {
+            reingested.push((checkpoint_seq, content_hash, indexed_checkpoint));
+        } else {
+            checkpoints_to_commit.push(indexed_checkpoint);
+            content_hashes.push((checkpoint_seq, content_hash));
+        }
     }
+
+    let (Some((first, ..)), Some((last, ..))) = (reingested.first(), reingested.last()) else {
+        return (checkpoints_to_commit, content_hashes);
+    };
+    let (first, last) = (*first, *last);
+    let stored_hashes = state
+        .get_checkpoint_content_hashes(first, last)
+        .await
+        .expect("Reading checkpoint content hashes from DB should not fail.");
+    let dead_letters = state
+        .get_dead_letter_sequence_numbers(first, last)
+        .await
+        .expect("Reading dead letter checkpoints from DB should not fail.");
+
+    for (checkpoint_seq, content_hash, mut indexed_checkpoint) in reingested {
+        match stored_hashes.get(&checkpoint_seq) {
+            Some(stored_hash) if stored_hash[..] == content_hash[..] => {
+                metrics.total_reingested_checkpoints_verified.inc();
+            }
+            Some(stored_hash) => {
+                error!(
+                    checkpoint_seq,
+                    stored_hash = %Hex::encode(stored_hash),
+                    content_hash = %Hex::encode(content_hash),
+                    "Re-ingested checkpoint diverges from the rows committed for it"
+                );
+                metrics.total_reingested_checkpoints_diverged.inc();
+            }
+            None if dead_letters.contains(&checkpoint_seq) => {
+                // The objects table only holds the latest version of each object, as written by
+                // the later checkpoints that were committed already. Replaying this checkpoint's
+                // changes could bring back objects they deleted or wrapped, or delete objects
+                // they unwrapped.
+                indexed_checkpoint.object_changes = TransactionObjectChangesToCommit {
+                    changed_objects: vec![],
+                    deleted_objects: vec![],
+                };
+                checkpoints_to_commit.push(indexed_checkpoint);
+                content_hashes.push((checkpoint_seq, content_hash));
+            }
+            None => {
+                warn!(
+                    checkpoint_seq,
+                    "Re-ingested checkpoint was committed without a content hash, it can't be verified"
+                );
+            }
+        }
+    }
+    (checkpoints_to_commit, content_hashes)
 }
 
 // Unwrap: Caller needs to make sure indexed_checkpoint_batch is not empty
@@ -64,6 +181,8 @@ pub async fn start_tx_checkpoint_commit_task<S>(
 async fn commit_checkpoints<S>(
     state: &S,
     indexed_checkpoint_batch: Vec<CheckpointDataToCommit>,
+    content_hashes: Vec<(u64, [u8; 32])>,
+    watermark: CheckpointSequenceNumber,
     metrics: &IndexerMetrics,
     commit_notifier: &watch::Sender<Option<CheckpointSequenceNumber>>,
 ) where
@@ -72,27 +191,39 @@ async fn commit_checkpoints<S>(
     let mut checkpoint_batch = vec![];
     let mut tx_batch = vec![];
     let mut events_batch = vec![];
+    let mut event_schemas_batch = vec![];
     let mut tx_indices_batch = vec![];
+    let mut shared_objects_batch = vec![];
+    let mut package_activity_batch = vec![];
     let mut object_changes_batch = vec![];
     let mut packages_batch = vec![];
     let mut epochs_batch = vec![];
+    let mut change_feed_batch = vec![];
 
     for indexed_checkpoint in indexed_checkpoint_batch {
         let CheckpointDataToCommit {
             checkpoint,
             transactions,
             events,
+            event_schemas,
             tx_indices,
+            shared_objects,
+            package_activity,
             object_changes,
             packages,
             epoch,
+            change_feed,
         } = indexed_checkpoint;
         checkpoint_batch.push(checkpoint);
         tx_batch.push(transactions);
         events_batch.push(events);
+        event_schemas_batch.push(event_schemas);
         tx_indices_batch.push(tx_indices);
+        shared_objects_batch.push(shared_objects);
+        package_activity_batch.push(package_activity);
         object_changes_batch.push(object_changes);
         packages_batch.push(packages);
+        change_feed_batch.extend(change_feed);
         if let Some(epoch) = epoch {
             epochs_batch.push(epoch);
         }
@@ -104,21 +235,40 @@ async fn commit_checkpoints<S>(
     let guard = metrics.checkpoint_db_commit_latency.start_timer();
     let tx_batch = tx_batch.into_iter().flatten().collect::<Vec<_>>();
     let tx_indices_batch = tx_indices_batch.into_iter().flatten().collect::<Vec<_>>();
+    let shared_objects_batch = shared_objects_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let package_activity_batch = package_activity_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
     let events_batch = events_batch.into_iter().flatten().collect::<Vec<_>>();
+    let event_schemas_batch = event_schemas_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
     let packages_batch = packages_batch.into_iter().flatten().collect::<Vec<_>>();
     let checkpoint_num = checkpoint_batch.len();
     let tx_count = tx_batch.len();
     let epochs_count = epochs_batch.len();
 
+    // Dead letter checkpoints are cleared along with the checkpoints, once committed.
+    let content_hashes_to_clear = content_hashes.clone();
     {
         let _step_1_guard = metrics.checkpoint_db_commit_latency_step_1.start_timer();
         futures::future::join_all(vec![
             state.persist_transactions(tx_batch),
             state.persist_tx_indices(tx_indices_batch),
+            state.persist_tx_shared_objects(shared_objects_batch),
+            state.persist_package_activity(package_activity_batch),
             state.persist_events(events_batch),
+            state.persist_event_schemas(event_schemas_batch),
             state.persist_packages(packages_batch),
             state.persist_objects(object_changes_batch),
             state.persist_epoch(epochs_batch),
+            state.persist_checkpoint_content_hashes(content_hashes),
+            state.persist_change_feed_messages(change_feed_batch),
         ])
         .await
         .into_iter()
@@ -133,7 +283,7 @@ async fn commit_checkpoints<S>(
     }
 
     state
-        .persist_checkpoints(checkpoint_batch)
+        .persist_checkpoints(checkpoint_batch, content_hashes_to_clear)
         .await
         .tap_err(|e| {
             error!(
@@ -145,12 +295,12 @@ async fn commit_checkpoints<S>(
     let elapsed = guard.stop_and_record();
 
     commit_notifier
-        .send(Some(last_checkpoint_seq))
+        .send(Some(watermark))
         .expect("Commit watcher should not be closed");
 
     metrics
         .latest_tx_checkpoint_sequence_number
-        .set(last_checkpoint_seq as i64);
+        .set(watermark as i64);
 
     metrics
         .total_tx_checkpoint_committed
@@ -6,11 +6,23 @@ pub mod checkpoint_handler_v2;
 pub mod committer;
 pub mod tx_processor;
 
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::Serialize;
 use sui_types::base_types::ObjectRef;
 
+use crate::models_v2::change_feed_outbox::StoredChangeFeedMessage;
+use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::models_v2::epoch::StoredEpochInfo;
+use crate::models_v2::event_schemas::StoredEventSchema;
+use crate::models_v2::events::StoredEvent;
+use crate::models_v2::objects::StoredObject;
+use crate::models_v2::packages::StoredPackage;
+use crate::models_v2::transactions::StoredTransaction;
+use crate::models_v2::tx_indices::StoredTxIndex;
+use crate::models_v2::tx_shared_objects::StoredTxSharedObject;
 use crate::types_v2::{
-    IndexedCheckpoint, IndexedEpochInfo, IndexedEvent, IndexedObject, IndexedPackage,
-    IndexedTransaction, TxIndex,
+    IndexedCheckpoint, IndexedEpochInfo, IndexedEvent, IndexedEventSchema, IndexedObject,
+    IndexedPackage, IndexedTransaction, PackageActivity, TxIndex, TxSharedObject,
 };
 
 #[derive(Debug)]
@@ -18,10 +30,74 @@ pub struct CheckpointDataToCommit {
     pub checkpoint: IndexedCheckpoint,
     pub transactions: Vec<IndexedTransaction>,
     pub events: Vec<IndexedEvent>,
+    /// Layouts of the types of `events`, see `IndexedEventSchema`.
+    pub event_schemas: Vec<IndexedEventSchema>,
     pub tx_indices: Vec<TxIndex>,
+    /// Empty unless `IndexerConfig::index_shared_object_versions` is set.
+    pub shared_objects: Vec<TxSharedObject>,
+    /// Empty unless `IndexerConfig::index_package_activity` is set. Not part of the content hash,
+    /// as it is derived from `transactions`, which are.
+    pub package_activity: Vec<PackageActivity>,
     pub object_changes: TransactionObjectChangesToCommit,
     pub packages: Vec<IndexedPackage>,
     pub epoch: Option<EpochToCommit>,
+    /// Empty unless the change feed is enabled, see `cdc.rs`. Not part of the content hash, as it
+    /// is derived from the rest.
+    pub change_feed: Vec<StoredChangeFeedMessage>,
+}
+
+impl CheckpointDataToCommit {
+    /// Digest of the rows this checkpoint is written to the DB as. Two runs of the indexer over
+    /// the same checkpoint agree on it unless they derive different rows from it.
+    pub fn content_hash(&self) -> [u8; 32] {
+        fn update(hasher: &mut Blake2b256, row: &impl Serialize) {
+            hasher.update(bcs::to_bytes(row).expect("Serializing a row should not fail."));
+        }
+
+        let mut hasher = Blake2b256::default();
+        update(&mut hasher, &StoredCheckpoint::from(&self.checkpoint));
+        for tx in &self.transactions {
+            update(&mut hasher, &StoredTransaction::from(tx));
+        }
+        for event in &self.events {
+            update(&mut hasher, &StoredEvent::from(event.clone()));
+        }
+        for schema in &self.event_schemas {
+            let schema = StoredEventSchema::from(schema.clone());
+            update(&mut hasher, &(schema.event_type, schema.schema_version));
+        }
+        for index in &self.tx_indices {
+            update(&mut hasher, &StoredTxIndex::from(index.clone()));
+        }
+        for shared_object in &self.shared_objects {
+            update(
+                &mut hasher,
+                &StoredTxSharedObject::from(shared_object.clone()),
+            );
+        }
+        for object in &self.object_changes.changed_objects {
+            update(&mut hasher, &StoredObject::from(object.clone()));
+        }
+        for object_ref in &self.object_changes.deleted_objects {
+            update(&mut hasher, object_ref);
+        }
+        for package in &self.packages {
+            update(&mut hasher, &StoredPackage::from(package.clone()));
+        }
+        if let Some(epoch) = &self.epoch {
+            if let Some(last_epoch) = &epoch.last_epoch {
+                update(
+                    &mut hasher,
+                    &StoredEpochInfo::from_epoch_end_info(last_epoch),
+                );
+            }
+            update(
+                &mut hasher,
+                &StoredEpochInfo::from_epoch_beginning_info(&epoch.new_epoch),
+            );
+        }
+        hasher.finalize().digest
+    }
 }
 
 #[derive(Debug)]
@@ -10,10 +10,20 @@ use crate::{
     errors::IndexerError,
     models_v2::objects::StoredObject,
     models_v2::{
-        checkpoints::StoredCheckpoint, epoch::StoredEpochInfo, packages::StoredPackage,
+        address_object_stats::StoredAddressObjectStats,
+        checkpoints::StoredCheckpoint,
+        epoch::StoredEpochInfo,
+        package_leaderboards::{
+            LeaderboardMetric, LeaderboardPeriod, PackageLeaderboard,
+            StoredPackageLeaderboardEntry, StoredPackageLeaderboardWindow,
+        },
+        packages::StoredPackage,
         transactions::StoredTransaction,
     },
-    schema_v2::{checkpoints, epochs, objects, packages, transactions},
+    schema_v2::{
+        address_object_stats, checkpoints, epochs, objects, package_leaderboard_windows,
+        package_leaderboards, packages, transactions,
+    },
     PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection,
 };
 use anyhow::{anyhow, Result};
@@ -23,7 +33,7 @@ use diesel::{
 };
 use sui_json_rpc_types::{CheckpointId, EpochInfo};
 use sui_types::{
-    base_types::{ObjectID, VersionNumber},
+    base_types::{ObjectID, SuiAddress, VersionNumber},
     committee::EpochId,
     digests::TransactionDigest,
     move_package::MovePackage,
@@ -68,6 +78,16 @@ impl IndexerReader {
         })
     }
 
+    /// Number of connections in the pool, and how many of them are idle.
+    pub fn pool_state(&self) -> diesel::r2d2::State {
+        self.pool.state()
+    }
+
+    /// Most connections the pool opens.
+    pub fn pool_max_size(&self) -> u32 {
+        self.pool.max_size()
+    }
+
     fn get_connection(&self) -> Result<PgPoolConnection, IndexerError> {
         self.pool.get().map_err(|e| {
             IndexerError::PgPoolConnectionError(format!(
@@ -334,6 +354,92 @@ impl IndexerReader {
         sui_json_rpc_types::Checkpoint::try_from(stored_checkpoint)
     }
 
+    /// The latest checkpoint whose timestamp is at or before `timestamp_ms`, or None if every
+    /// checkpoint is later than that.
+    pub fn get_checkpoint_at_or_before_timestamp(
+        &self,
+        timestamp_ms: i64,
+    ) -> Result<Option<StoredCheckpoint>, IndexerError> {
+        self.run_query(|conn| {
+            checkpoints::dsl::checkpoints
+                .filter(checkpoints::timestamp_ms.le(timestamp_ms))
+                .order_by((
+                    checkpoints::timestamp_ms.desc(),
+                    checkpoints::sequence_number.desc(),
+                ))
+                .first::<StoredCheckpoint>(conn)
+                .optional()
+        })
+    }
+
+    pub async fn get_checkpoint_at_or_before_timestamp_async(
+        &self,
+        timestamp_ms: i64,
+    ) -> Result<Option<StoredCheckpoint>, IndexerError> {
+        self.spawn_blocking(move |this| this.get_checkpoint_at_or_before_timestamp(timestamp_ms))
+            .await
+    }
+
+    /// Sequence numbers of the first and last checkpoints whose timestamps are in the range
+    /// `[start_ms, end_ms]`, either end of which can be left open. Returns None if there are no
+    /// checkpoints in the range. Checkpoint timestamps never decrease, so every checkpoint between
+    /// the two is also in the range.
+    pub fn get_checkpoint_range_for_timestamps(
+        &self,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+    ) -> Result<Option<(u64, u64)>, IndexerError> {
+        self.run_query(|conn| {
+            let first = match start_ms {
+                Some(start_ms) => checkpoints::dsl::checkpoints
+                    .select(checkpoints::sequence_number)
+                    .filter(checkpoints::timestamp_ms.ge(start_ms))
+                    .order_by((
+                        checkpoints::timestamp_ms.asc(),
+                        checkpoints::sequence_number.asc(),
+                    ))
+                    .first::<i64>(conn)
+                    .optional()?,
+                None => checkpoints::dsl::checkpoints
+                    .select(checkpoints::sequence_number)
+                    .order_by(checkpoints::sequence_number.asc())
+                    .first::<i64>(conn)
+                    .optional()?,
+            };
+
+            let last = match end_ms {
+                Some(end_ms) => checkpoints::dsl::checkpoints
+                    .select(checkpoints::sequence_number)
+                    .filter(checkpoints::timestamp_ms.le(end_ms))
+                    .order_by((
+                        checkpoints::timestamp_ms.desc(),
+                        checkpoints::sequence_number.desc(),
+                    ))
+                    .first::<i64>(conn)
+                    .optional()?,
+                None => checkpoints::dsl::checkpoints
+                    .select(checkpoints::sequence_number)
+                    .order_by(checkpoints::sequence_number.desc())
+                    .first::<i64>(conn)
+                    .optional()?,
+            };
+
+            Ok::<_, diesel::result::Error>(match (first, last) {
+                (Some(first), Some(last)) if first <= last => Some((first as u64, last as u64)),
+                _ => None,
+            })
+        })
+    }
+
+    pub async fn get_checkpoint_range_for_timestamps_async(
+        &self,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+    ) -> Result<Option<(u64, u64)>, IndexerError> {
+        self.spawn_blocking(move |this| this.get_checkpoint_range_for_timestamps(start_ms, end_ms))
+            .await
+    }
+
     pub fn get_checkpoints_from_db(
         &self,
         cursor: Option<u64>,
@@ -464,6 +570,68 @@ impl IndexerReader {
         self.spawn_blocking(move |this| this.get_transaction_events(digest))
             .await
     }
+
+    /// Number and total storage rebate of the objects owned by `address`. Returns None if the
+    /// address has never owned an object.
+    pub fn get_address_object_stats(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Option<StoredAddressObjectStats>, IndexerError> {
+        self.run_query(|conn| {
+            address_object_stats::dsl::address_object_stats
+                .filter(address_object_stats::dsl::owner_id.eq(address.to_vec()))
+                .first::<StoredAddressObjectStats>(conn)
+                .optional()
+        })
+    }
+
+    pub async fn get_address_object_stats_async(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Option<StoredAddressObjectStats>, IndexerError> {
+        self.spawn_blocking(move |this| this.get_address_object_stats(address))
+            .await
+    }
+
+    /// The `limit` packages with the most activity by `metric` over `period`, most active first,
+    /// as of the last time the leaderboards were refreshed, along with the activity that they
+    /// cover. Empty unless the indexer records package activity.
+    pub fn get_package_leaderboard(
+        &self,
+        period: LeaderboardPeriod,
+        metric: LeaderboardMetric,
+        limit: usize,
+    ) -> Result<PackageLeaderboard, IndexerError> {
+        use package_leaderboards::dsl;
+        self.run_query(|conn| {
+            let window = package_leaderboard_windows::table
+                .filter(package_leaderboard_windows::period.eq(period as i16))
+                .first::<StoredPackageLeaderboardWindow>(conn)
+                .optional()?;
+            let query = dsl::package_leaderboards
+                .filter(dsl::period.eq(period as i16))
+                .limit(limit as i64)
+                .into_boxed();
+            let query = match metric {
+                LeaderboardMetric::Calls => query.order_by((dsl::calls.desc(), dsl::package)),
+                LeaderboardMetric::Events => query.order_by((dsl::events.desc(), dsl::package)),
+                LeaderboardMetric::Senders => query.order_by((dsl::senders.desc(), dsl::package)),
+                LeaderboardMetric::GasUsed => query.order_by((dsl::gas_used.desc(), dsl::package)),
+            };
+            let entries = query.load::<StoredPackageLeaderboardEntry>(conn)?;
+            Ok::<_, diesel::result::Error>(PackageLeaderboard { window, entries })
+        })
+    }
+
+    pub async fn get_package_leaderboard_async(
+        &self,
+        period: LeaderboardPeriod,
+        metric: LeaderboardMetric,
+        limit: usize,
+    ) -> Result<PackageLeaderboard, IndexerError> {
+        self.spawn_blocking(move |this| this.get_package_leaderboard(period, metric, limit))
+            .await
+    }
 }
 
 #[derive(Clone, Default)]
@@ -60,6 +60,13 @@ impl IndexerV2 {
             .get_latest_tx_checkpoint_sequence_number()
             .await
             .expect("Failed to get latest tx checkpoint sequence number from DB");
+        let last_downloaded_checkpoint = match config.reingest_from_checkpoint {
+            Some(checkpoint) => {
+                info!("Re-ingesting checkpoints from {checkpoint}");
+                checkpoint.checked_sub(1)
+            }
+            None => last_seq_from_db,
+        };
         let (downloaded_checkpoint_data_sender, downloaded_checkpoint_data_receiver) =
             mysten_metrics::metered_channel::channel(
                 DOWNLOAD_QUEUE_SIZE,
@@ -73,12 +80,15 @@ impl IndexerV2 {
         let rest_client = sui_rest_api::Client::new(&rest_api_url);
         let fetcher = CheckpointFetcher::new(
             rest_client.clone(),
-            last_seq_from_db,
+            last_downloaded_checkpoint,
             downloaded_checkpoint_data_sender,
         );
         spawn_monitored_task!(fetcher.run());
 
-        let checkpoint_handler = new_handlers(store, metrics, config).await?;
+        let mut checkpoint_handler = new_handlers(store, metrics, config).await?;
+        if config.replay_dead_letter_checkpoints {
+            checkpoint_handler.replay_dead_letter_checkpoints().await?;
+        }
 
         crate::framework::runner::run(
             mysten_metrics::metered_channel::ReceiverStream::new(
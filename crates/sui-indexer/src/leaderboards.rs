@@ -0,0 +1,422 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Maintains `package_leaderboards`, the packages ranked by their activity over rolling periods,
+//! from the activity recorded per checkpoint in `package_activity`. Leaderboards are brought up to
+//! the latest checkpoint periodically, so they lag behind it by up to the refresh interval.
+//!
+//! Each refresh only reads the activity that entered or left a period since the previous one: it
+//! is added to or subtracted from the totals of the packages it is for. Distinct senders are
+//! counted as they enter and leave `package_leaderboard_senders`, which records when each sender
+//! last called each package. Activity that has fallen out of the longest period is pruned at the
+//! same time.
+
+use std::time::Duration;
+
+use diesel::{
+    sql_query,
+    sql_types::{BigInt, Nullable, SmallInt},
+    upsert::excluded,
+    ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, QueryableByName, RunQueryDsl,
+};
+use tracing::{error, info};
+
+use crate::{
+    errors::IndexerError,
+    get_pg_pool_connection,
+    models_v2::package_leaderboards::{LeaderboardPeriod, StoredPackageLeaderboardWindow},
+    schema_v2::package_leaderboard_windows,
+    store::diesel_macro::transactional_blocking,
+    PgConnectionPool,
+};
+
+#[derive(Clone)]
+pub struct PackageLeaderboards {
+    pool: PgConnectionPool,
+    interval: Duration,
+}
+
+#[derive(QueryableByName)]
+struct Watermark {
+    #[diesel(sql_type = Nullable<BigInt>)]
+    timestamp_ms: Option<i64>,
+}
+
+impl PackageLeaderboards {
+    pub fn new(pool: PgConnectionPool, interval: Duration) -> Self {
+        Self { pool, interval }
+    }
+
+    pub async fn run(self) {
+        loop {
+            let this = self.clone();
+            match tokio::task::spawn_blocking(move || this.refresh()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => error!("Failed to refresh package leaderboards: {e}"),
+                Err(e) => error!("Refreshing package leaderboards panicked: {e}"),
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Brings the leaderboards of every period up to the latest checkpoint, and prunes the
+    /// activity from before the start of the longest one.
+    pub fn refresh(&self) -> Result<(), IndexerError> {
+        let mut conn = get_pg_pool_connection(&self.pool)?;
+
+        // Activity is committed before the checkpoints it is from, so only activity up to the
+        // latest checkpoint is complete.
+        let Some(window_end_ms) =
+            sql_query("SELECT MAX(timestamp_ms) AS timestamp_ms FROM checkpoints")
+                .get_result::<Watermark>(&mut conn)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?
+                .timestamp_ms
+        else {
+            return Ok(());
+        };
+
+        // Each period is updated in one transaction, so readers never see it partially updated.
+        for period in LeaderboardPeriod::ALL {
+            transactional_blocking!(&self.pool, |conn| {
+                refresh_period(conn, period, window_end_ms)
+            })?;
+        }
+
+        // Activity is only pruned once every period has moved past it, because it is read again
+        // to subtract it from the periods it leaves.
+        let retention_ms = LeaderboardPeriod::ALL
+            .iter()
+            .map(LeaderboardPeriod::duration_ms)
+            .max()
+            .unwrap_or_default();
+        let pruned = sql_query("DELETE FROM package_activity WHERE timestamp_ms <= $1")
+            .bind::<BigInt, _>(window_end_ms - retention_ms)
+            .execute(&mut conn)
+            .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))?;
+        info!(pruned, "Pruned package_activity");
+
+        Ok(())
+    }
+}
+
+/// Moves the window of `period` so that it ends at `window_end_ms`, subtracting the activity that
+/// it no longer covers, and adding the activity that it now does.
+fn refresh_period(
+    conn: &mut PgConnection,
+    period: LeaderboardPeriod,
+    window_end_ms: i64,
+) -> Result<(), IndexerError> {
+    let window_start_ms = window_end_ms - period.duration_ms();
+    let previous = package_leaderboard_windows::table
+        .filter(package_leaderboard_windows::period.eq(period as i16))
+        .first::<StoredPackageLeaderboardWindow>(conn)
+        .optional()?;
+
+    // Activity in (entered_after, window_end_ms] is new to the window, and activity in
+    // (left_after, left_until] has left it.
+    let (entered_after, left) = match previous {
+        Some(previous) if previous.window_end_ms >= window_end_ms => return Ok(()),
+        Some(previous) => (
+            previous.window_end_ms.max(window_start_ms),
+            Some((
+                previous.window_start_ms,
+                previous.window_end_ms.min(window_start_ms),
+            )),
+        ),
+        None => (window_start_ms, None),
+    };
+
+    if let Some((left_after, left_until)) = left.filter(|(after, until)| after < until) {
+        sql_query(
+            "UPDATE package_leaderboards l \
+             SET calls = l.calls - a.calls, \
+                 events = l.events - a.events, \
+                 gas_used = l.gas_used - a.gas_used \
+             FROM ( \
+                 SELECT package, SUM(calls)::BIGINT AS calls, SUM(events)::BIGINT AS events, \
+                        SUM(gas_used)::BIGINT AS gas_used \
+                 FROM package_activity \
+                 WHERE timestamp_ms > $2 AND timestamp_ms <= $3 \
+                 GROUP BY package \
+             ) a \
+             WHERE l.period = $1 AND l.package = a.package",
+        )
+        .bind::<SmallInt, _>(period as i16)
+        .bind::<BigInt, _>(left_after)
+        .bind::<BigInt, _>(left_until)
+        .execute(conn)?;
+    }
+
+    sql_query(
+        "INSERT INTO package_leaderboards (period, package, calls, events, senders, gas_used) \
+         SELECT $1, package, SUM(calls)::BIGINT, SUM(events)::BIGINT, 0, SUM(gas_used)::BIGINT \
+         FROM package_activity \
+         WHERE timestamp_ms > $2 AND timestamp_ms <= $3 \
+         GROUP BY package \
+         ON CONFLICT (period, package) DO UPDATE \
+         SET calls = package_leaderboards.calls + EXCLUDED.calls, \
+             events = package_leaderboards.events + EXCLUDED.events, \
+             gas_used = package_leaderboards.gas_used + EXCLUDED.gas_used",
+    )
+    .bind::<SmallInt, _>(period as i16)
+    .bind::<BigInt, _>(entered_after)
+    .bind::<BigInt, _>(window_end_ms)
+    .execute(conn)?;
+
+    // Senders that called the package again stay in the window for longer...
+    sql_query(
+        "UPDATE package_leaderboard_senders s \
+         SET last_active_ms = a.last_active_ms \
+         FROM ( \
+             SELECT package, sender, MAX(timestamp_ms) AS last_active_ms \
+             FROM package_activity \
+             WHERE calls > 0 AND timestamp_ms > $2 AND timestamp_ms <= $3 \
+             GROUP BY package, sender \
+         ) a \
+         WHERE s.period = $1 AND s.package = a.package AND s.sender = a.sender",
+    )
+    .bind::<SmallInt, _>(period as i16)
+    .bind::<BigInt, _>(entered_after)
+    .bind::<BigInt, _>(window_end_ms)
+    .execute(conn)?;
+
+    // ...while senders that hadn't called it within the window are counted for the first time...
+    sql_query(
+        "WITH entered AS ( \
+             INSERT INTO package_leaderboard_senders (period, package, sender, last_active_ms) \
+             SELECT $1, package, sender, MAX(timestamp_ms) \
+             FROM package_activity \
+             WHERE calls > 0 AND timestamp_ms > $2 AND timestamp_ms <= $3 \
+             GROUP BY package, sender \
+             ON CONFLICT DO NOTHING \
+             RETURNING package \
+         ) \
+         UPDATE package_leaderboards l \
+         SET senders = l.senders + e.senders \
+         FROM (SELECT package, COUNT(*) AS senders FROM entered GROUP BY package) e \
+         WHERE l.period = $1 AND l.package = e.package",
+    )
+    .bind::<SmallInt, _>(period as i16)
+    .bind::<BigInt, _>(entered_after)
+    .bind::<BigInt, _>(window_end_ms)
+    .execute(conn)?;
+
+    // ...and senders that last called it before the start of the window are no longer counted.
+    sql_query(
+        "WITH left_window AS ( \
+             DELETE FROM package_leaderboard_senders \
+             WHERE period = $1 AND last_active_ms <= $2 \
+             RETURNING package \
+         ) \
+         UPDATE package_leaderboards l \
+         SET senders = l.senders - e.senders \
+         FROM (SELECT package, COUNT(*) AS senders FROM left_window GROUP BY package) e \
+         WHERE l.period = $1 AND l.package = e.package",
+    )
+    .bind::<SmallInt, _>(period as i16)
+    .bind::<BigInt, _>(window_start_ms)
+    .execute(conn)?;
+
+    let removed = sql_query(
+        "DELETE FROM package_leaderboards \
+         WHERE period = $1 AND calls = 0 AND events = 0 AND senders = 0",
+    )
+    .bind::<SmallInt, _>(period as i16)
+    .execute(conn)?;
+
+    diesel::insert_into(package_leaderboard_windows::table)
+        .values((
+            package_leaderboard_windows::period.eq(period as i16),
+            package_leaderboard_windows::window_start_ms.eq(window_start_ms),
+            package_leaderboard_windows::window_end_ms.eq(window_end_ms),
+        ))
+        .on_conflict(package_leaderboard_windows::period)
+        .do_update()
+        .set((
+            package_leaderboard_windows::window_start_ms
+                .eq(excluded(package_leaderboard_windows::window_start_ms)),
+            package_leaderboard_windows::window_end_ms
+                .eq(excluded(package_leaderboard_windows::window_end_ms)),
+        ))
+        .execute(conn)?;
+
+    info!(
+        ?period,
+        window_start_ms, window_end_ms, removed, "Refreshed package leaderboard"
+    );
+    Ok(())
+}
+
+#[cfg(all(test, feature = "pg_integration"))]
+mod tests {
+    use crate::models_v2::checkpoints::StoredCheckpoint;
+    use crate::models_v2::package_leaderboards::{
+        StoredPackageActivity, StoredPackageLeaderboardEntry,
+    };
+    use crate::pg_integration::test_pool;
+    use crate::schema_v2::{checkpoints, package_activity, package_leaderboards};
+
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    fn set_latest_checkpoint(conn: &mut PgConnection, sequence_number: i64, timestamp_ms: i64) {
+        diesel::insert_into(checkpoints::table)
+            .values(StoredCheckpoint {
+                sequence_number,
+                timestamp_ms,
+                ..Default::default()
+            })
+            .execute(conn)
+            .unwrap();
+    }
+
+    /// Records `calls` calls from `sender` to `package`, that emitted as many events and used
+    /// `10 * calls` gas.
+    fn record_calls(
+        conn: &mut PgConnection,
+        package: u8,
+        sender: u8,
+        checkpoint: i64,
+        timestamp_ms: i64,
+        calls: i64,
+    ) {
+        diesel::insert_into(package_activity::table)
+            .values(StoredPackageActivity {
+                package: vec![package],
+                sender: vec![sender],
+                checkpoint_sequence_number: checkpoint,
+                timestamp_ms,
+                calls,
+                events: calls,
+                gas_used: 10 * calls,
+            })
+            .execute(conn)
+            .unwrap();
+    }
+
+    /// (package, calls, events, senders, gas_used) of every package in `period`'s leaderboard.
+    fn leaderboard(
+        conn: &mut PgConnection,
+        period: LeaderboardPeriod,
+    ) -> Vec<(u8, i64, i64, i64, i64)> {
+        package_leaderboards::table
+            .filter(package_leaderboards::period.eq(period as i16))
+            .order_by(package_leaderboards::package)
+            .load::<StoredPackageLeaderboardEntry>(conn)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.package[0], e.calls, e.events, e.senders, e.gas_used))
+            .collect()
+    }
+
+    fn window(conn: &mut PgConnection, period: LeaderboardPeriod) -> (i64, i64) {
+        let window = package_leaderboard_windows::table
+            .filter(package_leaderboard_windows::period.eq(period as i16))
+            .first::<StoredPackageLeaderboardWindow>(conn)
+            .unwrap();
+        (window.window_start_ms, window.window_end_ms)
+    }
+
+    #[test]
+    fn test_refresh_without_checkpoints() {
+        let pool = test_pool();
+        let mut conn = get_pg_pool_connection(&pool).unwrap();
+        let leaderboards = PackageLeaderboards::new(pool.clone(), Duration::from_secs(1));
+
+        record_calls(&mut conn, 1, 1, 0, 0, 1);
+        leaderboards.refresh().unwrap();
+
+        assert!(leaderboard(&mut conn, LeaderboardPeriod::Day).is_empty());
+        assert!(leaderboard(&mut conn, LeaderboardPeriod::Week).is_empty());
+    }
+
+    #[test]
+    fn test_refresh_moves_window() {
+        let pool = test_pool();
+        let mut conn = get_pg_pool_connection(&pool).unwrap();
+        let leaderboards = PackageLeaderboards::new(pool.clone(), Duration::from_secs(1));
+
+        let t0 = 10 * DAY_MS;
+        record_calls(&mut conn, 1, 1, 0, t0, 1);
+        record_calls(&mut conn, 1, 2, 1, t0 + 1, 2);
+        record_calls(&mut conn, 2, 1, 1, t0 + 1, 3);
+        set_latest_checkpoint(&mut conn, 1, t0 + 1);
+        // Not yet part of a checkpoint.
+        record_calls(&mut conn, 2, 2, 2, t0 + 2, 4);
+
+        leaderboards.refresh().unwrap();
+        let day = vec![(1, 3, 3, 2, 30), (2, 3, 3, 1, 30)];
+        assert_eq!(leaderboard(&mut conn, LeaderboardPeriod::Day), day);
+        assert_eq!(leaderboard(&mut conn, LeaderboardPeriod::Week), day);
+        assert_eq!(
+            window(&mut conn, LeaderboardPeriod::Day),
+            (t0 + 1 - DAY_MS, t0 + 1)
+        );
+
+        // Nothing new to add.
+        leaderboards.refresh().unwrap();
+        assert_eq!(leaderboard(&mut conn, LeaderboardPeriod::Day), day);
+
+        // A day later, the first calls have left the day's window, but sender 1 called package
+        // 1 again, so is still counted.
+        set_latest_checkpoint(&mut conn, 2, t0 + 2);
+        record_calls(&mut conn, 1, 1, 3, t0 + DAY_MS, 5);
+        set_latest_checkpoint(&mut conn, 3, t0 + DAY_MS);
+
+        leaderboards.refresh().unwrap();
+        assert_eq!(
+            leaderboard(&mut conn, LeaderboardPeriod::Day),
+            vec![(1, 7, 7, 2, 70), (2, 7, 7, 2, 70)],
+        );
+        assert_eq!(
+            leaderboard(&mut conn, LeaderboardPeriod::Week),
+            vec![(1, 8, 8, 2, 80), (2, 7, 7, 2, 70)],
+        );
+
+        // Almost two days later, only sender 1's latest calls to package 1 are within the day's
+        // window, and package 2 has left it altogether.
+        set_latest_checkpoint(&mut conn, 4, t0 + 2 * DAY_MS - 1);
+
+        leaderboards.refresh().unwrap();
+        assert_eq!(
+            leaderboard(&mut conn, LeaderboardPeriod::Day),
+            vec![(1, 5, 5, 1, 50)],
+        );
+        assert_eq!(
+            leaderboard(&mut conn, LeaderboardPeriod::Week),
+            vec![(1, 8, 8, 2, 80), (2, 7, 7, 2, 70)],
+        );
+    }
+
+    #[test]
+    fn test_refresh_prunes_activity_outside_longest_period() {
+        let pool = test_pool();
+        let mut conn = get_pg_pool_connection(&pool).unwrap();
+        let leaderboards = PackageLeaderboards::new(pool.clone(), Duration::from_secs(1));
+
+        let t0 = 10 * DAY_MS;
+        record_calls(&mut conn, 1, 1, 0, t0, 1);
+        set_latest_checkpoint(&mut conn, 0, t0);
+        leaderboards.refresh().unwrap();
+        assert_eq!(
+            leaderboard(&mut conn, LeaderboardPeriod::Week),
+            vec![(1, 1, 1, 1, 10)],
+        );
+
+        // Long after the last refresh, the calls have left both windows.
+        record_calls(&mut conn, 2, 1, 1, t0 + 8 * DAY_MS, 1);
+        set_latest_checkpoint(&mut conn, 1, t0 + 8 * DAY_MS);
+        leaderboards.refresh().unwrap();
+
+        let expected = vec![(2, 1, 1, 1, 10)];
+        assert_eq!(leaderboard(&mut conn, LeaderboardPeriod::Day), expected);
+        assert_eq!(leaderboard(&mut conn, LeaderboardPeriod::Week), expected);
+
+        let remaining: i64 = package_activity::table
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::Parser;
+use std::time::Duration;
 use tracing::{error, info};
 
 use sui_indexer::errors::IndexerError;
 use sui_indexer::indexer_v2::IndexerV2;
-use sui_indexer::metrics::IndexerMetrics;
+use sui_indexer::leaderboards::PackageLeaderboards;
+use sui_indexer::maintenance::DbMaintenance;
+use sui_indexer::metrics::{IndexerDbMaintenanceMetrics, IndexerMetrics, IndexerPrunerMetrics};
+use sui_indexer::pruning::ObjectsHistoryPruner;
 use sui_indexer::start_prometheus_server;
 use sui_indexer::store::PgIndexerStore;
 use sui_indexer::store::PgIndexerStoreV2;
@@ -22,6 +26,15 @@ async fn main() -> Result<(), IndexerError> {
 
     let indexer_config = IndexerConfig::parse();
     info!("Parsed indexer config: {:#?}", indexer_config);
+    let prunes_objects_history = indexer_config
+        .objects_history_retention_checkpoints
+        .is_some();
+    if indexer_config.use_v2 && prunes_objects_history {
+        // The v2 schema does not keep a history of object versions to prune.
+        return Err(IndexerError::InvalidArgumentError(
+            "--objects-history-retention-checkpoints is not supported with --use-v2".to_string(),
+        ));
+    }
     let (_registry_service, registry) = start_prometheus_server(
         // NOTE: this parses the input host addr and port number for socket addr,
         // so unwrap() is safe here.
@@ -86,10 +99,37 @@ async fn main() -> Result<(), IndexerError> {
     }
     if indexer_config.use_v2 {
         info!("Use v2");
-        let store = PgIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
+        if let Some(interval) = indexer_config.db_maintenance_interval_secs {
+            let maintenance = DbMaintenance::new(
+                blocking_cp.clone(),
+                IndexerDbMaintenanceMetrics::new(&registry),
+                Duration::from_secs(interval),
+                indexer_config.analyze_modified_fraction,
+            );
+            tokio::spawn(maintenance.run());
+        }
+        if indexer_config.index_package_activity {
+            let leaderboards = PackageLeaderboards::new(
+                blocking_cp.clone(),
+                Duration::from_secs(indexer_config.package_leaderboards_refresh_interval_secs),
+            );
+            tokio::spawn(leaderboards.run());
+        }
+        let store = PgIndexerStoreV2::new(blocking_cp, indexer_metrics.clone())
+            .with_tx_insertion_order(indexer_config.tx_insertion_order)
+            .with_name_service_package(indexer_config.name_service_package_address);
         return IndexerV2::start(&indexer_config, &registry, store, indexer_metrics).await;
     }
 
+    if let Some(retention) = indexer_config.objects_history_retention_checkpoints {
+        let pruner = ObjectsHistoryPruner::new(
+            blocking_cp.clone(),
+            IndexerPrunerMetrics::new(&registry),
+            Duration::from_secs(indexer_config.objects_history_prune_interval_secs),
+            retention,
+        );
+        tokio::spawn(pruner.run());
+    }
     let store = PgIndexerStore::new(blocking_cp, indexer_metrics.clone());
     Indexer::start(&indexer_config, &registry, store, indexer_metrics, None).await
 }
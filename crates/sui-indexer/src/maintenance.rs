@@ -0,0 +1,164 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Watches the health of the tables that ingestion writes to the most. Heavy ingestion leaves dead
+//! rows behind faster than autovacuum can clean them up, and large backfills change the
+//! distribution of a table faster than autoanalyze notices, which can lead Postgres to pick bad
+//! query plans. This module exposes metrics on both, and analyzes a table itself once enough of
+//! its rows have changed since it was last analyzed.
+
+use std::time::Duration;
+
+use diesel::{
+    sql_query,
+    sql_types::{Array, BigInt, Double, Nullable, Text},
+    QueryableByName, RunQueryDsl,
+};
+use tracing::{error, info};
+
+use crate::{
+    errors::IndexerError, get_pg_pool_connection, metrics::IndexerDbMaintenanceMetrics,
+    PgConnectionPool,
+};
+
+/// Tables that see the most writes during ingestion.
+const HOT_TABLES: &[&str] = &["objects", "tx_indices"];
+
+/// Tables are not analyzed more than once per this many rows changed, however small they are.
+const MIN_ROWS_BEFORE_ANALYZE: i64 = 10_000;
+
+#[derive(Clone)]
+pub struct DbMaintenance {
+    pool: PgConnectionPool,
+    metrics: IndexerDbMaintenanceMetrics,
+    interval: Duration,
+    /// Fraction of a table's rows that must have changed since it was last analyzed to analyze it
+    /// again. Zero means never.
+    analyze_modified_fraction: f64,
+}
+
+#[derive(QueryableByName, Debug)]
+struct TableStats {
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    #[diesel(sql_type = BigInt)]
+    live_tuples: i64,
+    #[diesel(sql_type = BigInt)]
+    dead_tuples: i64,
+    #[diesel(sql_type = BigInt)]
+    modified_since_analyze: i64,
+    /// `None` if the table has never been vacuumed.
+    #[diesel(sql_type = Nullable<Double>)]
+    seconds_since_vacuum: Option<f64>,
+    #[diesel(sql_type = BigInt)]
+    table_bytes: i64,
+    #[diesel(sql_type = BigInt)]
+    index_bytes: i64,
+}
+
+impl DbMaintenance {
+    pub fn new(
+        pool: PgConnectionPool,
+        metrics: IndexerDbMaintenanceMetrics,
+        interval: Duration,
+        analyze_modified_fraction: f64,
+    ) -> Self {
+        Self {
+            pool,
+            metrics,
+            interval,
+            analyze_modified_fraction,
+        }
+    }
+
+    pub async fn run(self) {
+        loop {
+            let this = self.clone();
+            match tokio::task::spawn_blocking(move || this.check()).await {
+                Ok(Ok(())) => (),
+                Ok(Err(e)) => error!("Failed to check hot tables: {e}"),
+                Err(e) => error!("Hot table check panicked: {e}"),
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Updates the metrics of each hot table, and analyzes those that need it.
+    fn check(&self) -> Result<(), IndexerError> {
+        let mut conn = get_pg_pool_connection(&self.pool)?;
+        let stats: Vec<TableStats> = sql_query(
+            "SELECT relname::text AS table_name, \
+                    n_live_tup AS live_tuples, \
+                    n_dead_tup AS dead_tuples, \
+                    n_mod_since_analyze AS modified_since_analyze, \
+                    EXTRACT(EPOCH FROM now() - GREATEST(last_vacuum, last_autovacuum))::float8 \
+                        AS seconds_since_vacuum, \
+                    pg_table_size(relid) AS table_bytes, \
+                    pg_indexes_size(relid) AS index_bytes \
+             FROM pg_stat_user_tables \
+             WHERE relname = ANY($1)",
+        )
+        .bind::<Array<Text>, _>(HOT_TABLES)
+        .load(&mut conn)
+        .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?;
+
+        for table in stats {
+            self.report(&table);
+            if self.needs_analyze(&table) {
+                info!(
+                    table = table.table_name,
+                    modified = table.modified_since_analyze,
+                    "Analyzing table"
+                );
+                // Table names come from `HOT_TABLES`, so they are safe to interpolate.
+                sql_query(format!("ANALYZE {}", table.table_name))
+                    .execute(&mut conn)
+                    .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))?;
+                self.metrics
+                    .db_table_analyzes_triggered
+                    .with_label_values(&[&table.table_name])
+                    .inc();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn report(&self, table: &TableStats) {
+        let labels = &[table.table_name.as_str()];
+        let m = &self.metrics;
+        m.db_table_live_tuples
+            .with_label_values(labels)
+            .set(table.live_tuples);
+        m.db_table_dead_tuples
+            .with_label_values(labels)
+            .set(table.dead_tuples);
+        let total = table.live_tuples + table.dead_tuples;
+        if total > 0 {
+            m.db_table_dead_tuple_ratio
+                .with_label_values(labels)
+                .set(table.dead_tuples as f64 / total as f64);
+        }
+        m.db_table_rows_modified_since_analyze
+            .with_label_values(labels)
+            .set(table.modified_since_analyze);
+        if let Some(seconds) = table.seconds_since_vacuum {
+            m.db_table_seconds_since_vacuum
+                .with_label_values(labels)
+                .set(seconds as i64);
+        }
+        m.db_table_size_bytes
+            .with_label_values(labels)
+            .set(table.table_bytes);
+        m.db_table_index_size_bytes
+            .with_label_values(labels)
+            .set(table.index_bytes);
+    }
+
+    fn needs_analyze(&self, table: &TableStats) -> bool {
+        self.analyze_modified_fraction > 0.0
+            && table.modified_since_analyze >= MIN_ROWS_BEFORE_ANALYZE
+            && table.modified_since_analyze as f64
+                >= self.analyze_modified_fraction * table.live_tuples as f64
+    }
+}
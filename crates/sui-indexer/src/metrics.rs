@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry,
-    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
+    register_gauge_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, GaugeVec, Histogram,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 
 /// Prometheus metrics for sui-indexer.
@@ -28,6 +30,12 @@ pub struct IndexerMetrics {
     pub total_transaction_chunk_committed: IntCounter,
     pub total_object_change_chunk_committed: IntCounter,
     pub total_epoch_committed: IntCounter,
+    pub total_dead_letter_checkpoints: IntCounter,
+    pub total_reingested_checkpoints_verified: IntCounter,
+    pub total_reingested_checkpoints_diverged: IntCounter,
+    pub total_cdc_messages_published: IntCounter,
+    pub total_cdc_publish_failures: IntCounter,
+    pub latest_cdc_checkpoint_sequence_number: IntGauge,
     pub latest_fullnode_checkpoint_sequence_number: IntGauge,
     pub latest_tx_checkpoint_sequence_number: IntGauge,
     pub latest_indexer_object_checkpoint_sequence_number: IntGauge,
@@ -148,6 +156,42 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            total_dead_letter_checkpoints: register_int_counter_with_registry!(
+                "total_dead_letter_checkpoints",
+                "Total number of checkpoints that failed indexing and were dead-lettered",
+                registry,
+            )
+            .unwrap(),
+            total_reingested_checkpoints_verified: register_int_counter_with_registry!(
+                "total_reingested_checkpoints_verified",
+                "Total number of re-ingested checkpoints whose rows matched the ones in the DB",
+                registry,
+            )
+            .unwrap(),
+            total_reingested_checkpoints_diverged: register_int_counter_with_registry!(
+                "total_reingested_checkpoints_diverged",
+                "Total number of re-ingested checkpoints whose rows differed from the ones in the DB",
+                registry,
+            )
+            .unwrap(),
+            total_cdc_messages_published: register_int_counter_with_registry!(
+                "total_cdc_messages_published",
+                "Total number of change feed messages published",
+                registry,
+            )
+            .unwrap(),
+            total_cdc_publish_failures: register_int_counter_with_registry!(
+                "total_cdc_publish_failures",
+                "Total number of failed attempts to publish a change feed message",
+                registry,
+            )
+            .unwrap(),
+            latest_cdc_checkpoint_sequence_number: register_int_gauge_with_registry!(
+                "latest_cdc_checkpoint_sequence_number",
+                "Latest checkpoint sequence number published to the change feed",
+                registry,
+            )
+            .unwrap(),
             latest_fullnode_checkpoint_sequence_number: register_int_gauge_with_registry!(
                 "latest_fullnode_checkpoint_sequence_number",
                 "Latest checkpoint sequence number from the Full Node",
@@ -567,3 +611,113 @@ impl IndexerObjectProcessorMetrics {
         }
     }
 }
+
+/// Metrics on the health of the tables that ingestion writes to the most, see `maintenance.rs`.
+#[derive(Clone)]
+pub struct IndexerDbMaintenanceMetrics {
+    pub db_table_live_tuples: IntGaugeVec,
+    pub db_table_dead_tuples: IntGaugeVec,
+    pub db_table_dead_tuple_ratio: GaugeVec,
+    pub db_table_rows_modified_since_analyze: IntGaugeVec,
+    pub db_table_seconds_since_vacuum: IntGaugeVec,
+    pub db_table_size_bytes: IntGaugeVec,
+    pub db_table_index_size_bytes: IntGaugeVec,
+    pub db_table_analyzes_triggered: IntCounterVec,
+}
+
+impl IndexerDbMaintenanceMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            db_table_live_tuples: register_int_gauge_vec_with_registry!(
+                "db_table_live_tuples",
+                "Estimated number of live rows in a table",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_dead_tuples: register_int_gauge_vec_with_registry!(
+                "db_table_dead_tuples",
+                "Estimated number of dead rows in a table, waiting to be vacuumed",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_dead_tuple_ratio: register_gauge_vec_with_registry!(
+                "db_table_dead_tuple_ratio",
+                "Fraction of the rows in a table that are dead",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_rows_modified_since_analyze: register_int_gauge_vec_with_registry!(
+                "db_table_rows_modified_since_analyze",
+                "Estimated number of rows of a table modified since it was last analyzed",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_seconds_since_vacuum: register_int_gauge_vec_with_registry!(
+                "db_table_seconds_since_vacuum",
+                "Time since a table was last vacuumed, manually or by autovacuum",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_size_bytes: register_int_gauge_vec_with_registry!(
+                "db_table_size_bytes",
+                "Size of a table on disk, excluding its indexes",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_index_size_bytes: register_int_gauge_vec_with_registry!(
+                "db_table_index_size_bytes",
+                "Size of all of the indexes of a table on disk",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            db_table_analyzes_triggered: register_int_counter_vec_with_registry!(
+                "db_table_analyzes_triggered",
+                "Number of times the indexer analyzed a table because many of its rows changed",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Metrics on the pruning of `objects_history`, see `pruning.rs`.
+#[derive(Clone)]
+pub struct IndexerPrunerMetrics {
+    pub objects_history_rows_pruned: IntCounter,
+    pub objects_history_pruned_checkpoint: IntGauge,
+    pub objects_history_prune_aborts: IntCounter,
+}
+
+impl IndexerPrunerMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            objects_history_rows_pruned: register_int_counter_with_registry!(
+                "objects_history_rows_pruned",
+                "Number of superseded object versions pruned from objects_history",
+                registry,
+            )
+            .unwrap(),
+            objects_history_pruned_checkpoint: register_int_gauge_with_registry!(
+                "objects_history_pruned_checkpoint",
+                "Checkpoint up to which objects_history only holds the latest version of objects",
+                registry,
+            )
+            .unwrap(),
+            objects_history_prune_aborts: register_int_counter_with_registry!(
+                "objects_history_prune_aborts",
+                "Number of pruning batches that were rolled back, e.g. because they would have \
+                 removed the latest version of an object",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
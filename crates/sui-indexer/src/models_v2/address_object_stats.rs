@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::address_object_stats;
+
+/// Number and total storage rebate of the objects owned by an address. Rows are maintained by a
+/// trigger on the `objects` table, so they are only ever read.
+#[derive(Queryable, Clone, Debug, Identifiable)]
+#[diesel(table_name = address_object_stats, primary_key(owner_id))]
+pub struct StoredAddressObjectStats {
+    pub owner_id: Vec<u8>,
+    pub object_count: i64,
+    pub storage_rebate: i64,
+}
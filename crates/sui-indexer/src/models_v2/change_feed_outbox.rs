@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::change_feed_outbox;
+
+/// A change feed message waiting to be published, see `cdc.rs`.
+#[derive(Queryable, Insertable, Clone, Debug, Identifiable)]
+#[diesel(table_name = change_feed_outbox, primary_key(checkpoint_sequence_number, message_index))]
+pub struct StoredChangeFeedMessage {
+    pub checkpoint_sequence_number: i64,
+    pub message_index: i16,
+    pub subject: String,
+    pub payload: Vec<u8>,
+}
@@ -0,0 +1,15 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::checkpoint_content_hashes;
+
+/// Digest of the rows derived from a checkpoint, so that re-ingesting the checkpoint can check
+/// the rows it would write against the ones that are already there.
+#[derive(Queryable, Insertable, Clone, Debug, Identifiable)]
+#[diesel(table_name = checkpoint_content_hashes, primary_key(sequence_number))]
+pub struct StoredCheckpointContentHash {
+    pub sequence_number: i64,
+    pub content_hash: Vec<u8>,
+}
@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use sui_rest_api::CheckpointData;
+
+use crate::errors::IndexerError;
+use crate::schema_v2::dead_letter_checkpoints;
+
+/// A checkpoint that could not be transformed into rows, kept so that it can be replayed
+/// once the indexer has been fixed.
+#[derive(Queryable, Insertable, Clone, Debug, Identifiable)]
+#[diesel(table_name = dead_letter_checkpoints, primary_key(sequence_number))]
+pub struct StoredDeadLetterCheckpoint {
+    pub sequence_number: i64,
+    pub raw_checkpoint: Vec<u8>,
+    pub error: String,
+    pub failed_at_ms: i64,
+}
+
+impl StoredDeadLetterCheckpoint {
+    pub fn new(
+        checkpoint: &CheckpointData,
+        error: String,
+        failed_at_ms: u64,
+    ) -> Result<Self, IndexerError> {
+        Ok(Self {
+            sequence_number: *checkpoint.checkpoint_summary.sequence_number() as i64,
+            raw_checkpoint: bcs::to_bytes(checkpoint).map_err(|e| {
+                IndexerError::SerdeError(format!("Failed to serialize dead letter checkpoint: {e}"))
+            })?,
+            error,
+            failed_at_ms: failed_at_ms as i64,
+        })
+    }
+}
+
+impl TryFrom<StoredDeadLetterCheckpoint> for CheckpointData {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredDeadLetterCheckpoint) -> Result<Self, Self::Error> {
+        bcs::from_bytes(&stored.raw_checkpoint).map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to deserialize dead letter checkpoint {}: {e}",
+                stored.sequence_number
+            ))
+        })
+    }
+}
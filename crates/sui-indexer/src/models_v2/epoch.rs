@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use diesel::{Insertable, Queryable};
+use serde::Serialize;
 
 use crate::errors::IndexerError;
 use crate::schema_v2::epochs;
 use crate::types_v2::IndexedEpochInfo;
 use sui_json_rpc_types::{EndOfEpochInfo, EpochInfo};
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Serialize, Debug, Clone, Default)]
 #[diesel(table_name = epochs)]
 pub struct StoredEpochInfo {
     pub epoch: i64,
@@ -0,0 +1,41 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use move_core_types::value::MoveStructLayout;
+
+use crate::errors::IndexerError;
+use crate::schema_v2::event_schemas;
+use crate::types_v2::IndexedEventSchema;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = event_schemas)]
+pub struct StoredEventSchema {
+    pub event_type: String,
+    pub schema_version: Vec<u8>,
+    pub layout: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+}
+
+impl From<IndexedEventSchema> for StoredEventSchema {
+    fn from(schema: IndexedEventSchema) -> Self {
+        Self {
+            event_type: schema.event_type,
+            schema_version: schema.schema_version.to_vec(),
+            layout: bcs::to_bytes(&schema.layout).expect("Serializing a layout should not fail."),
+            checkpoint_sequence_number: schema.checkpoint_sequence_number as i64,
+        }
+    }
+}
+
+impl StoredEventSchema {
+    /// The layout to decode the contents of events with this schema version.
+    pub fn layout(&self) -> Result<MoveStructLayout, IndexerError> {
+        bcs::from_bytes(&self.layout).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse layout of event type {}: {e}",
+                self.event_type
+            ))
+        })
+    }
+}
@@ -7,6 +7,7 @@ use diesel::prelude::*;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
 use move_core_types::value::MoveStruct;
+use serde::Serialize;
 
 use sui_json_rpc_types::{SuiEvent, SuiMoveStruct};
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -18,7 +19,7 @@ use crate::errors::IndexerError;
 use crate::schema_v2::events;
 use crate::types_v2::IndexedEvent;
 
-#[derive(Queryable, Insertable, Debug, Clone)]
+#[derive(Queryable, Insertable, Serialize, Debug, Clone)]
 #[diesel(table_name = events)]
 pub struct StoredEvent {
     pub tx_sequence_number: i64,
@@ -31,6 +32,8 @@ pub struct StoredEvent {
     pub event_type: String,
     pub timestamp_ms: i64,
     pub bcs: Vec<u8>,
+    /// Identifies the layout of `bcs` in `event_schemas`.
+    pub schema_version: Option<Vec<u8>>,
 }
 
 impl From<IndexedEvent> for StoredEvent {
@@ -50,6 +53,7 @@ impl From<IndexedEvent> for StoredEvent {
             event_type: event.event_type.clone(),
             bcs: event.bcs.clone(),
             timestamp_ms: event.timestamp_ms as i64,
+            schema_version: event.schema_version.map(|version| version.to_vec()),
         }
     }
 }
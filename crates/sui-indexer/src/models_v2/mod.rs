@@ -1,10 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address_object_stats;
+pub mod change_feed_outbox;
+pub mod checkpoint_content_hashes;
 pub mod checkpoints;
+pub mod dead_letter_checkpoints;
 pub mod epoch;
+pub mod event_schemas;
 pub mod events;
 pub mod objects;
+pub mod package_leaderboards;
 pub mod packages;
+pub mod search_names;
 pub mod transactions;
 pub mod tx_indices;
+pub mod tx_insertion_order;
+pub mod tx_shared_objects;
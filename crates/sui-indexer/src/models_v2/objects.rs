@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use diesel::prelude::*;
+use serde::Serialize;
 use sui_types::digests::ObjectDigest;
 
 use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::StructTag;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldType};
 use sui_types::object::Object;
@@ -17,7 +19,7 @@ use crate::types_v2::IndexedObject;
 // NOTE: please add updating statement like below in pg_indexer_store_v2.rs,
 // if new columns are added here:
 // objects::epoch.eq(excluded(objects::epoch))
-#[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
+#[derive(Queryable, Insertable, Serialize, Debug, Identifiable, Clone, QueryableByName)]
 #[diesel(table_name = objects, primary_key(object_id))]
 pub struct StoredObject {
     pub object_id: Vec<u8>,
@@ -34,6 +36,8 @@ pub struct StoredObject {
     pub df_name: Option<Vec<u8>>,
     pub df_object_type: Option<String>,
     pub df_object_id: Option<Vec<u8>>,
+    pub storage_rebate: i64,
+    pub object_type: Option<String>,
 }
 
 #[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
@@ -61,6 +65,11 @@ impl From<IndexedObject> for StoredObject {
             df_name: o.df_info.as_ref().map(|n| bcs::to_bytes(&n.name).unwrap()),
             df_object_type: o.df_info.as_ref().map(|v| v.object_type.clone()),
             df_object_id: o.df_info.as_ref().map(|v| v.object_id.to_vec()),
+            storage_rebate: o.object.storage_rebate as i64,
+            object_type: o
+                .object
+                .type_()
+                .map(|t| StructTag::from(t.clone()).to_string()),
         }
     }
 }
@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::{package_activity, package_leaderboard_windows, package_leaderboards};
+use crate::types_v2::PackageActivity;
+
+/// The periods that packages are ranked over, counting back from the latest checkpoint.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LeaderboardPeriod {
+    Day = 0,
+    Week = 1,
+}
+
+/// What packages are ranked by.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    /// Number of Move calls to the package.
+    Calls,
+    /// Number of events emitted by the package.
+    Events,
+    /// Number of distinct senders that called the package.
+    Senders,
+    /// Gas used by the transactions that called the package.
+    GasUsed,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [LeaderboardPeriod; 2] = [LeaderboardPeriod::Day, LeaderboardPeriod::Week];
+
+    pub fn duration_ms(&self) -> i64 {
+        match self {
+            Self::Day => 24 * 60 * 60 * 1000,
+            Self::Week => 7 * 24 * 60 * 60 * 1000,
+        }
+    }
+}
+
+impl TryFrom<i16> for LeaderboardPeriod {
+    type Error = i16;
+
+    fn try_from(period: i16) -> Result<Self, Self::Error> {
+        Ok(match period {
+            0 => Self::Day,
+            1 => Self::Week,
+            _ => return Err(period),
+        })
+    }
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = package_activity)]
+pub struct StoredPackageActivity {
+    pub package: Vec<u8>,
+    pub sender: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub timestamp_ms: i64,
+    pub calls: i64,
+    pub events: i64,
+    pub gas_used: i64,
+}
+
+impl From<PackageActivity> for StoredPackageActivity {
+    fn from(a: PackageActivity) -> Self {
+        StoredPackageActivity {
+            package: a.package.to_vec(),
+            sender: a.sender.to_vec(),
+            checkpoint_sequence_number: a.checkpoint_sequence_number as i64,
+            timestamp_ms: a.timestamp_ms as i64,
+            calls: a.calls as i64,
+            events: a.events as i64,
+            gas_used: a.gas_used as i64,
+        }
+    }
+}
+
+/// A package's activity over a leaderboard period. Rows are maintained by
+/// `leaderboards::PackageLeaderboards`, so they are only ever read.
+#[derive(Queryable, Debug, Clone)]
+#[diesel(table_name = package_leaderboards, primary_key(period, package))]
+pub struct StoredPackageLeaderboardEntry {
+    pub period: i16,
+    pub package: Vec<u8>,
+    pub calls: i64,
+    pub events: i64,
+    pub senders: i64,
+    pub gas_used: i64,
+}
+
+/// The activity that a leaderboard period covers, as of its last refresh.
+#[derive(Queryable, Debug, Clone, Copy, PartialEq, Eq)]
+#[diesel(table_name = package_leaderboard_windows, primary_key(period))]
+pub struct StoredPackageLeaderboardWindow {
+    pub period: i16,
+    pub window_start_ms: i64,
+    pub window_end_ms: i64,
+}
+
+/// The entries of a leaderboard, read together with the activity that they cover.
+#[derive(Debug, Clone)]
+pub struct PackageLeaderboard {
+    pub window: Option<StoredPackageLeaderboardWindow>,
+    pub entries: Vec<StoredPackageLeaderboardEntry>,
+}
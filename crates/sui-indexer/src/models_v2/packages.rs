@@ -5,12 +5,15 @@ use crate::schema_v2::packages;
 use crate::types_v2::IndexedPackage;
 
 use diesel::prelude::*;
+use serde::Serialize;
 
-#[derive(Queryable, Insertable, Clone, Debug, Identifiable)]
+#[derive(Queryable, Insertable, Serialize, Clone, Debug, Identifiable)]
 #[diesel(table_name = packages, primary_key(package_id))]
 pub struct StoredPackage {
     pub package_id: Vec<u8>,
     pub move_package: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub publisher: Vec<u8>,
 }
 
 impl From<IndexedPackage> for StoredPackage {
@@ -18,6 +21,8 @@ impl From<IndexedPackage> for StoredPackage {
         Self {
             package_id: p.package_id.to_vec(),
             move_package: bcs::to_bytes(&p.move_package).unwrap(),
+            checkpoint_sequence_number: p.checkpoint_sequence_number as i64,
+            publisher: p.publisher.to_vec(),
         }
     }
 }
@@ -0,0 +1,187 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_json_rpc::name_service::{Domain, NameRecord};
+use sui_types::base_types::ObjectID;
+use sui_types::dynamic_field::Field;
+
+use crate::schema_v2::search_names;
+use crate::types_v2::{IndexedObject, IndexedPackage};
+
+const NAME_SERVICE_DOMAIN_MODULE: &str = "domain";
+const NAME_SERVICE_DOMAIN_STRUCT: &str = "Domain";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SearchNameKind {
+    Package = 0,
+    Module = 1,
+    Struct = 2,
+    NameService = 3,
+}
+
+impl TryFrom<i16> for SearchNameKind {
+    type Error = i16;
+
+    fn try_from(kind: i16) -> Result<Self, Self::Error> {
+        Ok(match kind {
+            0 => Self::Package,
+            1 => Self::Module,
+            2 => Self::Struct,
+            3 => Self::NameService,
+            _ => return Err(kind),
+        })
+    }
+}
+
+#[derive(Queryable, Insertable, Clone, Debug)]
+#[diesel(table_name = search_names, primary_key(kind, full_name))]
+pub struct StoredSearchName {
+    pub kind: i16,
+    pub name: String,
+    pub full_name: String,
+    pub address: Option<Vec<u8>>,
+    /// The object holding a SuiNS name's record, which removes the name when it is deleted.
+    pub object_id: Option<Vec<u8>>,
+    /// When a SuiNS name expires, and is removed.
+    pub expiration_timestamp_ms: Option<i64>,
+}
+
+impl StoredSearchName {
+    /// The names of a package and of the modules and structs it defines.
+    pub fn from_package(p: &IndexedPackage) -> Vec<Self> {
+        let package_id = p.package_id.to_string();
+        let address = Some(p.package_id.to_vec());
+        let mut names = vec![Self {
+            kind: SearchNameKind::Package as i16,
+            name: package_id.clone(),
+            full_name: package_id.clone(),
+            address: address.clone(),
+            object_id: None,
+            expiration_timestamp_ms: None,
+        }];
+        for (module_name, bytes) in p.move_package.serialized_module_map() {
+            let module_full_name = format!("{package_id}::{module_name}");
+            // Modules were verified when published, so they are expected to deserialize.
+            if let Ok(module) = CompiledModule::deserialize_with_defaults(bytes) {
+                for def in module.struct_defs() {
+                    let handle = module.struct_handle_at(def.struct_handle);
+                    let struct_name = module.identifier_at(handle.name);
+                    names.push(Self {
+                        kind: SearchNameKind::Struct as i16,
+                        name: struct_name.to_string(),
+                        full_name: format!("{module_full_name}::{struct_name}"),
+                        address: address.clone(),
+                        object_id: None,
+                        expiration_timestamp_ms: None,
+                    });
+                }
+            }
+            names.push(Self {
+                kind: SearchNameKind::Module as i16,
+                name: module_name.clone(),
+                full_name: module_full_name,
+                address: address.clone(),
+                object_id: None,
+                expiration_timestamp_ms: None,
+            });
+        }
+        names
+    }
+
+    /// The SuiNS domain registered by `o`, if it is a record in the SuiNS registry. Records are
+    /// recognized by their type, `Field<domain::Domain, name_record::NameRecord>`, where `Domain`
+    /// must be defined by the SuiNS package, `name_service_package`, so that records in lookalike
+    /// registries published by anyone else are not indexed.
+    pub fn from_name_record(o: &IndexedObject, name_service_package: ObjectID) -> Option<Self> {
+        let df_info = o.df_info.as_ref()?;
+        let TypeTag::Struct(name_type) = &df_info.name.type_ else {
+            return None;
+        };
+        let contents = o.object.data.try_as_move()?.contents();
+        let (domain, record) = decode_name_record(name_type, contents, name_service_package)?;
+        let domain = domain.to_string();
+        Some(Self {
+            kind: SearchNameKind::NameService as i16,
+            name: domain.clone(),
+            full_name: domain,
+            address: record.target_address.map(|a| a.to_vec()),
+            object_id: Some(o.object_id.to_vec()),
+            expiration_timestamp_ms: Some(record.expiration_timestamp_ms as i64),
+        })
+    }
+}
+
+/// Decodes the `contents` of a dynamic field whose name is of type `name_type` as a SuiNS record.
+fn decode_name_record(
+    name_type: &StructTag,
+    contents: &[u8],
+    name_service_package: ObjectID,
+) -> Option<(Domain, NameRecord)> {
+    if ObjectID::from(name_type.address) != name_service_package
+        || name_type.module.as_str() != NAME_SERVICE_DOMAIN_MODULE
+        || name_type.name.as_str() != NAME_SERVICE_DOMAIN_STRUCT
+    {
+        return None;
+    }
+    let field: Field<Domain, NameRecord> = bcs::from_bytes(contents).ok()?;
+    Some((field.name, field.value))
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::identifier::Identifier;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::collection_types::VecMap;
+    use sui_types::id::{ID, UID};
+
+    use super::*;
+
+    fn domain_type(package: ObjectID) -> StructTag {
+        StructTag {
+            address: package.into(),
+            module: Identifier::new(NAME_SERVICE_DOMAIN_MODULE).unwrap(),
+            name: Identifier::new(NAME_SERVICE_DOMAIN_STRUCT).unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    fn record(domain: &str, target: SuiAddress) -> Vec<u8> {
+        bcs::to_bytes(&Field {
+            id: UID::new(ObjectID::random()),
+            name: domain.parse::<Domain>().unwrap(),
+            value: NameRecord {
+                nft_id: ID::new(ObjectID::random()),
+                expiration_timestamp_ms: 1_700_000_000_000,
+                target_address: Some(target),
+                data: VecMap { contents: vec![] },
+            },
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_name_record() {
+        let package = ObjectID::random();
+        let target = SuiAddress::random_for_testing_only();
+        let contents = record("sub.example.sui", target);
+
+        let (domain, record) = decode_name_record(&domain_type(package), &contents, package)
+            .expect("Record should be decoded");
+        assert_eq!(domain.to_string(), "sub.example.sui");
+        assert_eq!(record.target_address, Some(target));
+        assert_eq!(record.expiration_timestamp_ms, 1_700_000_000_000);
+    }
+
+    #[test]
+    fn test_name_record_from_other_package() {
+        let package = ObjectID::random();
+        let contents = record("example.sui", SuiAddress::random_for_testing_only());
+
+        // A registry with the same layout, published by someone else.
+        let other = domain_type(ObjectID::random());
+        assert!(decode_name_record(&other, &contents, package).is_none());
+    }
+}
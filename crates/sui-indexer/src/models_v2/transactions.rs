@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use diesel::prelude::*;
 use move_bytecode_utils::module_cache::GetModule;
+use serde::Serialize;
 use sui_json_rpc_types::BalanceChange;
 use sui_json_rpc_types::ObjectChange;
 use sui_json_rpc_types::SuiTransactionBlock;
@@ -13,15 +14,16 @@ use sui_types::digests::TransactionDigest;
 use sui_types::effects::TransactionEffects;
 use sui_types::effects::TransactionEvents;
 use sui_types::event::Event;
-use sui_types::transaction::SenderSignedData;
+use sui_types::transaction::{SenderSignedData, TransactionDataAPI};
 
 use crate::errors::IndexerError;
 use crate::schema_v2::transactions;
 use crate::types_v2::IndexedObjectChange;
 use crate::types_v2::IndexedTransaction;
 use crate::types_v2::IndexerResult;
+use crate::types_v2::TransactionKind;
 
-#[derive(Clone, Debug, Queryable, Insertable, QueryableByName)]
+#[derive(Clone, Debug, Queryable, Insertable, QueryableByName, Serialize)]
 #[diesel(table_name = transactions)]
 pub struct StoredTransaction {
     pub tx_sequence_number: i64,
@@ -34,6 +36,9 @@ pub struct StoredTransaction {
     pub balance_changes: Vec<Option<Vec<u8>>>,
     pub events: Vec<Option<Vec<u8>>>,
     pub transaction_kind: i16,
+    /// Gas price of programmable transactions. Null for system transactions, and for transactions
+    /// indexed before it was recorded.
+    pub gas_price: Option<i64>,
 }
 
 impl From<&IndexedTransaction> for StoredTransaction {
@@ -61,6 +66,12 @@ impl From<&IndexedTransaction> for StoredTransaction {
                 .collect(),
             transaction_kind: tx.transaction_kind.clone() as i16,
             timestamp_ms: tx.timestamp_ms as i64,
+            gas_price: match tx.transaction_kind {
+                TransactionKind::ProgrammableTransaction => {
+                    Some(tx.sender_signed_data.transaction_data().gas_price() as i64)
+                }
+                TransactionKind::SystemTransaction => None,
+            },
         }
     }
 }
@@ -3,8 +3,9 @@
 
 use crate::{schema_v2::tx_indices, types_v2::TxIndex};
 use diesel::prelude::*;
+use serde::Serialize;
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Serialize, Debug, Clone, Default)]
 #[diesel(table_name = tx_indices)]
 pub struct StoredTxIndex {
     pub tx_sequence_number: i64,
@@ -0,0 +1,41 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::schema_v2::{export_watermarks, tx_insertion_order};
+
+#[derive(Queryable, Debug, Clone)]
+#[diesel(table_name = tx_insertion_order)]
+pub struct StoredTxInsertionOrder {
+    pub insertion_order: i64,
+    pub tx_sequence_number: i64,
+    pub checkpoint_sequence_number: i64,
+}
+
+/// A row of `tx_insertion_order` to be written. Its `insertion_order` is assigned by the database.
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = tx_insertion_order)]
+pub struct NewTxInsertionOrder {
+    pub tx_sequence_number: i64,
+    pub checkpoint_sequence_number: i64,
+}
+
+impl NewTxInsertionOrder {
+    /// One row per transaction in `checkpoint`, in the order they were executed.
+    pub fn from_checkpoint(checkpoint: &StoredCheckpoint) -> impl Iterator<Item = Self> + '_ {
+        let first = checkpoint.network_total_transactions - checkpoint.tx_digests.len() as i64;
+        (first..checkpoint.network_total_transactions).map(|tx_sequence_number| Self {
+            tx_sequence_number,
+            checkpoint_sequence_number: checkpoint.sequence_number,
+        })
+    }
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = export_watermarks)]
+pub struct StoredExportWatermark {
+    pub exporter: String,
+    pub insertion_order: i64,
+}
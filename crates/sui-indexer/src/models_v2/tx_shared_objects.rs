@@ -0,0 +1,31 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::{schema_v2::tx_shared_objects, types_v2::TxSharedObject};
+
+#[derive(Queryable, Insertable, Serialize, Debug, Clone)]
+#[diesel(table_name = tx_shared_objects)]
+pub struct StoredTxSharedObject {
+    pub tx_sequence_number: i64,
+    pub checkpoint_sequence_number: i64,
+    pub transaction_digest: Vec<u8>,
+    pub object_id: Vec<u8>,
+    pub object_version: i64,
+    pub mutable: bool,
+}
+
+impl From<TxSharedObject> for StoredTxSharedObject {
+    fn from(o: TxSharedObject) -> Self {
+        StoredTxSharedObject {
+            tx_sequence_number: o.tx_sequence_number as i64,
+            checkpoint_sequence_number: o.checkpoint_sequence_number as i64,
+            transaction_digest: o.transaction_digest.into_inner().to_vec(),
+            object_id: o.object_id.to_vec(),
+            object_version: o.version.value() as i64,
+            mutable: o.mutable,
+        }
+    }
+}
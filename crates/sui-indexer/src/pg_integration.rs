@@ -0,0 +1,46 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for tests that run against a standalone Postgres database, at the host and port in
+//! POSTGRES_HOST and POSTGRES_PORT, with the password in POSTGRES_PASSWORD. Tests reset the
+//! database they share, so they must be run one at a time.
+
+use diesel::RunQueryDsl;
+
+use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::schema_v2::checkpoints;
+use crate::utils::reset_database;
+use crate::{get_pg_pool_connection, new_pg_connection_pool, PgConnectionPool};
+
+pub fn db_url() -> String {
+    let pg_host = std::env::var("POSTGRES_HOST").unwrap_or_else(|_| "localhost".into());
+    let pg_port = std::env::var("POSTGRES_PORT").unwrap_or_else(|_| "32770".into());
+    let pw = std::env::var("POSTGRES_PASSWORD").unwrap_or_else(|_| "postgrespw".into());
+    format!("postgres://postgres:{pw}@{pg_host}:{pg_port}")
+}
+
+/// A pool of connections to the test database, after dropping all of its tables and running the
+/// v2 migrations.
+pub fn test_pool() -> PgConnectionPool {
+    test_pool_with_migrations(true)
+}
+
+/// Like `test_pool`, running the v2 migrations if `use_v2`, and the v1 ones otherwise.
+pub fn test_pool_with_migrations(use_v2: bool) -> PgConnectionPool {
+    let pool = new_pg_connection_pool(&db_url()).unwrap();
+    reset_database(&mut get_pg_pool_connection(&pool).unwrap(), true, use_v2).unwrap();
+    pool
+}
+
+/// Inserts checkpoint `sequence_number` into the v2 `checkpoints` table, with its other fields
+/// left at their defaults.
+pub fn insert_checkpoint(pool: &PgConnectionPool, sequence_number: i64) {
+    diesel::insert_into(checkpoints::table)
+        .values(StoredCheckpoint {
+            sequence_number,
+            checkpoint_digest: vec![sequence_number as u8],
+            ..Default::default()
+        })
+        .execute(&mut get_pg_pool_connection(pool).unwrap())
+        .unwrap();
+}
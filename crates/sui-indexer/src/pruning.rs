@@ -0,0 +1,355 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prunes old object versions from `objects_history`. Every version of every live object is
+//! recorded there (by a trigger on `objects`), so it grows with every transaction. Versions from
+//! checkpoints within the retention window are kept, so that objects can still be read as of any
+//! of those checkpoints, and so is the latest version of every object, however old, so that
+//! pruning never changes what is read as of the latest checkpoint.
+//!
+//! Versions that are not yet part of a checkpoint (recorded at checkpoint -1) are never pruned, nor
+//! used to prune older versions. How far pruning has got is recorded in `pruner_watermarks`, so
+//! that it picks up where it left off after a restart.
+
+use std::time::Duration;
+
+use diesel::{
+    sql_query,
+    sql_types::{Array, BigInt, Nullable, Text},
+    upsert::excluded,
+    ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, QueryableByName, RunQueryDsl,
+};
+use tracing::{error, info};
+
+use crate::{
+    errors::IndexerError, get_pg_pool_connection, metrics::IndexerPrunerMetrics,
+    schema::pruner_watermarks, store::diesel_macro::transactional_blocking, PgConnectionPool,
+};
+
+/// Number of checkpoints whose versions are used to prune older versions in one transaction.
+const PRUNE_BATCH_CHECKPOINTS: i64 = 1000;
+
+/// Name of this pruner's row in `pruner_watermarks`.
+const PRUNER_NAME: &str = "objects_history";
+
+#[derive(Clone)]
+pub struct ObjectsHistoryPruner {
+    pool: PgConnectionPool,
+    metrics: IndexerPrunerMetrics,
+    interval: Duration,
+    /// Number of checkpoints, counting back from the latest one, whose versions are all kept.
+    retention_checkpoints: u64,
+}
+
+#[derive(QueryableByName)]
+struct Watermark {
+    #[diesel(sql_type = Nullable<BigInt>)]
+    checkpoint: Option<i64>,
+}
+
+#[derive(QueryableByName)]
+struct PrunedVersion {
+    #[diesel(sql_type = Text)]
+    object_id: String,
+}
+
+impl ObjectsHistoryPruner {
+    pub fn new(
+        pool: PgConnectionPool,
+        metrics: IndexerPrunerMetrics,
+        interval: Duration,
+        retention_checkpoints: u64,
+    ) -> Self {
+        Self {
+            pool,
+            metrics,
+            interval,
+            retention_checkpoints,
+        }
+    }
+
+    pub async fn run(self) {
+        // Versions from checkpoints before this one have already been used to prune the versions
+        // they supersede. Read from `pruner_watermarks` on the first run.
+        let mut next_checkpoint: Option<i64> = None;
+        loop {
+            let this = self.clone();
+            match tokio::task::spawn_blocking(move || this.prune(next_checkpoint)).await {
+                Ok(Ok(checkpoint)) => next_checkpoint = checkpoint,
+                Ok(Err(e)) => error!("Failed to prune objects_history: {e}"),
+                Err(e) => error!("Pruning objects_history panicked: {e}"),
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Prunes the versions in `objects_history` that are older than the retention window and that
+    /// are superseded by a version from a checkpoint at or before its start, using the versions
+    /// from checkpoints `next_checkpoint` onwards, or from where the previous run left off if it
+    /// is not known. Returns the checkpoint to continue from next time.
+    pub fn prune(&self, next_checkpoint: Option<i64>) -> Result<Option<i64>, IndexerError> {
+        let mut conn = get_pg_pool_connection(&self.pool)?;
+
+        let Some(latest) = sql_query("SELECT MAX(sequence_number) AS checkpoint FROM checkpoints")
+            .get_result::<Watermark>(&mut conn)
+            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?
+            .checkpoint
+        else {
+            return Ok(next_checkpoint);
+        };
+
+        let cutoff = latest - self.retention_checkpoints as i64;
+        if cutoff <= 0 {
+            return Ok(next_checkpoint);
+        }
+
+        let next_checkpoint = match next_checkpoint {
+            Some(checkpoint) => Some(checkpoint),
+            None => read_watermark(&mut conn)?,
+        };
+        let mut lo = match next_checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => {
+                let oldest = sql_query(
+                    "SELECT MIN(checkpoint) AS checkpoint FROM objects_history \
+                     WHERE checkpoint >= 0",
+                )
+                .get_result::<Watermark>(&mut conn)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?
+                .checkpoint;
+                let Some(oldest) = oldest else {
+                    return Ok(None);
+                };
+                oldest
+            }
+        };
+
+        // Versions from checkpoints up to and including the cutoff can supersede older ones.
+        while lo <= cutoff {
+            let hi = (lo + PRUNE_BATCH_CHECKPOINTS).min(cutoff + 1);
+            let pruned = self.prune_batch(lo, hi, cutoff)?;
+            info!(lo, hi, pruned, "Pruned objects_history");
+            self.metrics.objects_history_rows_pruned.inc_by(pruned);
+            self.metrics.objects_history_pruned_checkpoint.set(hi - 1);
+            lo = hi;
+        }
+
+        Ok(Some(lo))
+    }
+
+    /// Deletes the versions from before `cutoff` that are superseded by a version from a
+    /// checkpoint in `[lo, hi)`, returning how many were deleted, and records that pruning
+    /// continues from `hi`. Runs in a single transaction, which is rolled back if any object would
+    /// be left without the version that `objects` holds for it.
+    fn prune_batch(&self, lo: i64, hi: i64, cutoff: i64) -> Result<u64, IndexerError> {
+        let result = transactional_blocking!(&self.pool, |conn| {
+            let pruned: Vec<PrunedVersion> = sql_query(
+                "DELETE FROM objects_history h \
+                 USING ( \
+                     SELECT object_id, MAX(version) AS version \
+                     FROM objects_history \
+                     WHERE checkpoint >= $1 AND checkpoint < $2 \
+                     GROUP BY object_id \
+                 ) n \
+                 WHERE h.object_id = n.object_id \
+                   AND h.version < n.version \
+                   AND h.checkpoint >= 0 \
+                   AND h.checkpoint < $3 \
+                 RETURNING h.object_id::text AS object_id",
+            )
+            .bind::<BigInt, _>(lo)
+            .bind::<BigInt, _>(hi)
+            .bind::<BigInt, _>(cutoff)
+            .load(conn)?;
+
+            write_watermark(conn, hi)?;
+            if pruned.is_empty() {
+                return Ok::<u64, IndexerError>(0);
+            }
+
+            let mut object_ids: Vec<String> = pruned.into_iter().map(|v| v.object_id).collect();
+            let count = object_ids.len() as u64;
+            object_ids.sort();
+            object_ids.dedup();
+
+            // The latest version of an object can't be superseded, so this only fails if the
+            // history is inconsistent with `objects`, in which case nothing is pruned.
+            let missing: Vec<PrunedVersion> = sql_query(
+                "SELECT o.object_id::text AS object_id \
+                 FROM objects o \
+                 WHERE o.object_id = ANY($1::varchar[]) \
+                   AND NOT EXISTS ( \
+                       SELECT 1 FROM objects_history h \
+                       WHERE h.object_id = o.object_id AND h.version = o.version \
+                   ) \
+                 LIMIT 1",
+            )
+            .bind::<Array<Text>, _>(object_ids)
+            .load(conn)?;
+
+            if let Some(missing) = missing.first() {
+                return Err(IndexerError::PostgresWriteError(format!(
+                    "Pruning would remove the latest version of object {}",
+                    missing.object_id
+                )));
+            }
+
+            Ok(count)
+        });
+
+        if result.is_err() {
+            self.metrics.objects_history_prune_aborts.inc();
+        }
+        result
+    }
+}
+
+/// The checkpoint that the previous run of the pruner left off at, if any.
+fn read_watermark(conn: &mut PgConnection) -> Result<Option<i64>, IndexerError> {
+    pruner_watermarks::table
+        .select(pruner_watermarks::next_checkpoint)
+        .filter(pruner_watermarks::pruner.eq(PRUNER_NAME))
+        .first::<i64>(conn)
+        .optional()
+        .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+}
+
+fn write_watermark(conn: &mut PgConnection, next_checkpoint: i64) -> Result<(), IndexerError> {
+    diesel::insert_into(pruner_watermarks::table)
+        .values((
+            pruner_watermarks::pruner.eq(PRUNER_NAME),
+            pruner_watermarks::next_checkpoint.eq(next_checkpoint),
+        ))
+        .on_conflict(pruner_watermarks::pruner)
+        .do_update()
+        .set(pruner_watermarks::next_checkpoint.eq(excluded(pruner_watermarks::next_checkpoint)))
+        .execute(conn)
+        .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "pg_integration"))]
+mod tests {
+    use prometheus::Registry;
+
+    use super::*;
+    use crate::pg_integration::test_pool_with_migrations;
+
+    #[derive(QueryableByName, Debug, PartialEq, Eq)]
+    struct Version {
+        #[diesel(sql_type = Text)]
+        object_id: String,
+        #[diesel(sql_type = BigInt)]
+        version: i64,
+    }
+
+    fn pruner(pool: &PgConnectionPool) -> ObjectsHistoryPruner {
+        ObjectsHistoryPruner::new(
+            pool.clone(),
+            IndexerPrunerMetrics::new(&Registry::default()),
+            Duration::from_secs(1),
+            /* retention_checkpoints */ 10,
+        )
+    }
+
+    fn set_latest_checkpoint(conn: &mut PgConnection, checkpoint: i64) {
+        sql_query(
+            "INSERT INTO checkpoints (sequence_number, checkpoint_digest, epoch, transactions, \
+             end_of_epoch, total_gas_cost, total_computation_cost, total_storage_cost, \
+             total_storage_rebate, total_transaction_blocks, total_transactions, \
+             total_successful_transaction_blocks, total_successful_transactions, \
+             network_total_transactions, timestamp_ms, validator_signature) \
+             VALUES ($1, $1::text, 0, '{}', false, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+        )
+        .bind::<BigInt, _>(checkpoint)
+        .execute(conn)
+        .unwrap();
+    }
+
+    /// Writes `version` of object `object_id` to `objects`, which records it in
+    /// `objects_history`.
+    fn write_object(conn: &mut PgConnection, object_id: &str, version: i64, checkpoint: i64) {
+        sql_query(
+            "INSERT INTO objects (epoch, checkpoint, object_id, version, object_digest, \
+             owner_type, previous_transaction, object_type, object_status, has_public_transfer, \
+             storage_rebate, bcs) \
+             VALUES (0, $1, $2, $3, 'digest', 'immutable', 'tx', 'type', 'created', false, 0, \
+             ARRAY[]::bcs_bytes[]) \
+             ON CONFLICT (object_id) DO UPDATE \
+             SET version = EXCLUDED.version, checkpoint = EXCLUDED.checkpoint",
+        )
+        .bind::<BigInt, _>(checkpoint)
+        .bind::<Text, _>(object_id)
+        .bind::<BigInt, _>(version)
+        .execute(conn)
+        .unwrap();
+    }
+
+    fn history(conn: &mut PgConnection) -> Vec<(String, i64)> {
+        sql_query(
+            "SELECT object_id::text AS object_id, version FROM objects_history \
+             ORDER BY object_id, version",
+        )
+        .load::<Version>(conn)
+        .unwrap()
+        .into_iter()
+        .map(|v| (v.object_id, v.version))
+        .collect()
+    }
+
+    fn versions(versions: &[(&str, i64)]) -> Vec<(String, i64)> {
+        versions
+            .iter()
+            .map(|(id, v)| (id.to_string(), *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_prune_outside_retention() {
+        let pool = test_pool_with_migrations(false);
+        let mut conn = get_pg_pool_connection(&pool).unwrap();
+
+        write_object(&mut conn, "0xa", 1, 0);
+        write_object(&mut conn, "0xa", 2, 5);
+        write_object(&mut conn, "0xa", 3, 15);
+        // Not yet part of a checkpoint, and then superseded.
+        write_object(&mut conn, "0xb", 1, -1);
+        write_object(&mut conn, "0xb", 2, 3);
+        set_latest_checkpoint(&mut conn, 20);
+
+        // Versions up to checkpoint 10 supersede older ones: 0xa's first version is superseded by
+        // its second, which is only superseded from checkpoint 15, within the retention window.
+        assert_eq!(pruner(&pool).prune(None).unwrap(), Some(11));
+        assert_eq!(
+            history(&mut conn),
+            versions(&[("0xa", 2), ("0xa", 3), ("0xb", 1), ("0xb", 2)]),
+        );
+        assert_eq!(read_watermark(&mut conn).unwrap(), Some(11));
+
+        // Pruning again picks up where the previous run left off, without scanning the versions
+        // before it again, even from a new pruner.
+        write_object(&mut conn, "0xc", 1, 1);
+        write_object(&mut conn, "0xc", 2, 2);
+        set_latest_checkpoint(&mut conn, 30);
+        assert_eq!(pruner(&pool).prune(None).unwrap(), Some(21));
+        assert_eq!(
+            history(&mut conn),
+            versions(&[("0xa", 3), ("0xb", 1), ("0xb", 2), ("0xc", 1), ("0xc", 2)]),
+        );
+        assert_eq!(read_watermark(&mut conn).unwrap(), Some(21));
+    }
+
+    #[test]
+    fn test_prune_ignores_unchecked_versions() {
+        let pool = test_pool_with_migrations(false);
+        let mut conn = get_pg_pool_connection(&pool).unwrap();
+
+        // Only versions that are not part of a checkpoint yet.
+        write_object(&mut conn, "0xa", 1, -1);
+        set_latest_checkpoint(&mut conn, 20);
+
+        assert_eq!(pruner(&pool).prune(None).unwrap(), None);
+        assert_eq!(history(&mut conn), versions(&[("0xa", 1)]));
+        assert_eq!(read_watermark(&mut conn).unwrap(), None);
+    }
+}
@@ -257,6 +257,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    pruner_watermarks (pruner) {
+        pruner -> Text,
+        next_checkpoint -> Int8,
+    }
+}
+
 diesel::table! {
     recipients (id) {
         id -> Int8,
@@ -388,6 +395,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     objects,
     objects_history,
     packages,
+    pruner_watermarks,
     recipients,
     system_states,
     transactions,
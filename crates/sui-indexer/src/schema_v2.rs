@@ -2,6 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    address_object_stats (owner_id) {
+        owner_id -> Bytea,
+        object_count -> Int8,
+        storage_rebate -> Int8,
+    }
+}
+
+diesel::table! {
+    change_feed_outbox (checkpoint_sequence_number, message_index) {
+        checkpoint_sequence_number -> Int8,
+        message_index -> Int2,
+        subject -> Text,
+        payload -> Bytea,
+    }
+}
+
 diesel::table! {
     checkpoints (sequence_number) {
         sequence_number -> Int8,
@@ -23,6 +40,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    checkpoint_content_hashes (sequence_number) {
+        sequence_number -> Int8,
+        content_hash -> Bytea,
+    }
+}
+
+diesel::table! {
+    dead_letter_checkpoints (sequence_number) {
+        sequence_number -> Int8,
+        raw_checkpoint -> Bytea,
+        error -> Text,
+        failed_at_ms -> Int8,
+    }
+}
+
 diesel::table! {
     epochs (epoch) {
         epoch -> Int8,
@@ -49,6 +82,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    event_schemas (event_type, schema_version) {
+        event_type -> Text,
+        schema_version -> Bytea,
+        layout -> Bytea,
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
 diesel::table! {
     events (tx_sequence_number, event_sequence_number) {
         tx_sequence_number -> Int8,
@@ -61,6 +103,14 @@ diesel::table! {
         event_type -> Text,
         timestamp_ms -> Int8,
         bcs -> Bytea,
+        schema_version -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    export_watermarks (exporter) {
+        exporter -> Text,
+        insertion_order -> Int8,
     }
 }
 
@@ -79,6 +129,48 @@ diesel::table! {
         df_name -> Nullable<Bytea>,
         df_object_type -> Nullable<Text>,
         df_object_id -> Nullable<Bytea>,
+        storage_rebate -> Int8,
+        object_type -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    package_activity (package, sender, checkpoint_sequence_number) {
+        package -> Bytea,
+        sender -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        timestamp_ms -> Int8,
+        calls -> Int8,
+        events -> Int8,
+        gas_used -> Int8,
+    }
+}
+
+diesel::table! {
+    package_leaderboard_senders (period, package, sender) {
+        period -> Int2,
+        package -> Bytea,
+        sender -> Bytea,
+        last_active_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    package_leaderboard_windows (period) {
+        period -> Int2,
+        window_start_ms -> Int8,
+        window_end_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    package_leaderboards (period, package) {
+        period -> Int2,
+        package -> Bytea,
+        calls -> Int8,
+        events -> Int8,
+        senders -> Int8,
+        gas_used -> Int8,
     }
 }
 
@@ -86,6 +178,19 @@ diesel::table! {
     packages (package_id) {
         package_id -> Bytea,
         move_package -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        publisher -> Bytea,
+    }
+}
+
+diesel::table! {
+    search_names (kind, full_name) {
+        kind -> Int2,
+        name -> Text,
+        full_name -> Text,
+        address -> Nullable<Bytea>,
+        object_id -> Nullable<Bytea>,
+        expiration_timestamp_ms -> Nullable<Int8>,
     }
 }
 
@@ -101,6 +206,7 @@ diesel::table! {
         balance_changes -> Array<Nullable<Bytea>>,
         events -> Array<Nullable<Bytea>>,
         transaction_kind -> Int2,
+        gas_price -> Nullable<Int8>,
     }
 }
 
@@ -120,12 +226,44 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_insertion_order (insertion_order) {
+        insertion_order -> Int8,
+        tx_sequence_number -> Int8,
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
+diesel::table! {
+    tx_shared_objects (tx_sequence_number, object_id) {
+        tx_sequence_number -> Int8,
+        checkpoint_sequence_number -> Int8,
+        transaction_digest -> Bytea,
+        object_id -> Bytea,
+        object_version -> Int8,
+        mutable -> Bool,
+    }
+}
+
 diesel::allow_tables_to_appear_in_same_query!(
+    address_object_stats,
+    change_feed_outbox,
+    checkpoint_content_hashes,
     checkpoints,
+    dead_letter_checkpoints,
     epochs,
+    event_schemas,
     events,
+    export_watermarks,
     objects,
+    package_activity,
+    package_leaderboard_senders,
+    package_leaderboard_windows,
+    package_leaderboards,
     packages,
+    search_names,
     transactions,
     tx_indices,
+    tx_insertion_order,
+    tx_shared_objects,
 );
@@ -4,16 +4,20 @@
 use async_trait::async_trait;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use sui_rest_api::CheckpointData;
 
 use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::object::ObjectRead;
 
 use crate::errors::IndexerError;
 use crate::handlers::{EpochToCommit, TransactionObjectChangesToCommit};
+use crate::models_v2::change_feed_outbox::StoredChangeFeedMessage;
 
 use crate::types_v2::{
-    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
+    IndexedCheckpoint, IndexedEvent, IndexedEventSchema, IndexedPackage, IndexedTransaction,
+    PackageActivity, TxIndex, TxSharedObject,
 };
 
 #[async_trait]
@@ -36,9 +40,12 @@ pub trait IndexerStoreV2 {
         object_changes: Vec<TransactionObjectChangesToCommit>,
     ) -> Result<(), IndexerError>;
 
+    /// Commits `checkpoints`, after the rest of their data was persisted, and removes the ones
+    /// whose stored content hash is in `content_hashes` from the dead letter queue.
     async fn persist_checkpoints(
         &self,
         checkpoints: Vec<IndexedCheckpoint>,
+        content_hashes: Vec<(u64, [u8; 32])>,
     ) -> Result<(), IndexerError>;
 
     async fn persist_transactions(
@@ -48,8 +55,23 @@ pub trait IndexerStoreV2 {
 
     async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError>;
 
+    async fn persist_tx_shared_objects(
+        &self,
+        shared_objects: Vec<TxSharedObject>,
+    ) -> Result<(), IndexerError>;
+
+    async fn persist_package_activity(
+        &self,
+        activity: Vec<PackageActivity>,
+    ) -> Result<(), IndexerError>;
+
     async fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError>;
 
+    async fn persist_event_schemas(
+        &self,
+        schemas: Vec<IndexedEventSchema>,
+    ) -> Result<(), IndexerError>;
+
     async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError>;
 
     async fn persist_epoch(&self, data: Vec<EpochToCommit>) -> Result<(), IndexerError>;
@@ -59,5 +81,50 @@ pub trait IndexerStoreV2 {
         epoch: u64,
     ) -> Result<u64, IndexerError>;
 
+    async fn persist_dead_letter_checkpoint(
+        &self,
+        checkpoint: &CheckpointData,
+        error: String,
+    ) -> Result<(), IndexerError>;
+
+    async fn get_dead_letter_checkpoints(&self) -> Result<Vec<CheckpointData>, IndexerError>;
+
+    /// Sequence numbers of the checkpoints in `first..=last` that are in the dead letter queue.
+    async fn get_dead_letter_sequence_numbers(
+        &self,
+        first: u64,
+        last: u64,
+    ) -> Result<HashSet<u64>, IndexerError>;
+
+    async fn persist_checkpoint_content_hashes(
+        &self,
+        content_hashes: Vec<(u64, [u8; 32])>,
+    ) -> Result<(), IndexerError>;
+
+    /// Content hashes of the checkpoints in `first..=last` that have one, by sequence number.
+    async fn get_checkpoint_content_hashes(
+        &self,
+        first: u64,
+        last: u64,
+    ) -> Result<HashMap<u64, Vec<u8>>, IndexerError>;
+
+    async fn persist_change_feed_messages(
+        &self,
+        messages: Vec<StoredChangeFeedMessage>,
+    ) -> Result<(), IndexerError>;
+
+    /// Up to `limit` change feed messages waiting to be published whose checkpoints have been
+    /// committed, in the order they should be published in.
+    async fn get_committed_change_feed_messages(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<StoredChangeFeedMessage>, IndexerError>;
+
+    async fn delete_change_feed_message(
+        &self,
+        checkpoint_sequence_number: i64,
+        message_index: i16,
+    ) -> Result<(), IndexerError>;
+
     fn module_cache(&self) -> Arc<Self::ModuleCache>;
 }
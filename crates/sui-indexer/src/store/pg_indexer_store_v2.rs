@@ -11,7 +11,8 @@ use std::time::Duration;
 use tap::Tap;
 
 use async_trait::async_trait;
-use diesel::dsl::max;
+use diesel::dsl::{exists, max};
+use diesel::sql_types::{Array, BigInt, Bytea};
 use diesel::upsert::excluded;
 use diesel::ExpressionMethods;
 use diesel::OptionalExtension;
@@ -19,6 +20,7 @@ use diesel::{QueryDsl, RunQueryDsl};
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use tracing::info;
 
+use sui_rest_api::CheckpointData;
 use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::object::ObjectRead;
 
@@ -27,18 +29,31 @@ use crate::handlers::EpochToCommit;
 use crate::handlers::TransactionObjectChangesToCommit;
 use crate::metrics::IndexerMetrics;
 
+use crate::models_v2::change_feed_outbox::StoredChangeFeedMessage;
+use crate::models_v2::checkpoint_content_hashes::StoredCheckpointContentHash;
 use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::models_v2::dead_letter_checkpoints::StoredDeadLetterCheckpoint;
 use crate::models_v2::epoch::StoredEpochInfo;
+use crate::models_v2::event_schemas::StoredEventSchema;
 use crate::models_v2::events::StoredEvent;
 use crate::models_v2::objects::StoredObject;
+use crate::models_v2::package_leaderboards::StoredPackageActivity;
 use crate::models_v2::packages::StoredPackage;
+use crate::models_v2::search_names::{SearchNameKind, StoredSearchName};
 use crate::models_v2::transactions::StoredTransaction;
 use crate::models_v2::tx_indices::StoredTxIndex;
-use crate::schema_v2::{checkpoints, epochs, events, objects, packages, transactions, tx_indices};
+use crate::models_v2::tx_insertion_order::NewTxInsertionOrder;
+use crate::models_v2::tx_shared_objects::StoredTxSharedObject;
+use crate::schema_v2::{
+    change_feed_outbox, checkpoint_content_hashes, checkpoints, dead_letter_checkpoints, epochs,
+    event_schemas, events, objects, package_activity, packages, search_names, transactions,
+    tx_indices, tx_insertion_order, tx_shared_objects,
+};
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::store::module_resolver_v2::IndexerStoreModuleResolver;
 use crate::types_v2::{
-    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
+    IndexedCheckpoint, IndexedEvent, IndexedEventSchema, IndexedPackage, IndexedTransaction,
+    PackageActivity, TxIndex, TxSharedObject,
 };
 use crate::PgConnectionPool;
 
@@ -75,6 +90,10 @@ pub struct PgIndexerStoreV2 {
     metrics: IndexerMetrics,
     parallel_chunk_size: usize,
     parallel_objects_chunk_size: usize,
+    /// Whether to record the order transactions are committed in, for exporters.
+    tx_insertion_order: bool,
+    /// The SuiNS package whose name records are indexed for search, if any.
+    name_service_package: Option<ObjectID>,
 }
 
 impl PgIndexerStoreV2 {
@@ -96,9 +115,25 @@ impl PgIndexerStoreV2 {
             metrics,
             parallel_chunk_size,
             parallel_objects_chunk_size,
+            tx_insertion_order: false,
+            name_service_package: None,
         }
     }
 
+    /// Record the order in which transactions are committed in `tx_insertion_order`, so that
+    /// [`crate::export::TxExporter`]s can follow it.
+    pub fn with_tx_insertion_order(mut self, tx_insertion_order: bool) -> Self {
+        self.tx_insertion_order = tx_insertion_order;
+        self
+    }
+
+    /// Index the names registered with the SuiNS package `name_service_package` for search,
+    /// see [`StoredSearchName::from_name_record`].
+    pub fn with_name_service_package(mut self, name_service_package: Option<ObjectID>) -> Self {
+        self.name_service_package = name_service_package;
+        self
+    }
+
     fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
         read_only_blocking!(&self.blocking_cp, |conn| {
             checkpoints::dsl::checkpoints
@@ -182,7 +217,13 @@ impl PgIndexerStoreV2 {
                             objects::df_name.eq(excluded(objects::df_name)),
                             objects::df_object_type.eq(excluded(objects::df_object_type)),
                             objects::df_object_id.eq(excluded(objects::df_object_id)),
+                            objects::storage_rebate.eq(excluded(objects::storage_rebate)),
+                            objects::object_type.eq(excluded(objects::object_type)),
                         ))
+                        // Versions never go back, even if checkpoints are committed out of order.
+                        // Replayed dead letter checkpoints are committed without their object
+                        // changes, as deletions can't be guarded against this way.
+                        .filter(objects::object_version.lt(excluded(objects::object_version)))
                         .execute(conn)
                         .map_err(IndexerError::from)
                         .context("Failed to write object mutation to PostgresDB")?;
@@ -221,7 +262,11 @@ impl PgIndexerStoreV2 {
         })
     }
 
-    fn persist_checkpoints(&self, checkpoints: Vec<IndexedCheckpoint>) -> Result<(), IndexerError> {
+    fn persist_checkpoints(
+        &self,
+        checkpoints: Vec<IndexedCheckpoint>,
+        content_hashes: Vec<(u64, [u8; 32])>,
+    ) -> Result<(), IndexerError> {
         if checkpoints.is_empty() {
             return Ok(());
         }
@@ -245,6 +290,72 @@ impl PgIndexerStoreV2 {
                         .map_err(IndexerError::from)
                         .context("Failed to write checkpoints to PostgresDB")?;
                 }
+                // Written in the same DB transaction as the checkpoints, which are committed
+                // after the rest of their data, so that exporters only see complete transactions.
+                if self.tx_insertion_order {
+                    let insertion_order = checkpoints
+                        .iter()
+                        .flat_map(NewTxInsertionOrder::from_checkpoint)
+                        .collect::<Vec<_>>();
+                    for insertion_order_chunk in
+                        insertion_order.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
+                    {
+                        diesel::insert_into(tx_insertion_order::table)
+                            .values(insertion_order_chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write tx_insertion_order to PostgresDB")?;
+                    }
+                }
+                // The changes to the objects owned by each address, logged as the objects of
+                // these checkpoints were written, are applied to their stats in one go.
+                diesel::sql_query(
+                    "WITH deltas AS (DELETE FROM address_object_stats_deltas RETURNING *) \
+                     INSERT INTO address_object_stats (owner_id, object_count, storage_rebate) \
+                     SELECT owner_id, SUM(object_count), SUM(storage_rebate) \
+                     FROM deltas GROUP BY owner_id \
+                     ON CONFLICT (owner_id) DO UPDATE \
+                     SET object_count = address_object_stats.object_count \
+                                      + EXCLUDED.object_count, \
+                         storage_rebate = address_object_stats.storage_rebate \
+                                        + EXCLUDED.storage_rebate",
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to update address object stats in PostgresDB")?;
+                // SuiNS names are no longer searchable once they expire.
+                if self.name_service_package.is_some() {
+                    let latest_timestamp_ms = checkpoints.iter().map(|c| c.timestamp_ms).max();
+                    diesel::delete(
+                        search_names::table
+                            .filter(search_names::kind.eq(SearchNameKind::NameService as i16))
+                            .filter(search_names::expiration_timestamp_ms.le(latest_timestamp_ms)),
+                    )
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to delete expired search names from PostgresDB")?;
+                }
+                // Replayed dead letter checkpoints leave the queue only once they are committed,
+                // and only if the content hash committed for them is the one they were indexed
+                // with, i.e. their data was not written by a different run in the meantime.
+                let (sequence_numbers, hashes): (Vec<_>, Vec<_>) = content_hashes
+                    .iter()
+                    .map(|(seq, hash)| (*seq as i64, hash.to_vec()))
+                    .unzip();
+                diesel::sql_query(
+                    "DELETE FROM dead_letter_checkpoints d \
+                     USING checkpoint_content_hashes h, \
+                           UNNEST($1::BIGINT[], $2::BYTEA[]) AS c(sequence_number, content_hash) \
+                     WHERE d.sequence_number = c.sequence_number \
+                       AND h.sequence_number = c.sequence_number \
+                       AND h.content_hash = c.content_hash",
+                )
+                .bind::<Array<BigInt>, _>(&sequence_numbers)
+                .bind::<Array<Bytea>, _>(&hashes)
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to delete committed dead letter checkpoints from PostgresDB")?;
                 Ok::<(), IndexerError>(())
             },
             Duration::from_secs(60)
@@ -336,6 +447,10 @@ impl PgIndexerStoreV2 {
             .metrics
             .checkpoint_db_commit_latency_packages
             .start_timer();
+        let search_names = packages
+            .iter()
+            .flat_map(StoredSearchName::from_package)
+            .collect::<Vec<_>>();
         let packages = packages
             .into_iter()
             .map(StoredPackage::from)
@@ -351,7 +466,12 @@ impl PgIndexerStoreV2 {
                         // TODO: race condition is possible here. Figure out how to avoid/detect
                         .on_conflict(packages::package_id)
                         .do_update()
-                        .set(packages::move_package.eq(excluded(packages::move_package)))
+                        .set((
+                            packages::move_package.eq(excluded(packages::move_package)),
+                            packages::checkpoint_sequence_number
+                                .eq(excluded(packages::checkpoint_sequence_number)),
+                            packages::publisher.eq(excluded(packages::publisher)),
+                        ))
                         .execute(conn)
                         .map_err(IndexerError::from)
                         .context("Failed to write packages to PostgresDB")?;
@@ -363,7 +483,60 @@ impl PgIndexerStoreV2 {
         .tap(|_| {
             let elapsed = guard.stop_and_record();
             info!(elapsed, "Persisted {} packages", packages.len())
-        })
+        })?;
+        self.persist_search_names(search_names, vec![])
+    }
+
+    /// Upserts `names`, and removes the SuiNS names whose records were deleted, i.e. those held
+    /// by `removed_object_ids`.
+    fn persist_search_names(
+        &self,
+        names: Vec<StoredSearchName>,
+        removed_object_ids: Vec<Vec<u8>>,
+    ) -> Result<(), IndexerError> {
+        if names.is_empty() && removed_object_ids.is_empty() {
+            return Ok(());
+        }
+        // A row can only be upserted once per statement, so keep the last of any duplicates
+        let names = names
+            .into_iter()
+            .map(|n| ((n.kind, n.full_name.clone()), n))
+            .collect::<HashMap<_, _>>()
+            .into_values()
+            .collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for names_chunk in names.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(search_names::table)
+                        .values(names_chunk)
+                        .on_conflict((search_names::kind, search_names::full_name))
+                        .do_update()
+                        .set((
+                            search_names::address.eq(excluded(search_names::address)),
+                            search_names::object_id.eq(excluded(search_names::object_id)),
+                            search_names::expiration_timestamp_ms
+                                .eq(excluded(search_names::expiration_timestamp_ms)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write search names to PostgresDB")?;
+                }
+                for removed_chunk in removed_object_ids.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::delete(
+                        search_names::table
+                            .filter(search_names::kind.eq(SearchNameKind::NameService as i16))
+                            .filter(search_names::object_id.eq_any(removed_chunk)),
+                    )
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to delete search names from PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| info!("Persisted {} search names", names.len()))
     }
 
     fn persist_tx_indices_chunk(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
@@ -396,6 +569,84 @@ impl PgIndexerStoreV2 {
         })
     }
 
+    fn persist_event_schemas(&self, schemas: Vec<IndexedEventSchema>) -> Result<(), IndexerError> {
+        // A layout is registered with the first checkpoint it was seen in, so only the earliest
+        // sighting of each layout in the batch is written.
+        let mut seen = HashSet::new();
+        let schemas = schemas
+            .into_iter()
+            .sorted_by_key(|schema| schema.checkpoint_sequence_number)
+            .filter(|schema| seen.insert((schema.event_type.clone(), schema.schema_version)))
+            .map(StoredEventSchema::from)
+            .collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for schemas_chunk in schemas.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(event_schemas::table)
+                        .values(schemas_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write event_schemas to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| info!("Persisted {} event_schemas", schemas.len()))
+    }
+
+    fn persist_tx_shared_objects(
+        &self,
+        shared_objects: Vec<TxSharedObject>,
+    ) -> Result<(), IndexerError> {
+        let shared_objects = shared_objects
+            .into_iter()
+            .map(StoredTxSharedObject::from)
+            .collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for shared_objects_chunk in shared_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
+                {
+                    diesel::insert_into(tx_shared_objects::table)
+                        .values(shared_objects_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_shared_objects to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| info!("Persisted {} tx_shared_objects", shared_objects.len()))
+    }
+
+    fn persist_package_activity(&self, activity: Vec<PackageActivity>) -> Result<(), IndexerError> {
+        let activity = activity
+            .into_iter()
+            .map(StoredPackageActivity::from)
+            .collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for activity_chunk in activity.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(package_activity::table)
+                        .values(activity_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write package_activity to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| info!("Persisted {} package_activity", activity.len()))
+    }
+
     fn persist_epoch(&self, data: &Vec<EpochToCommit>) -> Result<(), IndexerError> {
         if data.is_empty() {
             return Ok(());
@@ -481,6 +732,187 @@ impl PgIndexerStoreV2 {
         .map(|v| v as u64)
     }
 
+    fn persist_dead_letter_checkpoint(
+        &self,
+        checkpoint: StoredDeadLetterCheckpoint,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                // A checkpoint that fails again on replay replaces its previous entry, so that
+                // the latest error is kept.
+                diesel::insert_into(dead_letter_checkpoints::table)
+                    .values(&checkpoint)
+                    .on_conflict(dead_letter_checkpoints::sequence_number)
+                    .do_update()
+                    .set((
+                        dead_letter_checkpoints::error.eq(excluded(dead_letter_checkpoints::error)),
+                        dead_letter_checkpoints::failed_at_ms
+                            .eq(excluded(dead_letter_checkpoints::failed_at_ms)),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write dead letter checkpoint to PostgresDB")?;
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| {
+            info!(
+                checkpoint_seq = checkpoint.sequence_number,
+                "Persisted dead letter checkpoint"
+            )
+        })
+    }
+
+    fn get_dead_letter_checkpoints(&self) -> Result<Vec<CheckpointData>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            dead_letter_checkpoints::table
+                .order(dead_letter_checkpoints::sequence_number.asc())
+                .load::<StoredDeadLetterCheckpoint>(conn)
+        })
+        .context("Failed to read dead letter checkpoints from PostgresDB")?
+        .into_iter()
+        .map(CheckpointData::try_from)
+        .collect()
+    }
+
+    fn get_dead_letter_sequence_numbers(
+        &self,
+        first: u64,
+        last: u64,
+    ) -> Result<HashSet<u64>, IndexerError> {
+        let sequence_numbers = read_only_blocking!(&self.blocking_cp, |conn| {
+            dead_letter_checkpoints::table
+                .select(dead_letter_checkpoints::sequence_number)
+                .filter(dead_letter_checkpoints::sequence_number.between(first as i64, last as i64))
+                .load::<i64>(conn)
+        })
+        .context("Failed to read dead letter checkpoints from PostgresDB")?;
+        Ok(sequence_numbers.into_iter().map(|seq| seq as u64).collect())
+    }
+
+    fn persist_checkpoint_content_hashes(
+        &self,
+        content_hashes: Vec<StoredCheckpointContentHash>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in content_hashes.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    // Checkpoints past the last committed one may have been partially written by
+                    // a run that crashed, so their hashes are overwritten rather than kept.
+                    diesel::insert_into(checkpoint_content_hashes::table)
+                        .values(chunk)
+                        .on_conflict(checkpoint_content_hashes::sequence_number)
+                        .do_update()
+                        .set(
+                            checkpoint_content_hashes::content_hash
+                                .eq(excluded(checkpoint_content_hashes::content_hash)),
+                        )
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write checkpoint content hashes to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| {
+            info!(
+                "Persisted {} checkpoint content hashes",
+                content_hashes.len()
+            )
+        })
+    }
+
+    fn get_checkpoint_content_hashes(
+        &self,
+        first: u64,
+        last: u64,
+    ) -> Result<HashMap<u64, Vec<u8>>, IndexerError> {
+        let content_hashes = read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoint_content_hashes::table
+                .filter(
+                    checkpoint_content_hashes::sequence_number.between(first as i64, last as i64),
+                )
+                .load::<StoredCheckpointContentHash>(conn)
+        })
+        .context("Failed to read checkpoint content hashes from PostgresDB")?;
+        Ok(content_hashes
+            .into_iter()
+            .map(|h| (h.sequence_number as u64, h.content_hash))
+            .collect())
+    }
+
+    fn persist_change_feed_messages(
+        &self,
+        messages: Vec<StoredChangeFeedMessage>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in messages.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    // Checkpoints past the last committed one may have been written by a run
+                    // that crashed, along with their messages, which are the same.
+                    diesel::insert_into(change_feed_outbox::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write change feed messages to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| info!("Persisted {} change feed messages", messages.len()))
+    }
+
+    fn get_committed_change_feed_messages(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<StoredChangeFeedMessage>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            change_feed_outbox::table
+                .filter(exists(checkpoints::table.filter(
+                    checkpoints::sequence_number.eq(change_feed_outbox::checkpoint_sequence_number),
+                )))
+                .order((
+                    change_feed_outbox::checkpoint_sequence_number.asc(),
+                    change_feed_outbox::message_index.asc(),
+                ))
+                .limit(limit as i64)
+                .load::<StoredChangeFeedMessage>(conn)
+        })
+        .context("Failed to read change feed messages from PostgresDB")
+    }
+
+    fn delete_change_feed_message(
+        &self,
+        checkpoint_sequence_number: i64,
+        message_index: i16,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::delete(
+                    change_feed_outbox::table
+                        .filter(
+                            change_feed_outbox::checkpoint_sequence_number
+                                .eq(checkpoint_sequence_number),
+                        )
+                        .filter(change_feed_outbox::message_index.eq(message_index)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to delete change feed message from PostgresDB")?;
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
     async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
     where
         F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
@@ -543,6 +975,21 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
             .metrics
             .checkpoint_db_commit_latency_objects
             .start_timer();
+        let (search_names, removed_names) = match self.name_service_package {
+            Some(package) => (
+                object_changes
+                    .iter()
+                    .flat_map(|changes| &changes.changed_objects)
+                    .filter_map(|o| StoredSearchName::from_name_record(o, package))
+                    .collect::<Vec<_>>(),
+                object_changes
+                    .iter()
+                    .flat_map(|changes| &changes.deleted_objects)
+                    .map(|o| o.0.to_vec())
+                    .collect::<Vec<_>>(),
+            ),
+            None => (vec![], vec![]),
+        };
         let objects = make_final_list_of_objects_to_commit(object_changes);
         let len = objects.len();
         let chunks = chunk!(objects, self.parallel_objects_chunk_size);
@@ -563,15 +1010,24 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
             })?;
         let elapsed = guard.stop_and_record();
         info!(elapsed, "Persisted {} objects", len);
+        if !search_names.is_empty() || !removed_names.is_empty() {
+            self.execute_in_blocking_worker(move |this| {
+                this.persist_search_names(search_names, removed_names)
+            })
+            .await?;
+        }
         Ok(())
     }
 
     async fn persist_checkpoints(
         &self,
         checkpoints: Vec<IndexedCheckpoint>,
+        content_hashes: Vec<(u64, [u8; 32])>,
     ) -> Result<(), IndexerError> {
-        self.execute_in_blocking_worker(move |this| this.persist_checkpoints(checkpoints))
-            .await
+        self.execute_in_blocking_worker(move |this| {
+            this.persist_checkpoints(checkpoints, content_hashes)
+        })
+        .await
     }
 
     async fn persist_transactions(
@@ -635,6 +1091,17 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
         Ok(())
     }
 
+    async fn persist_event_schemas(
+        &self,
+        schemas: Vec<IndexedEventSchema>,
+    ) -> Result<(), IndexerError> {
+        if schemas.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_event_schemas(schemas))
+            .await
+    }
+
     async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
         if packages.is_empty() {
             return Ok(());
@@ -673,6 +1140,28 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
         Ok(())
     }
 
+    async fn persist_tx_shared_objects(
+        &self,
+        shared_objects: Vec<TxSharedObject>,
+    ) -> Result<(), IndexerError> {
+        if shared_objects.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_tx_shared_objects(shared_objects))
+            .await
+    }
+
+    async fn persist_package_activity(
+        &self,
+        activity: Vec<PackageActivity>,
+    ) -> Result<(), IndexerError> {
+        if activity.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_package_activity(activity))
+            .await
+    }
+
     async fn persist_epoch(&self, data: Vec<EpochToCommit>) -> Result<(), IndexerError> {
         self.execute_in_blocking_worker(move |this| this.persist_epoch(&data))
             .await
@@ -688,6 +1177,97 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
         .await
     }
 
+    async fn persist_dead_letter_checkpoint(
+        &self,
+        checkpoint: &CheckpointData,
+        error: String,
+    ) -> Result<(), IndexerError> {
+        let failed_at_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let checkpoint = StoredDeadLetterCheckpoint::new(checkpoint, error, failed_at_ms)?;
+        self.execute_in_blocking_worker(move |this| this.persist_dead_letter_checkpoint(checkpoint))
+            .await
+    }
+
+    async fn get_dead_letter_checkpoints(&self) -> Result<Vec<CheckpointData>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_dead_letter_checkpoints())
+            .await
+    }
+
+    async fn get_dead_letter_sequence_numbers(
+        &self,
+        first: u64,
+        last: u64,
+    ) -> Result<HashSet<u64>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.get_dead_letter_sequence_numbers(first, last)
+        })
+        .await
+    }
+
+    async fn persist_checkpoint_content_hashes(
+        &self,
+        content_hashes: Vec<(u64, [u8; 32])>,
+    ) -> Result<(), IndexerError> {
+        if content_hashes.is_empty() {
+            return Ok(());
+        }
+        let content_hashes = content_hashes
+            .into_iter()
+            .map(
+                |(sequence_number, content_hash)| StoredCheckpointContentHash {
+                    sequence_number: sequence_number as i64,
+                    content_hash: content_hash.to_vec(),
+                },
+            )
+            .collect();
+        self.execute_in_blocking_worker(move |this| {
+            this.persist_checkpoint_content_hashes(content_hashes)
+        })
+        .await
+    }
+
+    async fn get_checkpoint_content_hashes(
+        &self,
+        first: u64,
+        last: u64,
+    ) -> Result<HashMap<u64, Vec<u8>>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_checkpoint_content_hashes(first, last))
+            .await
+    }
+
+    async fn persist_change_feed_messages(
+        &self,
+        messages: Vec<StoredChangeFeedMessage>,
+    ) -> Result<(), IndexerError> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_change_feed_messages(messages))
+            .await
+    }
+
+    async fn get_committed_change_feed_messages(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<StoredChangeFeedMessage>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_committed_change_feed_messages(limit))
+            .await
+    }
+
+    async fn delete_change_feed_message(
+        &self,
+        checkpoint_sequence_number: i64,
+        message_index: i16,
+    ) -> Result<(), IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.delete_change_feed_message(checkpoint_sequence_number, message_index)
+        })
+        .await
+    }
+
     fn module_cache(&self) -> Arc<Self::ModuleCache> {
         self.module_cache.clone()
     }
@@ -740,3 +1320,7 @@ enum ObjectChangeToCommit {
     MutatedObject(StoredObject),
     DeletedObject(ObjectID),
 }
+
+#[cfg(all(test, feature = "pg_integration"))]
+#[path = "pg_indexer_store_v2_tests.rs"]
+mod pg_indexer_store_v2_tests;
@@ -0,0 +1,389 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests of `PgIndexerStoreV2` against a Postgres database, reset before each test. Run with
+//! `cargo test --package sui-indexer --features pg_integration -- --test-threads=1`.
+
+use diesel::connection::SimpleConnection;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use prometheus::Registry;
+use sui_types::base_types::{MoveObjectType, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::coin::Coin;
+use sui_types::crypto::AggregateAuthoritySignature;
+use sui_types::digests::{ObjectDigest, TransactionDigest};
+use sui_types::messages_checkpoint::CheckpointDigest;
+use sui_types::object::{MoveObject, Object, Owner};
+
+use super::*;
+use crate::get_pg_pool_connection;
+use crate::pg_integration::test_pool;
+use crate::schema_v2::address_object_stats;
+use crate::types_v2::{IndexedCheckpoint, IndexedObject};
+
+fn test_store() -> PgIndexerStoreV2 {
+    PgIndexerStoreV2::new(test_pool(), IndexerMetrics::new(&Registry::default()))
+}
+
+fn object_changes(checkpoint: u64, object: Object) -> TransactionObjectChangesToCommit {
+    TransactionObjectChangesToCommit {
+        changed_objects: vec![IndexedObject::from_object(checkpoint, object, None)],
+        deleted_objects: vec![],
+    }
+}
+
+fn checkpoint(sequence_number: u64) -> IndexedCheckpoint {
+    IndexedCheckpoint {
+        sequence_number,
+        checkpoint_digest: CheckpointDigest::random(),
+        epoch: 0,
+        tx_digests: vec![],
+        network_total_transactions: 0,
+        previous_checkpoint_digest: None,
+        timestamp_ms: 0,
+        total_gas_cost: 0,
+        computation_cost: 0,
+        storage_cost: 0,
+        storage_rebate: 0,
+        non_refundable_storage_fee: 0,
+        checkpoint_commitments: vec![],
+        validator_signature: AggregateAuthoritySignature::default(),
+        successful_tx_num: 0,
+        end_of_epoch_data: None,
+        end_of_epoch: false,
+    }
+}
+
+fn dead_letter(sequence_number: u64) -> StoredDeadLetterCheckpoint {
+    StoredDeadLetterCheckpoint {
+        sequence_number: sequence_number as i64,
+        raw_checkpoint: vec![],
+        error: "failed to index".to_string(),
+        failed_at_ms: 0,
+    }
+}
+
+fn live_version(store: &PgIndexerStoreV2, id: ObjectID) -> SequenceNumber {
+    store
+        .get_object_read(id, None)
+        .unwrap()
+        .into_object()
+        .unwrap()
+        .version()
+}
+
+#[tokio::test]
+async fn test_replayed_checkpoint_does_not_overwrite_newer_objects() {
+    let store = test_store();
+    let id = ObjectID::random();
+    let owner = SuiAddress::random_for_testing_only();
+    let version = SequenceNumber::from_u64;
+
+    // Checkpoint 2 is committed while checkpoint 1, which touched the same object, is in the
+    // dead letter queue.
+    store
+        .persist_objects(vec![object_changes(
+            2,
+            Object::with_id_owner_version_for_testing(id, version(5), owner),
+        )])
+        .await
+        .unwrap();
+    assert_eq!(live_version(&store, id), version(5));
+
+    // Replaying checkpoint 1 leaves the newer version in place.
+    store
+        .persist_objects(vec![object_changes(
+            1,
+            Object::with_id_owner_version_for_testing(id, version(3), owner),
+        )])
+        .await
+        .unwrap();
+    assert_eq!(live_version(&store, id), version(5));
+
+    // Later checkpoints still update it.
+    store
+        .persist_objects(vec![object_changes(
+            3,
+            Object::with_id_owner_version_for_testing(id, version(7), owner),
+        )])
+        .await
+        .unwrap();
+    assert_eq!(live_version(&store, id), version(7));
+}
+
+#[tokio::test]
+async fn test_dead_letter_checkpoints_cleared_on_commit() {
+    let store = test_store();
+    for seq in [1, 2, 3] {
+        store
+            .persist_dead_letter_checkpoint(dead_letter(seq))
+            .unwrap();
+    }
+
+    // Checkpoint 1 is replayed and its data persisted, but it is not committed yet.
+    store
+        .persist_checkpoint_content_hashes(vec![StoredCheckpointContentHash {
+            sequence_number: 1,
+            content_hash: vec![1; 32],
+        }])
+        .unwrap();
+    assert_eq!(
+        store.get_dead_letter_sequence_numbers(0, 10).unwrap(),
+        HashSet::from([1, 2, 3])
+    );
+
+    // Committing it removes it from the queue.
+    store
+        .persist_checkpoints(vec![checkpoint(1)], vec![(1, [1; 32])])
+        .unwrap();
+    assert_eq!(
+        store.get_dead_letter_sequence_numbers(0, 10).unwrap(),
+        HashSet::from([2, 3])
+    );
+
+    // Checkpoint 2 was persisted with different content than the commit expects, it stays.
+    store
+        .persist_checkpoint_content_hashes(vec![StoredCheckpointContentHash {
+            sequence_number: 2,
+            content_hash: vec![2; 32],
+        }])
+        .unwrap();
+    store
+        .persist_checkpoints(vec![checkpoint(2)], vec![(2, [3; 32])])
+        .unwrap();
+    assert_eq!(
+        store.get_dead_letter_sequence_numbers(0, 10).unwrap(),
+        HashSet::from([2, 3])
+    );
+}
+
+#[tokio::test]
+async fn test_change_feed_messages_published_once_committed() {
+    let store = test_store();
+    let message = |checkpoint: i64, message_index: i16| StoredChangeFeedMessage {
+        checkpoint_sequence_number: checkpoint,
+        message_index,
+        subject: "sui.transactions".to_string(),
+        payload: vec![],
+    };
+    let keys = |messages: Vec<StoredChangeFeedMessage>| {
+        messages
+            .into_iter()
+            .map(|m| (m.checkpoint_sequence_number, m.message_index))
+            .collect::<Vec<_>>()
+    };
+
+    store
+        .persist_change_feed_messages(vec![message(2, 0), message(1, 1), message(1, 0)])
+        .unwrap();
+    assert!(store
+        .get_committed_change_feed_messages(10)
+        .unwrap()
+        .is_empty());
+
+    // Only the messages of committed checkpoints are published, in order.
+    store
+        .persist_checkpoints(vec![checkpoint(1)], vec![])
+        .unwrap();
+    assert_eq!(
+        keys(store.get_committed_change_feed_messages(10).unwrap()),
+        vec![(1, 0), (1, 1)]
+    );
+    assert_eq!(
+        keys(store.get_committed_change_feed_messages(1).unwrap()),
+        vec![(1, 0)]
+    );
+
+    // Messages written again, when re-ingesting, are ignored.
+    store
+        .persist_change_feed_messages(vec![message(1, 0)])
+        .unwrap();
+    store.delete_change_feed_message(1, 0).unwrap();
+    store
+        .persist_checkpoints(vec![checkpoint(2)], vec![])
+        .unwrap();
+    assert_eq!(
+        keys(store.get_committed_change_feed_messages(10).unwrap()),
+        vec![(1, 1), (2, 0)]
+    );
+}
+
+#[tokio::test]
+async fn test_name_service_names_removed() {
+    let store = test_store().with_name_service_package(Some(ObjectID::random()));
+    let name = |domain: &str, object_id: ObjectID, expiration_timestamp_ms: i64| StoredSearchName {
+        kind: SearchNameKind::NameService as i16,
+        name: domain.to_string(),
+        full_name: domain.to_string(),
+        address: None,
+        object_id: Some(object_id.to_vec()),
+        expiration_timestamp_ms: Some(expiration_timestamp_ms),
+    };
+    let names = |store: &PgIndexerStoreV2| {
+        read_only_blocking!(&store.blocking_cp, |conn| {
+            search_names::table
+                .select(search_names::full_name)
+                .order_by(search_names::full_name)
+                .load::<String>(conn)
+        })
+        .unwrap()
+    };
+
+    let deleted = ObjectID::random();
+    store
+        .persist_search_names(
+            vec![
+                name("deleted.sui", deleted, 2000),
+                name("expired.sui", ObjectID::random(), 1000),
+                name("live.sui", ObjectID::random(), 2000),
+            ],
+            vec![],
+        )
+        .unwrap();
+    assert_eq!(
+        names(&store),
+        vec!["deleted.sui", "expired.sui", "live.sui"]
+    );
+
+    // Names are removed when the objects holding their records are deleted.
+    store
+        .persist_objects(vec![TransactionObjectChangesToCommit {
+            changed_objects: vec![],
+            deleted_objects: vec![(deleted, SequenceNumber::from_u64(2), ObjectDigest::random())],
+        }])
+        .await
+        .unwrap();
+    assert_eq!(names(&store), vec!["expired.sui", "live.sui"]);
+
+    // ...and once a checkpoint has been committed at or after they expire.
+    let mut expiring = checkpoint(1);
+    expiring.timestamp_ms = 1000;
+    store.persist_checkpoints(vec![expiring], vec![]).unwrap();
+    assert_eq!(names(&store), vec!["live.sui"]);
+}
+
+#[tokio::test]
+async fn test_address_object_stats() {
+    let store = test_store();
+    let stats = |store: &PgIndexerStoreV2, owner: SuiAddress| {
+        read_only_blocking!(&store.blocking_cp, |conn| {
+            address_object_stats::table
+                .select((
+                    address_object_stats::object_count,
+                    address_object_stats::storage_rebate,
+                ))
+                .filter(address_object_stats::owner_id.eq(owner.to_vec()))
+                .first::<(i64, i64)>(conn)
+                .optional()
+        })
+        .unwrap()
+    };
+    let object = |id: ObjectID, version: u64, owner: SuiAddress, storage_rebate: u64| {
+        let mut object =
+            Object::with_id_owner_version_for_testing(id, SequenceNumber::from_u64(version), owner);
+        object.storage_rebate = storage_rebate;
+        object
+    };
+
+    let (a, b) = (
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    );
+    let (x, y) = (ObjectID::random(), ObjectID::random());
+    store
+        .persist_objects(vec![
+            object_changes(1, object(x, 1, a, 100)),
+            object_changes(1, object(y, 1, a, 50)),
+        ])
+        .await
+        .unwrap();
+    // The stats only change once the checkpoint is committed.
+    assert_eq!(stats(&store, a), None);
+    store
+        .persist_checkpoints(vec![checkpoint(1)], vec![])
+        .unwrap();
+    assert_eq!(stats(&store, a), Some((2, 150)));
+
+    // One object is deleted, and the other transferred and resized.
+    store
+        .persist_objects(vec![
+            object_changes(2, object(y, 2, b, 70)),
+            TransactionObjectChangesToCommit {
+                changed_objects: vec![],
+                deleted_objects: vec![(x, SequenceNumber::from_u64(2), ObjectDigest::random())],
+            },
+        ])
+        .await
+        .unwrap();
+    store
+        .persist_checkpoints(vec![checkpoint(2)], vec![])
+        .unwrap();
+    assert_eq!(stats(&store, a), Some((0, 0)));
+    assert_eq!(stats(&store, b), Some((1, 70)));
+}
+
+#[test]
+fn test_object_type_backfill() {
+    let pool = test_pool();
+    let mut conn = get_pg_pool_connection(&pool).unwrap();
+    let custom = StructTag {
+        address: AccountAddress::from_hex_literal("0xbeef").unwrap(),
+        module: Identifier::new("m").unwrap(),
+        name: Identifier::new("T").unwrap(),
+        type_params: vec![],
+    };
+
+    let types = [
+        MoveObjectType::gas_coin(),
+        MoveObjectType::staked_sui(),
+        Coin::type_(TypeTag::Struct(Box::new(custom.clone()))).into(),
+        StructTag {
+            type_params: vec![
+                TypeTag::Vector(Box::new(TypeTag::U8)),
+                TypeTag::U64,
+                TypeTag::Struct(Box::new(custom.clone())),
+            ],
+            ..custom
+        }
+        .into(),
+    ];
+
+    // Objects indexed before `object_type` was added only have their serialized contents.
+    let mut expected = vec![];
+    for type_ in types {
+        let object = MoveObject::new_coin(type_, SequenceNumber::new(), ObjectID::random(), 1);
+        let object = Object::new_move(
+            object,
+            Owner::AddressOwner(SuiAddress::ZERO),
+            TransactionDigest::genesis(),
+        );
+        let stored = StoredObject::from(IndexedObject::from_object(0, object, None));
+        expected.push((
+            stored.object_id.clone(),
+            stored.object_type.clone().unwrap(),
+        ));
+        diesel::insert_into(objects::table)
+            .values(StoredObject {
+                object_type: None,
+                ..stored
+            })
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    // The migration that added the column backfills it, and can be run again to do so.
+    conn.batch_execute(include_str!(
+        "../../migrations_v2/2023-10-16-041907_objects_object_type/up.sql"
+    ))
+    .unwrap();
+
+    for (object_id, object_type) in expected {
+        let backfilled: Option<String> = objects::table
+            .select(objects::object_type)
+            .filter(objects::object_id.eq(object_id))
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(backfilled, Some(object_type));
+    }
+}
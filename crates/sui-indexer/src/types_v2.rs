@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::errors::IndexerError;
+use fastcrypto::hash::{Blake2b256, HashFunction};
 use move_core_types::language_storage::StructTag;
+use move_core_types::value::MoveStructLayout;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sui_json_rpc_types::ObjectChange;
@@ -175,6 +177,8 @@ pub struct IndexedEvent {
     pub event_type: String,
     pub bcs: Vec<u8>,
     pub timestamp_ms: u64,
+    /// Set once the layout of `event_type` has been resolved, see `IndexedEventSchema`.
+    pub schema_version: Option<[u8; 32]>,
 }
 
 impl IndexedEvent {
@@ -197,6 +201,33 @@ impl IndexedEvent {
             event_type: event.type_.to_string(),
             bcs: event.contents.clone(),
             timestamp_ms,
+            schema_version: None,
+        }
+    }
+}
+
+/// The layout of an event type, registered so that events can be decoded with the layout they
+/// were emitted with, even once their type resolves to a different layout.
+#[derive(Debug, Clone)]
+pub struct IndexedEventSchema {
+    pub event_type: String,
+    pub schema_version: [u8; 32],
+    pub layout: MoveStructLayout,
+    pub checkpoint_sequence_number: u64,
+}
+
+impl IndexedEventSchema {
+    pub fn new(
+        event_type: String,
+        layout: MoveStructLayout,
+        checkpoint_sequence_number: u64,
+    ) -> Self {
+        let bytes = bcs::to_bytes(&layout).expect("Serializing a layout should not fail.");
+        Self {
+            event_type,
+            schema_version: Blake2b256::digest(bytes).digest,
+            layout,
+            checkpoint_sequence_number,
         }
     }
 }
@@ -225,7 +256,7 @@ pub enum DynamicFieldKind {
     DynamicObject = 1,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct IndexedObject {
     pub object_id: ObjectID,
     pub object_version: u64,
@@ -268,11 +299,13 @@ impl IndexedObject {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct IndexedPackage {
     pub package_id: ObjectID,
     pub move_package: MovePackage,
     pub checkpoint_sequence_number: u64,
+    /// Sender of the transaction that published or upgraded the package.
+    pub publisher: SuiAddress,
 }
 
 #[derive(Debug, Clone)]
@@ -309,6 +342,30 @@ pub struct TxIndex {
     pub move_calls: Vec<(ObjectID, String, String)>,
 }
 
+/// The version of a shared object that a transaction was sequenced against.
+#[derive(Debug, Clone)]
+pub struct TxSharedObject {
+    pub tx_sequence_number: u64,
+    pub checkpoint_sequence_number: u64,
+    pub transaction_digest: TransactionDigest,
+    pub object_id: ObjectID,
+    pub version: SequenceNumber,
+    pub mutable: bool,
+}
+
+/// The calls that a sender made to a package in a checkpoint, the events the package emitted in
+/// those transactions, and the gas used by the transactions that made them.
+#[derive(Debug, Clone)]
+pub struct PackageActivity {
+    pub package: ObjectID,
+    pub sender: SuiAddress,
+    pub checkpoint_sequence_number: u64,
+    pub timestamp_ms: u64,
+    pub calls: u64,
+    pub events: u64,
+    pub gas_used: u64,
+}
+
 // ObjectChange is not bcs deserializable, IndexedObjectChange is.
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
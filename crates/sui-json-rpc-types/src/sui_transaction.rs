@@ -289,6 +289,8 @@ pub enum SuiTransactionBlockKind {
     AuthenticatorStateUpdate(SuiAuthenticatorStateUpdate),
     /// The transaction which occurs only at the end of the epoch
     EndOfEpochTransaction(SuiEndOfEpochTransaction),
+    /// A transaction which records the latest output of the randomness beacon
+    RandomnessStateUpdate(SuiRandomnessStateUpdate),
     // .. more transaction types go here
 }
 
@@ -325,6 +327,9 @@ impl Display for SuiTransactionBlockKind {
             Self::EndOfEpochTransaction(_) => {
                 writeln!(writer, "Transaction Kind : End of Epoch Transaction")?;
             }
+            Self::RandomnessStateUpdate(_) => {
+                writeln!(writer, "Transaction Kind : Randomness State Update")?;
+            }
         }
         write!(f, "{}", writer)
     }
@@ -369,6 +374,9 @@ impl SuiTransactionBlockKind {
                             EndOfEpochTransactionKind::AuthenticatorStateCreate => {
                                 SuiEndOfEpochTransactionKind::AuthenticatorStateCreate
                             }
+                            EndOfEpochTransactionKind::RandomnessStateCreate => {
+                                SuiEndOfEpochTransactionKind::RandomnessStateCreate
+                            }
                             EndOfEpochTransactionKind::AuthenticatorStateExpire(expire) => {
                                 SuiEndOfEpochTransactionKind::AuthenticatorStateExpire(
                                     SuiAuthenticatorStateExpire {
@@ -380,6 +388,13 @@ impl SuiTransactionBlockKind {
                         .collect(),
                 })
             }
+            TransactionKind::RandomnessStateUpdate(update) => {
+                Self::RandomnessStateUpdate(SuiRandomnessStateUpdate {
+                    epoch: update.epoch,
+                    randomness_round: update.randomness_round,
+                    random_bytes: update.random_bytes,
+                })
+            }
         })
     }
 
@@ -398,6 +413,7 @@ impl SuiTransactionBlockKind {
             Self::ProgrammableTransaction(_) => "ProgrammableTransaction",
             Self::AuthenticatorStateUpdate(_) => "AuthenticatorStateUpdate",
             Self::EndOfEpochTransaction(_) => "EndOfEpochTransaction",
+            Self::RandomnessStateUpdate(_) => "RandomnessStateUpdate",
         }
     }
 }
@@ -728,6 +744,7 @@ impl Display for SuiTransactionBlockEffects {
     }
 }
 
+#[serde_as]
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DryRunTransactionBlockResponse {
@@ -736,6 +753,13 @@ pub struct DryRunTransactionBlockResponse {
     pub object_changes: Vec<ObjectChange>,
     pub balance_changes: Vec<BalanceChange>,
     pub input: SuiTransactionBlockData,
+    /// Computation gas units used by each command of a programmable transaction, by command index.
+    /// They are not multiplied by the gas price, and are not rounded like the computation cost in
+    /// the effects. Commands after one that failed are not listed.
+    #[schemars(with = "Vec<BigInt<u64>>")]
+    #[serde_as(as = "Vec<BigInt<u64>>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_gas_used: Vec<u64>,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -1089,6 +1113,19 @@ pub struct SuiAuthenticatorStateUpdate {
     pub new_active_jwks: Vec<SuiActiveJwk>,
 }
 
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SuiRandomnessStateUpdate {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub randomness_round: u64,
+
+    pub random_bytes: Vec<u8>,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct SuiEndOfEpochTransaction {
@@ -1101,6 +1138,7 @@ pub enum SuiEndOfEpochTransactionKind {
     ChangeEpoch(SuiChangeEpoch),
     AuthenticatorStateCreate,
     AuthenticatorStateExpire(SuiAuthenticatorStateExpire),
+    RandomnessStateCreate,
 }
 
 #[serde_as]
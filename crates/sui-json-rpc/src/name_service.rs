@@ -107,7 +107,7 @@ impl fmt::Display for Domain {
 
 /// A single record in the registry.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
-struct NameRecord {
+pub struct NameRecord {
     /// The ID of the `RegistrationNFT` assigned to this record.
     ///
     /// The owner of the corrisponding `RegistrationNFT` has the rights to
@@ -115,11 +115,11 @@ struct NameRecord {
     ///
     /// It is possible that the ID changes if the record expires and is
     /// purchased by someone else.
-    nft_id: ID,
+    pub nft_id: ID,
     /// Timestamp in milliseconds when the record expires.
-    expiration_timestamp_ms: u64,
+    pub expiration_timestamp_ms: u64,
     /// The target address that this domain points to
-    target_address: Option<SuiAddress>,
+    pub target_address: Option<SuiAddress>,
     /// Additional data which may be stored in a record
-    data: VecMap<String, String>,
+    pub data: VecMap<String, String>,
 }
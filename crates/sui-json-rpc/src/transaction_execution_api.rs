@@ -262,6 +262,7 @@ impl TransactionExecutionApi {
             object_changes,
             balance_changes,
             input: resp.input,
+            command_gas_used: resp.command_gas_used,
         })
     }
 }
@@ -23,7 +23,9 @@ use move_compiler::{
     compiled_unit::{
         AnnotatedCompiledModule, AnnotatedCompiledScript, CompiledUnitEnum, NamedCompiledModule,
     },
-    diagnostics::{report_diagnostics_to_color_buffer, report_warnings},
+    diagnostics::{
+        report_diagnostics_to_color_buffer, report_warnings, Diagnostics, FilesSourceText,
+    },
     expansion::ast::{AttributeName_, Attributes},
     shared::known_attributes::KnownAttribute,
     typing::visitor::TypingVisitor,
@@ -44,7 +46,9 @@ use move_package::{
     resolution::resolution_graph::Package, source_package::parsed_manifest::CustomDepInfo,
 };
 use move_symbol_pool::Symbol;
+use move_vm_config::verifier::VerifierConfig;
 use serde_reflection::Registry;
+use sui_protocol_config::ProtocolConfig;
 use sui_types::{
     base_types::ObjectID,
     error::{SuiError, SuiResult},
@@ -56,9 +60,10 @@ use sui_verifier::verifier as sui_bytecode_verifier;
 
 use crate::linters::{
     coin_field::CoinFieldVisitor, collection_equality::CollectionEqualityVisitor,
-    custom_state_change::CustomStateChangeVerifier, freeze_wrapped::FreezeWrappedVisitor,
+    custom_state_change::CustomStateChangeVerifier,
+    duplicate_struct_layout::DuplicateStructLayoutVisitor, freeze_wrapped::FreezeWrappedVisitor,
     known_filters, self_transfer::SelfTransferVerifier, share_owned::ShareOwnedVerifier,
-    LINT_WARNING_PREFIX,
+    unconditional_abort::UnconditionalAbortVerifier, LINT_WARNING_PREFIX,
 };
 
 #[cfg(test)]
@@ -133,10 +138,17 @@ impl BuildConfig {
         resolution_graph: ResolvedGraph,
         lint: bool,
         writer: &mut W,
+        diagnostics: &mut BTreeSet<String>,
     ) -> anyhow::Result<(MoveCompiledPackage, FnInfoMap)> {
+        let verifier_limits = resolution_graph.build_options.verifier_limits;
         let build_plan = BuildPlan::create(resolution_graph)?;
         let mut fn_info = None;
         let compiled_pkg = build_plan.compile_with_driver(writer, |compiler| {
+            let compiler = if verifier_limits {
+                compiler.set_verifier_config(verifier_limits_config())
+            } else {
+                compiler
+            };
             let (files, units_res) = if lint {
                 let lint_visitors = vec![
                     ShareOwnedVerifier.visitor(),
@@ -145,6 +157,8 @@ impl BuildConfig {
                     CoinFieldVisitor.visitor(),
                     FreezeWrappedVisitor.visitor(),
                     CollectionEqualityVisitor.visitor(),
+                    UnconditionalAbortVerifier.visitor(),
+                    DuplicateStructLayoutVisitor.visitor(),
                 ];
                 let (filter_attr_name, filters) = known_filters();
                 compiler
@@ -156,6 +170,7 @@ impl BuildConfig {
             };
             match units_res {
                 Ok((units, warning_diags)) => {
+                    diagnostics.extend(summarize_diagnostics(&files, &warning_diags));
                     let any_linter_warnings = warning_diags.any_with_prefix(LINT_WARNING_PREFIX);
                     let (filtered_diags_num, filtered_categories) =
                         warning_diags.filtered_source_diags_with_prefix(LINT_WARNING_PREFIX);
@@ -171,6 +186,7 @@ impl BuildConfig {
                 }
                 Err(error_diags) => {
                     assert!(!error_diags.is_empty());
+                    diagnostics.extend(summarize_diagnostics(&files, &error_diags));
                     let any_linter_warnings = error_diags.any_with_prefix(LINT_WARNING_PREFIX);
                     let (filtered_diags_num, filtered_categories) =
                         error_diags.filtered_source_diags_with_prefix(LINT_WARNING_PREFIX);
@@ -194,22 +210,44 @@ impl BuildConfig {
     /// Given a `path` and a `build_config`, build the package in that path, including its dependencies.
     /// If we are building the Sui framework, we skip the check that the addresses should be 0
     pub fn build(self, path: PathBuf) -> SuiResult<CompiledPackage> {
+        self.build_with_diagnostics(path).0
+    }
+
+    /// Like `build`, but also returns one line summaries of the diagnostics that compiling the
+    /// package produced, whether or not it succeeded.
+    pub fn build_with_diagnostics(
+        self,
+        path: PathBuf,
+    ) -> (SuiResult<CompiledPackage>, BTreeSet<String>) {
         let lint = self.lint;
         let print_diags_to_stderr = self.print_diags_to_stderr;
         let run_bytecode_verifier = self.run_bytecode_verifier;
-        let resolution_graph = self.resolution_graph(&path)?;
-        build_from_resolution_graph(
+        let resolution_graph = match self.resolution_graph(&path) {
+            Ok(graph) => graph,
+            Err(e) => return (Err(e), BTreeSet::new()),
+        };
+
+        let mut diagnostics = BTreeSet::new();
+        let result = build_from_resolution_graph_impl(
             path,
             resolution_graph,
             run_bytecode_verifier,
             print_diags_to_stderr,
             lint,
-        )
+            &mut diagnostics,
+        );
+        (result, diagnostics)
     }
 
     pub fn resolution_graph(mut self, path: &Path) -> SuiResult<ResolvedGraph> {
         use move_compiler::editions::Flavor;
 
+        // Record the version of Sui that builds the package, rather than the Move compiler's own
+        // version, which does not change between releases.
+        self.config
+            .toolchain_version
+            .get_or_insert_with(|| env!("CARGO_PKG_VERSION").to_string());
+
         let flavor = self.config.default_flavor.get_or_insert(Flavor::Sui);
         if flavor != &Flavor::Sui {
             return Err(SuiError::ModuleBuildFailure {
@@ -240,13 +278,31 @@ pub fn build_from_resolution_graph(
     run_bytecode_verifier: bool,
     print_diags_to_stderr: bool,
     lint: bool,
+) -> SuiResult<CompiledPackage> {
+    build_from_resolution_graph_impl(
+        path,
+        resolution_graph,
+        run_bytecode_verifier,
+        print_diags_to_stderr,
+        lint,
+        &mut BTreeSet::new(),
+    )
+}
+
+fn build_from_resolution_graph_impl(
+    path: PathBuf,
+    resolution_graph: ResolvedGraph,
+    run_bytecode_verifier: bool,
+    print_diags_to_stderr: bool,
+    lint: bool,
+    diagnostics: &mut BTreeSet<String>,
 ) -> SuiResult<CompiledPackage> {
     let (published_at, dependency_ids) = gather_published_ids(&resolution_graph);
 
     let result = if print_diags_to_stderr {
-        BuildConfig::compile_package(resolution_graph, lint, &mut std::io::stderr())
+        BuildConfig::compile_package(resolution_graph, lint, &mut std::io::stderr(), diagnostics)
     } else {
-        BuildConfig::compile_package(resolution_graph, lint, &mut std::io::sink())
+        BuildConfig::compile_package(resolution_graph, lint, &mut std::io::sink(), diagnostics)
     };
     // write build failure diagnostics to stderr, convert `error` to `String` using `Debug`
     // format to include anyhow's error context chain.
@@ -278,6 +334,48 @@ pub fn build_from_resolution_graph(
     })
 }
 
+/// The limits that the bytecode verifier enforces on modules published at the latest protocol
+/// version, for the compiler to warn about modules that come close to them. Checking them runs
+/// the bytecode verifier on every module, so it is only done when the build asks for it.
+fn verifier_limits_config() -> VerifierConfig {
+    let protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+    VerifierConfig {
+        max_basic_blocks: Some(protocol_config.max_basic_blocks() as usize),
+        max_type_nodes: Some(protocol_config.max_type_nodes() as usize),
+        max_back_edges_per_function: Some(protocol_config.max_back_edges_per_function() as usize),
+        max_back_edges_per_module: Some(protocol_config.max_back_edges_per_module() as usize),
+        max_per_fun_meter_units: Some(
+            protocol_config.max_verifier_meter_ticks_per_function() as u128
+        ),
+        max_per_mod_meter_units: Some(protocol_config.max_meter_ticks_per_module() as u128),
+        enforce_package_visibility: protocol_config.enforce_package_visibility(),
+        ..VerifierConfig::default()
+    }
+}
+
+/// Summarizes each of `diags` on one line, as `<severity> <file>:<line>:<column>: <message>: <label>`,
+/// so that the diagnostics of two builds can be compared.
+fn summarize_diagnostics<'a>(
+    files: &'a FilesSourceText,
+    diags: &Diagnostics,
+) -> impl Iterator<Item = String> + 'a {
+    diags.clone().into_codespan_format().into_iter().map(
+        move |(severity, message, (loc, label), _, _)| {
+            let position = files.get(&loc.file_hash()).and_then(|(name, source)| {
+                let prefix = source.get(..loc.start() as usize)?;
+                let line = prefix.matches('\n').count() + 1;
+                let column = prefix.len() - prefix.rfind('\n').map_or(0, |i| i + 1) + 1;
+                Some(format!("{name}:{line}:{column}"))
+            });
+            format!(
+                "{} {}: {message}: {label}",
+                format!("{severity:?}").to_lowercase(),
+                position.as_deref().unwrap_or("<unknown>"),
+            )
+        },
+    )
+}
+
 impl CompiledPackage {
     /// Return all of the bytecode modules in this package (not including direct or transitive deps)
     /// Note: these are not topologically sorted by dependency--use `get_dependency_sorted_modules` to produce a list of modules suitable
@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags structs in different modules of the same package that have the same fields
+//! (names and types, in the same order), type parameters and abilities. These are most often
+//! accidental copies of one another that should be consolidated into a single definition, so that
+//! values of the type can be passed between the modules.
+
+use std::collections::BTreeMap;
+
+use move_compiler::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    naming::ast as N,
+    shared::{program_info::TypingProgramInfo, CompilationEnv},
+    typing::{ast as T, visitor::TypingVisitor},
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+
+use super::{
+    has_test_attribute, LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX,
+};
+
+const DUPLICATE_STRUCT_LAYOUT_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::DuplicateStructLayout as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "duplicate struct layout",
+);
+
+pub struct DuplicateStructLayoutVisitor;
+
+/// Everything that makes up the layout of a struct: its abilities, its type parameters (whether
+/// they are phantom and their constraints) and its fields in declaration order. Type parameters in
+/// field types are referred to by position, so that their names do not matter.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct StructLayout {
+    package: Option<Symbol>,
+    abilities: Vec<String>,
+    type_parameters: Vec<(bool, Vec<String>)>,
+    fields: Vec<(Symbol, String)>,
+}
+
+struct StructSite {
+    mident: ModuleIdent,
+    name: Symbol,
+    loc: Loc,
+    module_warning_filter: WarningFilters,
+    warning_filter: WarningFilters,
+}
+
+impl TypingVisitor for DuplicateStructLayoutVisitor {
+    fn visit(
+        &mut self,
+        env: &mut CompilationEnv,
+        _program_info: &TypingProgramInfo,
+        program: &mut T::Program_,
+    ) {
+        let mut layouts: BTreeMap<StructLayout, Vec<StructSite>> = BTreeMap::new();
+        for (mident, mdef) in program.modules.key_cloned_iter() {
+            if !mdef.is_source_module || has_test_attribute(&mdef.attributes) {
+                continue;
+            }
+            for (sloc, sname, sdef) in mdef.structs.iter() {
+                if has_test_attribute(&sdef.attributes) {
+                    continue;
+                }
+                let Some(layout) = struct_layout(mdef.package_name, sdef) else {
+                    continue;
+                };
+                layouts.entry(layout).or_default().push(StructSite {
+                    mident,
+                    name: *sname,
+                    loc: sloc,
+                    module_warning_filter: mdef.warning_filter.clone(),
+                    warning_filter: sdef.warning_filter.clone(),
+                });
+            }
+        }
+
+        for sites in layouts.into_values() {
+            let Some((first, duplicates)) = sites.split_first() else {
+                continue;
+            };
+            for site in duplicates.iter().filter(|site| site.mident != first.mident) {
+                env.add_warning_filter_scope(site.module_warning_filter.clone());
+                env.add_warning_filter_scope(site.warning_filter.clone());
+                let msg = format!(
+                    "'{}::{}' has the same fields and abilities as '{}::{}'",
+                    site.mident.value.module, site.name, first.mident.value.module, first.name,
+                );
+                let first_msg = "Consider using this struct in both modules instead";
+                env.add_diag(diag!(
+                    DUPLICATE_STRUCT_LAYOUT_DIAG,
+                    (site.loc, msg),
+                    (first.loc, first_msg)
+                ));
+                env.pop_warning_filter_scope();
+                env.pop_warning_filter_scope();
+            }
+        }
+    }
+}
+
+/// Returns None for native structs and structs without fields, which are typically witnesses and
+/// are expected to look alike.
+fn struct_layout(package: Option<Symbol>, sdef: &N::StructDefinition) -> Option<StructLayout> {
+    let N::StructFields::Defined(sfields) = &sdef.fields else {
+        return None;
+    };
+    if sfields.is_empty() {
+        return None;
+    }
+    let tparams: Vec<_> = sdef.type_parameters.iter().map(|tp| &tp.param).collect();
+    let mut fields: Vec<_> = sfields
+        .iter()
+        .map(|(_, fname, (idx, ftype))| (*idx, *fname, type_key(ftype, &tparams)))
+        .collect();
+    fields.sort_by_key(|(idx, _, _)| *idx);
+    Some(StructLayout {
+        package,
+        abilities: sdef.abilities.iter().map(|a| a.value.to_string()).collect(),
+        type_parameters: sdef
+            .type_parameters
+            .iter()
+            .map(|tp| {
+                let abilities = tp.param.abilities.iter();
+                (
+                    tp.is_phantom,
+                    abilities.map(|a| a.value.to_string()).collect(),
+                )
+            })
+            .collect(),
+        fields: fields
+            .into_iter()
+            .map(|(_, fname, ftype)| (fname, ftype))
+            .collect(),
+    })
+}
+
+fn type_key(sp!(_, t): &N::Type, tparams: &[&N::TParam]) -> String {
+    use N::Type_ as T;
+    match t {
+        T::Unit => "()".to_string(),
+        T::Ref(mut_, inner) => {
+            let prefix = if *mut_ { "&mut " } else { "&" };
+            format!("{prefix}{}", type_key(inner, tparams))
+        }
+        T::Param(tp) => match tparams.iter().position(|p| p.id == tp.id) {
+            Some(idx) => format!("#{idx}"),
+            None => tp.user_specified_name.value.to_string(),
+        },
+        T::Apply(_, sp!(_, tname), targs) => {
+            if targs.is_empty() {
+                return tname.to_string();
+            }
+            let targs: Vec<_> = targs.iter().map(|t| type_key(t, tparams)).collect();
+            format!("{tname}<{}>", targs.join(", "))
+        }
+        T::Var(_) | T::Anything | T::UnresolvedError => "_".to_string(),
+    }
+}
@@ -1,15 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use move_compiler::{diagnostics::codes::WarningFilter, expansion::ast as E, naming::ast as N};
+use move_compiler::{
+    diagnostics::codes::WarningFilter,
+    expansion::ast as E,
+    naming::ast as N,
+    shared::known_attributes::{KnownAttribute, TestingAttribute},
+};
 use move_ir_types::location::Loc;
 
 pub mod coin_field;
 pub mod collection_equality;
 pub mod custom_state_change;
+pub mod duplicate_struct_layout;
 pub mod freeze_wrapped;
 pub mod self_transfer;
 pub mod share_owned;
+pub mod unconditional_abort;
 
 pub const SUI_PKG_NAME: &str = "sui";
 
@@ -57,6 +64,8 @@ pub const CUSTOM_STATE_CHANGE_FILTER_NAME: &str = "custom_state_change";
 pub const COIN_FIELD_FILTER_NAME: &str = "coin_field";
 pub const FREEZE_WRAPPED_FILTER_NAME: &str = "freeze_wrapped";
 pub const COLLECTION_EQUALITY_FILTER_NAME: &str = "collection_equality";
+pub const UNCONDITIONAL_ABORT_FILTER_NAME: &str = "unconditional_abort";
+pub const DUPLICATE_STRUCT_LAYOUT_FILTER_NAME: &str = "duplicate_struct_layout";
 
 pub const INVALID_LOC: Loc = Loc::invalid();
 
@@ -67,6 +76,8 @@ pub enum LinterDiagCategory {
     CoinField,
     FreezeWrapped,
     CollectionEquality,
+    UnconditionalAbort,
+    DuplicateStructLayout,
 }
 
 /// A default code for each linter category (as long as only one code per category is used, no other
@@ -114,6 +125,18 @@ pub fn known_filters() -> (E::AttributeName_, Vec<WarningFilter>) {
                 LINTER_DEFAULT_DIAG_CODE,
                 Some(COLLECTION_EQUALITY_FILTER_NAME),
             ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::UnconditionalAbort as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(UNCONDITIONAL_ABORT_FILTER_NAME),
+            ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::DuplicateStructLayout as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(DUPLICATE_STRUCT_LAYOUT_FILTER_NAME),
+            ),
         ],
     )
 }
@@ -126,3 +149,15 @@ pub fn base_type(t: &N::Type) -> Option<&N::Type> {
         T::Unit | T::Var(_) | T::Anything | T::UnresolvedError => None,
     }
 }
+
+/// Whether the item is test code, i.e. it has a `#[test]` or `#[test_only]` attribute.
+pub fn has_test_attribute(attributes: &E::Attributes) -> bool {
+    attributes.iter().any(|(_, attr_, _)| {
+        matches!(
+            attr_,
+            E::AttributeName_::Known(KnownAttribute::Testing(
+                TestingAttribute::TestOnly | TestingAttribute::Test
+            ))
+        )
+    })
+}
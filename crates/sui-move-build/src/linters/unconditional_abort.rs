@@ -0,0 +1,107 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags public and entry functions that abort on every path through their body.
+//! Such functions are most often leftover stubs, and once published they can only be fixed by an
+//! upgrade.
+
+use std::collections::BTreeSet;
+
+use move_compiler::{
+    cfgir::{
+        ast::Program,
+        cfg::{ImmForwardCFG, CFG},
+        visitor::AbstractInterpreterVisitor,
+        CFGContext, MemberName,
+    },
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        Diagnostics,
+    },
+    expansion::ast::Visibility,
+    hlir::ast::Command_,
+    shared::CompilationEnv,
+};
+use move_ir_types::location::*;
+
+use super::{
+    has_test_attribute, LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX,
+};
+
+const UNCONDITIONAL_ABORT_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::UnconditionalAbort as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "function always aborts",
+);
+
+pub struct UnconditionalAbortVerifier;
+
+impl AbstractInterpreterVisitor for UnconditionalAbortVerifier {
+    fn verify(
+        &mut self,
+        _env: &CompilationEnv,
+        program: &Program,
+        context: &CFGContext,
+        cfg: &ImmForwardCFG,
+    ) -> Diagnostics {
+        let mut diags = Diagnostics::new();
+        let (Some(mident), MemberName::Function(name)) = (&context.module, &context.member) else {
+            return diags;
+        };
+        let Some(mdef) = program.modules.get(mident) else {
+            return diags;
+        };
+        if !mdef.is_source_module || has_test_attribute(&mdef.attributes) {
+            return diags;
+        }
+        let Some(fdef) = mdef.functions.get_(&name.value) else {
+            return diags;
+        };
+        let callable = matches!(fdef.visibility, Visibility::Public(_)) || fdef.entry.is_some();
+        if !callable || has_test_attribute(&fdef.attributes) {
+            return diags;
+        }
+        let Some(abort_loc) = unconditional_abort(cfg) else {
+            return diags;
+        };
+        let msg = format!(
+            "Every path through '{}' aborts, so calling it can never succeed",
+            name.value
+        );
+        diags.add(diag!(
+            UNCONDITIONAL_ABORT_DIAG,
+            (name.loc, msg),
+            (abort_loc, "Aborts here")
+        ));
+        diags
+    }
+}
+
+/// Returns the location of the first abort in the function, if no path from its start reaches a
+/// return.
+fn unconditional_abort(cfg: &ImmForwardCFG) -> Option<Loc> {
+    let mut first_abort: Option<Loc> = None;
+    let mut visited = BTreeSet::new();
+    let mut worklist = vec![cfg.start_block()];
+    while let Some(label) = worklist.pop() {
+        if !visited.insert(label) {
+            continue;
+        }
+        for (_, sp!(loc, cmd_)) in cfg.commands(label) {
+            match cmd_ {
+                Command_::Return { .. } => return None,
+                Command_::Abort(_)
+                    if first_abort.map_or(true, |first| loc.start() < first.start()) =>
+                {
+                    first_abort = Some(*loc)
+                }
+                _ => (),
+            }
+        }
+        worklist.extend(cfg.successors(label));
+    }
+    first_abort
+}
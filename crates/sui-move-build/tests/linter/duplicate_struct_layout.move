@@ -0,0 +1,43 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module 0x42::a {
+    struct Config has store, drop {
+        owner: address,
+        limit: u64,
+    }
+
+    struct Pair<T: store> has store {
+        first: T,
+        second: T,
+    }
+
+    struct Witness has drop {}
+}
+
+module 0x42::b {
+    struct Settings has store, drop {
+        owner: address,
+        limit: u64,
+    }
+
+    struct Couple<U: store> has store {
+        first: U,
+        second: U,
+    }
+
+    struct Swapped has store, drop {
+        limit: u64,
+        owner: address,
+    }
+
+    struct Witness has drop {}
+}
+
+module 0x42::c {
+    #[lint_allow(duplicate_struct_layout)]
+    struct Config has store, drop {
+        owner: address,
+        limit: u64,
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module 0x42::test {
+    const ENotImplemented: u64 = 0;
+
+    public fun stub(): u64 {
+        abort ENotImplemented
+    }
+
+    entry fun entry_stub(x: u64) {
+        if (x > 0) abort 1 else abort 2
+    }
+
+    public fun conditional(x: u64): u64 {
+        assert!(x > 0, ENotImplemented);
+        if (x == 42) private_stub();
+        x
+    }
+
+    fun private_stub() {
+        abort 0
+    }
+
+    #[lint_allow(unconditional_abort)]
+    public fun suppressed() {
+        abort 0
+    }
+}
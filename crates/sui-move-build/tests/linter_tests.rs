@@ -20,9 +20,10 @@ use move_compiler::{
 
 use sui_move_build::linters::{
     coin_field::CoinFieldVisitor, collection_equality::CollectionEqualityVisitor,
-    custom_state_change::CustomStateChangeVerifier, freeze_wrapped::FreezeWrappedVisitor,
+    custom_state_change::CustomStateChangeVerifier,
+    duplicate_struct_layout::DuplicateStructLayoutVisitor, freeze_wrapped::FreezeWrappedVisitor,
     known_filters, self_transfer::SelfTransferVerifier, share_owned::ShareOwnedVerifier,
-    LINT_WARNING_PREFIX,
+    unconditional_abort::UnconditionalAbortVerifier, LINT_WARNING_PREFIX,
 };
 
 const SUI_FRAMEWORK_PATH: &str = "../sui-framework/packages/sui-framework";
@@ -71,6 +72,8 @@ fn run_tests(path: &Path) -> anyhow::Result<()> {
         CoinFieldVisitor.visitor(),
         FreezeWrappedVisitor.visitor(),
         CollectionEqualityVisitor.visitor(),
+        UnconditionalAbortVerifier.visitor(),
+        DuplicateStructLayoutVisitor.visitor(),
     ];
     let (filter_attr_name, filters) = known_filters_for_test();
     let (files, comments_and_compiler_res) = Compiler::from_files(
@@ -8,6 +8,8 @@ use serde_json::json;
 use std::{fs, path::PathBuf};
 use sui_move_build::{check_invalid_dependencies, check_unpublished_dependencies, BuildConfig};
 
+use crate::watch::watch;
+
 const LAYOUTS_DIR: &str = "layouts";
 const STRUCT_LAYOUTS_FILENAME: &str = "struct_layouts.yaml";
 
@@ -31,6 +33,14 @@ pub struct Build {
     /// If `true`, enable linters
     #[clap(long, global = true)]
     pub lint: bool,
+    /// Build the package again whenever its sources change, and report which diagnostics are new
+    /// or resolved since the previous build.
+    #[clap(
+        long,
+        global = true,
+        conflicts_with_all = ["dump_bytecode_as_base64", "generate_struct_layouts"]
+    )]
+    pub watch: bool,
 }
 
 impl Build {
@@ -41,6 +51,22 @@ impl Build {
     ) -> anyhow::Result<()> {
         let rerooted_path = base::reroot_path(path.clone())?;
         let build_config = resolve_lock_file_path(build_config, path)?;
+        if self.watch {
+            return watch(&rerooted_path, || {
+                let (result, diagnostics) = BuildConfig {
+                    config: build_config.clone(),
+                    run_bytecode_verifier: true,
+                    print_diags_to_stderr: true,
+                    lint: self.lint,
+                }
+                .build_with_diagnostics(rerooted_path.clone());
+                if let Err(e) = result {
+                    eprintln!("Failed to build package: {e}");
+                }
+                Ok(diagnostics)
+            });
+        }
+
         Self::execute_internal(
             rerooted_path,
             build_config,
@@ -15,11 +15,14 @@ pub mod build;
 pub mod coverage;
 #[cfg(feature = "disassemble")]
 pub mod disassemble;
+pub mod manage_deps;
 pub mod new;
 #[cfg(feature = "prove")]
 pub mod prove;
 #[cfg(feature = "unit_test")]
 pub mod unit_test;
+#[cfg(feature = "build")]
+pub mod watch;
 
 #[derive(Parser)]
 pub enum Command {
@@ -29,6 +32,7 @@ pub enum Command {
     Coverage(coverage::Coverage),
     #[cfg(feature = "disassemble")]
     Disassemble(disassemble::Disassemble),
+    ManageDeps(manage_deps::ManageDeps),
     New(new::New),
     #[cfg(feature = "prove")]
     Prove(prove::Prover),
@@ -55,6 +59,7 @@ pub fn execute_move_command(
         Command::Coverage(c) => c.execute(package_path, build_config),
         #[cfg(feature = "disassemble")]
         Command::Disassemble(c) => c.execute(package_path, build_config),
+        Command::ManageDeps(c) => c.execute(package_path),
         Command::New(c) => c.execute(package_path),
         #[cfg(feature = "prove")]
         Command::Prove(c) => c.execute(package_path, build_config),
@@ -0,0 +1,623 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Context};
+use clap::{Parser, Subcommand};
+use move_cli::base;
+use move_command_line_common::env::MOVE_HOME;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use toml_edit::{Document, InlineTable, Item, Table, Value};
+
+const MANIFEST_FILE: &str = "Move.toml";
+const LOCK_FILE: &str = "Move.lock";
+const MIRRORS_FILE: &str = "mirrors.toml";
+
+/// Manage the package's git dependencies: pin them to commits, vendor them into the package, or
+/// fetch them from mirrors.
+#[derive(Parser)]
+#[group(id = "sui-move-manage-deps")]
+pub struct ManageDeps {
+    /// TOML file with a `[mirrors]` table mapping git URL prefixes to the prefixes of the mirrors
+    /// to fetch from instead, e.g. `"https://github.com/" = "https://git.example.com/github/"`.
+    /// Defaults to `$MOVE_HOME/mirrors.toml`, if it exists.
+    #[clap(long, global = true)]
+    pub mirrors: Option<PathBuf>,
+    #[clap(subcommand)]
+    pub command: ManageDepsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ManageDepsCommand {
+    /// Replace the branch or tag that each git dependency in Move.lock is fetched at with the
+    /// commit it currently points to. Builds only reuse Move.lock while the digests it records
+    /// still match Move.toml and the manifests of its dependencies, so editing Move.toml (or a
+    /// dependency's branch changing its manifest) regenerates the lock at the branches and tags in
+    /// Move.toml, discarding the pins. Pin again after any such change.
+    Pin,
+    /// Copy each git dependency in Move.lock into the package, at the commit it currently points
+    /// to, and depend on the copies instead, so that the package builds without network access.
+    Vendor {
+        /// Directory inside the package to copy dependencies into.
+        #[clap(long, default_value = "vendor")]
+        dir: PathBuf,
+    },
+    /// Replace the URLs of git dependencies in Move.toml and Move.lock with their mirrors.
+    Mirror,
+}
+
+/// Where a git dependency is fetched from, as recorded in Move.lock.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct GitSource {
+    url: String,
+    rev: String,
+    subdir: String,
+}
+
+impl ManageDeps {
+    pub fn execute(self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        let root = base::reroot_path(path)?;
+        let mirrors = Mirrors::load(self.mirrors.as_deref())?;
+        match self.command {
+            ManageDepsCommand::Pin => pin(&root, &mirrors),
+            ManageDepsCommand::Vendor { dir } => vendor(&root, &dir, &mirrors),
+            ManageDepsCommand::Mirror => mirror(&root, &mirrors),
+        }
+    }
+}
+
+fn pin(root: &Path, mirrors: &Mirrors) -> anyhow::Result<()> {
+    let lock_path = root.join(LOCK_FILE);
+    let mut lock = read_lock(root)?;
+
+    let pinned = pin_lock(&mut lock, |url, rev| resolve_rev(&mirrors.apply(url), rev))?;
+
+    fs::write(&lock_path, lock.to_string())?;
+    println!(
+        "Pinned {pinned} git dependencies in {}. It is regenerated, unpinned, if {MANIFEST_FILE} \
+         changes.",
+        lock_path.display()
+    );
+    Ok(())
+}
+
+/// Pins the git dependencies in `lock` that are not at a commit to the commits that `resolve`
+/// finds their branches or tags at, returning how many were pinned.
+fn pin_lock(
+    lock: &mut Document,
+    resolve: impl Fn(&str, &str) -> anyhow::Result<String>,
+) -> anyhow::Result<usize> {
+    let mut pinned = 0;
+    for package in lock_packages(lock)?.iter_mut() {
+        let name = package_name(package)?;
+        let Some(source) = git_source(package)? else {
+            continue;
+        };
+        if is_commit(&source.rev) {
+            continue;
+        }
+
+        let commit = resolve(&source.url, &source.rev)?;
+        println!("Pinned {name} at {} to {commit}", source.rev);
+        source_table(package)?.insert("rev", commit.into());
+        pinned += 1;
+    }
+    Ok(pinned)
+}
+
+fn vendor(root: &Path, dir: &Path, mirrors: &Mirrors) -> anyhow::Result<()> {
+    let mut lock = read_lock(root)?;
+    let vendor_dir = root.join(dir);
+    let checkouts_dir = vendor_dir.join(".checkouts");
+
+    // The commit each repository is checked out at, and where.
+    let mut checkouts: BTreeMap<(String, String), PathBuf> = BTreeMap::new();
+    // Where each git dependency is vendored, by its repository and subdirectory.
+    let mut vendored: BTreeMap<(String, String), String> = BTreeMap::new();
+
+    for package in lock_packages(&mut lock)?.iter_mut() {
+        let name = package_name(package)?;
+        let Some(source) = git_source(package)? else {
+            continue;
+        };
+
+        let url = mirrors.apply(&source.url);
+        let commit = if is_commit(&source.rev) {
+            source.rev.clone()
+        } else {
+            resolve_rev(&url, &source.rev)?
+        };
+
+        let next = checkouts.len();
+        let checkout = match checkouts.get(&(url.clone(), commit.clone())) {
+            Some(checkout) => checkout.clone(),
+            None => {
+                let checkout = checkouts_dir.join(next.to_string());
+                fetch_commit(&url, &commit, &checkout)?;
+                checkouts.insert((url.clone(), commit.clone()), checkout.clone());
+                checkout
+            }
+        };
+
+        let dest = vendor_dir.join(&name);
+        if dest.exists() {
+            fs::remove_dir_all(&dest)?;
+        }
+        copy_package(&checkout.join(&source.subdir), &dest)?;
+        println!("Vendored {name} at {commit} into {}", dest.display());
+        vendored.insert((source.url.clone(), source.subdir.clone()), name);
+    }
+
+    if checkouts_dir.exists() {
+        fs::remove_dir_all(&checkouts_dir)?;
+    }
+
+    // Point the package, and each vendored dependency, at the vendored copies of their
+    // dependencies. Vendored packages are siblings of each other.
+    let dir = dir.to_string_lossy().to_string();
+    rewrite_deps(&root.join(MANIFEST_FILE), None, &vendored, |name| {
+        format!("{dir}/{name}")
+    })?;
+    for (origin, name) in &vendored {
+        rewrite_deps(
+            &vendor_dir.join(name).join(MANIFEST_FILE),
+            Some(origin),
+            &vendored,
+            |name| format!("../{name}"),
+        )?;
+    }
+
+    println!(
+        "Vendored {} git dependencies. Move.lock will be regenerated on the next build.",
+        vendored.len()
+    );
+    Ok(())
+}
+
+fn mirror(root: &Path, mirrors: &Mirrors) -> anyhow::Result<()> {
+    if mirrors.is_empty() {
+        bail!("No mirrors configured, see --mirrors");
+    }
+
+    let manifest_path = root.join(MANIFEST_FILE);
+    let mut manifest = read_toml(&manifest_path)?;
+    for (_, dep) in manifest_deps(&mut manifest) {
+        mirror_source(dep, mirrors);
+    }
+    fs::write(&manifest_path, manifest.to_string())?;
+
+    let lock_path = root.join(LOCK_FILE);
+    if lock_path.exists() {
+        let mut lock = read_lock(root)?;
+        for package in lock_packages(&mut lock)?.iter_mut() {
+            mirror_source(source_table(package)?, mirrors);
+        }
+        fs::write(&lock_path, lock.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the `git` URL of `source`, if it has one, with its mirror.
+fn mirror_source(source: &mut InlineTable, mirrors: &Mirrors) {
+    let Some(url) = source.get("git").and_then(Value::as_str) else {
+        return;
+    };
+
+    let mirrored = mirrors.apply(url);
+    if mirrored != url {
+        println!("Mirrored {url} to {mirrored}");
+        source.insert("git", mirrored.into());
+    }
+}
+
+/// Rewrites the dependencies of the package with manifest `manifest_path` that were vendored (as
+/// recorded in `vendored`, by repository and subdirectory) into local dependencies on their
+/// copies, at `local_path(name)` relative to the package. If the package was itself vendored from
+/// `origin`, its local dependencies refer to other packages in the same repository, which are
+/// rewritten too.
+fn rewrite_deps(
+    manifest_path: &Path,
+    origin: Option<&(String, String)>,
+    vendored: &BTreeMap<(String, String), String>,
+    local_path: impl Fn(&str) -> String,
+) -> anyhow::Result<()> {
+    let mut manifest = read_toml(manifest_path)?;
+    for (dep_name, dep) in manifest_deps(&mut manifest) {
+        let key = match (
+            dep.get("git").and_then(Value::as_str),
+            dep.get("local").and_then(Value::as_str),
+            origin,
+        ) {
+            (Some(url), _, _) => (
+                url.to_string(),
+                normalize_subdir(dep.get("subdir").and_then(Value::as_str)),
+            ),
+            (None, Some(local), Some((url, subdir))) => (url.clone(), join_subdir(subdir, local)),
+            _ => continue,
+        };
+
+        let Some(name) = vendored.get(&key) else {
+            eprintln!(
+                "Dependency {dep_name} of {} was not vendored, leaving it as is",
+                manifest_path.display()
+            );
+            continue;
+        };
+
+        let path = local_path(name);
+        dep.remove("git");
+        dep.remove("rev");
+        dep.remove("subdir");
+        dep.insert("local", path.into());
+    }
+
+    fs::write(manifest_path, manifest.to_string())?;
+    Ok(())
+}
+
+/// Prefixes of git URLs, and the prefixes of the mirrors to fetch them from instead.
+#[derive(Default)]
+struct Mirrors(BTreeMap<String, String>);
+
+impl Mirrors {
+    /// Loads mirrors from `path`, or from `$MOVE_HOME/mirrors.toml` if not given and it exists.
+    fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let default = Path::new(&*MOVE_HOME).join(MIRRORS_FILE);
+                if !default.exists() {
+                    return Ok(Self::default());
+                }
+                default
+            }
+        };
+
+        let config = read_toml(&path)?;
+        let Some(table) = config.get("mirrors").and_then(Item::as_table_like) else {
+            bail!("No [mirrors] table in {}", path.display());
+        };
+
+        let mut mirrors = BTreeMap::new();
+        for (prefix, mirror) in table.iter() {
+            let Some(mirror) = mirror.as_str() else {
+                bail!("Mirror of {prefix} in {} is not a string", path.display());
+            };
+            mirrors.insert(prefix.to_string(), mirror.to_string());
+        }
+        Ok(Self(mirrors))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `url`, with its longest prefix that has a mirror replaced by the mirror's.
+    fn apply(&self, url: &str) -> String {
+        self.0
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or_else(
+                || url.to_string(),
+                |(prefix, mirror)| format!("{mirror}{}", &url[prefix.len()..]),
+            )
+    }
+}
+
+fn read_toml(path: &Path) -> anyhow::Result<Document> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    contents
+        .parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn read_lock(root: &Path) -> anyhow::Result<Document> {
+    let lock_path = root.join(LOCK_FILE);
+    if !lock_path.exists() {
+        bail!(
+            "No {LOCK_FILE} in {}, build the package to generate one",
+            root.display()
+        );
+    }
+    read_toml(&lock_path)
+}
+
+/// The `[[move.package]]` entries of a lock file, one for each transitive dependency.
+fn lock_packages(lock: &mut Document) -> anyhow::Result<&mut toml_edit::ArrayOfTables> {
+    lock.get_mut("move")
+        .and_then(|m| m.get_mut("package"))
+        .and_then(Item::as_array_of_tables_mut)
+        .ok_or_else(|| anyhow!("{LOCK_FILE} has no dependencies to manage"))
+}
+
+fn package_name(package: &Table) -> anyhow::Result<String> {
+    package
+        .get("name")
+        .and_then(Item::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Package without a name in {LOCK_FILE}"))
+}
+
+fn source_table(package: &mut Table) -> anyhow::Result<&mut InlineTable> {
+    package
+        .get_mut("source")
+        .and_then(Item::as_inline_table_mut)
+        .ok_or_else(|| anyhow!("Package without a source in {LOCK_FILE}"))
+}
+
+/// Where `package` is fetched from, if it is a git dependency.
+fn git_source(package: &mut Table) -> anyhow::Result<Option<GitSource>> {
+    let source = source_table(package)?;
+    let (Some(url), Some(rev)) = (
+        source.get("git").and_then(Value::as_str),
+        source.get("rev").and_then(Value::as_str),
+    ) else {
+        return Ok(None);
+    };
+
+    Ok(Some(GitSource {
+        url: url.to_string(),
+        rev: rev.to_string(),
+        subdir: normalize_subdir(source.get("subdir").and_then(Value::as_str)),
+    }))
+}
+
+/// The entries of the `[dependencies]` and `[dev-dependencies]` tables of a manifest.
+fn manifest_deps(manifest: &mut Document) -> Vec<(String, &mut InlineTable)> {
+    let mut deps = vec![];
+    for (table, item) in manifest.iter_mut() {
+        if table.get() != "dependencies" && table.get() != "dev-dependencies" {
+            continue;
+        }
+        let Some(table) = item.as_table_like_mut() else {
+            continue;
+        };
+        for (name, dep) in table.iter_mut() {
+            if let Some(dep) = dep.as_inline_table_mut() {
+                deps.push((name.get().to_string(), dep));
+            }
+        }
+    }
+    deps
+}
+
+fn normalize_subdir(subdir: Option<&str>) -> String {
+    join_subdir("", subdir.unwrap_or(""))
+}
+
+/// The subdirectory of a repository that `path`, relative to its subdirectory `base`, refers to.
+fn join_subdir(base: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+    for part in base.split('/').chain(path.split('/')) {
+        match part {
+            "" | "." => (),
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn is_commit(rev: &str) -> bool {
+    rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The commit that branch or tag `rev` of the repository at `url` points to.
+fn resolve_rev(url: &str, rev: &str) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(["ls-remote", url, rev, &format!("{rev}^{{}}")])
+        .output()
+        .with_context(|| format!("Failed to run git to resolve {rev} of {url}"))?;
+    if !output.status.success() {
+        bail!(
+            "Failed to resolve {rev} of {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    // Annotated tags are listed twice: once as the tag object, and once peeled (with a `^{}`
+    // suffix) as the commit it points to, which is the one to pin to.
+    let stdout = String::from_utf8(output.stdout)?;
+    let refs: Vec<(&str, &str)> = stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect();
+    refs.iter()
+        .find(|(_, name)| name.ends_with("^{}"))
+        .or_else(|| refs.first())
+        .map(|(commit, _)| commit.to_string())
+        .ok_or_else(|| anyhow!("No branch or tag {rev} in {url}"))
+}
+
+/// Checks out `commit` of the repository at `url` into `dest`, without its history.
+fn fetch_commit(url: &str, commit: &str, dest: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dest)?;
+    for args in [
+        vec!["init", "--quiet"],
+        vec!["fetch", "--quiet", "--depth", "1", url, commit],
+        vec!["checkout", "--quiet", "FETCH_HEAD"],
+    ] {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dest)
+            .args(&args)
+            .output()
+            .with_context(|| format!("Failed to run git to fetch {commit} of {url}"))?;
+        if !output.status.success() {
+            bail!(
+                "Failed to fetch {commit} of {url}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Copies the package at `root` to `dest`, leaving out its build directory and hidden files.
+fn copy_package(root: &Path, dest: &Path) -> anyhow::Result<()> {
+    if !root.join(MANIFEST_FILE).exists() {
+        bail!("No Move package at {}", root.display());
+    }
+
+    let mut dirs = vec![(root.to_path_buf(), dest.to_path_buf())];
+    while let Some((src, dest)) = dirs.pop() {
+        fs::create_dir_all(&dest)?;
+        for entry in fs::read_dir(&src)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let hidden = name.to_string_lossy().starts_with('.');
+            if hidden || (src == root && name == "build") {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                dirs.push((entry.path(), dest.join(&name)));
+            } else {
+                fs::copy(entry.path(), dest.join(&name))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use move_package::{
+        resolution::dependency_graph::DependencyGraph,
+        source_package::parsed_manifest::DependencyKind,
+    };
+
+    use super::*;
+
+    const COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    const LOCK: &str = r#"# @generated by Move, please check-in and do not edit manually.
+
+[move]
+version = 0
+manifest_digest = "9103DEB6FFF2EB2174E817791FAD0E0AE2E2A3B6EED81A4659F36D5AF62B82EF"
+deps_digest = "9AE2C7D9835B118893E2BF651D37467EBBC20D37D996AD986866F7351DEF407F"
+
+dependencies = [
+  { name = "Sui" },
+]
+
+[[move.package]]
+name = "MoveStdlib"
+source = { git = "https://github.com/MystenLabs/sui.git", rev = "framework/testnet", subdir = "crates/sui-framework/packages/move-stdlib" }
+
+[[move.package]]
+name = "Sui"
+source = { git = "https://github.com/MystenLabs/sui.git", rev = "0123456789abcdef0123456789abcdef01234567", subdir = "crates/sui-framework/packages/sui-framework" }
+
+dependencies = [
+  { name = "MoveStdlib" },
+]
+"#;
+
+    fn mirrors(mirrors: &[(&str, &str)]) -> Mirrors {
+        Mirrors(
+            mirrors
+                .iter()
+                .map(|(prefix, mirror)| (prefix.to_string(), mirror.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_join_subdir() {
+        assert_eq!(join_subdir("", ""), "");
+        assert_eq!(join_subdir("a/b", ""), "a/b");
+        assert_eq!(join_subdir("", "./a//b/"), "a/b");
+        assert_eq!(join_subdir("a/b", "../c"), "a/c");
+        assert_eq!(join_subdir("a/b", "../../c/./d"), "c/d");
+        // Paths can't escape the repository.
+        assert_eq!(join_subdir("a", "../../b"), "b");
+    }
+
+    #[test]
+    fn test_mirrors_apply() {
+        let mirrors = mirrors(&[
+            ("https://github.com/", "https://git.example.com/github/"),
+            (
+                "https://github.com/MystenLabs/",
+                "https://git.example.com/mysten/",
+            ),
+        ]);
+
+        // The longest matching prefix wins.
+        assert_eq!(
+            mirrors.apply("https://github.com/MystenLabs/sui.git"),
+            "https://git.example.com/mysten/sui.git",
+        );
+        assert_eq!(
+            mirrors.apply("https://github.com/move-language/move.git"),
+            "https://git.example.com/github/move-language/move.git",
+        );
+        // URLs without a mirror are left as they are.
+        assert_eq!(
+            mirrors.apply("https://gitlab.com/a/b.git"),
+            "https://gitlab.com/a/b.git",
+        );
+        assert_eq!(
+            Mirrors::default().apply("https://github.com/a/b.git"),
+            "https://github.com/a/b.git",
+        );
+    }
+
+    #[test]
+    fn test_is_commit() {
+        assert!(is_commit(COMMIT));
+        assert!(is_commit(&COMMIT.to_uppercase()));
+        assert!(!is_commit("main"));
+        assert!(!is_commit("framework/testnet"));
+        // Abbreviated commits can't be told apart from branch names.
+        assert!(!is_commit(&COMMIT[..7]));
+        assert!(!is_commit(&COMMIT.replace('0', "g")));
+    }
+
+    #[test]
+    fn test_pinned_lock_round_trip() {
+        let mut lock: Document = LOCK.parse().unwrap();
+        let pinned = pin_lock(&mut lock, |url, rev| {
+            assert_eq!(url, "https://github.com/MystenLabs/sui.git");
+            assert_eq!(rev, "framework/testnet");
+            Ok(COMMIT.to_string())
+        })
+        .unwrap();
+        assert_eq!(pinned, 1);
+
+        // Only the branch changes, everything else is written back as it was.
+        let pinned_lock = lock.to_string();
+        assert_eq!(pinned_lock, LOCK.replace("framework/testnet", COMMIT));
+
+        // The pinned lock is still one that builds read, with every git dependency at a commit.
+        let graph = DependencyGraph::read_from_lock(
+            PathBuf::from("."),
+            "Root".into(),
+            &mut pinned_lock.as_bytes(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(graph.package_table.len(), 2);
+        for package in graph.package_table.values() {
+            let DependencyKind::Git(git) = &package.kind else {
+                panic!("Expected a git dependency, got {:?}", package.kind);
+            };
+            assert_eq!(git.git_rev.as_str(), COMMIT);
+        }
+
+        // Pinning again has nothing left to do.
+        let pinned = pin_lock(&mut lock, |_, _| panic!("Nothing to resolve")).unwrap();
+        assert_eq!(pinned, 0);
+        assert_eq!(lock.to_string(), pinned_lock);
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::build;
+use super::{build, watch::watch};
 use clap::Parser;
 use move_cli::base::{
     self,
@@ -34,6 +34,10 @@ pub struct Test {
     /// If `true`, enable linters
     #[clap(long, global = true)]
     pub lint: bool,
+    /// Build the package and run its tests again whenever its sources change, and report which
+    /// diagnostics are new or resolved since the previous run.
+    #[clap(long, global = true)]
+    pub watch: bool,
 }
 
 impl Test {
@@ -45,6 +49,34 @@ impl Test {
     ) -> anyhow::Result<UnitTestResult> {
         // find manifest file directory from a given path or (if missing) from current dir
         let rerooted_path = base::reroot_path(path)?;
+        if self.watch {
+            return watch(&rerooted_path, || {
+                let (result, diagnostics) = sui_move_build::BuildConfig {
+                    config: BuildConfig {
+                        test_mode: true,
+                        ..build_config.clone()
+                    },
+                    run_bytecode_verifier: true,
+                    print_diags_to_stderr: true,
+                    lint: self.lint,
+                }
+                .build_with_diagnostics(rerooted_path.clone());
+                match result {
+                    Ok(_) => {
+                        run_move_unit_tests(
+                            rerooted_path.clone(),
+                            build_config.clone(),
+                            Some(unit_test_config.clone()),
+                            self.test.compute_coverage,
+                        )?;
+                    }
+                    Err(e) => eprintln!("Failed to build package: {e}"),
+                }
+                Ok(diagnostics)
+            })
+            .map(|()| UnitTestResult::Success);
+        }
+
         // pre build for Sui-specific verifications
         let with_unpublished_deps = false;
         let dump_bytecode_as_base64 = false;
@@ -0,0 +1,137 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use colored::Colorize;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::Path,
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+/// How long to wait for more changes after one is seen, so that a burst of changes (an editor
+/// writing a file in several steps, or saving several files at once) is built once.
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Runs `step` straight away, and again every time a source file or the manifest of the package at
+/// `root` changes, until `step` fails. `step` returns summaries of the diagnostics it produced,
+/// and after each run, the diagnostics that are new or resolved since the previous run are
+/// printed.
+///
+/// Changes are reported by the file system, and each run builds the package from scratch.
+pub fn watch(
+    root: &Path,
+    mut step: impl FnMut() -> anyhow::Result<BTreeSet<String>>,
+) -> anyhow::Result<()> {
+    // Events name the files that changed by their absolute paths.
+    let root = root.canonicalize()?;
+    let (tx, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        // The receiver only goes away once watching has stopped.
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+
+    let mut previous: Option<BTreeSet<String>> = None;
+    loop {
+        let diagnostics = step()?;
+        if let Some(previous) = &previous {
+            print_diff(previous, &diagnostics);
+        }
+        previous = Some(diagnostics);
+
+        println!("Watching {} for changes...", root.display());
+        wait_for_change(&root, &events)?;
+    }
+}
+
+/// Blocks until a source file or the manifest of the package at `root` changes, and then until no
+/// more changes have been reported for `SETTLE_TIME`. Changes made while the previous step ran are
+/// reported straight away, and the step's own writes, e.g. to the build directory, are ignored.
+fn wait_for_change(root: &Path, events: &Receiver<notify::Result<Event>>) -> anyhow::Result<()> {
+    loop {
+        let event = events.recv()??;
+        if !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|path| is_source(root, path))
+        {
+            break;
+        }
+    }
+
+    while let Ok(event) = events.recv_timeout(SETTLE_TIME) {
+        event?;
+    }
+    Ok(())
+}
+
+/// Whether `path` is a Move source or the manifest of the package at `root`, outside its build
+/// directory and hidden directories.
+fn is_source(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+
+    let hidden = relative
+        .components()
+        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+    if hidden || relative.starts_with("build") {
+        return false;
+    }
+
+    relative == Path::new("Move.toml") || relative.extension().is_some_and(|ext| ext == "move")
+}
+
+fn print_diff(previous: &BTreeSet<String>, current: &BTreeSet<String>) {
+    let resolved: Vec<_> = previous.difference(current).collect();
+    let new: Vec<_> = current.difference(previous).collect();
+    if resolved.is_empty() && new.is_empty() {
+        println!("No change in diagnostics ({} in total)", current.len());
+        return;
+    }
+
+    println!(
+        "Diagnostics: {} resolved, {} new ({} in total)",
+        resolved.len(),
+        new.len(),
+        current.len()
+    );
+    for diag in resolved {
+        println!("  {} {diag}", "-".green());
+    }
+    for diag in new {
+        println!("  {} {diag}", "+".red());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_source() {
+        let root = Path::new("/pkg");
+        for path in [
+            "/pkg/Move.toml",
+            "/pkg/sources/a.move",
+            "/pkg/tests/b/c.move",
+        ] {
+            assert!(is_source(root, Path::new(path)), "{path}");
+        }
+
+        for path in [
+            // Written by builds.
+            "/pkg/Move.lock",
+            "/pkg/build/pkg/sources/a.move",
+            // Hidden, e.g. editor swap files and version control.
+            "/pkg/sources/.a.move",
+            "/pkg/.git/a.move",
+            // Not sources, or not in the package.
+            "/pkg/sources/a.move~",
+            "/pkg/sub/Move.toml",
+            "/other/sources/a.move",
+        ] {
+            assert!(!is_source(root, Path::new(path)), "{path}");
+        }
+    }
+}
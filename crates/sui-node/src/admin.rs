@@ -4,13 +4,18 @@
 use crate::SuiNode;
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     routing::{get, post},
-    Router,
+    Json, Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use sui_core::checkpoints::CheckpointExecutionRecord;
+use sui_core::consensus_commit_log::ConsensusCommitInfo;
+use sui_core::consensus_handler::{audit_consensus_ordering, ConsensusOrderingEntry};
+use sui_core::epoch::epoch_metrics::ReconfigTimestamps;
 use sui_types::error::SuiError;
 use telemetry_subscribers::FilterHandle;
 use tracing::info;
@@ -37,6 +42,28 @@ use tracing::info;
 // View the node config (private keys will be masked):
 //
 //   $ curl 'http://127.0.0.1:1337/node-config'
+//
+// View the last 10 consensus commits, with their leaders, reputation scores and how many of this
+// validator's transactions they included (requires `admin-interface-token` to be set in the node
+// config):
+//
+//   $ curl -H 'Authorization: Bearer <token>' 'http://127.0.0.1:1337/consensus-commits?limit=10'
+//
+// View when the last few reconfigurations reached each of their stages (last checkpoint
+// certified, epoch ended, new committee formed, first commit of the new epoch):
+//
+//   $ curl 'http://127.0.0.1:1337/reconfig-timeline'
+//
+// View the order that the consensus transactions this validator has submitted and that are still
+// pending would be sequenced in, if consensus included them all in the same commit (requires
+// `admin-interface-token` to be set in the node config):
+//
+//   $ curl -H 'Authorization: Bearer <token>' 'http://127.0.0.1:1337/consensus-ordering'
+//
+// Export how this node executed checkpoints 1000 to 1999 (transaction counts, effects
+// accumulator, execution time and cache stats), e.g. to compare with another node that diverged:
+//
+//   $ curl 'http://127.0.0.1:1337/checkpoint-execution-records?start=1000&end=1999'
 
 const LOGGING_ROUTE: &str = "/logging";
 const SET_BUFFER_STAKE_ROUTE: &str = "/set-override-buffer-stake";
@@ -44,6 +71,13 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const CONSENSUS_COMMITS: &str = "/consensus-commits";
+const RECONFIG_TIMELINE: &str = "/reconfig-timeline";
+const CONSENSUS_ORDERING: &str = "/consensus-ordering";
+const CHECKPOINT_EXECUTION_RECORDS: &str = "/checkpoint-execution-records";
+
+const DEFAULT_CONSENSUS_COMMITS_LIMIT: usize = 20;
+const MAX_CHECKPOINT_EXECUTION_RECORDS: u64 = 1000;
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -62,6 +96,13 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, filter_handle: Filt
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(CONSENSUS_COMMITS, get(consensus_commits))
+        .route(RECONFIG_TIMELINE, get(reconfig_timeline))
+        .route(CONSENSUS_ORDERING, get(consensus_ordering))
+        .route(
+            CHECKPOINT_EXECUTION_RECORDS,
+            get(checkpoint_execution_records),
+        )
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -120,12 +161,130 @@ async fn capabilities(State(state): State<Arc<AppState>>) -> (StatusCode, String
 }
 
 async fn node_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
-    let node_config = &state.node.config;
+    let mut node_config = state.node.config.clone();
+    if node_config.admin_interface_token.is_some() {
+        node_config.admin_interface_token = Some("<masked>".to_string());
+    }
 
     // Note private keys will be masked
     (StatusCode::OK, format!("{:#?}\n", node_config))
 }
 
+/// Checks the bearer token of a request against the one configured for the node. Routes using
+/// this are disabled when the node has no token configured.
+fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    let Some(expected) = &state.node.config.admin_interface_token else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "admin-interface-token is not set in the node config\n".to_string(),
+        ));
+    };
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Compared in constant time, so that the time taken does not reveal how much of the token
+    // matched.
+    let valid = token.is_some_and(|token| bool::from(token.as_bytes().ct_eq(expected.as_bytes())));
+    if !valid {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            "missing or invalid bearer token\n".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ConsensusCommitsQuery {
+    limit: Option<usize>,
+}
+
+async fn consensus_commits(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    query: Query<ConsensusCommitsQuery>,
+) -> Result<Json<Vec<ConsensusCommitInfo>>, (StatusCode, String)> {
+    authenticate(&state, &headers)?;
+    let Query(ConsensusCommitsQuery { limit }) = query;
+
+    match state
+        .node
+        .recent_consensus_commits(limit.unwrap_or(DEFAULT_CONSENSUS_COMMITS_LIMIT))
+        .await
+    {
+        Some(commits) => Ok(Json(commits)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            "node is not running as a validator\n".to_string(),
+        )),
+    }
+}
+
+async fn reconfig_timeline(State(state): State<Arc<AppState>>) -> Json<Vec<ReconfigTimestamps>> {
+    let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
+    Json(epoch_store.reconfig_timeline())
+}
+
+async fn consensus_ordering(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ConsensusOrderingEntry>>, (StatusCode, String)> {
+    authenticate(&state, &headers)?;
+    let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
+    let pending = epoch_store.get_all_pending_consensus_transactions();
+    Ok(Json(audit_consensus_ordering(
+        epoch_store
+            .protocol_config()
+            .consensus_transaction_ordering(),
+        &pending,
+    )))
+}
+
+#[derive(Deserialize)]
+struct CheckpointRange {
+    start: u64,
+    end: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct CheckpointExecutionEntry {
+    sequence_number: u64,
+    #[serde(flatten)]
+    record: CheckpointExecutionRecord,
+}
+
+/// Records of the checkpoints from `start` to `end` inclusive that this node executed, at most
+/// `MAX_CHECKPOINT_EXECUTION_RECORDS` of them.
+async fn checkpoint_execution_records(
+    State(state): State<Arc<AppState>>,
+    range: Query<CheckpointRange>,
+) -> Result<Json<Vec<CheckpointExecutionEntry>>, (StatusCode, String)> {
+    let Query(CheckpointRange { start, end }) = range;
+    let last = start.saturating_add(MAX_CHECKPOINT_EXECUTION_RECORDS - 1);
+    let end = end.map_or(last, |end| end.min(last));
+    if end < start {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("end {end} is before start {start}\n"),
+        ));
+    }
+
+    let records = state
+        .node
+        .checkpoint_store
+        .get_checkpoint_execution_records(start, end);
+    Ok(Json(
+        records
+            .into_iter()
+            .map(|(sequence_number, record)| CheckpointExecutionEntry {
+                sequence_number,
+                record,
+            })
+            .collect(),
+    ))
+}
+
 #[derive(Deserialize)]
 struct Epoch {
     epoch: u64,
@@ -3,6 +3,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::str::FromStr;
 #[cfg(msim)]
@@ -28,6 +29,7 @@ use sui_json_rpc::api::JsonRpcMetrics;
 use sui_types::authenticator_state::get_authenticator_state_obj_initial_shared_version;
 use sui_types::digests::ChainIdentifier;
 use sui_types::message_envelope::get_google_jwk_bytes;
+use sui_types::randomness_state::get_randomness_state_obj_initial_shared_version;
 use sui_types::sui_system_state::SuiSystemState;
 use tap::tap::TapFallible;
 use tokio::runtime::Handle;
@@ -50,6 +52,8 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
 use sui_config::node::DBCheckpointConfig;
 use sui_config::node_config_metrics::NodeConfigMetrics;
+use sui_config::qos_config::QosClassesConfig;
+use sui_config::Config;
 use sui_config::{ConsensusConfig, NodeConfig};
 use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
@@ -65,6 +69,7 @@ use sui_core::checkpoints::{
 use sui_core::consensus_adapter::{
     CheckConnection, ConnectionMonitorStatus, ConsensusAdapter, ConsensusAdapterMetrics,
 };
+use sui_core::consensus_commit_log::ConsensusCommitInfo;
 use sui_core::consensus_handler::ConsensusHandler;
 use sui_core::consensus_validator::{SuiTxValidator, SuiTxValidatorMetrics};
 use sui_core::db_checkpoint_handler::DBCheckpointHandler;
@@ -78,6 +83,7 @@ use sui_core::signature_verifier::SignatureVerifierMetrics;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_core::storage::RocksDbStore;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
+use sui_core::validator_qos::{ValidatorQos, ValidatorQosMetrics};
 use sui_core::{
     authority::{AuthorityState, AuthorityStore},
     authority_client::NetworkAuthorityClient,
@@ -97,6 +103,7 @@ use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
 use sui_network::state_sync;
 use sui_protocol_config::{Chain, ProtocolConfig, SupportedProtocolVersions};
+use sui_snapshot::cold_tier::SnapshotColdObjectStore;
 use sui_snapshot::uploader::StateSnapshotUploader;
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_storage::{
@@ -434,6 +441,22 @@ impl SuiNode {
             &prometheus_registry,
         )
         .await?;
+        if let Some(cold_object_store_config) = &config.cold_object_store_config {
+            let cache_capacity = NonZeroUsize::new(cold_object_store_config.cache_capacity)
+                .ok_or_else(|| anyhow!("Cold object store cache capacity must be non-zero"))?;
+            let max_indexed_snapshots =
+                NonZeroUsize::new(cold_object_store_config.max_indexed_snapshots).ok_or_else(
+                    || anyhow!("Cold object store max indexed snapshots must be non-zero"),
+                )?;
+            let cold_object_store = SnapshotColdObjectStore::new(
+                &cold_object_store_config.object_store_config,
+                config.cold_object_store_path(),
+                cache_capacity,
+                max_indexed_snapshots,
+                &prometheus_registry,
+            )?;
+            store.set_cold_object_store(Arc::new(cold_object_store))?;
+        }
         let cur_epoch = store.get_recovery_epoch_at_restart()?;
         let committee = committee_store
             .get_committee(&cur_epoch)?
@@ -574,6 +597,7 @@ impl SuiNode {
             config.indirect_objects_threshold,
             config.state_debug_dump_config.clone(),
             archive_readers,
+            config.dry_run_cache_config.clone(),
         )
         .await;
         // ensure genesis txn was executed
@@ -795,6 +819,11 @@ impl SuiNode {
                 &config.snapshot_path(),
                 remote_store_config.clone(),
                 60,
+                config.state_snapshot_write_config.partition_by_type,
+                prometheus_registry,
+            )?
+            .with_replicas(
+                &config.state_snapshot_write_config.replica_object_store_configs,
                 prometheus_registry,
             )?;
             Ok(Some(snapshot_uploader.start()))
@@ -1055,9 +1084,16 @@ impl SuiNode {
 
         consensus_adapter.swap_low_scoring_authorities(low_scoring_authorities.clone());
 
+        let low_scoring_exclusions = config
+            .consensus_config
+            .as_ref()
+            .map(|c| c.low_scoring_exclusions(config.protocol_public_key()))
+            .unwrap_or_default();
+
         let new_epoch_start_state = epoch_store.epoch_start_state();
         let committee = new_epoch_start_state.get_narwhal_committee();
 
+        let commit_log = consensus_adapter.commit_log();
         let consensus_handler_initializer = || {
             ConsensusHandler::new(
                 epoch_store.clone(),
@@ -1065,7 +1101,9 @@ impl SuiNode {
                 state.transaction_manager().clone(),
                 state.db(),
                 low_scoring_authorities.clone(),
+                low_scoring_exclusions.clone(),
                 committee.clone(),
+                commit_log.clone(),
                 state.metrics.clone(),
             )
         };
@@ -1210,11 +1248,23 @@ impl SuiNode {
         consensus_adapter: Arc<ConsensusAdapter>,
         prometheus_registry: &Registry,
     ) -> Result<tokio::task::JoinHandle<Result<()>>> {
-        let validator_service = ValidatorService::new(
+        let mut validator_service = ValidatorService::new(
             state.clone(),
             consensus_adapter,
             Arc::new(ValidatorServiceMetrics::new(prometheus_registry)),
         );
+        if let Some(qos_config) = &config.validator_qos_config {
+            let classes = QosClassesConfig::load(&qos_config.classes_path)?;
+            let qos = Arc::new(ValidatorQos::new(
+                classes,
+                ValidatorQosMetrics::new(prometheus_registry),
+            ));
+            qos.spawn_reloader(
+                qos_config.classes_path.clone(),
+                Duration::from_secs(qos_config.reload_interval_secs),
+            );
+            validator_service = validator_service.with_qos(qos);
+        }
 
         let mut server_conf = mysten_network::config::Config::new();
         server_conf.global_concurrency_limit = config.grpc_concurrency_limit;
@@ -1239,6 +1289,16 @@ impl SuiNode {
         self.state.clone()
     }
 
+    /// Up to `limit` of the most recent consensus commits, newest first, or None if this node is
+    /// not running as a validator.
+    pub async fn recent_consensus_commits(&self, limit: usize) -> Option<Vec<ConsensusCommitInfo>> {
+        self.validator_components
+            .lock()
+            .await
+            .as_ref()
+            .map(|components| components.consensus_adapter.commit_log().last(limit))
+    }
+
     // Only used for testing because of how epoch store is loaded.
     pub fn reference_gas_price_for_testing(&self) -> Result<u64, anyhow::Error> {
         self.state.reference_gas_price_for_testing()
@@ -1518,10 +1578,15 @@ impl SuiNode {
             get_authenticator_state_obj_initial_shared_version(&state.database)
                 .expect("read cannot fail");
 
+        let randomness_state_obj_initial_shared_version =
+            get_randomness_state_obj_initial_shared_version(&state.database)
+                .expect("read cannot fail");
+
         let epoch_start_configuration = EpochStartConfiguration::new(
             next_epoch_start_system_state,
             *last_checkpoint.digest(),
             authenticator_state_obj_initial_shared_version,
+            randomness_state_obj_initial_shared_version,
         );
 
         let new_epoch_store = self
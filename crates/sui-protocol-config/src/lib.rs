@@ -77,6 +77,7 @@ const MAX_PROTOCOL_VERSION: u64 = 27;
 // Version 26: New gas model version.
 //             Add support for receiving objects off of other objects in devnet only.
 // Version 27: Add sui::zklogin::verify_zklogin_id and related functions to sui framework.
+//             Verify the package visibility metadata of modules in devnet only.
 
 #[derive(Copy, Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProtocolVersion(u64);
@@ -295,6 +296,15 @@ struct FeatureFlags {
 
     #[serde(skip_serializing_if = "is_false")]
     enable_effects_v2: bool,
+
+    // Enable the randomness beacon, and reading its output from Move through `sui::random`.
+    #[serde(skip_serializing_if = "is_false")]
+    random_beacon: bool,
+
+    // Verify the metadata that lists the `public(package)` functions of published modules, only
+    // allow calls to them from the same address, and have the compiler emit the metadata.
+    #[serde(skip_serializing_if = "is_false")]
+    enforce_package_visibility: bool,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -937,6 +947,8 @@ impl ProtocolConfig {
         if !ret {
             // jwk updates required end-of-epoch transactions
             assert!(!self.feature_flags.enable_jwk_consensus_updates);
+            // as does the randomness beacon
+            assert!(!self.feature_flags.random_beacon);
         }
         ret
     }
@@ -949,6 +961,19 @@ impl ProtocolConfig {
     pub fn enable_effects_v2(&self) -> bool {
         self.feature_flags.enable_effects_v2
     }
+
+    pub fn random_beacon(&self) -> bool {
+        let ret = self.feature_flags.random_beacon;
+        if ret {
+            // the randomness state object is created by an end-of-epoch transaction
+            assert!(self.feature_flags.end_of_epoch_transaction_supported);
+        }
+        ret
+    }
+
+    pub fn enforce_package_visibility(&self) -> bool {
+        self.feature_flags.enforce_package_visibility
+    }
 }
 
 #[cfg(not(msim))]
@@ -1516,6 +1541,7 @@ impl ProtocolConfig {
                     // Only enable effects v2 on devnet.
                     if chain != Chain::Mainnet && chain != Chain::Testnet {
                         cfg.feature_flags.enable_effects_v2 = true;
+                        cfg.feature_flags.enforce_package_visibility = true;
                     }
                 }
                 // Use this template when making changes:
@@ -1588,6 +1614,9 @@ impl ProtocolConfig {
     pub fn set_receive_object_for_testing(&mut self, val: bool) {
         self.feature_flags.receive_objects = val
     }
+    pub fn set_random_beacon_for_testing(&mut self, val: bool) {
+        self.feature_flags.random_beacon = val
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
@@ -51,6 +51,7 @@ use sui_types::{
     inner_temporary_store::InnerTemporaryStore,
     metrics::LimitsMetrics,
     object::{Data, Object, Owner},
+    randomness_state::get_randomness_state_obj_initial_shared_version,
     storage::get_module_by_id,
     storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
     sui_system_state::epoch_start_sui_system_state::EpochStartSystemState,
@@ -2054,6 +2055,8 @@ async fn create_epoch_store(
         CheckpointDigest::random(),
         get_authenticator_state_obj_initial_shared_version(&authority_state.database)
             .expect("read cannot fail"),
+        get_randomness_state_obj_initial_shared_version(&authority_state.database)
+            .expect("read cannot fail"),
     );
 
     let registry = Registry::new();
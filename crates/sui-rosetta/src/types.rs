@@ -408,6 +408,7 @@ pub enum OperationType {
     ProgrammableTransaction,
     AuthenticatorStateUpdate,
     EndOfEpochTransaction,
+    RandomnessStateUpdate,
 }
 
 impl From<&SuiTransactionBlockKind> for OperationType {
@@ -427,6 +428,9 @@ impl From<&SuiTransactionBlockKind> for OperationType {
             SuiTransactionBlockKind::EndOfEpochTransaction(_) => {
                 OperationType::EndOfEpochTransaction
             }
+            SuiTransactionBlockKind::RandomnessStateUpdate(_) => {
+                OperationType::RandomnessStateUpdate
+            }
         }
     }
 }
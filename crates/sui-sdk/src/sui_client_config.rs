@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::fmt::{Display, Formatter, Write};
+use std::path::PathBuf;
 
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
@@ -48,6 +49,19 @@ impl SuiClientConfig {
         })
     }
 
+    pub fn get_active_env_mut(&mut self) -> Result<&mut SuiEnv, anyhow::Error> {
+        let env = match &self.active_env {
+            Some(alias) => self.envs.iter_mut().find(|env| &env.alias == alias),
+            None => self.envs.first_mut(),
+        };
+        env.ok_or_else(|| {
+            anyhow!(
+                "Environment config not found for env [{}]",
+                self.active_env.as_deref().unwrap_or("None")
+            )
+        })
+    }
+
     pub fn add_env(&mut self, env: SuiEnv) {
         if !self
             .envs
@@ -59,11 +73,34 @@ impl SuiClientConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SuiEnv {
     pub alias: String,
     pub rpc: String,
     pub ws: Option<String>,
+    /// GraphQL endpoint of the environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql: Option<String>,
+    /// Path to a file based keystore that replaces the client-wide keystore while this environment
+    /// is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<PathBuf>,
+    /// Sender used for commands when no address is specified while this environment is active,
+    /// instead of the client-wide active address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_address: Option<SuiAddress>,
+    /// Transactions with a larger gas budget are refused while this environment is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_gas_budget: Option<u64>,
+}
+
+/// A setting that has different values in two environments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiEnvFieldDiff {
+    pub field: &'static str,
+    pub left: Option<String>,
+    pub right: Option<String>,
 }
 
 impl SuiEnv {
@@ -86,11 +123,35 @@ impl SuiEnv {
         Ok(builder.build(&self.rpc).await?)
     }
 
+    /// Returns the settings (other than the alias) that differ between `self` and `other`.
+    pub fn diff(&self, other: &SuiEnv) -> Vec<SuiEnvFieldDiff> {
+        let fields = |env: &SuiEnv| {
+            [
+                ("rpc", Some(env.rpc.clone())),
+                ("ws", env.ws.clone()),
+                ("graphql", env.graphql.clone()),
+                (
+                    "keystore",
+                    env.keystore.as_ref().map(|p| p.display().to_string()),
+                ),
+                ("active_address", env.active_address.map(|a| a.to_string())),
+                ("max_gas_budget", env.max_gas_budget.map(|b| b.to_string())),
+            ]
+        };
+        fields(self)
+            .into_iter()
+            .zip(fields(other))
+            .filter(|((_, left), (_, right))| left != right)
+            .map(|((field, left), (_, right))| SuiEnvFieldDiff { field, left, right })
+            .collect()
+    }
+
     pub fn devnet() -> Self {
         Self {
             alias: "devnet".to_string(),
             rpc: SUI_DEVNET_URL.into(),
             ws: None,
+            ..Default::default()
         }
     }
     pub fn testnet() -> Self {
@@ -98,6 +159,7 @@ impl SuiEnv {
             alias: "testnet".to_string(),
             rpc: SUI_TESTNET_URL.into(),
             ws: None,
+            ..Default::default()
         }
     }
 
@@ -106,6 +168,7 @@ impl SuiEnv {
             alias: "local".to_string(),
             rpc: SUI_LOCAL_NETWORK_URL.into(),
             ws: None,
+            ..Default::default()
         }
     }
 }
@@ -119,6 +182,22 @@ impl Display for SuiEnv {
             writeln!(writer)?;
             write!(writer, "Websocket URL: {ws}")?;
         }
+        if let Some(graphql) = &self.graphql {
+            writeln!(writer)?;
+            write!(writer, "GraphQL URL: {graphql}")?;
+        }
+        if let Some(keystore) = &self.keystore {
+            writeln!(writer)?;
+            write!(writer, "Keystore: {}", keystore.display())?;
+        }
+        if let Some(address) = &self.active_address {
+            writeln!(writer)?;
+            write!(writer, "Active address: {address}")?;
+        }
+        if let Some(max_gas_budget) = &self.max_gas_budget {
+            writeln!(writer)?;
+            write!(writer, "Max gas budget: {max_gas_budget}")?;
+        }
         write!(f, "{}", writer)
     }
 }
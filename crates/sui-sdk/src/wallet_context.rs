@@ -14,7 +14,7 @@ use sui_json_rpc_types::{
     SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
     SuiObjectResponseQuery, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
-use sui_keys::keystore::AccountKeystore;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_types::gas_coin::GasCoin;
 use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
@@ -23,6 +23,8 @@ use tracing::warn;
 
 pub struct WalletContext {
     pub config: PersistedConfig<SuiClientConfig>,
+    /// The keystore pinned by the active environment, if it has one.
+    env_keystore: Option<Keystore>,
     request_timeout: Option<std::time::Duration>,
     client: Arc<RwLock<Option<SuiClient>>>,
     max_concurrent_requests: Option<u64>,
@@ -42,17 +44,60 @@ impl WalletContext {
         })?;
 
         let config = config.persisted(config_path);
-        let context = Self {
+        let mut context = Self {
             config,
+            env_keystore: None,
             request_timeout,
             client: Default::default(),
             max_concurrent_requests,
         };
+        context.load_env_keystore()?;
         Ok(context)
     }
 
+    /// Loads the keystore pinned by the active environment, falling back to the client-wide
+    /// keystore if it doesn't pin one. Must be called after switching environments.
+    pub fn load_env_keystore(&mut self) -> Result<(), anyhow::Error> {
+        self.env_keystore = match self
+            .config
+            .get_active_env()
+            .ok()
+            .and_then(|env| env.keystore.clone())
+        {
+            Some(path) => Some(Keystore::from(FileBasedKeystore::new(&path)?)),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// The keystore of the active environment.
+    pub fn keystore(&self) -> &Keystore {
+        self.env_keystore.as_ref().unwrap_or(&self.config.keystore)
+    }
+
+    pub fn keystore_mut(&mut self) -> &mut Keystore {
+        self.env_keystore
+            .as_mut()
+            .unwrap_or(&mut self.config.keystore)
+    }
+
     pub fn get_addresses(&self) -> Vec<SuiAddress> {
-        self.config.keystore.addresses()
+        self.keystore().addresses()
+    }
+
+    /// Fails if `gas_budget` exceeds the maximum allowed by the active environment.
+    pub fn check_gas_budget(&self, gas_budget: u64) -> Result<(), anyhow::Error> {
+        if let Ok(env) = self.config.get_active_env() {
+            if let Some(max_gas_budget) = env.max_gas_budget {
+                if gas_budget > max_gas_budget {
+                    return Err(anyhow!(
+                        "Gas budget {gas_budget} exceeds the maximum of {max_gas_budget} configured for env [{}]",
+                        env.alias
+                    ));
+                }
+            }
+        }
+        Ok(())
     }
 
     pub async fn get_client(&self) -> Result<SuiClient, anyhow::Error> {
@@ -77,12 +122,30 @@ impl WalletContext {
 
     // TODO: Ger rid of mut
     pub fn active_address(&mut self) -> Result<SuiAddress, anyhow::Error> {
-        if self.config.keystore.addresses().is_empty() {
+        let addresses = self.keystore().addresses();
+        if addresses.is_empty() {
             return Err(anyhow!(
                 "No managed addresses. Create new address with `new-address` command."
             ));
         }
 
+        if let Some(address) = self
+            .config
+            .get_active_env()
+            .ok()
+            .and_then(|env| env.active_address)
+        {
+            return Ok(address);
+        }
+
+        // The client-wide active address may not be managed by the environment's keystore
+        if self.env_keystore.is_some() {
+            return Ok(match self.config.active_address {
+                Some(address) if addresses.contains(&address) => address,
+                _ => addresses[0],
+            });
+        }
+
         // Ok to unwrap because we checked that config addresses not empty
         // Set it if not exists
         self.config.active_address = Some(
@@ -281,8 +344,7 @@ impl WalletContext {
     /// Sign a transaction with a key currently managed by the WalletContext
     pub fn sign_transaction(&self, data: &TransactionData) -> Transaction {
         let sig = self
-            .config
-            .keystore
+            .keystore()
             .sign_secure(&data.sender(), data, Intent::sui_transaction())
             .unwrap();
         // TODO: To support sponsored transaction, we should also look at the gas owner.
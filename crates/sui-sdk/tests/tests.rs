@@ -3,6 +3,7 @@
 use tempfile::TempDir;
 
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::sui_client_config::{SuiEnv, SuiEnvFieldDiff};
 use sui_types::crypto::Ed25519SuiSignature;
 use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
 #[test]
@@ -32,3 +33,36 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn env_diff_test() {
+    let local = SuiEnv {
+        keystore: Some("local.keystore".into()),
+        max_gas_budget: Some(1_000_000_000),
+        ..SuiEnv::localnet()
+    };
+    let mut other = SuiEnv {
+        alias: "other".to_string(),
+        ..local.clone()
+    };
+    // The alias isn't a setting
+    assert!(local.diff(&other).is_empty());
+
+    other.rpc = "http://127.0.0.1:9001".to_string();
+    other.max_gas_budget = None;
+    assert_eq!(
+        local.diff(&other),
+        vec![
+            SuiEnvFieldDiff {
+                field: "rpc",
+                left: Some(local.rpc.clone()),
+                right: Some(other.rpc.clone()),
+            },
+            SuiEnvFieldDiff {
+                field: "max_gas_budget",
+                left: Some("1000000000".to_string()),
+                right: None,
+            },
+        ]
+    );
+}
@@ -0,0 +1,430 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reader::{LiveObjectIter, ObjectRefIter, StateSnapshotReaderV1};
+use crate::{FileMetadata, FileType};
+use anyhow::{Context, Result};
+use lru::LruCache;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use parking_lot::Mutex;
+use prometheus::{register_int_counter_with_registry, IntCounter, Registry};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_core::authority::authority_store_cold_tier::ColdObjectStore;
+use sui_core::authority::authority_store_tables::LiveObject;
+use sui_storage::object_store::util::{
+    copy_file, find_all_dirs_with_epoch_prefix, get, path_to_filesystem,
+};
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_types::base_types::{ObjectDigest, ObjectID, VersionNumber};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+pub struct ColdObjectStoreMetrics {
+    pub cold_object_store_cache_hits: IntCounter,
+    pub cold_object_store_cache_misses: IntCounter,
+    pub cold_object_store_objects_fetched: IntCounter,
+}
+
+impl ColdObjectStoreMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            cold_object_store_cache_hits: register_int_counter_with_registry!(
+                "cold_object_store_cache_hits",
+                "Number of historical object reads served from the cold tier's cache",
+                registry
+            )
+            .unwrap(),
+            cold_object_store_cache_misses: register_int_counter_with_registry!(
+                "cold_object_store_cache_misses",
+                "Number of historical object reads that had to search snapshot files",
+                registry
+            )
+            .unwrap(),
+            cold_object_store_objects_fetched: register_int_counter_with_registry!(
+                "cold_object_store_objects_fetched",
+                "Number of objects found in and fetched from snapshot files",
+                registry
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// How long the list of epochs with a snapshot in the remote store is reused before listing them
+/// again. Snapshots are uploaded once per epoch, so new ones are rare.
+const EPOCH_LIST_TTL: Duration = Duration::from_secs(300);
+
+/// The files of a single epoch's formal snapshot. Object and reference files are cut together, so
+/// the object file with the same (bucket, partition) as the reference file that lists an object
+/// holds it. Objects are written in the order of their IDs, so the reference files of a bucket list
+/// consecutive ranges of IDs, in partition order.
+struct EpochSnapshot {
+    epoch: u64,
+    object_files: BTreeMap<(u32, u32), FileMetadata>,
+    /// The reference files of each bucket, in partition order.
+    ref_files: BTreeMap<u32, Vec<FileMetadata>>,
+    /// The first object ID listed by each reference file read so far, by (bucket, partition). One
+    /// ID is kept per file, however many objects the snapshot holds.
+    first_ids: Mutex<HashMap<(u32, u32), ObjectID>>,
+}
+
+impl EpochSnapshot {
+    fn epoch_dir(&self) -> Path {
+        Path::from(format!("epoch_{}", self.epoch))
+    }
+}
+
+/// SnapshotColdObjectStore serves historical object versions out of the formal snapshots in a
+/// remote store. A version can be found as long as it was live at the end of some epoch that has a
+/// snapshot. To find an object in a snapshot, the reference file that can list it is picked by
+/// binary search over the first ID listed by each reference file, and only that reference file and
+/// the object file next to it are read in full. The manifests of up to `max_indexed_snapshots`
+/// snapshots, along with the first IDs learned so far, are kept in memory, so that later searches
+/// read fewer files. Found objects are kept in an in-memory LRU cache.
+pub struct SnapshotColdObjectStore {
+    local_staging_dir_root: PathBuf,
+    remote_object_store: Arc<DynObjectStore>,
+    local_object_store: Arc<DynObjectStore>,
+    epochs: Mutex<Option<(Instant, Vec<u64>)>>,
+    snapshots: Mutex<LruCache<u64, Arc<EpochSnapshot>>>,
+    cache: Mutex<LruCache<ObjectKey, Object>>,
+    runtime: DownloadRuntime,
+    metrics: ColdObjectStoreMetrics,
+}
+
+impl SnapshotColdObjectStore {
+    pub fn new(
+        remote_store_config: &ObjectStoreConfig,
+        local_staging_dir_root: PathBuf,
+        cache_capacity: NonZeroUsize,
+        max_indexed_snapshots: NonZeroUsize,
+        registry: &Registry,
+    ) -> Result<Self> {
+        // Anything left in the staging dir was being read when the node stopped.
+        if local_staging_dir_root.exists() {
+            fs::remove_dir_all(&local_staging_dir_root)?;
+        }
+        fs::create_dir_all(&local_staging_dir_root)?;
+        let local_store_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(local_staging_dir_root.clone()),
+            ..Default::default()
+        };
+        Ok(SnapshotColdObjectStore {
+            local_staging_dir_root,
+            remote_object_store: remote_store_config.make()?,
+            local_object_store: local_store_config.make()?,
+            epochs: Mutex::new(None),
+            snapshots: Mutex::new(LruCache::new(max_indexed_snapshots)),
+            cache: Mutex::new(LruCache::new(cache_capacity)),
+            runtime: DownloadRuntime::new()?,
+            metrics: ColdObjectStoreMetrics::new(registry),
+        })
+    }
+
+    fn get_object(&self, object_id: &ObjectID, version: VersionNumber) -> Result<Option<Object>> {
+        let key = ObjectKey(*object_id, version);
+        if let Some(object) = self.cache.lock().get(&key) {
+            self.metrics.cold_object_store_cache_hits.inc();
+            return Ok(Some(object.clone()));
+        }
+        self.metrics.cold_object_store_cache_misses.inc();
+
+        // Search from the most recent snapshot backwards
+        for epoch in self.epochs()?.into_iter().rev() {
+            let Some(snapshot) = self.snapshot(epoch)? else {
+                continue;
+            };
+            let Some((bucket_and_part, snapshot_version, digest)) =
+                self.locate(&snapshot, object_id)?
+            else {
+                continue;
+            };
+            if snapshot_version < version {
+                // The requested version was created after this epoch and was overwritten before
+                // the end of any later one, so no snapshot has it.
+                return Ok(None);
+            }
+            if snapshot_version == version {
+                let object = self.fetch_object(&snapshot, bucket_and_part, &key, digest)?;
+                if let Some(object) = &object {
+                    self.metrics.cold_object_store_objects_fetched.inc();
+                    self.cache.lock().put(key, object.clone());
+                }
+                return Ok(object);
+            }
+        }
+        Ok(None)
+    }
+
+    /// The epochs that have a snapshot directory in the remote store, in ascending order.
+    fn epochs(&self) -> Result<Vec<u64>> {
+        if let Some((listed_at, epochs)) = &*self.epochs.lock() {
+            if listed_at.elapsed() < EPOCH_LIST_TTL {
+                return Ok(epochs.clone());
+            }
+        }
+        let remote_object_store = self.remote_object_store.clone();
+        let epochs: Vec<u64> = self
+            .runtime
+            .block_on(
+                async move { find_all_dirs_with_epoch_prefix(&remote_object_store, None).await },
+            )??
+            .into_keys()
+            .collect();
+        *self.epochs.lock() = Some((Instant::now(), epochs.clone()));
+        Ok(epochs)
+    }
+
+    /// Loads the manifest of the snapshot for `epoch`, or returns None if the snapshot has not been
+    /// completely uploaded yet.
+    fn snapshot(&self, epoch: u64) -> Result<Option<Arc<EpochSnapshot>>> {
+        if let Some(snapshot) = self.snapshots.lock().get(&epoch) {
+            return Ok(Some(snapshot.clone()));
+        }
+        let epoch_dir = Path::from(format!("epoch_{}", epoch));
+        let manifest_file_path = epoch_dir.child("MANIFEST");
+        // The manifest is written last, so its absence means the upload is still in progress
+        let remote_object_store = self.remote_object_store.clone();
+        let head_path = manifest_file_path.clone();
+        match self
+            .runtime
+            .block_on(async move { remote_object_store.head(&head_path).await })?
+        {
+            Ok(_) => {}
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let local_epoch_dir = path_to_filesystem(self.local_staging_dir_root.clone(), &epoch_dir)?;
+        let snapshot = self.load_snapshot(epoch, &manifest_file_path);
+        // The staged manifest is only needed to list the snapshot's files.
+        if local_epoch_dir.exists() {
+            fs::remove_dir_all(&local_epoch_dir)?;
+        }
+        let snapshot = Arc::new(snapshot?);
+        debug!(
+            "Loaded the manifest of the snapshot of epoch {epoch}, with {} object files",
+            snapshot.object_files.len()
+        );
+        self.snapshots.lock().put(epoch, snapshot.clone());
+        Ok(Some(snapshot))
+    }
+
+    fn load_snapshot(&self, epoch: u64, manifest_file_path: &Path) -> Result<EpochSnapshot> {
+        self.stage(manifest_file_path.clone())?;
+        let manifest = StateSnapshotReaderV1::read_manifest(path_to_filesystem(
+            self.local_staging_dir_root.clone(),
+            manifest_file_path,
+        )?)?;
+        let mut object_files = BTreeMap::new();
+        let mut ref_files: BTreeMap<u32, Vec<FileMetadata>> = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            match file_metadata.file_type {
+                FileType::Object => {
+                    let bucket_and_part = (file_metadata.bucket_num, file_metadata.part_num);
+                    object_files.insert(bucket_and_part, file_metadata.clone());
+                }
+                FileType::Reference => {
+                    ref_files
+                        .entry(file_metadata.bucket_num)
+                        .or_default()
+                        .push(file_metadata.clone());
+                }
+            }
+        }
+        for files in ref_files.values_mut() {
+            files.sort_by_key(|f| f.part_num);
+        }
+        Ok(EpochSnapshot {
+            epoch,
+            object_files,
+            ref_files,
+            first_ids: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The (bucket, partition) of the files in `snapshot` that hold `object_id`, along with the
+    /// version and digest it had at the end of the snapshot's epoch, or None if it was not live
+    /// then.
+    fn locate(
+        &self,
+        snapshot: &EpochSnapshot,
+        object_id: &ObjectID,
+    ) -> Result<Option<((u32, u32), VersionNumber, ObjectDigest)>> {
+        for files in snapshot.ref_files.values() {
+            let Some(file) = find_ref_file(files, object_id, |f| self.first_id(snapshot, f))?
+            else {
+                continue;
+            };
+            for (id, version, digest) in self.read_refs(snapshot, file)? {
+                if id == *object_id {
+                    return Ok(Some(((file.bucket_num, file.part_num), version, digest)));
+                }
+                if id > *object_id {
+                    break;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The first object ID listed by the reference file `file` of `snapshot`, or None if it is
+    /// empty.
+    fn first_id(&self, snapshot: &EpochSnapshot, file: &FileMetadata) -> Result<Option<ObjectID>> {
+        let bucket_and_part = (file.bucket_num, file.part_num);
+        if let Some(id) = snapshot.first_ids.lock().get(&bucket_and_part) {
+            return Ok(Some(*id));
+        }
+        let first_id = self.read_refs(snapshot, file)?.next().map(|(id, ..)| id);
+        if let Some(id) = first_id {
+            snapshot.first_ids.lock().insert(bucket_and_part, id);
+        }
+        Ok(first_id)
+    }
+
+    /// Downloads the reference file `file` of `snapshot`, to iterate over the refs it lists.
+    fn read_refs(&self, snapshot: &EpochSnapshot, file: &FileMetadata) -> Result<ObjectRefIter> {
+        let file_path = file.file_path(&snapshot.epoch_dir());
+        let remote_object_store = self.remote_object_store.clone();
+        let bytes = self
+            .runtime
+            .block_on(async move { get(&file_path, remote_object_store).await })??;
+        ObjectRefIter::from_bytes(file.file_compression, bytes)
+    }
+
+    /// Copies the file at `path` in the remote store to the same path in the staging dir.
+    fn stage(&self, path: Path) -> Result<()> {
+        let remote_object_store = self.remote_object_store.clone();
+        let local_object_store = self.local_object_store.clone();
+        self.runtime.block_on(async move {
+            copy_file(path.clone(), path, remote_object_store, local_object_store).await
+        })?
+    }
+
+    fn fetch_object(
+        &self,
+        snapshot: &EpochSnapshot,
+        bucket_and_part: (u32, u32),
+        key: &ObjectKey,
+        digest: ObjectDigest,
+    ) -> Result<Option<Object>> {
+        let file_metadata = snapshot
+            .object_files
+            .get(&bucket_and_part)
+            .context(format!(
+                "No object file found for bucket: {}, part: {}",
+                bucket_and_part.0, bucket_and_part.1
+            ))?;
+        let file_path = file_metadata.file_path(&snapshot.epoch_dir());
+        let remote_object_store = self.remote_object_store.clone();
+        let bytes = self
+            .runtime
+            .block_on(async move { get(&file_path, remote_object_store).await })??;
+        let object = LiveObjectIter::new(file_metadata, bytes)?.find_map(|object| match object {
+            LiveObject::Normal(object) if object.id() == key.0 && object.version() == key.1 => {
+                Some(object)
+            }
+            _ => None,
+        });
+        if let Some(object) = &object {
+            let computed_digest = object.compute_object_reference().2;
+            anyhow::ensure!(
+                computed_digest == digest,
+                "Digest of object {:?} doesn't match the snapshot reference: {:?} != {:?}",
+                key,
+                computed_digest,
+                digest
+            );
+        }
+        Ok(object)
+    }
+}
+
+/// The last of `files`, which list consecutive ranges of object IDs in ascending order, whose first
+/// ID is at most `object_id`, i.e. the only one that can list it. `first_id` reads the first ID
+/// listed by a file, and is called for O(log n) of them.
+pub(crate) fn find_ref_file<'a>(
+    files: &'a [FileMetadata],
+    object_id: &ObjectID,
+    mut first_id: impl FnMut(&FileMetadata) -> Result<Option<ObjectID>>,
+) -> Result<Option<&'a FileMetadata>> {
+    // The files before `lo` start at or before `object_id`, and the files from `hi` after it.
+    let (mut lo, mut hi) = (0, files.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if first_id(&files[mid])?.is_some_and(|first| first <= *object_id) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(lo.checked_sub(1).map(|i| &files[i]))
+}
+
+/// A runtime on a thread of its own that the cold tier downloads files with. Reads wait for the
+/// downloads synchronously, which works from any thread, including the only thread of a
+/// current_thread runtime, because the downloads make progress on this runtime's thread instead.
+struct DownloadRuntime {
+    handle: Handle,
+    /// Dropping this stops the runtime.
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl DownloadRuntime {
+    fn new() -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let handle = runtime.handle().clone();
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        std::thread::Builder::new()
+            .name("cold-object-store".to_string())
+            .spawn(move || {
+                let _ = runtime.block_on(shutdown_rx);
+            })?;
+        Ok(Self {
+            handle,
+            _shutdown: shutdown,
+        })
+    }
+
+    fn block_on<F>(&self, future: F) -> Result<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let task = self.handle.spawn(future);
+        let wait = || futures::executor::block_on(task);
+        let output = match Handle::try_current() {
+            // Let the runtime move its other tasks off this worker while it waits.
+            Ok(current) if current.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(wait)
+            }
+            _ => wait(),
+        };
+        output.context("Cold object store download failed")
+    }
+}
+
+impl ColdObjectStore for SnapshotColdObjectStore {
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> SuiResult<Option<Object>> {
+        self.get_object(object_id, version)
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))
+    }
+}
@@ -5,11 +5,17 @@
 #[cfg(test)]
 mod tests;
 
+pub mod cold_tier;
+pub mod mount;
 mod reader;
+pub mod replicator;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod uploader;
 mod writer;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use fastcrypto::hash::{HashFunction, Sha3_256};
 use num_enum::IntoPrimitive;
 use num_enum::TryFromPrimitive;
 use object_store::path::Path;
@@ -20,11 +26,15 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::epoch_start_configuration::EpochStartConfiguration;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::epoch::committee_store::CommitteeStore;
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
 use sui_storage::object_store::util::path_to_filesystem;
 use sui_storage::{compute_sha3_checksum, FileCompression, SHA3_BYTES};
 use sui_types::accumulator::Accumulator;
 use sui_types::authenticator_state::get_authenticator_state_obj_initial_shared_version;
 use sui_types::base_types::ObjectID;
+use sui_types::committee::Committee;
+use sui_types::messages_checkpoint::CertifiedCheckpointSummary;
+use sui_types::randomness_state::get_randomness_state_obj_initial_shared_version;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::sui_system_state::SuiSystemStateTrait;
@@ -57,9 +67,26 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 ///        - ...
 ///        - REFERENCE-1000
 ///        - MANIFEST
+///        - EPOCH_METADATA
 ///     - epoch_1/
 ///       - 1_1.obj
 ///       - ...
+///
+/// Writers can optionally also partition the live object set by type, for analytics jobs that only
+/// need some object types and would otherwise have to restore the whole snapshot. Objects are then
+/// written a second time to files in the same format as *.obj files, under a directory per struct
+/// (with type parameters erased) and one for packages. Wrapped object tombstones are left out. The
+/// TYPE_MANIFEST file lists these files and is uploaded before MANIFEST.
+///  - epoch_0/
+///     - types/
+///        - 0x0000..0002/
+///           - coin/
+///              - Coin/
+///                 - 1.obj
+///                 - 2.obj
+///        - package/
+///           - 1.obj
+///     - TYPE_MANIFEST
 /// Object File Disk Format
 ///┌──────────────────────────────┐
 ///│  magic(0x00B7EC75) <4 byte>  │
@@ -102,9 +129,20 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 ///├──────────────────────────────┤
 ///│      sha3 <32 bytes>         │
 ///└──────────────────────────────┘
+///
+/// TYPE_MANIFEST has the same format as MANIFEST, with magic 0x00C0FFEF and a serialized
+/// TypeManifest.
+///
+/// EPOCH_METADATA has the same format as MANIFEST, with magic 0x00C0FFF0 and a serialized
+/// EpochMetadata: the committee of the epoch, the digest of its protocol config and the certified
+/// summary of its last checkpoint. It lets a node restored from the snapshot check that it
+/// continues from the epoch the snapshot was taken at, before it contacts any peers. It is
+/// uploaded before MANIFEST, and is missing from snapshots written before it was introduced.
 const OBJECT_FILE_MAGIC: u32 = 0x00B7EC75;
 const REFERENCE_FILE_MAGIC: u32 = 0xDEADBEEF;
 const MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEE;
+const TYPE_MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEF;
+const EPOCH_METADATA_FILE_MAGIC: u32 = 0x00C0FFF0;
 const MAGIC_BYTES: usize = 4;
 const SNAPSHOT_VERSION_BYTES: usize = 1;
 const ADDRESS_LENGTH_BYTES: usize = 8;
@@ -157,6 +195,68 @@ impl FileMetadata {
     }
 }
 
+/// Name of the type partition holding package objects.
+pub const PACKAGE_TYPE_PARTITION: &str = "package";
+
+/// Metadata of an object file in the type partitioned layout.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TypedFileMetadata {
+    /// `<address>::<module>::<name>` of the struct the objects in the file are instances of, or
+    /// `PACKAGE_TYPE_PARTITION` for package objects.
+    pub object_type: String,
+    pub part_num: u32,
+    pub num_objects: u64,
+    pub file_compression: FileCompression,
+    pub sha3_digest: [u8; 32],
+}
+
+impl TypedFileMetadata {
+    pub fn file_path(&self, dir_path: &Path) -> Path {
+        let type_dir = self
+            .object_type
+            .split("::")
+            .fold(dir_path.child("types"), |path, part| path.child(part));
+        type_dir.child(&*format!("{}.obj", self.part_num))
+    }
+    pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
+        path_to_filesystem(root_path.to_path_buf(), &self.file_path(dir_path))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TypeManifestV1 {
+    pub epoch: u64,
+    pub file_metadata: Vec<TypedFileMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TypeManifest {
+    V1(TypeManifestV1),
+}
+
+impl TypeManifest {
+    pub fn epoch(&self) -> u64 {
+        match self {
+            Self::V1(manifest) => manifest.epoch,
+        }
+    }
+    pub fn file_metadata(&self) -> &Vec<TypedFileMetadata> {
+        match self {
+            Self::V1(manifest) => &manifest.file_metadata,
+        }
+    }
+    /// Files holding objects of the struct `<address>::<module>::<name>`, or of packages if
+    /// `object_type` is `PACKAGE_TYPE_PARTITION`.
+    pub fn files_for_type<'a>(
+        &'a self,
+        object_type: &'a str,
+    ) -> impl Iterator<Item = &'a TypedFileMetadata> + 'a {
+        self.file_metadata()
+            .iter()
+            .filter(move |file_metadata| file_metadata.object_type == object_type)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct ManifestV1 {
     pub snapshot_version: u8,
@@ -165,32 +265,234 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ManifestV2 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    /// Protocol version of the network in the epoch the snapshot was taken at
+    pub protocol_version: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    V2(ManifestV2),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::V2(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::V2(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::V2(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::V2(manifest) => manifest.epoch,
+        }
+    }
+    /// Protocol version the snapshot was written at, if it was recorded (it is missing from
+    /// snapshots written before `ManifestV2`).
+    pub fn protocol_version(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(manifest) => Some(manifest.protocol_version),
+        }
+    }
+    /// Refuse to restore a snapshot into a binary that does not support the protocol version it
+    /// was written at, because the objects in it may not be interpreted correctly.
+    pub fn check_protocol_version(
+        &self,
+        supported_protocol_versions: &SupportedProtocolVersions,
+    ) -> Result<()> {
+        let Some(protocol_version) = self.protocol_version() else {
+            return Ok(());
+        };
+        if !supported_protocol_versions.is_version_supported(ProtocolVersion::new(protocol_version))
+        {
+            return Err(anyhow!(
+                "Snapshot for epoch {} was written at protocol version {}, which is outside of the \
+                 range supported by this binary: [{}, {}]",
+                self.epoch(),
+                protocol_version,
+                supported_protocol_versions.min.as_u64(),
+                supported_protocol_versions.max.as_u64(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EpochMetadataV1 {
+    pub epoch: u64,
+    /// Committee that certified the checkpoints of the epoch
+    pub committee: Committee,
+    pub protocol_version: u64,
+    /// Sha3 digest of the protocol config in effect during the epoch, see `protocol_config_digest`
+    pub protocol_config_digest: [u8; 32],
+    /// Last checkpoint of the epoch, certified by `committee`
+    pub last_checkpoint: CertifiedCheckpointSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EpochMetadata {
+    V1(EpochMetadataV1),
+}
+
+impl EpochMetadata {
+    /// Collects the metadata of `epoch`, which must have ended, from the stores of a node.
+    pub fn new(
+        epoch: u64,
+        protocol_config: &ProtocolConfig,
+        checkpoint_store: &CheckpointStore,
+        committee_store: &CommitteeStore,
+    ) -> Result<Self> {
+        let committee = committee_store
+            .get_committee(&epoch)?
+            .ok_or_else(|| anyhow!("No committee found for epoch: {epoch}"))?;
+        let last_checkpoint = checkpoint_store
+            .get_epoch_last_checkpoint(epoch)?
+            .ok_or_else(|| anyhow!("No last checkpoint found for epoch: {epoch}"))?;
+        Ok(Self::V1(EpochMetadataV1 {
+            epoch,
+            committee: (*committee).clone(),
+            protocol_version: protocol_config.version.as_u64(),
+            protocol_config_digest: protocol_config_digest(protocol_config)?,
+            last_checkpoint: last_checkpoint.into_inner(),
+        }))
+    }
+    pub fn epoch(&self) -> u64 {
+        match self {
+            Self::V1(metadata) => metadata.epoch,
+        }
+    }
+    pub fn committee(&self) -> &Committee {
+        match self {
+            Self::V1(metadata) => &metadata.committee,
         }
     }
+    pub fn protocol_version(&self) -> u64 {
+        match self {
+            Self::V1(metadata) => metadata.protocol_version,
+        }
+    }
+    pub fn protocol_config_digest(&self) -> &[u8; 32] {
+        match self {
+            Self::V1(metadata) => &metadata.protocol_config_digest,
+        }
+    }
+    pub fn last_checkpoint(&self) -> &CertifiedCheckpointSummary {
+        match self {
+            Self::V1(metadata) => &metadata.last_checkpoint,
+        }
+    }
+    /// The committee of the epoch after this one, as recorded in its last checkpoint.
+    pub fn next_epoch_committee(&self) -> Result<Committee> {
+        let next_epoch_committee =
+            self.last_checkpoint()
+                .next_epoch_committee()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Last checkpoint of epoch {} has no end of epoch data",
+                        self.epoch()
+                    )
+                })?;
+        Ok(Committee::new(
+            self.epoch() + 1,
+            next_epoch_committee.iter().cloned().collect(),
+        ))
+    }
+    /// Checks that the metadata is for `epoch`, and that its last checkpoint ends the epoch and
+    /// is certified by its committee. If the node already knows the committee of the epoch (e.g.
+    /// from the end of the previous epoch), it should pass it as `trusted_committee`, otherwise
+    /// the committee in the metadata is only checked for consistency.
+    pub fn verify(&self, epoch: u64, trusted_committee: Option<&Committee>) -> Result<()> {
+        if self.epoch() != epoch {
+            return Err(anyhow!(
+                "Epoch metadata is for epoch {}, expected: {}",
+                self.epoch(),
+                epoch
+            ));
+        }
+        let committee = self.committee();
+        if committee.epoch != epoch {
+            return Err(anyhow!(
+                "Committee in epoch metadata is for epoch {}, expected: {}",
+                committee.epoch,
+                epoch
+            ));
+        }
+        if let Some(trusted_committee) = trusted_committee {
+            if trusted_committee != committee {
+                return Err(anyhow!(
+                    "Committee in epoch metadata does not match the committee of epoch {}",
+                    epoch
+                ));
+            }
+        }
+        let last_checkpoint = self.last_checkpoint();
+        if last_checkpoint.epoch() != epoch {
+            return Err(anyhow!(
+                "Last checkpoint in epoch metadata is from epoch {}, expected: {}",
+                last_checkpoint.epoch(),
+                epoch
+            ));
+        }
+        if last_checkpoint.end_of_epoch_data.is_none() {
+            return Err(anyhow!(
+                "Checkpoint {} in epoch metadata is not the last of epoch {}",
+                last_checkpoint.sequence_number,
+                epoch
+            ));
+        }
+        last_checkpoint
+            .verify_authority_signatures(committee)
+            .map_err(|e| anyhow!("Last checkpoint of epoch {} is not certified: {}", epoch, e))?;
+        Ok(())
+    }
+    /// Checks that the protocol config this binary has for the metadata's protocol version on
+    /// `chain` is the one that was in effect during the epoch.
+    pub fn check_protocol_config(&self, chain: Chain) -> Result<()> {
+        let protocol_config =
+            ProtocolConfig::get_for_version(ProtocolVersion::new(self.protocol_version()), chain);
+        if &protocol_config_digest(&protocol_config)? != self.protocol_config_digest() {
+            return Err(anyhow!(
+                "Protocol config of version {} differs from the one epoch {} ran with",
+                self.protocol_version(),
+                self.epoch()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Sha3 digest of the BCS serialized values of every attribute and feature flag of
+/// `protocol_config`, by name. Unlike the BCS of the config itself, which only holds the values of
+/// the attributes in the order they are declared in, this changes whenever the value of any
+/// attribute does, and not when attributes are merely reordered.
+pub fn protocol_config_digest(protocol_config: &ProtocolConfig) -> Result<[u8; 32]> {
+    let mut hasher = Sha3_256::default();
+    hasher.update(bcs::to_bytes(&(
+        protocol_config.attr_map(),
+        protocol_config.feature_map(),
+    ))?);
+    Ok(hasher.finalize().digest)
 }
 
 pub fn create_file_metadata(
@@ -224,6 +526,8 @@ pub async fn setup_db_state(
     let system_state_object = get_sui_system_state(&perpetual_db)?;
     let authenticator_state_obj_initial_shared_version =
         get_authenticator_state_obj_initial_shared_version(&perpetual_db)?;
+    let randomness_state_obj_initial_shared_version =
+        get_randomness_state_obj_initial_shared_version(&perpetual_db)?;
     let new_epoch_start_state = system_state_object.into_epoch_start_state();
     let next_epoch_committee = new_epoch_start_state.get_sui_committee();
     let last_checkpoint = checkpoint_store
@@ -234,6 +538,7 @@ pub async fn setup_db_state(
         new_epoch_start_state,
         *last_checkpoint.digest(),
         authenticator_state_obj_initial_shared_version,
+        randomness_state_obj_initial_shared_version,
     );
     perpetual_db
         .set_epoch_start_configuration(&epoch_start_configuration)
@@ -0,0 +1,218 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reader::{LiveObjectIter, ObjectRefIter, StateSnapshotReaderV1};
+use crate::{FileMetadata, FileType};
+use anyhow::{anyhow, Context, Result};
+use object_store::path::Path;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_core::authority::authority_store_tables::LiveObject;
+use sui_storage::object_store::util::path_to_filesystem;
+use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, VersionNumber};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::object::Object;
+use sui_types::storage::ObjectStore;
+
+/// Where an object is in the snapshot: its partition within the bucket, the offset of its record in
+/// that partition's object file, and its ref.
+#[derive(Copy, Clone)]
+struct ObjectLocation {
+    part_num: u32,
+    offset: u64,
+    object_ref: ObjectRef,
+}
+
+/// Locations of the objects in a bucket.
+type BucketIndex = HashMap<ObjectID, ObjectLocation>;
+
+/// MountedSnapshot serves the live object set of a snapshot that has already been downloaded to a
+/// local directory, straight from its files, without restoring it into a database. The files of a
+/// bucket are only indexed once a lookup needs to search that bucket, and a lookup then reads just
+/// the record of the object it found.
+pub struct MountedSnapshot {
+    epoch: u64,
+    local_dir_root: PathBuf,
+    ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    object_files: BTreeMap<(u32, u32), FileMetadata>,
+    indices: Mutex<BTreeMap<u32, Arc<BucketIndex>>>,
+}
+
+impl MountedSnapshot {
+    /// Opens the snapshot of `epoch` under `local_dir_root`, which is laid out the same way as the
+    /// remote store the snapshot was downloaded from (i.e. it contains an `epoch_<epoch>`
+    /// directory with the MANIFEST and all the files it lists).
+    pub fn open(local_dir_root: PathBuf, epoch: u64) -> Result<Self> {
+        let epoch_dir = Path::from(format!("epoch_{}", epoch));
+        let manifest = StateSnapshotReaderV1::read_manifest(path_to_filesystem(
+            local_dir_root.clone(),
+            &epoch_dir.child("MANIFEST"),
+        )?)?;
+        let snapshot_version = manifest.snapshot_version();
+        if snapshot_version != 1u8 {
+            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
+        }
+        if manifest.epoch() != epoch {
+            return Err(anyhow!("Local manifest is not for epoch: {}", epoch));
+        }
+
+        let mut ref_files = BTreeMap::new();
+        let mut object_files = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            let (bucket_num, part_num) = (file_metadata.bucket_num, file_metadata.part_num);
+            match file_metadata.file_type {
+                FileType::Object => {
+                    object_files.insert((bucket_num, part_num), file_metadata.clone());
+                }
+                FileType::Reference => {
+                    ref_files
+                        .entry(bucket_num)
+                        .or_insert_with(BTreeMap::new)
+                        .insert(part_num, file_metadata.clone());
+                }
+            }
+        }
+        Ok(MountedSnapshot {
+            epoch,
+            local_dir_root,
+            ref_files,
+            object_files,
+            indices: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The reference of `object_id` in the snapshot, if it was live at the end of the epoch.
+    pub fn get_object_ref(&self, object_id: &ObjectID) -> Result<Option<ObjectRef>> {
+        Ok(self
+            .find_object(object_id)?
+            .map(|(_, location)| location.object_ref))
+    }
+
+    /// Reads `object_id` out of the snapshot, as long as it is at `version` if one is given.
+    fn read_live_object(
+        &self,
+        object_id: &ObjectID,
+        version: Option<VersionNumber>,
+    ) -> Result<Option<Object>> {
+        let Some((bucket_num, location)) = self.find_object(object_id)? else {
+            return Ok(None);
+        };
+        if location.object_ref.2 == ObjectDigest::OBJECT_DIGEST_WRAPPED
+            || version.is_some_and(|version| version != location.object_ref.1)
+        {
+            return Ok(None);
+        }
+        self.read_object(bucket_num, location)
+    }
+
+    /// Searches the buckets in order for `object_id`, indexing each one the first time it is
+    /// searched.
+    fn find_object(&self, object_id: &ObjectID) -> Result<Option<(u32, ObjectLocation)>> {
+        for bucket_num in self.ref_files.keys() {
+            let index = self.bucket_index(*bucket_num)?;
+            if let Some(location) = index.get(object_id) {
+                return Ok(Some((*bucket_num, *location)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn bucket_index(&self, bucket_num: u32) -> Result<Arc<BucketIndex>> {
+        if let Some(index) = self.indices.lock().get(&bucket_num) {
+            return Ok(index.clone());
+        }
+        let mut index = BucketIndex::new();
+        for (part_num, file_metadata) in &self.ref_files[&bucket_num] {
+            let refs =
+                ObjectRefIter::new(file_metadata, self.local_dir_root.clone(), self.epoch_dir())?;
+            // Objects and their refs are written in the same order, so the n-th ref is of the
+            // object in the n-th record of the object file.
+            let offsets = LiveObjectIter::record_offsets(
+                self.object_file(bucket_num, *part_num)?,
+                &self.object_file_path(bucket_num, *part_num)?,
+            )?;
+            let mut num_refs = 0;
+            for (object_ref, offset) in refs.zip(offsets.iter()) {
+                index.insert(
+                    object_ref.0,
+                    ObjectLocation {
+                        part_num: *part_num,
+                        offset: *offset,
+                        object_ref,
+                    },
+                );
+                num_refs += 1;
+            }
+            anyhow::ensure!(
+                num_refs == offsets.len(),
+                "Reference and object files of bucket: {}, part: {} hold a different number of objects",
+                bucket_num,
+                part_num
+            );
+        }
+        let index = Arc::new(index);
+        self.indices.lock().insert(bucket_num, index.clone());
+        Ok(index)
+    }
+
+    /// Reads the record at `location` in the object file of `bucket_num`, and checks that it is
+    /// the object the index says it is.
+    fn read_object(&self, bucket_num: u32, location: ObjectLocation) -> Result<Option<Object>> {
+        let object = LiveObjectIter::read_at(
+            self.object_file(bucket_num, location.part_num)?,
+            &self.object_file_path(bucket_num, location.part_num)?,
+            location.offset,
+        )?;
+        let LiveObject::Normal(object) = object else {
+            return Ok(None);
+        };
+        let computed_ref = object.compute_object_reference();
+        anyhow::ensure!(
+            computed_ref == location.object_ref,
+            "Object {:?} doesn't match the snapshot reference: {:?}",
+            computed_ref,
+            location.object_ref
+        );
+        Ok(Some(object))
+    }
+
+    fn object_file(&self, bucket_num: u32, part_num: u32) -> Result<&FileMetadata> {
+        self.object_files
+            .get(&(bucket_num, part_num))
+            .context(format!(
+                "No object file found for bucket: {}, part: {}",
+                bucket_num, part_num
+            ))
+    }
+
+    fn object_file_path(&self, bucket_num: u32, part_num: u32) -> Result<PathBuf> {
+        self.object_file(bucket_num, part_num)?
+            .local_file_path(&self.local_dir_root, &self.epoch_dir())
+    }
+
+    fn epoch_dir(&self) -> Path {
+        Path::from(format!("epoch_{}", self.epoch))
+    }
+}
+
+impl ObjectStore for MountedSnapshot {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.read_live_object(object_id, None)
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> SuiResult<Option<Object>> {
+        self.read_live_object(object_id, Some(version))
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))
+    }
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
+    EpochMetadata, FileMetadata, FileType, Manifest, TypeManifest, EPOCH_METADATA_FILE_MAGIC,
+    MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC, OBJECT_ID_BYTES, OBJECT_REF_BYTES,
+    REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES, TYPE_MANIFEST_FILE_MAGIC,
 };
 use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ReadBytesExt};
@@ -11,23 +12,26 @@ use bytes::{Buf, Bytes};
 use fastcrypto::hash::{HashFunction, Sha3_256};
 use futures::future::{AbortRegistration, Abortable};
 use futures::{StreamExt, TryStreamExt};
-use integer_encoding::VarIntReader;
+use integer_encoding::{VarInt, VarIntReader};
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use std::collections::BTreeMap;
 use std::fs;
-use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::authority::AuthorityStore;
-use sui_storage::blob::{Blob, BlobEncoding};
+use sui_protocol_config::SupportedProtocolVersions;
+use sui_storage::blob::{Blob, BlobEncoding, BLOB_ENCODING_BYTES};
 use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem};
 use sui_storage::object_store::ObjectStoreConfig;
+use sui_storage::FileCompression;
 use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
 use tokio::sync::Mutex;
+use tracing::info;
+use typed_store::rocks::ColumnFamilyStats;
 
 pub type DigestByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, [u8; 32]>>;
 pub struct StateSnapshotReaderV1 {
@@ -37,6 +41,7 @@ pub struct StateSnapshotReaderV1 {
     local_object_store: Arc<DynObjectStore>,
     ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
     object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    epoch_metadata: Option<EpochMetadata>,
     indirect_objects_threshold: usize,
     concurrency: usize,
 }
@@ -48,6 +53,7 @@ impl StateSnapshotReaderV1 {
         local_store_config: &ObjectStoreConfig,
         indirect_objects_threshold: usize,
         download_concurrency: NonZeroUsize,
+        supported_protocol_versions: SupportedProtocolVersions,
     ) -> Result<Self> {
         let epoch_dir = format!("epoch_{}", epoch);
         let remote_object_store = remote_store_config.make()?;
@@ -89,6 +95,20 @@ impl StateSnapshotReaderV1 {
         if manifest.epoch() != epoch {
             return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
         }
+        manifest.check_protocol_version(&supported_protocol_versions)?;
+        let epoch_metadata =
+            Self::download_epoch_metadata(&remote_object_store, &Path::from(epoch_dir.clone()))
+                .await?;
+        if let Some(epoch_metadata) = &epoch_metadata {
+            epoch_metadata.verify(epoch, None)?;
+            if manifest.protocol_version() != Some(epoch_metadata.protocol_version()) {
+                return Err(anyhow!(
+                    "Epoch metadata is for protocol version {}, but the manifest is for: {:?}",
+                    epoch_metadata.protocol_version(),
+                    manifest.protocol_version(),
+                ));
+            }
+        }
         let mut object_files = BTreeMap::new();
         let mut ref_files = BTreeMap::new();
         for file_metadata in manifest.file_metadata() {
@@ -133,11 +153,35 @@ impl StateSnapshotReaderV1 {
             local_object_store,
             ref_files,
             object_files,
+            epoch_metadata,
             indirect_objects_threshold,
             concurrency: download_concurrency.get(),
         })
     }
 
+    /// Committee, protocol config digest and last checkpoint of the epoch the snapshot was taken
+    /// at, if the snapshot has them. They have been checked to be consistent with each other, but
+    /// not against the committee of the epoch, which the caller can do with
+    /// `EpochMetadata::verify` if it knows it.
+    pub fn epoch_metadata(&self) -> Option<&EpochMetadata> {
+        self.epoch_metadata.as_ref()
+    }
+
+    async fn download_epoch_metadata(
+        remote_object_store: &Arc<DynObjectStore>,
+        epoch_dir: &Path,
+    ) -> Result<Option<EpochMetadata>> {
+        match remote_object_store
+            .get(&epoch_dir.child("EPOCH_METADATA"))
+            .await
+        {
+            Ok(result) => Ok(Some(Self::parse_epoch_metadata(&result.bytes().await?)?)),
+            // Snapshots written before EPOCH_METADATA was introduced don't have one
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
@@ -235,6 +279,36 @@ impl StateSnapshotReaderV1 {
         .await?
     }
 
+    /// Reports the size and shape of the tables `read` restored the live object set to, first
+    /// compacting them if `compact` is set, so that operators can tell whether the database is in
+    /// a steady state before it starts serving traffic.
+    pub fn finalize(
+        &self,
+        perpetual_db: &AuthorityPerpetualTables,
+        compact: bool,
+    ) -> Result<Vec<ColumnFamilyStats>> {
+        if compact {
+            info!("Compacting restored tables for epoch: {}", self.epoch);
+            perpetual_db.compact_live_object_tables();
+        }
+
+        let stats = perpetual_db.live_object_table_stats()?;
+        for table in &stats {
+            info!(
+                "Restored table {}: total sst files size: {}, estimated live data size: {}, \
+                 estimated number of keys: {}, files per level: {:?}, estimated read \
+                 amplification: {}",
+                table.name,
+                table.total_sst_files_size,
+                table.estimated_live_data_size,
+                table.estimated_num_keys,
+                table.files_per_level,
+                table.estimated_read_amplification(),
+            );
+        }
+        Ok(stats)
+    }
+
     pub fn ref_iter(&mut self, bucket_num: u32, part_num: u32) -> Result<ObjectRefIter> {
         let file_metadata = self
             .ref_files
@@ -259,23 +333,53 @@ impl StateSnapshotReaderV1 {
         Path::from(format!("epoch_{}", self.epoch))
     }
 
-    fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
-        let manifest_file = File::open(path)?;
-        let manifest_file_size = manifest_file.metadata()?.len() as usize;
-        let mut manifest_reader = BufReader::new(manifest_file);
-        manifest_reader.rewind()?;
+    pub(crate) fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
+        Self::read_manifest_file(path, MANIFEST_FILE_MAGIC)
+    }
+
+    /// Reads the TYPE_MANIFEST listing the type partitioned object files of a snapshot.
+    pub fn read_type_manifest(path: PathBuf) -> anyhow::Result<TypeManifest> {
+        Self::read_manifest_file(path, TYPE_MANIFEST_FILE_MAGIC)
+    }
+
+    /// Parses the contents of a MANIFEST file, e.g. as downloaded from a remote store.
+    pub(crate) fn parse_manifest(bytes: &[u8]) -> anyhow::Result<Manifest> {
+        Self::parse_manifest_bytes(bytes, MANIFEST_FILE_MAGIC)
+    }
+
+    /// Parses the contents of a TYPE_MANIFEST file.
+    pub(crate) fn parse_type_manifest(bytes: &[u8]) -> anyhow::Result<TypeManifest> {
+        Self::parse_manifest_bytes(bytes, TYPE_MANIFEST_FILE_MAGIC)
+    }
+
+    /// Parses the contents of an EPOCH_METADATA file.
+    pub(crate) fn parse_epoch_metadata(bytes: &[u8]) -> anyhow::Result<EpochMetadata> {
+        Self::parse_manifest_bytes(bytes, EPOCH_METADATA_FILE_MAGIC)
+    }
+
+    fn read_manifest_file<T: serde::de::DeserializeOwned>(
+        path: PathBuf,
+        expected_magic: u32,
+    ) -> anyhow::Result<T> {
+        let bytes = fs::read(path)?;
+        Self::parse_manifest_bytes(&bytes, expected_magic)
+    }
+
+    fn parse_manifest_bytes<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        expected_magic: u32,
+    ) -> anyhow::Result<T> {
+        if bytes.len() < MAGIC_BYTES + SHA3_BYTES {
+            return Err(anyhow!("Manifest is too short: {} bytes", bytes.len()));
+        }
+        let mut manifest_reader = bytes;
         let magic = manifest_reader.read_u32::<BigEndian>()?;
-        if magic != MANIFEST_FILE_MAGIC {
+        if magic != expected_magic {
             return Err(anyhow!("Unexpected magic byte: {}", magic));
         }
-        manifest_reader.seek(SeekFrom::End(-(SHA3_BYTES as i64)))?;
-        let mut sha3_digest = [0u8; SHA3_BYTES];
-        manifest_reader.read_exact(&mut sha3_digest)?;
-        manifest_reader.rewind()?;
-        let mut content_buf = vec![0u8; manifest_file_size - SHA3_BYTES];
-        manifest_reader.read_exact(&mut content_buf)?;
+        let (content_buf, sha3_digest) = bytes.split_at(bytes.len() - SHA3_BYTES);
         let mut hasher = Sha3_256::default();
-        hasher.update(&content_buf);
+        hasher.update(content_buf);
         let computed_digest = hasher.finalize().digest;
         if computed_digest != sha3_digest {
             return Err(anyhow!(
@@ -284,8 +388,6 @@ impl StateSnapshotReaderV1 {
                 sha3_digest
             ));
         }
-        manifest_reader.rewind()?;
-        manifest_reader.seek(SeekFrom::Start(MAGIC_BYTES as u64))?;
         let manifest = bcs::from_bytes(&content_buf[MAGIC_BYTES..])?;
         Ok(manifest)
     }
@@ -299,7 +401,15 @@ pub struct ObjectRefIter {
 impl ObjectRefIter {
     pub fn new(file_metadata: &FileMetadata, root_path: PathBuf, dir_path: Path) -> Result<Self> {
         let file_path = file_metadata.local_file_path(&root_path, &dir_path)?;
-        let mut reader = file_metadata.file_compression.decompress(&file_path)?;
+        Self::from_reader(file_metadata.file_compression.decompress(&file_path)?)
+    }
+
+    /// Iterates over the object refs in `bytes`, the contents of a .ref file.
+    pub fn from_bytes(file_compression: FileCompression, bytes: Bytes) -> Result<Self> {
+        Self::from_reader(file_compression.bytes_decompress(bytes)?)
+    }
+
+    fn from_reader(mut reader: Box<dyn Read>) -> Result<Self> {
         let magic = reader.read_u32::<BigEndian>()?;
         if magic != REFERENCE_FILE_MAGIC {
             Err(anyhow!(
@@ -342,7 +452,13 @@ pub struct LiveObjectIter {
 
 impl LiveObjectIter {
     pub fn new(file_metadata: &FileMetadata, bytes: Bytes) -> Result<Self> {
-        let mut reader = file_metadata.file_compression.bytes_decompress(bytes)?;
+        Self::from_bytes(file_metadata.file_compression, bytes)
+    }
+
+    /// Iterates over the objects in `bytes`, which can be the contents of any file in the object
+    /// file format, including the type partitioned ones.
+    pub fn from_bytes(file_compression: FileCompression, bytes: Bytes) -> Result<Self> {
+        let mut reader = file_compression.bytes_decompress(bytes)?;
         let magic = reader.read_u32::<BigEndian>()?;
         if magic != OBJECT_FILE_MAGIC {
             Err(anyhow!(
@@ -354,6 +470,74 @@ impl LiveObjectIter {
         }
     }
 
+    /// Offsets of the records in the object file at `file_path`, as if it were uncompressed, in the
+    /// order they were written, which is also the order of the refs in the matching reference file.
+    /// The objects are skipped over without being decoded.
+    pub fn record_offsets(file_metadata: &FileMetadata, file_path: &PathBuf) -> Result<Vec<u64>> {
+        let mut reader = file_metadata.file_compression.decompress(file_path)?;
+        let magic = reader.read_u32::<BigEndian>()?;
+        if magic != OBJECT_FILE_MAGIC {
+            return Err(anyhow!(
+                "Unexpected magic string in object file: {:?}",
+                magic
+            ));
+        }
+        let mut offsets = vec![];
+        let mut offset = MAGIC_BYTES as u64;
+        loop {
+            let len = match reader.read_varint::<u64>() {
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            };
+            if len == 0 {
+                return Err(anyhow!("Invalid object length of 0 in file"));
+            }
+            let record_len = BLOB_ENCODING_BYTES as u64 + len;
+            let skipped = io::copy(&mut reader.by_ref().take(record_len), &mut io::sink())?;
+            if skipped != record_len {
+                return Err(anyhow!("Truncated object at offset {} in file", offset));
+            }
+            offsets.push(offset);
+            offset += len.required_space() as u64 + record_len;
+        }
+        Ok(offsets)
+    }
+
+    /// Reads the single object whose record starts at `offset`, as returned by `record_offsets`, in
+    /// the object file at `file_path`. Uncompressed files are seeked into, compressed ones are
+    /// decompressed up to the record.
+    pub fn read_at(
+        file_metadata: &FileMetadata,
+        file_path: &PathBuf,
+        offset: u64,
+    ) -> Result<LiveObject> {
+        let mut reader: Box<dyn Read> = match file_metadata.file_compression {
+            FileCompression::None => {
+                let mut file = fs::File::open(file_path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                Box::new(BufReader::new(file))
+            }
+            FileCompression::Zstd => {
+                let mut reader = file_metadata.file_compression.decompress(file_path)?;
+                io::copy(&mut reader.by_ref().take(offset), &mut io::sink())?;
+                reader
+            }
+        };
+        let len = reader.read_varint::<u64>()? as usize;
+        if len == 0 {
+            return Err(anyhow!("Invalid object length of 0 in file"));
+        }
+        let encoding = reader.read_u8()?;
+        let mut data = vec![0u8; len];
+        reader.read_exact(&mut data)?;
+        let blob = Blob {
+            data,
+            encoding: BlobEncoding::try_from(encoding)?,
+        };
+        blob.decode()
+    }
+
     fn next_object(&mut self) -> Result<LiveObject> {
         let len = self.reader.read_varint::<u64>()? as usize;
         if len == 0 {
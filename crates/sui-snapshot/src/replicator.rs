@@ -0,0 +1,326 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reader::StateSnapshotReaderV1;
+use anyhow::{anyhow, Context, Result};
+use bytes::Bytes;
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntCounterVec,
+    IntGaugeVec, Registry,
+};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_core::db_checkpoint_handler::SUCCESS_MARKER;
+use sui_storage::object_store::util::{
+    find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, get, put,
+};
+use sui_storage::object_store::ObjectStoreConfig;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::oneshot::{channel, Sender};
+use tracing::{error, info};
+
+pub struct StateSnapshotReplicatorMetrics {
+    pub first_missing_replicated_state_snapshot_epoch: IntGaugeVec,
+    pub state_snapshot_replication_failures: IntCounterVec,
+}
+
+impl StateSnapshotReplicatorMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            first_missing_replicated_state_snapshot_epoch: register_int_gauge_vec_with_registry!(
+                "first_missing_replicated_state_snapshot_epoch",
+                "First epoch for which there is no state snapshot in a replica store",
+                &["replica"],
+                registry
+            )
+            .unwrap(),
+            state_snapshot_replication_failures: register_int_counter_vec_with_registry!(
+                "state_snapshot_replication_failures",
+                "Number of times replicating state snapshots to a replica store failed",
+                &["replica"],
+                registry
+            )
+            .unwrap(),
+        }
+    }
+}
+
+struct Replica {
+    /// Bucket or directory of the replica, used to label metrics and logs
+    name: String,
+    store: Arc<DynObjectStore>,
+}
+
+/// StateSnapshotReplicator mirrors the snapshots completed in the primary snapshot store to a list
+/// of secondary stores, e.g. buckets in other regions, so that restores do not depend on a single
+/// bucket being available. Every file is checked against the checksum its manifest records for it,
+/// both as read from the primary store and as read back from the replica. Manifests are copied
+/// after the files they list, and the success marker last, so an epoch whose replication was
+/// interrupted is copied again in full on the next attempt.
+pub struct StateSnapshotReplicator {
+    /// Store the uploader writes snapshots to
+    snapshot_store: Arc<DynObjectStore>,
+    replicas: Vec<Replica>,
+    concurrency: NonZeroUsize,
+    metrics: StateSnapshotReplicatorMetrics,
+}
+
+impl StateSnapshotReplicator {
+    pub fn new(
+        snapshot_store: Arc<DynObjectStore>,
+        replica_store_configs: &[ObjectStoreConfig],
+        concurrency: NonZeroUsize,
+        registry: &Registry,
+    ) -> Result<Self> {
+        let replicas = replica_store_configs
+            .iter()
+            .enumerate()
+            .map(|(i, config)| {
+                let name = config
+                    .bucket
+                    .clone()
+                    .or_else(|| config.directory.as_ref().map(|d| d.display().to_string()))
+                    .unwrap_or_else(|| format!("replica_{i}"));
+                Ok(Replica {
+                    name,
+                    store: config.make()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(StateSnapshotReplicator {
+            snapshot_store,
+            replicas,
+            concurrency,
+            metrics: StateSnapshotReplicatorMetrics::new(registry),
+        })
+    }
+
+    /// Replicates snapshots every `interval`, in a task of its own so that copying snapshots does
+    /// not hold back uploading them, until the returned sender is used or dropped.
+    pub fn start(self, interval: Duration) -> Sender<()> {
+        let (sender, mut recv) = channel::<()>();
+        let mut interval = tokio::time::interval(interval);
+        tokio::task::spawn(async move {
+            info!("State snapshot replicator loop started");
+            loop {
+                tokio::select! {
+                    _now = interval.tick() => {
+                        if let Err(err) = self.replicate().await {
+                            error!("Failed to replicate state snapshots with err: {:?}", err);
+                        }
+                    },
+                    _ = &mut recv => break,
+                }
+            }
+        });
+        sender
+    }
+
+    /// Copies every snapshot that is complete in the primary store but missing from a replica to
+    /// that replica. Replicas are handled independently, so one that is unavailable does not hold
+    /// back the others.
+    pub async fn replicate(&self) -> Result<()> {
+        let mut failures = 0;
+        for replica in &self.replicas {
+            if let Err(err) = self.replicate_to(replica).await {
+                error!(
+                    "Failed to replicate state snapshots to {} with err: {:?}",
+                    replica.name, err
+                );
+                self.metrics
+                    .state_snapshot_replication_failures
+                    .with_label_values(&[&replica.name])
+                    .inc();
+                failures += 1;
+            }
+        }
+        if failures > 0 {
+            return Err(anyhow!(
+                "Failed to replicate state snapshots to {failures} replica(s)"
+            ));
+        }
+        Ok(())
+    }
+
+    async fn replicate_to(&self, replica: &Replica) -> Result<()> {
+        let missing_epochs = find_missing_epochs_dirs(&replica.store, SUCCESS_MARKER).await?;
+        let first_missing_epoch = missing_epochs.first().cloned().unwrap_or(0);
+        let last_missing_epoch = missing_epochs.last().cloned().unwrap_or(0);
+        self.metrics
+            .first_missing_replicated_state_snapshot_epoch
+            .with_label_values(&[&replica.name])
+            .set(first_missing_epoch as i64);
+        let snapshots_by_epoch =
+            find_all_dirs_with_epoch_prefix(&self.snapshot_store, None).await?;
+        let mut failures = 0;
+        for (epoch, epoch_dir) in snapshots_by_epoch {
+            if !missing_epochs.contains(&epoch) && epoch < last_missing_epoch {
+                continue;
+            }
+            // Snapshots still being uploaded are picked up once they are complete
+            if self
+                .snapshot_store
+                .head(&epoch_dir.child(SUCCESS_MARKER))
+                .await
+                .is_err()
+            {
+                continue;
+            }
+            // An epoch that fails to replicate, e.g. because a file in the primary store is
+            // corrupt, is retried on the next attempt, and does not hold back the ones after it
+            if let Err(err) = self.replicate_epoch(&epoch_dir, replica).await {
+                error!(
+                    "Failed to replicate state snapshot for epoch: {epoch} to {} with err: {:?}",
+                    replica.name, err
+                );
+                failures += 1;
+                continue;
+            }
+            info!(
+                "Replicated state snapshot for epoch: {epoch} to {}",
+                replica.name
+            );
+        }
+        if failures > 0 {
+            return Err(anyhow!("Failed to replicate {failures} state snapshot(s)"));
+        }
+        Ok(())
+    }
+
+    async fn replicate_epoch(&self, epoch_dir: &Path, replica: &Replica) -> Result<()> {
+        let manifest_path = epoch_dir.child("MANIFEST");
+        let manifest_bytes = get(&manifest_path, self.snapshot_store.clone()).await?;
+        let manifest = StateSnapshotReaderV1::parse_manifest(&manifest_bytes)?;
+        let mut files: Vec<_> = manifest
+            .file_metadata()
+            .iter()
+            .map(|file_metadata| {
+                (
+                    file_metadata.file_path(epoch_dir),
+                    file_metadata.sha3_digest,
+                )
+            })
+            .collect();
+
+        let type_manifest_path = epoch_dir.child("TYPE_MANIFEST");
+        let type_manifest_bytes = match self.snapshot_store.get(&type_manifest_path).await {
+            Ok(result) => Some(result.bytes().await?),
+            Err(object_store::Error::NotFound { .. }) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(bytes) = &type_manifest_bytes {
+            let type_manifest = StateSnapshotReaderV1::parse_type_manifest(bytes)?;
+            files.extend(type_manifest.file_metadata().iter().map(|file_metadata| {
+                (
+                    file_metadata.file_path(epoch_dir),
+                    file_metadata.sha3_digest,
+                )
+            }));
+        }
+
+        let epoch_metadata_path = epoch_dir.child("EPOCH_METADATA");
+        let epoch_metadata_bytes = match self.snapshot_store.get(&epoch_metadata_path).await {
+            Ok(result) => Some(result.bytes().await?),
+            Err(object_store::Error::NotFound { .. }) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(bytes) = &epoch_metadata_bytes {
+            StateSnapshotReaderV1::parse_epoch_metadata(bytes)?;
+        }
+
+        futures::stream::iter(files)
+            .map(|(path, sha3_digest)| self.copy_file_verified(path, sha3_digest, replica))
+            .buffer_unordered(self.concurrency.get())
+            .try_collect::<Vec<()>>()
+            .await?;
+
+        // Same order as the writer, so that a replica with a MANIFEST is always complete
+        if let Some(bytes) = epoch_metadata_bytes {
+            self.copy_manifest(epoch_metadata_path, bytes, replica)
+                .await?;
+        }
+        if let Some(bytes) = type_manifest_bytes {
+            self.copy_manifest(type_manifest_path, bytes, replica)
+                .await?;
+        }
+        self.copy_manifest(manifest_path, manifest_bytes, replica)
+            .await?;
+        put(
+            &epoch_dir.child(SUCCESS_MARKER),
+            Bytes::from_static(b"success"),
+            replica.store.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Streams the file at `path` from the primary store to the replica, checksumming it on the
+    /// way, so that snapshot files, which can be large, are never held in memory whole. The upload
+    /// is only completed if the file read from the primary store is intact.
+    async fn copy_file_verified(
+        &self,
+        path: Path,
+        sha3_digest: [u8; 32],
+        replica: &Replica,
+    ) -> Result<()> {
+        let mut source = self.snapshot_store.get(&path).await?.into_stream();
+        let (multipart_id, mut writer) = replica.store.put_multipart(&path).await?;
+        let copied = async {
+            let mut hasher = Sha3_256::default();
+            while let Some(chunk) = source.try_next().await? {
+                hasher.update(&chunk);
+                writer.write_all(&chunk).await?;
+            }
+            verify_checksum(&path, hasher.finalize().digest, sha3_digest)
+                .context("Snapshot file in the primary store is corrupt")?;
+            writer.shutdown().await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        if let Err(err) = copied {
+            if let Err(abort_err) = replica.store.abort_multipart(&path, &multipart_id).await {
+                error!(
+                    "Failed to abort upload of {path} to {}: {abort_err}",
+                    replica.name
+                );
+            }
+            return Err(err);
+        }
+
+        let mut replicated = replica.store.get(&path).await?.into_stream();
+        let mut hasher = Sha3_256::default();
+        while let Some(chunk) = replicated.try_next().await? {
+            hasher.update(&chunk);
+        }
+        verify_checksum(&path, hasher.finalize().digest, sha3_digest)
+            .with_context(|| format!("Snapshot file in {} is corrupt", replica.name))?;
+        Ok(())
+    }
+
+    /// Manifests carry their own checksum, which was verified when they were parsed, so reading
+    /// them back only needs to return the same bytes.
+    async fn copy_manifest(&self, path: Path, bytes: Bytes, replica: &Replica) -> Result<()> {
+        put(&path, bytes.clone(), replica.store.clone()).await?;
+        let replicated = get(&path, replica.store.clone()).await?;
+        if replicated != bytes {
+            return Err(anyhow!("{path} in {} is corrupt", replica.name));
+        }
+        Ok(())
+    }
+}
+
+fn verify_checksum(path: &Path, computed_digest: [u8; 32], sha3_digest: [u8; 32]) -> Result<()> {
+    if computed_digest != sha3_digest {
+        return Err(anyhow!(
+            "Checksum of {path}: {:?} doesn't match: {:?}",
+            computed_digest,
+            sha3_digest
+        ));
+    }
+    Ok(())
+}
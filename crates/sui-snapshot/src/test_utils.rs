@@ -0,0 +1,196 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic contents for the perpetual tables snapshots are taken from, and a summary of the
+//! snapshots written from them, so that features built on snapshots can be tested in other crates
+//! against golden values.
+
+use crate::reader::{LiveObjectIter, StateSnapshotReaderV1};
+use crate::{FileType, PACKAGE_TYPE_PARTITION};
+use anyhow::Result;
+use bytes::Bytes;
+use object_store::path::Path;
+use std::collections::BTreeMap;
+use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use sui_storage::object_store::util::path_to_filesystem;
+use sui_types::balance::Supply;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::coin::TreasuryCap;
+use sui_types::gas_coin::GAS;
+use sui_types::id::UID;
+use sui_types::object::{Object, Owner, OBJECT_START_VERSION};
+
+/// Owners are drawn from this range of addresses, away from the object IDs.
+const FIRST_OWNER: ObjectID = ObjectID::new([0xa; ObjectID::LENGTH]);
+
+/// Type of the generated objects.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TestObjectType {
+    /// `0x2::coin::Coin<0x2::sui::SUI>`
+    GasCoin,
+    /// `0x2::coin::TreasuryCap<0x2::sui::SUI>`
+    TreasuryCap,
+}
+
+/// Kind of owner of the generated objects.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TestOwner {
+    Immutable,
+    Address,
+    Object,
+    Shared,
+}
+
+/// Describes the objects `insert_objects` generates. The same description always generates the
+/// same objects: object `i` has ID `first_id + i`, its type is picked round robin from
+/// `object_types`, its kind of owner according to the weights in `owners`, and address and object
+/// owners are picked round robin from `num_owners` fixed IDs.
+#[derive(Clone, Debug)]
+pub struct TestObjects {
+    pub num_objects: u64,
+    pub first_id: ObjectID,
+    pub object_types: Vec<TestObjectType>,
+    pub owners: Vec<(TestOwner, u64)>,
+    pub num_owners: u64,
+}
+
+impl TestObjects {
+    /// `num_objects` immutable gas coins.
+    pub fn new(num_objects: u64) -> Self {
+        Self {
+            num_objects,
+            first_id: ObjectID::ZERO,
+            object_types: vec![TestObjectType::GasCoin],
+            owners: vec![(TestOwner::Immutable, 1)],
+            num_owners: 1,
+        }
+    }
+
+    pub fn with_object_types(mut self, object_types: Vec<TestObjectType>) -> Self {
+        self.object_types = object_types;
+        self
+    }
+
+    /// Each kind of owner is used for `weight` out of every `sum of weights` objects.
+    pub fn with_owners(mut self, owners: Vec<(TestOwner, u64)>) -> Self {
+        self.owners = owners;
+        self
+    }
+
+    pub fn with_num_owners(mut self, num_owners: u64) -> Self {
+        self.num_owners = num_owners;
+        self
+    }
+
+    pub fn generate(&self) -> Result<Vec<Object>> {
+        anyhow::ensure!(!self.object_types.is_empty(), "No object types to generate");
+        let total_weight: u64 = self.owners.iter().map(|(_, weight)| weight).sum();
+        anyhow::ensure!(total_weight > 0, "No owners to generate objects for");
+        anyhow::ensure!(self.num_owners > 0, "Need at least one owner");
+
+        let owner_ids = ObjectID::in_range(FIRST_OWNER, self.num_owners)?;
+        let ids = ObjectID::in_range(self.first_id, self.num_objects)?;
+        Ok(ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let i = i as u64;
+                let object_type = self.object_types[(i % self.object_types.len() as u64) as usize];
+                let owner_id = owner_ids[(i % self.num_owners) as usize];
+                let mut object = match object_type {
+                    TestObjectType::GasCoin => Object::immutable_with_id_for_testing(id),
+                    TestObjectType::TreasuryCap => Object::treasury_cap_for_testing(
+                        GAS::type_(),
+                        TreasuryCap {
+                            id: UID::new(id),
+                            total_supply: Supply { value: i },
+                        },
+                    ),
+                };
+                object.owner = match self.owner_kind(i % total_weight) {
+                    TestOwner::Immutable => Owner::Immutable,
+                    TestOwner::Address => Owner::AddressOwner(SuiAddress::from(owner_id)),
+                    TestOwner::Object => Owner::ObjectOwner(SuiAddress::from(owner_id)),
+                    TestOwner::Shared => Owner::Shared {
+                        initial_shared_version: OBJECT_START_VERSION,
+                    },
+                };
+                object
+            })
+            .collect())
+    }
+
+    fn owner_kind(&self, mut slot: u64) -> TestOwner {
+        for (owner, weight) in &self.owners {
+            if slot < *weight {
+                return *owner;
+            }
+            slot -= weight;
+        }
+        unreachable!("slot is less than the sum of weights")
+    }
+}
+
+/// Inserts the objects described by `objects` into `db`.
+pub fn insert_objects(db: &AuthorityPerpetualTables, objects: &TestObjects) -> Result<()> {
+    for object in objects.generate()? {
+        db.insert_object_test_only(object)?;
+    }
+    Ok(())
+}
+
+/// What a snapshot written to a local directory contains, independent of how its files are
+/// compressed, to compare against golden values.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ManifestSummary {
+    pub epoch: u64,
+    pub address_length: u64,
+    /// Paths of the files in the snapshot, relative to the epoch directory.
+    pub files: Vec<String>,
+    /// Number of live objects in the snapshot by type, with wrapped tombstones under `"wrapped"`
+    /// and packages under `PACKAGE_TYPE_PARTITION`.
+    pub objects_by_type: BTreeMap<String, u64>,
+}
+
+impl ManifestSummary {
+    pub fn read(root: &std::path::Path, epoch: u64) -> Result<Self> {
+        let epoch_dir = Path::from(format!("epoch_{epoch}"));
+        let manifest = StateSnapshotReaderV1::read_manifest(path_to_filesystem(
+            root.to_path_buf(),
+            &epoch_dir.child("MANIFEST"),
+        )?)?;
+
+        let mut files = vec![];
+        let mut objects_by_type = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            let path = file_metadata.file_path(&Path::default());
+            files.push(path.to_string());
+            if file_metadata.file_type != FileType::Object {
+                continue;
+            }
+            let bytes = std::fs::read(file_metadata.local_file_path(root, &epoch_dir)?)?;
+            for object in LiveObjectIter::new(file_metadata, Bytes::from(bytes))? {
+                let object_type = match &object {
+                    LiveObject::Normal(object) => match object.type_() {
+                        Some(type_) => type_.to_string(),
+                        None => PACKAGE_TYPE_PARTITION.to_string(),
+                    },
+                    LiveObject::Wrapped(_) => "wrapped".to_string(),
+                };
+                *objects_by_type.entry(object_type).or_default() += 1;
+            }
+        }
+        files.sort();
+
+        Ok(Self {
+            epoch: manifest.epoch(),
+            address_length: manifest.address_length(),
+            files,
+            objects_by_type,
+        })
+    }
+
+    pub fn num_objects(&self) -> u64 {
+        self.objects_by_type.values().sum()
+    }
+}
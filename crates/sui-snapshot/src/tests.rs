@@ -1,18 +1,37 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::reader::StateSnapshotReaderV1;
+use crate::cold_tier::{find_ref_file, SnapshotColdObjectStore};
+use crate::mount::MountedSnapshot;
+use crate::reader::{LiveObjectIter, StateSnapshotReaderV1};
+use crate::replicator::StateSnapshotReplicator;
+use crate::test_utils::{insert_objects, ManifestSummary, TestObjectType, TestObjects, TestOwner};
 use crate::writer::StateSnapshotWriterV1;
-use crate::FileCompression;
+use crate::{
+    protocol_config_digest, EpochMetadata, EpochMetadataV1, FileCompression, FileMetadata,
+    FileType, PACKAGE_TYPE_PARTITION,
+};
+use bytes::Bytes;
 use futures::future::AbortHandle;
+use object_store::path::Path;
+use prometheus::Registry;
 use std::collections::HashSet;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
-use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
-use sui_protocol_config::ProtocolConfig;
+use sui_core::authority::authority_store_cold_tier::ColdObjectStore;
+use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use sui_core::db_checkpoint_handler::SUCCESS_MARKER;
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
+use sui_storage::object_store::util::{get, path_to_filesystem, put};
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_types::base_types::ObjectID;
-use sui_types::object::Object;
+use sui_types::committee::Committee;
+use sui_types::gas::GasCostSummary;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, EndOfEpochData,
+};
+use sui_types::storage::ObjectStore;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
 use tempfile::tempdir;
 
 fn temp_dir() -> std::path::PathBuf {
@@ -21,18 +40,6 @@ fn temp_dir() -> std::path::PathBuf {
         .into_path()
 }
 
-pub fn insert_keys(
-    db: &AuthorityPerpetualTables,
-    total_unique_object_ids: u64,
-) -> Result<(), anyhow::Error> {
-    let ids = ObjectID::in_range(ObjectID::ZERO, total_unique_object_ids)?;
-    for id in ids {
-        let object = Object::immutable_with_id_for_testing(id);
-        db.insert_object_test_only(object)?;
-    }
-    Ok(())
-}
-
 fn compare_live_objects(
     db1: &AuthorityPerpetualTables,
     db2: &AuthorityPerpetualTables,
@@ -76,9 +83,15 @@ async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     )
     .await?;
     let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
-    insert_keys(&perpetual_db, 1000)?;
+    insert_objects(&perpetual_db, &TestObjects::new(1000))?;
     snapshot_writer
-        .write_internal(0, true, perpetual_db.clone())
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
         .await?;
     let local_store_restore_config = ObjectStoreConfig {
         object_store: Some(ObjectStoreType::File),
@@ -91,6 +104,7 @@ async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
         &local_store_restore_config,
         usize::MAX,
         NonZeroUsize::new(1).unwrap(),
+        SupportedProtocolVersions::SYSTEM_DEFAULT,
     )
     .await?;
     let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
@@ -99,6 +113,75 @@ async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
         .read(&restored_perpetual_db, abort_registration)
         .await?;
     compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+
+    let stats = snapshot_reader.finalize(&restored_perpetual_db, true)?;
+    let objects = stats.iter().find(|table| table.name == "objects").unwrap();
+    assert_eq!(objects.files_per_level[0], 0);
+    assert_eq!(objects.estimated_read_amplification(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_golden_manifest() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    let objects = TestObjects::new(12)
+        .with_object_types(vec![TestObjectType::GasCoin, TestObjectType::TreasuryCap])
+        .with_owners(vec![
+            (TestOwner::Address, 2),
+            (TestOwner::Object, 1),
+            (TestOwner::Shared, 1),
+        ])
+        .with_num_owners(3);
+    // Generating the same objects twice gives the same objects
+    assert_eq!(objects.generate()?, objects.generate()?);
+    insert_objects(&perpetual_db, &objects)?;
+    snapshot_writer
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
+        .await?;
+
+    let summary = ManifestSummary::read(&remote, 0)?;
+    assert_eq!(
+        summary,
+        ManifestSummary {
+            epoch: 0,
+            address_length: ObjectID::LENGTH as u64,
+            files: vec!["1_1.obj".to_string(), "1_1.ref".to_string()],
+            objects_by_type: [
+                ("0x2::coin::Coin<0x2::sui::SUI>".to_string(), 6),
+                ("0x2::coin::TreasuryCap<0x2::sui::SUI>".to_string(), 6),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    );
+    assert_eq!(summary.num_objects(), 12);
     Ok(())
 }
 
@@ -130,7 +213,13 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     .await?;
     let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
     snapshot_writer
-        .write_internal(0, true, perpetual_db.clone())
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
         .await?;
     let local_store_restore_config = ObjectStoreConfig {
         object_store: Some(ObjectStoreType::File),
@@ -143,6 +232,7 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
         &local_store_restore_config,
         usize::MAX,
         NonZeroUsize::new(1).unwrap(),
+        SupportedProtocolVersions::SYSTEM_DEFAULT,
     )
     .await?;
     let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
@@ -157,3 +247,609 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_snapshot_unsupported_protocol_version() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_objects(&perpetual_db, &TestObjects::new(10))?;
+    snapshot_writer
+        .write_internal(0, 10, true, None, perpetual_db.clone())
+        .await?;
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+
+    // The protocol version the snapshot was written at is newer than the reader supports.
+    let err = StateSnapshotReaderV1::new(
+        0,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        SupportedProtocolVersions::new_for_testing(1, 9),
+    )
+    .await
+    .err()
+    .expect("Restoring into an incompatible binary should fail");
+    assert!(err.to_string().contains("protocol version 10"));
+
+    // And it can be restored once it is in range.
+    StateSnapshotReaderV1::new(
+        0,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        SupportedProtocolVersions::new_for_testing(1, 10),
+    )
+    .await?;
+    Ok(())
+}
+
+fn epoch_metadata_for_testing(epoch: u64, end_of_epoch: bool) -> EpochMetadata {
+    let (committee, keypairs) = Committee::new_simple_test_committee();
+    let committee = Committee::new(epoch, committee.voting_rights.into_iter().collect());
+    let end_of_epoch_data = end_of_epoch.then(|| EndOfEpochData {
+        next_epoch_committee: committee.voting_rights.clone(),
+        next_epoch_protocol_version: ProtocolVersion::MAX,
+        epoch_commitments: vec![],
+    });
+    let summary = CheckpointSummary::new(
+        epoch,
+        100,
+        1000,
+        &CheckpointContents::new_with_causally_ordered_transactions(vec![]),
+        None,
+        GasCostSummary::default(),
+        end_of_epoch_data,
+        0,
+    );
+    let protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+    EpochMetadata::V1(EpochMetadataV1 {
+        epoch,
+        last_checkpoint: CertifiedCheckpointSummary::new_from_keypairs_for_testing(
+            summary, &keypairs, &committee,
+        ),
+        committee,
+        protocol_version: protocol_config.version.as_u64(),
+        protocol_config_digest: protocol_config_digest(&protocol_config).unwrap(),
+    })
+}
+
+#[test]
+fn test_check_protocol_config() {
+    let metadata = epoch_metadata_for_testing(5, true);
+    metadata.check_protocol_config(Chain::Unknown).unwrap();
+
+    // Changing the value of any attribute or feature flag changes the digest, and the binary's
+    // config no longer matches the epoch's.
+    let protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+    let mut changed_attr = protocol_config.clone();
+    changed_attr.set_attr_for_testing("max_tx_gas".to_string(), "1".to_string());
+    let mut changed_feature = protocol_config.clone();
+    changed_feature.set_zklogin_auth_for_testing(!protocol_config.feature_map()["zklogin_auth"]);
+
+    for changed in [changed_attr, changed_feature] {
+        let digest = protocol_config_digest(&changed).unwrap();
+        assert_ne!(&digest, metadata.protocol_config_digest());
+
+        let EpochMetadata::V1(mut changed_metadata) = metadata.clone();
+        changed_metadata.protocol_config_digest = digest;
+        let err = EpochMetadata::V1(changed_metadata)
+            .check_protocol_config(Chain::Unknown)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("differs from the one epoch 5 ran with"));
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_epoch_metadata() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_objects(&perpetual_db, &TestObjects::new(10))?;
+
+    for (epoch_metadata, valid) in [
+        (epoch_metadata_for_testing(0, true), true),
+        // The last checkpoint of an epoch carries the committee of the next one.
+        (epoch_metadata_for_testing(0, false), false),
+        // Metadata of another epoch.
+        (epoch_metadata_for_testing(1, true), false),
+    ] {
+        StateSnapshotWriterV1::new(
+            &local_store_config,
+            &remote_store_config,
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+        .await?
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            Some(epoch_metadata.clone()),
+            perpetual_db.clone(),
+        )
+        .await?;
+        let snapshot_reader = StateSnapshotReaderV1::new(
+            0,
+            &remote_store_config,
+            &local_store_restore_config,
+            usize::MAX,
+            NonZeroUsize::new(1).unwrap(),
+            SupportedProtocolVersions::SYSTEM_DEFAULT,
+        )
+        .await;
+        if !valid {
+            assert!(snapshot_reader.is_err());
+            continue;
+        }
+
+        let snapshot_reader = snapshot_reader?;
+        let restored = snapshot_reader.epoch_metadata().unwrap();
+        assert_eq!(restored, &epoch_metadata);
+        assert_eq!(
+            restored.next_epoch_committee()?.voting_rights,
+            epoch_metadata.committee().voting_rights
+        );
+        restored.verify(0, Some(epoch_metadata.committee()))?;
+        let (other_committee, _) = Committee::new_simple_test_committee_of_size(7);
+        assert!(restored.verify(0, Some(&other_committee)).is_err());
+    }
+    Ok(())
+}
+
+// Runs on a current_thread runtime: reads must not depend on being able to block a worker.
+#[tokio::test]
+async fn test_cold_object_store() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let cold_local = temp_dir().join("cold_local_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_objects(&perpetual_db, &TestObjects::new(100))?;
+    snapshot_writer
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
+        .await?;
+
+    let cold_store = SnapshotColdObjectStore::new(
+        &remote_store_config,
+        cold_local.clone(),
+        NonZeroUsize::new(10).unwrap(),
+        NonZeroUsize::new(1).unwrap(),
+        &Registry::new(),
+    )?;
+    let object_id = ObjectID::in_range(ObjectID::ZERO, 100)?[42];
+    let expected = perpetual_db.get_object(&object_id)?.unwrap();
+    let version = expected.version();
+
+    // Served from the snapshot files first, then from the cache
+    for _ in 0..2 {
+        let object = cold_store.get_object_by_key(&object_id, version)?;
+        assert_eq!(object, Some(expected.clone()));
+    }
+    // Versions that were never live at the end of an epoch cannot be served
+    assert!(cold_store
+        .get_object_by_key(&object_id, version.next())?
+        .is_none());
+    assert!(cold_store
+        .get_object_by_key(&ObjectID::random(), version)?
+        .is_none());
+    // Staged files are deleted once the manifest is read
+    assert_eq!(std::fs::read_dir(&cold_local)?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_find_ref_file() -> Result<(), anyhow::Error> {
+    let ids = ObjectID::in_range(ObjectID::ZERO, 10)?;
+    let ref_file = |part_num| FileMetadata {
+        file_type: FileType::Reference,
+        bucket_num: 1,
+        part_num,
+        file_compression: FileCompression::Zstd,
+        sha3_digest: [0; 32],
+    };
+    // The files list ids[1..4], ids[4..7] and ids[7..10]
+    let files: Vec<_> = (0..3).map(ref_file).collect();
+    let reads = std::cell::Cell::new(0);
+    let part_of = |id: &ObjectID| {
+        find_ref_file(&files, id, |f| {
+            reads.set(reads.get() + 1);
+            Ok(Some(ids[1 + 3 * f.part_num as usize]))
+        })
+        .map(|f| f.map(|f| f.part_num))
+    };
+
+    assert_eq!(part_of(&ids[0])?, None);
+    assert_eq!(part_of(&ids[1])?, Some(0));
+    assert_eq!(part_of(&ids[6])?, Some(1));
+    assert_eq!(part_of(&ids[7])?, Some(2));
+    assert_eq!(part_of(&ids[9])?, Some(2));
+    // Each search reads the first IDs of at most 2 of the 3 files
+    assert!(reads.get() <= 5 * 2);
+
+    // Empty files are never picked
+    let empty_files: Vec<_> = (0..2).map(ref_file).collect();
+    assert!(find_ref_file(&empty_files, &ids[5], |_| Ok(None))?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mounted_snapshot() -> Result<(), anyhow::Error> {
+    // Objects are read by seeking into uncompressed files, and by decompressing compressed ones
+    check_mounted_snapshot(FileCompression::Zstd).await?;
+    check_mounted_snapshot(FileCompression::None).await
+}
+
+async fn check_mounted_snapshot(file_compression: FileCompression) -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        file_compression,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_objects(&perpetual_db, &TestObjects::new(100))?;
+    snapshot_writer
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
+        .await?;
+
+    assert!(MountedSnapshot::open(remote.clone(), 1).is_err());
+    let snapshot = MountedSnapshot::open(remote, 0)?;
+    for object_id in ObjectID::in_range(ObjectID::ZERO, 100)? {
+        let expected = perpetual_db.get_object(&object_id)?.unwrap();
+        assert_eq!(
+            snapshot.get_object_ref(&object_id)?,
+            Some(expected.compute_object_reference())
+        );
+        assert_eq!(
+            snapshot.get_object_by_key(&object_id, expected.version())?,
+            Some(expected.clone())
+        );
+        assert!(snapshot
+            .get_object_by_key(&object_id, expected.version().next())?
+            .is_none());
+        assert_eq!(snapshot.get_object(&object_id)?, Some(expected));
+    }
+    assert!(snapshot.get_object(&ObjectID::random())?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_type_partitions() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .with_type_partitions(true);
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_objects(&perpetual_db, &TestObjects::new(100))?;
+    snapshot_writer
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
+        .await?;
+
+    let epoch_dir = Path::from("epoch_0");
+    let type_manifest = StateSnapshotReaderV1::read_type_manifest(path_to_filesystem(
+        remote,
+        &epoch_dir.child("TYPE_MANIFEST"),
+    )?)?;
+    assert_eq!(type_manifest.epoch(), 0);
+    assert_eq!(
+        type_manifest.files_for_type(PACKAGE_TYPE_PARTITION).count(),
+        0
+    );
+
+    // All the test objects are gas coins, so they end up in a single partition
+    let framework = SUI_FRAMEWORK_ADDRESS.to_canonical_string();
+    let files: Vec<_> = type_manifest
+        .files_for_type(&format!("0x{framework}::coin::Coin"))
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].num_objects, 100);
+    assert_eq!(
+        files[0].file_path(&epoch_dir).to_string(),
+        format!("epoch_0/types/0x{framework}/coin/Coin/1.obj")
+    );
+
+    let remote_store = remote_store_config.make()?;
+    let bytes = get(&files[0].file_path(&epoch_dir), remote_store).await?;
+    let mut object_ids: Vec<_> = LiveObjectIter::from_bytes(files[0].file_compression, bytes)?
+        .map(|object| match object {
+            LiveObject::Normal(object) => object.id(),
+            LiveObject::Wrapped(_) => panic!("Wrapped tombstones are not partitioned by type"),
+        })
+        .collect();
+    object_ids.sort();
+    assert_eq!(object_ids, ObjectID::in_range(ObjectID::ZERO, 100)?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_type_partitions_with_bounded_open_files() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+
+    // Objects alternate between two types, so with only one partition's file open at a time,
+    // every write closes the other partition's file, and reopens its own
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .with_type_partitions(true)
+    .with_max_open_type_partitions(NonZeroUsize::new(1).unwrap());
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    let objects = TestObjects::new(100)
+        .with_object_types(vec![TestObjectType::GasCoin, TestObjectType::TreasuryCap]);
+    insert_objects(&perpetual_db, &objects)?;
+    snapshot_writer
+        .write_internal(
+            0,
+            ProtocolVersion::MAX.as_u64(),
+            true,
+            None,
+            perpetual_db.clone(),
+        )
+        .await?;
+
+    let epoch_dir = Path::from("epoch_0");
+    let type_manifest = StateSnapshotReaderV1::read_type_manifest(path_to_filesystem(
+        remote,
+        &epoch_dir.child("TYPE_MANIFEST"),
+    )?)?;
+    let remote_store = remote_store_config.make()?;
+    let framework = SUI_FRAMEWORK_ADDRESS.to_canonical_string();
+    for (name, first) in [("Coin", 0), ("TreasuryCap", 1)] {
+        let files: Vec<_> = type_manifest
+            .files_for_type(&format!("0x{framework}::coin::{name}"))
+            .collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].num_objects, 50);
+
+        let bytes = get(&files[0].file_path(&epoch_dir), remote_store.clone()).await?;
+        let mut object_ids: Vec<_> = LiveObjectIter::from_bytes(files[0].file_compression, bytes)?
+            .map(|object| object.object_id())
+            .collect();
+        object_ids.sort();
+        let expected: Vec<_> = ObjectID::in_range(ObjectID::ZERO, 100)?
+            .into_iter()
+            .skip(first)
+            .step_by(2)
+            .collect();
+        assert_eq!(object_ids, expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_replication() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+    let replica_store_configs: Vec<_> = ["replica_1", "replica_2"]
+        .into_iter()
+        .map(|name| ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(temp_dir().join(name)),
+            ..Default::default()
+        })
+        .collect();
+
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_objects(&perpetual_db, &TestObjects::new(1000))?;
+    let remote_store = remote_store_config.make()?;
+    for epoch in 0..2 {
+        let snapshot_writer = StateSnapshotWriterV1::new(
+            &local_store_config,
+            &remote_store_config,
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+        .await?
+        .with_type_partitions(true);
+        snapshot_writer
+            .write_internal(
+                epoch,
+                ProtocolVersion::MAX.as_u64(),
+                true,
+                None,
+                perpetual_db.clone(),
+            )
+            .await?;
+        let success_marker = Path::from(format!("epoch_{epoch}")).child(SUCCESS_MARKER);
+        put(
+            &success_marker,
+            Bytes::from_static(b"success"),
+            remote_store.clone(),
+        )
+        .await?;
+    }
+
+    // Corrupt a file of the first snapshot, which must then not be replicated, without holding
+    // back the second
+    let manifest = StateSnapshotReaderV1::read_manifest(path_to_filesystem(
+        remote.clone(),
+        &Path::from("epoch_0/MANIFEST"),
+    )?)?;
+    let corrupt_file = manifest.file_metadata()[0].file_path(&Path::from("epoch_0"));
+    let mut bytes = get(&corrupt_file, remote_store.clone()).await?.to_vec();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    put(&corrupt_file, Bytes::from(bytes), remote_store.clone()).await?;
+
+    let replicator = StateSnapshotReplicator::new(
+        remote_store.clone(),
+        &replica_store_configs,
+        NonZeroUsize::new(1).unwrap(),
+        &Registry::new(),
+    )?;
+    assert!(replicator.replicate().await.is_err());
+
+    for replica_store_config in &replica_store_configs {
+        let replica_store = replica_store_config.make()?;
+        let epoch_0_marker = Path::from("epoch_0").child(SUCCESS_MARKER);
+        let epoch_1_marker = Path::from("epoch_1").child(SUCCESS_MARKER);
+        assert!(replica_store.head(&epoch_0_marker).await.is_err());
+        assert!(replica_store.head(&epoch_1_marker).await.is_ok());
+    }
+
+    // The second snapshot can be restored from a replica alone
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new(
+        1,
+        &replica_store_configs[1],
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        SupportedProtocolVersions::SYSTEM_DEFAULT,
+    )
+    .await?;
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    snapshot_reader
+        .read(&restored_perpetual_db, abort_registration)
+        .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::replicator::StateSnapshotReplicator;
 use crate::writer::StateSnapshotWriterV1;
 use anyhow::Result;
 use bytes::Bytes;
@@ -12,7 +13,9 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
 use sui_core::db_checkpoint_handler::{STATE_SNAPSHOT_COMPLETED_MARKER, SUCCESS_MARKER};
+use sui_core::epoch::committee_store::CommitteeStore;
 use sui_storage::object_store::util::{
     find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
 };
@@ -21,6 +24,7 @@ use sui_storage::FileCompression;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
 use tracing::{debug, error, info};
+use typed_store::rocks::MetricConf;
 
 pub struct StateSnapshotUploaderMetrics {
     pub first_missing_state_snapshot_epoch: IntGauge,
@@ -53,6 +57,11 @@ pub struct StateSnapshotUploader {
     snapshot_store: Arc<DynObjectStore>,
     /// Time interval to check for presence of new db checkpoint
     interval: Duration,
+    /// Whether to also write snapshots partitioned by object type
+    partition_by_type: bool,
+    /// Mirrors uploaded snapshots to secondary stores, see `with_replicas`. Runs in its own task,
+    /// started along with the uploader's
+    replicator: Option<StateSnapshotReplicator>,
     metrics: Arc<StateSnapshotUploaderMetrics>,
 }
 
@@ -62,6 +71,7 @@ impl StateSnapshotUploader {
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
         interval_s: u64,
+        partition_by_type: bool,
         registry: &Registry,
     ) -> Result<Self> {
         let db_checkpoint_store_config = ObjectStoreConfig {
@@ -81,14 +91,40 @@ impl StateSnapshotUploader {
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
             interval: Duration::from_secs(interval_s),
+            partition_by_type,
+            replicator: None,
             metrics: StateSnapshotUploaderMetrics::new(registry),
         })
     }
 
-    pub fn start(self) -> Sender<()> {
+    /// Also copy every completed snapshot to each of `replica_store_configs`, so that restores can
+    /// fall back to another bucket or region.
+    pub fn with_replicas(
+        mut self,
+        replica_store_configs: &[ObjectStoreConfig],
+        registry: &Registry,
+    ) -> Result<Self> {
+        if !replica_store_configs.is_empty() {
+            self.replicator = Some(StateSnapshotReplicator::new(
+                self.snapshot_store.clone(),
+                replica_store_configs,
+                NonZeroUsize::new(20).unwrap(),
+                registry,
+            )?);
+        }
+        Ok(self)
+    }
+
+    pub fn start(mut self) -> Sender<()> {
         let (sender, mut recv) = channel::<()>();
         let mut interval = tokio::time::interval(self.interval);
+        // Stops along with the uploader, when its sender is dropped
+        let replicator_sender = self
+            .replicator
+            .take()
+            .map(|replicator| replicator.start(self.interval));
         tokio::task::spawn(async move {
+            let _replicator_sender = replicator_sender;
             info!("State snapshot uploader loop started");
             loop {
                 tokio::select! {
@@ -122,6 +158,19 @@ impl StateSnapshotUploader {
         for (epoch, db_path) in dirs {
             if missing_epochs.contains(epoch) || *epoch >= last_missing_epoch {
                 info!("Starting state snapshot creation for epoch: {}", *epoch);
+                let checkpoint_store = Arc::new(CheckpointStore::open_tables_read_write(
+                    path_to_filesystem(
+                        self.db_checkpoint_path.clone(),
+                        &db_path.child("checkpoints"),
+                    )?,
+                    MetricConf::default(),
+                    None,
+                    None,
+                ));
+                let committee_store = Arc::new(CommitteeStore::open_existing(
+                    path_to_filesystem(self.db_checkpoint_path.clone(), &db_path.child("epochs"))?,
+                    None,
+                ));
                 let state_snapshot_writer = StateSnapshotWriterV1::new_from_store(
                     &self.staging_path,
                     &self.staging_store,
@@ -129,7 +178,9 @@ impl StateSnapshotUploader {
                     FileCompression::Zstd,
                     NonZeroUsize::new(20).unwrap(),
                 )
-                .await?;
+                .await?
+                .with_type_partitions(self.partition_by_type)
+                .with_epoch_stores(checkpoint_store, committee_store);
                 let db = Arc::new(AuthorityPerpetualTables::open(
                     &path_to_filesystem(self.db_checkpoint_path.clone(), &db_path.child("store"))?,
                     None,
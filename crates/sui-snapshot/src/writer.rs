@@ -3,14 +3,17 @@
 #![allow(dead_code)]
 
 use crate::{
-    compute_sha3_checksum, create_file_metadata, FileCompression, FileMetadata, FileType, Manifest,
-    ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    compute_sha3_checksum, create_file_metadata, EpochMetadata, FileCompression, FileMetadata,
+    FileType, Manifest, ManifestV2, TypeManifest, TypeManifestV1, TypedFileMetadata,
+    EPOCH_METADATA_FILE_MAGIC, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
+    OBJECT_REF_BYTES, PACKAGE_TYPE_PARTITION, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    TYPE_MANIFEST_FILE_MAGIC,
 };
 use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ByteOrder};
 use futures::StreamExt;
 use integer_encoding::VarInt;
+use lru::LruCache;
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use std::collections::hash_map::Entry::Vacant;
@@ -23,6 +26,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::authority::CHAIN_IDENTIFIER;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::epoch::committee_store::CommitteeStore;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_storage::blob::{Blob, BlobEncoding, BLOB_ENCODING_BYTES};
 use sui_storage::object_store::util::{copy_file, delete_recursively, path_to_filesystem};
@@ -36,31 +41,55 @@ use tokio::task::JoinHandle;
 use tokio_stream::wrappers::ReceiverStream;
 use tracing::debug;
 
+/// Default for the number of type partitions whose files are kept open at once while writing a
+/// snapshot, see `StateSnapshotWriterV1::with_max_open_type_partitions`.
+const MAX_OPEN_TYPE_PARTITIONS: usize = 256;
+
+/// Creates an object file at `file_path`, returning it along with the number of header bytes
+/// written to it.
+fn create_object_file(file_path: PathBuf) -> Result<(usize, File)> {
+    let mut tmp_file_path = file_path.clone().into_os_string();
+    tmp_file_path.push(".tmp");
+    let mut f = File::create(&tmp_file_path)?;
+    let mut metab = [0u8; MAGIC_BYTES];
+    BigEndian::write_u32(&mut metab, OBJECT_FILE_MAGIC);
+    f.rewind()?;
+    let n = f.write(&metab)?;
+    drop(f);
+    fs::rename(tmp_file_path, file_path.clone())?;
+    let mut f = OpenOptions::new().append(true).open(file_path)?;
+    f.seek(SeekFrom::Start(n as u64))?;
+    Ok((n, f))
+}
+
 /// LiveObjectSetWriterV1 writes live object set. It creates multiple *.obj files and *.ref file
 struct LiveObjectSetWriterV1 {
     dir_path: PathBuf,
+    epoch_dir: Path,
     bucket_num: u32,
     current_part_num: u32,
     wbuf: BufWriter<File>,
     ref_wbuf: BufWriter<File>,
     n: usize,
     files: Vec<FileMetadata>,
-    sender: Option<Sender<FileMetadata>>,
+    sender: Option<Sender<Path>>,
     file_compression: FileCompression,
 }
 
 impl LiveObjectSetWriterV1 {
     fn new(
         dir_path: PathBuf,
+        epoch_dir: Path,
         bucket_num: u32,
         file_compression: FileCompression,
-        sender: Sender<FileMetadata>,
+        sender: Sender<Path>,
     ) -> Result<Self> {
         let part_num = 1;
         let (n, obj_file) = Self::object_file(dir_path.clone(), bucket_num, part_num)?;
         let ref_file = Self::ref_file(dir_path.clone(), bucket_num, part_num)?;
         Ok(LiveObjectSetWriterV1 {
             dir_path,
+            epoch_dir,
             bucket_num,
             current_part_num: part_num,
             wbuf: BufWriter::new(obj_file),
@@ -84,18 +113,7 @@ impl LiveObjectSetWriterV1 {
         Ok(self.files.clone())
     }
     fn object_file(dir_path: PathBuf, bucket_num: u32, part_num: u32) -> Result<(usize, File)> {
-        let next_part_file_path = dir_path.join(format!("{bucket_num}_{part_num}.obj"));
-        let next_part_file_tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.obj.tmp"));
-        let mut f = File::create(next_part_file_tmp_path.clone())?;
-        let mut metab = [0u8; MAGIC_BYTES];
-        BigEndian::write_u32(&mut metab, OBJECT_FILE_MAGIC);
-        f.rewind()?;
-        let n = f.write(&metab)?;
-        drop(f);
-        fs::rename(next_part_file_tmp_path, next_part_file_path.clone())?;
-        let mut f = OpenOptions::new().append(true).open(next_part_file_path)?;
-        f.seek(SeekFrom::Start(n as u64))?;
-        Ok((n, f))
+        create_object_file(dir_path.join(format!("{bucket_num}_{part_num}.obj")))
     }
     fn ref_file(dir_path: PathBuf, bucket_num: u32, part_num: u32) -> Result<File> {
         let ref_path = dir_path.join(format!("{bucket_num}_{part_num}.ref"));
@@ -126,10 +144,10 @@ impl LiveObjectSetWriterV1 {
             self.bucket_num,
             self.current_part_num,
         )?;
-        self.files.push(file_metadata.clone());
         if let Some(sender) = &self.sender {
-            sender.blocking_send(file_metadata)?;
+            sender.blocking_send(file_metadata.file_path(&self.epoch_dir))?;
         }
+        self.files.push(file_metadata);
         Ok(())
     }
     fn finalize_ref(&mut self) -> Result<()> {
@@ -147,10 +165,10 @@ impl LiveObjectSetWriterV1 {
             self.bucket_num,
             self.current_part_num,
         )?;
-        self.files.push(file_metadata.clone());
         if let Some(sender) = &self.sender {
-            sender.blocking_send(file_metadata)?;
+            sender.blocking_send(file_metadata.file_path(&self.epoch_dir))?;
         }
+        self.files.push(file_metadata);
         Ok(())
     }
     fn cut(&mut self) -> Result<()> {
@@ -202,6 +220,153 @@ impl LiveObjectSetWriterV1 {
     }
 }
 
+/// TypedObjectWriterV1 writes the objects of a single type partition to *.obj files under
+/// types/<address>/<module>/<name>/ (or types/package/ for packages). A snapshot can have more
+/// partitions than files can be open at once, so the current file can be closed between writes,
+/// see `close`.
+struct TypedObjectWriterV1 {
+    local_staging_dir: PathBuf,
+    epoch_dir: Path,
+    object_type: String,
+    current_part_num: u32,
+    /// Writer for the current file, or `None` while it is closed.
+    wbuf: Option<BufWriter<File>>,
+    n: usize,
+    num_objects: u64,
+    files: Vec<TypedFileMetadata>,
+    sender: Sender<Path>,
+    file_compression: FileCompression,
+}
+
+impl TypedObjectWriterV1 {
+    fn new(
+        local_staging_dir: PathBuf,
+        epoch_dir: Path,
+        object_type: String,
+        file_compression: FileCompression,
+        sender: Sender<Path>,
+    ) -> Result<Self> {
+        let part_num = 1;
+        let file_path =
+            Self::local_file_path(&local_staging_dir, &epoch_dir, &object_type, part_num)?;
+        if let Some(type_dir) = file_path.parent() {
+            fs::create_dir_all(type_dir)?;
+        }
+        let (n, f) = create_object_file(file_path)?;
+        Ok(TypedObjectWriterV1 {
+            local_staging_dir,
+            epoch_dir,
+            object_type,
+            current_part_num: part_num,
+            wbuf: Some(BufWriter::new(f)),
+            n,
+            num_objects: 0,
+            files: vec![],
+            sender,
+            file_compression,
+        })
+    }
+    /// Type partition of an object: `<address>::<module>::<name>` of its struct with type
+    /// parameters erased, or `PACKAGE_TYPE_PARTITION` for packages. Wrapped object tombstones have
+    /// no type and are not written to any partition.
+    fn partition(object: &LiveObject) -> Option<String> {
+        let LiveObject::Normal(object) = object else {
+            return None;
+        };
+        Some(match object.type_() {
+            Some(object_type) => format!(
+                "0x{}::{}::{}",
+                object_type.address().to_canonical_string(),
+                object_type.module(),
+                object_type.name()
+            ),
+            None => PACKAGE_TYPE_PARTITION.to_string(),
+        })
+    }
+    fn local_file_path(
+        local_staging_dir: &std::path::Path,
+        epoch_dir: &Path,
+        object_type: &str,
+        part_num: u32,
+    ) -> Result<PathBuf> {
+        TypedFileMetadata {
+            object_type: object_type.to_string(),
+            part_num,
+            num_objects: 0,
+            file_compression: FileCompression::None,
+            sha3_digest: [0; 32],
+        }
+        .local_file_path(local_staging_dir, epoch_dir)
+    }
+    fn write(&mut self, object: &LiveObject) -> Result<()> {
+        let blob = Blob::encode(object, BlobEncoding::Bcs)?;
+        let mut blob_size = blob.data.len().required_space();
+        blob_size += BLOB_ENCODING_BYTES;
+        blob_size += blob.data.len();
+        if (self.n + blob_size) > FILE_MAX_BYTES {
+            self.finalize()?;
+            self.current_part_num += 1;
+            let (n, f) = create_object_file(self.current_file_path()?)?;
+            self.n = n;
+            self.num_objects = 0;
+            self.wbuf = Some(BufWriter::new(f));
+        }
+        self.n += blob.write(self.wbuf()?)?;
+        self.num_objects += 1;
+        Ok(())
+    }
+    /// Closes the current file, to be reopened to append to it by the next write.
+    fn close(&mut self) -> Result<()> {
+        if let Some(mut wbuf) = self.wbuf.take() {
+            wbuf.flush()?;
+        }
+        Ok(())
+    }
+    fn done(mut self) -> Result<Vec<TypedFileMetadata>> {
+        self.finalize()?;
+        Ok(self.files)
+    }
+    fn current_file_path(&self) -> Result<PathBuf> {
+        Self::local_file_path(
+            &self.local_staging_dir,
+            &self.epoch_dir,
+            &self.object_type,
+            self.current_part_num,
+        )
+    }
+    /// Writer for the current file, reopening it if it was closed.
+    fn wbuf(&mut self) -> Result<&mut BufWriter<File>> {
+        let wbuf = match self.wbuf.take() {
+            Some(wbuf) => wbuf,
+            None => BufWriter::new(
+                OpenOptions::new()
+                    .append(true)
+                    .open(self.current_file_path()?)?,
+            ),
+        };
+        Ok(self.wbuf.insert(wbuf))
+    }
+    fn finalize(&mut self) -> Result<()> {
+        let wbuf = self.wbuf()?;
+        wbuf.flush()?;
+        wbuf.get_ref().sync_data()?;
+        self.wbuf = None;
+        let file_path = self.current_file_path()?;
+        self.file_compression.compress(&file_path)?;
+        let file_metadata = TypedFileMetadata {
+            object_type: self.object_type.clone(),
+            part_num: self.current_part_num,
+            num_objects: self.num_objects,
+            file_compression: self.file_compression,
+            sha3_digest: compute_sha3_checksum(&file_path)?,
+        };
+        self.sender
+            .blocking_send(file_metadata.file_path(&self.epoch_dir))?;
+        self.files.push(file_metadata);
+        Ok(())
+    }
+}
+
 /// StateSnapshotWriterV1 writes snapshot files to a local staging dir and simultaneously uploads them
 /// to a remote object store
 pub struct StateSnapshotWriterV1 {
@@ -210,6 +375,13 @@ pub struct StateSnapshotWriterV1 {
     remote_object_store: Arc<DynObjectStore>,
     local_staging_store: Arc<DynObjectStore>,
     concurrency: usize,
+    /// Whether to also write objects partitioned by type, see `with_type_partitions`
+    partition_by_type: bool,
+    /// Number of type partitions whose files are kept open at once, see
+    /// `with_max_open_type_partitions`
+    max_open_type_partitions: NonZeroUsize,
+    /// Stores to read the EPOCH_METADATA from, see `with_epoch_stores`
+    epoch_stores: Option<(Arc<CheckpointStore>, Arc<CommitteeStore>)>,
 }
 
 impl StateSnapshotWriterV1 {
@@ -226,6 +398,9 @@ impl StateSnapshotWriterV1 {
             remote_object_store: remote_object_store.clone(),
             local_staging_store: local_staging_store.clone(),
             concurrency: concurrency.get(),
+            partition_by_type: false,
+            max_open_type_partitions: NonZeroUsize::new(MAX_OPEN_TYPE_PARTITIONS).unwrap(),
+            epoch_stores: None,
         })
     }
 
@@ -248,9 +423,38 @@ impl StateSnapshotWriterV1 {
             remote_object_store,
             local_staging_store,
             concurrency: concurrency.get(),
+            partition_by_type: false,
+            max_open_type_partitions: NonZeroUsize::new(MAX_OPEN_TYPE_PARTITIONS).unwrap(),
+            epoch_stores: None,
         })
     }
 
+    /// Additionally write every object to a directory per type, so that consumers can read the
+    /// objects of the types they are interested in without restoring the whole snapshot.
+    pub fn with_type_partitions(mut self, partition_by_type: bool) -> Self {
+        self.partition_by_type = partition_by_type;
+        self
+    }
+
+    /// Keep the files of at most `max_open_type_partitions` type partitions open at once while
+    /// writing, closing the least recently written to first. Snapshots can have many more types
+    /// than files can be open at once.
+    pub fn with_max_open_type_partitions(mut self, max_open_type_partitions: NonZeroUsize) -> Self {
+        self.max_open_type_partitions = max_open_type_partitions;
+        self
+    }
+
+    /// Also write the EPOCH_METADATA file, reading the committee and last checkpoint of the epoch
+    /// from `checkpoint_store` and `committee_store`.
+    pub fn with_epoch_stores(
+        mut self,
+        checkpoint_store: Arc<CheckpointStore>,
+        committee_store: Arc<CommitteeStore>,
+    ) -> Self {
+        self.epoch_stores = Some((checkpoint_store, committee_store));
+        self
+    }
+
     pub async fn write(
         self,
         epoch: u64,
@@ -267,28 +471,50 @@ impl StateSnapshotWriterV1 {
             chain_identifier.chain(),
         );
         let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
-        self.write_internal(epoch, include_wrapped_tombstone, perpetual_db)
-            .await
+        let epoch_metadata = self
+            .epoch_stores
+            .as_ref()
+            .map(|(checkpoint_store, committee_store)| {
+                EpochMetadata::new(epoch, &protocol_config, checkpoint_store, committee_store)
+            })
+            .transpose()?;
+        self.write_internal(
+            epoch,
+            protocol_version,
+            include_wrapped_tombstone,
+            epoch_metadata,
+            perpetual_db,
+        )
+        .await
     }
 
     pub(crate) async fn write_internal(
         mut self,
         epoch: u64,
+        protocol_version: u64,
         include_wrapped_tombstone: bool,
+        epoch_metadata: Option<EpochMetadata>,
         perpetual_db: Arc<AuthorityPerpetualTables>,
     ) -> Result<()> {
         self.setup_epoch_dir(epoch).await?;
+        if let Some(epoch_metadata) = &epoch_metadata {
+            self.write_epoch_metadata(epoch, epoch_metadata)?;
+        }
 
         let manifest_file_path = self.epoch_dir(epoch).child("MANIFEST");
+        let type_manifest_file_path = self.epoch_dir(epoch).child("TYPE_MANIFEST");
+        let epoch_metadata_file_path = self.epoch_dir(epoch).child("EPOCH_METADATA");
+        let partition_by_type = self.partition_by_type;
         let local_staging_dir = self.local_staging_dir.clone();
         let local_object_store = self.local_staging_store.clone();
         let remote_object_store = self.remote_object_store.clone();
 
-        let (sender, receiver) = mpsc::channel::<FileMetadata>(1000);
-        let upload_handle = self.start_upload(epoch, receiver)?;
+        let (sender, receiver) = mpsc::channel::<Path>(1000);
+        let upload_handle = self.start_upload(receiver)?;
         let write_handler = tokio::task::spawn_blocking(move || {
             self.write_live_object_set(
                 epoch,
+                protocol_version,
                 perpetual_db,
                 sender,
                 Self::bucket_func,
@@ -305,6 +531,26 @@ impl StateSnapshotWriterV1 {
             &epoch
         ))?;
 
+        // The type manifest and epoch metadata go first, so that a snapshot with a MANIFEST is
+        // always complete
+        if epoch_metadata.is_some() {
+            Self::sync_file_to_remote(
+                local_staging_dir.clone(),
+                epoch_metadata_file_path,
+                local_object_store.clone(),
+                remote_object_store.clone(),
+            )
+            .await?;
+        }
+        if partition_by_type {
+            Self::sync_file_to_remote(
+                local_staging_dir.clone(),
+                type_manifest_file_path,
+                local_object_store.clone(),
+                remote_object_store.clone(),
+            )
+            .await?;
+        }
         Self::sync_file_to_remote(
             local_staging_dir,
             manifest_file_path,
@@ -317,18 +563,15 @@ impl StateSnapshotWriterV1 {
 
     fn start_upload(
         &self,
-        epoch: u64,
-        receiver: Receiver<FileMetadata>,
+        receiver: Receiver<Path>,
     ) -> Result<JoinHandle<Result<Vec<()>, anyhow::Error>>> {
         let remote_object_store = self.remote_object_store.clone();
         let local_staging_store = self.local_staging_store.clone();
         let local_dir_path = self.local_staging_dir.clone();
-        let epoch_dir = self.epoch_dir(epoch);
         let upload_concurrency = self.concurrency;
         let join_handle = tokio::spawn(async move {
             let results: Vec<Result<(), anyhow::Error>> = ReceiverStream::new(receiver)
-                .map(|file_metadata| {
-                    let file_path = file_metadata.file_path(&epoch_dir);
+                .map(|file_path| {
                     let remote_object_store = remote_object_store.clone();
                     let local_object_store = local_staging_store.clone();
                     let local_dir_path = local_dir_path.clone();
@@ -357,8 +600,9 @@ impl StateSnapshotWriterV1 {
     fn write_live_object_set<F>(
         &mut self,
         epoch: u64,
+        protocol_version: u64,
         perpetual_db: Arc<AuthorityPerpetualTables>,
-        sender: Sender<FileMetadata>,
+        sender: Sender<Path>,
         bucket_func: F,
         include_wrapped_tombstone: bool,
     ) -> Result<()>
@@ -366,13 +610,19 @@ impl StateSnapshotWriterV1 {
         F: Fn(&LiveObject) -> u32,
     {
         let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
+        let mut typed_object_writers: HashMap<String, TypedObjectWriterV1> = HashMap::new();
+        // Partitions whose files are open, least recently written to first
+        let mut open_partitions: LruCache<String, ()> =
+            LruCache::new(self.max_open_type_partitions);
+        let epoch_dir = self.epoch_dir(epoch);
         let local_staging_dir_path =
-            path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
+            path_to_filesystem(self.local_staging_dir.clone(), &epoch_dir)?;
         for object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
             let bucket_num = bucket_func(&object);
             if let Vacant(entry) = object_writers.entry(bucket_num) {
                 entry.insert(LiveObjectSetWriterV1::new(
                     local_staging_dir_path.clone(),
+                    epoch_dir.clone(),
                     bucket_num,
                     self.file_compression,
                     sender.clone(),
@@ -382,25 +632,98 @@ impl StateSnapshotWriterV1 {
                 .get_mut(&bucket_num)
                 .context("Unexpected missing bucket writer")?;
             writer.write(&object)?;
+            if !self.partition_by_type {
+                continue;
+            }
+            let Some(object_type) = TypedObjectWriterV1::partition(&object) else {
+                continue;
+            };
+            if let Some((closed, ())) = open_partitions.push(object_type.clone(), ()) {
+                if closed != object_type {
+                    typed_object_writers
+                        .get_mut(&closed)
+                        .context("Unexpected missing type partition writer")?
+                        .close()?;
+                }
+            }
+            if let Vacant(entry) = typed_object_writers.entry(object_type.clone()) {
+                entry.insert(TypedObjectWriterV1::new(
+                    self.local_staging_dir.clone(),
+                    epoch_dir.clone(),
+                    object_type.clone(),
+                    self.file_compression,
+                    sender.clone(),
+                )?);
+            }
+            let writer = typed_object_writers
+                .get_mut(&object_type)
+                .context("Unexpected missing type partition writer")?;
+            writer.write(&object)?;
         }
         let mut files = vec![];
         for (_, writer) in object_writers.into_iter() {
             files.extend(writer.done()?);
         }
-        self.write_manifest(epoch, files)?;
+        if self.partition_by_type {
+            let mut typed_files = vec![];
+            for (_, writer) in typed_object_writers.into_iter() {
+                typed_files.extend(writer.done()?);
+            }
+            typed_files
+                .sort_by(|a, b| (&a.object_type, a.part_num).cmp(&(&b.object_type, b.part_num)));
+            self.write_type_manifest(epoch, typed_files)?;
+        }
+        self.write_manifest(epoch, protocol_version, files)?;
         Ok(())
     }
 
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
-        let (f, manifest_file_path) = self.manifest_file(epoch)?;
-        let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
+    fn write_manifest(
+        &mut self,
+        epoch: u64,
+        protocol_version: u64,
+        file_metadata: Vec<FileMetadata>,
+    ) -> Result<()> {
+        let manifest: Manifest = Manifest::V2(ManifestV2 {
             snapshot_version: 1,
             address_length: ObjectID::LENGTH as u64,
             file_metadata,
             epoch,
+            protocol_version,
         });
-        let serialized_manifest = bcs::to_bytes(&manifest)?;
+        self.write_manifest_file(epoch, "MANIFEST", MANIFEST_FILE_MAGIC, &manifest)
+    }
+
+    fn write_type_manifest(
+        &mut self,
+        epoch: u64,
+        file_metadata: Vec<TypedFileMetadata>,
+    ) -> Result<()> {
+        let manifest = TypeManifest::V1(TypeManifestV1 {
+            epoch,
+            file_metadata,
+        });
+        self.write_manifest_file(epoch, "TYPE_MANIFEST", TYPE_MANIFEST_FILE_MAGIC, &manifest)
+    }
+
+    fn write_epoch_metadata(&mut self, epoch: u64, epoch_metadata: &EpochMetadata) -> Result<()> {
+        self.write_manifest_file(
+            epoch,
+            "EPOCH_METADATA",
+            EPOCH_METADATA_FILE_MAGIC,
+            epoch_metadata,
+        )
+    }
+
+    fn write_manifest_file<T: serde::Serialize>(
+        &mut self,
+        epoch: u64,
+        name: &str,
+        magic: u32,
+        manifest: &T,
+    ) -> Result<()> {
+        let (f, manifest_file_path) = self.manifest_file(epoch, name, magic)?;
+        let mut wbuf = BufWriter::new(f);
+        let serialized_manifest = bcs::to_bytes(manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
         wbuf.get_ref().sync_data()?;
@@ -413,18 +736,18 @@ impl StateSnapshotWriterV1 {
         Ok(())
     }
 
-    fn manifest_file(&mut self, epoch: u64) -> Result<(File, PathBuf)> {
+    fn manifest_file(&mut self, epoch: u64, name: &str, magic: u32) -> Result<(File, PathBuf)> {
         let manifest_file_path = path_to_filesystem(
             self.local_staging_dir.clone(),
-            &self.epoch_dir(epoch).child("MANIFEST"),
+            &self.epoch_dir(epoch).child(name),
         )?;
         let manifest_file_tmp_path = path_to_filesystem(
             self.local_staging_dir.clone(),
-            &self.epoch_dir(epoch).child("MANIFEST.tmp"),
+            &self.epoch_dir(epoch).child(&*format!("{name}.tmp")),
         )?;
         let mut f = File::create(manifest_file_tmp_path.clone())?;
         let mut metab = vec![0u8; MAGIC_BYTES];
-        BigEndian::write_u32(&mut metab, MANIFEST_FILE_MAGIC);
+        BigEndian::write_u32(&mut metab, magic);
         f.rewind()?;
         f.write_all(&metab)?;
         drop(f);
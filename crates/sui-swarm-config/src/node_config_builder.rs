@@ -89,6 +89,8 @@ impl ValidatorConfigBuilder {
             max_pending_transactions: None,
             max_submit_position: None,
             submit_delay_step_override_millis: None,
+            exclude_self_from_low_scoring: false,
+            low_scoring_exclusions: vec![],
             narwhal_config: narwhal_config::Parameters {
                 network_admin_server: NetworkAdminServerParameters {
                     primary_network_admin_server_port: local_ip_utils::get_available_port(
@@ -131,6 +133,7 @@ impl ValidatorConfigBuilder {
             network_address,
             metrics_address: validator.metrics_address,
             admin_interface_port: local_ip_utils::get_available_port(&localhost),
+            admin_interface_token: None,
             json_rpc_address: local_ip_utils::new_tcp_address_for_testing(&localhost)
                 .to_socket_addr()
                 .unwrap(),
@@ -160,6 +163,8 @@ impl ValidatorConfigBuilder {
             state_archive_write_config: StateArchiveConfig::default(),
             state_archive_read_config: vec![],
             state_snapshot_write_config: StateSnapshotConfig::default(),
+            cold_object_store_config: None,
+            dry_run_cache_config: None,
             indexer_max_subscriptions: Default::default(),
             transaction_kv_store_read_config: Default::default(),
             transaction_kv_store_write_config: None,
@@ -168,6 +173,7 @@ impl ValidatorConfigBuilder {
                 .jwk_fetch_interval
                 .map(|i| i.as_secs())
                 .unwrap_or(3600),
+            validator_qos_config: None,
         }
     }
 
@@ -367,6 +373,7 @@ impl FullnodeConfigBuilder {
             admin_interface_port: self
                 .admin_interface_port
                 .unwrap_or(local_ip_utils::get_available_port(&localhost)),
+            admin_interface_token: None,
             json_rpc_address: self.json_rpc_address.unwrap_or(json_rpc_address),
             consensus_config: None,
             enable_event_processing: true, // This is unused.
@@ -397,12 +404,15 @@ impl FullnodeConfigBuilder {
             state_archive_write_config: StateArchiveConfig::default(),
             state_archive_read_config: vec![],
             state_snapshot_write_config: StateSnapshotConfig::default(),
+            cold_object_store_config: None,
+            dry_run_cache_config: None,
             indexer_max_subscriptions: Default::default(),
             transaction_kv_store_read_config: Default::default(),
             transaction_kv_store_write_config: Default::default(),
             enable_experimental_rest_api: true,
             // note: not used by fullnodes.
             jwk_fetch_interval_seconds: 3600,
+            validator_qos_config: None,
         }
     }
 }
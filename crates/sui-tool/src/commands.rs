@@ -3,8 +3,8 @@
 
 use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
-    download_db_snapshot, get_object, get_transaction_block, make_clients,
-    restore_from_db_checkpoint, state_sync_from_archive, verify_archive,
+    download_db_snapshot, download_formal_snapshot, get_object, get_transaction_block,
+    make_clients, restore_from_db_checkpoint, state_sync_from_archive, verify_archive,
     verify_archive_by_checksum, ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
 };
 use anyhow::{anyhow, Result};
@@ -227,6 +227,27 @@ pub enum ToolCommand {
         archive_bucket_type: ObjectStoreType,
     },
 
+    /// Restores the live objects of an epoch from a formal snapshot, once its epoch metadata has
+    /// been verified and this binary's protocol config matches the epoch's.
+    #[clap(name = "download-formal-snapshot")]
+    DownloadFormalSnapshot {
+        #[clap(long = "epoch")]
+        epoch: u64,
+        #[clap(long = "genesis")]
+        genesis: PathBuf,
+        #[clap(long = "path", default_value = "/tmp")]
+        path: PathBuf,
+        #[clap(long = "num-parallel-downloads", default_value = "50")]
+        num_parallel_downloads: usize,
+        #[clap(
+            long = "snapshot-bucket",
+            default_value = "mysten-mainnet-formal-snapshots"
+        )]
+        snapshot_bucket: String,
+        #[clap(long = "snapshot-bucket-type", default_value = "s3")]
+        snapshot_bucket_type: ObjectStoreType,
+    },
+
     #[clap(name = "replay")]
     Replay {
         #[arg(long = "rpc")]
@@ -425,51 +446,8 @@ impl ToolCommand {
                 archive_bucket,
                 archive_bucket_type,
             } => {
-                let snapshot_store_config = match snapshot_bucket_type {
-                    ObjectStoreType::S3 => {
-                        ObjectStoreConfig {
-                            object_store: Some(ObjectStoreType::S3),
-                            bucket: Some(snapshot_bucket),
-                            aws_access_key_id: Some(env::var(
-                                "AWS_SNAPSHOT_ACCESS_KEY_ID",
-                            ).map_err(|_| anyhow!("Please provide AWS_SNAPSHOT_ACCESS_KEY_ID as env variable"))?),
-                            aws_secret_access_key: Some(env::var(
-                                "AWS_SNAPSHOT_SECRET_ACCESS_KEY",
-                            ).map_err(|_| anyhow!("Please provide AWS_SNAPSHOT_SECRET_ACCESS_KEY as env variable"))?),
-                            aws_region: Some(env::var(
-                                "AWS_SNAPSHOT_REGION",
-                            ).map_err(|_| anyhow!("Please provide AWS_SNAPSHOT_REGION as env variable"))?),
-                            object_store_connection_limit: 200,
-                            ..Default::default()
-                        }
-                    },
-                    ObjectStoreType::GCS => {
-                        ObjectStoreConfig {
-                            object_store: Some(ObjectStoreType::GCS),
-                            bucket: Some(snapshot_bucket),
-                            google_service_account: Some(env::var(
-                                "GCS_SNAPSHOT_SERVICE_ACCOUNT_FILE_PATH",
-                            ).map_err(|_| anyhow!("Please provide GCS_SNAPSHOT_SERVICE_ACCOUNT_FILE_PATH as env variable"))?),
-                            object_store_connection_limit: 200,
-                            ..Default::default()
-                        }
-                    },
-                    ObjectStoreType::Azure => {
-                        ObjectStoreConfig {
-                            object_store: Some(ObjectStoreType::Azure),
-                            bucket: Some(snapshot_bucket),
-                            azure_storage_account: Some(env::var(
-                                "AZURE_SNAPSHOT_STORAGE_ACCOUNT",
-                            ).map_err(|_| anyhow!("Please provide AZURE_SNAPSHOT_STORAGE_ACCOUNT as env variable"))?),
-                            azure_storage_access_key: Some(env::var(
-                                "AZURE_SNAPSHOT_STORAGE_ACCESS_KEY",
-                            ).map_err(|_| anyhow!("Please provide AZURE_SNAPSHOT_STORAGE_ACCESS_KEY as env variable"))?),
-                            object_store_connection_limit: 200,
-                            ..Default::default()
-                        }
-                    },
-                    ObjectStoreType::File => panic!("Download from local filesystem is not supported")
-                };
+                let snapshot_store_config =
+                    snapshot_store_config(snapshot_bucket, snapshot_bucket_type)?;
 
                 let archive_store_config = match archive_bucket_type {
                     ObjectStoreType::S3 => {
@@ -529,6 +507,25 @@ impl ToolCommand {
                 )
                 .await?;
             }
+            ToolCommand::DownloadFormalSnapshot {
+                epoch,
+                genesis,
+                path,
+                num_parallel_downloads,
+                snapshot_bucket,
+                snapshot_bucket_type,
+            } => {
+                let snapshot_store_config =
+                    snapshot_store_config(snapshot_bucket, snapshot_bucket_type)?;
+                download_formal_snapshot(
+                    &path,
+                    epoch,
+                    &genesis,
+                    snapshot_store_config,
+                    num_parallel_downloads,
+                )
+                .await?;
+            }
             ToolCommand::Replay {
                 rpc_url,
                 safety_checks,
@@ -586,3 +583,53 @@ impl ToolCommand {
         Ok(())
     }
 }
+
+/// Where snapshots are downloaded from, with credentials read from the environment.
+fn snapshot_store_config(
+    snapshot_bucket: String,
+    snapshot_bucket_type: ObjectStoreType,
+) -> Result<ObjectStoreConfig> {
+    let config = match snapshot_bucket_type {
+        ObjectStoreType::S3 => ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::S3),
+            bucket: Some(snapshot_bucket),
+            aws_access_key_id: Some(env::var("AWS_SNAPSHOT_ACCESS_KEY_ID").map_err(|_| {
+                anyhow!("Please provide AWS_SNAPSHOT_ACCESS_KEY_ID as env variable")
+            })?),
+            aws_secret_access_key: Some(env::var("AWS_SNAPSHOT_SECRET_ACCESS_KEY").map_err(
+                |_| anyhow!("Please provide AWS_SNAPSHOT_SECRET_ACCESS_KEY as env variable"),
+            )?),
+            aws_region: Some(
+                env::var("AWS_SNAPSHOT_REGION")
+                    .map_err(|_| anyhow!("Please provide AWS_SNAPSHOT_REGION as env variable"))?,
+            ),
+            object_store_connection_limit: 200,
+            ..Default::default()
+        },
+        ObjectStoreType::GCS => ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::GCS),
+            bucket: Some(snapshot_bucket),
+            google_service_account: Some(
+                env::var("GCS_SNAPSHOT_SERVICE_ACCOUNT_FILE_PATH").map_err(|_| {
+                    anyhow!("Please provide GCS_SNAPSHOT_SERVICE_ACCOUNT_FILE_PATH as env variable")
+                })?,
+            ),
+            object_store_connection_limit: 200,
+            ..Default::default()
+        },
+        ObjectStoreType::Azure => ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::Azure),
+            bucket: Some(snapshot_bucket),
+            azure_storage_account: Some(env::var("AZURE_SNAPSHOT_STORAGE_ACCOUNT").map_err(
+                |_| anyhow!("Please provide AZURE_SNAPSHOT_STORAGE_ACCOUNT as env variable"),
+            )?),
+            azure_storage_access_key: Some(env::var("AZURE_SNAPSHOT_STORAGE_ACCESS_KEY").map_err(
+                |_| anyhow!("Please provide AZURE_SNAPSHOT_STORAGE_ACCESS_KEY as env variable"),
+            )?),
+            object_store_connection_limit: 200,
+            ..Default::default()
+        },
+        ObjectStoreType::File => panic!("Download from local filesystem is not supported"),
+    };
+    Ok(config)
+}
@@ -4,7 +4,7 @@
 
 use anyhow::Result;
 use fastcrypto::traits::ToFromBytes;
-use futures::future::join_all;
+use futures::future::{join_all, AbortHandle};
 use itertools::Itertools;
 use std::collections::BTreeMap;
 use std::fmt::Write;
@@ -17,8 +17,11 @@ use std::{fs, io};
 use sui_config::{genesis::Genesis, NodeConfig};
 use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
 use sui_network::default_mysten_network_config;
+use sui_protocol_config::SupportedProtocolVersions;
 use sui_sdk::SuiClientBuilder;
+use sui_snapshot::reader::StateSnapshotReaderV1;
 use sui_types::crypto::AuthorityPublicKeyBytes;
+use sui_types::digests::ChainIdentifier;
 use sui_types::multiaddr::Multiaddr;
 use sui_types::object::ObjectFormatOptions;
 use sui_types::{base_types::*, object::Owner};
@@ -622,6 +625,51 @@ pub async fn restore_from_db_checkpoint(
     Ok(())
 }
 
+/// Restores the live objects of `epoch` from the formal snapshot in `snapshot_store_config` into a
+/// new database at `path`. Refuses to restore a snapshot whose epoch metadata is not certified by
+/// the committee it names, or that was taken at a protocol config that differs from the one this
+/// binary has for the epoch's protocol version, as objects would be read differently from how the
+/// network wrote them.
+pub async fn download_formal_snapshot(
+    path: &Path,
+    epoch: EpochId,
+    genesis: &Path,
+    snapshot_store_config: ObjectStoreConfig,
+    num_parallel_downloads: usize,
+) -> Result<(), anyhow::Error> {
+    let genesis = Genesis::load(genesis)?;
+    let chain = ChainIdentifier::from(*genesis.checkpoint().digest()).chain();
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(path.join("snapshot")),
+        ..Default::default()
+    };
+    let mut reader = StateSnapshotReaderV1::new(
+        epoch,
+        &snapshot_store_config,
+        &local_store_config,
+        usize::MAX,
+        NonZeroUsize::new(num_parallel_downloads)
+            .ok_or_else(|| anyhow!("Number of parallel downloads must be positive"))?,
+        SupportedProtocolVersions::SYSTEM_DEFAULT,
+    )
+    .await?;
+
+    // The reader has already checked that the metadata is consistent and certified.
+    match reader.epoch_metadata() {
+        Some(epoch_metadata) => epoch_metadata.check_protocol_config(chain)?,
+        None => info!(
+            "Snapshot for epoch {epoch} has no epoch metadata, not checking its protocol config"
+        ),
+    }
+
+    let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    reader.read(&perpetual_db, abort_registration).await?;
+    info!("Restored snapshot of epoch {epoch} to {}", path.display());
+    Ok(())
+}
+
 pub async fn download_db_snapshot(
     path: &Path,
     epoch: u32,
@@ -32,6 +32,7 @@ mod checked {
     };
     use sui_types::{
         SUI_AUTHENTICATOR_STATE_OBJECT_ID, SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION,
+        SUI_RANDOMNESS_STATE_OBJECT_ID,
     };
     use tracing::error;
     use tracing::instrument;
@@ -522,6 +523,21 @@ mod checked {
                     });
                 }
             }
+            InputObjectKind::SharedMoveObject {
+                id: SUI_RANDOMNESS_STATE_OBJECT_ID,
+                mutable: true,
+                ..
+            } => {
+                // Only system transactions can accept the Random
+                // object as a mutable parameter.
+                if system_transaction {
+                    return Ok(());
+                } else {
+                    return Err(UserInputError::ImmutableParameterExpectedError {
+                        object_id: SUI_RANDOMNESS_STATE_OBJECT_ID,
+                    });
+                }
+            }
             InputObjectKind::SharedMoveObject {
                 initial_shared_version: input_initial_shared_version,
                 ..
@@ -585,6 +585,17 @@ pub enum SuiError {
 
     #[error("Failed to get JWK")]
     JWKRetrievalError,
+
+    // New variants go at the end, as errors are BCS serialized in RPC responses, and their
+    // variant indices must not change.
+    #[error(
+        "Transaction expires in epoch {expiration}, later than the latest accepted expiration \
+         epoch {max_expiration}"
+    )]
+    TransactionExpirationTooFar {
+        expiration: EpochId,
+        max_expiration: EpochId,
+    },
 }
 
 #[repr(u64)]
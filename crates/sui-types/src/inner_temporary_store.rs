@@ -29,6 +29,9 @@ pub struct InnerTemporaryStore {
     pub max_binary_format_version: u32,
     pub no_extraneous_module_bytes: bool,
     pub runtime_packages_loaded_from_db: BTreeMap<ObjectID, Object>,
+    /// Gas units (before multiplying by the gas price) used by each command of the programmable
+    /// transaction, by command index. Empty if the execution engine does not track them.
+    pub command_gas_used: Vec<u64>,
 }
 
 pub struct TemporaryModuleResolver<'a, R> {
@@ -62,6 +62,7 @@ pub mod multisig_legacy;
 pub mod object;
 pub mod programmable_transaction_builder;
 pub mod quorum_driver_types;
+pub mod randomness_state;
 pub mod signature;
 pub mod storage;
 pub mod sui_serde;
@@ -114,6 +115,11 @@ pub const SUI_AUTHENTICATOR_STATE_ADDRESS: AccountAddress = address_from_single_
 pub const SUI_AUTHENTICATOR_STATE_OBJECT_ID: ObjectID =
     ObjectID::from_address(SUI_AUTHENTICATOR_STATE_ADDRESS);
 
+/// 0x8: hardcode object ID for the singleton randomness state object.
+pub const SUI_RANDOMNESS_STATE_ADDRESS: AccountAddress = address_from_single_byte(8);
+pub const SUI_RANDOMNESS_STATE_OBJECT_ID: ObjectID =
+    ObjectID::from_address(SUI_RANDOMNESS_STATE_ADDRESS);
+
 /// Return `true` if `id` is a special system package that can be upgraded at epoch boundaries
 /// All new system package ID's must be added here
 pub fn is_system_package(id: ObjectID) -> bool {
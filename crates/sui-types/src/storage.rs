@@ -149,6 +149,9 @@ pub trait Storage {
         &mut self,
         loaded_runtime_objects: BTreeMap<ObjectID, DynamicallyLoadedObjectMetadata>,
     );
+
+    /// Record the gas units used by each command of a programmable transaction, for diagnostics.
+    fn save_command_gas_used(&mut self, _command_gas_used: Vec<u64>) {}
 }
 
 pub type PackageFetchResults<Package> = Result<Vec<Package>, Vec<ObjectID>>;
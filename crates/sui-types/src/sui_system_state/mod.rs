@@ -409,6 +409,14 @@ pub struct PoolTokenExchangeRate {
 }
 
 impl PoolTokenExchangeRate {
+    pub fn sui_amount(&self) -> u64 {
+        self.sui_amount
+    }
+
+    pub fn pool_token_amount(&self) -> u64 {
+        self.pool_token_amount
+    }
+
     /// Rate of the staking pool, pool token amount : Sui amount
     pub fn rate(&self) -> f64 {
         if self.sui_amount == 0 {
@@ -62,6 +62,11 @@ impl ZkLoginAuthenticator {
     pub fn get_iss(&self) -> &str {
         self.inputs.get_iss()
     }
+
+    /// The signature by the ephemeral key.
+    pub fn get_user_signature(&self) -> &Signature {
+        &self.user_signature
+    }
 }
 
 /// Necessary trait for [struct SenderSignedData].
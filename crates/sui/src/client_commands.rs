@@ -5,6 +5,7 @@ use core::fmt;
 use std::{
     fmt::{Debug, Display, Formatter, Write},
     path::PathBuf,
+    str::FromStr,
     sync::Arc,
 };
 
@@ -13,7 +14,7 @@ use bip32::DerivationPath;
 use clap::*;
 use colored::Colorize;
 use fastcrypto::{
-    encoding::{Base64, Encoding},
+    encoding::{Base64, Encoding, Hex},
     traits::ToFromBytes,
 };
 use json_to_table::json_to_table;
@@ -29,33 +30,36 @@ use sui_source_validation::{BytecodeSourceVerifier, SourceMode};
 use shared_crypto::intent::Intent;
 use sui_execution::verifier::VerifierOverrides;
 use sui_json::SuiJsonValue;
+use sui_json_rpc_types::{Balance, SuiCoinMetadata, SuiExecutionStatus, SuiObjectDataOptions};
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    BalanceChange, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiParsedData, SuiRawData, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
-use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
-use sui_keys::keystore::AccountKeystore;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
 use sui_move_build::{
     build_from_resolution_graph, check_invalid_dependencies, check_unpublished_dependencies,
     gather_published_ids, BuildConfig, CompiledPackage, PackageDependencies, PublishedAtError,
 };
-use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
+use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv, SuiEnvFieldDiff};
 use sui_sdk::wallet_context::WalletContext;
 use sui_sdk::SuiClient;
 use sui_types::{
     base_types::{ObjectID, SequenceNumber, SuiAddress},
-    crypto::SignatureScheme,
+    crypto::{default_hash, SignatureScheme},
     digests::TransactionDigest,
     dynamic_field::DynamicFieldInfo,
     error::SuiError,
-    gas_coin::GasCoin,
+    gas_coin::{GasCoin, GAS},
     metrics::BytecodeVerifierMetrics,
     move_package::UpgradeCap,
     object::Owner,
     parse_sui_type_tag,
     signature::GenericSignature,
-    transaction::{SenderSignedData, Transaction, TransactionData, TransactionDataAPI},
+    transaction::{
+        CallArg, ObjectArg, SenderSignedData, Transaction, TransactionData, TransactionDataAPI,
+        TransactionExpiration, TransactionKind,
+    },
 };
 
 use tabled::{
@@ -76,7 +80,8 @@ macro_rules! serialize_or_execute {
         if $serialize_unsigned {
             SuiClientCommandResult::SerializedUnsignedTransaction($tx_data)
         } else {
-            let signature = $context.config.keystore.sign_secure(
+            $context.check_gas_budget($tx_data.gas_budget())?;
+            let signature = $context.keystore().sign_secure(
                 &$tx_data.sender(),
                 &$tx_data,
                 Intent::sui_transaction(),
@@ -119,6 +124,19 @@ pub enum SuiClientCommands {
     #[clap(name = "addresses")]
     Addresses,
 
+    /// Show the total balance of each coin type owned by the address, and how many coin objects
+    /// it is split across.
+    #[clap(name = "balance")]
+    Balance {
+        /// Address owning the coins
+        #[clap(name = "owner_address")]
+        address: Option<SuiAddress>,
+        /// Price in USD of one whole coin, as `COIN_TYPE=PRICE` (e.g. `0x2::sui::SUI=0.5`), used
+        /// to estimate the value of the balance of that coin type. Can be repeated.
+        #[clap(long = "usd-price", value_parser = parse_usd_price)]
+        usd_prices: Vec<(TypeTag, f64)>,
+    },
+
     /// Call Move function
     #[clap(name = "call")]
     Call {
@@ -181,6 +199,13 @@ pub enum SuiClientCommands {
         limit: usize,
     },
 
+    /// Inspect Sui environments
+    #[clap(name = "env")]
+    Env {
+        #[clap(subcommand)]
+        cmd: SuiClientEnvCommands,
+    },
+
     /// List all Sui environments
     Envs,
 
@@ -203,6 +228,15 @@ pub enum SuiClientCommands {
         address: Option<SuiAddress>,
     },
 
+    /// Decode a transaction and print its inputs, commands and gas data, e.g. to review a
+    /// transaction received from someone else before signing it.
+    #[clap(name = "inspect-tx")]
+    InspectTx {
+        /// Base64 encoded BCS bytes of `TransactionData` or `SenderSignedData`, or the digest of an
+        /// executed transaction, in which case its effects and balance changes are shown as well.
+        tx: String,
+    },
+
     /// Merge two coin objects into one coin
     MergeCoin {
         /// Coin to merge into, in 20 bytes Hex string
@@ -250,6 +284,19 @@ pub enum SuiClientCommands {
         rpc: String,
         #[clap(long, value_hint = ValueHint::Url)]
         ws: Option<String>,
+        #[clap(long, value_hint = ValueHint::Url)]
+        graphql: Option<String>,
+        /// Path to a keystore to use instead of the client-wide one while the environment is
+        /// active.
+        #[clap(long, value_hint = ValueHint::FilePath)]
+        keystore_path: Option<PathBuf>,
+        /// Address to use as the sender while the environment is active, instead of the
+        /// client-wide active address.
+        #[clap(long)]
+        address: Option<SuiAddress>,
+        /// Refuse to sign transactions with a larger gas budget while the environment is active.
+        #[clap(long)]
+        max_gas_budget: Option<u64>,
     },
 
     /// Get object info
@@ -443,7 +490,7 @@ pub enum SuiClientCommands {
     #[clap(name = "switch")]
     Switch {
         /// An Sui address to be used as the active address for subsequent
-        /// commands.
+        /// commands. If the active environment pins its own sender, that is updated instead.
         #[clap(long)]
         address: Option<SuiAddress>,
         /// The RPC server URL (e.g., local rpc server, devnet rpc server, etc) to be
@@ -609,6 +656,18 @@ pub enum SuiClientCommands {
     },
 }
 
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum SuiClientEnvCommands {
+    /// Show the settings that differ between two environments
+    #[clap(name = "diff")]
+    Diff {
+        env_a: String,
+        /// Defaults to the active environment if not specified
+        env_b: Option<String>,
+    },
+}
+
 impl SuiClientCommands {
     pub async fn execute(
         self,
@@ -617,13 +676,33 @@ impl SuiClientCommands {
         let ret = Ok(match self {
             SuiClientCommands::Addresses => {
                 let active_address = context.active_address()?;
-                let addresses = context.config.keystore.addresses();
+                let addresses = context.keystore().addresses();
                 SuiClientCommandResult::Addresses(AddressesOutput {
                     addresses,
                     active_address,
                 })
             }
 
+            SuiClientCommands::Balance {
+                address,
+                usd_prices,
+            } => {
+                let address = address.unwrap_or(context.active_address()?);
+                let client = context.get_client().await?;
+                let mut balances = client.coin_read_api().get_all_balances(address).await?;
+                balances.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+
+                let mut outputs = vec![];
+                for balance in balances {
+                    let metadata = client
+                        .coin_read_api()
+                        .get_coin_metadata(balance.coin_type.clone())
+                        .await?;
+                    outputs.push(BalanceOutput::new(balance, metadata, &usd_prices));
+                }
+                SuiClientCommandResult::Balance(outputs)
+            }
+
             SuiClientCommands::DynamicFieldQuery { id, cursor, limit } => {
                 let client = context.get_client().await?;
                 let df_read = client
@@ -832,6 +911,45 @@ impl SuiClientCommands {
                 }
             }
 
+            SuiClientCommands::InspectTx { tx } => {
+                let output = if let Ok(digest) = TransactionDigest::from_str(&tx) {
+                    let client = context.get_client().await?;
+                    let response = client
+                        .read_api()
+                        .get_transaction_with_options(
+                            digest,
+                            SuiTransactionBlockResponseOptions::new()
+                                .with_raw_input()
+                                .with_effects()
+                                .with_balance_changes(),
+                        )
+                        .await?;
+                    let signed: SenderSignedData = bcs::from_bytes(&response.raw_transaction)?;
+                    InspectTxOutput::new(
+                        signed.transaction_data().clone(),
+                        signed.tx_signatures(),
+                        Some(response),
+                    )
+                } else {
+                    let bytes = Base64::decode(&tx).map_err(|e| {
+                        anyhow!("Expected a transaction digest or Base64 encoded bytes: {e}")
+                    })?;
+                    if let Ok(signed) = bcs::from_bytes::<SenderSignedData>(&bytes) {
+                        InspectTxOutput::new(
+                            signed.transaction_data().clone(),
+                            signed.tx_signatures(),
+                            None,
+                        )
+                    } else {
+                        let data: TransactionData = bcs::from_bytes(&bytes).map_err(|e| {
+                            anyhow!("Cannot decode TransactionData or SenderSignedData: {e}")
+                        })?;
+                        InspectTxOutput::new(data, &[], None)
+                    }
+                };
+                SuiClientCommandResult::InspectTx(output)
+            }
+
             SuiClientCommands::TransactionBlock { digest } => {
                 let client = context.get_client().await?;
                 let tx_read = client
@@ -1061,7 +1179,7 @@ impl SuiClientCommands {
                 derivation_path,
                 word_length,
             } => {
-                let (address, phrase, scheme) = context.config.keystore.generate_and_add_new_key(
+                let (address, phrase, scheme) = context.keystore_mut().generate_and_add_new_key(
                     key_scheme,
                     derivation_path,
                     word_length,
@@ -1158,12 +1276,18 @@ impl SuiClientCommands {
                 match (address, &env) {
                     (None, Some(env)) => {
                         Self::switch_env(&mut context.config, env)?;
+                        context.load_env_keystore()?;
                     }
                     (Some(addr), None) => {
-                        if !context.config.keystore.addresses().contains(&addr) {
+                        if !context.keystore().addresses().contains(&addr) {
                             return Err(anyhow!("Address {} not managed by wallet", addr));
                         }
-                        context.config.active_address = Some(addr);
+                        match context.config.get_active_env_mut() {
+                            Ok(env) if env.active_address.is_some() => {
+                                env.active_address = Some(addr)
+                            }
+                            _ => context.config.active_address = Some(addr),
+                        }
                     }
                     _ => return Err(anyhow!("No address or env specified. Please Specify one.")),
                 }
@@ -1203,13 +1327,39 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
-            SuiClientCommands::NewEnv { alias, rpc, ws } => {
+            SuiClientCommands::NewEnv {
+                alias,
+                rpc,
+                ws,
+                graphql,
+                keystore_path,
+                address,
+                max_gas_budget,
+            } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
                         "Environment config with name [{alias}] already exists."
                     ));
                 }
-                let env = SuiEnv { alias, rpc, ws };
+                if let Some(address) = address {
+                    let addresses = match &keystore_path {
+                        Some(path) => FileBasedKeystore::new(path)?.addresses(),
+                        None => context.config.keystore.addresses(),
+                    };
+                    ensure!(
+                        addresses.contains(&address),
+                        "Address {address} not managed by the keystore of env [{alias}]"
+                    );
+                }
+                let env = SuiEnv {
+                    alias,
+                    rpc,
+                    ws,
+                    graphql,
+                    keystore: keystore_path,
+                    active_address: address,
+                    max_gas_budget,
+                };
 
                 // Check urls are valid and server is reachable
                 env.create_rpc_client(None, None).await?;
@@ -1220,6 +1370,26 @@ impl SuiClientCommands {
             SuiClientCommands::ActiveEnv => {
                 SuiClientCommandResult::ActiveEnv(context.config.active_env.clone())
             }
+            SuiClientCommands::Env {
+                cmd: SuiClientEnvCommands::Diff { env_a, env_b },
+            } => {
+                let get_env = |alias: &str| {
+                    context
+                        .config
+                        .get_env(&Some(alias.to_string()))
+                        .ok_or_else(|| anyhow!("Environment config not found for [{alias}]"))
+                };
+                let left = get_env(&env_a)?;
+                let right = match &env_b {
+                    Some(env_b) => get_env(env_b)?,
+                    None => context.config.get_active_env()?,
+                };
+                SuiClientCommandResult::EnvDiff(
+                    left.alias.clone(),
+                    right.alias.clone(),
+                    left.diff(right),
+                )
+            }
             SuiClientCommands::Envs => SuiClientCommandResult::Envs(
                 context.config.envs.clone(),
                 context.config.active_env.clone(),
@@ -1392,6 +1562,50 @@ impl Display for SuiClientCommandResult {
                 table.with(style);
                 write!(f, "{}", table)?
             }
+            SuiClientCommandResult::Balance(balances) => {
+                if balances.is_empty() {
+                    write!(f, "No coins are owned by this address")?;
+                    return Ok(());
+                }
+
+                let mut builder = TableBuilder::default();
+                builder.set_header(vec![
+                    "coinType",
+                    "symbol",
+                    "balance",
+                    "coinObjects",
+                    "estimatedUsd",
+                ]);
+                for balance in balances {
+                    builder.push_record(vec![
+                        balance.coin_type.clone(),
+                        balance.symbol.clone().unwrap_or_default(),
+                        balance.formatted_balance(),
+                        balance.coin_object_count.to_string(),
+                        balance
+                            .estimated_usd
+                            .map(|usd| format!("~${usd:.2}"))
+                            .unwrap_or_default(),
+                    ]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                write!(f, "{}", table)?;
+
+                for balance in balances.iter().filter(|b| b.fragmented) {
+                    write!(
+                        f,
+                        "\n{} is split across {} coin objects, consider merging them with `sui client {}`",
+                        balance.symbol.as_deref().unwrap_or(&balance.coin_type),
+                        balance.coin_object_count,
+                        if balance.is_sui {
+                            "pay-all-sui"
+                        } else {
+                            "merge-coin"
+                        },
+                    )?;
+                }
+            }
             SuiClientCommandResult::DynamicFieldQuery(df_refs) => {
                 let df_refs = DynamicFieldOutput {
                     has_next_page: df_refs.has_next_page,
@@ -1505,6 +1719,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::TransactionBlock(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::InspectTx(output) => {
+                write!(writer, "{}", output)?;
+            }
             SuiClientCommandResult::RawObject(raw_object_read) => {
                 let raw_object = match raw_object_read.object() {
                     Ok(v) => match &v.bcs {
@@ -1588,6 +1805,27 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::NewEnv(env) => {
                 writeln!(writer, "Added new Sui env [{}] to config.", env.alias)?;
             }
+            SuiClientCommandResult::EnvDiff(left, right, diffs) => {
+                if diffs.is_empty() {
+                    write!(
+                        writer,
+                        "Environments [{left}] and [{right}] have the same settings."
+                    )?;
+                } else {
+                    let mut builder = TableBuilder::default();
+                    builder.set_header(["", left.as_str(), right.as_str()]);
+                    for diff in diffs {
+                        builder.push_record(vec![
+                            diff.field.to_string(),
+                            diff.left.clone().unwrap_or_else(|| "None".to_string()),
+                            diff.right.clone().unwrap_or_else(|| "None".to_string()),
+                        ]);
+                    }
+                    let mut table = builder.build();
+                    table.with(TableStyle::rounded());
+                    write!(writer, "{}", table)?
+                }
+            }
             SuiClientCommandResult::Envs(envs, active) => {
                 let mut builder = TableBuilder::default();
                 builder.set_header(["alias", "url", "active"]);
@@ -1853,6 +2091,86 @@ impl From<&SuiObjectData> for ObjectOutput {
     }
 }
 
+/// Coin types held in more coin objects than this are flagged as fragmented.
+const FRAGMENTED_COIN_OBJECT_COUNT: usize = 10;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceOutput {
+    pub coin_type: String,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// Total balance, in the coin's smallest unit.
+    pub total_balance: u128,
+    pub coin_object_count: usize,
+    pub fragmented: bool,
+    pub estimated_usd: Option<f64>,
+    #[serde(skip)]
+    is_sui: bool,
+}
+
+impl BalanceOutput {
+    fn new(
+        balance: Balance,
+        metadata: Option<SuiCoinMetadata>,
+        usd_prices: &[(TypeTag, f64)],
+    ) -> Self {
+        let symbol = metadata.as_ref().map(|m| m.symbol.clone());
+        let decimals = metadata.as_ref().map(|m| m.decimals);
+        // Symbols are chosen by coins' publishers, so they are not unique, unlike coin types.
+        let coin_type = parse_sui_type_tag(&balance.coin_type).ok();
+        let estimated_usd = coin_type
+            .as_ref()
+            .zip(decimals)
+            .and_then(|(coin_type, decimals)| {
+                let (_, price) = usd_prices.iter().find(|(t, _)| t == coin_type)?;
+                Some(balance.total_balance as f64 / 10f64.powi(decimals as i32) * price)
+            });
+        Self {
+            coin_type: balance.coin_type,
+            symbol,
+            decimals,
+            total_balance: balance.total_balance,
+            coin_object_count: balance.coin_object_count,
+            fragmented: balance.coin_object_count > FRAGMENTED_COIN_OBJECT_COUNT,
+            estimated_usd,
+            is_sui: coin_type.is_some_and(|t| t == GAS::type_tag()),
+        }
+    }
+
+    /// The balance in whole coins, if the coin's decimals are known, or in its smallest unit
+    /// otherwise.
+    fn formatted_balance(&self) -> String {
+        let Some(decimals) = self.decimals.filter(|d| *d > 0) else {
+            return self.total_balance.to_string();
+        };
+        let unit = 10u128.pow(decimals as u32);
+        let fraction = format!(
+            "{:0width$}",
+            self.total_balance % unit,
+            width = decimals as usize
+        );
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            (self.total_balance / unit).to_string()
+        } else {
+            format!("{}.{}", self.total_balance / unit, fraction)
+        }
+    }
+}
+
+fn parse_usd_price(s: &str) -> Result<(TypeTag, f64), anyhow::Error> {
+    let (coin_type, price) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected COIN_TYPE=PRICE, got {s}"))?;
+    let price = price
+        .parse::<f64>()
+        .map_err(|e| anyhow!("Invalid USD price for {coin_type}: {e}"))?;
+    let coin_type =
+        parse_sui_type_tag(coin_type).map_err(|e| anyhow!("Invalid coin type {coin_type}: {e}"))?;
+    Ok((coin_type, price))
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GasCoinOutput {
@@ -1917,12 +2235,15 @@ pub enum SuiClientCommandResult {
     ActiveAddress(Option<SuiAddress>),
     ActiveEnv(Option<String>),
     Addresses(AddressesOutput),
+    Balance(Vec<BalanceOutput>),
     Call(SuiTransactionBlockResponse),
     ChainIdentifier(String),
     DynamicFieldQuery(DynamicFieldPage),
+    EnvDiff(String, String, Vec<SuiEnvFieldDiff>),
     Envs(Vec<SuiEnv>, Option<String>),
     ExecuteSignedTx(SuiTransactionBlockResponse),
     Gas(Vec<GasCoin>),
+    InspectTx(InspectTxOutput),
     MergeCoin(SuiTransactionBlockResponse),
     NewAddress(NewAddressOutput),
     NewEnv(SuiEnv),
@@ -1951,6 +2272,151 @@ pub enum SuiClientCommandResult {
     VerifySource,
 }
 
+/// A decoded transaction, and the outcome of executing it, if it was executed.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectTxOutput {
+    pub digest: TransactionDigest,
+    pub data: TransactionData,
+    /// Base64 encoded signatures the transaction came with, if any.
+    pub signatures: Vec<String>,
+    pub effects: Option<SuiTransactionBlockEffects>,
+    pub balance_changes: Option<Vec<BalanceChange>>,
+}
+
+impl InspectTxOutput {
+    fn new(
+        data: TransactionData,
+        signatures: &[GenericSignature],
+        response: Option<SuiTransactionBlockResponse>,
+    ) -> Self {
+        let (effects, balance_changes) = match response {
+            Some(response) => (response.effects, response.balance_changes),
+            None => (None, None),
+        };
+        Self {
+            digest: TransactionDigest::new(default_hash(&data)),
+            signatures: signatures
+                .iter()
+                .map(|sig| Base64::encode(sig.as_ref()))
+                .collect(),
+            data,
+            effects,
+            balance_changes,
+        }
+    }
+}
+
+impl Display for InspectTxOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let gas = self.data.gas_data();
+        let mut builder = TableBuilder::default();
+        builder.push_record(vec!["digest".to_string(), self.digest.to_string()]);
+        builder.push_record(vec!["sender".to_string(), self.data.sender().to_string()]);
+        builder.push_record(vec![
+            "kind".to_string(),
+            self.data.kind().name().to_string(),
+        ]);
+        builder.push_record(vec!["gasOwner".to_string(), gas.owner.to_string()]);
+        builder.push_record(vec!["gasPrice".to_string(), gas.price.to_string()]);
+        builder.push_record(vec!["gasBudget".to_string(), gas.budget.to_string()]);
+        builder.push_record(vec![
+            "gasPayment".to_string(),
+            gas.payment
+                .iter()
+                .map(|(id, version, digest)| {
+                    format!("{id} (version {}, {digest})", version.value())
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ]);
+        builder.push_record(vec![
+            "expiration".to_string(),
+            match self.data.expiration() {
+                TransactionExpiration::None => "None".to_string(),
+                TransactionExpiration::Epoch(epoch) => format!("Epoch {epoch}"),
+            },
+        ]);
+        builder.push_record(vec![
+            "signatures".to_string(),
+            if self.signatures.is_empty() {
+                "None".to_string()
+            } else {
+                self.signatures.join("\n")
+            },
+        ]);
+        let mut table = builder.build();
+        table.with(TableStyle::rounded());
+        writeln!(f, "{}", table)?;
+
+        match self.data.kind() {
+            TransactionKind::ProgrammableTransaction(pt) => {
+                let mut builder = TableBuilder::default();
+                builder.set_header(vec!["input", "kind", "value"]);
+                for (i, input) in pt.inputs.iter().enumerate() {
+                    let (kind, value) = match input {
+                        CallArg::Pure(bytes) => ("Pure", format!("0x{}", Hex::encode(bytes))),
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject((id, version, digest))) => (
+                            "ImmOrOwnedObject",
+                            format!("{id} (version {}, {digest})", version.value()),
+                        ),
+                        CallArg::Object(ObjectArg::SharedObject {
+                            id,
+                            initial_shared_version,
+                            mutable,
+                        }) => (
+                            "SharedObject",
+                            format!(
+                                "{id} (initial version {}, {})",
+                                initial_shared_version.value(),
+                                if *mutable { "mutable" } else { "immutable" }
+                            ),
+                        ),
+                        CallArg::Object(ObjectArg::Receiving((id, version, digest))) => (
+                            "Receiving",
+                            format!("{id} (version {}, {digest})", version.value()),
+                        ),
+                    };
+                    builder.push_record(vec![i.to_string(), kind.to_string(), value]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                writeln!(f, "{}", table)?;
+
+                let mut builder = TableBuilder::default();
+                builder.set_header(vec!["command", ""]);
+                for (i, command) in pt.commands.iter().enumerate() {
+                    builder.push_record(vec![i.to_string(), command.to_string()]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                writeln!(f, "{}", table)?;
+            }
+            kind => write!(f, "{}", kind)?,
+        }
+
+        if let Some(effects) = &self.effects {
+            writeln!(f, "{}", effects)?;
+        }
+
+        if let Some(balance_changes) = &self.balance_changes {
+            let mut builder = TableBuilder::default();
+            builder.set_header(vec!["owner", "coinType", "amount"]);
+            for change in balance_changes {
+                builder.push_record(vec![
+                    change.owner.to_string(),
+                    change.coin_type.to_string(),
+                    change.amount.to_string(),
+                ]);
+            }
+            let mut table = builder.build();
+            table.with(TableStyle::rounded());
+            writeln!(f, "{}", table)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct SwitchResponse {
     /// Active address
@@ -21,19 +21,21 @@ use rand::Rng;
 use rand::SeedableRng;
 use rusoto_core::Region;
 use rusoto_kms::{Kms, KmsClient, SignRequest};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use shared_crypto::intent::{Intent, IntentMessage};
 use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
 use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_sdk::SuiClientBuilder;
 use sui_types::base_types::SuiAddress;
 use sui_types::committee::EpochId;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
@@ -42,7 +44,7 @@ use sui_types::error::SuiResult;
 use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
 use sui_types::multisig_legacy::{MultiSigLegacy, MultiSigPublicKeyLegacy};
 use sui_types::signature::{AuthenticatorTrait, GenericSignature, VerifyParams};
-use sui_types::transaction::TransactionData;
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
 use sui_types::zk_login_authenticator::ZkLoginAuthenticator;
 use tabled::builder::Builder;
 use tabled::settings::Rotate;
@@ -105,6 +107,13 @@ pub enum KeyToolCommand {
     /// (Base64 encoded `privkey`). This prints out the account keypair as Base64 encoded `flag || privkey`,
     /// the network keypair, worker keypair, protocol keypair as Base64 encoded `privkey`.
     LoadKeypair { file: PathBuf },
+    /// Scripted offline signing ceremony for a MultiSig address: describe the address in a file,
+    /// have each member sign a transaction into a partial signature file, combine the partial
+    /// signatures, and submit the transaction.
+    MultiSig {
+        #[clap(subcommand)]
+        cmd: MultiSigCommand,
+    },
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
     /// See `keytool list` for example public keys.
     MultiSigAddress {
@@ -224,6 +233,74 @@ pub enum KeyToolCommand {
     },
 }
 
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum MultiSigCommand {
+    /// Create a MultiSig address from the public keys `flag || pk` in Base64 and weights of its
+    /// members, and write its description to `output`, for the other steps of the ceremony.
+    Create {
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        #[clap(long, num_args(1..))]
+        pks: Vec<PublicKey>,
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        output: PathBuf,
+    },
+    /// Sign the Base64 encoded transaction bytes with the key in sui.keystore for `address`,
+    /// which must be a member of the MultiSig described in `multisig`, and write the partial
+    /// signature `flag || sig || pk` in Base64 to `output`.
+    Sign {
+        #[clap(long)]
+        multisig: PathBuf,
+        #[clap(long, value_parser = decode_bytes_hex::<SuiAddress>)]
+        address: SuiAddress,
+        #[clap(long)]
+        tx_bytes: String,
+        #[clap(long)]
+        output: PathBuf,
+    },
+    /// Combine the partial signatures in the `sigs` files, in any order, into a MultiSig
+    /// signature for the MultiSig described in `multisig`. If tx_bytes is passed in, the MultiSig
+    /// signature is verified against it.
+    Combine {
+        #[clap(long)]
+        multisig: PathBuf,
+        #[clap(long, num_args(1..))]
+        sigs: Vec<PathBuf>,
+        #[clap(long)]
+        tx_bytes: Option<String>,
+    },
+    /// Execute the transaction in the Base64 encoded transaction bytes, signed by the serialized
+    /// MultiSig signature output by `combine`, through the fullnode at `rpc`.
+    Submit {
+        #[clap(long)]
+        tx_bytes: String,
+        #[clap(long)]
+        multisig_sig: String,
+        #[clap(long)]
+        rpc: String,
+    },
+}
+
+/// Description of a MultiSig address, as written by `multi-sig create` and read by the other
+/// steps of the ceremony.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigFile {
+    multisig_address: SuiAddress,
+    threshold: ThresholdUnit,
+    members: Vec<MultiSigMember>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigMember {
+    public_base64_key: String,
+    weight: WeightUnit,
+}
+
 // Command Output types
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -286,6 +363,20 @@ pub struct MultiSigCombinePartialSigLegacyOutput {
     multisig_legacy_serialized: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigPartialSig {
+    multisig_address: SuiAddress,
+    signer: SuiAddress,
+    sig_file: PathBuf,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigSubmit {
+    tx_digest: String,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MultiSigOutput {
@@ -359,6 +450,8 @@ pub enum CommandOutput {
     MultiSigAddress(MultiSigAddress),
     MultiSigCombinePartialSig(MultiSigCombinePartialSig),
     MultiSigCombinePartialSigLegacy(MultiSigCombinePartialSigLegacyOutput),
+    MultiSigPartialSig(MultiSigPartialSig),
+    MultiSigSubmit(MultiSigSubmit),
     PrivateKeyBase64(PrivateKeyBase64),
     Show(Key),
     Sign(SignData),
@@ -545,6 +638,8 @@ impl KeyToolCommand {
                 CommandOutput::LoadKeypair(output)
             }
 
+            KeyToolCommand::MultiSig { cmd } => cmd.execute(keystore).await?,
+
             KeyToolCommand::MultiSigAddress {
                 threshold,
                 pks,
@@ -892,6 +987,199 @@ impl KeyToolCommand {
     }
 }
 
+impl MultiSigCommand {
+    pub async fn execute(self, keystore: &mut Keystore) -> Result<CommandOutput, anyhow::Error> {
+        Ok(match self {
+            MultiSigCommand::Create {
+                threshold,
+                pks,
+                weights,
+                output,
+            } => {
+                let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), threshold)?;
+                let address: SuiAddress = (&multisig_pk).into();
+                let file = MultiSigFile {
+                    multisig_address: address,
+                    threshold,
+                    members: pks
+                        .iter()
+                        .zip(weights.iter())
+                        .map(|(pk, w)| MultiSigMember {
+                            public_base64_key: pk.encode_base64(),
+                            weight: *w,
+                        })
+                        .collect(),
+                };
+                fs::write(&output, serde_json::to_string_pretty(&file)?)?;
+
+                CommandOutput::MultiSigAddress(MultiSigAddress {
+                    multisig_address: address.to_string(),
+                    multisig: pks
+                        .into_iter()
+                        .zip(weights)
+                        .map(|(pk, w)| MultiSigOutput {
+                            address: Into::<SuiAddress>::into(&pk),
+                            public_base64_key: pk.encode_base64(),
+                            weight: w,
+                        })
+                        .collect(),
+                })
+            }
+
+            MultiSigCommand::Sign {
+                multisig,
+                address,
+                tx_bytes,
+                output,
+            } => {
+                let (multisig_address, multisig_pk) = read_multisig_file(&multisig)?;
+                let pk = keystore.get_key(&address)?.public();
+                if multisig_pk.get_index(&pk).is_none() {
+                    return Err(anyhow!(
+                        "{address} is not a member of MultiSig address {multisig_address}"
+                    ));
+                }
+
+                let tx_data = decode_tx_data(&tx_bytes)?;
+                if tx_data.sender() != multisig_address {
+                    return Err(anyhow!(
+                        "Transaction is sent by {}, not by MultiSig address {multisig_address}",
+                        tx_data.sender()
+                    ));
+                }
+
+                let sig = keystore.sign_secure(&address, &tx_data, Intent::sui_transaction())?;
+                fs::write(&output, sig.encode_base64())?;
+                CommandOutput::MultiSigPartialSig(MultiSigPartialSig {
+                    multisig_address,
+                    signer: address,
+                    sig_file: output,
+                })
+            }
+
+            MultiSigCommand::Combine {
+                multisig,
+                sigs,
+                tx_bytes,
+            } => {
+                let (address, multisig_pk) = read_multisig_file(&multisig)?;
+
+                let mut indexed_sigs = vec![];
+                for path in sigs {
+                    let content = fs::read_to_string(&path)
+                        .map_err(|e| anyhow!("Cannot read partial signature {path:?}: {e}"))?;
+                    let sig = Signature::from_str(content.trim())
+                        .map_err(|e| anyhow!("Invalid partial signature in {path:?}: {e}"))?;
+                    let index = multisig_pk
+                        .get_index(&sig.to_public_key()?)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Partial signature in {path:?} is not from a member of {address}"
+                            )
+                        })?;
+                    indexed_sigs.push((index, sig));
+                }
+
+                // Signatures must be combined in the order that their signers appear in the
+                // MultiSig public key.
+                indexed_sigs.sort_by_key(|(index, _)| *index);
+                let weight: u16 = indexed_sigs
+                    .iter()
+                    .map(|(index, _)| multisig_pk.pubkeys()[*index as usize].1 as u16)
+                    .sum();
+                if weight < *multisig_pk.threshold() {
+                    return Err(anyhow!(
+                        "Partial signatures have a combined weight of {weight}, below the threshold of {}",
+                        multisig_pk.threshold()
+                    ));
+                }
+
+                let sigs = indexed_sigs.into_iter().map(|(_, sig)| sig).collect();
+                let multisig = MultiSig::combine(sigs, multisig_pk)?;
+                let generic_sig: GenericSignature = multisig.into();
+
+                if let Some(tx_bytes) = tx_bytes {
+                    let tx_data = decode_tx_data(&tx_bytes)?;
+                    generic_sig.verify_authenticator(
+                        &IntentMessage::new(Intent::sui_transaction(), tx_data),
+                        address,
+                        None,
+                        &VerifyParams::default(),
+                    )?;
+                }
+
+                let multisig_serialized = generic_sig.encode_base64();
+                CommandOutput::MultiSigCombinePartialSig(MultiSigCombinePartialSig {
+                    multisig_address: address,
+                    multisig_parsed: generic_sig,
+                    multisig_serialized,
+                })
+            }
+
+            MultiSigCommand::Submit {
+                tx_bytes,
+                multisig_sig,
+                rpc,
+            } => {
+                let tx_data = decode_tx_data(&tx_bytes)?;
+                let sig = GenericSignature::decode_base64(&multisig_sig)
+                    .map_err(|e| anyhow!("Invalid MultiSig signature: {e}"))?;
+                let client = SuiClientBuilder::default().build(rpc).await?;
+                let response = client
+                    .quorum_driver_api()
+                    .execute_transaction_block(
+                        Transaction::from_generic_sig_data(
+                            tx_data,
+                            Intent::sui_transaction(),
+                            vec![sig],
+                        ),
+                        SuiTransactionBlockResponseOptions::full_content(),
+                        None,
+                    )
+                    .await?;
+                CommandOutput::MultiSigSubmit(MultiSigSubmit {
+                    tx_digest: response.digest.base58_encode(),
+                })
+            }
+        })
+    }
+}
+
+/// Reads the description of a MultiSig address written by `multi-sig create`, checking that it
+/// describes the address it claims to.
+fn read_multisig_file(path: &Path) -> Result<(SuiAddress, MultiSigPublicKey), anyhow::Error> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Cannot read MultiSig description {path:?}: {e}"))?;
+    let file: MultiSigFile = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Invalid MultiSig description {path:?}: {e}"))?;
+
+    let mut pks = vec![];
+    let mut weights = vec![];
+    for member in file.members {
+        pks.push(
+            PublicKey::decode_base64(&member.public_base64_key)
+                .map_err(|e| anyhow!("Invalid public key {}: {e}", member.public_base64_key))?,
+        );
+        weights.push(member.weight);
+    }
+    let multisig_pk = MultiSigPublicKey::new(pks, weights, file.threshold)?;
+
+    let address: SuiAddress = (&multisig_pk).into();
+    if address != file.multisig_address {
+        return Err(anyhow!(
+            "MultiSig description {path:?} is for {address}, not {}",
+            file.multisig_address
+        ));
+    }
+    Ok((address, multisig_pk))
+}
+
+fn decode_tx_data(tx_bytes: &str) -> Result<TransactionData, anyhow::Error> {
+    let tx_bytes =
+        Base64::decode(tx_bytes).map_err(|e| anyhow!("Invalid base64 tx bytes: {:?}", e))?;
+    Ok(bcs::from_bytes(&tx_bytes)?)
+}
+
 impl From<&SuiKeyPair> for Key {
     fn from(skp: &SuiKeyPair) -> Self {
         Key::from(skp.public())
@@ -546,6 +546,7 @@ async fn genesis(
         alias: "localnet".to_string(),
         rpc: format!("http://{}", fullnode_config.json_rpc_address),
         ws: None,
+        ..Default::default()
     });
     client_config.add_env(SuiEnv::devnet());
 
@@ -570,6 +571,7 @@ async fn prompt_if_no_config(
                 alias: "custom".to_string(),
                 rpc: v.into_string().unwrap(),
                 ws: None,
+                ..Default::default()
             }),
             None => {
                 if accept_defaults {
@@ -605,6 +607,7 @@ async fn prompt_if_no_config(
                             alias,
                             rpc: url,
                             ws: None,
+                            ..Default::default()
                         }
                     })
                 } else {
@@ -7,7 +7,9 @@ use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
 
 use super::write_keypair_to_file;
+use super::CommandOutput;
 use super::KeyToolCommand;
+use super::MultiSigCommand;
 use anyhow::Ok;
 use fastcrypto::encoding::Base64;
 use fastcrypto::encoding::Encoding;
@@ -494,3 +496,130 @@ async fn test_sign_command() -> Result<(), anyhow::Error> {
     .await?;
     Ok(())
 }
+
+#[test]
+async fn test_multi_sig_ceremony() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(3));
+    let members = keystore.addresses();
+    let pks = keystore.keys();
+    let dir = tempfile::tempdir()?;
+    let multisig = dir.path().join("multisig.json");
+
+    // Create a 2-of-3 MultiSig address.
+    let output = MultiSigCommand::Create {
+        threshold: 2,
+        pks: pks.clone(),
+        weights: vec![1, 1, 1],
+        output: multisig.clone(),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let CommandOutput::MultiSigAddress(output) = output else {
+        panic!("Unexpected output")
+    };
+    let multisig_address = SuiAddress::from_str(&output.multisig_address)?;
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui(
+        multisig_address,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        1,
+    )
+    .unwrap();
+    let tx_bytes = Base64::encode(bcs::to_bytes(&tx_data)?);
+
+    // The last and first members sign, out of order.
+    let mut sigs = vec![];
+    for member in [members[2], members[0]] {
+        let sig = dir.path().join(format!("{member}.sig"));
+        MultiSigCommand::Sign {
+            multisig: multisig.clone(),
+            address: member,
+            tx_bytes: tx_bytes.clone(),
+            output: sig.clone(),
+        }
+        .execute(&mut keystore)
+        .await?;
+        sigs.push(sig);
+    }
+
+    // One partial signature is not enough.
+    assert!(MultiSigCommand::Combine {
+        multisig: multisig.clone(),
+        sigs: sigs[..1].to_vec(),
+        tx_bytes: None,
+    }
+    .execute(&mut keystore)
+    .await
+    .is_err());
+
+    // Two are, and the combined signature verifies against the transaction.
+    let output = MultiSigCommand::Combine {
+        multisig: multisig.clone(),
+        sigs,
+        tx_bytes: Some(tx_bytes),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let CommandOutput::MultiSigCombinePartialSig(output) = output else {
+        panic!("Unexpected output")
+    };
+    assert_eq!(output.multisig_address, multisig_address);
+    Ok(())
+}
+
+#[test]
+async fn test_multi_sig_sign_rejects_non_members() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(2));
+    let addresses = keystore.addresses();
+    let dir = tempfile::tempdir()?;
+    let multisig = dir.path().join("multisig.json");
+
+    // Only the first key is a member of the MultiSig address.
+    let output = MultiSigCommand::Create {
+        threshold: 1,
+        pks: vec![keystore.get_key(&addresses[0])?.public()],
+        weights: vec![1],
+        output: multisig.clone(),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let CommandOutput::MultiSigAddress(output) = output else {
+        panic!("Unexpected output")
+    };
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui(
+        SuiAddress::from_str(&output.multisig_address)?,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        1,
+    )
+    .unwrap();
+
+    assert!(MultiSigCommand::Sign {
+        multisig,
+        address: addresses[1],
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        output: dir.path().join("sig"),
+    }
+    .execute(&mut keystore)
+    .await
+    .is_err());
+    Ok(())
+}
@@ -6,6 +6,7 @@ use std::os::unix::prelude::FileExt;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_package::BuildConfig as MoveBuildConfig;
 use serde_json::json;
 use sui_test_transaction_builder::batch_make_transfer_transactions;
@@ -43,7 +44,11 @@ use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
-use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
+use sui_types::{
+    base_types::ObjectID,
+    crypto::get_key_pair,
+    gas_coin::{GasCoin, GAS},
+};
 use test_cluster::TestClusterBuilder;
 
 const TEST_DATA_DIR: &str = "tests/data/";
@@ -346,6 +351,36 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_balance_command() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let gas_coins = context.gas_objects(address).await?;
+    let total: u64 = gas_coins.iter().map(|(value, _)| value).sum();
+
+    let result = SuiClientCommands::Balance {
+        address: Some(address),
+        usd_prices: vec![(GAS::type_tag(), 2.0)],
+    }
+    .execute(context)
+    .await?;
+    result.print(true);
+
+    let SuiClientCommandResult::Balance(balances) = result else {
+        panic!("Expected a balance summary, got {result:?}");
+    };
+    assert_eq!(balances.len(), 1);
+    let sui = &balances[0];
+    assert_eq!(sui.symbol.as_deref(), Some("SUI"));
+    assert_eq!(sui.total_balance, total as u128);
+    assert_eq!(sui.coin_object_count, gas_coins.len());
+    assert_eq!(sui.estimated_usd, Some(total as f64 / 1e9 * 2.0));
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -1851,6 +1886,43 @@ async fn test_execute_signed_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_inspect_tx() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let context = &mut test_cluster.wallet;
+    let mut txns = batch_make_transfer_transactions(context, 1).await;
+    let txn = txns.swap_remove(0);
+    let digest = *txn.digest();
+
+    // Before it is executed, the transaction can only be inspected from its bytes.
+    let resp = SuiClientCommands::InspectTx {
+        tx: Base64::encode(bcs::to_bytes(txn.data())?),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::InspectTx(output) = resp else {
+        panic!("Expected an InspectTx result")
+    };
+    assert_eq!(output.digest, digest);
+    assert_eq!(output.signatures.len(), 1);
+    assert!(output.effects.is_none());
+
+    context.execute_transaction_must_succeed(txn).await;
+
+    let resp = SuiClientCommands::InspectTx {
+        tx: digest.to_string(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::InspectTx(output) = resp else {
+        panic!("Expected an InspectTx result")
+    };
+    assert_eq!(output.digest, digest);
+    assert!(output.effects.unwrap().status().is_ok());
+    assert!(!output.balance_changes.unwrap().is_empty());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
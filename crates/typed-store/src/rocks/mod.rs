@@ -268,6 +268,14 @@ impl RocksDB {
         delegate_call!(self.property_int_value_cf(cf, name))
     }
 
+    pub fn property_value_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: impl CStrLike,
+    ) -> Result<Option<String>, rocksdb::Error> {
+        delegate_call!(self.property_value_cf(cf, name))
+    }
+
     pub fn get_pinned_cf_opt<K: AsRef<[u8]>>(
         &self,
         cf: &impl AsColumnFamilyRef,
@@ -660,6 +668,27 @@ impl MetricConf {
 const CF_METRICS_REPORT_PERIOD_MILLIS: u64 = 1000;
 const METRICS_ERROR: i64 = -1;
 
+/// Size and shape of a column family's LSM tree, as reported by RocksDB.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnFamilyStats {
+    pub name: String,
+    pub total_sst_files_size: u64,
+    pub estimated_live_data_size: u64,
+    pub estimated_num_keys: u64,
+    /// Number of SST files in each level, starting from L0.
+    pub files_per_level: Vec<u64>,
+}
+
+impl ColumnFamilyStats {
+    /// Upper bound on the number of SST files a point lookup may have to read: every L0 file can
+    /// overlap the key, but each deeper level contributes at most one file.
+    pub fn estimated_read_amplification(&self) -> u64 {
+        let mut levels = self.files_per_level.iter();
+        let l0 = levels.next().copied().unwrap_or(0);
+        l0 + levels.filter(|files| **files > 0).count() as u64
+    }
+}
+
 /// An interface to a rocksDB database, keyed by a columnfamily
 #[derive(Clone, Debug)]
 pub struct DBMap<K, V> {
@@ -823,12 +852,51 @@ impl<K, V> DBMap<K, V> {
         Ok(())
     }
 
+    /// Compacts the whole column family down to its bottommost level.
+    pub fn compact_to_bottom(&self) {
+        self.rocksdb
+            .compact_range_to_bottom(&self.cf(), None::<Vec<u8>>, None);
+    }
+
     pub fn cf(&self) -> Arc<rocksdb::BoundColumnFamily<'_>> {
         self.rocksdb
             .cf_handle(&self.cf)
             .expect("Map-keying column family should have been checked at DB creation")
     }
 
+    pub fn column_family_stats(&self) -> Result<ColumnFamilyStats, TypedStoreError> {
+        let cf = self.cf();
+        let int_property = |name: &'static std::ffi::CStr| {
+            self.rocksdb
+                .property_int_value_cf(&cf, name)
+                .map(Option::unwrap_or_default)
+                .map_err(|e| TypedStoreError::RocksDBError(e.into_string()))
+        };
+
+        // RocksDB stops answering once the level is past the number of levels the column family
+        // was configured with.
+        let mut files_per_level = vec![];
+        loop {
+            let property = format!("rocksdb.num-files-at-level{}", files_per_level.len());
+            let files = self
+                .rocksdb
+                .property_value_cf(&cf, property.as_str())
+                .map_err(|e| TypedStoreError::RocksDBError(e.into_string()))?;
+            match files.and_then(|files| files.trim().parse().ok()) {
+                Some(files) => files_per_level.push(files),
+                None => break,
+            }
+        }
+
+        Ok(ColumnFamilyStats {
+            name: self.cf.clone(),
+            total_sst_files_size: int_property(properties::TOTAL_SST_FILES_SIZE)?,
+            estimated_live_data_size: int_property(properties::ESTIMATE_LIVE_DATA_SIZE)?,
+            estimated_num_keys: int_property(properties::ESTIMATE_NUM_KEYS)?,
+            files_per_level,
+        })
+    }
+
     pub fn iterator_cf(&self) -> RocksDBIter<'_> {
         self.rocksdb
             .iterator_cf(&self.cf(), self.opts.readopts(), IteratorMode::Start)
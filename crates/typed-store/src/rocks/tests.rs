@@ -1234,6 +1234,37 @@ async fn refcount_with_compaction_test() {
     assert!(is_ref_count_value(&value));
 }
 
+#[tokio::test]
+async fn test_column_family_stats() {
+    let db = DBMap::<i32, String>::open(
+        temp_dir(),
+        MetricConf::default(),
+        None,
+        None,
+        &ReadWriteOptions::default(),
+    )
+    .expect("failed to open rocksdb");
+
+    // Each flush leaves a new file in L0.
+    for i in 0..2 {
+        db.multi_insert((i * 100..(i + 1) * 100).map(|k| (k, k.to_string())))
+            .unwrap();
+        db.flush().unwrap();
+    }
+
+    let stats = db.column_family_stats().unwrap();
+    assert_eq!(stats.files_per_level[0], 2);
+    assert_eq!(stats.estimated_read_amplification(), 2);
+    assert!(stats.total_sst_files_size > 0);
+
+    db.compact_to_bottom();
+    let stats = db.column_family_stats().unwrap();
+    assert_eq!(stats.files_per_level[0], 0);
+    assert_eq!(stats.files_per_level.iter().sum::<u64>(), 1);
+    assert_eq!(stats.estimated_read_amplification(), 1);
+    assert_eq!(stats.estimated_num_keys, 200);
+}
+
 fn open_map<P: AsRef<Path>, K, V>(
     path: P,
     opt_cf: Option<&str>,
@@ -22,6 +22,7 @@ pub mod loop_summary_tests;
 pub mod many_back_edges;
 pub mod multi_pass_tests;
 pub mod negative_stack_size_tests;
+pub mod package_visibility_tests;
 pub mod reference_safety_tests;
 pub mod signature_tests;
 pub mod struct_defs_tests;
@@ -52,5 +53,6 @@ pub(crate) fn production_config() -> VerifierConfig {
         max_per_mod_meter_units: Some(1000 * 8000),
         max_constant_vector_len: Some(DEFAULT_MAX_CONSTANT_VECTOR_LEN),
         max_idenfitier_len: Some(DEFAULT_MAX_IDENTIFIER_LENGTH),
+        enforce_package_visibility: false,
     }
 }
@@ -0,0 +1,204 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::*;
+use move_bytecode_verifier::{dependencies, friends, package_visibility};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    metadata::{Metadata, PACKAGE_VISIBILITY_METADATA_KEY},
+    vm_status::StatusCode,
+};
+use move_vm_config::verifier::VerifierConfig;
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+/// `basic_test_module`, with `foo` as a friend function.
+fn friend_foo_module() -> CompiledModule {
+    let mut m = basic_test_module();
+    m.function_defs[0].visibility = Visibility::Friend;
+    m
+}
+
+fn status(m: &CompiledModule) -> StatusCode {
+    package_visibility::verify_module(m)
+        .unwrap_err()
+        .major_status()
+}
+
+#[test]
+fn no_metadata() {
+    package_visibility::verify_module(&basic_test_module()).unwrap();
+}
+
+#[test]
+fn package_visible_friend_function() {
+    let mut m = friend_foo_module();
+    m.metadata
+        .push(Metadata::package_visibility(&[ident("foo")]));
+    package_visibility::verify_module(&m).unwrap();
+    assert!(package_visibility::package_visible_functions(&m).contains(&ident("foo")));
+}
+
+#[test]
+fn package_visible_function_not_friend() {
+    let mut m = basic_test_module();
+    m.metadata
+        .push(Metadata::package_visibility(&[ident("foo")]));
+    assert_eq!(status(&m), StatusCode::LOOKUP_FAILED);
+}
+
+#[test]
+fn package_visible_function_undefined() {
+    let mut m = friend_foo_module();
+    m.metadata
+        .push(Metadata::package_visibility(&[ident("bar")]));
+    assert_eq!(status(&m), StatusCode::LOOKUP_FAILED);
+}
+
+#[test]
+fn package_visible_function_duplicated() {
+    let mut m = friend_foo_module();
+    m.metadata
+        .push(Metadata::package_visibility(&[ident("foo"), ident("foo")]));
+    assert_eq!(status(&m), StatusCode::DUPLICATE_ELEMENT);
+
+    let mut m = friend_foo_module();
+    m.metadata
+        .push(Metadata::package_visibility(&[ident("foo")]));
+    m.metadata.push(Metadata::package_visibility(&[]));
+    assert_eq!(status(&m), StatusCode::DUPLICATE_ELEMENT);
+}
+
+#[test]
+fn malformed_metadata() {
+    let mut m = friend_foo_module();
+    m.metadata.push(Metadata {
+        key: PACKAGE_VISIBILITY_METADATA_KEY.to_vec(),
+        value: vec![0xff],
+    });
+    assert_eq!(status(&m), StatusCode::MALFORMED);
+    assert!(package_visibility::package_visible_functions(&m).is_empty());
+}
+
+/// `friend_foo_module`, with `foo` listed as `public(package)`, and `friends` declared as its
+/// friends.
+fn package_foo_module(friends: &[(AccountAddress, &str)]) -> CompiledModule {
+    let mut m = friend_foo_module();
+    m.metadata
+        .push(Metadata::package_visibility(&[ident("foo")]));
+    for (address, name) in friends {
+        m.friend_decls.push(ModuleHandle {
+            address: AddressIdentifierIndex(m.address_identifiers.len() as TableIndex),
+            name: IdentifierIndex(m.identifiers.len() as TableIndex),
+        });
+        m.address_identifiers.push(*address);
+        m.identifiers.push(ident(name));
+    }
+    m
+}
+
+/// A module named `Caller` at `address`, calling `foo` in the module `basic_test_module` defines.
+fn calling_module(address: AccountAddress) -> CompiledModule {
+    let mut m = empty_module();
+    m.identifiers = vec![
+        ident("Caller"),
+        ident("call_foo"),
+        self_module_name().to_owned(),
+        ident("foo"),
+    ];
+    m.address_identifiers = vec![address, AccountAddress::ZERO];
+    m.module_handles.push(ModuleHandle {
+        address: AddressIdentifierIndex(1),
+        name: IdentifierIndex(2),
+    });
+    m.function_handles = vec![
+        FunctionHandle {
+            module: ModuleHandleIndex(0),
+            name: IdentifierIndex(1),
+            parameters: SignatureIndex(0),
+            return_: SignatureIndex(0),
+            type_parameters: vec![],
+        },
+        FunctionHandle {
+            module: ModuleHandleIndex(1),
+            name: IdentifierIndex(3),
+            parameters: SignatureIndex(0),
+            return_: SignatureIndex(0),
+            type_parameters: vec![],
+        },
+    ];
+    m.function_defs.push(FunctionDefinition {
+        function: FunctionHandleIndex(0),
+        visibility: Visibility::Public,
+        is_entry: false,
+        acquires_global_resources: vec![],
+        code: Some(CodeUnit {
+            locals: SignatureIndex(0),
+            code: vec![Bytecode::Call(FunctionHandleIndex(1)), Bytecode::Ret],
+        }),
+    });
+    m
+}
+
+#[test]
+fn same_package_call() {
+    let callee = package_foo_module(&[(AccountAddress::ZERO, "Caller")]);
+    friends::verify_module(&callee).unwrap();
+    package_visibility::verify_module(&callee).unwrap();
+
+    let caller = calling_module(AccountAddress::ZERO);
+    dependencies::verify_module(&caller, &[callee]).unwrap();
+}
+
+#[test]
+fn cross_package_call() {
+    let other_package = AccountAddress::from_hex_literal("0x2").unwrap();
+
+    // A caller in another package cannot call the function without being a friend...
+    let callee = package_foo_module(&[]);
+    let caller = calling_module(other_package);
+    assert_eq!(
+        dependencies::verify_module(&caller, &[callee])
+            .unwrap_err()
+            .major_status(),
+        StatusCode::LOOKUP_FAILED,
+    );
+
+    // ...and cannot be declared as one.
+    let callee = package_foo_module(&[(other_package, "Caller")]);
+    assert_eq!(
+        friends::verify_module(&callee).unwrap_err().major_status(),
+        StatusCode::INVALID_FRIEND_DECL_WITH_MODULES_OUTSIDE_ACCOUNT_ADDRESS,
+    );
+}
+
+#[test]
+fn cross_address_friend_call() {
+    let enforced = VerifierConfig {
+        enforce_package_visibility: true,
+        ..VerifierConfig::default()
+    };
+    let other_package = AccountAddress::from_hex_literal("0x2").unwrap();
+    // The dependency check does not rely on `friends` rejecting friends at other addresses.
+    let callee = package_foo_module(&[(other_package, "Caller")]);
+    let caller = calling_module(other_package);
+    dependencies::verify_module(&caller, [&callee]).unwrap();
+    assert_eq!(
+        dependencies::verify_module_with_config(&enforced, &caller, [&callee])
+            .unwrap_err()
+            .major_status(),
+        StatusCode::LOOKUP_FAILED,
+    );
+
+    // Friend functions that are not listed in the metadata can still be called.
+    let mut callee = callee;
+    callee.metadata.clear();
+    dependencies::verify_module_with_config(&enforced, &caller, [&callee]).unwrap();
+
+    let callee = package_foo_module(&[(AccountAddress::ZERO, "Caller")]);
+    let caller = calling_module(AccountAddress::ZERO);
+    dependencies::verify_module_with_config(&enforced, &caller, [&callee]).unwrap();
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! This module contains verification of usage of dependencies for modules and scripts.
+use crate::package_visibility;
 use move_binary_format::{
     access::{ModuleAccess, ScriptAccess},
     binary_views::BinaryIndexedView,
@@ -16,6 +17,7 @@ use move_binary_format::{
     safe_unwrap, IndexKind,
 };
 use move_core_types::{identifier::Identifier, language_storage::ModuleId, vm_status::StatusCode};
+use move_vm_config::verifier::VerifierConfig;
 use std::collections::{BTreeMap, BTreeSet};
 
 struct Context<'a, 'b> {
@@ -36,20 +38,23 @@ struct Context<'a, 'b> {
 
 impl<'a, 'b> Context<'a, 'b> {
     fn module(
+        config: &VerifierConfig,
         module: &'a CompiledModule,
         dependencies: impl IntoIterator<Item = &'b CompiledModule>,
     ) -> Self {
-        Self::new(BinaryIndexedView::Module(module), dependencies)
+        Self::new(config, BinaryIndexedView::Module(module), dependencies)
     }
 
     fn script(
+        config: &VerifierConfig,
         script: &'a CompiledScript,
         dependencies: impl IntoIterator<Item = &'b CompiledModule>,
     ) -> Self {
-        Self::new(BinaryIndexedView::Script(script), dependencies)
+        Self::new(config, BinaryIndexedView::Script(script), dependencies)
     }
 
     fn new(
+        config: &VerifierConfig,
         resolver: BinaryIndexedView<'a>,
         dependencies: impl IntoIterator<Item = &'b CompiledModule>,
     ) -> Self {
@@ -83,6 +88,11 @@ impl<'a, 'b> Context<'a, 'b> {
         let mut dependency_visibilities = BTreeMap::new();
         for (module_id, module) in &context.dependency_map {
             let friend_module_ids: BTreeSet<_> = module.immediate_friends().into_iter().collect();
+            let package_functions = if config.enforce_package_visibility {
+                package_visibility::package_visible_functions(module)
+            } else {
+                BTreeSet::new()
+            };
 
             // Module::StructName -> def handle idx
             for struct_def in module.struct_defs() {
@@ -103,6 +113,15 @@ impl<'a, 'b> Context<'a, 'b> {
                 );
                 let may_be_called = match func_def.visibility {
                     Visibility::Public => true,
+                    // `public(package)` functions can only be called by friends at the same
+                    // address. `friends` only rejects friends at other addresses as a matter of
+                    // policy, so this keeps them in their package even if that were lifted.
+                    Visibility::Friend if package_functions.contains(func_name) => {
+                        self_module.as_ref().map_or(false, |self_id| {
+                            self_id.address() == module_id.address()
+                                && friend_module_ids.contains(self_id)
+                        })
+                    }
                     Visibility::Friend => self_module
                         .as_ref()
                         .map_or(false, |self_id| friend_module_ids.contains(self_id)),
@@ -164,15 +183,24 @@ pub fn verify_module<'a>(
     module: &CompiledModule,
     dependencies: impl IntoIterator<Item = &'a CompiledModule>,
 ) -> VMResult<()> {
-    verify_module_impl(module, dependencies)
+    verify_module_with_config(&VerifierConfig::default(), module, dependencies)
+}
+
+pub fn verify_module_with_config<'a>(
+    config: &VerifierConfig,
+    module: &CompiledModule,
+    dependencies: impl IntoIterator<Item = &'a CompiledModule>,
+) -> VMResult<()> {
+    verify_module_impl(config, module, dependencies)
         .map_err(|e| e.finish(Location::Module(module.self_id())))
 }
 
 fn verify_module_impl<'a>(
+    config: &VerifierConfig,
     module: &CompiledModule,
     dependencies: impl IntoIterator<Item = &'a CompiledModule>,
 ) -> PartialVMResult<()> {
-    let context = &Context::module(module, dependencies);
+    let context = &Context::module(config, module, dependencies);
 
     verify_imported_modules(context)?;
     verify_imported_structs(context)?;
@@ -191,7 +219,7 @@ pub fn verify_script_impl<'a>(
     script: &CompiledScript,
     dependencies: impl IntoIterator<Item = &'a CompiledModule>,
 ) -> PartialVMResult<()> {
-    let context = &Context::script(script, dependencies);
+    let context = &Context::script(&VerifierConfig::default(), script, dependencies);
 
     verify_imported_modules(context)?;
     verify_imported_structs(context)?;
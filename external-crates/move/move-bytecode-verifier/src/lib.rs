@@ -21,6 +21,7 @@ pub mod instantiation_loops;
 pub mod instruction_consistency;
 pub mod limits;
 pub mod loop_summary;
+pub mod package_visibility;
 pub mod script_signature;
 pub mod signature;
 pub mod struct_defs;
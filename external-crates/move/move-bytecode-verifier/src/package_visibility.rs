@@ -0,0 +1,74 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains verification of the package visibility metadata of modules, which lists
+//! the functions declared `public(package)`. In bytecode, these are `friend` functions, and when
+//! package visibility is enforced, the metadata is what keeps them from being called outside of
+//! their address through a friend declaration (see `dependencies`). This pass checks that
+//! - a module has at most one such metadata entry, and it decodes
+//! - it lists every function at most once
+//! - every function it lists is defined in the module, with `friend` visibility
+use move_binary_format::{
+    access::ModuleAccess,
+    errors::{Location, PartialVMError, PartialVMResult, VMResult},
+    file_format::{CompiledModule, Visibility},
+};
+use move_core_types::{identifier::Identifier, vm_status::StatusCode};
+use std::collections::BTreeSet;
+
+pub fn verify_module(module: &CompiledModule) -> VMResult<()> {
+    verify_module_impl(module).map_err(|e| e.finish(Location::Module(module.self_id())))
+}
+
+fn verify_module_impl(module: &CompiledModule) -> PartialVMResult<()> {
+    let mut entries = module
+        .metadata
+        .iter()
+        .filter_map(|metadata| metadata.as_package_visibility());
+    let Some(functions) = entries.next() else {
+        return Ok(());
+    };
+    if entries.next().is_some() {
+        return Err(PartialVMError::new(StatusCode::DUPLICATE_ELEMENT)
+            .with_message("More than one package visibility metadata entry".to_string()));
+    }
+
+    let functions = functions.map_err(|e| {
+        PartialVMError::new(StatusCode::MALFORMED)
+            .with_message(format!("Malformed package visibility metadata: {e}"))
+    })?;
+    let mut seen = BTreeSet::new();
+    for function in functions {
+        let is_friend_function = module.function_defs().iter().any(|def| {
+            def.visibility == Visibility::Friend
+                && module.identifier_at(module.function_handle_at(def.function).name)
+                    == function.as_ident_str()
+        });
+        if !is_friend_function {
+            return Err(
+                PartialVMError::new(StatusCode::LOOKUP_FAILED).with_message(format!(
+                    "Package visible function {function} is not a friend function of the module"
+                )),
+            );
+        }
+        if !seen.insert(function.clone()) {
+            return Err(
+                PartialVMError::new(StatusCode::DUPLICATE_ELEMENT).with_message(format!(
+                    "Package visible function {function} is listed more than once"
+                )),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Names of the functions that `module` declares `public(package)`. Metadata that does not decode
+/// is ignored: modules published before package visibility was enforced may have anything in it.
+pub fn package_visible_functions(module: &CompiledModule) -> BTreeSet<Identifier> {
+    module
+        .metadata
+        .iter()
+        .filter_map(|metadata| metadata.as_package_visibility())
+        .flat_map(|functions| functions.unwrap_or_default())
+        .collect()
+}
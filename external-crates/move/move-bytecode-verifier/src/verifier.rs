@@ -8,7 +8,7 @@ use crate::{
     ability_field_requirements, check_duplication::DuplicationChecker,
     code_unit_verifier::CodeUnitVerifier, constants, friends,
     instantiation_loops::InstantiationLoopChecker, instruction_consistency::InstructionConsistency,
-    limits::LimitsVerifier, script_signature,
+    limits::LimitsVerifier, package_visibility, script_signature,
     script_signature::no_additional_script_signature_checks, signature::SignatureChecker,
     struct_defs::RecursiveStructDefChecker,
 };
@@ -82,6 +82,9 @@ pub fn verify_module_with_config_metered(
     InstructionConsistency::verify_module(module)?;
     constants::verify_module(module)?;
     friends::verify_module(module)?;
+    if config.enforce_package_visibility {
+        package_visibility::verify_module(module)?;
+    }
     ability_field_requirements::verify_module(module)?;
     RecursiveStructDefChecker::verify_module(module)?;
     InstantiationLoopChecker::verify_module(module)?;
@@ -0,0 +1,71 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collects the `abort`s reachable in a function's control flow graph, along with the codes they
+//! abort with, so that abort codes seen on-chain can be traced back to their source.
+
+use crate::{
+    cfgir::ast::{Constant, Function, FunctionBody_},
+    compiled_unit::{AbortCode, AbortSite},
+    hlir::ast::{Command_, Exp, UnannotatedExp_, Value_},
+    parser::ast::ConstantName,
+    shared::unique_map::UniqueMap,
+};
+use move_core_types::value::MoveValue;
+use move_ir_types::location::*;
+use std::collections::BTreeSet;
+
+/// Returns the abort sites reachable from the entry of `fdef`, ordered by their position in the
+/// source. Native functions have no abort sites.
+pub fn function(constants: &UniqueMap<ConstantName, Constant>, fdef: &Function) -> Vec<AbortSite> {
+    let FunctionBody_::Defined { start, blocks, .. } = &fdef.body.value else {
+        return vec![];
+    };
+
+    let mut sites = vec![];
+    let mut visited = BTreeSet::new();
+    let mut worklist = vec![*start];
+    while let Some(label) = worklist.pop() {
+        if !visited.insert(label) {
+            continue;
+        }
+        let Some(block) = blocks.get(&label) else {
+            continue;
+        };
+        for sp!(loc, cmd_) in block {
+            match cmd_ {
+                Command_::Abort(code) => sites.push(AbortSite {
+                    loc: *loc,
+                    code: abort_code(constants, code),
+                }),
+                Command_::Jump { target, .. } => worklist.push(*target),
+                Command_::JumpIf {
+                    if_true, if_false, ..
+                } => {
+                    worklist.push(*if_true);
+                    worklist.push(*if_false);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    sites.sort_by_key(|site| site.loc.start());
+    sites
+}
+
+fn abort_code(constants: &UniqueMap<ConstantName, Constant>, e: &Exp) -> AbortCode {
+    match &e.exp.value {
+        UnannotatedExp_::Constant(name) => AbortCode::Constant {
+            name: name.0.value,
+            value: constants
+                .get(name)
+                .and_then(|constant| match constant.value {
+                    Some(MoveValue::U64(value)) => Some(value),
+                    _ => None,
+                }),
+        },
+        UnannotatedExp_::Value(sp!(_, Value_::U64(value))) => AbortCode::Value(*value),
+        _ => AbortCode::Unknown,
+    }
+}
@@ -2,6 +2,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod abort_sites;
 pub mod absint;
 pub mod ast;
 mod borrows;
@@ -28,6 +28,7 @@ use move_command_line_common::files::{
 };
 use move_core_types::language_storage::ModuleId as CompiledModuleId;
 use move_symbol_pool::Symbol;
+use move_vm_config::verifier::VerifierConfig;
 use std::{
     collections::{BTreeMap, BTreeSet},
     fs,
@@ -65,6 +66,7 @@ pub struct Compiler<'a> {
     known_warning_filters: BTreeSet<KnownFiltersInfo>,
     package_configs: BTreeMap<Symbol, PackageConfig>,
     default_config: Option<PackageConfig>,
+    verifier_config: Option<VerifierConfig>,
 }
 
 pub struct SteppedCompiler<'a, const P: Pass> {
@@ -172,6 +174,7 @@ impl<'a> Compiler<'a> {
             known_warning_filters: BTreeSet::new(),
             package_configs,
             default_config: None,
+            verifier_config: None,
         })
     }
 
@@ -269,6 +272,14 @@ impl<'a> Compiler<'a> {
         self
     }
 
+    /// Warns about modules that come close to the limits in `config`, which the bytecode verifier
+    /// enforces when they are published.
+    pub fn set_verifier_config(mut self, config: VerifierConfig) -> Self {
+        assert!(self.verifier_config.is_none());
+        self.verifier_config = Some(config);
+        self
+    }
+
     pub fn run<const TARGET: Pass>(
         self,
     ) -> anyhow::Result<(
@@ -288,6 +299,7 @@ impl<'a> Compiler<'a> {
             known_warning_filters,
             package_configs,
             default_config,
+            verifier_config,
         } = self;
         generate_interface_files_for_deps(
             &mut deps,
@@ -299,6 +311,9 @@ impl<'a> Compiler<'a> {
         if let Some(filter) = warning_filter {
             compilation_env.add_warning_filter_scope(filter);
         }
+        if let Some(config) = verifier_config {
+            compilation_env.set_verifier_config(config);
+        }
         for KnownFiltersInfo {
             filter_attr_name,
             filters,
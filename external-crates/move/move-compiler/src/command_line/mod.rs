@@ -28,6 +28,8 @@ pub const VERIFY_SHORT: char = 'v';
 
 pub const BYTECODE_VERSION: &str = "bytecode-version";
 
+pub const LINT: &str = "lint";
+
 pub const COLOR_MODE_ENV_VAR: &str = "COLOR_MODE";
 
 pub const MOVE_COMPILED_INTERFACES_DIR: &str = "mv_interfaces";
@@ -37,11 +37,30 @@ pub struct SpecInfo {
     pub used_locals: UniqueMap<H::Var, VarInfo>,
 }
 
+/// The code an `abort` aborts with, as far as it is known at compile time.
+#[derive(Debug, Clone)]
+pub enum AbortCode {
+    /// A named constant, along with its value if it is a `u64`.
+    Constant { name: Symbol, value: Option<u64> },
+    /// A literal value.
+    Value(u64),
+    /// A value that is only known at runtime.
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct AbortSite {
+    pub loc: Loc,
+    pub code: AbortCode,
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
     pub spec_info: BTreeMap<SpecId, SpecInfo>,
     pub parameters: Vec<(H::Var, VarInfo)>,
     pub attributes: Attributes,
+    // Aborts reachable in the function body, not including those in functions it calls
+    pub abort_sites: Vec<AbortSite>,
 }
 
 #[derive(Debug, Clone)]
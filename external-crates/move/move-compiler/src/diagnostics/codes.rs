@@ -205,6 +205,8 @@ codes!(
         InvalidVisibilityModifier:
             { msg: "invalid visibility modifier", severity: NonblockingError },
         InvalidUseFun: { msg: "invalid 'use fun' declaration", severity: NonblockingError },
+        ShadowedVariable:
+            { msg: "local shadows a local from an enclosing scope", severity: Warning },
     ],
     // errors name resolution, mostly expansion/translate and naming/translate
     NameResolution: [
@@ -252,6 +254,7 @@ codes!(
             severity: Warning
         },
         InvalidMethodCall: { msg: "invalid method call", severity: BlockingError },
+        ConstantAssertion: { msg: "assertion condition is constant", severity: Warning },
     ],
     // errors for ability rules. mostly typing/translate
     AbilitySafety: [
@@ -315,7 +318,11 @@ codes!(
             msg: "feature is not supported in specified edition",
             severity: BlockingError,
         },
-    ]
+    ],
+    // warnings about modules that come close to the bytecode verifier's limits on publish
+    VerifierLimits: [
+        CloseToLimit: { msg: "close to bytecode verifier limit", severity: Warning },
+    ],
 );
 
 //**************************************************************************************************
@@ -104,11 +104,14 @@ pub struct Constant {
 // Functions
 //**************************************************************************************************
 
-// package visibility is removed after typing is done
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Visibility {
     Public(Loc),
     Friend(Loc),
+    // Typing adds the modules calling the function as friends, so in bytecode, this is a friend
+    // function. When the protocol enforces package visibility, it is also listed in the package
+    // visibility metadata of its module.
+    Package(Loc),
     Internal,
 }
 
@@ -401,11 +404,14 @@ impl Var {
 impl Visibility {
     pub const FRIEND: &'static str = P::Visibility::FRIEND;
     pub const INTERNAL: &'static str = P::Visibility::INTERNAL;
+    pub const PACKAGE: &'static str = P::Visibility::PACKAGE;
     pub const PUBLIC: &'static str = P::Visibility::PUBLIC;
 
     pub fn loc(&self) -> Option<Loc> {
         match self {
-            Visibility::Friend(loc) | Visibility::Public(loc) => Some(*loc),
+            Visibility::Friend(loc) | Visibility::Package(loc) | Visibility::Public(loc) => {
+                Some(*loc)
+            }
             Visibility::Internal => None,
         }
     }
@@ -763,6 +769,7 @@ impl std::fmt::Display for Visibility {
             match &self {
                 Visibility::Public(_) => Visibility::PUBLIC,
                 Visibility::Friend(_) => Visibility::FRIEND,
+                Visibility::Package(_) => Visibility::PACKAGE,
                 Visibility::Internal => Visibility::INTERNAL,
             }
         )
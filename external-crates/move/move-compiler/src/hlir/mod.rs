@@ -3,4 +3,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ast;
+mod optimize;
 pub(crate) mod translate;
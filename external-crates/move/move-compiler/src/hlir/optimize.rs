@@ -0,0 +1,592 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cleans up the temporaries introduced while translating to HLIR, before the body is lowered to a
+//! CFG. Most temporaries hold the value of a sub-expression for the statement right after them, so
+//! that sub-expressions are evaluated in order. Those holding expressions without effects, that do
+//! not read locals, are substituted back into the statement when that keeps the order of
+//! evaluation, the same expressions `cfgir::optimize::eliminate_locals` substitutes. Temporaries
+//! that are never read are dropped.
+
+use crate::{hlir::ast::*, shared::unique_map::UniqueMap};
+use move_ir_types::location::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub fn function_body(locals: &mut UniqueMap<Var, SingleType>, body: &mut Block) {
+    let (single_use, unused) = {
+        let mut counts = count::Context::default();
+        count::block(&mut counts, body);
+        counts.finish(locals)
+    };
+    if single_use.is_empty() && unused.is_empty() {
+        return;
+    }
+
+    let mut context = Context {
+        single_use,
+        unused,
+        removed: BTreeSet::new(),
+    };
+    block(&mut context, body);
+    for v in &context.removed {
+        locals.remove(v);
+    }
+}
+
+struct Context {
+    /// Temporaries assigned once, and moved once, that can be substituted for their use if the
+    /// expression assigned to them has no effects.
+    single_use: BTreeSet<Var>,
+    /// Temporaries with the `drop` ability that are assigned, but never used.
+    unused: BTreeSet<Var>,
+    /// Temporaries that no longer appear in the body.
+    removed: BTreeSet<Var>,
+}
+
+//**************************************************************************************************
+// Count assignment and usage
+//**************************************************************************************************
+
+mod count {
+    use crate::{
+        hlir::{ast::*, translate::is_temp_name},
+        parser::ast::Ability_,
+        shared::unique_map::UniqueMap,
+    };
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[derive(Default)]
+    pub struct Context {
+        assigned: BTreeMap<Var, usize>,
+        moved: BTreeMap<Var, usize>,
+        /// Locals that are copied, borrowed, or used in specs.
+        otherwise_used: BTreeSet<Var>,
+    }
+
+    impl Context {
+        /// Temporaries that are assigned and moved once, and temporaries with the `drop` ability
+        /// that are assigned but never used.
+        pub fn finish(self, locals: &UniqueMap<Var, SingleType>) -> (BTreeSet<Var>, BTreeSet<Var>) {
+            let Context {
+                assigned,
+                moved,
+                otherwise_used,
+            } = self;
+            let mut single_use = BTreeSet::new();
+            let mut unused = BTreeSet::new();
+            for (v, n) in assigned {
+                if !is_temp_name(v.value()) || otherwise_used.contains(&v) {
+                    continue;
+                }
+                let Some(ty) = locals.get(&v) else {
+                    continue;
+                };
+                match moved.get(&v) {
+                    // Temporaries holding references are left for the borrow checker to see.
+                    Some(1) if n == 1 && matches!(ty.value, SingleType_::Base(_)) => {
+                        single_use.insert(v);
+                    }
+                    None if ty.value.abilities(ty.loc).has_ability_(Ability_::Drop) => {
+                        unused.insert(v);
+                    }
+                    _ => (),
+                }
+            }
+            (single_use, unused)
+        }
+    }
+
+    pub fn block(context: &mut Context, block: &Block) {
+        block.iter().for_each(|s| statement(context, s))
+    }
+
+    fn statement(context: &mut Context, sp!(_, stmt_): &Statement) {
+        use Statement_ as S;
+        match stmt_ {
+            S::Command(cmd) => command(context, cmd),
+            S::IfElse {
+                cond,
+                if_block,
+                else_block,
+            } => {
+                exp(context, cond);
+                block(context, if_block);
+                block(context, else_block)
+            }
+            S::While {
+                cond: (cond_block, cond),
+                block: body,
+            } => {
+                block(context, cond_block);
+                exp(context, cond);
+                block(context, body)
+            }
+            S::Loop { block: body, .. } => block(context, body),
+        }
+    }
+
+    fn command(context: &mut Context, sp!(_, cmd_): &Command) {
+        use Command_ as C;
+        match cmd_ {
+            C::Assign(ls, e) => {
+                exp(context, e);
+                ls.iter().for_each(|l| lvalue(context, l))
+            }
+            C::Mutate(el, er) => {
+                exp(context, er);
+                exp(context, el)
+            }
+            C::Abort(e)
+            | C::Return { exp: e, .. }
+            | C::IgnoreAndPop { exp: e, .. }
+            | C::JumpIf { cond: e, .. } => exp(context, e),
+            C::Break | C::Continue | C::Jump { .. } => (),
+        }
+    }
+
+    fn lvalue(context: &mut Context, sp!(_, l_): &LValue) {
+        use LValue_ as L;
+        match l_ {
+            L::Ignore => (),
+            L::Var(v, _) => *context.assigned.entry(*v).or_default() += 1,
+            L::Unpack(_, _, fields) => fields.iter().for_each(|(_, l)| lvalue(context, l)),
+        }
+    }
+
+    fn exp(context: &mut Context, e: &Exp) {
+        use UnannotatedExp_ as E;
+        match &e.exp.value {
+            E::Unit { .. } | E::Value(_) | E::Constant(_) => (),
+            E::Unreachable | E::UnresolvedError => (),
+
+            E::Move { var, .. } => *context.moved.entry(*var).or_default() += 1,
+            E::Copy { var, .. } | E::BorrowLocal(_, var) => {
+                context.otherwise_used.insert(*var);
+            }
+            E::Spec(_, used_locals) => context.otherwise_used.extend(used_locals.keys()),
+
+            E::ModuleCall(mcall) => mcall.arguments.iter().for_each(|e| exp(context, e)),
+            E::Builtin(_, es) | E::Vector(_, _, _, es) | E::Multiple(es) => {
+                es.iter().for_each(|e| exp(context, e))
+            }
+            E::Freeze(e)
+            | E::Dereference(e)
+            | E::UnaryExp(_, e)
+            | E::Borrow(_, e, _)
+            | E::Cast(e, _) => exp(context, e),
+            E::BinopExp(e1, _, e2) => {
+                exp(context, e1);
+                exp(context, e2)
+            }
+            E::Pack(_, _, fields) => fields.iter().for_each(|(_, _, e)| exp(context, e)),
+        }
+    }
+}
+
+//**************************************************************************************************
+// Coalesce temporaries and remove dead stores
+//**************************************************************************************************
+
+fn block(context: &mut Context, block: &mut Block) {
+    for mut stmt in std::mem::take(block) {
+        nested_blocks(context, &mut stmt);
+        if dead_store(context, &mut stmt) && is_no_op(&stmt) {
+            continue;
+        }
+        block.push_back(stmt);
+    }
+
+    let mut i = 0;
+    while i < block.len() {
+        let n = coalesce(context, block, i);
+        // The statement now evaluates the expressions that were substituted into it first, and
+        // those may start by moving the temporaries assigned right before them.
+        i = if n == 0 { i + 1 } else { i - n };
+    }
+}
+
+fn nested_blocks(context: &mut Context, sp!(_, stmt_): &mut Statement) {
+    use Statement_ as S;
+    match stmt_ {
+        S::Command(_) => (),
+        S::IfElse {
+            if_block,
+            else_block,
+            ..
+        } => {
+            block(context, if_block);
+            block(context, else_block)
+        }
+        S::While {
+            cond: (cond_block, _),
+            block: body,
+        } => {
+            block(context, cond_block);
+            block(context, body)
+        }
+        S::Loop { block: body, .. } => block(context, body),
+    }
+}
+
+/// Stops assigning to temporaries that are never used, returning whether it did.
+fn dead_store(context: &mut Context, sp!(_, stmt_): &mut Statement) -> bool {
+    let Statement_::Command(sp!(_, Command_::Assign(ls, _))) = stmt_ else {
+        return false;
+    };
+    ls.iter_mut()
+        .fold(false, |changed, l| dead_lvalue(context, l) || changed)
+}
+
+fn dead_lvalue(context: &mut Context, sp!(_, l_): &mut LValue) -> bool {
+    match l_ {
+        LValue_::Ignore => false,
+        LValue_::Var(v, _) if context.unused.contains(v) => {
+            context.removed.insert(*v);
+            *l_ = LValue_::Ignore;
+            true
+        }
+        LValue_::Var(_, _) => false,
+        LValue_::Unpack(_, _, fields) => fields
+            .iter_mut()
+            .fold(false, |changed, (_, l)| dead_lvalue(context, l) || changed),
+    }
+}
+
+/// Whether `stmt` assigns a value that has no effects to nothing, so it can be removed.
+fn is_no_op(sp!(_, stmt_): &Statement) -> bool {
+    let Statement_::Command(sp!(_, Command_::Assign(ls, e))) = stmt_ else {
+        return false;
+    };
+    !ls.is_empty() && ls.iter().all(|l| matches!(l.value, LValue_::Ignore)) && is_value(e)
+}
+
+fn is_value(e: &Exp) -> bool {
+    use UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Unit { .. } | E::Value(_) | E::Constant(_) => true,
+        E::Multiple(es) => es.iter().all(is_value),
+        _ => false,
+    }
+}
+
+/// Substitutes the temporaries assigned right before `block[i]` into it, if it moves them first
+/// and in the order they were assigned, and removes their assignments. Returns how many were
+/// substituted.
+fn coalesce(context: &mut Context, block: &mut Block, i: usize) -> usize {
+    // The temporaries assigned by the statements right before this one.
+    let mut temps = vec![];
+    for stmt in block.range(..i).rev() {
+        match single_use_assignment(context, stmt) {
+            Some(v) => temps.push(v),
+            None => break,
+        }
+    }
+    temps.reverse();
+    if temps.is_empty() {
+        return 0;
+    }
+
+    let mut moves = vec![];
+    leading_moves(&block[i], &mut moves);
+
+    // Substitute the longest run of temporaries, ending right before this statement, that this
+    // statement starts by moving, so that their expressions are still evaluated in the same order,
+    // and before anything else in this statement.
+    let n = (1..=temps.len().min(moves.len()))
+        .rev()
+        .find(|&n| temps[temps.len() - n..] == moves[..n])
+        .unwrap_or(0);
+    if n == 0 {
+        return 0;
+    }
+
+    let mut substitutions = BTreeMap::new();
+    for stmt in block.drain(i - n..i) {
+        let sp!(_, Statement_::Command(sp!(_, Command_::Assign(mut ls, e)))) = stmt else {
+            panic!("ICE temporary assignment expected")
+        };
+        let Some(sp!(_, LValue_::Var(v, _))) = ls.pop() else {
+            panic!("ICE temporary assignment expected")
+        };
+        context.removed.insert(v);
+        substitutions.insert(v, e);
+    }
+    substitute_statement(&mut substitutions, &mut block[i - n]);
+    assert!(
+        substitutions.is_empty(),
+        "ICE temporary substitution failed"
+    );
+    n
+}
+
+/// The temporary that `stmt` assigns, if it only assigns an expression without effects to a
+/// single-use temporary.
+fn single_use_assignment(context: &Context, sp!(_, stmt_): &Statement) -> Option<Var> {
+    let Statement_::Command(sp!(_, Command_::Assign(ls, e))) = stmt_ else {
+        return None;
+    };
+    let [sp!(_, LValue_::Var(v, _))] = &ls[..] else {
+        return None;
+    };
+    (context.single_use.contains(v) && can_subst_exp(e)).then_some(*v)
+}
+
+/// Whether `e` can be substituted for the temporary it is assigned to: it does not read or borrow
+/// locals, call functions, or read from references. Matches `can_subst_exp_single` in
+/// `cfgir::optimize::eliminate_locals`, except that unreachable code is not substituted.
+fn can_subst_exp(e: &Exp) -> bool {
+    use UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::UnresolvedError
+        | E::Spec(_, _)
+        | E::BorrowLocal(_, _)
+        | E::Copy { .. }
+        | E::Builtin(_, _)
+        | E::Freeze(_)
+        | E::Dereference(_)
+        | E::ModuleCall(_)
+        | E::Move { .. }
+        | E::Borrow(_, _, _)
+        | E::Unreachable => false,
+
+        E::Unit { .. } | E::Value(_) | E::Constant(_) => true,
+
+        E::Cast(e, _) => can_subst_exp(e),
+        E::UnaryExp(op, e) => op.value.is_pure() && can_subst_exp(e),
+        E::BinopExp(e1, op, e2) => op.value.is_pure() && can_subst_exp(e1) && can_subst_exp(e2),
+        E::Multiple(es) | E::Vector(_, _, _, es) => es.iter().all(can_subst_exp),
+        E::Pack(_, _, fields) => fields.iter().all(|(_, _, e)| can_subst_exp(e)),
+    }
+}
+
+//**************************************************************************************************
+// Evaluation order
+//**************************************************************************************************
+
+/// Collects the locals that `stmt` moves, in the order it evaluates them, before it does anything
+/// else with an effect, or that could observe an effect, other than loading values.
+fn leading_moves(sp!(_, stmt_): &Statement, moves: &mut Vec<Var>) {
+    use Command_ as C;
+    use Statement_ as S;
+    match stmt_ {
+        S::Command(sp!(_, cmd_)) => match cmd_ {
+            C::Assign(_, e)
+            | C::Abort(e)
+            | C::Return { exp: e, .. }
+            | C::IgnoreAndPop { exp: e, .. }
+            | C::JumpIf { cond: e, .. } => {
+                exp_leading_moves(e, moves);
+            }
+            C::Mutate(el, er) => {
+                let _ = exp_leading_moves(er, moves) && exp_leading_moves(el, moves);
+            }
+            C::Break | C::Continue | C::Jump { .. } => (),
+        },
+        S::IfElse { cond, .. } => {
+            exp_leading_moves(cond, moves);
+        }
+        // The condition of a loop is evaluated on every iteration.
+        S::While { .. } | S::Loop { .. } => (),
+    }
+}
+
+/// Collects the locals that `e` moves first, and returns whether it could be evaluated entirely
+/// without doing anything else.
+fn exp_leading_moves(e: &Exp, moves: &mut Vec<Var>) -> bool {
+    use UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Unit { .. } | E::Value(_) | E::Constant(_) => true,
+        E::Move { var, .. } => {
+            moves.push(*var);
+            true
+        }
+        E::Multiple(es) => es.iter().all(|e| exp_leading_moves(e, moves)),
+
+        // These evaluate their arguments first, then do something with them.
+        E::ModuleCall(mcall) => {
+            let _ = mcall.arguments.iter().all(|e| exp_leading_moves(e, moves));
+            false
+        }
+        E::Builtin(_, es) | E::Vector(_, _, _, es) => {
+            let _ = es.iter().all(|e| exp_leading_moves(e, moves));
+            false
+        }
+        E::Freeze(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::Cast(e, _) => {
+            let _ = exp_leading_moves(e, moves);
+            false
+        }
+        E::BinopExp(e1, _, e2) => {
+            let _ = exp_leading_moves(e1, moves) && exp_leading_moves(e2, moves);
+            false
+        }
+        E::Pack(_, _, fields) => {
+            let _ = fields.iter().all(|(_, _, e)| exp_leading_moves(e, moves));
+            false
+        }
+
+        E::Copy { .. }
+        | E::BorrowLocal(_, _)
+        | E::Spec(_, _)
+        | E::Unreachable
+        | E::UnresolvedError => false,
+    }
+}
+
+//**************************************************************************************************
+// Substitution
+//**************************************************************************************************
+
+fn substitute_statement(substitutions: &mut BTreeMap<Var, Exp>, sp!(_, stmt_): &mut Statement) {
+    use Command_ as C;
+    use Statement_ as S;
+    match stmt_ {
+        S::Command(sp!(_, cmd_)) => match cmd_ {
+            C::Assign(_, e)
+            | C::Abort(e)
+            | C::Return { exp: e, .. }
+            | C::IgnoreAndPop { exp: e, .. }
+            | C::JumpIf { cond: e, .. } => substitute(substitutions, e),
+            C::Mutate(el, er) => {
+                substitute(substitutions, er);
+                substitute(substitutions, el)
+            }
+            C::Break | C::Continue | C::Jump { .. } => (),
+        },
+        S::IfElse { cond, .. } => substitute(substitutions, cond),
+        S::While { .. } | S::Loop { .. } => (),
+    }
+}
+
+fn substitute(substitutions: &mut BTreeMap<Var, Exp>, e: &mut Exp) {
+    use UnannotatedExp_ as E;
+    if substitutions.is_empty() {
+        return;
+    }
+    match &mut e.exp.value {
+        E::Move { var, .. } => {
+            if let Some(replacement) = substitutions.remove(var) {
+                *e = replacement
+            }
+        }
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Constant(_)
+        | E::Copy { .. }
+        | E::BorrowLocal(_, _)
+        | E::Spec(_, _)
+        | E::Unreachable
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(mcall) => mcall
+            .arguments
+            .iter_mut()
+            .for_each(|e| substitute(substitutions, e)),
+        E::Builtin(_, es) | E::Vector(_, _, _, es) | E::Multiple(es) => {
+            es.iter_mut().for_each(|e| substitute(substitutions, e))
+        }
+        E::Freeze(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::Cast(e, _) => substitute(substitutions, e),
+        E::BinopExp(e1, _, e2) => {
+            substitute(substitutions, e1);
+            substitute(substitutions, e2)
+        }
+        E::Pack(_, _, fields) => fields
+            .iter_mut()
+            .for_each(|(_, _, e)| substitute(substitutions, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        command_line::compiler::{Compiler, PASS_HLIR},
+        editions::Edition,
+        hlir::ast::{FunctionBody_, Program},
+        shared::{NumericalAddress, PackageConfig, PackagePaths},
+    };
+    use move_symbol_pool::Symbol;
+    use std::collections::BTreeMap;
+
+    /// Compiles `source` in `edition` to HLIR, with `a` bound to 0x42.
+    fn compile(edition: Edition, source: &str) -> Program {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("m.move");
+        std::fs::write(&path, source).unwrap();
+        let package = PackagePaths {
+            name: Some((
+                "p".into(),
+                PackageConfig {
+                    edition,
+                    ..PackageConfig::default()
+                },
+            )),
+            paths: vec![path.to_string_lossy().to_string()],
+            named_address_map: BTreeMap::from([(
+                "a".to_string(),
+                NumericalAddress::parse_str("0x42").unwrap(),
+            )]),
+        };
+        let (_, res) = Compiler::from_package_paths(vec![package], vec![])
+            .unwrap()
+            .run::<PASS_HLIR>()
+            .unwrap();
+        let (_, stepped) = res.unwrap();
+        stepped.into_ast().1
+    }
+
+    /// The number of locals function `name` has in `program`, including its parameters.
+    fn num_locals(program: &Program, name: &str) -> usize {
+        let (_, _, module) = program.modules.iter().next().unwrap();
+        let function = module.functions.get_(&Symbol::from(name)).unwrap();
+        let FunctionBody_::Defined { locals, .. } = &function.body.value else {
+            panic!("{name} is not defined")
+        };
+        locals.len()
+    }
+
+    /// Compiles the same functions with `S` declared as `struct` in the legacy edition, and as
+    /// `public struct` in the 2024 edition, returning the number of locals of `name` in each.
+    fn num_locals_by_edition(functions: &str, name: &str) -> (usize, usize) {
+        let legacy = compile(
+            Edition::LEGACY,
+            &format!("module a::m {{ struct S has drop {{ a: u64, b: u64 }} {functions} }}"),
+        );
+        let e2024 = compile(
+            Edition::E2024_ALPHA,
+            &format!("module a::m {{ public struct S has drop {{ a: u64, b: u64 }} {functions} }}"),
+        );
+        (num_locals(&legacy, name), num_locals(&e2024, name))
+    }
+
+    #[test]
+    fn values_substituted() {
+        // Fields out of declaration order are bound to temporaries, to evaluate them in order.
+        let (legacy, e2024) = num_locals_by_edition("fun f(): S { S { b: 1 ^ 2, a: 3 } }", "f");
+        assert_eq!(legacy, 2);
+        assert_eq!(e2024, 0);
+    }
+
+    #[test]
+    fn effects_not_substituted() {
+        let (legacy, e2024) = num_locals_by_edition(
+            "fun g(): u64 { 1 } fun f(x: u64): S { S { b: g(), a: x } }",
+            "f",
+        );
+        // `x` is copied or moved, and `g` is called, so neither field is substituted.
+        assert_eq!(legacy, 3);
+        assert_eq!(e2024, 3);
+
+        let (legacy, e2024) =
+            num_locals_by_edition("fun g(): u64 { 1 } fun f(): S { S { b: g(), a: 2 } }", "f");
+        // Substituting `2` still calls `g` first.
+        assert_eq!(legacy, 2);
+        assert_eq!(e2024, 1);
+    }
+}
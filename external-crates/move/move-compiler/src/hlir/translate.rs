@@ -4,17 +4,20 @@
 
 use crate::{
     diag,
+    editions::Edition,
     expansion::ast::{self as E, AbilitySet, Fields, ModuleIdent},
-    hlir::ast::{self as H, Block, MoveOpAnnotation},
+    hlir::{
+        ast::{self as H, Block, MoveOpAnnotation},
+        optimize,
+    },
     naming::ast as N,
-    parser::ast::{BinOp_, ConstantName, Field, FunctionName, StructName},
+    parser::ast::{BinOp_, ConstantName, Field, FunctionName, StructName, UnaryOp_},
     shared::{ast_debug::AstDebug, unique_map::UniqueMap, *},
     typing::ast as T,
     FullyCompiledProgram,
 };
-use move_ir_types::location::*;
+use move_ir_types::{location::*, mangling};
 use move_symbol_pool::Symbol;
-use once_cell::sync::Lazy;
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     convert::TryInto,
@@ -24,7 +27,8 @@ use std::{
 // Vars
 //**************************************************************************************************
 
-const NEW_NAME_DELIM: &str = "#";
+// Names of locals follow the scheme in `move_ir_types::mangling`, which tools reading source maps
+// rely on to demangle them.
 
 fn translate_var(sp!(loc, v_): N::Var) -> H::Var {
     let N::Var_ {
@@ -32,29 +36,16 @@ fn translate_var(sp!(loc, v_): N::Var) -> H::Var {
         id: depth,
         color,
     } = v_;
-    let s = format!(
-        "{}{}{}{}{}",
-        name, NEW_NAME_DELIM, depth, NEW_NAME_DELIM, color
-    )
-    .into();
+    let s = mangling::mangle_local(&name, depth, color).into();
     H::Var(sp(loc, s))
 }
 
-const TEMP_PREFIX: &str = "%";
-static TEMP_PREFIX_SYMBOL: Lazy<Symbol> = Lazy::new(|| TEMP_PREFIX.into());
-
 fn new_temp_name(context: &mut Context) -> Symbol {
-    format!(
-        "{}{}{}",
-        *TEMP_PREFIX_SYMBOL,
-        NEW_NAME_DELIM,
-        context.counter_next()
-    )
-    .into()
+    mangling::mangle_temp(context.counter_next()).into()
 }
 
 pub fn is_temp_name(s: Symbol) -> bool {
-    s.starts_with(TEMP_PREFIX)
+    mangling::is_temp(&s)
 }
 
 pub enum DisplayVar {
@@ -63,14 +54,9 @@ pub enum DisplayVar {
 }
 
 pub fn display_var(s: Symbol) -> DisplayVar {
-    if is_temp_name(s) {
-        DisplayVar::Tmp
-    } else {
-        let mut orig = s.as_str().to_string();
-        if let Some(i) = orig.find(NEW_NAME_DELIM) {
-            orig.truncate(i)
-        }
-        DisplayVar::Orig(orig)
+    match mangling::demangle(&s).source_name() {
+        Some(orig) => DisplayVar::Orig(orig.to_string()),
+        None => DisplayVar::Tmp,
     }
 }
 
@@ -83,6 +69,8 @@ struct Context<'env> {
     structs: UniqueMap<ModuleIdent, UniqueMap<StructName, UniqueMap<Field, usize>>>,
     function_locals: UniqueMap<H::Var, H::SingleType>,
     signature: Option<H::FunctionSignature>,
+    /// The package of the module or script being translated.
+    current_package: Option<Symbol>,
     tmp_counter: usize,
     /// collects all struct fields used in the current module
     pub used_fields: BTreeMap<Symbol, BTreeSet<Symbol>>,
@@ -129,6 +117,7 @@ impl<'env> Context<'env> {
             structs,
             function_locals: UniqueMap::new(),
             signature: None,
+            current_package: None,
             tmp_counter: 0,
             used_fields: BTreeMap::new(),
         }
@@ -227,6 +216,7 @@ fn module(
         spec_dependencies: _,
     } = mdef;
     context.env.add_warning_filter_scope(warning_filter.clone());
+    context.current_package = package_name;
     let structs = tstructs.map(|name, s| struct_def(context, name, s));
 
     let constants = tconstants.map(|name, c| constant(context, name, c));
@@ -275,6 +265,7 @@ fn script(context: &mut Context, tscript: T::Script) -> H::Script {
         spec_dependencies: _,
     } = tscript;
     context.env.add_warning_filter_scope(warning_filter.clone());
+    context.current_package = package_name;
     let constants = tconstants.map(|name, c| constant(context, name, c));
     let function = function(context, function_name, tfunction);
     context.env.pop_warning_filter_scope();
@@ -385,8 +376,13 @@ fn function_body_defined(
             body.push_back(sp(eloc, S::Command(ret)))
         }
     }
-    let locals = context.extract_function_locals();
+    let mut locals = context.extract_function_locals();
     check_trailing_unit(context, &mut body);
+    // Changing the bytecode generated for existing packages would stop their source from
+    // verifying against the bytecode that was published, so this is only done in new editions.
+    if context.env.package_config(context.current_package).edition != Edition::LEGACY {
+        optimize::function_body(&mut locals, &mut body);
+    }
     context.signature = None;
     (locals, body)
 }
@@ -395,8 +391,7 @@ fn visibility(evisibility: E::Visibility) -> H::Visibility {
     match evisibility {
         E::Visibility::Internal => H::Visibility::Internal,
         E::Visibility::Friend(loc) => H::Visibility::Friend(loc),
-        // We added any friends we needed during typing, so we convert this over.
-        E::Visibility::Package(loc) => H::Visibility::Friend(loc),
+        E::Visibility::Package(loc) => H::Visibility::Package(loc),
         E::Visibility::Public(loc) => H::Visibility::Public(loc),
     }
 }
@@ -1012,6 +1007,7 @@ fn exp_(
             TE::Builtin(bt, arguments)
                 if matches!(&*bt, sp!(_, T::BuiltinFunction_::Assert(false))) =>
             {
+                check_constant_assertion(stack.context, &arguments);
                 let tbool = N::Type_::bool(loc);
                 let tu64 = N::Type_::u64(loc);
                 let tunit = sp(loc, N::Type_::Unit);
@@ -1069,6 +1065,7 @@ fn exp_(
             TE::Builtin(bt, arguments)
                 if matches!(&*bt, sp!(_, T::BuiltinFunction_::Assert(true))) =>
             {
+                check_constant_assertion(stack.context, &arguments);
                 use T::ExpListItem as TI;
                 let tunit = sp(loc, N::Type_::Unit);
                 let [cond_item, code_item]: [TI; 2] = match arguments.exp.value {
@@ -1116,6 +1113,60 @@ fn exp_(
     e_res
 }
 
+/// When linting, warns if the condition of an assertion with `arguments` is a constant, in which
+/// case the assertion either always aborts or never does.
+fn check_constant_assertion(context: &mut Context, arguments: &T::Exp) {
+    use T::UnannotatedExp_ as TE;
+    if !context.env.flags().lint() {
+        return;
+    }
+    let TE::ExpList(items) = &arguments.exp.value else {
+        return;
+    };
+    let Some(T::ExpListItem::Single(econd, _)) = items.first() else {
+        return;
+    };
+    let Some(value) = constant_bool(econd) else {
+        return;
+    };
+    let msg = if value {
+        "Assertion always passes. Its condition is always 'true'"
+    } else {
+        "Assertion always fails. Its condition is always 'false'"
+    };
+    context
+        .env
+        .add_diag(diag!(TypeSafety::ConstantAssertion, (econd.exp.loc, msg)));
+}
+
+/// The value of `e` if it is a boolean literal, or a negation, conjunction, disjunction or
+/// comparison of them.
+fn constant_bool(e: &T::Exp) -> Option<bool> {
+    use BinOp_ as B;
+    use T::UnannotatedExp_ as TE;
+    match &e.exp.value {
+        TE::Value(sp!(_, E::Value_::Bool(b))) => Some(*b),
+        TE::Annotate(e, _) => constant_bool(e),
+        TE::Block(seq) if seq.len() == 1 => match &seq.front()?.value {
+            T::SequenceItem_::Seq(e) => constant_bool(e),
+            _ => None,
+        },
+        TE::UnaryExp(sp!(_, UnaryOp_::Not), e) => constant_bool(e).map(|b| !b),
+        TE::BinopExp(lhs, sp!(_, op), _, rhs) => {
+            let lhs = constant_bool(lhs);
+            let rhs = constant_bool(rhs);
+            match (op, lhs, rhs) {
+                (B::And, Some(false), _) | (B::Or, Some(true), _) => lhs,
+                (B::And | B::Or, Some(_), rhs) => rhs,
+                (B::Eq, Some(l), Some(r)) => Some(l == r),
+                (B::Neq, Some(l), Some(r)) => Some(l != r),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 fn exp_list(
     context: &mut Context,
     result: &mut Block,
@@ -38,3 +38,6 @@ pub use command_line::{
 };
 pub use parser::comments::{CommentMap, FileCommentMap, MatchedFileCommentMap};
 pub use shared::Flags;
+
+/// Version of the compiler, recorded in the metadata of packages compiled with it.
+pub const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -4,5 +4,6 @@
 
 pub mod ast;
 pub(crate) mod fake_natives;
+mod recursive_structs;
 pub(crate) mod resolve_use_funs;
 pub(crate) mod translate;
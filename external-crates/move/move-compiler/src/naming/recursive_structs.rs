@@ -9,7 +9,6 @@ use crate::{
     naming::ast::{self as N, TypeName_},
     parser::ast::StructName,
     shared::{unique_map::UniqueMap, *},
-    typing::ast as T,
 };
 use move_ir_types::location::*;
 use petgraph::{algo::tarjan_scc as petgraph_scc, graphmap::DiGraphMap};
@@ -55,14 +54,14 @@ impl Context {
 
 pub fn modules(
     compilation_env: &mut CompilationEnv,
-    modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    modules: &UniqueMap<ModuleIdent, N::ModuleDefinition>,
 ) {
     modules
         .key_cloned_iter()
         .for_each(|(mname, m)| module(compilation_env, mname, m))
 }
 
-fn module(compilation_env: &mut CompilationEnv, mname: ModuleIdent, module: &T::ModuleDefinition) {
+fn module(compilation_env: &mut CompilationEnv, mname: ModuleIdent, module: &N::ModuleDefinition) {
     let context = &mut Context::new(mname);
     module
         .structs
@@ -67,7 +67,8 @@ struct Context<'env> {
     scoped_functions: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
     unscoped_constants: BTreeMap<Symbol, Loc>,
     scoped_constants: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
-    local_scopes: Vec<BTreeMap<Symbol, u16>>,
+    /// The locals in scope, by name, with their IDs and where they were declared.
+    local_scopes: Vec<BTreeMap<Symbol, (u16, Loc)>>,
     local_count: BTreeMap<Symbol, u16>,
     used_locals: BTreeSet<N::Var_>,
     /// Type parameters used in a function (they have to be cleared after processing each function).
@@ -398,21 +399,58 @@ impl<'env> Context<'env> {
     }
 
     fn declare_local(&mut self, is_parameter: bool, sp!(vloc, name): Name) -> N::Var {
+        if !is_parameter {
+            self.check_shadowing(vloc, name);
+        }
         let default = if is_parameter { 0 } else { 1 };
         let id = *self
             .local_count
             .entry(name)
             .and_modify(|c| *c += 1)
             .or_insert(default);
-        self.local_scopes.last_mut().unwrap().insert(name, id);
+        self.local_scopes
+            .last_mut()
+            .unwrap()
+            .insert(name, (id, vloc));
         // all locals start at color zero
         // they will be incremented when substituted for macros
         let nvar_ = N::Var_ { name, id, color: 0 };
         sp(vloc, nvar_)
     }
 
+    /// When linting, warns if a local named `name` declared at `loc` would shadow a local declared
+    /// in an enclosing scope. Redeclaring a local in the same scope, redeclaring a parameter, and
+    /// names starting with `_` are not reported.
+    fn check_shadowing(&mut self, loc: Loc, name: Symbol) {
+        if !self.env.flags().lint() || name.as_str().starts_with('_') {
+            return;
+        }
+        let [.., outer, current] = &self.local_scopes[..] else {
+            return;
+        };
+        let Some(&(id, prev_loc)) = current.get(&name) else {
+            return;
+        };
+        // Scopes start as a copy of their enclosing scope, so a local that is the same in both
+        // was declared outside of the current scope.
+        if id == 0 || outer.get(&name) != Some(&(id, prev_loc)) {
+            return;
+        }
+        let msg = format!("Local '{name}' shadows a local declared in an enclosing scope");
+        self.env.add_diag(diag!(
+            Declarations::ShadowedVariable,
+            (loc, msg),
+            (prev_loc, "Previously declared here"),
+        ));
+    }
+
     fn resolve_local(&mut self, loc: Loc, verb: &str, sp!(vloc, name): Name) -> Option<N::Var> {
-        let id_opt = self.local_scopes.last().unwrap().get(&name).copied();
+        let id_opt = self
+            .local_scopes
+            .last()
+            .unwrap()
+            .get(&name)
+            .map(|(id, _)| *id);
         match id_opt {
             None => {
                 let msg = format!("Invalid {}. Unbound variable '{}'", verb, name);
@@ -447,6 +485,9 @@ pub fn program(
     } = prog;
     let modules = modules(&mut context, emodules);
     let scripts = scripts(&mut context, escripts);
+    // Report cyclic struct definitions as soon as field types are resolved, rather than waiting
+    // until typing.
+    super::recursive_structs::modules(context.env, &modules);
     let mut inner = N::Program_ { modules, scripts };
     let mut info = NamingProgramInfo::new(pre_compiled_lib, &inner);
     super::resolve_use_funs::program(compilation_env, &mut info, &mut inner);
@@ -6,7 +6,9 @@ use crate::{
     cfgir::visitor::{AbsIntVisitorObj, AbstractInterpreterVisitor},
     command_line as cli,
     diagnostics::{
-        codes::{Category, Declarations, DiagnosticsID, Severity, UnusedItem, WarningFilter},
+        codes::{
+            Category, Declarations, DiagnosticsID, Severity, TypeSafety, UnusedItem, WarningFilter,
+        },
         Diagnostic, Diagnostics, WarningFilters,
     },
     editions::{check_feature as edition_check_feature, Edition, FeatureGate, Flavor},
@@ -19,6 +21,7 @@ use crate::{
 use clap::*;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
+use move_vm_config::verifier::VerifierConfig;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
 use std::{
     cell::RefCell,
@@ -155,6 +158,8 @@ pub const FILTER_UNUSED_FUNCTION: &str = "unused_function";
 pub const FILTER_UNUSED_STRUCT_FIELD: &str = "unused_field";
 pub const FILTER_UNUSED_CONST: &str = "unused_const";
 pub const FILTER_DEAD_CODE: &str = "dead_code";
+pub const FILTER_SHADOWED_VARIABLE: &str = "shadowed_variable";
+pub const FILTER_CONSTANT_ASSERTION: &str = "constant_assertion";
 
 pub type NamedAddressMap = BTreeMap<Symbol, NumericalAddress>;
 
@@ -231,6 +236,9 @@ pub struct CompilationEnv {
     known_filter_attributes: BTreeSet<E::AttributeName_>,
     prim_definers:
         BTreeMap<crate::naming::ast::BuiltinTypeName_, crate::expansion::ast::ModuleIdent>,
+    /// Limits that the bytecode verifier enforces on modules when they are published, to warn
+    /// about modules that come close to them (see `to_bytecode::verifier_limits`).
+    verifier_config: Option<VerifierConfig>,
     // TODO(tzakian): Remove the global counter and use this counter instead
     // pub counter: u64,
 }
@@ -333,6 +341,16 @@ impl CompilationEnv {
             ),
             known_code_filter!(FILTER_UNUSED_CONST, UnusedItem::Constant, filter_attr_name),
             known_code_filter!(FILTER_DEAD_CODE, UnusedItem::DeadCode, filter_attr_name),
+            known_code_filter!(
+                FILTER_SHADOWED_VARIABLE,
+                Declarations::ShadowedVariable,
+                filter_attr_name
+            ),
+            known_code_filter!(
+                FILTER_CONSTANT_ASSERTION,
+                TypeSafety::ConstantAssertion,
+                filter_attr_name
+            ),
         ]);
 
         let known_filter_names: BTreeMap<DiagnosticsID, KnownFilterInfo> = known_filters
@@ -365,6 +383,7 @@ impl CompilationEnv {
             known_filter_names,
             known_filter_attributes: filter_attributes,
             prim_definers: BTreeMap::new(),
+            verifier_config: None,
         }
     }
 
@@ -524,6 +543,14 @@ impl CompilationEnv {
         &self.flags
     }
 
+    pub fn set_verifier_config(&mut self, config: VerifierConfig) {
+        self.verifier_config = Some(config);
+    }
+
+    pub fn verifier_config(&self) -> Option<&VerifierConfig> {
+        self.verifier_config.as_ref()
+    }
+
     pub fn visitors(&self) -> Rc<Visitors> {
         self.visitors.clone()
     }
@@ -627,6 +654,10 @@ pub struct Flags {
     )]
     shadow: bool,
 
+    /// If set, also report lints: warnings about code that is valid, but likely to be a mistake
+    #[clap(long = cli::LINT)]
+    lint: bool,
+
     /// Internal flag used by the model builder to maintain functions which would be otherwise
     /// included only in tests, without creating the unit test code regular tests do.
     #[clap(skip)]
@@ -639,6 +670,7 @@ impl Flags {
             test: false,
             verify: false,
             shadow: false,
+            lint: false,
             bytecode_version: None,
             keep_testing_functions: false,
         }
@@ -649,6 +681,7 @@ impl Flags {
             test: true,
             verify: false,
             shadow: false,
+            lint: false,
             bytecode_version: None,
             keep_testing_functions: false,
         }
@@ -659,6 +692,7 @@ impl Flags {
             test: false,
             verify: true,
             shadow: true, // allows overlapping between sources and deps
+            lint: false,
             bytecode_version: None,
             keep_testing_functions: false,
         }
@@ -678,6 +712,13 @@ impl Flags {
         }
     }
 
+    pub fn set_lint(self, value: bool) -> Self {
+        Self {
+            lint: value,
+            ..self
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self == &Self::empty()
     }
@@ -701,6 +742,10 @@ impl Flags {
     pub fn bytecode_version(&self) -> Option<u32> {
         self.bytecode_version
     }
+
+    pub fn lint(&self) -> bool {
+        self.lint
+    }
 }
 
 //**************************************************************************************************
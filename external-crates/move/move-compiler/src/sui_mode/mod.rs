@@ -25,6 +25,7 @@ pub const OBJECT_NEW: Symbol = symbol!("new");
 pub const OBJECT_NEW_UID_FROM_HASH: Symbol = symbol!("new_uid_from_hash");
 pub const TEST_SCENARIO_MODULE_NAME: Symbol = symbol!("test_scenario");
 pub const TS_NEW_OBJECT: Symbol = symbol!("new_object");
+pub const TEST_UTILS_MODULE_NAME: Symbol = symbol!("test_utils");
 pub const UID_TYPE_NAME: Symbol = symbol!("UID");
 pub const ID_TYPE_NAME: Symbol = symbol!("ID");
 pub const TX_CONTEXT_MODULE_NAME: Symbol = symbol!("tx_context");
@@ -57,6 +58,15 @@ pub const PRIVATE_TRANSFER_FUNCTIONS: &[Symbol] = &[
     SHARE_FUNCTION_NAME,
 ];
 
+/// Natives that give unit tests control over time and randomness. They only exist in test builds,
+/// so they can only be called from test code.
+pub const TEST_ONLY_NATIVES: &[(Symbol, Symbol)] = &[
+    (TEST_UTILS_MODULE_NAME, symbol!("set_clock_timestamp_ms")),
+    (TEST_UTILS_MODULE_NAME, symbol!("clock_timestamp_ms")),
+    (TEST_UTILS_MODULE_NAME, symbol!("set_random_seed")),
+    (TEST_UTILS_MODULE_NAME, symbol!("random_bytes")),
+];
+
 //**************************************************************************************************
 // Diagnostics
 //**************************************************************************************************
@@ -145,3 +155,10 @@ pub const GLOBAL_STORAGE_DIAG: DiagnosticInfo = custom(
     /* code */ 9,
     "global storage is not supported in Sui",
 );
+pub const TEST_ONLY_NATIVE_CALL_DIAG: DiagnosticInfo = custom(
+    SUI_DIAG_PREFIX,
+    Severity::NonblockingError,
+    /* category */ TYPING,
+    /* code */ 10,
+    "invalid test-only native call",
+);
@@ -624,8 +624,8 @@ fn entry_param(
 }
 
 /// A valid entry param type is
-/// - A primitive (including strings, ID, and object)
-/// - A vector of primitives (including nested vectors)
+/// - A primitive (including strings, ID, and object), by value
+/// - A vector of primitives (including nested vectors), by value
 ///
 /// - An object
 /// - A reference to an object
@@ -658,9 +658,7 @@ fn entry_param_ty(
                 n = CLOCK_TYPE_NAME,
             )
         } else {
-            "'entry' parameters must be primitives (by-value), vectors of primitives, objects \
-            (by-reference or by-value), vectors of objects, or 'Receiving' arguments (by-reference or by-value)"
-                .to_owned()
+            invalid_entry_param_ty_msg(param_ty)
         };
         let emsg = format!("'{name}' was declared 'entry' here");
         context.env.add_diag(diag!(
@@ -672,6 +670,43 @@ fn entry_param_ty(
     }
 }
 
+/// Explains why `param_ty` is not a valid entry parameter type. Types that the adapter would accept
+/// if they were passed differently get a specific explanation, everything else falls back to
+/// listing the kinds of types that are accepted.
+fn invalid_entry_param_ty_msg(param_ty: &Type) -> String {
+    if contains_signer(param_ty) {
+        return "'signer' is not supported in 'entry' parameters. \
+            Use 'sui::tx_context::sender' to get the sender of the transaction"
+            .to_owned();
+    }
+    match &param_ty.value {
+        Type_::Ref(_, inner) if is_entry_primitive_ty(inner) => format!(
+            "Primitive 'entry' parameters must be passed by value, e.g. {}",
+            error_format(inner, &Subst::empty())
+        ),
+        Type_::Ref(_, inner) if is_entry_object_ty(inner) => format!(
+            "Vectors of objects must be passed by value, e.g. {}",
+            error_format(inner, &Subst::empty())
+        ),
+        _ => "'entry' parameters must be primitives (by-value), vectors of primitives, objects \
+            (by-reference or by-value), vectors of objects, or 'Receiving' arguments (by-reference or by-value)"
+            .to_owned(),
+    }
+}
+
+fn contains_signer(sp!(_, ty_): &Type) -> bool {
+    match ty_ {
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, BuiltinTypeName_::Signer))), _) => true,
+        Type_::Apply(_, _, targs) => targs.iter().any(contains_signer),
+        Type_::Ref(_, t) => contains_signer(t),
+        Type_::Unit
+        | Type_::Param(_)
+        | Type_::Var(_)
+        | Type_::Anything
+        | Type_::UnresolvedError => false,
+    }
+}
+
 fn is_mut_clock(param_ty: &Type) -> bool {
     match &param_ty.value {
         Type_::Ref(/* mut */ false, _) => false,
@@ -710,8 +745,9 @@ fn is_entry_primitive_ty(param_ty: &Type) -> bool {
         Type_::Param(tp) => !tp.abilities.has_ability_(Ability_::Key),
         // nonsensical, but no error needed
         Type_::Apply(_, sp!(_, N::Multiple(_)), ts) => ts.iter().all(is_entry_primitive_ty),
+        // Primitives are only accepted by value
+        Type_::Ref(_, _) => false,
         // Simple recursive cases
-        Type_::Ref(_, t) => is_entry_primitive_ty(t),
         Type_::Apply(_, sp!(_, N::Builtin(sp!(_, B::Vector))), targs) => {
             debug_assert!(targs.len() == 1);
             is_entry_primitive_ty(&targs[0])
@@ -733,6 +769,9 @@ fn is_entry_primitive_ty(param_ty: &Type) -> bool {
             is_entry_primitive_ty(&targs[0])
         }
 
+        // signer cannot be created from transaction inputs
+        Type_::Apply(_, sp!(_, N::Builtin(sp!(_, B::Signer))), _) => false,
+
         // primitives
         Type_::Apply(_, sp!(_, N::Builtin(_)), targs) => {
             debug_assert!(targs.is_empty());
@@ -901,6 +940,17 @@ fn exp(context: &mut Context, e: &T::Exp) {
             if is_transfer_module && PRIVATE_TRANSFER_FUNCTIONS.contains(&name.value()) {
                 check_private_transfer(context, e.exp.loc, mcall)
             }
+            if !context.in_test && is_test_only_native(module, name) {
+                let msg = format!(
+                    "Invalid call to '{}::{}'. \
+                    This native is only available in tests, and can only be called from \
+                    '#[test]' or '#[test_only]' functions and modules",
+                    module, name
+                );
+                context
+                    .env
+                    .add_diag(diag!(TEST_ONLY_NATIVE_CALL_DIAG, (e.exp.loc, msg)))
+            }
         }
         T::UnannotatedExp_::Builtin(b, _) => match &b.value {
             T::BuiltinFunction_::MoveTo(_)
@@ -935,6 +985,12 @@ fn exp(context: &mut Context, e: &T::Exp) {
     }
 }
 
+fn is_test_only_native(module: &ModuleIdent, name: &FunctionName) -> bool {
+    TEST_ONLY_NATIVES
+        .iter()
+        .any(|(m, f)| module.value.is(SUI_ADDR_NAME, *m) && name.value() == *f)
+}
+
 fn check_event_emit(context: &mut Context, loc: Loc, mcall: &ModuleCall) {
     let current_module = context.current_module();
     let ModuleCall {
@@ -7,3 +7,4 @@ mod canonicalize_handles;
 mod context;
 mod optimize;
 pub mod translate;
+mod verifier_limits;
@@ -2,9 +2,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{canonicalize_handles, context::*, optimize};
+use super::{canonicalize_handles, context::*, optimize, verifier_limits};
 use crate::{
-    cfgir::{ast as G, translate::move_value_from_value_},
+    cfgir::{abort_sites, ast as G, translate::move_value_from_value_},
     compiled_unit::*,
     diag,
     expansion::ast::{AbilitySet, Address, Attributes, ModuleIdent, ModuleIdent_, SpecId},
@@ -25,7 +25,9 @@ use crate::{
 };
 use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
-use move_core_types::account_address::AccountAddress as MoveAddress;
+use move_core_types::{
+    account_address::AccountAddress as MoveAddress, identifier::Identifier, metadata::Metadata,
+};
 use move_ir_types::{ast as IR, location::*};
 use move_symbol_pool::Symbol;
 use std::{
@@ -189,6 +191,15 @@ fn module(
         constants: gconstants,
         functions: gfunctions,
     } = mdef;
+    let abort_sites = gfunctions
+        .key_cloned_iter()
+        .map(|(f, fdef)| (f.value(), abort_sites::function(&gconstants, fdef)))
+        .collect();
+    let package_functions = gfunctions
+        .key_cloned_iter()
+        .filter(|(_, fdef)| matches!(fdef.visibility, Visibility::Package(_)))
+        .map(|(f, _)| Identifier::new(f.value().as_str()).unwrap())
+        .collect::<BTreeSet<_>>();
     let structs = struct_defs(&mut context, &ident, gstructs);
     let constants = constants(&mut context, Some(&ident), gconstants);
     let (collected_function_infos, functions) = functions(&mut context, Some(&ident), gfunctions);
@@ -243,7 +254,27 @@ fn module(
             }
         };
     canonicalize_handles::in_module(&mut module, &address_names(dependency_orderings.keys()));
-    let function_infos = module_function_infos(&module, &source_map, &collected_function_infos);
+    // The metadata is only emitted when the protocol the module is compiled for enforces package
+    // visibility, and only for modules declaring `public(package)` functions, so the bytecode of
+    // every other module is unchanged.
+    let enforce_package_visibility = compilation_env
+        .verifier_config()
+        .map_or(false, |config| config.enforce_package_visibility);
+    if enforce_package_visibility && !package_functions.is_empty() {
+        let package_functions = package_functions.into_iter().collect::<Vec<_>>();
+        module
+            .metadata
+            .push(Metadata::package_visibility(&package_functions));
+    }
+    if let Some(config) = compilation_env.verifier_config().cloned() {
+        verifier_limits::module(compilation_env, &config, &module, &source_map, ident_loc);
+    }
+    let function_infos = module_function_infos(
+        &module,
+        &source_map,
+        &collected_function_infos,
+        &abort_sites,
+    );
     let module = NamedCompiledModule {
         package_name: mdef.package_name,
         address: addr_bytes,
@@ -280,6 +311,7 @@ fn script(
     let loc = name.loc();
     let mut context = Context::new(compilation_env, None);
 
+    let abort_sites = abort_sites::function(&gconstants, &fdef);
     let constants = constants(&mut context, None, gconstants);
 
     let ((_, main), info) = function(&mut context, None, name, fdef);
@@ -309,7 +341,7 @@ fn script(
             }
         };
     canonicalize_handles::in_script(&mut script, &address_names(dependency_orderings.keys()));
-    let function_info = script_function_info(&source_map, info);
+    let function_info = script_function_info(&source_map, info, abort_sites);
     let script = NamedCompiledScript {
         package_name,
         name: key,
@@ -345,10 +377,17 @@ fn module_function_infos(
     compile_module: &F::CompiledModule,
     source_map: &SourceMap,
     collected_function_infos: &CollectedInfos,
+    abort_sites: &BTreeMap<Symbol, Vec<AbortSite>>,
 ) -> UniqueMap<FunctionName, FunctionInfo> {
     UniqueMap::maybe_from_iter((0..compile_module.function_defs.len()).map(|i| {
         let idx = F::FunctionDefinitionIndex(i as F::TableIndex);
-        function_info_map(compile_module, source_map, collected_function_infos, idx)
+        function_info_map(
+            compile_module,
+            source_map,
+            collected_function_infos,
+            abort_sites,
+            idx,
+        )
     }))
     .unwrap()
 }
@@ -357,6 +396,7 @@ fn function_info_map(
     compile_module: &F::CompiledModule,
     source_map: &SourceMap,
     collected_function_infos: &CollectedInfos,
+    abort_sites: &BTreeMap<Symbol, Vec<AbortSite>>,
     idx: F::FunctionDefinitionIndex,
 ) -> (FunctionName, FunctionInfo) {
     let module = compile_module;
@@ -391,6 +431,7 @@ fn function_info_map(
         spec_info,
         parameters,
         attributes: attributes.clone(),
+        abort_sites: abort_sites.get(&name).cloned().unwrap_or_default(),
     };
 
     let name_loc = *collected_function_infos.get_loc_(&name).unwrap();
@@ -401,6 +442,7 @@ fn function_info_map(
 fn script_function_info(
     source_map: &SourceMap,
     (params, specs, attributes): CollectedInfo,
+    abort_sites: Vec<AbortSite>,
 ) -> FunctionInfo {
     let idx = F::FunctionDefinitionIndex(0);
     let function_source_map = source_map.get_function_source_map(idx).unwrap();
@@ -429,6 +471,7 @@ fn script_function_info(
         spec_info,
         parameters,
         attributes,
+        abort_sites,
     }
 }
 
@@ -658,7 +701,8 @@ fn function(
 fn visibility(v: Visibility) -> IR::FunctionVisibility {
     match v {
         Visibility::Public(_) => IR::FunctionVisibility::Public,
-        Visibility::Friend(_) => IR::FunctionVisibility::Friend,
+        // Package visibility is recorded in the module's metadata, see `module`.
+        Visibility::Friend(_) | Visibility::Package(_) => IR::FunctionVisibility::Friend,
         Visibility::Internal => IR::FunctionVisibility::Internal,
     }
 }
@@ -1260,3 +1304,131 @@ fn binary_op(code: &mut IR::BytecodeBlock, sp!(loc, op_): BinOp) {
         },
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command_line::compiler::Compiler,
+        diagnostics::{codes::TypeSafety, Diagnostics},
+        editions::Edition,
+    };
+    use move_vm_config::verifier::VerifierConfig;
+
+    /// A package named `name`, with the modules in `source`, and `a` bound to 0x42.
+    fn package(dir: &tempfile::TempDir, name: &str, source: &str) -> PackagePaths<String, String> {
+        let path = dir.path().join(format!("{name}.move"));
+        std::fs::write(&path, source).unwrap();
+        PackagePaths {
+            name: Some((
+                name.into(),
+                PackageConfig {
+                    edition: Edition::E2024_ALPHA,
+                    ..PackageConfig::default()
+                },
+            )),
+            paths: vec![path.to_string_lossy().to_string()],
+            named_address_map: BTreeMap::from([(
+                "a".to_string(),
+                NumericalAddress::parse_str("0x42").unwrap(),
+            )]),
+        }
+    }
+
+    /// Compiles `targets` for a protocol that enforces package visibility.
+    fn compile(
+        targets: Vec<PackagePaths<String, String>>,
+        deps: Vec<PackagePaths<String, String>>,
+    ) -> Result<BTreeMap<Symbol, F::CompiledModule>, Diagnostics> {
+        compile_with(targets, deps, |compiler| {
+            compiler.set_verifier_config(VerifierConfig {
+                enforce_package_visibility: true,
+                ..VerifierConfig::default()
+            })
+        })
+    }
+
+    fn compile_with(
+        targets: Vec<PackagePaths<String, String>>,
+        deps: Vec<PackagePaths<String, String>>,
+        configure: impl FnOnce(Compiler) -> Compiler,
+    ) -> Result<BTreeMap<Symbol, F::CompiledModule>, Diagnostics> {
+        let compiler = Compiler::from_package_paths(targets, deps).unwrap();
+        let (_, units) = configure(compiler).build().unwrap();
+        let (units, _warnings) = units?;
+        Ok(units
+            .into_iter()
+            .filter_map(|unit| match unit {
+                CompiledUnitEnum::Module(m) => Some((m.named_module.name, m.named_module.module)),
+                CompiledUnitEnum::Script(_) => None,
+            })
+            .collect())
+    }
+
+    #[test]
+    fn package_visibility_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let modules = compile(
+            vec![package(
+                &dir,
+                "p",
+                r#"
+                module a::m {
+                    public(package) fun f() {}
+                    public(friend) fun g() {}
+                    public fun h() {}
+                }
+                module a::n {
+                    public fun call() { a::m::f() }
+                }
+                "#,
+            )],
+            vec![],
+        )
+        .unwrap();
+
+        let m = &modules[&Symbol::from("m")];
+        assert_eq!(
+            m.metadata,
+            vec![Metadata::package_visibility(&[
+                Identifier::new("f").unwrap()
+            ])]
+        );
+        // Modules without `public(package)` functions are compiled as before.
+        assert!(modules[&Symbol::from("n")].metadata.is_empty());
+    }
+
+    #[test]
+    fn package_visibility_metadata_not_enforced() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "module a::m { public(package) fun f() {} }";
+        // Without a verifier config, or with one that does not enforce package visibility, the
+        // bytecode is the same as before the metadata was introduced.
+        for configure in [
+            (|compiler| compiler) as fn(Compiler) -> Compiler,
+            |compiler| compiler.set_verifier_config(VerifierConfig::default()),
+        ] {
+            let modules =
+                compile_with(vec![package(&dir, "p", source)], vec![], configure).unwrap();
+            assert!(modules[&Symbol::from("m")].metadata.is_empty());
+        }
+    }
+
+    #[test]
+    fn cross_package_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let dep = package(&dir, "dep", "module a::m { public(package) fun f() {} }");
+        // The caller is at the same address, but in a different package.
+        let target = package(
+            &dir,
+            "target",
+            "module a::n { public fun call() { a::m::f() } }",
+        );
+        let diags = compile(vec![target], vec![dep]).unwrap_err();
+        let visibility = TypeSafety::Visibility.into_info().id();
+        assert!(
+            diags.into_vec().iter().any(|d| d.info().id() == visibility),
+            "Expected a visibility error"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Estimates how close a compiled module comes to the limits that the bytecode verifier enforces
+//! when it is published (see `CompilationEnv::verifier_config`), and warns about those it comes
+//! close to, so that a module that would be rejected on publish is caught when it is built. This
+//! covers
+//! - the units metered while verifying each function, and the module as a whole
+//! - the number of basic blocks in each function
+//! - the number of back edges (loops) in each function, and the module as a whole
+//! - the number of nodes in each type the module refers to
+
+use crate::{diag, diagnostics::codes::VerifierLimits, shared::CompilationEnv};
+use move_binary_format::{
+    access::ModuleAccess,
+    control_flow_graph::{ControlFlowGraph, VMControlFlowGraph},
+    errors::PartialVMResult,
+    file_format::{
+        CompiledModule, FunctionDefinitionIndex, SignatureIndex, SignatureToken,
+        StructDefinitionIndex, StructFieldInformation,
+    },
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_bytecode_verifier::meter::{Meter, Scope};
+use move_ir_types::location::Loc;
+use move_vm_config::verifier::VerifierConfig;
+use std::collections::BTreeMap;
+
+/// Fraction of a limit that a module can reach before it is warned about.
+const WARNING_THRESHOLD: f64 = 0.8;
+
+/// Weights of types that can expand to more nodes, mirroring the bytecode verifier's
+/// `LimitsVerifier`.
+const STRUCT_SIZE_WEIGHT: usize = 4;
+const PARAM_SIZE_WEIGHT: usize = 4;
+
+/// Records the units metered while verifying each function, and the module, without bounding
+/// them.
+#[derive(Default)]
+struct UsageMeter {
+    function: String,
+    function_units: u128,
+    module_units: u128,
+    /// Units metered for each function, by name, recorded once its verification is done.
+    functions: BTreeMap<String, u128>,
+}
+
+impl Meter for UsageMeter {
+    fn enter_scope(&mut self, name: &str, scope: Scope) {
+        if scope == Scope::Module {
+            self.module_units = 0;
+        } else {
+            self.function = name.to_string();
+            self.function_units = 0;
+        }
+    }
+
+    fn transfer(&mut self, from: Scope, to: Scope, factor: f32) -> PartialVMResult<()> {
+        let units = if from == Scope::Module {
+            self.module_units
+        } else {
+            // The verifier transfers a function's units to its module once it is done with it.
+            self.functions
+                .insert(self.function.clone(), self.function_units);
+            self.function_units
+        };
+        self.add(to, (units as f32 * factor) as u128)
+    }
+
+    fn add(&mut self, scope: Scope, units: u128) -> PartialVMResult<()> {
+        let total = if scope == Scope::Module {
+            &mut self.module_units
+        } else {
+            &mut self.function_units
+        };
+        *total = total.saturating_add(units);
+        Ok(())
+    }
+}
+
+pub fn module(
+    compilation_env: &mut CompilationEnv,
+    config: &VerifierConfig,
+    module: &CompiledModule,
+    source_map: &SourceMap,
+    module_loc: Loc,
+) {
+    let function_loc = |idx: usize| {
+        source_map
+            .get_function_source_map(FunctionDefinitionIndex(idx as u16))
+            .map_or(module_loc, |f| f.definition_location)
+    };
+
+    // Metering only applies to modules that pass verification, which is reported on separately.
+    let mut meter = UsageMeter::default();
+    let verified = move_bytecode_verifier::verify_module_with_config_metered(
+        &VerifierConfig::unbounded(),
+        module,
+        &mut meter,
+    );
+    if verified.is_ok() {
+        report(
+            compilation_env,
+            module_loc,
+            "Verifying this module",
+            "meter units",
+            meter.module_units,
+            config.max_per_mod_meter_units,
+        );
+    }
+
+    let mut module_back_edges = 0;
+    for (idx, fdef) in module.function_defs().iter().enumerate() {
+        let Some(code) = &fdef.code else { continue };
+        let loc = function_loc(idx);
+        let name = module
+            .identifier_at(module.function_handle_at(fdef.function).name)
+            .as_str();
+
+        if let Some(units) = meter.functions.get(name).filter(|_| verified.is_ok()) {
+            report(
+                compilation_env,
+                loc,
+                "Verifying this function",
+                "meter units",
+                *units,
+                config.max_per_fun_meter_units,
+            );
+        }
+
+        let cfg = VMControlFlowGraph::new(&code.code);
+        report(
+            compilation_env,
+            loc,
+            "This function",
+            "basic blocks",
+            cfg.blocks().len() as u128,
+            config.max_basic_blocks.map(|max| max as u128),
+        );
+
+        let back_edges = cfg.num_back_edges();
+        module_back_edges += back_edges;
+        report(
+            compilation_env,
+            loc,
+            "This function",
+            "back edges",
+            back_edges as u128,
+            config.max_back_edges_per_function.map(|max| max as u128),
+        );
+    }
+
+    report(
+        compilation_env,
+        module_loc,
+        "This module",
+        "back edges",
+        module_back_edges as u128,
+        config.max_back_edges_per_module.map(|max| max as u128),
+    );
+
+    if let Some(max_type_nodes) = config.max_type_nodes {
+        type_nodes(
+            compilation_env,
+            max_type_nodes,
+            module,
+            source_map,
+            module_loc,
+        );
+    }
+}
+
+/// Reports the largest type in each function's signature and locals, and in each struct's fields,
+/// at that function or struct, and the largest of the module's other types at the module.
+fn type_nodes(
+    compilation_env: &mut CompilationEnv,
+    max_type_nodes: usize,
+    module: &CompiledModule,
+    source_map: &SourceMap,
+    module_loc: Loc,
+) {
+    let mut signature_locs: BTreeMap<SignatureIndex, Loc> = BTreeMap::new();
+    for (idx, fdef) in module.function_defs().iter().enumerate() {
+        let Ok(fmap) = source_map.get_function_source_map(FunctionDefinitionIndex(idx as u16))
+        else {
+            continue;
+        };
+        let handle = module.function_handle_at(fdef.function);
+        let locals = fdef.code.as_ref().map(|code| code.locals);
+        for sig in [Some(handle.parameters), Some(handle.return_), locals]
+            .into_iter()
+            .flatten()
+        {
+            signature_locs.insert(sig, fmap.definition_location);
+        }
+    }
+
+    // Largest type at each location, in the order they are first seen.
+    let mut largest: Vec<(Loc, usize)> = vec![];
+    let mut record = |loc: Loc, ty: &SignatureToken| {
+        let size = type_size(ty);
+        match largest.iter_mut().find(|(l, _)| *l == loc) {
+            Some((_, largest)) => *largest = (*largest).max(size),
+            None => largest.push((loc, size)),
+        }
+    };
+
+    for (idx, sig) in module.signatures().iter().enumerate() {
+        let loc = signature_locs
+            .get(&SignatureIndex(idx as u16))
+            .copied()
+            .unwrap_or(module_loc);
+        for ty in &sig.0 {
+            record(loc, ty);
+        }
+    }
+    for constant in module.constant_pool() {
+        record(module_loc, &constant.type_);
+    }
+    for (idx, sdef) in module.struct_defs().iter().enumerate() {
+        let StructFieldInformation::Declared(fields) = &sdef.field_information else {
+            continue;
+        };
+        let loc = source_map
+            .get_struct_source_map(StructDefinitionIndex(idx as u16))
+            .map_or(module_loc, |s| s.definition_location);
+        for field in fields {
+            record(loc, &field.signature.0);
+        }
+    }
+
+    for (loc, size) in largest {
+        report(
+            compilation_env,
+            loc,
+            "The largest type here",
+            "type nodes",
+            size as u128,
+            Some(max_type_nodes as u128),
+        );
+    }
+}
+
+/// Size of `ty`, as the bytecode verifier counts it against `max_type_nodes`.
+fn type_size(ty: &SignatureToken) -> usize {
+    ty.preorder_traversal()
+        .map(|t| match t {
+            SignatureToken::Struct(..) | SignatureToken::StructInstantiation(..) => {
+                STRUCT_SIZE_WEIGHT
+            }
+            SignatureToken::TypeParameter(..) => PARAM_SIZE_WEIGHT,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Warns at `loc` if `usage` reaches `WARNING_THRESHOLD` of `limit`, or exceeds it.
+fn report(
+    compilation_env: &mut CompilationEnv,
+    loc: Loc,
+    subject: &str,
+    unit: &str,
+    usage: u128,
+    limit: Option<u128>,
+) {
+    let Some(limit) = limit else { return };
+    if (usage as f64) < limit as f64 * WARNING_THRESHOLD {
+        return;
+    }
+
+    let msg = if usage > limit {
+        format!(
+            "{subject} uses {usage} {unit}, more than the bytecode verifier's limit of {limit}. \
+             Publishing it will fail"
+        )
+    } else {
+        let percent = usage * 100 / limit.max(1);
+        format!(
+            "{subject} uses {usage} {unit}, {percent}% of the bytecode verifier's limit of {limit}"
+        )
+    };
+    compilation_env.add_diag(diag!(VerifierLimits::CloseToLimit, (loc, msg)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::StructHandleIndex;
+
+    #[test]
+    fn usage_meter_records_functions() {
+        let mut meter = UsageMeter::default();
+        meter.enter_scope("m", Scope::Module);
+        for (name, units) in [("f", 10), ("g", 25)] {
+            meter.enter_scope(name, Scope::Function);
+            meter.add(Scope::Function, units).unwrap();
+            meter.transfer(Scope::Function, Scope::Module, 1.0).unwrap();
+        }
+
+        assert_eq!(meter.module_units, 35);
+        assert_eq!(
+            meter.functions,
+            BTreeMap::from([("f".to_string(), 10), ("g".to_string(), 25)])
+        );
+    }
+
+    #[test]
+    fn type_size_weights() {
+        // vector<S<T>>: one node for the vector, and weighted nodes for the struct and parameter.
+        let ty = SignatureToken::Vector(Box::new(SignatureToken::StructInstantiation(
+            StructHandleIndex(0),
+            vec![SignatureToken::TypeParameter(0)],
+        )));
+        assert_eq!(type_size(&ty), 1 + STRUCT_SIZE_WEIGHT + PARAM_SIZE_WEIGHT);
+        assert_eq!(type_size(&SignatureToken::U64), 1);
+    }
+}
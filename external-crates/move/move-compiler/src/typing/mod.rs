@@ -8,6 +8,5 @@ mod dependency_ordering;
 mod expand;
 mod globals;
 mod infinite_instantiations;
-mod recursive_structs;
 pub(crate) mod translate;
 pub mod visitor;
@@ -4,7 +4,7 @@
 
 use super::{
     core::{self, Context, Subst},
-    expand, globals, infinite_instantiations, recursive_structs,
+    expand, globals, infinite_instantiations,
 };
 use crate::{
     diag,
@@ -54,7 +54,6 @@ pub fn program(
 
     assert!(context.constraints.is_empty());
     dependency_ordering::program(context.env, &mut modules, &mut scripts);
-    recursive_structs::modules(context.env, &modules);
     infinite_instantiations::modules(context.env, &modules);
     let mut prog = T::Program_ { modules, scripts };
     let module_use_funs = context
@@ -0,0 +1,20 @@
+module 0x42::m {
+    fun always_fails() {
+        assert!(false, 0);
+    }
+
+    fun always_passes() {
+        assert!(!(true && false), 1);
+    }
+
+    // Conditions that depend on values are not reported
+    fun depends_on_value(x: u64) {
+        assert!(x > 0, 2);
+        assert!(false || x == 1, 3);
+    }
+
+    #[allow(constant_assertion)]
+    fun allowed() {
+        assert!(false, 4);
+    }
+}
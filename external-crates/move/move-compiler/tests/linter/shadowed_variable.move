@@ -0,0 +1,25 @@
+module 0x42::m {
+    fun shadows(): u64 {
+        let x = 0;
+        x + { let x = 1; x }
+    }
+
+    // Redeclaring a parameter, or a local in the same scope, is not reported
+    fun redeclares(p: u64): u64 {
+        let p = p + 1;
+        let y = p;
+        let y = y + 1;
+        y
+    }
+
+    fun underscore(): u64 {
+        let _x = 0;
+        { let _x = 1; _x }
+    }
+
+    #[allow(shadowed_variable)]
+    fun allowed(): u64 {
+        let x = 0;
+        x + { let x = 1; x }
+    }
+}
@@ -0,0 +1,39 @@
+// Temporaries that hold arguments, operands and conditions without effects are substituted back
+// into the statements that use them, as long as that keeps the order of evaluation.
+module 0x42::m {
+    public struct S has drop { a: u64, b: u64 }
+
+    fun f(x: u64, y: u64): u64 { x + y }
+    fun g(): u64 { 1 }
+    fun h(s: &mut S): u64 { s.a = s.a + 1; s.a }
+
+    fun nested_calls(): u64 {
+        f(f(g(), 2), f(g(), g()))
+    }
+
+    fun operands(x: u64): u64 {
+        (g() + x) * (g() - 1)
+    }
+
+    fun reads_after_effect(s: &mut S): u64 {
+        // `s.a` is read after `h` increments it
+        h(s) + s.a
+    }
+
+    fun conditions(x: u64): u64 {
+        if (f(x, g()) > 2) return g();
+        while (f(x, 1) < 10) x = f(x, g());
+        x
+    }
+
+    fun packs(): S {
+        let s = S { b: g(), a: f(g(), 1) };
+        s.a = f(s.b, g());
+        s
+    }
+
+    fun unpacks(): u64 {
+        let S { a, b: _ } = packs();
+        a
+    }
+}
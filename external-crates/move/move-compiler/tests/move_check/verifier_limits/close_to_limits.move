@@ -0,0 +1,29 @@
+// The test suite limits functions to 2 back edges, and types to 20 nodes.
+module 0x42::m {
+    struct S<T> has drop { t: T }
+
+    public fun one_loop(n: u64) {
+        let i = 0;
+        while (i < n) i = i + 1;
+    }
+
+    public fun two_loops(n: u64) {
+        let i = 0;
+        while (i < n) i = i + 1;
+        let j = 0;
+        while (j < n) j = j + 1;
+    }
+
+    public fun three_loops(n: u64) {
+        let i = 0;
+        while (i < n) i = i + 1;
+        let j = 0;
+        while (j < n) j = j + 1;
+        let k = 0;
+        while (k < n) k = k + 1;
+    }
+
+    public fun small_type(_s: S<S<u64>>) {}
+
+    public fun large_type(_s: S<S<S<S<u64>>>>) {}
+}
@@ -15,6 +15,7 @@ use move_compiler::{
     shared::{Flags, NumericalAddress, PackageConfig, PackagePaths},
     Compiler, PASS_PARSER,
 };
+use move_vm_config::verifier::VerifierConfig;
 
 /// Shared flag to keep any temporary results of the test
 const KEEP_TMP: &str = "KEEP";
@@ -25,6 +26,8 @@ const UNUSED_EXT: &str = "unused";
 
 const SUI_MODE_DIR: &str = "sui_mode";
 const MOVE_2024_DIR: &str = "move_2024";
+const LINTER_DIR: &str = "linter";
+const VERIFIER_LIMITS_DIR: &str = "verifier_limits";
 
 fn default_testing_addresses(flavor: Flavor) -> BTreeMap<String, NumericalAddress> {
     let mut mapping = vec![
@@ -47,6 +50,18 @@ fn default_testing_addresses(flavor: Flavor) -> BTreeMap<String, NumericalAddres
         .collect()
 }
 
+/// Limits small enough for the tests to come close to with small modules. Metering is not limited,
+/// so that the expected diagnostics don't change with the verifier's costs.
+fn testing_verifier_limits() -> VerifierConfig {
+    VerifierConfig {
+        max_back_edges_per_function: Some(2),
+        max_type_nodes: Some(20),
+        max_per_fun_meter_units: None,
+        max_per_mod_meter_units: None,
+        ..VerifierConfig::default()
+    }
+}
+
 fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
     let flavor = if path.components().any(|c| c.as_os_str() == SUI_MODE_DIR) {
         Flavor::Sui
@@ -143,7 +158,8 @@ fn testsuite(path: &Path, mut config: PackageConfig) -> datatest_stable::Result<
     let exp_path = path.with_extension(EXP_EXT);
     let out_path = path.with_extension(OUT_EXT);
 
-    let flags = Flags::empty();
+    let lint = path.components().any(|c| c.as_os_str() == LINTER_DIR);
+    let flags = Flags::empty().set_lint(lint);
 
     config
         .warning_filter
@@ -174,11 +190,17 @@ pub fn run_test(
     }];
 
     let flags = flags.set_sources_shadow_deps(true);
-    let (files, comments_and_compiler_res) = Compiler::from_package_paths(targets, deps)
+    let mut compiler = Compiler::from_package_paths(targets, deps)
         .unwrap()
         .set_flags(flags)
-        .set_default_config(default_config)
-        .run::<PASS_PARSER>()?;
+        .set_default_config(default_config);
+    if path
+        .components()
+        .any(|c| c.as_os_str() == VERIFIER_LIMITS_DIR)
+    {
+        compiler = compiler.set_verifier_config(testing_verifier_limits());
+    }
+    let (files, comments_and_compiler_res) = compiler.run::<PASS_PARSER>()?;
     let diags = move_check_for_errors(comments_and_compiler_res);
 
     let has_diags = !diags.is_empty();
@@ -0,0 +1,8 @@
+// invalid, primitives must be passed by value
+
+module a::m {
+    public entry fun no(_: &u64) {
+        abort 0
+    }
+
+}
@@ -0,0 +1,8 @@
+// invalid, signer is not a valid entry parameter
+
+module a::m {
+    public entry fun no(_: signer) {
+        abort 0
+    }
+
+}
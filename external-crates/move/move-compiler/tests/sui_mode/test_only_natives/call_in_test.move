@@ -0,0 +1,34 @@
+// test-only natives can be called from test and test-only code
+
+#[test_only]
+module a::helpers {
+    use sui::test_utils;
+
+    public fun advance(ms: u64) {
+        test_utils::set_clock_timestamp_ms(test_utils::clock_timestamp_ms() + ms)
+    }
+}
+
+module a::m {
+    #[test_only]
+    use sui::test_utils;
+
+    #[test_only]
+    public fun seeded_bytes(): vector<u8> {
+        test_utils::set_random_seed(b"seed");
+        test_utils::random_bytes(8)
+    }
+
+    #[test]
+    fun test_clock() {
+        a::helpers::advance(10);
+        assert!(test_utils::clock_timestamp_ms() == 10, 0);
+    }
+}
+
+module sui::test_utils {
+    public native fun set_clock_timestamp_ms(timestamp_ms: u64);
+    public native fun clock_timestamp_ms(): u64;
+    public native fun set_random_seed(seed: vector<u8>);
+    public native fun random_bytes(n: u64): vector<u8>;
+}
@@ -0,0 +1,21 @@
+// test-only natives cannot be called from code that is not test code, even in test builds
+
+module a::m {
+    use sui::test_utils;
+
+    public fun now(): u64 {
+        test_utils::clock_timestamp_ms()
+    }
+
+    public fun roll(): vector<u8> {
+        test_utils::set_random_seed(b"seed");
+        test_utils::random_bytes(8)
+    }
+}
+
+module sui::test_utils {
+    public native fun set_clock_timestamp_ms(timestamp_ms: u64);
+    public native fun clock_timestamp_ms(): u64;
+    public native fun set_random_seed(seed: vector<u8>);
+    public native fun random_bytes(n: u64): vector<u8>;
+}
@@ -0,0 +1,55 @@
+//# init --edition 2024.alpha
+
+// Temporaries introduced in the 2024 edition are substituted back into the statements that use
+// them only if that does not change the values computed, or the order of effects.
+
+//# publish
+module 0x42::m {
+    public struct S has drop { a: u64, b: u64 }
+    public struct P has drop { x: bool, y: u8, z: vector<u64> }
+
+    fun next(counter: &mut u64): u64 {
+        *counter = *counter + 1;
+        *counter
+    }
+
+    fun f(x: u64, y: u64): u64 { x * 10 + y }
+
+    public fun pure_fields() {
+        let p = P { z: vector[1, 2], y: (1u64 as u8), x: 1 < 2 && !false };
+        assert!(p.x, 0);
+        assert!(p.y == 1, 1);
+        assert!(p.z == vector[1, 2], 2);
+    }
+
+    public fun effectful_fields() {
+        let counter = 0;
+        let s = S { b: next(&mut counter), a: next(&mut counter) };
+        assert!(s.b == 1, 0);
+        assert!(s.a == 2, 1);
+    }
+
+    public fun reads_after_effect() {
+        let counter = 0;
+        let s = S { b: counter, a: next(&mut counter) };
+        assert!(s.b == 0, 0);
+        assert!(s.a == 1, 1);
+        let x = f(next(&mut counter), counter);
+        assert!(x == 22, 2);
+    }
+
+    public fun mixed_fields() {
+        let counter = 0;
+        let s = S { b: 7 ^ 2, a: f(next(&mut counter), next(&mut counter)) };
+        assert!(s.b == 5, 0);
+        assert!(s.a == 12, 1);
+    }
+}
+
+//# run 0x42::m::pure_fields
+
+//# run 0x42::m::effectful_fields
+
+//# run 0x42::m::reads_after_effect
+
+//# run 0x42::m::mixed_fields
@@ -1,8 +1,15 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::identifier::Identifier;
 use serde::{Deserialize, Serialize};
 
+/// Key of the metadata listing the functions of a module declared `public(package)`. In bytecode,
+/// these functions have `friend` visibility, so that the modules of the same package that call
+/// them can be declared as friends, and the metadata tells them apart from functions declared
+/// `public(friend)`.
+pub const PACKAGE_VISIBILITY_METADATA_KEY: &[u8] = b"package_visibility";
+
 /// Representation of metadata,
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -12,3 +19,19 @@ pub struct Metadata {
     /// The value of the metadata.
     pub value: Vec<u8>,
 }
+
+impl Metadata {
+    /// Metadata listing the functions of a module declared `public(package)`, by name.
+    pub fn package_visibility(functions: &[Identifier]) -> Self {
+        Self {
+            key: PACKAGE_VISIBILITY_METADATA_KEY.to_vec(),
+            value: bcs::to_bytes(functions).expect("Serializing identifiers cannot fail"),
+        }
+    }
+
+    /// The functions listed by this metadata, if it lists `public(package)` functions. Returns an
+    /// error if it does, but they can't be decoded.
+    pub fn as_package_visibility(&self) -> Option<Result<Vec<Identifier>, bcs::Error>> {
+        (self.key == PACKAGE_VISIBILITY_METADATA_KEY).then(|| bcs::from_bytes(&self.value))
+    }
+}
@@ -6,4 +6,5 @@
 
 pub mod ast;
 pub mod location;
+pub mod mangling;
 pub mod spec_language_ast;
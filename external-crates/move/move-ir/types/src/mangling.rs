@@ -0,0 +1,120 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The scheme the Move compiler uses to name the locals of the functions it compiles. These names
+//! are recorded in source maps, so debuggers and other tools reading source maps can use
+//! `demangle` to recover the names of locals as they were written in source.
+//!
+//! Version 1 of the scheme has two kinds of names:
+//! - `name#id#color` for a local (or parameter) declared in source as `name`. `id` tells apart
+//!   locals of the same name declared in the same function, starting from 0 for parameters and 1
+//!   for other locals. `color` tells apart locals substituted in by macro expansion, and is 0
+//!   otherwise.
+//! - `%#n` for the `n`th temporary introduced by the compiler in a function. Temporaries have no
+//!   name in source.
+//!
+//! Any other name was not produced by the compiler from Move source (e.g. it comes from Move IR),
+//! and is left as is. `MANGLING_VERSION` is incremented whenever names produced by the compiler
+//! change in a way that `demangle` of a previous version would not understand.
+
+use std::fmt;
+
+/// Version of the scheme described above.
+pub const MANGLING_VERSION: u32 = 1;
+
+const DELIMITER: char = '#';
+const TEMP_PREFIX: &str = "%";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Demangled<'a> {
+    /// A local declared in source
+    Local { name: &'a str, id: u16, color: u16 },
+    /// A temporary introduced by the compiler
+    Temp { index: usize },
+    /// A name that was not mangled
+    Unmangled(&'a str),
+}
+
+/// The name of the local declared in source as `name`
+pub fn mangle_local(name: &str, id: u16, color: u16) -> String {
+    format!("{name}{DELIMITER}{id}{DELIMITER}{color}")
+}
+
+/// The name of the `index`th temporary of a function
+pub fn mangle_temp(index: usize) -> String {
+    format!("{TEMP_PREFIX}{DELIMITER}{index}")
+}
+
+pub fn is_temp(mangled: &str) -> bool {
+    matches!(demangle(mangled), Demangled::Temp { .. })
+}
+
+pub fn demangle(mangled: &str) -> Demangled<'_> {
+    if let Some(index) = mangled
+        .strip_prefix(TEMP_PREFIX)
+        .and_then(|rest| rest.strip_prefix(DELIMITER))
+        .and_then(|index| index.parse().ok())
+    {
+        return Demangled::Temp { index };
+    }
+
+    let mut parts = mangled.rsplitn(3, DELIMITER);
+    let (Some(color), Some(id), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+        return Demangled::Unmangled(mangled);
+    };
+    match (id.parse(), color.parse()) {
+        (Ok(id), Ok(color)) if !name.is_empty() => Demangled::Local { name, id, color },
+        _ => Demangled::Unmangled(mangled),
+    }
+}
+
+impl<'a> Demangled<'a> {
+    /// The name as written in source, if there is one
+    pub fn source_name(&self) -> Option<&'a str> {
+        match self {
+            Demangled::Local { name, .. } | Demangled::Unmangled(name) => Some(name),
+            Demangled::Temp { .. } => None,
+        }
+    }
+}
+
+/// Displays locals by their name in source, and temporaries as `%tmp<index>`. Locals that share a
+/// name in source are not told apart.
+impl fmt::Display for Demangled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Demangled::Local { name, .. } | Demangled::Unmangled(name) => write!(f, "{name}"),
+            Demangled::Temp { index } => write!(f, "{TEMP_PREFIX}tmp{index}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(
+            demangle(&mangle_local("x", 1, 0)),
+            Demangled::Local {
+                name: "x",
+                id: 1,
+                color: 0
+            }
+        );
+        assert_eq!(demangle(&mangle_temp(12)), Demangled::Temp { index: 12 });
+        assert_eq!(mangle_local("x", 1, 0), "x#1#0");
+        assert_eq!(mangle_temp(12), "%#12");
+    }
+
+    #[test]
+    fn test_unmangled() {
+        for name in ["x", "x#1", "x#a#0", "#1#0", "%tmp"] {
+            assert_eq!(demangle(name), Demangled::Unmangled(name));
+        }
+        assert_eq!(demangle("x#1#0").to_string(), "x");
+        assert_eq!(demangle("%#3").to_string(), "%tmp3");
+        assert_eq!(demangle("%#3").source_name(), None);
+    }
+}
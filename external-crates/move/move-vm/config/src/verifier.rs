@@ -24,6 +24,7 @@ pub struct VerifierConfig {
     pub max_per_fun_meter_units: Option<u128>,
     pub max_per_mod_meter_units: Option<u128>,
     pub max_idenfitier_len: Option<u64>,
+    pub enforce_package_visibility: bool,
 }
 
 impl Default for VerifierConfig {
@@ -61,6 +62,9 @@ impl Default for VerifierConfig {
             max_per_mod_meter_units: Some(1000 * 8000),
             max_constant_vector_len: Some(DEFAULT_MAX_CONSTANT_VECTOR_LEN),
             max_idenfitier_len: Some(DEFAULT_MAX_IDENTIFIER_LENGTH),
+            // Check the metadata listing `public(package)` functions, and keep calls to them
+            // within their address
+            enforce_package_visibility: false,
         }
     }
 }
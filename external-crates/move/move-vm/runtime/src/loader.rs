@@ -1263,7 +1263,8 @@ impl Loader {
         let deps = bundle_deps
             .into_iter()
             .chain(cached_deps.iter().map(Arc::as_ref));
-        let result = dependencies::verify_module(module, deps);
+        let result =
+            dependencies::verify_module_with_config(&self.vm_config.verifier, module, deps);
 
         // if dependencies loading is not allowed to fail, the linking should not fail as well
         if allow_dependency_loading_failure {
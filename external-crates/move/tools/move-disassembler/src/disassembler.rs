@@ -24,7 +24,7 @@ use move_bytecode_source_map::{
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule, NamedCompiledScript};
 use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
 use move_coverage::coverage_map::{ExecCoverageMap, FunctionCoverage};
-use move_ir_types::location::Loc;
+use move_ir_types::{location::Loc, mangling::demangle};
 
 /// Holds the various options that we support while disassembling code.
 #[derive(Debug, Default, Parser)]
@@ -44,6 +44,11 @@ pub struct DisassemblerOptions {
     /// Print the locals inside each function body.
     #[clap(long = "print-locals")]
     pub print_locals: bool,
+
+    /// Print parameters and locals by their names in source, instead of the names the compiler
+    /// gave them. Locals that share a name in source are then printed the same.
+    #[clap(long = "demangle-names")]
+    pub demangle_names: bool,
 }
 
 impl DisassemblerOptions {
@@ -53,6 +58,7 @@ impl DisassemblerOptions {
             print_code: true,
             print_basic_blocks: true,
             print_locals: true,
+            demangle_names: false,
         }
     }
 }
@@ -387,7 +393,15 @@ impl<'a> Disassembler<'a> {
                     )
                 })?
                 .0;
-        Ok(name)
+        Ok(self.display_local_name(&name))
+    }
+
+    fn display_local_name(&self, name: &str) -> String {
+        if self.options.demangle_names {
+            demangle(name).to_string()
+        } else {
+            name.to_string()
+        }
     }
 
     fn type_for_parameter_or_local(
@@ -1061,7 +1075,7 @@ impl<'a> Disassembler<'a> {
             .map(|(local_idx, (name, _))| {
                 let ty =
                     self.type_for_local(parameter_len + local_idx, signature, function_source_map)?;
-                Ok(format!("{}: {}", name, ty))
+                Ok(format!("{}: {}", self.display_local_name(name), ty))
             })
             .collect::<Result<Vec<String>>>()?;
         Ok(locals_names_tys)
@@ -1128,7 +1142,7 @@ impl<'a> Disassembler<'a> {
             .map(|(tok, (name, _))| {
                 Ok(format!(
                     "{}: {}",
-                    name,
+                    self.display_local_name(name),
                     self.disassemble_sig_tok(tok.clone(), &function_source_map.type_parameters)?
                 ))
             })
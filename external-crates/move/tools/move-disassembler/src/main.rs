@@ -37,6 +37,10 @@ struct Args {
     #[clap(long = "skip-basic-blocks")]
     pub skip_basic_blocks: bool,
 
+    /// Print parameters and locals by their names in source.
+    #[clap(long = "demangle-names")]
+    pub demangle_names: bool,
+
     /// Treat input file as a script (default is to treat file as a module)
     #[clap(short = 's', long = "script")]
     pub is_script: bool,
@@ -85,6 +89,7 @@ fn main() {
     disassembler_options.only_externally_visible = args.skip_private;
     disassembler_options.print_basic_blocks = !args.skip_basic_blocks;
     disassembler_options.print_locals = !args.skip_locals;
+    disassembler_options.demangle_names = args.demangle_names;
 
     // TODO: make source mapping work with the Move source language
     let no_loc = Spanned::unsafe_no_loc(()).loc;
@@ -10,7 +10,7 @@ use anyhow::Result;
 use move_compiler::{
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::{report_diagnostics_to_color_buffer, report_warnings, FilesSourceText},
-    Compiler,
+    Compiler, FullyCompiledProgram,
 };
 use std::{collections::BTreeSet, io::Write, path::Path};
 
@@ -35,6 +35,14 @@ impl BuildPlan {
         })
     }
 
+    pub fn root_package(&self) -> PackageName {
+        self.root
+    }
+
+    pub fn resolution_graph(&self) -> &ResolvedGraph {
+        &self.resolution_graph
+    }
+
     /// Compilation results in the process exit upon warning/failure
     pub fn compile<W: Write>(&self, writer: &mut W) -> Result<CompiledPackage> {
         self.compile_with_driver(writer, |compiler| compiler.build_and_report())
@@ -64,6 +72,20 @@ impl BuildPlan {
     pub fn compile_with_driver<W: Write>(
         &self,
         writer: &mut W,
+        compiler_driver: impl FnMut(
+            Compiler,
+        )
+            -> anyhow::Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)>,
+    ) -> Result<CompiledPackage> {
+        self.compile_with_pre_compiled_lib(writer, None, compiler_driver)
+    }
+
+    /// Compile the package, taking the dependencies that are in `pre_compiled_lib` from it instead
+    /// of compiling them again.
+    pub fn compile_with_pre_compiled_lib<W: Write>(
+        &self,
+        writer: &mut W,
+        pre_compiled_lib: Option<&FullyCompiledProgram>,
         mut compiler_driver: impl FnMut(
             Compiler,
         )
@@ -109,6 +131,7 @@ impl BuildPlan {
             root_package.clone(),
             transitive_dependencies,
             &self.resolution_graph,
+            pre_compiled_lib,
             &mut compiler_driver,
         )?;
 
@@ -14,7 +14,10 @@ use crate::{
 use anyhow::{ensure, Result};
 use colored::Colorize;
 use move_abigen::{Abigen, AbigenOptions};
-use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CompiledModule, CompiledScript, Visibility},
+};
 use move_bytecode_source_map::utils::source_map_from_file;
 use move_bytecode_utils::Modules;
 use move_command_line_common::{
@@ -26,12 +29,15 @@ use move_command_line_common::{
 };
 use move_compiler::{
     compiled_unit::{
-        self, AnnotatedCompiledUnit, CompiledUnit, NamedCompiledModule, NamedCompiledScript,
+        self, AbortCode, AnnotatedCompiledModule, AnnotatedCompiledUnit, CompiledUnit,
+        NamedCompiledModule, NamedCompiledScript,
     },
     diagnostics::FilesSourceText,
+    editions::{Edition, Flavor},
     shared::{Flags, NamedAddressMap, NumericalAddress, PackageConfig, PackagePaths},
-    Compiler,
+    Compiler, FullyCompiledProgram, COMPILER_VERSION,
 };
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
 use move_docgen::{Docgen, DocgenOptions};
 use move_model::{model::GlobalEnv, options::ModelBuilderOptions, run_model_builder_with_options};
 use move_symbol_pool::Symbol;
@@ -69,6 +75,26 @@ pub struct CompiledPackageInfo {
     pub source_digest: Option<PackageDigest>,
     /// The build flags that were used when compiling this package.
     pub build_flags: BuildConfig,
+    /// The toolchain and dependency sources used when compiling this package. Missing from
+    /// packages built before it was recorded.
+    #[serde(default)]
+    pub toolchain: ToolchainMetadata,
+}
+
+/// Everything besides the package's own source and build flags that its compiled bytecode depends
+/// on, so that a build can be reproduced byte-for-byte (e.g. to verify that published bytecode was
+/// compiled from some source).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ToolchainMetadata {
+    /// The version of the tool that compiled the package, see `BuildConfig::toolchain_version`
+    pub compiler_version: String,
+    /// The bytecode version modules were serialized with, if it was overridden from the default
+    pub bytecode_version: Option<u32>,
+    /// The edition and flavor the package's source was compiled as
+    pub edition: Edition,
+    pub flavor: Flavor,
+    /// The hash of the source directory of every transitive dependency
+    pub dependency_digests: BTreeMap<PackageName, PackageDigest>,
 }
 
 /// Represents a compiled package in memory.
@@ -89,6 +115,39 @@ pub struct CompiledPackage {
     /// filename -> json bytes for ScriptABI. Can then be used to generate transaction builders in
     /// various languages.
     pub compiled_abis: Option<Vec<(String, Vec<u8>)>>,
+    /// filename -> json bytes for ModuleAbortInfo. Can be used to map abort codes back to the
+    /// source that raised them.
+    pub compiled_abort_info: Option<Vec<(String, Vec<u8>)>>,
+}
+
+/// The aborts reachable from a module's public and entry functions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleAbortInfo {
+    pub address: String,
+    pub module: String,
+    pub functions: Vec<FunctionAbortInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionAbortInfo {
+    pub name: String,
+    pub aborts: Vec<AbortInfo>,
+}
+
+/// An `abort` reachable from a function, either in its own body or in a function of the same
+/// package that it calls, directly or indirectly. `module` and `function` name the function whose
+/// body contains the `abort`, and `line` and `column` its position in that module's source.
+/// `constant` and `code` are set as far as they are known at compile time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbortInfo {
+    pub module: String,
+    pub function: String,
+    pub line: usize,
+    pub column: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<u64>,
 }
 
 /// Represents a compiled package that has been saved to disk. This holds only the minimal metadata
@@ -194,12 +253,33 @@ impl OnDiskCompiledPackage {
             None
         };
 
+        let abort_info_path = self
+            .root_path
+            .join(self.package.compiled_package_info.package_name.as_str())
+            .join(CompiledPackageLayout::CompiledAbortInfo.path());
+        let compiled_abort_info = if abort_info_path.is_dir() {
+            Some(
+                find_filenames(&[abort_info_path.to_string_lossy().to_string()], |path| {
+                    extension_equals(path, "json")
+                })?
+                .into_iter()
+                .map(|path| {
+                    let contents = std::fs::read(&path).unwrap();
+                    (path, contents)
+                })
+                .collect(),
+            )
+        } else {
+            None
+        };
+
         Ok(CompiledPackage {
             compiled_package_info: self.package.compiled_package_info.clone(),
             root_compiled_units,
             deps_compiled_units,
             compiled_docs,
             compiled_abis,
+            compiled_abort_info,
         })
     }
 
@@ -280,20 +360,6 @@ impl OnDiskCompiledPackage {
         std::fs::write(path_to_save, bytes).map_err(|err| err.into())
     }
 
-    #[allow(unused)]
-    pub(crate) fn has_source_changed_since_last_compile(&self, resolved_package: &Package) -> bool {
-        match &self.package.compiled_package_info.source_digest {
-            // Don't have source available to us
-            None => false,
-            Some(digest) => digest != &resolved_package.source_digest,
-        }
-    }
-
-    #[allow(unused)]
-    pub(crate) fn are_build_flags_different(&self, build_config: &BuildConfig) -> bool {
-        build_config != &self.package.compiled_package_info.build_flags
-    }
-
     fn get_compiled_units_paths(&self, package_name: Symbol) -> Result<Vec<String>> {
         let package_dir = if self.package.compiled_package_info.package_name == package_name {
             self.root_path.clone()
@@ -502,27 +568,6 @@ impl CompiledPackage {
             .filter(|unit| matches!(unit.unit, CompiledUnit::Script(_)))
     }
 
-    #[allow(unused)]
-    fn can_load_cached(
-        package: &OnDiskCompiledPackage,
-        resolution_graph: &ResolvedGraph,
-        resolved_package: &Package,
-        is_root_package: bool,
-    ) -> bool {
-        // TODO: add more tests for the different caching cases
-        !(package.has_source_changed_since_last_compile(resolved_package) // recompile if source has changed
-            // Recompile if the flags are different
-                || package.are_build_flags_different(&resolution_graph.build_options)
-                // Force root package recompilation in test mode
-                || resolution_graph.build_options.test_mode && is_root_package
-                // Recompile if force recompilation is set
-                || resolution_graph.build_options.force_recompilation) &&
-                // Dive deeper to make sure that instantiations haven't changed since that
-                // can be changed by other packages above us in the dependency graph possibly
-                package.package.compiled_package_info.address_alias_instantiation
-                    == resolved_package.resolved_table
-    }
-
     pub(crate) fn build_all<W: Write>(
         w: &mut W,
         project_root: &Path,
@@ -535,6 +580,7 @@ impl CompiledPackage {
             /* compiler config */ PackageConfig,
         )>,
         resolution_graph: &ResolvedGraph,
+        pre_compiled_lib: Option<&FullyCompiledProgram>,
         mut compiler_driver: impl FnMut(
             Compiler,
         )
@@ -564,6 +610,25 @@ impl CompiledPackage {
         let root_package_name = resolved_package.source_package.package.name;
         writeln!(w, "{} {}", "BUILDING".bold().green(), root_package_name)?;
 
+        let root_config = resolved_package.compiler_config(
+            /* is_dependency */ false,
+            &resolution_graph.build_options,
+        );
+        let toolchain = ToolchainMetadata {
+            compiler_version: resolution_graph
+                .build_options
+                .toolchain_version
+                .clone()
+                .unwrap_or_else(|| COMPILER_VERSION.to_string()),
+            bytecode_version: get_bytecode_version_from_env(),
+            edition: root_config.edition,
+            flavor: root_config.flavor,
+            dependency_digests: transitive_dependencies
+                .iter()
+                .map(|(name, _, _, _)| (*name, resolution_graph.package_table[name].source_digest))
+                .collect(),
+        };
+
         // gather source/dep files with their address mappings
         let (sources_package_paths, deps_package_paths) = make_source_and_deps_for_compiler(
             resolution_graph,
@@ -574,23 +639,73 @@ impl CompiledPackage {
             Flags::testing()
         } else {
             Flags::empty()
-        };
+        }
+        .set_lint(resolution_graph.build_options.lint);
+        // dependencies that are in the pre-compiled library are not compiled again, their units
+        // are taken from the library instead
+        let pre_compiled_packages = pre_compiled_lib
+            .map(pre_compiled_package_names)
+            .unwrap_or_default();
+        let dep_names = transitive_dependencies
+            .iter()
+            .map(|(name, _, _, _)| *name)
+            .collect::<BTreeSet<_>>();
         // invoke the compiler
-        let mut paths = deps_package_paths.clone();
+        let mut paths = deps_package_paths
+            .iter()
+            .filter(|dep| {
+                !dep.name
+                    .as_ref()
+                    .is_some_and(|(name, _)| pre_compiled_packages.contains(name))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
         paths.push(sources_package_paths.clone());
 
         let compiler = Compiler::from_package_paths(paths, vec![])
             .unwrap()
-            .set_flags(flags);
+            .set_flags(flags)
+            .set_pre_compiled_lib_opt(pre_compiled_lib);
         let (file_map, all_compiled_units) = compiler_driver(compiler)?;
         let mut root_compiled_units = vec![];
         let mut deps_compiled_units = vec![];
+        if let Some(lib) = pre_compiled_lib {
+            for annot_unit in &lib.compiled {
+                let compiled_unit::CompiledUnitEnum::Module(m) = annot_unit else {
+                    continue;
+                };
+                let Some(package_name) = m.named_module.package_name else {
+                    continue;
+                };
+                if !dep_names.contains(&package_name) {
+                    continue;
+                }
+                let (file_name, _) = &lib.files[&annot_unit.loc().file_hash()];
+                let unit = CompiledUnitWithSource {
+                    unit: annot_unit.clone().into_compiled_unit(),
+                    source_path: PathBuf::from(file_name.as_str()),
+                };
+                deps_compiled_units.push((package_name, unit))
+            }
+        }
+        let mut abort_info_modules = resolution_graph
+            .build_options
+            .generate_abort_info
+            .then(Vec::new);
         for annot_unit in all_compiled_units {
-            let source_path = PathBuf::from(file_map[&annot_unit.loc().file_hash()].0.as_str());
+            let (file_name, source) = &file_map[&annot_unit.loc().file_hash()];
+            let source_path = PathBuf::from(file_name.as_str());
             let package_name = match &annot_unit {
                 compiled_unit::CompiledUnitEnum::Module(m) => m.named_module.package_name.unwrap(),
                 compiled_unit::CompiledUnitEnum::Script(s) => s.named_script.package_name.unwrap(),
             };
+            if let (Some(modules), compiled_unit::CompiledUnitEnum::Module(m)) =
+                (&mut abort_info_modules, &annot_unit)
+            {
+                if package_name == root_package_name {
+                    modules.push((source.clone(), m.clone()));
+                }
+            }
             let unit = CompiledUnitWithSource {
                 unit: annot_unit.into_compiled_unit(),
                 source_path,
@@ -601,6 +716,9 @@ impl CompiledPackage {
                 deps_compiled_units.push((package_name, unit))
             }
         }
+        let compiled_abort_info = abort_info_modules
+            .map(|modules| Self::build_abort_info(&modules))
+            .transpose()?;
 
         let mut compiled_docs = None;
         let mut compiled_abis = None;
@@ -639,11 +757,13 @@ impl CompiledPackage {
                 address_alias_instantiation: resolved_package.resolved_table,
                 source_digest: Some(resolved_package.source_digest),
                 build_flags: resolution_graph.build_options.clone(),
+                toolchain,
             },
             root_compiled_units,
             deps_compiled_units,
             compiled_docs,
             compiled_abis,
+            compiled_abort_info,
         };
 
         compiled_package.save_to_disk(project_root.join(CompiledPackageLayout::Root.path()))?;
@@ -763,6 +883,18 @@ impl CompiledPackage {
             }
         }
 
+        if let Some(abort_info) = &self.compiled_abort_info {
+            for (filename, abort_info_bytes) in abort_info {
+                on_disk_package.save_under(
+                    CompiledPackageLayout::CompiledAbortInfo
+                        .path()
+                        .join(filename)
+                        .with_extension("json"),
+                    abort_info_bytes,
+                )?;
+            }
+        }
+
         on_disk_package.save_under(
             CompiledPackageLayout::BuildInfo.path(),
             serde_yaml::to_string(&on_disk_package.package)?.as_bytes(),
@@ -799,6 +931,48 @@ impl CompiledPackage {
         abigen.into_result()
     }
 
+    /// Builds the abort info of each of the package's `modules` (paired with their source). Calls
+    /// are followed into the functions of any of these modules, but not into dependencies.
+    fn build_abort_info(
+        modules: &[(String, AnnotatedCompiledModule)],
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let modules_by_id: BTreeMap<_, _> = modules
+            .iter()
+            .map(|(source, module)| {
+                let id = module.named_module.module.self_id();
+                (id, (source.as_str(), module))
+            })
+            .collect();
+
+        modules
+            .iter()
+            .map(|(_, module)| {
+                let compiled = &module.named_module.module;
+                let functions = compiled
+                    .function_defs
+                    .iter()
+                    .filter(|def| def.visibility == Visibility::Public || def.is_entry)
+                    .map(|def| {
+                        let handle = compiled.function_handle_at(def.function);
+                        let name = compiled.identifier_at(handle.name);
+                        FunctionAbortInfo {
+                            name: name.to_string(),
+                            aborts: reachable_aborts(&modules_by_id, compiled.self_id(), name),
+                        }
+                    })
+                    .collect();
+
+                let name = module.named_module.name.to_string();
+                let abort_info = ModuleAbortInfo {
+                    address: module.named_module.address.into_inner().to_hex_literal(),
+                    module: name.clone(),
+                    functions,
+                };
+                Ok((name, serde_json::to_vec_pretty(&abort_info)?))
+            })
+            .collect()
+    }
+
     fn build_docs(
         package_name: PackageName,
         model: &GlobalEnv,
@@ -891,6 +1065,18 @@ pub(crate) fn apply_named_address_renaming(
         .collect()
 }
 
+/// Names of the packages whose modules are in `pre_compiled_lib`.
+pub(crate) fn pre_compiled_package_names(
+    pre_compiled_lib: &FullyCompiledProgram,
+) -> BTreeSet<Symbol> {
+    pre_compiled_lib
+        .parser
+        .source_definitions
+        .iter()
+        .filter_map(|def| def.package)
+        .collect()
+}
+
 pub(crate) fn make_source_and_deps_for_compiler(
     resolution_graph: &ResolvedGraph,
     root: &Package,
@@ -939,3 +1125,86 @@ pub(crate) fn make_source_and_deps_for_compiler(
     };
     Ok((source_package_paths, deps_package_paths))
 }
+
+/// The aborts reachable from function `name` in module `id`: its own, followed by those of the
+/// functions it calls, in a depth-first walk over calls. Each function is visited at most once, so
+/// the walk terminates for recursive functions, and calls to functions outside `modules` are not
+/// followed.
+fn reachable_aborts(
+    modules: &BTreeMap<ModuleId, (&str, &AnnotatedCompiledModule)>,
+    id: ModuleId,
+    name: &IdentStr,
+) -> Vec<AbortInfo> {
+    let mut aborts = vec![];
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![(id, name.to_owned())];
+    while let Some((id, name)) = stack.pop() {
+        let Some((source, module)) = modules.get(&id) else {
+            continue;
+        };
+        if !visited.insert((id.clone(), name.clone())) {
+            continue;
+        }
+
+        let sites = module
+            .function_infos
+            .get_(&Symbol::from(name.as_str()))
+            .map(|info| info.abort_sites.as_slice())
+            .unwrap_or_default();
+        for site in sites {
+            let (line, column) = line_and_column(source, site.loc.start() as usize);
+            let (constant, code) = match &site.code {
+                AbortCode::Constant { name, value } => (Some(name.to_string()), *value),
+                AbortCode::Value(value) => (None, Some(*value)),
+                AbortCode::Unknown => (None, None),
+            };
+            aborts.push(AbortInfo {
+                module: id.name().to_string(),
+                function: name.to_string(),
+                line,
+                column,
+                constant,
+                code,
+            });
+        }
+
+        let compiled = &module.named_module.module;
+        let Some(code) = compiled
+            .function_defs
+            .iter()
+            .find(|def| {
+                let handle = compiled.function_handle_at(def.function);
+                compiled.identifier_at(handle.name) == name.as_ident_str()
+            })
+            .and_then(|def| def.code.as_ref())
+        else {
+            continue;
+        };
+        let callees: Vec<_> = code
+            .code
+            .iter()
+            .filter_map(|instr| match instr {
+                Bytecode::Call(idx) => Some(*idx),
+                Bytecode::CallGeneric(idx) => Some(compiled.function_instantiation_at(*idx).handle),
+                _ => None,
+            })
+            .map(|idx| {
+                let handle = compiled.function_handle_at(idx);
+                let module =
+                    compiled.module_id_for_handle(compiled.module_handle_at(handle.module));
+                (module, compiled.identifier_at(handle.name).to_owned())
+            })
+            .collect();
+        // Pushed in reverse, so that callees are visited in the order they are called.
+        stack.extend(callees.into_iter().rev());
+    }
+    aborts
+}
+
+/// 1-based line and column of the byte at `offset` in `source`.
+fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let prefix = &source[..offset.min(source.len())];
+    let line = prefix.matches('\n').count() + 1;
+    let line_start = prefix.rfind('\n').map_or(0, |i| i + 1);
+    (line, offset - line_start + 1)
+}
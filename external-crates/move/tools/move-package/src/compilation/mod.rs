@@ -6,3 +6,4 @@ pub mod build_plan;
 pub mod compiled_package;
 pub mod model_builder;
 pub mod package_layout;
+pub mod workspace_build_plan;
@@ -0,0 +1,268 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    compilation::{
+        build_plan::BuildPlan,
+        compiled_package::{named_address_mapping_for_compiler, CompiledPackage},
+    },
+    resolution::resolution_graph::ResolvedGraph,
+    source_package::parsed_manifest::{NamedAddress, PackageName},
+};
+use anyhow::{bail, Result};
+use move_compiler::{
+    construct_pre_compiled_lib,
+    diagnostics::{report_diagnostics_to_buffer, Diagnostics, FilesSourceText},
+    shared::PackagePaths,
+    Flags, FullyCompiledProgram,
+};
+use move_core_types::account_address::AccountAddress;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    path::PathBuf,
+};
+
+/// Builds several packages (the members of the workspace) in one go. Dependencies that are not
+/// members themselves are compiled once, and the result is shared by all members that depend on
+/// them. Members are compiled after the members they depend on, and the diagnostics of all of them
+/// are reported together.
+#[derive(Debug, Clone)]
+pub struct WorkspaceBuildPlan {
+    /// Build plans of the members, ordered so that a member comes after the members it depends on.
+    members: Vec<BuildPlan>,
+}
+
+impl WorkspaceBuildPlan {
+    pub fn create(resolution_graphs: Vec<ResolvedGraph>) -> Result<Self> {
+        let mut member_names = BTreeSet::new();
+        for graph in &resolution_graphs {
+            let name = graph.root_package();
+            if !member_names.insert(name) {
+                bail!("Package '{name}' appears more than once in the workspace");
+            }
+        }
+
+        check_consistent_packages(&resolution_graphs)?;
+        check_consistent_addresses(&resolution_graphs)?;
+
+        let mut members = resolution_graphs
+            .into_iter()
+            .map(|mut graph| {
+                // Members would otherwise all be installed under the same build directory, where
+                // each of them removes the outputs of the packages it does not depend on.
+                if let Some(install_dir) = &graph.build_options.install_dir {
+                    let member_dir = install_dir.join(graph.root_package().as_str());
+                    graph.build_options.install_dir = Some(member_dir);
+                }
+                BuildPlan::create(graph)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // A member's graph contains the members it depends on, as well as all of the members in
+        // their graphs, so it contains strictly more members than any of them.
+        members.sort_by_key(|plan| {
+            plan.resolution_graph()
+                .topological_order()
+                .into_iter()
+                .filter(|name| member_names.contains(name))
+                .count()
+        });
+
+        Ok(Self { members })
+    }
+
+    pub fn member_names(&self) -> Vec<PackageName> {
+        self.members.iter().map(BuildPlan::root_package).collect()
+    }
+
+    /// Compile all members, and write the diagnostics of all of them to `writer` as one report.
+    /// Does not exit the process on warnings or failures. Members that depend on a member that
+    /// failed to compile are not compiled.
+    pub fn compile<W: Write>(&self, writer: &mut W) -> Result<Vec<CompiledPackage>> {
+        let mut files = FilesSourceText::new();
+        let mut diags = Diagnostics::new();
+
+        let pre_compiled_lib = match self.compile_shared_dependencies()? {
+            Ok(lib) => lib,
+            Err((lib_files, lib_diags)) => {
+                writer.write_all(&report_diagnostics_to_buffer(&lib_files, lib_diags))?;
+                bail!("Compilation error in the dependencies shared by the workspace");
+            }
+        };
+
+        let mut compiled = vec![];
+        let mut failed = BTreeSet::new();
+        let mut skipped = BTreeSet::new();
+        for plan in &self.members {
+            let name = plan.root_package();
+            let graph = plan.resolution_graph();
+            if graph
+                .topological_order()
+                .iter()
+                .any(|dep| failed.contains(dep) || skipped.contains(dep))
+            {
+                skipped.insert(name);
+                continue;
+            }
+
+            let result =
+                plan.compile_with_pre_compiled_lib(writer, pre_compiled_lib.as_ref(), |compiler| {
+                    let (package_files, units_res) = compiler.build()?;
+                    files.extend(package_files.clone());
+                    match units_res {
+                        Ok((units, warning_diags)) => {
+                            diags.extend(warning_diags);
+                            Ok((package_files, units))
+                        }
+                        Err(error_diags) => {
+                            assert!(!error_diags.is_empty());
+                            diags.extend(error_diags);
+                            bail!("Compilation error");
+                        }
+                    }
+                });
+
+            match result {
+                Ok(package) => compiled.push(package),
+                Err(_) => {
+                    failed.insert(name);
+                }
+            }
+        }
+
+        if !diags.is_empty() {
+            writer.write_all(&report_diagnostics_to_buffer(&files, diags))?;
+        }
+
+        if !failed.is_empty() {
+            let names = |packages: BTreeSet<PackageName>| {
+                packages
+                    .iter()
+                    .map(|name| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            if skipped.is_empty() {
+                bail!("Failed to compile {}", names(failed));
+            }
+            bail!(
+                "Failed to compile {}, and did not compile {} as they depend on a package that \
+                 failed",
+                names(failed),
+                names(skipped),
+            );
+        }
+
+        Ok(compiled)
+    }
+
+    /// Compile the dependencies of the members that are not members themselves, if there are any.
+    fn compile_shared_dependencies(
+        &self,
+    ) -> Result<Result<Option<FullyCompiledProgram>, (FilesSourceText, Diagnostics)>> {
+        let member_names = self.member_names().into_iter().collect::<BTreeSet<_>>();
+        let mut shared = BTreeMap::new();
+        for plan in &self.members {
+            let graph = plan.resolution_graph();
+            for name in graph.topological_order() {
+                if member_names.contains(&name) || shared.contains_key(&name) {
+                    continue;
+                }
+                let package = graph.get_package(name);
+                if let Some(member) = package
+                    .immediate_dependencies(graph)
+                    .into_iter()
+                    .find(|dep| member_names.contains(dep))
+                {
+                    bail!(
+                        "Dependency '{name}' depends on workspace member '{member}', so it cannot \
+                         be shared by the workspace. Consider adding it to the workspace."
+                    );
+                }
+                let paths = PackagePaths {
+                    name: Some((
+                        name,
+                        package
+                            .compiler_config(/* is_dependency */ true, &graph.build_options),
+                    )),
+                    paths: package.get_sources(&graph.build_options)?,
+                    named_address_map: named_address_mapping_for_compiler(&package.resolved_table),
+                };
+                shared.insert(name, paths);
+            }
+        }
+
+        if shared.is_empty() {
+            return Ok(Ok(None));
+        }
+
+        let test_mode = self
+            .members
+            .iter()
+            .any(|plan| plan.resolution_graph().build_options.test_mode);
+        let flags = if test_mode {
+            Flags::testing()
+        } else {
+            Flags::empty()
+        };
+        Ok(construct_pre_compiled_lib(shared.into_values().collect(), None, flags)?.map(Some))
+    }
+}
+
+/// A package that several members depend on must be the same package, with the same named
+/// addresses, in all of them. Otherwise its compilation could not be shared.
+fn check_consistent_packages(resolution_graphs: &[ResolvedGraph]) -> Result<()> {
+    let mut seen: BTreeMap<PackageName, (PathBuf, PackageName)> = BTreeMap::new();
+    let mut tables = BTreeMap::new();
+    for graph in resolution_graphs {
+        let member = graph.root_package();
+        for (name, package) in &graph.package_table {
+            let Some((path, prev_member)) = seen.get(name) else {
+                seen.insert(*name, (package.package_path.clone(), member));
+                tables.insert(*name, &package.resolved_table);
+                continue;
+            };
+            if path != &package.package_path {
+                bail!(
+                    "Package '{name}' is located at '{}' for workspace member '{prev_member}' but \
+                     at '{}' for workspace member '{member}'",
+                    path.display(),
+                    package.package_path.display(),
+                );
+            }
+            if tables[name] != &package.resolved_table {
+                bail!(
+                    "Package '{name}' has different named addresses for workspace members \
+                     '{prev_member}' and '{member}'"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A named address must be assigned the same address throughout the workspace.
+fn check_consistent_addresses(resolution_graphs: &[ResolvedGraph]) -> Result<()> {
+    let mut assignments: BTreeMap<NamedAddress, (AccountAddress, PackageName)> = BTreeMap::new();
+    for graph in resolution_graphs {
+        for (package_name, package) in &graph.package_table {
+            for (name, addr) in &package.resolved_table {
+                let Some((prev_addr, prev_package)) = assignments.get(name) else {
+                    assignments.insert(*name, (*addr, *package_name));
+                    continue;
+                };
+                if prev_addr != addr {
+                    bail!(
+                        "Conflicting assignments of named address '{name}' in the workspace: \
+                         '0x{}' in package '{prev_package}' and '0x{}' in package \
+                         '{package_name}'",
+                        prev_addr.short_str_lossless(),
+                        addr.short_str_lossless(),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -10,7 +10,7 @@ pub mod package_hooks;
 pub mod resolution;
 pub mod source_package;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::*;
 use move_compiler::editions::{Edition, Flavor};
 use move_core_types::account_address::AccountAddress;
@@ -27,12 +27,15 @@ use std::{
 use crate::{
     compilation::{
         build_plan::BuildPlan, compiled_package::CompiledPackage, model_builder::ModelBuilder,
+        workspace_build_plan::WorkspaceBuildPlan,
     },
     package_lock::PackageLock,
 };
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Default)]
 #[clap(author, version, about)]
+// Flags added since a package was built are missing from its BuildInfo.yaml.
+#[serde(default)]
 pub struct BuildConfig {
     /// Compile in 'dev' mode. The 'dev-addresses' and 'dev-dependencies' fields will be used if
     /// this flag is set. This flag is useful for development of packages that expose named
@@ -53,6 +56,11 @@ pub struct BuildConfig {
     #[clap(name = "generate-abis", long = "abi", global = true)]
     pub generate_abis: bool,
 
+    /// Generate a JSON file per module, listing the aborts reachable in its public and entry
+    /// functions
+    #[clap(name = "generate-abort-info", long = "abort-info", global = true)]
+    pub generate_abort_info: bool,
+
     /// Installation directory for compiled artifacts. Defaults to current directory.
     #[clap(long = "install-dir", global = true)]
     pub install_dir: Option<PathBuf>,
@@ -89,6 +97,26 @@ pub struct BuildConfig {
     /// warning suppression in dependency packages.
     #[clap(long = "dependencies-are-root", global = true)]
     pub deps_as_root: bool,
+
+    /// Fail instead of updating the lock file if it is missing or out of date, so that the build
+    /// uses exactly the dependencies recorded in it.
+    #[clap(long = "locked", global = true)]
+    pub locked: bool,
+
+    /// Also report lints: warnings about code that is valid, but likely to be a mistake
+    #[clap(long = "lint", global = true)]
+    pub lint: bool,
+
+    /// Also warn about modules that come close to the limits that the bytecode verifier enforces
+    /// when they are published
+    #[clap(long = "verifier-limits", global = true)]
+    pub verifier_limits: bool,
+
+    /// Version of the tool running the build, recorded as the compiler version in the metadata of
+    /// the packages it compiles. Defaults to the version of the Move compiler crate.
+    #[clap(skip)]
+    #[serde(skip)]
+    pub toolchain_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
@@ -121,6 +149,22 @@ impl BuildConfig {
         BuildPlan::create(resolved_graph)?.compile_no_exit(writer)
     }
 
+    /// Compile the packages at `paths` (or the Move packages containing them) as one workspace,
+    /// compiling the dependencies they have in common only once. Do not exit process on warning or
+    /// failure; the diagnostics of all packages are written to `writer` together.
+    pub fn compile_workspace<W: Write>(
+        self,
+        paths: &[PathBuf],
+        writer: &mut W,
+    ) -> Result<Vec<CompiledPackage>> {
+        let resolved_graphs = paths
+            .iter()
+            .map(|path| self.clone().resolution_graph_for_package(path, writer))
+            .collect::<Result<Vec<_>>>()?;
+        let _mutx = PackageLock::lock(); // held until function returns
+        WorkspaceBuildPlan::create(resolved_graphs)?.compile(writer)
+    }
+
     // NOTE: If there are no renamings, then the root package has the global resolution of all named
     // addresses in the package graph in scope. So we can simply grab all of the source files
     // across all packages and build the Move model from that.
@@ -178,6 +222,12 @@ impl BuildConfig {
         )?;
 
         if modified {
+            if self.locked {
+                bail!(
+                    "{} is missing or out of date, and cannot be updated in locked mode",
+                    SourcePackageLayout::Lock.path().display()
+                );
+            }
             let lock = dependency_graph.write_to_lock(install_dir)?;
             if let Some(lock_path) = &self.lock_file {
                 lock.commit(lock_path)?;
@@ -0,0 +1,135 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{
+    compilation::{compiled_package::ModuleAbortInfo, package_layout::CompiledPackageLayout},
+    BuildConfig,
+};
+use std::fs;
+use tempfile::tempdir;
+
+const MANIFEST: &str = r#"[package]
+name = "Aborts"
+"#;
+
+const SOURCE: &str = r#"module 0x1::aborts {
+    use 0x1::other;
+
+    const ENotPositive: u64 = 1;
+
+    public fun check(x: u64) {
+        assert!(x > 0, ENotPositive);
+        if (x > 100) abort 42;
+        helper(x);
+        helper(x + 1)
+    }
+
+    entry fun always(_x: u64) {
+        abort ENotPositive
+    }
+
+    public fun recurse(x: u64) {
+        if (x == 0) abort 5;
+        down(x - 1)
+    }
+
+    fun down(x: u64) {
+        recurse(x)
+    }
+
+    fun helper(x: u64) {
+        assert!(x != 7, 3);
+        other::check(x)
+    }
+}
+"#;
+
+const OTHER: &str = r#"module 0x1::other {
+    const ETooBig: u64 = 2;
+
+    public fun check(x: u64) {
+        assert!(x < 1000, ETooBig);
+    }
+}
+"#;
+
+#[test]
+fn test_abort_info_for_public_and_entry_functions() {
+    let dir = tempdir().unwrap();
+    let package = dir.path().join("aborts");
+    fs::create_dir_all(package.join("sources")).unwrap();
+    fs::write(package.join("Move.toml"), MANIFEST).unwrap();
+    fs::write(package.join("sources").join("aborts.move"), SOURCE).unwrap();
+    fs::write(package.join("sources").join("other.move"), OTHER).unwrap();
+
+    BuildConfig {
+        generate_abort_info: true,
+        install_dir: Some(dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_package(&package, &mut Vec::new())
+    .unwrap();
+
+    let path = dir
+        .path()
+        .join(CompiledPackageLayout::Root.path())
+        .join("Aborts")
+        .join(CompiledPackageLayout::CompiledAbortInfo.path())
+        .join("aborts.json");
+    let info: ModuleAbortInfo = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    assert_eq!(info.module, "aborts");
+
+    // Private functions are not included, but the aborts in the functions that public and entry
+    // functions call are, once each, even if they are called more than once, or recursively.
+    let summary: Vec<_> = info
+        .functions
+        .iter()
+        .map(|f| {
+            let aborts: Vec<_> = f
+                .aborts
+                .iter()
+                .map(|a| {
+                    (
+                        format!("{}::{}", a.module, a.function),
+                        a.line,
+                        a.constant.as_deref(),
+                        a.code,
+                    )
+                })
+                .collect();
+            (f.name.as_str(), aborts)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "check",
+                vec![
+                    (
+                        "aborts::check".to_string(),
+                        7,
+                        Some("ENotPositive"),
+                        Some(1)
+                    ),
+                    ("aborts::check".to_string(), 8, None, Some(42)),
+                    ("aborts::helper".to_string(), 27, None, Some(3)),
+                    ("other::check".to_string(), 5, Some("ETooBig"), Some(2)),
+                ]
+            ),
+            (
+                "always",
+                vec![(
+                    "aborts::always".to_string(),
+                    14,
+                    Some("ENotPositive"),
+                    Some(1)
+                )]
+            ),
+            (
+                "recurse",
+                vec![("aborts::recurse".to_string(), 18, None, Some(5))]
+            ),
+        ]
+    );
+}
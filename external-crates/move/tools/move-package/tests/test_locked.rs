@@ -0,0 +1,48 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::BuildConfig;
+use std::{fs, path::Path};
+use tempfile::tempdir;
+
+const MANIFEST: &str = r#"[package]
+name = "test"
+version = "0.0.0"
+"#;
+
+fn build(package: &Path, locked: bool) -> anyhow::Result<()> {
+    BuildConfig {
+        install_dir: Some(package.to_path_buf()),
+        lock_file: Some(package.join("Move.lock")),
+        locked,
+        ..Default::default()
+    }
+    .compile_package_no_exit(package, &mut Vec::new())?;
+    Ok(())
+}
+
+#[test]
+fn test_locked_requires_up_to_date_lock_file() {
+    let dir = tempdir().unwrap();
+    let package = dir.path();
+    fs::create_dir_all(package.join("sources")).unwrap();
+    fs::write(package.join("sources").join("m.move"), "module 0x1::m {}\n").unwrap();
+    fs::write(package.join("Move.toml"), MANIFEST).unwrap();
+
+    // There is no lock file yet
+    let err = build(package, true).unwrap_err();
+    assert!(err.to_string().contains("Move.lock"), "{err}");
+    assert!(!package.join("Move.lock").exists());
+
+    build(package, false).unwrap();
+    assert!(package.join("Move.lock").exists());
+    build(package, true).unwrap();
+
+    // The manifest changed since the lock file was written
+    fs::write(
+        package.join("Move.toml"),
+        format!("{MANIFEST}\n[addresses]\nA = \"0x1\"\n"),
+    )
+    .unwrap();
+    build(package, true).unwrap_err();
+}
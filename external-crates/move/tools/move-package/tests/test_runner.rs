@@ -121,6 +121,7 @@ impl Test<'_> {
             test_mode: false,
             generate_docs: false,
             generate_abis: false,
+            generate_abort_info: false,
             install_dir: Some(out_path),
             force_recompilation: false,
             lock_file: ["locked", "notlocked"]
@@ -184,6 +185,10 @@ fn scrub_build_config(config: &mut BuildConfig) {
 fn scrub_compiled_package(pkg: &mut CompiledPackageInfo) {
     pkg.source_digest = Some(PackageDigest::from("ELIDED_FOR_TEST"));
     scrub_build_config(&mut pkg.build_flags);
+    pkg.toolchain.compiler_version = "ELIDED_FOR_TEST".to_string();
+    for digest in pkg.toolchain.dependency_digests.values_mut() {
+        *digest = PackageDigest::from("ELIDED_FOR_TEST");
+    }
 }
 
 fn scrub_resolved_package(pkg: &mut Package) {
@@ -0,0 +1,82 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::compiled_unit::CompiledUnit;
+use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+fn member(name: &str) -> PathBuf {
+    ["tests", "workspace_package_test_sources", name]
+        .into_iter()
+        .collect()
+}
+
+fn unit_name(unit: &CompiledUnit) -> String {
+    match unit {
+        CompiledUnit::Module(m) => m.name.to_string(),
+        CompiledUnit::Script(s) => s.name.to_string(),
+    }
+}
+
+fn dep_units(package: &CompiledPackage) -> Vec<(String, String)> {
+    let mut units: Vec<_> = package
+        .deps_compiled_units
+        .iter()
+        .map(|(name, unit)| (name.to_string(), unit_name(&unit.unit)))
+        .collect();
+    units.sort();
+    units
+}
+
+#[test]
+fn test_workspace_shares_dependencies() {
+    let dir = tempdir().unwrap().path().to_path_buf();
+    let packages = BuildConfig {
+        install_dir: Some(dir.clone()),
+        ..Default::default()
+    }
+    // B depends on A, so it is compiled after it.
+    .compile_workspace(&[member("B"), member("A")], &mut Vec::new())
+    .unwrap();
+
+    let names: Vec<_> = packages
+        .iter()
+        .map(|p| p.compiled_package_info.package_name.to_string())
+        .collect();
+    assert_eq!(names, vec!["A", "B"]);
+
+    // Units of the shared dependency come from its pre-compiled library, units of other members
+    // are compiled from source.
+    assert_eq!(
+        dep_units(&packages[0]),
+        vec![("Shared".to_string(), "counter".to_string())],
+    );
+    assert_eq!(
+        dep_units(&packages[1]),
+        vec![
+            ("A".to_string(), "a".to_string()),
+            ("Shared".to_string(), "counter".to_string()),
+        ],
+    );
+
+    // Each member is installed under its own directory.
+    assert!(dir.join("A").join("build").join("A").is_dir());
+    assert!(dir.join("B").join("build").join("B").is_dir());
+}
+
+#[test]
+fn test_workspace_conflicting_addresses() {
+    let err = BuildConfig {
+        install_dir: Some(tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_workspace(&[member("A"), member("Conflict")], &mut Vec::new())
+    .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Conflicting assignments of named address 'shared'"),
+        "{err}"
+    );
+}
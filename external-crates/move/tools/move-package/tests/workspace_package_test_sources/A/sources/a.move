@@ -0,0 +1,9 @@
+module a::a {
+    use shared::counter;
+
+    public fun one(): u64 {
+        let counter = counter::new();
+        counter::increment(&mut counter);
+        counter::value(&counter)
+    }
+}
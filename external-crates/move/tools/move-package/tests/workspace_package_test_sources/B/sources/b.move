@@ -0,0 +1,9 @@
+module b::b {
+    use shared::counter;
+
+    public fun two(): u64 {
+        let counter = counter::new();
+        counter::increment(&mut counter);
+        a::a::one() + counter::value(&counter)
+    }
+}
@@ -0,0 +1,5 @@
+module shared::conflict {
+    public fun zero(): u64 {
+        0
+    }
+}
@@ -0,0 +1,17 @@
+module shared::counter {
+    struct Counter has drop {
+        value: u64,
+    }
+
+    public fun new(): Counter {
+        Counter { value: 0 }
+    }
+
+    public fun increment(counter: &mut Counter) {
+        counter.value = counter.value + 1;
+    }
+
+    public fun value(counter: &Counter): u64 {
+        counter.value
+    }
+}
@@ -136,6 +136,12 @@ export interface DisplayFieldsResponse {
 }
 export interface DryRunTransactionBlockResponse {
 	balanceChanges: BalanceChange[];
+	/**
+	 * Computation gas units used by each command of a programmable transaction, by command index. They
+	 * are not multiplied by the gas price, and are not rounded like the computation cost in the effects.
+	 * Commands after one that failed are not listed.
+	 */
+	commandGasUsed?: string[];
 	effects: TransactionEffects;
 	events: SuiEvent[];
 	input: TransactionBlockData;
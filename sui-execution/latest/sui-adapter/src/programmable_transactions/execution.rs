@@ -86,13 +86,25 @@ mod checked {
         )?;
         // execute commands
         let mut mode_results = Mode::empty_results();
+        let mut command_gas_used = vec![];
         for (idx, command) in commands.into_iter().enumerate() {
-            if let Err(err) = execute_command::<Mode>(&mut context, &mut mode_results, command) {
+            let gas_used_before = context
+                .gas_charger
+                .move_gas_status()
+                .gas_used_pre_gas_price();
+            let result = execute_command::<Mode>(&mut context, &mut mode_results, command);
+            let gas_used_after = context
+                .gas_charger
+                .move_gas_status()
+                .gas_used_pre_gas_price();
+            command_gas_used.push(gas_used_after - gas_used_before);
+            if let Err(err) = result {
                 let object_runtime: &ObjectRuntime = context.object_runtime();
                 // We still need to record the loaded child objects for replay
                 let loaded_runtime_objects = object_runtime.loaded_runtime_objects();
                 drop(context);
                 state_view.save_loaded_runtime_objects(loaded_runtime_objects);
+                state_view.save_command_gas_used(command_gas_used);
                 return Err(err.with_command_index(idx));
             };
         }
@@ -108,6 +120,7 @@ mod checked {
         let finished = context.finish::<Mode>();
         // Save loaded objects for debug. We dont want to lose the info
         state_view.save_loaded_runtime_objects(loaded_runtime_objects);
+        state_view.save_command_gas_used(command_gas_used);
         state_view.record_execution_results(finished?);
         Ok(mode_results)
     }
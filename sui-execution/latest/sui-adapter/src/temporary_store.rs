@@ -53,6 +53,9 @@ pub struct TemporaryStore<'backing> {
     /// These packages were not previously loaded into the temporary store.
     runtime_packages_loaded_from_db: RwLock<BTreeMap<ObjectID, Object>>,
 
+    /// Gas units used by each command of the programmable transaction.
+    command_gas_used: Vec<u64>,
+
     /// The set of objects that we may receive during execution. Not guaranteed to receive all, or
     /// any of the objects referenced in this set.
     receiving_objects: Vec<ObjectRef>,
@@ -81,6 +84,7 @@ impl<'backing> TemporaryStore<'backing> {
             protocol_config: protocol_config.clone(),
             loaded_runtime_objects: BTreeMap::new(),
             runtime_packages_loaded_from_db: RwLock::new(BTreeMap::new()),
+            command_gas_used: vec![],
             receiving_objects,
         }
     }
@@ -114,6 +118,7 @@ impl<'backing> TemporaryStore<'backing> {
             loaded_runtime_objects: self.loaded_runtime_objects,
             no_extraneous_module_bytes: self.protocol_config.no_extraneous_module_bytes(),
             runtime_packages_loaded_from_db: self.runtime_packages_loaded_from_db.read().clone(),
+            command_gas_used: self.command_gas_used,
         }
     }
 
@@ -954,6 +959,11 @@ impl<'backing> Storage for TemporaryStore<'backing> {
     ) {
         TemporaryStore::save_loaded_runtime_objects(self, loaded_runtime_objects)
     }
+
+    fn save_command_gas_used(&mut self, command_gas_used: Vec<u64>) {
+        // As with loaded runtime objects, the execution engine may be called more than once.
+        self.command_gas_used.extend(command_gas_used);
+    }
 }
 
 impl<'backing> BackingPackageStore for TemporaryStore<'backing> {
@@ -695,6 +695,26 @@ pub fn all_natives(silent: bool) -> NativeFunctionTable {
             "create_one_time_witness",
             make_native!(test_utils::create_one_time_witness),
         ),
+        (
+            "test_utils",
+            "set_clock_timestamp_ms",
+            make_native!(test_utils::set_clock_timestamp_ms),
+        ),
+        (
+            "test_utils",
+            "clock_timestamp_ms",
+            make_native!(test_utils::clock_timestamp_ms),
+        ),
+        (
+            "test_utils",
+            "set_random_seed",
+            make_native!(test_utils::set_random_seed),
+        ),
+        (
+            "test_utils",
+            "random_bytes",
+            make_native!(test_utils::random_bytes),
+        ),
         (
             "zklogin_verified_id",
             "check_zklogin_id_internal",
@@ -29,7 +29,8 @@ use sui_types::{
     metrics::LimitsMetrics,
     object::{MoveObject, Owner},
     storage::ChildObjectResolver,
-    SUI_AUTHENTICATOR_STATE_OBJECT_ID, SUI_CLOCK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID,
+    SUI_AUTHENTICATOR_STATE_OBJECT_ID, SUI_CLOCK_OBJECT_ID, SUI_RANDOMNESS_STATE_OBJECT_ID,
+    SUI_SYSTEM_STATE_OBJECT_ID,
 };
 
 pub(crate) mod object_store;
@@ -63,6 +64,15 @@ pub(crate) struct TestInventories {
     pub(crate) taken: BTreeMap<ObjectID, Owner>,
 }
 
+/// Deterministic sources of time and randomness, exposed to unit tests through test-only natives
+/// in `sui::test_utils`.
+#[derive(Default)]
+pub(crate) struct TestEnvironment {
+    pub(crate) clock_timestamp_ms: u64,
+    // state of the hash chain that random bytes are drawn from
+    pub(crate) random_state: Vec<u8>,
+}
+
 pub struct LoadedRuntimeObject {
     pub version: SequenceNumber,
     pub is_modified: bool,
@@ -144,6 +154,8 @@ pub struct ObjectRuntime<'a> {
     child_object_store: ChildObjectStore<'a>,
     // inventories for test scenario
     pub(crate) test_inventories: TestInventories,
+    // clock and randomness for unit tests
+    pub(crate) test_environment: TestEnvironment,
     // the internal state
     pub(crate) state: ObjectRuntimeState,
     // whether or not this TX is gas metered
@@ -204,6 +216,7 @@ impl<'a> ObjectRuntime<'a> {
                 epoch_id,
             ),
             test_inventories: TestInventories::new(),
+            test_environment: TestEnvironment::default(),
             state: ObjectRuntimeState {
                 input_objects: input_object_owners,
                 new_ids: Set::new(),
@@ -290,6 +303,7 @@ impl<'a> ObjectRuntime<'a> {
             SUI_SYSTEM_STATE_OBJECT_ID,
             SUI_CLOCK_OBJECT_ID,
             SUI_AUTHENTICATOR_STATE_OBJECT_ID,
+            SUI_RANDOMNESS_STATE_OBJECT_ID,
         ]
         .contains(&id);
         let transfer_result = if self.state.new_ids.contains_key(&id) {
@@ -1,12 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{legacy_test_cost, types::is_otw_struct};
+use crate::{legacy_test_cost, object_runtime::ObjectRuntime, types::is_otw_struct};
+use fastcrypto::hash::{HashFunction, Sha3_256};
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::{gas_algebra::InternalGas, value::MoveTypeLayout};
 use move_vm_runtime::native_functions::NativeContext;
 use move_vm_types::{
-    loaded_data::runtime_types::Type, natives::function::NativeResult, values::Value,
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
 };
 use smallvec::smallvec;
 use std::collections::VecDeque;
@@ -47,3 +48,82 @@ pub fn create_one_time_witness(
         Ok(NativeResult::err(InternalGas::new(1), 1))
     }
 }
+
+// native fun set_clock_timestamp_ms(timestamp_ms: u64);
+pub fn set_clock_timestamp_ms(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let timestamp_ms = pop_arg!(args, u64);
+    let object_runtime: &mut ObjectRuntime = context.extensions_mut().get_mut();
+    object_runtime.test_environment.clock_timestamp_ms = timestamp_ms;
+    Ok(NativeResult::ok(legacy_test_cost(), smallvec![]))
+}
+
+// native fun clock_timestamp_ms(): u64;
+pub fn clock_timestamp_ms(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let object_runtime: &ObjectRuntime = context.extensions().get();
+    let timestamp_ms = object_runtime.test_environment.clock_timestamp_ms;
+    Ok(NativeResult::ok(
+        legacy_test_cost(),
+        smallvec![Value::u64(timestamp_ms)],
+    ))
+}
+
+// native fun set_random_seed(seed: vector<u8>);
+pub fn set_random_seed(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let seed = pop_arg!(args, Vec<u8>);
+    let object_runtime: &mut ObjectRuntime = context.extensions_mut().get_mut();
+    object_runtime.test_environment.random_state = seed;
+    Ok(NativeResult::ok(legacy_test_cost(), smallvec![]))
+}
+
+/// The most bytes that `random_bytes` allocates up front. `n` comes from the test, so larger
+/// outputs grow as they are generated, rather than reserving an arbitrary amount of memory.
+const RANDOM_BYTES_MAX_CAPACITY: usize = 4096;
+
+// native fun random_bytes(n: u64): vector<u8>;
+pub fn random_bytes(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let n = pop_arg!(args, u64) as usize;
+    let object_runtime: &mut ObjectRuntime = context.extensions_mut().get_mut();
+    let state = &mut object_runtime.test_environment.random_state;
+
+    // Hash chain over the seed: every digest becomes the next state, and contributes its bytes to
+    // the output.
+    let mut bytes = Vec::with_capacity(n.min(RANDOM_BYTES_MAX_CAPACITY));
+    while bytes.len() < n {
+        *state = Sha3_256::digest(state.as_slice()).to_vec();
+        let remaining = n - bytes.len();
+        bytes.extend(state.iter().take(remaining));
+    }
+
+    Ok(NativeResult::ok(
+        legacy_test_cost(),
+        smallvec![Value::vector_u8(bytes)],
+    ))
+}